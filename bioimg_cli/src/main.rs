@@ -0,0 +1,484 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use bioimg_spec::rdf::model::{ModelRdf, ModelRdfV04};
+use bioimg_spec::rdf::{spec_error, Changelist, Severity, ValidationReport, Version};
+use bioimg_spec::runtime::{conformance::run_corpus, network, package_reader::read_rdf_text, validate_path, NetworkConfig};
+use bioimg_spec::runtime::inference::{load_backend, BackendConfig};
+use bioimg_spec::runtime::{model_card, CardFormat, FileCompression, ModelPackage, PackageManifest};
+use bioimg_spec::runtime::{tiling::TiledBackend, SelfTestReport, Tolerance};
+use bioimg_spec::runtime::{watch::DEFAULT_POLL_INTERVAL, ValidationWatcher, WatchUpdate};
+
+const USAGE: &str = "\
+Usage:
+  bioimg validate [--json] [--show <level>] [--fail-on <level>] [--watch] <rdf.yaml|model.zip|model_dir>
+  bioimg package [--output <model.zip>] [--compression-level <0-9>] [--store <ext>]... [--deflate <ext>]...
+                 [--recompute-hashes] <rdf.yaml>
+  bioimg upgrade [--output <rdf.yaml>] <rdf.yaml|model.zip|model_dir>
+  bioimg card [--html] [--output <README.md>] <rdf.yaml|model.zip|model_dir>
+  bioimg diff [--json] <old rdf.yaml|model.zip|model_dir> <new rdf.yaml|model.zip|model_dir>
+  bioimg test [--threads <n>] [--tile-size <n>] <rdf.yaml|model.zip|model_dir>
+  bioimg corpus <dir>
+
+validate checks a model description, or a packaged model along with its files, and reports every
+problem found. It exits with 1 if anything at least as severe as the --fail-on level was found.
+With --watch it keeps running, validating again whenever the description or a local file it refers to
+changes, and prints the problems that appeared (+) or went away (-) since the last time.
+
+package writes a description and the local files it refers to, relative to the description, into
+a zip. Hashes already in the description must match the files, unless --recompute-hashes is given.
+Files that are compressed already, like .pt weights and .png images, are stored rather than deflated.
+
+upgrade converts a format 0.4 model description to format 0.5, printing it unless --output is given.
+Fields that couldn't be carried over as they were are reported as warnings.
+
+card writes a model card, with the authors, citations, tensors and weights of the model, as markdown or
+as HTML. It is printed unless --output is given.
+
+diff lists what was added, removed or changed from one model description to another, one field per
+line. Tensors, axes and other items with an id are matched by id. It exits with 1 if the descriptions
+differ, and with 2 if either can't be read.
+
+test runs the model on its test inputs with the first of its weights that a built in runtime can run,
+and compares the results to its test outputs. It exits with 1 if they differ or the model can't be run.
+With --tile-size the inputs are split into overlapping tiles of a size the model allows, which are run
+one after the other and put together again with their halo cut off.
+
+corpus checks every yaml file under a directory the way the conformance corpus of bioimg_spec is checked:
+files under an invalid/ directory must fail with the error of their '# expect-error:' line, and any other
+file must parse and round trip. Either must be reported by validate as their '# expect-entry:' lines say.
+It exits with 1 if any file didn't, and with 2 if the directory can't be read.
+
+Arguments that can't be parsed make any command exit with 2.
+
+Options:
+  --json                       Print the report or changes as JSON instead of one line each
+  --html                       Write the model card as HTML instead of markdown
+  --show <level>               Only report problems at least this severe: info, warning or error [default: info]
+  --fail-on <level>            The least severe problems to exit with 1 on [default: error]
+  --watch                      Validate again on every change until interrupted; with --json, one report per line
+  -o, --output <path>          Where to write the package [default: model.zip], upgraded description or card
+  --compression-level <0-9>    From fastest to smallest [default: 6]
+  --store <ext>                Store files with this extension without compressing them; can be repeated
+  --deflate <ext>              Deflate files with this extension, even if they are stored by default
+  --recompute-hashes           Fill in the hashes from the files instead of checking them
+  --threads <n>                How many threads to run the model on [default: one per core]
+  --tile-size <n>              Run the model on tiles of at least this size along its space and time axes
+  -h, --help                   Print this message
+
+Environment:
+  BIOIMG_PROXY                 Proxy for all requests [default: ALL_PROXY, HTTPS_PROXY or HTTP_PROXY]
+  BIOIMG_CA_BUNDLE             PEM file of certificates to trust on top of the usual ones
+  BIOIMG_TOKENS                Comma separated host=token pairs, sent as bearer tokens to those hosts
+";
+
+enum Command {
+    Validate {
+        path: PathBuf,
+        json: bool,
+        show: Severity,
+        fail_on: Severity,
+        watch: bool,
+    },
+    Package {
+        rdf_path: PathBuf,
+        output: PathBuf,
+        compression_level: Option<i32>,
+        /// Extensions of the files to store or deflate instead of the default
+        compression_overrides: Vec<(String, FileCompression)>,
+        recompute_hashes: bool,
+    },
+    Upgrade {
+        path: PathBuf,
+        output: Option<PathBuf>,
+    },
+    Card {
+        path: PathBuf,
+        format: CardFormat,
+        output: Option<PathBuf>,
+    },
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        json: bool,
+    },
+    Test {
+        path: PathBuf,
+        config: BackendConfig,
+        tile_size: Option<usize>,
+    },
+    Corpus {
+        dir: PathBuf,
+    },
+}
+
+fn parse_args() -> Result<Command, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    let command = match args.subcommand()?.as_deref() {
+        Some("validate") => Command::Validate {
+            json: args.contains("--json"),
+            show: args.opt_value_from_fn("--show", parse_severity)?.unwrap_or(Severity::Info),
+            fail_on: args.opt_value_from_fn("--fail-on", parse_severity)?.unwrap_or(Severity::Error),
+            watch: args.contains("--watch"),
+            path: args.free_from_str()?,
+        },
+        Some("package") => Command::Package {
+            output: args
+                .opt_value_from_str(["-o", "--output"])?
+                .unwrap_or_else(|| PathBuf::from("model.zip")),
+            compression_level: args.opt_value_from_fn("--compression-level", parse_compression_level)?,
+            compression_overrides: {
+                let stored = args.values_from_str::<_, String>("--store")?.into_iter();
+                let deflated = args.values_from_str::<_, String>("--deflate")?.into_iter();
+                let stored = stored.map(|extension| (extension, FileCompression::Store));
+                stored.chain(deflated.map(|extension| (extension, FileCompression::Deflate))).collect()
+            },
+            recompute_hashes: args.contains("--recompute-hashes"),
+            rdf_path: args.free_from_str()?,
+        },
+        Some("upgrade") => Command::Upgrade {
+            output: args.opt_value_from_str(["-o", "--output"])?,
+            path: args.free_from_str()?,
+        },
+        Some("card") => Command::Card {
+            format: match args.contains("--html") {
+                true => CardFormat::Html,
+                false => CardFormat::Markdown,
+            },
+            output: args.opt_value_from_str(["-o", "--output"])?,
+            path: args.free_from_str()?,
+        },
+        Some("diff") => Command::Diff {
+            json: args.contains("--json"),
+            old: args.free_from_str()?,
+            new: args.free_from_str()?,
+        },
+        Some("test") => Command::Test {
+            config: {
+                let default = BackendConfig::default();
+                BackendConfig {
+                    num_threads: args.opt_value_from_str("--threads")?.unwrap_or(default.num_threads),
+                    ..default
+                }
+            },
+            tile_size: args.opt_value_from_str("--tile-size")?,
+            path: args.free_from_str()?,
+        },
+        Some("corpus") => Command::Corpus {
+            dir: args.free_from_str()?,
+        },
+        Some(other) => {
+            return Err(pico_args::Error::ArgumentParsingFailed {
+                cause: format!("unknown command '{other}'"),
+            })
+        }
+        None => {
+            return Err(pico_args::Error::ArgumentParsingFailed {
+                cause: "no command given".to_owned(),
+            })
+        }
+    };
+    let rest = args.finish();
+    if !rest.is_empty() {
+        let cause = format!("unexpected arguments {rest:?}");
+        return Err(pico_args::Error::ArgumentParsingFailed { cause });
+    }
+    Ok(command)
+}
+
+fn parse_severity(raw: &str) -> Result<Severity, String> {
+    raw.parse().map_err(|_| format!("'{raw}' is not one of info, warning or error"))
+}
+
+fn parse_compression_level(raw: &str) -> Result<i32, String> {
+    match raw.parse() {
+        Ok(level @ 0..=9) => Ok(level),
+        _ => Err(format!("'{raw}' is not a level from 0 to 9")),
+    }
+}
+
+fn package(
+    rdf_path: &Path,
+    output: &Path,
+    compression_level: Option<i32>,
+    compression_overrides: &[(String, FileCompression)],
+    recompute_hashes: bool,
+) -> Result<PackageManifest, String> {
+    let mut package = ModelPackage::from_description_file(rdf_path).map_err(|err| err.to_string())?;
+    package.set_compression_level(compression_level);
+    for (extension, compression) in compression_overrides {
+        package.set_compression_for(extension.trim_start_matches('.'), *compression);
+    }
+    if recompute_hashes {
+        package.clear_hashes();
+    }
+    let manifest = package.write_to_path(output).map_err(|err| err.to_string());
+    if manifest.is_err() {
+        // don't leave a broken zip behind
+        std::fs::remove_file(output).ok();
+    }
+    manifest
+}
+
+fn read_model(path: &Path) -> Result<ModelRdf, String> {
+    let raw = read_rdf_text(path).map_err(|err| err.to_string())?;
+    ModelRdf::from_yaml(&raw).map_err(|err| err.to_string())
+}
+
+fn card(path: &Path, format: CardFormat) -> Result<String, String> {
+    Ok(model_card(&read_model(path)?, format))
+}
+
+fn diff(old: &Path, new: &Path) -> Result<Changelist, String> {
+    let read = |path: &Path| read_model(path).map_err(|err| format!("Could not read {}: {err}", path.display()));
+    read(old)?.diff(&read(new)?).map_err(|err| err.to_string())
+}
+
+fn self_test(path: &Path, config: BackendConfig, tile_size: Option<usize>) -> Result<SelfTestReport, String> {
+    let package = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => ModelPackage::from_description_file(path),
+        _ => ModelPackage::open(path),
+    };
+    let package = package.map_err(|err| err.to_string())?;
+    let mut backend = load_backend(&package, config).map_err(|err| err.to_string())?;
+    if let Some(tile_size) = tile_size {
+        backend = Box::new(TiledBackend::new(&package.rdf, backend, tile_size).map_err(|err| err.to_string())?);
+    }
+    package.self_test(backend.as_mut(), Tolerance::default()).map_err(|err| err.to_string())
+}
+
+/// The upgraded description and the warnings about what couldn't be carried over as it was
+fn upgrade(path: &Path) -> Result<(String, ValidationReport), String> {
+    let raw = read_rdf_text(path).map_err(|err| err.to_string())?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&raw).map_err(|err| err.to_string())?;
+    let format_version = value.get("format_version").and_then(|v| Version::try_from(v.as_str()?).ok());
+    match format_version {
+        Some(Version { major: 0, minor: 4, .. }) => (),
+        Some(version) => {
+            return Err(format!(
+                "Only format 0.4 descriptions can be upgraded, found format {version}"
+            ))
+        }
+        None => return Err("Description has no format_version".to_owned()),
+    }
+    let v04: ModelRdfV04 = spec_error::from_value(value.clone()).map_err(|err| err.to_string())?;
+    let notes = v04.upgrade_notes(&value);
+    let upgraded = ModelRdf::try_from(v04).map_err(|err| err.to_string())?;
+    Ok((upgraded.to_yaml().map_err(|err| err.to_string())?, notes))
+}
+
+fn shown(report: &ValidationReport, show: Severity) -> ValidationReport {
+    ValidationReport {
+        entries: report.at_least(show).cloned().collect(),
+    }
+}
+
+fn summary(path: &Path, report: &ValidationReport) -> String {
+    let (errors, warnings, infos) = (report.errors().count(), report.warnings().count(), report.infos().count());
+    format!("{}: {errors} errors, {warnings} warnings, {infos} infos", path.display())
+}
+
+/// Prints the whole first report, then only what changed in later ones
+fn print_update(path: &Path, update: &WatchUpdate, json: bool, show: Severity) {
+    if json {
+        println!("{}", shown(&update.report, show).to_json());
+        return;
+    }
+    if update.changed_files.is_empty() {
+        for entry in update.report.at_least(show) {
+            println!("{entry}");
+        }
+    } else {
+        let changed: Vec<_> = update.changed_files.iter().map(|file| file.display().to_string()).collect();
+        println!("\nChanged: {}", changed.join(", "));
+        for entry in update.resolved.iter().filter(|entry| entry.severity >= show) {
+            println!("- {entry}");
+        }
+        for entry in update.added.iter().filter(|entry| entry.severity >= show) {
+            println!("+ {entry}");
+        }
+    }
+    println!("{}", summary(path, &update.report));
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|arg| arg == "-h" || arg == "--help") {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    let command = match parse_args() {
+        Ok(command) => command,
+        Err(err) => {
+            eprint!("{err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    if let Err(err) = NetworkConfig::from_env().and_then(|config| network::configure(&config)) {
+        eprintln!("{err}");
+        return ExitCode::from(2);
+    }
+
+    match command {
+        Command::Validate {
+            path,
+            json,
+            show,
+            watch: true,
+            ..
+        } => {
+            ValidationWatcher::new(&path).run(DEFAULT_POLL_INTERVAL, |update| {
+                print_update(&path, update, json, show);
+                ControlFlow::Continue(())
+            });
+            ExitCode::SUCCESS
+        }
+        Command::Validate {
+            path,
+            json,
+            show,
+            fail_on,
+            watch: false,
+        } => {
+            let report = validate_path(&path);
+            if json {
+                println!("{:#}", shown(&report, show).to_json());
+            } else {
+                for entry in report.at_least(show) {
+                    println!("{entry}");
+                }
+                println!("{}", summary(&path, &report));
+            }
+            match report.fails_at(fail_on) {
+                false => ExitCode::SUCCESS,
+                true => ExitCode::FAILURE,
+            }
+        }
+        Command::Package {
+            rdf_path,
+            output,
+            compression_level,
+            compression_overrides,
+            recompute_hashes,
+        } => match package(&rdf_path, &output, compression_level, &compression_overrides, recompute_hashes) {
+            Ok(manifest) => {
+                for entry in &manifest.entries {
+                    println!("{}  {} ({} bytes)", entry.sha256, entry.path.display(), entry.size);
+                }
+                println!("Wrote {}", output.display());
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Could not package {}: {err}", rdf_path.display());
+                ExitCode::FAILURE
+            }
+        },
+        Command::Upgrade { path, output } => {
+            let (upgraded, notes) = match upgrade(&path) {
+                Ok(upgraded) => upgraded,
+                Err(err) => {
+                    eprintln!("Could not upgrade {}: {err}", path.display());
+                    return ExitCode::FAILURE;
+                }
+            };
+            for entry in &notes.entries {
+                eprintln!("{entry}");
+            }
+            match output {
+                None => print!("{upgraded}"),
+                Some(output) => {
+                    if let Err(err) = std::fs::write(&output, upgraded) {
+                        eprintln!("Could not write {}: {err}", output.display());
+                        return ExitCode::FAILURE;
+                    }
+                    eprintln!("Wrote {}", output.display());
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Card { path, format, output } => {
+            let card = match card(&path, format) {
+                Ok(card) => card,
+                Err(err) => {
+                    eprintln!("Could not read {}: {err}", path.display());
+                    return ExitCode::FAILURE;
+                }
+            };
+            match output {
+                None => print!("{card}"),
+                Some(output) => {
+                    if let Err(err) = std::fs::write(&output, card) {
+                        eprintln!("Could not write {}: {err}", output.display());
+                        return ExitCode::FAILURE;
+                    }
+                    eprintln!("Wrote {}", output.display());
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Diff { old, new, json } => {
+            let changelist = match diff(&old, &new) {
+                Ok(changelist) => changelist,
+                Err(err) => {
+                    eprintln!("{err}");
+                    return ExitCode::from(2);
+                }
+            };
+            if json {
+                println!("{:#}", changelist.to_json());
+            } else {
+                for change in &changelist.changes {
+                    println!("{change}");
+                }
+            }
+            match changelist.is_empty() {
+                true => ExitCode::SUCCESS,
+                false => ExitCode::FAILURE,
+            }
+        }
+        Command::Test { path, config, tile_size } => {
+            let report = match self_test(&path, config, tile_size) {
+                Ok(report) => report,
+                Err(err) => {
+                    eprintln!("Could not test {}: {err}", path.display());
+                    return ExitCode::FAILURE;
+                }
+            };
+            for output in &report.outputs {
+                if output.passed() {
+                    println!("{}: matches the test output", output.tensor_id);
+                } else if output.found_shape != output.expected_shape {
+                    println!(
+                        "{}: expected shape {:?}, found {:?}",
+                        output.tensor_id, output.expected_shape, output.found_shape
+                    );
+                } else {
+                    println!(
+                        "{}: {} values differ from the test output, by up to {}",
+                        output.tensor_id, output.mismatched, output.max_difference
+                    );
+                }
+            }
+            match report.passed() {
+                true => ExitCode::SUCCESS,
+                false => ExitCode::FAILURE,
+            }
+        }
+        Command::Corpus { dir } => {
+            let report = match run_corpus(&dir) {
+                Ok(report) => report,
+                Err(err) => {
+                    eprintln!("{err}");
+                    return ExitCode::from(2);
+                }
+            };
+            print!("{report}");
+            match report.is_success() {
+                true => ExitCode::SUCCESS,
+                false => ExitCode::FAILURE,
+            }
+        }
+    }
+}
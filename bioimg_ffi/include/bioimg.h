@@ -0,0 +1,55 @@
+/* Generated with `cbindgen --config cbindgen.toml --output include/bioimg.h` from bioimg_ffi/src/lib.rs */
+
+#ifndef BIOIMG_H
+#define BIOIMG_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * Validates the text of a model description, returning the report as
+ * `{"valid": bool, "entries": [{"severity": "error" | "warning" | "info", "path": str, "message": str}]}`
+ *
+ * # Safety
+ * `yaml` must be null or point to a nul-terminated string
+ */
+char *bioimg_validate_yaml(const char *yaml);
+
+/**
+ * Validates a description file, or a packaged model (a zip or a directory) along with its files. The report is
+ * the same as the one of [bioimg_validate_yaml].
+ *
+ * # Safety
+ * `path` must be null or point to a nul-terminated string
+ */
+char *bioimg_validate_path(const char *path);
+
+/**
+ * Writes a description and the local files it refers to into a zip at `output`. `compression_level` goes from 0
+ * (fastest) to 9 (smallest), or is -1 for the default. Hashes already in the description must match the files,
+ * unless `recompute_hashes` is set.
+ *
+ * Returns `{"ok": true, "files": [{"path": str, "size": int, "sha256": str}]}` with every file written, or
+ * `{"ok": false, "error": str}`.
+ *
+ * # Safety
+ * `rdf_path` and `output` must be null or point to nul-terminated strings
+ */
+char *bioimg_package(const char *rdf_path, const char *output, int compression_level, bool recompute_hashes);
+
+/**
+ * Frees a string returned by any of the functions above. Passing null does nothing.
+ *
+ * # Safety
+ * `string` must be null or have been returned by this library, and must not be used afterwards
+ */
+void bioimg_string_free(char *string);
+
+#ifdef __cplusplus
+}  /* extern "C" */
+#endif
+
+#endif  /* BIOIMG_H */
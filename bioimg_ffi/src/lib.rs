@@ -0,0 +1,173 @@
+//! A C interface to the validator and the packager, for tools that embed them instead of running the command line,
+//! e.g. ImageJ plugins or acquisition software. `include/bioimg.h` declares it.
+//!
+//! Every function returns a JSON string allocated here, which has to be given back to [bioimg_string_free]. Bad
+//! arguments are reported in that JSON as well, so the result never needs to be checked for null. So are panics,
+//! which must not unwind into the host application.
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, UnwindSafe};
+use std::path::Path;
+
+use bioimg_spec::rdf::{Severity, ValidationReport};
+use bioimg_spec::runtime::{validate_path, validate_yaml, ModelPackage};
+
+/// Hands `json` over to the caller
+fn into_c_string(json: serde_json::Value) -> *mut c_char {
+    // serde_json escapes control characters, so there can't be a nul in the middle
+    CString::new(json.to_string()).expect("json has no nul bytes").into_raw()
+}
+
+/// Reads a utf-8 string argument, naming it in the error otherwise
+///
+/// # Safety
+/// `raw` must be null or point to a nul-terminated string
+unsafe fn read_str<'a>(raw: *const c_char, name: &str) -> Result<&'a str, String> {
+    if raw.is_null() {
+        return Err(format!("{name} is null"));
+    }
+    CStr::from_ptr(raw).to_str().map_err(|err| format!("{name} is not utf-8: {err}"))
+}
+
+/// Runs `job`, turning a panic into an error message rather than letting it unwind across the C boundary
+fn catch_panic<T>(job: impl FnOnce() -> Result<T, String> + UnwindSafe) -> Result<T, String> {
+    catch_unwind(job).unwrap_or_else(|payload| {
+        let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+            (Some(message), _) => message.to_string(),
+            (None, Some(message)) => message.clone(),
+            (None, None) => "unknown error".to_owned(),
+        };
+        Err(format!("Internal error: {message}"))
+    })
+}
+
+fn error_report(message: String) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    report.push(Severity::Error, vec![], message);
+    report
+}
+
+/// Validates the text of a model description, returning the report as
+/// `{"valid": bool, "entries": [{"severity": "error" | "warning" | "info", "path": str, "message": str}]}`
+///
+/// # Safety
+/// `yaml` must be null or point to a nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn bioimg_validate_yaml(yaml: *const c_char) -> *mut c_char {
+    let report = catch_panic(|| read_str(yaml, "yaml").map(validate_yaml)).unwrap_or_else(error_report);
+    into_c_string(report.to_json())
+}
+
+/// Validates a description file, or a packaged model (a zip or a directory) along with its files. The report is
+/// the same as the one of [bioimg_validate_yaml].
+///
+/// # Safety
+/// `path` must be null or point to a nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn bioimg_validate_path(path: *const c_char) -> *mut c_char {
+    let report = catch_panic(|| read_str(path, "path").map(|path| validate_path(Path::new(path)))).unwrap_or_else(error_report);
+    into_c_string(report.to_json())
+}
+
+fn package(
+    rdf_path: &Path,
+    output: &Path,
+    compression_level: c_int,
+    recompute_hashes: bool,
+) -> Result<serde_json::Value, String> {
+    let compression_level = match compression_level {
+        -1 => None,
+        level @ 0..=9 => Some(level),
+        level => return Err(format!("{level} is not a compression level from 0 to 9")),
+    };
+    let mut package = ModelPackage::from_description_file(rdf_path).map_err(|err| err.to_string())?;
+    package.set_compression_level(compression_level);
+    if recompute_hashes {
+        package.clear_hashes();
+    }
+    let manifest = package.write_to_path(output).map_err(|err| {
+        // don't leave a broken zip behind
+        std::fs::remove_file(output).ok();
+        err.to_string()
+    })?;
+    let files: Vec<_> = manifest
+        .entries
+        .iter()
+        .map(|entry| {
+            serde_json::json!({
+                "path": entry.path.to_string_lossy(),
+                "size": entry.size,
+                "sha256": entry.sha256.to_string(),
+            })
+        })
+        .collect();
+    Ok(serde_json::json!({ "ok": true, "files": files }))
+}
+
+/// Writes a description and the local files it refers to into a zip at `output`. `compression_level` goes from 0
+/// (fastest) to 9 (smallest), or is -1 for the default. Hashes already in the description must match the files,
+/// unless `recompute_hashes` is set.
+///
+/// Returns `{"ok": true, "files": [{"path": str, "size": int, "sha256": str}]}` with every file written, or
+/// `{"ok": false, "error": str}`.
+///
+/// # Safety
+/// `rdf_path` and `output` must be null or point to nul-terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn bioimg_package(
+    rdf_path: *const c_char,
+    output: *const c_char,
+    compression_level: c_int,
+    recompute_hashes: bool,
+) -> *mut c_char {
+    let result = catch_panic(|| {
+        let rdf_path = read_str(rdf_path, "rdf_path")?;
+        let output = read_str(output, "output")?;
+        package(Path::new(rdf_path), Path::new(output), compression_level, recompute_hashes)
+    });
+    into_c_string(result.unwrap_or_else(|error| serde_json::json!({ "ok": false, "error": error })))
+}
+
+/// Frees a string returned by any of the functions above. Passing null does nothing.
+///
+/// # Safety
+/// `string` must be null or have been returned by this library, and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn bioimg_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[test]
+fn test_validating_through_the_c_interface() {
+    let read_result = |raw: *mut c_char| {
+        let json: serde_json::Value = serde_json::from_str(unsafe { CStr::from_ptr(raw) }.to_str().unwrap()).unwrap();
+        unsafe { bioimg_string_free(raw) };
+        json
+    };
+
+    let yaml = CString::new("format_version: 0.5.0\ntype: model\n").unwrap();
+    let report = read_result(unsafe { bioimg_validate_yaml(yaml.as_ptr()) });
+    assert_eq!(report["valid"], false);
+    assert!(!report["entries"].as_array().unwrap().is_empty());
+
+    let report = read_result(unsafe { bioimg_validate_yaml(std::ptr::null()) });
+    assert_eq!(report["entries"][0]["message"], "yaml is null");
+
+    let output = CString::new("unused.zip").unwrap();
+    let result = read_result(unsafe { bioimg_package(std::ptr::null(), output.as_ptr(), 12, false) });
+    assert_eq!(result["ok"], false);
+    assert_eq!(result["error"], "rdf_path is null");
+}
+
+#[test]
+fn test_catching_panics() {
+    assert_eq!(catch_panic(|| Ok::<_, String>(1)), Ok(1));
+    assert_eq!(catch_panic::<()>(|| panic!("oops")), Err("Internal error: oops".into()));
+    let index = 3;
+    assert_eq!(
+        catch_panic::<()>(move || panic!("index {index}")),
+        Err("Internal error: index 3".into())
+    );
+}
@@ -0,0 +1,579 @@
+# German catalog. Messages missing here are shown in English.
+
+## Main window
+packages = Pakete
+no-file-selected = Keine Datei ausgewählt
+model = Modell
+dataset = Datensatz
+collection = Sammlung
+import = Importieren...
+imported-from = Importiert aus { $source } (sha256 { $sha256 }…)
+export-collection = Sammlung exportieren...
+export-dataset = Datensatz exportieren...
+saved-to = Gespeichert unter { $path }
+export-failed = Export fehlgeschlagen: { $error }
+export-model = Modell exportieren...
+model-exported = Modell nach { $path } exportiert (sha256 { $sha256 })
+export-blocked = Modell kann nicht exportiert werden
+export-blocked-summary = Das Modell kann erst exportiert werden, wenn diese Fehler behoben sind:
+model-needs-one = Das Modell braucht mindestens ein(e) { $item }
+package-duplicate-name = Es gibt mehr als eine Datei namens { $name } im Paket
+import-failed = Import fehlgeschlagen: { $error }
+open-model = Modell öffnen...
+open-model-failed = Öffnen des Modells fehlgeschlagen: { $error }
+hash-computed = sha256 berechnet: { $sha256 }
+imported-invalid = Die importierte Ressource ist nicht gültig: { $error }
+save-draft = Entwurf speichern...
+open-draft = Entwurf öffnen...
+recent = Zuletzt geöffnet
+clear-recent = Liste leeren
+no-recent-files = Keine zuletzt geöffneten Dateien
+recent-draft = Entwurf
+recent-model = Modell
+recent-file-missing = { $path } existiert nicht mehr
+welcome = Willkommen zurück
+welcome-recent = Dort weitermachen, wo Sie aufgehört haben:
+start-new = Etwas Neues beginnen
+recover-title = Ungespeicherte Arbeit wiederherstellen
+recover-prompt = Die App wurde beim letzten Mal nicht richtig beendet. Soll wiederhergestellt werden, was damals bearbeitet wurde?
+restore-autosave = Wiederherstellen
+discard-autosave = Verwerfen
+autosave-restored = Die ungespeicherte Arbeit der letzten Sitzung wurde wiederhergestellt
+autosave-restore-failed = Die ungespeicherte Arbeit konnte nicht wiederhergestellt werden: { $error }
+drafts = Entwürfe
+draft-opened = Entwurf { $path } geöffnet
+draft-save-failed = Der Entwurf konnte nicht gespeichert werden: { $error }
+draft-open-failed = Der Entwurf konnte nicht geöffnet werden: { $error }
+draft-too-new = Dieser Entwurf stammt aus einer neueren Version der App ({ $app_version }). Bitte aktualisieren Sie die App.
+draft-unsupported = Das Entwurfsformat { $version } wird nicht mehr unterstützt
+undo = Rückgängig
+redo = Wiederholen
+validate-all = Alles prüfen
+validation-passed = Alle Felder sind gültig
+validation-failed = Ungültiges Feld: { $error }
+validate-model = Modell validieren
+validation-report-clean = Das Modell hat alle Prüfungen der Spezifikation bestanden
+validation-report-summary = { $errors } Fehler, { $warnings } Warnungen und { $infos } Hinweise gefunden
+validation-report-incomplete = Das Modell ließ sich nicht zusammensetzen, daher sind nur Fehler einzelner Felder aufgeführt
+severity-error = Fehler
+severity-warning = Warnung
+severity-info = Hinweis
+validation-report-show = Anzeigen:
+validation-report-fail-on = Fehlschlagen ab:
+validation-report-fails = Scheitert auf dieser Stufe
+validation-report-passes = Besteht auf dieser Stufe
+run-test = Test ausführen
+running-test = Das Modell wird mit seinen Testeingaben ausgeführt
+run-test-no-runtime = Diese Version hat noch keine Laufzeitumgebung, um Gewichte auszuführen
+run-test-unsupported-weights = Keine der Gewichte können hier ausgeführt werden; nur Gewichte im Format { $formats }
+run-test-missing-files = Erst müssen die Gewichte und der Testtensor jeder Eingabe geladen werden
+self-test-not-run = Der Test konnte nicht ausgeführt werden:
+self-test-passed = Das Modell hat alle Testausgaben erzeugt
+self-test-failed = Das Modell hat die Testausgaben nicht erzeugt
+self-test-shape-mismatch = Das Modell hat einen Tensor der Form ({ $found }) statt ({ $expected }) erzeugt
+self-test-max-difference = Größte absolute Abweichung: { $difference }
+self-test-mismatched = { $mismatched } von { $total } Werten weichen mehr als die Toleranz ab
+self-test-produced = Erzeugt
+self-test-expected = Erwartet
+error-summary = Fehler
+form-group-general = Allgemein
+form-group-people = Autoren & Zitate
+form-group-files = Dateien
+form-group-weights = Gewichte
+form-group-inputs = Eingaben
+form-group-outputs = Ausgaben
+form-group-errors = { $count } ungültige Felder in diesem Teil des Formulars
+field-search-hint = Feld suchen ({ $shortcut })
+field-search-matches = { $count } gefunden
+field-search-next = Zum nächsten gefundenen Feld
+field-search-clear = Suche beenden
+focus-first-error = Zum ersten Fehler springen
+no-errors = Nichts zu beheben
+jump-to-field = Zum Feld springen
+settings = Einstellungen
+inference = Inferenz
+appearance = Darstellung
+editing = Bearbeitung
+undo-depth-label = Rückgängig-Schritte:
+file-dialogs = Dateien
+default-save-dir-label = Speichern in:
+default-save-dir-hint = wo die letzte Datei gespeichert wurde
+browse-dir = Durchsuchen…
+last-used-dirs-label = Zuletzt verwendet:
+forget-dirs = Vergessen
+network = Netzwerk
+proxy-label = Proxy:
+proxy-hint = aus der Umgebung, z. B. HTTPS_PROXY
+ca-bundle-label = Zusätzliche Zertifikate:
+ca-bundle-hint = PEM-Datei, z. B. der CA einer Einrichtung
+bearer-tokens-label = Tokens:
+bearer-tokens-hint = host=token, durch Kommas getrennt
+bearer-tokens-help = Werden als Bearer-Tokens an ihre Hosts gesendet, z. B. private Modellregister. Sie werden nicht gespeichert.
+apply-network = Anwenden
+network-settings-failed = Netzwerkeinstellungen konnten nicht übernommen werden: { $error }
+unsupported-resource = Nicht unterstützte Ressource
+name-label = Name:
+description-label = Beschreibung:
+cover-images-label = Titelbilder:
+attachments-label = Anhänge:
+authors-label = Autoren:
+cite-label = Zitieren:
+git-repo-label = Git-Repository:
+icon-label = Symbol:
+links-label = Verweise:
+badges-label = Abzeichen:
+badge-label-label = Beschriftung:
+badge-icon-label = Symbol:
+badge-url-label = Url:
+loading-badge-icon = Symbol des Abzeichens wird geladen
+badge-icon-not-image = Das Symbol ist kein Bild, das sich hier zeigen lässt; svg-Symbole erscheinen nur im Web
+badge-icon-preview-failed = Das Symbol kann nicht angezeigt werden: { $error }
+maintainers-label = Betreuer:
+tags-label = Schlagwörter:
+resource-version-label = Ressourcenversion:
+new-version = Neue Version
+bump-version-hint = Vermerkt die aktuelle Version im Verlauf und beginnt Version { $next }
+version-already-exported = Version { $version } wurde bereits exportiert; erhöhen Sie sie vor der nächsten Veröffentlichung
+documentation-markdown-label = Dokumentation (Markdown):
+license-label = Lizenz:
+collection-label = Sammlung:
+dataset-label = Datensatz:
+example-tensor-label = Beispieltensor:
+model-family-label = Modellfamilie:
+parent-label = Elternmodell:
+run-mode-label = Ausführungsmodus:
+training-data-label = Trainingsdaten:
+training-data-linked = Datensatz im Zoo
+training-data-inline = Datensatz beschreiben
+tensor-data-label = Tensordaten:
+dependencies-label = Abhängigkeiten:
+model-properties = Modelleigenschaften
+dataset-properties = Datensatzeigenschaften
+collection-properties = Sammlungseigenschaften
+cover-image = Titelbild
+link = Verweis
+badge = Abzeichen
+attachment = Anhang
+author = Autor
+maintainer = Betreuer
+tag = Schlagwort
+possible-duplicates = { $entries } sehen nach derselben Person aus
+merge = Zusammenführen
+
+## Notifications
+dismiss = Schließen
+status-idle = Bereit
+tasks-button = Aufgaben ({ $count })
+
+## Tasks
+tasks = Aufgaben
+no-running-tasks = Keine laufenden Aufgaben
+cancel = Abbrechen
+cancelling = Wird abgebrochen...
+importing = { $path } wird importiert
+opening-model = { $path } wird geöffnet
+exporting-model = Modell wird nach { $path } exportiert
+
+## Command palette
+command-palette = Befehlspalette
+type-a-command = Befehl eingeben
+no-matching-commands = Keine passenden Befehle
+palette-switch-mode = Zu { $mode } wechseln
+palette-jump-to = Gehe zu { $section }
+
+## Session log
+session-log = Protokoll
+warnings-only = Nur Warnungen
+copy-diagnostics = Diagnose kopieren
+clear = Leeren
+log-is-empty = Bisher wurde nichts protokolliert
+
+## Zoo browser
+open-from-zoo = Modell aus dem Zoo öffnen...
+new-from-template = Neu aus Vorlage
+palette-new-from-template = Neues Modell aus Vorlage: { $template }
+template-applied = Neues Modell aus der Vorlage { $template } begonnen. Wählen Sie noch Testtensoren und Gewichte.
+template-segmentation-2d = 2D-U-Net-Segmentierung
+template-segmentation-2d-hint = Eingabe: einkanalige 2D-Bilder. Ausgabe: gleich große Vordergrund-Wahrscheinlichkeiten
+template-denoising-3d = 3D-Entrauschung
+template-denoising-3d-hint = Eingabe: einkanalige 3D-Volumen. Ausgabe: gleich große entrauschte Volumen
+template-classification-2d = 2D-Klassifikation
+template-classification-2d-hint = Eingabe: 2D-Bilder fester Größe. Ausgabe: eine Wahrscheinlichkeit pro Klasse
+loading-zoo = Die bioimage.io-Sammlung wird geladen...
+zoo-search = Nach Name oder Beschreibung suchen
+refresh = Aktualisieren
+model-card = Modellkarte
+markdown = Markdown
+html = HTML
+copy = Kopieren
+save-as = Speichern unter…
+model-card-incomplete = Das Modell kann noch nicht zusammengestellt werden:
+model-card-save-failed = Die Modellkarte konnte nicht gespeichert werden: { $error }
+zoo-no-matches = Keine passenden Modelle
+zoo-failed = Die Sammlung konnte nicht geladen werden: { $error }
+downloading = { $name } wird heruntergeladen
+
+## Upload
+upload-package = Paket hochladen...
+package-label = Paket:
+upload-target-label = Hochladen nach:
+zenodo-sandbox = Zenodo (Sandbox)
+upload-endpoint-label = Upload-Endpunkt:
+api-token-label = API-Token:
+upload = Hochladen
+upload-in-progress = Wird hochgeladen, der Fortschritt steht in der Aufgabenliste
+uploading = { $path } wird hochgeladen
+upload-finished = Hochladen abgeschlossen { $location }
+upload-failed = Hochladen fehlgeschlagen: { $error }
+
+## Settings
+ui-scale-label = UI-Skalierung:
+font-size-label = Schriftgröße:
+theme-label = Farbschema:
+language-label = Sprache:
+follow-system = Wie System
+dark = Dunkel
+light = Hell
+reset = Zurücksetzen
+author-profile = Autorenprofil
+apply-profile = In aktuelle Ressource übernehmen
+default-tags-label = Standard-Schlagwörter (durch Kommas getrennt):
+default-license-label = Standardlizenz:
+
+## Accessibility
+required = Pflichtfeld
+a11y-required-field = { $label }, Pflichtfeld
+a11y-invalid-field = { $label }, ungültig: { $error }
+
+## Attachment widget
+attachment-missing = { $path } existiert nicht mehr
+attachment-not-loaded = { $path } konnte nicht gelesen werden
+attachment-still-loading = { $path } wird noch gelesen
+attachment-duplicate-name = Es gibt mehr als einen Anhang namens { $name }
+
+## Author widget
+affiliation-label = Zugehörigkeit:
+email-label = E-Mail:
+github-user-label = GitHub-Benutzer:
+fetch-orcid = Abrufen
+fetch-orcid-hint = Name und Zugehörigkeit aus dem öffentlichen ORCID-Eintrag übernehmen
+paste-snippet = 📋 Einfügen
+paste-snippet-hint = Aus JSON oder YAML ausfüllen, das aus einer anderen rdf.yaml oder einem bioimage.io-Werkzeug kopiert wurde
+paste-snippet-placeholder = JSON oder YAML hier einfügen (Strg+V)
+paste-snippet-not-yaml = Der eingefügte Text ist weder JSON noch YAML: { $error }
+paste-snippet-many = Der eingefügte Text hat { $count } Einträge; fügen Sie nur einen ein
+paste-snippet-mismatch = Der eingefügte Text passt hier nicht: { $error }
+check-github-user = Prüfen
+check-github-user-hint = Den Benutzernamen auf GitHub nachschlagen, um Tippfehler zu finden
+checking-github-user = Benutzer wird auf GitHub nachgeschlagen
+github-user-exists = { $name } (github.com/{ $login })
+github-organization-exists = Die Organisation { $name } (github.com/{ $login })
+github-user-missing = Es gibt kein GitHub-Konto namens { $login }
+github-user-check-failed = GitHub konnte nicht nach dem Benutzer gefragt werden: { $error }
+fetching-orcid = Der ORCID-Eintrag wird abgerufen...
+orcid-fetch-failed = Der ORCID-Eintrag konnte nicht abgerufen werden: { $error }
+checking-link = Der Link wird geprüft...
+link-reachable = Der Link funktioniert
+link-error-status = Der Server antwortete mit Fehler { $status }; der Link ist womöglich tot
+link-timed-out = Der Server hat zu lange nicht geantwortet
+link-unreachable = Der Link war nicht erreichbar: { $reason }
+
+## Cite widget
+look-up-doi = Nachschlagen
+look-up-doi-hint = Text und URL aus den für diese DOI registrierten Metadaten übernehmen
+import-bibtex = Aus BibTeX importieren
+import-bibtex-hint = Literaturangaben aus Einträgen einer .bib-Datei hinzufügen, eingefügt oder geöffnet
+bibtex-placeholder = Einen oder mehrere BibTeX-Einträge hier einfügen, beginnend mit @article, @book, ...
+import-bibtex-entries = Literaturangaben hinzufügen
+open-bib-file = .bib-Datei öffnen...
+bibtex-failed = BibTeX konnte nicht gelesen werden: { $error }
+bibtex-imported = { $count } Literaturangaben hinzugefügt
+import-author-table = Aus CSV / TSV importieren
+import-author-table-hint = Pro Tabellenzeile einen Autor hinzufügen, mit Spalten für Name, Zugehörigkeit, E-Mail, ORCID, GitHub
+author-table-placeholder = Zeilen hier einfügen, mit einer Kopfzeile wie: name, affiliation, email, orcid, github
+import-author-rows = Autoren hinzufügen
+open-author-table = .csv- oder .tsv-Datei öffnen...
+author-table-failed = Die Tabelle konnte nicht gelesen werden: { $error }
+author-rows-skipped = { $count } Zeilen wurden übersprungen:
+authors-imported = { $count } Autoren hinzugefügt
+looking-up-doi = Die DOI wird nachgeschlagen...
+doi-lookup-failed = Die DOI konnte nicht nachgeschlagen werden: { $error }
+
+## Backend config widget
+threads-label = Threads:
+
+## Collection widget
+summary-label = Zusammenfassung:
+collection-drop-hint = Modellpakete hier ablegen, um sie der Sammlung hinzuzufügen
+entry-number = Eintrag #{ $number }
+add-entry = + Eintrag hinzufügen
+add-packages = Pakete hinzufügen...
+model-packages = Modellpakete
+
+## Conda env widget
+conda-env-summary = { $conda } conda- und { $pip } pip-Abhängigkeiten
+conda-env-channels = Kanäle: { $channels }
+conda-env-no-channels = Keine Kanäle angegeben, daher nutzt conda die Kanäle, die auf dem ausführenden Rechner eingestellt sind
+conda-env-pip = pip:
+
+## Cover image widget
+cover-image-fitted = Auf { $width }x{ $height } zugeschnitten und verkleinert
+cover-image-details = { $width }x{ $height }, { $ratio }:1, { $size } von höchstens { $max_size }
+cover-image-primary = ★ Hauptbild
+cover-image-make-primary = Zum Hauptbild machen, das zuerst gezeigt wird
+cover-image-not-loaded = { $path } wurde nicht als Titelbild geladen
+adjust-crop = Zuschnitt anpassen
+crop-size = Größe
+crop-hint = Ziehen Sie das Rechteck über den Teil des Bildes, der als Titelbild dienen soll
+crop-region = { $width }x{ $height } Pixel des Originalbildes
+use-crop = Diesen Zuschnitt verwenden
+fitting-cover-image = Das Titelbild wird angepasst...
+
+## Dataset widget
+source-label = Quelle:
+number-of-samples-label = Anzahl der Proben:
+size-in-bytes-label = Größe in Bytes:
+
+## Documentation widget
+documentation-type-in = Eintippen
+documentation-from-file = Aus einer Datei
+documentation-missing = Das Modell braucht eine Dokumentation
+markdown-line-count = { $lines } Zeilen
+preview = Vorschau
+nothing-to-preview = Noch nichts zur Vorschau
+markdown-image-not-found = [Bild nicht gefunden: { $alt } ({ $src })]
+markdown-image-failed = Bild { $src } konnte nicht geladen werden: { $error }
+
+## Enum widget
+pick-one = Bitte auswählen
+
+## File widget
+none = Keine
+could-not-load-file = Datei konnte nicht geladen werden
+file-drop-hint = Wählen Sie eine Datei aus oder ziehen Sie sie auf dieses Feld
+loading = Wird geladen...
+loading-file = { $path } wird geladen
+open = Öffnen...
+file-hash = sha256: { $hash }
+file-metadata = { $size }, geändert am { $modified }
+file-changed-on-disk = Die Datei wurde seit dem Laden auf der Festplatte geändert
+reload-file = Neu laden
+
+## Icon widget
+emoji-icon = Emoji
+image-file-icon = Bilddatei
+icon-not-loaded = Es wurde noch kein Bild geladen
+pick-emoji = Emoji auswählen
+search-emoji = Emoji suchen
+no-emoji-found = Keine passenden Emoji
+emoji-category-science = Wissenschaft
+emoji-category-nature = Natur
+emoji-category-animals = Tiere
+emoji-category-objects = Gegenstände
+emoji-category-symbols = Symbole
+emoji-category-faces = Gesichter
+
+## Linked resource widget
+resource-id-label = Id:
+sha256-label = SHA-256:
+version-number-label = Versionsnummer:
+
+## Run mode widget
+run-mode-name-label = Name:
+run-mode-kwargs-label = Kwargs (yaml):
+run-mode-bad-kwargs = Kwargs müssen ein yaml-Mapping sein: { $reason }
+
+## Shared widgets
+add = Hinzufügen
+vec-add-item = + { $item } hinzufügen
+vec-add-item-hint = { $shortcut } fügt nach dem bearbeiteten Eintrag einen hinzu
+vec-remove-item = { $item } entfernen
+vec-insert-above = { $item } darüber einfügen
+vec-duplicate-item = { $item } darunter duplizieren
+vec-move-up = Nach oben
+vec-move-down = Nach unten
+
+## Partner configs widget
+partner-configs-label = Partner-Werkzeuge:
+pyramidal-model = Pyramidales Modell
+allow-tiling = Kachelung erlauben
+min-ilastik-version-label = Älteste ilastik-Version:
+partner-configs-unparsed = Importierte Einstellungen, die nicht gelesen werden konnten, bleiben unverändert: { $partners }
+
+## Tags widget
+add-tag-hint = Neues Tag, dann Enter
+remove-tag = Dieses Tag entfernen
+suggested-tags = Auf bioimage.io verwendet:
+suggested-tag-hint = Von { $count } veröffentlichten Ressourcen verwendet
+loading-published-tags = Die auf bioimage.io verwendeten Tags werden geladen...
+published-tags-failed = Die auf bioimage.io verwendeten Tags konnten nicht geladen werden: { $error }
+tag-duplicate = '{ $tag }' ist bereits ein Tag
+too-many-tags = Es sind höchstens { $max } Tags möglich
+tag-bad-character = Tags dürfen '{ $character }' nicht enthalten
+
+## Tensor axis widget
+size-label = Größe:
+channel-names-label = Kanalnamen:
+unit-label = Einheit:
+scale-label = Skalierung:
+channel-name = Kanalname
+
+## Tensor data widget
+tensor-data-kind-label = Art:
+interval-or-ratio = Intervall oder Verhältnis
+nominal-or-ordinal = Nominal oder ordinal
+data-type-label = Datentyp:
+max-label = Max:
+tensor-data-values-label = Werte (yaml-Liste):
+tensor-data-bad-values = Werte müssen eine nicht leere yaml-Liste sein: { $reason }
+
+## Unknown resource widget
+general-fields-are-valid = Die allgemeinen Felder sind gültig.
+general-fields-label = Allgemeine Felder:
+
+## Weights widget
+weights-label = Gewichte:
+weights-file-label = Gewichtsdatei:
+tensorflow-version-label = TensorFlow-Version:
+pytorch-version-label = PyTorch-Version:
+opset-version-label = ONNX-Opset-Version:
+architecture-label = Architektur:
+weights-parent-label = Konvertiert aus:
+weights-comment-label = Kommentar:
+weights-file-missing = Es wurde keine Gewichtsdatei ausgewählt
+remove-weights = Diese Gewichte entfernen
+add-weights = Gewichte hinzufügen...
+architecture-source-label = Quelle:
+architecture-from-file = Python-Datei
+architecture-from-library = Installierte Bibliothek
+architecture-from-url = URL
+architecture-file-label = Datei:
+architecture-import-from-label = Importieren aus:
+architecture-url-label = URL:
+architecture-sha256-label = SHA-256:
+architecture-callable-label = Aufrufbares Objekt:
+architecture-kwargs-label = Kwargs:
+architecture-bad-import-from = '{ $part }' im Modulpfad ist kein Python-Bezeichner: { $reason }
+architecture-file-missing = Es wurde keine Architekturdatei ausgewählt
+python-mismatched-bracket = Klammer { $opening } aus Zeile { $opening_line } wird in Zeile { $line } mit { $closing } geschlossen
+python-unopened-bracket = Die Klammer { $bracket } in Zeile { $line } schließt nichts
+python-unclosed-bracket = Die in Zeile { $line } geöffnete Klammer { $bracket } wird nie geschlossen
+python-callable-missing = Der Quelltext scheint { $callable } nicht zu definieren
+architecture-code-label = Quelltext:
+architecture-show-code = Quelltext anzeigen
+
+## Input tensor widget
+inputs-label = Eingaben:
+input-tensor = Eingabetensor
+optional-input-label = Optional:
+optional-input = Das Modell läuft auch ohne diese Eingabe
+
+## Output tensor widget
+outputs-label = Ausgaben:
+output-tensor = Ausgabetensor
+axis = Achse
+axes-label = Achsen:
+axis-type-label = Typ:
+batch-axis = Batch
+channel-axis = Kanal
+index-axis = Index
+time-axis = Zeit
+space-axis = Raum
+halo-label = Halo:
+test-tensor-label = Testtensor:
+infer-axes = Achsen ableiten
+infer-axes-hint = Die Achsen aus der Form des Testtensors übernehmen, zum Anpassen
+axes-guess-failed = Die Form des Testtensors konnte nicht gelesen werden: { $error }
+test-tensor-preview-label = Vorschau:
+tensor-preview-info = { $dtype }, Form ({ $shape })
+preview-rows = Zeilen
+preview-columns = Spalten
+preview-in-color = In Farbe anzeigen
+test-tensor-from-image-label = Aus einem Bild:
+generate-test-tensor = Aus einem Beispielbild erzeugen
+open-example-image = Bild öffnen
+images = Bilder
+example-image-size = { $width }x{ $height }, { $channels } Kanal/Kanäle
+image-dimension-y = Zeilen (y)
+image-dimension-x = Spalten (x)
+image-dimension-channel = Kanäle
+image-dimension-singleton = Größe 1
+image-dimensions-needed = Je eine Achse muss die Zeilen und die Spalten des Bildes aufnehmen, höchstens eine seine Kanäle
+drop-alpha-channel = Alphakanal verwerfen
+normalization-label = Normalisierung:
+normalization-none = Keine
+normalization-min-max = Auf 0..1 skalieren
+normalization-zero-mean-unit-variance = Mittelwert 0, Varianz 1
+save-test-tensor = Als Testtensor speichern
+saving-test-tensor = { $path } wird gespeichert...
+sample-tensor-label = Beispieltensor:
+postprocessing-label = Nachverarbeitung:
+tensor-no-axes = Ein Tensor braucht mindestens eine Achse
+test-tensor-missing = Es wurde kein Testtensor ausgewählt
+
+## Processing widget
+preprocessing-label = Vorverarbeitung:
+operation-label = Operation:
+processing-axis-label = Achse:
+processing-axes-label = Achsen (durch Kommas getrennt):
+threshold-label = Schwellenwert:
+gain-label = Verstärkung:
+eps-label = Epsilon:
+min-percentile-label = Minimales Perzentil:
+max-percentile-label = Maximales Perzentil:
+reference-tensor-label = Referenztensor:
+reference-tensor-missing = Wählen Sie den Tensor, an dessen Statistik angepasst werden soll
+mean-label = Mittelwert:
+std-label = Standardabweichung:
+no-kwargs = Diese Operation hat keine Kwargs
+bad-floats = '{ $value }' ist keine Zahl und keine durch Kommas getrennte Liste von Zahlen
+drag-to-reorder = Zum Umsortieren ziehen
+remove-step = Diesen Schritt entfernen
+add-step = + Schritt hinzufügen
+yaml-preview = YAML
+step-error = Schritt { $number }: { $error }
+
+## Checks across fields
+
+duplicate-tensor-id = Ein anderer Tensor hat ebenfalls die ID „{ $id }“
+duplicate-axis-id = Eine andere Achse dieses Tensors hat ebenfalls die ID „{ $id }“
+unknown-reference-tensor = Es gibt keinen Tensor mit der ID „{ $id }“
+unknown-reference-axis = Tensor „{ $tensor }“ hat keine Achse mit der ID „{ $id }“
+duplicate-author-name = Ein anderer Autor heißt ebenfalls „{ $name }“
+
+## Parsing errors
+
+error-length = Erwartet wurden { $min } bis { $max } Zeichen, gefunden { $length }
+error-bad-identifier = Ungültiger Bezeichner: { $error }
+error-empty-identifier = Ein leerer Text kann kein Bezeichner sein
+error-identifier-start = Das erste Zeichen muss ein Buchstabe oder _ sein: '{ $value }'
+error-identifier-character = Bezeichner dürfen nur Buchstaben, Ziffern und '_' enthalten: '{ $value }'
+error-python-keyword = '{ $value }' ist ein Python-Schlüsselwort
+error-not-lowercase = Das Zeichen an Position { $index } ist kein Kleinbuchstabe: { $value }
+error-has-slashes = Der Text enthält Schrägstriche: { $value }
+error-orcid-code = Ungültige ORCID: { $code }
+error-orcid-char = Ungültiges Zeichen in der ORCID: { $found }
+error-orcid-checksum-char = Ungültige ORCID-Prüfziffer: { $found }
+error-orcid-check-digit = Die Prüfziffer der ORCID { $code } stimmt nicht: erwartet { $expected }, gefunden { $found }
+error-doi-syntax = Erwartet wurde eine DOI wie '10.1000/xyz123', gefunden '{ $value }'
+
+## Hilfe zu Feldern
+help-max-length = Höchstens { $max } Zeichen
+help-length = { $min } bis { $max } Zeichen
+help-identifier = Beginnt mit einem Buchstaben oder _, enthält nur Buchstaben, Ziffern und _ und ist kein Python-Schlüsselwort
+help-lowercase = Keine Großbuchstaben
+help-no-slashes = Kein /
+help-unique = Keine Duplikate
+help-orcid = Eine ORCID iD wie 0000-0002-1825-0097, deren letzte Ziffer die anderen prüft
+help-doi = Ein DOI wie 10.1000/xyz123, ohne https://doi.org/
+help-version = Drei durch Punkte getrennte Zahlen, wie 1.2.3
+help-url = Eine http- oder https-URL
+help-example = Beispiel: { $example }
+
+## Kwargs
+kwarg-name = Name
+kwarg-value = Wert (yaml)
+kwarg-add = ➕ Argument hinzufügen
+kwarg-remove = Dieses Argument entfernen
+kwarg-bad-value = Der Wert ist kein gültiges yaml: { $reason }
+kwarg-duplicate = Das Argument { $name } ist mehrfach angegeben
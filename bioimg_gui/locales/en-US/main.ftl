@@ -0,0 +1,622 @@
+# English catalog. Every other language falls back to it for messages it leaves out.
+
+## Main window
+packages = Packages
+no-file-selected = No file selected
+model = Model
+dataset = Dataset
+collection = Collection
+import = Import...
+imported-from = Imported from { $source } (sha256 { $sha256 }…)
+export-collection = Export Collection...
+export-dataset = Export Dataset...
+saved-to = Saved to { $path }
+export-failed = Export failed: { $error }
+export-model = Export Model...
+model-exported = Exported the model to { $path } (sha256 { $sha256 })
+export-blocked = Can't Export the Model
+export-blocked-summary = The model can't be exported until these are fixed:
+model-needs-one = The model needs at least one { $item }
+package-duplicate-name = There is more than one file named { $name } in the package
+import-failed = Import failed: { $error }
+open-model = Open Model...
+open-model-failed = Opening the model failed: { $error }
+hash-computed = Computed sha256 { $sha256 }
+imported-invalid = The imported resource is not valid: { $error }
+save-draft = Save Draft...
+open-draft = Open Draft...
+recent = Recent
+clear-recent = Clear the list
+no-recent-files = No recent files
+recent-draft = draft
+recent-model = model
+recent-file-missing = { $path } no longer exists
+welcome = Welcome back
+welcome-recent = Pick up where you left off:
+start-new = Start something new
+recover-title = Recover unsaved work
+recover-prompt = The app didn't shut down properly last time. Restore what was being edited then?
+restore-autosave = Restore
+discard-autosave = Discard
+autosave-restored = Restored the unsaved work of the last session
+autosave-restore-failed = Could not restore the unsaved work: { $error }
+drafts = Drafts
+draft-opened = Opened draft { $path }
+draft-save-failed = Could not save the draft: { $error }
+draft-open-failed = Could not open the draft: { $error }
+draft-too-new = This draft was saved by a newer version of the app ({ $app_version }). Please update to open it.
+draft-unsupported = Draft format { $version } is not supported anymore
+undo = Undo
+redo = Redo
+validate-all = Validate All
+validation-passed = All fields are valid
+validation-failed = Invalid field: { $error }
+validate-model = Validate Model
+validation-report-clean = The model passed every check of the spec
+validation-report-summary = { $errors } errors, { $warnings } warnings and { $infos } infos were found
+validation-report-incomplete = The model could not be put together, so only errors of single fields are listed
+severity-error = Error
+severity-warning = Warning
+severity-info = Info
+validation-report-show = Show:
+validation-report-fail-on = Fail on:
+validation-report-fails = Fails at this level
+validation-report-passes = Passes at this level
+run-test = Run Test
+running-test = Running the model on its test inputs
+run-test-no-runtime = This build has no runtime to run weights with yet
+run-test-unsupported-weights = None of the weights can be run here; only { $formats } weights can
+run-test-missing-files = The weights and the test tensor of every input have to be loaded first
+self-test-not-run = The test could not be run:
+self-test-passed = The model produced every test output
+self-test-failed = The model did not produce the test outputs
+self-test-shape-mismatch = The model produced a tensor of shape ({ $found }) instead of ({ $expected })
+self-test-max-difference = Largest absolute difference: { $difference }
+self-test-mismatched = { $mismatched } of { $total } values are off by more than the tolerance
+self-test-produced = Produced
+self-test-expected = Expected
+error-summary = Errors
+form-group-general = General
+form-group-people = Authors & Citations
+form-group-files = Files
+form-group-weights = Weights
+form-group-inputs = Inputs
+form-group-outputs = Outputs
+form-group-errors = { $count } invalid fields in this part of the form
+field-search-hint = Find a field ({ $shortcut })
+field-search-matches = { $count } found
+field-search-next = Go to the next field found
+field-search-clear = Stop searching
+focus-first-error = Go to the first error
+no-errors = Nothing to fix
+jump-to-field = Go to the field
+settings = Settings
+inference = Inference
+appearance = Appearance
+editing = Editing
+undo-depth-label = Undo steps:
+file-dialogs = Files
+default-save-dir-label = Save to:
+default-save-dir-hint = where the last file was saved
+browse-dir = Browse…
+last-used-dirs-label = Last used:
+forget-dirs = Forget
+network = Network
+proxy-label = Proxy:
+proxy-hint = from the environment, e.g. HTTPS_PROXY
+ca-bundle-label = Extra certificates:
+ca-bundle-hint = PEM file, e.g. of an institution's CA
+bearer-tokens-label = Tokens:
+bearer-tokens-hint = host=token, separated by commas
+bearer-tokens-help = Sent as bearer tokens to the hosts they are for, e.g. private model registries. They are not saved.
+apply-network = Apply
+network-settings-failed = Network settings could not be applied: { $error }
+unsupported-resource = Unsupported Resource
+name-label = Name:
+description-label = Description:
+cover-images-label = Cover Images:
+attachments-label = Attachments:
+authors-label = Authors:
+cite-label = Cite:
+git-repo-label = Git Repo:
+icon-label = Icon:
+links-label = Links:
+badges-label = Badges:
+badge-label-label = Label:
+badge-icon-label = Icon:
+badge-url-label = Url:
+loading-badge-icon = Loading the badge icon
+badge-icon-not-image = The icon is not an image that can be previewed; svg icons are shown on the web but not here
+badge-icon-preview-failed = Can't preview the icon: { $error }
+maintainers-label = Maintainers:
+tags-label = Tags:
+resource-version-label = Resource Version:
+major = Major
+minor = Minor
+patch = Patch
+new-version = New Version
+bump-version-hint = Records the current version in the history and starts version { $next }
+version-already-exported = Version { $version } was exported already; bump it before publishing this model again
+upload-count = (upload #{ $number }, { $previous } previous)
+documentation-markdown-label = Documentation (markdown):
+license-label = License:
+collection-label = Collection:
+dataset-label = Dataset:
+example-tensor-label = Example tensor:
+model-family-label = Model Family:
+parent-label = Parent:
+run-mode-label = Run Mode:
+training-data-label = Training Data:
+training-data-linked = Dataset in the zoo
+training-data-inline = Describe the dataset
+tensor-data-label = Tensor Data:
+dependencies-label = Dependencies:
+test-axis-size-label = Test axis size:
+model-properties = Model Properties
+dataset-properties = Dataset Properties
+collection-properties = Collection Properties
+cover-image = Cover Image
+link = Link
+badge = Badge
+attachment = Attachment
+author = Author
+cite = Cite
+maintainer = Maintainer
+tag = Tag
+possible-duplicates = { $entries } look like the same { $item }
+merge = Merge
+
+## Notifications
+dismiss = Dismiss
+status-idle = Ready
+tasks-button = Tasks ({ $count })
+
+## Tasks
+tasks = Tasks
+no-running-tasks = No tasks are running
+cancel = Cancel
+cancelling = Cancelling...
+importing = Importing { $path }
+opening-model = Opening { $path }
+exporting-model = Exporting the model to { $path }
+
+## Command palette
+command-palette = Command Palette
+type-a-command = Type a command
+no-matching-commands = No matching commands
+palette-switch-mode = Switch to { $mode }
+palette-jump-to = Go to { $section }
+
+## Session log
+session-log = Log
+warnings-only = Warnings only
+copy-diagnostics = Copy Diagnostics
+clear = Clear
+log-is-empty = Nothing has been logged yet
+
+## Zoo browser
+open-from-zoo = Open Model From Zoo...
+new-from-template = New From Template
+palette-new-from-template = New model from template: { $template }
+template-applied = Started a new model from the { $template } template. Pick test tensors and weights to finish it.
+template-segmentation-2d = 2D U-Net segmentation
+template-segmentation-2d-hint = Single-channel 2D images in, a foreground probability map of the same size out
+template-denoising-3d = 3D denoising
+template-denoising-3d-hint = Single-channel 3D volumes in, denoised volumes of the same size out
+template-classification-2d = 2D classification
+template-classification-2d-hint = Fixed-size 2D images in, one probability per class out
+loading-zoo = Loading the bioimage.io collection...
+zoo-search = Search by name or description
+refresh = Refresh
+model-card = Model Card
+markdown = Markdown
+html = HTML
+copy = Copy
+save-as = Save As…
+model-card-incomplete = The model can't be put together yet:
+model-card-save-failed = Could not save the model card: { $error }
+zoo-no-matches = No models match
+zoo-failed = Could not load the collection: { $error }
+downloading = Downloading { $name }
+
+## Upload
+upload-package = Upload Package...
+package-label = Package:
+upload-target-label = Upload To:
+zenodo-sandbox = Zenodo (sandbox)
+upload-endpoint-label = Upload Endpoint:
+api-token-label = API Token:
+upload = Upload
+upload-in-progress = Uploading, see the task list for progress
+uploading = Uploading { $path }
+upload-finished = Upload finished { $location }
+upload-failed = Upload failed: { $error }
+
+## Settings
+ui-scale-label = UI Scale:
+font-size-label = Font Size:
+theme-label = Theme:
+language-label = Language:
+follow-system = Follow System
+dark = Dark
+light = Light
+reset = Reset
+author-profile = Author Profile
+apply-profile = Fill In Current Resource
+default-tags-label = Default Tags (comma separated):
+default-license-label = Default License:
+
+## Accessibility
+required = Required
+a11y-required-field = { $label }, required
+a11y-invalid-field = { $label }, invalid: { $error }
+
+## Attachment widget
+attachment-missing = { $path } doesn't exist anymore
+attachment-not-loaded = { $path } could not be read
+attachment-still-loading = { $path } is still being read
+attachment-duplicate-name = There is more than one attachment named { $name }
+
+## Author widget
+affiliation-label = Affiliation:
+email-label = Email:
+github-user-label = Github User:
+orcid-label = Orcid:
+fetch-orcid = Fetch
+fetch-orcid-hint = Fill in the name and affiliation from the public ORCID record
+paste-snippet = 📋 Paste
+paste-snippet-hint = Fill this in from JSON or YAML copied out of another rdf.yaml or bioimage.io tool
+paste-snippet-placeholder = Paste the JSON or YAML here (Ctrl+V)
+paste-snippet-not-yaml = The pasted text is neither JSON nor YAML: { $error }
+paste-snippet-many = The pasted text has { $count } entries; paste only one
+paste-snippet-mismatch = The pasted text doesn't fit here: { $error }
+check-github-user = Check
+check-github-user-hint = Look the user name up on GitHub, to catch typos
+checking-github-user = Looking the user up on GitHub
+github-user-exists = { $name } (github.com/{ $login })
+github-organization-exists = The organization { $name } (github.com/{ $login })
+github-user-missing = There is no GitHub account named { $login }
+github-user-check-failed = Could not ask GitHub about the user: { $error }
+fetching-orcid = Fetching the ORCID record...
+orcid-fetch-failed = Could not fetch the ORCID record: { $error }
+checking-link = Checking the link...
+link-reachable = The link works
+link-error-status = The server answered with error { $status }; the link may be dead
+link-timed-out = The server took too long to answer
+link-unreachable = The link couldn't be reached: { $reason }
+
+## Axis size widget
+tensor-id-label = Tensor Id:
+axis-id-label = Axis Id:
+offset-label = Offset:
+min-label = Min:
+step-label = Step:
+fixed-size = Fixed Size
+parameterized = Parameterized
+reference = Reference
+extent-label = Extent:
+
+## Backend config widget
+threads-label = Threads:
+
+## Cite widget
+text-label = Text:
+doi-label = Doi:
+url-label = Url:
+look-up-doi = Look up
+look-up-doi-hint = Fill in the text and url from the metadata registered for this DOI
+import-bibtex = Import from BibTeX
+import-bibtex-hint = Add references from entries of a .bib file, pasted or opened
+bibtex-placeholder = Paste one or more BibTeX entries here, starting with @article, @book, ...
+import-bibtex-entries = Add references
+open-bib-file = Open .bib file...
+bibtex-failed = Could not read the BibTeX: { $error }
+bibtex-imported = Added { $count } references
+import-author-table = Import from CSV / TSV
+import-author-table-hint = Add one author per row of a spreadsheet with name, affiliation, email, ORCID and GitHub columns
+author-table-placeholder = Paste rows here, with a header row such as: name, affiliation, email, orcid, github
+import-author-rows = Add authors
+open-author-table = Open .csv or .tsv file...
+author-table-failed = Could not read the table: { $error }
+author-rows-skipped = { $count } rows were skipped:
+authors-imported = Added { $count } authors
+looking-up-doi = Looking up the DOI...
+doi-lookup-failed = Could not look up the DOI: { $error }
+
+## Collection widget
+id-label = Id:
+rdf-source-label = RDF Source:
+summary-label = Summary:
+collection-drop-hint = Drop model packages here to add them to the collection
+entry-number = Entry #{ $number }
+add-entry = + Add Entry
+add-packages = Add Packages...
+model-packages = Model packages
+
+## Conda env widget
+conda-env-summary = { $conda } conda and { $pip } pip dependencies
+conda-env-channels = Channels: { $channels }
+conda-env-no-channels = No channels are listed, so conda uses whichever channels are configured where the model is run
+conda-env-pip = pip:
+
+## Cover image widget
+cover-image-fitted = Cropped and resized to { $width }x{ $height }
+cover-image-details = { $width }x{ $height }, { $ratio }:1, { $size } of at most { $max_size }
+cover-image-primary = ★ Primary cover
+cover-image-make-primary = Make this the primary cover, the one shown first
+cover-image-not-loaded = { $path } has not been loaded as a cover image
+adjust-crop = Adjust crop
+crop-size = Size
+crop-hint = Drag the rectangle over the part of the image to use as the cover
+crop-region = { $width }x{ $height } pixels of the original image
+use-crop = Use this crop
+fitting-cover-image = Fitting the cover image...
+
+## Dataset widget
+source-label = Source:
+number-of-samples-label = Number of samples:
+size-in-bytes-label = Size in bytes:
+
+## Documentation widget
+documentation-type-in = Type in
+documentation-from-file = From a file
+documentation-missing = The model needs documentation
+markdown-line-count = { $lines } lines
+preview = Preview
+nothing-to-preview = Nothing to preview yet
+markdown-image-not-found = [image not found: { $alt } ({ $src })]
+markdown-image-failed = Could not load image { $src }: { $error }
+
+## Enum widget
+pick-one = Pick one
+
+## Example tensor widget
+c-order-shape = C-order shape: [{ $shape }]
+
+## File widget
+none = None
+could-not-load-file = Could not load file
+file-drop-hint = Pick a file, or drop one onto this field
+loading = Loading...
+loading-file = Loading { $path }
+open = Open...
+file-hash = sha256: { $hash }
+file-metadata = { $size }, modified { $modified }
+file-changed-on-disk = The file changed on disk since it was loaded
+reload-file = Reload
+
+## Icon widget
+emoji-icon = Emoji Icon
+image-file-icon = Image File Icon
+icon-not-loaded = No image has been loaded yet
+pick-emoji = Pick an emoji
+search-emoji = Search emoji
+no-emoji-found = No emoji match
+emoji-category-science = Science
+emoji-category-nature = Nature
+emoji-category-animals = Animals
+emoji-category-objects = Objects
+emoji-category-symbols = Symbols
+emoji-category-faces = Faces
+
+## Linked resource widget
+resource-id-label = Id:
+sha256-label = SHA-256:
+version-number-label = Version number:
+
+## Run mode widget
+run-mode-name-label = Name:
+run-mode-kwargs-label = Kwargs (yaml):
+run-mode-bad-kwargs = Kwargs must be a yaml mapping: { $reason }
+
+## Shared widgets
+add = Add
+vec-item-number = { $item } #{ $number }
+vec-add-item = + Add { $item }
+vec-add-item-hint = { $shortcut } adds one after the item being edited
+vec-remove-item = Remove this { $item }
+vec-insert-above = Insert { $item } above
+vec-duplicate-item = Add a copy of this { $item } below it
+vec-move-up = Move up
+vec-move-down = Move down
+
+## Model family widget
+preset-label = Preset:
+probability-threshold-label = Probability threshold:
+nms-threshold-label = NMS threshold:
+cellpose-diameter-label = Diameter (0 to estimate):
+flow-threshold-label = Flow threshold:
+cell-probability-threshold-label = Cell probability threshold:
+other = Other
+
+## Partner configs widget
+partner-configs-label = Partner tools:
+pyramidal-model = Pyramidal model
+allow-tiling = Allow tiling
+min-ilastik-version-label = Oldest ilastik version:
+partner-configs-unparsed = Imported settings that could not be read are kept as they are: { $partners }
+
+## Tags widget
+add-tag-hint = New tag, then Enter
+remove-tag = Remove this tag
+suggested-tags = Used on bioimage.io:
+suggested-tag-hint = Used by { $count } published resources
+loading-published-tags = Loading the tags used on bioimage.io...
+published-tags-failed = Could not load the tags used on bioimage.io: { $error }
+tag-duplicate = '{ $tag }' is already a tag
+too-many-tags = There can be at most { $max } tags
+tag-bad-character = Tags can't contain '{ $character }'
+
+## Tensor axis widget
+allow-auto-size = Allow auto size
+size-label = Size:
+channel-names-label = Channel Names:
+pattern = Pattern
+explicit = Explicit
+prefix-label = Prefix:
+suffix-label = Suffix:
+unit-label = Unit:
+scale-label = Scale:
+channel-name = Channel Name
+
+## Tensor data widget
+tensor-data-kind-label = Kind:
+interval-or-ratio = Interval or ratio
+nominal-or-ordinal = Nominal or ordinal
+data-type-label = Data type:
+max-label = Max:
+tensor-data-values-label = Values (yaml list):
+tensor-data-bad-values = Values must be a non-empty yaml list: { $reason }
+
+## Unknown resource widget
+unsupported-resource-type-notice = '{ $rdf_type }' resources can't be edited yet, showing it read-only.
+general-fields-are-valid = General fields are valid.
+unsupported-resource-notice = This resource type can't be edited yet, showing it read-only.
+general-fields-label = General fields:
+
+## Weights widget
+weights-label = Weights:
+weights-file-label = Weights File:
+tensorflow-version-label = TensorFlow Version:
+pytorch-version-label = PyTorch Version:
+opset-version-label = ONNX Opset Version:
+architecture-label = Architecture:
+weights-parent-label = Converted From:
+weights-comment-label = Comment:
+weights-file-missing = No weights file was picked
+remove-weights = Remove these weights
+add-weights = Add Weights...
+architecture-source-label = Source:
+architecture-from-file = Python File
+architecture-from-library = Installed Library
+architecture-from-url = Url
+architecture-file-label = File:
+architecture-import-from-label = Import From:
+architecture-url-label = Url:
+architecture-sha256-label = SHA-256:
+architecture-callable-label = Callable:
+architecture-kwargs-label = Kwargs:
+architecture-bad-import-from = '{ $part }' in the module path is no python identifier: { $reason }
+architecture-file-missing = No architecture file was picked
+python-mismatched-bracket = The { $opening } opened on line { $opening_line } is closed by a { $closing } on line { $line }
+python-unopened-bracket = The { $bracket } on line { $line } closes nothing
+python-unclosed-bracket = The { $bracket } opened on line { $line } is never closed
+python-callable-missing = The source doesn't seem to define { $callable }
+architecture-code-label = Source code:
+architecture-show-code = Show the source
+
+## Input tensor widget
+inputs-label = Inputs:
+input-tensor = Input Tensor
+optional-input-label = Optional:
+optional-input = The model also runs without this input
+
+## Output tensor widget
+outputs-label = Outputs:
+output-tensor = Output Tensor
+axis = Axis
+axes-label = Axes:
+axis-type-label = Type:
+batch-axis = Batch
+channel-axis = Channel
+index-axis = Index
+time-axis = Time
+space-axis = Space
+halo-label = Halo:
+test-tensor-label = Test Tensor:
+infer-axes = Infer axes
+infer-axes-hint = Fill in the axes from the shape of the test tensor, for you to adjust
+axes-guess-failed = Could not read the shape of the test tensor: { $error }
+test-tensor-preview-label = Preview:
+tensor-preview-info = { $dtype }, shape ({ $shape })
+preview-rows = Rows
+preview-columns = Columns
+preview-in-color = Show in color
+test-tensor-from-image-label = From an Image:
+generate-test-tensor = Generate from an example image
+open-example-image = Open image
+images = Images
+example-image-size = { $width }x{ $height }, { $channels } channel(s)
+image-dimension-y = Rows (y)
+image-dimension-x = Columns (x)
+image-dimension-channel = Channels
+image-dimension-singleton = Size 1
+image-dimensions-needed = Exactly one axis must take the rows and one the columns of the image, and at most one its channels
+drop-alpha-channel = Drop the alpha channel
+normalization-label = Normalization:
+normalization-none = None
+normalization-min-max = Scale to 0..1
+normalization-zero-mean-unit-variance = Zero mean, unit variance
+save-test-tensor = Save as test tensor
+saving-test-tensor = Saving { $path }...
+sample-tensor-label = Sample Tensor:
+postprocessing-label = Postprocessing:
+tensor-no-axes = A tensor needs at least one axis
+test-tensor-missing = No test tensor was picked
+
+## Processing widget
+preprocessing-label = Preprocessing:
+operation-label = Operation:
+processing-axis-label = Axis:
+processing-axes-label = Axes (comma separated):
+threshold-label = Threshold:
+gain-label = Gain:
+eps-label = Epsilon:
+min-percentile-label = Min Percentile:
+max-percentile-label = Max Percentile:
+reference-tensor-label = Reference Tensor:
+reference-tensor-missing = Pick the tensor whose statistics to scale to
+mean-label = Mean:
+std-label = Standard Deviation:
+no-kwargs = This operation has no kwargs
+bad-floats = '{ $value }' is not a number or a comma separated list of numbers
+drag-to-reorder = Drag to reorder
+remove-step = Remove this step
+add-step = + Add Step
+yaml-preview = YAML
+step-error = Step { $number }: { $error }
+
+## Checks across fields
+
+duplicate-tensor-id = Another tensor also has the id "{ $id }"
+duplicate-axis-id = Another axis of this tensor also has the id "{ $id }"
+unknown-reference-tensor = There is no tensor with the id "{ $id }"
+unknown-reference-axis = Tensor "{ $tensor }" has no axis with the id "{ $id }"
+duplicate-author-name = Another author is also called "{ $name }"
+
+## Parsing errors
+
+error-length = Expected between { $min } and { $max } characters, found { $length }
+error-bad-identifier = Bad identifier: { $error }
+error-empty-identifier = Empty string can't be an identifier
+error-identifier-start = Expected first character to be alphabetic or _: '{ $value }'
+error-identifier-character = Identifiers can only contain letters, digits and '_': '{ $value }'
+error-python-keyword = Value '{ $value }' is a python keyword
+error-not-lowercase = Character at { $index } is not lowercase: { $value }
+error-has-slashes = String has slashes: { $value }
+error-orcid-code = Bad ORCID string: { $code }
+error-orcid-char = Bad ORCID char: { $found }
+error-orcid-checksum-char = Bad ORCID checksum char: { $found }
+error-orcid-check-digit = ORCID { $code } fails its check digit: expected { $expected }, found { $found }
+error-doi-syntax = Expected a DOI like '10.1000/xyz123', found '{ $value }'
+
+## Help on fields
+help-max-length = At most { $max } characters
+help-length = { $min } to { $max } characters
+help-identifier = Starts with a letter or _, has nothing but letters, digits and _, and is no python keyword
+help-lowercase = No uppercase letters
+help-no-slashes = No /
+help-unique = No duplicates
+help-orcid = An ORCID iD like 0000-0002-1825-0097, whose last digit checks the others
+help-doi = A DOI like 10.1000/xyz123, without https://doi.org/
+help-version = Three numbers separated by dots, like 1.2.3
+help-url = An http or https url
+help-example = Example: { $example }
+
+## Kwargs
+kwarg-name = Name
+kwarg-value = Value (yaml)
+kwarg-add = ➕ Add argument
+kwarg-remove = Remove this argument
+kwarg-bad-value = The value is no valid yaml: { $reason }
+kwarg-duplicate = The argument { $name } is given more than once
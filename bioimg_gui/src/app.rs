@@ -1,171 +1,2285 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
 use bioimg_spec::rdf;
 use bioimg_spec::rdf::bounded_string::BoundedString;
+use bioimg_spec::rdf::collection::CollectionRdf;
+use bioimg_spec::rdf::dataset::{DatasetRdf, DatasetType};
+use bioimg_spec::rdf::model::tensor_id::TensorId;
+use bioimg_spec::rdf::model::v04::UPGRADED_FORMAT_VERSION;
+use bioimg_spec::rdf::model::{ModelRdf, ModelType};
+use bioimg_spec::rdf::non_empty_list::NonEmptyList;
+use bioimg_spec::rdf::resource_description::{AnyResourceRdf, WithExtras};
+use bioimg_spec::rdf::spec_error;
+use bioimg_spec::rdf::version::VersionBump;
+use bioimg_spec::rdf::version_history::VersionHistory;
+use bioimg_spec::rdf::{Severity, ValidationReport};
+use bioimg_spec::rdf::model::weights::WeightsFormat;
+use bioimg_spec::runtime::inference::{has_runtime, BackendConfig};
+use bioimg_spec::runtime::model_package::{ModelPackage, PackageFile};
+use bioimg_spec::runtime::provenance::RdfProvenance;
+use bioimg_spec::runtime::zoo::ZooEntry;
 
-use crate::result::Result;
+use crate::autosave::Autosave;
+use crate::command_palette::CommandPalette;
+use crate::cross_checks;
+use crate::draft::{open_draft, save_draft, DRAFT_EXTENSION};
+use crate::error_summary::{show_error_summary, SectionErrors};
+use crate::i18n::tr;
+use crate::model_card_preview::ModelCardPreview;
+use crate::notifications::{show_status_bar, Notifications};
+use crate::renames;
+use crate::result::{GuiError, Result};
+use crate::session_log::SessionLogViewer;
+use crate::settings::{AppearanceSettings, AuthorProfile, EditorSettings, FileDialogSettings, NetworkSettings};
+use crate::settings::{RecentFile, RecentFiles, RecentKind};
+use crate::task::{show_task_list, Task};
+use crate::templates::{show_template_menu, ModelTemplate};
+use crate::undo::UndoHistory;
+use crate::upload_dialog::UploadDialog;
+use crate::zoo_browser::ZooBrowser;
+use crate::widgets::accessibility::{field_label, field_label_with_help, required_field_label_with_help};
+use crate::widgets::attachment_widget::{attached_file, attached_files, attachment_package_files, AttachmentWidget};
 use crate::widgets::axis_size_widget::AnyAxisSizeWidget;
+use crate::widgets::backend_config_widget::BackendConfigWidget;
+use crate::widgets::collection_widget::CollectionWidget;
+use crate::widgets::dataset_widget::DatasetFieldsWidget;
+use crate::widgets::documentation_widget::DocumentationWidget;
+use crate::widgets::duplicate_people_widget::{duplicate_groups, merge_group, show_duplicate_people};
 use crate::widgets::enum_widget::EnumWidget;
+use crate::widgets::error_display::{reveal_all_errors, show_error};
+use crate::widgets::validation_report_widget::ValidationReportView;
+use crate::widgets::focus::focus_first_error;
+use crate::widgets::linked_resource_widget::LinkedResourceWidget;
+use crate::widgets::model_family_widget::ModelFamilyWidget;
+use crate::widgets::partner_config_widget::PartnerConfigsWidget;
+use crate::widgets::run_mode_widget::RunModeWidget;
+use crate::widgets::self_test_widget::SelfTestWidget;
+use crate::widgets::tensor_data_widget::TensorDataWidget;
+use crate::widgets::training_data_widget::TrainingDataWidget;
 use crate::widgets::tensor_axis_widget::IndexAxisWidget;
+use crate::widgets::unknown_resource_widget::UnknownResourceViewer;
+use crate::widgets::input_tensor_widget::InputTensorWidget;
+use crate::widgets::output_tensor_widget::OutputTensorWidget;
+use crate::widgets::tags_widget::TagsWidget;
+use crate::widgets::weights_widget::WeightsWidget;
 use crate::widgets::{
-    author_widget::StagingAuthor2, cite_widget::StagingCiteEntry2, code_editor_widget::CodeEditorWidget,
-    cover_image_widget::CoverImageWidget, example_tensor_widget::GuiNpyArray, file_widget::FileWidget, icon_widget::StagingIcon,
-    maintainer_widget::StagingMaintainer, url_widget::StagingUrl, util::group_frame, InputLines,
-    StagingOpt, StagingString, StagingVec, StatefulWidget,
+    author_widget::{AuthorTableImport, StagingAuthor2},
+    badge_widget::StagingBadge,
+    cite_widget::{BibtexImport, StagingCiteEntry2},
+    cover_image_widget::{cover_package_files, show_cover_gallery, CoverImageWidget},
+    example_tensor_widget::GuiNpyArray, field_search,
+    file_widget::{FileWidget, PackageFiles},
+    icon_widget::StagingIcon,
+    maintainer_widget::StagingMaintainer, restore_fields, restore_with_serde, url_widget::StagingUrl, util::group_frame,
+    version_widget::VersionWidget,
+    InputLines, Restore, SetValue, StagingOpt, StagingString, StagingVec, StatefulWidget,
 };
 
+#[derive(PartialEq, Eq, Copy, Clone, Default, Serialize, Deserialize)]
+pub enum EditorMode {
+    #[default]
+    Model,
+    Dataset,
+    Collection,
+}
+
+/// Rows of the form that the command palette can jump to
+#[derive(PartialEq, Eq, Copy, Clone, strum::VariantArray)]
+pub enum FormSection {
+    Name,
+    Description,
+    CoverImages,
+    Attachments,
+    Authors,
+    Cite,
+    GitRepo,
+    Icon,
+    Links,
+    Badges,
+    Maintainers,
+    Tags,
+    Version,
+    Documentation,
+    License,
+    Collection,
+    Dataset,
+    ExampleTensor,
+    ModelFamily,
+    PartnerConfigs,
+    Parent,
+    RunMode,
+    TrainingData,
+    TensorData,
+    Weights,
+    Inputs,
+    Outputs,
+}
+
+impl FormSection {
+    /// Message id of the label in front of the section
+    fn label_id(self) -> &'static str {
+        match self {
+            Self::Name => "name-label",
+            Self::Description => "description-label",
+            Self::CoverImages => "cover-images-label",
+            Self::Attachments => "attachments-label",
+            Self::Authors => "authors-label",
+            Self::Cite => "cite-label",
+            Self::GitRepo => "git-repo-label",
+            Self::Icon => "icon-label",
+            Self::Links => "links-label",
+            Self::Badges => "badges-label",
+            Self::Maintainers => "maintainers-label",
+            Self::Tags => "tags-label",
+            Self::Version => "resource-version-label",
+            Self::Documentation => "documentation-markdown-label",
+            Self::License => "license-label",
+            Self::Collection => "collection-label",
+            Self::Dataset => "dataset-label",
+            Self::ExampleTensor => "example-tensor-label",
+            Self::ModelFamily => "model-family-label",
+            Self::PartnerConfigs => "partner-configs-label",
+            Self::Parent => "parent-label",
+            Self::RunMode => "run-mode-label",
+            Self::TrainingData => "training-data-label",
+            Self::TensorData => "tensor-data-label",
+            Self::Weights => "weights-label",
+            Self::Inputs => "inputs-label",
+            Self::Outputs => "outputs-label",
+        }
+    }
+
+    /// The label of the section without the trailing colon
+    fn name(self) -> String {
+        let label = tr!(self.label_id());
+        label.trim_end_matches(|c: char| c == ':' || c.is_whitespace()).to_owned()
+    }
+
+    fn is_shown_in(self, mode: EditorMode) -> bool {
+        match self {
+            Self::Collection => mode == EditorMode::Collection,
+            Self::Dataset => mode == EditorMode::Dataset,
+            Self::ExampleTensor
+            | Self::ModelFamily
+            | Self::PartnerConfigs
+            | Self::Parent
+            | Self::RunMode
+            | Self::TrainingData
+            | Self::TensorData
+            | Self::Weights
+            | Self::Inputs
+            | Self::Outputs => mode == EditorMode::Model,
+            _ => true,
+        }
+    }
+
+    fn group(self) -> FormGroup {
+        match self {
+            Self::Name
+            | Self::Description
+            | Self::Tags
+            | Self::Version
+            | Self::License
+            | Self::Links
+            | Self::Badges
+            | Self::GitRepo
+            | Self::Collection
+            | Self::Dataset
+            | Self::ModelFamily
+            | Self::PartnerConfigs
+            | Self::Parent
+            | Self::RunMode
+            | Self::TrainingData
+            | Self::TensorData => FormGroup::General,
+            Self::Authors | Self::Cite | Self::Maintainers => FormGroup::People,
+            Self::CoverImages | Self::Attachments | Self::Icon | Self::Documentation | Self::ExampleTensor => FormGroup::Files,
+            Self::Weights => FormGroup::Weights,
+            Self::Inputs => FormGroup::Inputs,
+            Self::Outputs => FormGroup::Outputs,
+        }
+    }
+}
+
+/// The collapsible parts the form is split into, each with a few [FormSection]s
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
+pub enum FormGroup {
+    General,
+    People,
+    Files,
+    Weights,
+    Inputs,
+    Outputs,
+}
+
+impl FormGroup {
+    fn label_id(self) -> &'static str {
+        match self {
+            Self::General => "form-group-general",
+            Self::People => "form-group-people",
+            Self::Files => "form-group-files",
+            Self::Weights => "form-group-weights",
+            Self::Inputs => "form-group-inputs",
+            Self::Outputs => "form-group-outputs",
+        }
+    }
+}
+
+/// Everything that can be triggered from the command palette
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum AppAction {
+    SetMode(EditorMode),
+    Import,
+    OpenModel,
+    SaveDraft,
+    OpenDraft,
+    NewFromTemplate(ModelTemplate),
+    ToggleZoo,
+    ExportCollection,
+    ExportDataset,
+    ExportModel,
+    NewVersion,
+    ValidateAll,
+    ValidateModel,
+    RunTest,
+    AddAuthor,
+    AddCitation,
+    AddMaintainer,
+    AddTag,
+    JumpTo(FormSection),
+    ToggleSettings,
+    ToggleTasks,
+    ToggleLog,
+    ToggleUpload,
+    ToggleErrors,
+    ToggleModelCard,
+    FocusFirstError,
+    Undo,
+    Redo,
+}
+
+/// The outcome of checking the whole model at once
+struct ModelValidation {
+    report: ValidationReport,
+    /// Whether the model could be put together at all. If not, the report only has the errors of single fields,
+    /// and the checks across fields and files didn't run.
+    complete: bool,
+}
+
+/// A model package read for editing, with its files unpacked to where the file widgets can load them from
+struct OpenedModel {
+    /// The package or description file that was opened
+    path: PathBuf,
+    provenance: RdfProvenance,
+    yaml: serde_yaml::Value,
+    rdf: ModelRdf,
+    /// The unpacked file of each path in the package
+    files: BTreeMap<PathBuf, PathBuf>,
+}
+
+/// Where a model package was written to, the hash of the written zip and the version it has
+type ExportedModel = (PathBuf, rdf::Sha256, Option<rdf::Version>);
+
 pub struct TemplateApp {
+    mode: EditorMode,
+
     staging_name: StagingString<BoundedString<1, 127>>,
     staging_description: StagingString<BoundedString<1, 1023>>,
     cover_images: StagingVec<CoverImageWidget>,
     // id?
     staging_authors: StagingVec<StagingAuthor2>,
-    //attachments
+    staging_attachments: StagingVec<AttachmentWidget>,
     staging_citations: StagingVec<StagingCiteEntry2>,
+    author_table_import: AuthorTableImport,
+    bibtex_import: BibtexImport,
     //config
     staging_git_repo: StagingOpt<StagingUrl>,
     staging_icon: StagingIcon,
-    //links
+    staging_links: StagingVec<StagingOpt<StagingString<rdf::ResourceId>>>,
     staging_maintainers: StagingVec<StagingMaintainer>,
-    staging_tags: StagingVec<StagingString<BoundedString<3, 1024>>>,
-    staging_version: StagingString<rdf::Version>,
+    staging_tags: TagsWidget,
+    staging_version: VersionWidget,
+    version_bump: VersionBump,
+    version_history: VersionHistory,
 
-    staging_documentation: StagingOpt<CodeEditorWidget>,
+    staging_documentation: DocumentationWidget,
     staging_license: EnumWidget<rdf::SpdxLicense>,
-    //badges
+    staging_badges: StagingVec<StagingOpt<StagingBadge>>,
     staging_example_tensor: FileWidget<Result<GuiNpyArray>>,
+    staging_model_family: ModelFamilyWidget,
+    staging_partner_configs: PartnerConfigsWidget,
+    staging_parent: StagingOpt<LinkedResourceWidget>,
+    staging_run_mode: StagingOpt<RunModeWidget>,
+    staging_training_data: StagingOpt<TrainingDataWidget>,
+    staging_tensor_data: StagingOpt<TensorDataWidget>,
+    staging_weights: WeightsWidget,
+    staging_inputs: StagingVec<InputTensorWidget>,
+    staging_outputs: StagingVec<OutputTensorWidget>,
 
     ////
     staging_index_axis: IndexAxisWidget,
+
+    staging_dataset_fields: DatasetFieldsWidget,
+    staging_collection: CollectionWidget,
+
+    backend_config: BackendConfigWidget,
+    appearance: AppearanceSettings,
+    profile: AuthorProfile,
+    editor_settings: EditorSettings,
+    file_dialogs: FileDialogSettings,
+    network: NetworkSettings,
+    applied_appearance: Option<(AppearanceSettings, Option<eframe::Theme>)>,
+    show_settings: bool,
+    recent_files: RecentFiles,
+    /// Offers the recent files when the app starts, so they can be opened without going through a file dialog
+    show_welcome: bool,
+    autosave: Autosave,
+    /// Whether the last run crashed with unsaved edits that can be restored
+    offer_recovery: bool,
+    show_tasks: bool,
+    show_log: bool,
+    log_viewer: SessionLogViewer,
+    show_upload: bool,
+    upload_dialog: UploadDialog,
+    show_errors: bool,
+    show_zoo: bool,
+    zoo_browser: ZooBrowser,
+    show_model_card: bool,
+    /// Put together again whenever the resource is reset, e.g. when another one is opened
+    model_card: ModelCardPreview,
+
+    provenance: Option<RdfProvenance>,
+    /// The `config` of the imported resource, which is written back out so other tools don't lose
+    /// their settings. Sections edited here, like the model family, replace the imported ones.
+    imported_config: Option<serde_yaml::Mapping>,
+    /// Top level fields of the imported resource that no part of the spec knows, kept for the same reason
+    imported_extra: serde_yaml::Mapping,
+    pending_import: Option<Task<Result<(RdfProvenance, serde_yaml::Value)>>>,
+    pending_model: Option<Task<Result<OpenedModel>>>,
+    pending_export: Option<Task<Result<ExportedModel>>>,
+    /// Why the model couldn't be exported, shown until dismissed
+    export_errors: Vec<String>,
+    /// The outcome of the last check of the whole model, shown until closed
+    model_validation: Option<ModelValidation>,
+    validation_view: ValidationReportView,
+    self_test: SelfTestWidget,
+    unknown_resource: Option<UnknownResourceViewer>,
+
+    notifications: Notifications,
+    command_palette: CommandPalette,
+    scroll_target: Option<FormSection>,
+    /// The section jumped to from the error summary, and when that happened
+    highlighted: Option<(FormSection, f64)>,
+    undo_history: UndoHistory,
+    /// What the search box above the form looks for in the labels of the fields
+    field_query: String,
+    /// Which of the fields found by `field_query` was jumped to
+    field_match: usize,
 }
 
 impl Default for TemplateApp {
     fn default() -> Self {
         Self {
+            mode: Default::default(),
             staging_name: StagingString::new(InputLines::SingleLine),
             staging_description: StagingString::new(InputLines::Multiline),
-            cover_images: StagingVec::new("Cover Image"),
-            staging_authors: StagingVec::new("Author"),
-            staging_citations: StagingVec::new("Cite"),
+            cover_images: StagingVec::new("cover-image"),
+            staging_authors: StagingVec::new("author"),
+            staging_attachments: StagingVec::new("attachment"),
+            staging_citations: StagingVec::new("cite"),
+            author_table_import: Default::default(),
+            bibtex_import: Default::default(),
             staging_git_repo: Default::default(),
             staging_icon: Default::default(),
-            staging_maintainers: StagingVec::new("Maintainer"),
-            staging_tags: StagingVec::new("Tag"),
+            staging_links: StagingVec::new("link"),
+            staging_badges: StagingVec::new("badge"),
+            staging_maintainers: StagingVec::new("maintainer"),
+            staging_tags: Default::default(),
             staging_version: Default::default(),
+            version_bump: Default::default(),
+            version_history: Default::default(),
             staging_documentation: Default::default(),
-            staging_license: Default::default(),
+            staging_license: EnumWidget::default().with_pinned(&rdf::SpdxLicense::COMMON),
 
             staging_example_tensor: Default::default(),
+            staging_model_family: Default::default(),
+            staging_partner_configs: Default::default(),
+            staging_parent: Default::default(),
+            staging_run_mode: Default::default(),
+            staging_training_data: Default::default(),
+            staging_tensor_data: Default::default(),
+            staging_weights: Default::default(),
+            staging_inputs: StagingVec::new("input-tensor"),
+            staging_outputs: StagingVec::new("output-tensor"),
 
             staging_index_axis: Default::default(),
+
+            staging_dataset_fields: Default::default(),
+            staging_collection: Default::default(),
+
+            backend_config: Default::default(),
+            appearance: Default::default(),
+            profile: Default::default(),
+            editor_settings: Default::default(),
+            file_dialogs: Default::default(),
+            network: Default::default(),
+            applied_appearance: None,
+            show_settings: false,
+            recent_files: Default::default(),
+            show_welcome: false,
+            autosave: Default::default(),
+            offer_recovery: false,
+            show_tasks: false,
+            show_log: false,
+            log_viewer: Default::default(),
+            show_upload: false,
+            upload_dialog: Default::default(),
+            show_errors: false,
+            show_zoo: false,
+            zoo_browser: Default::default(),
+            show_model_card: false,
+            model_card: Default::default(),
+
+            provenance: None,
+            imported_config: None,
+            imported_extra: Default::default(),
+            pending_import: None,
+            pending_model: None,
+            pending_export: None,
+            export_errors: vec![],
+            model_validation: None,
+            validation_view: Default::default(),
+            self_test: Default::default(),
+            unknown_resource: None,
+
+            notifications: Default::default(),
+            command_palette: Default::default(),
+            scroll_target: None,
+            highlighted: None,
+            undo_history: Default::default(),
+            field_query: String::new(),
+            field_match: 0,
         }
     }
 }
 
 impl TemplateApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Default::default()
+    const BACKEND_CONFIG_KEY: &'static str = "backend_config";
+    /// How long a section jumped to from the error summary stays outlined
+    const HIGHLIGHT_SECONDS: f64 = 1.5;
+    const UNDO_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+    const REDO_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT), egui::Key::Z);
+    const FOCUS_ERROR_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::E);
+    const FIELD_SEARCH_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::F);
+
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::default();
+        if let Some(config) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<BackendConfig>(storage, Self::BACKEND_CONFIG_KEY))
+        {
+            app.backend_config = BackendConfigWidget::from(config);
+        }
+        if let Some(appearance) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<AppearanceSettings>(storage, AppearanceSettings::STORAGE_KEY))
+        {
+            app.appearance = appearance;
+        }
+        if let Some(editor_settings) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<EditorSettings>(storage, EditorSettings::STORAGE_KEY))
+        {
+            app.editor_settings = editor_settings;
+        }
+        if let Some(file_dialogs) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<FileDialogSettings>(storage, FileDialogSettings::STORAGE_KEY))
+        {
+            app.file_dialogs = file_dialogs;
+        }
+        if let Some(network) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<NetworkSettings>(storage, NetworkSettings::STORAGE_KEY))
+        {
+            app.network = network;
+        }
+        if let Err(err) = app.network.apply() {
+            app.notifications.error(tr!("network-settings-failed", error = err.to_string()));
+        }
+        if let Some(recent_files) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<RecentFiles>(storage, RecentFiles::STORAGE_KEY))
+        {
+            app.recent_files = recent_files;
+        }
+        (app.autosave, app.offer_recovery) = Autosave::start();
+        app.show_welcome = !app.recent_files.is_empty() && !app.offer_recovery;
+        if let Some(profile) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<AuthorProfile>(storage, AuthorProfile::STORAGE_KEY))
+        {
+            app.apply_profile(&profile);
+            app.profile = profile;
+        }
+        app
+    }
+
+    /// Fills in the author, maintainer, license and tags of the resource being edited
+    fn apply_profile(&mut self, profile: &AuthorProfile) {
+        if !profile.name.trim().is_empty() {
+            self.staging_authors.staging = vec![StagingAuthor2::from(profile)];
+        }
+        if !profile.github_user.trim().is_empty() {
+            self.staging_maintainers.staging = vec![StagingMaintainer::from(profile)];
+        }
+        if let Some(license) = profile.default_license {
+            self.staging_license.set_value(license);
+        }
+        if profile.default_tags().next().is_some() {
+            self.staging_tags.set_tags(profile.default_tags());
+        }
+    }
+
+    /// The general fields shared by all resource types
+    fn base_rdf(&self) -> Result<rdf::Rdf> {
+        let authors = self.staging_authors.state().into_iter().collect::<Result<Vec<_>>>()?;
+        let cite = self.staging_citations.state().into_iter().collect::<Result<Vec<_>>>()?;
+        let covers = cover_package_files(self.cover_images.state())?;
+        let icon = self.staging_icon.packaged()?.map(|(icon, _)| BoundedString::try_from(icon)).transpose()?;
+        Ok(rdf::Rdf {
+            format_version: rdf::Version {
+                major: 0,
+                minor: 2,
+                patch: 3,
+            },
+            description: self.staging_description.state()?,
+            name: BoundedString::try_from(self.staging_name.state()?.to_string())?,
+            attachments: match attached_files(self.staging_attachments.state())? {
+                files if files.is_empty() => None,
+                files => Some(rdf::attachment::Attachments { files }),
+            },
+            authors: match authors {
+                authors if authors.is_empty() => None,
+                authors => Some(authors),
+            },
+            badges: match self.staging_badges.state().into_iter().flatten().collect::<Result<Vec<_>>>()? {
+                badges if badges.is_empty() => None,
+                badges => Some(badges),
+            },
+            cite: match cite {
+                cite if cite.is_empty() => None,
+                cite => Some(cite),
+            },
+            config: self.config()?,
+            // the images themselves are written next to the rdf when exporting, under these names
+            covers: match covers {
+                covers if covers.is_empty() => None,
+                covers => Some(covers.into_iter().map(|(path, _)| rdf::FileReference::Path(path)).collect()),
+            },
+            // the markdown itself is written next to the rdf when exporting
+            documentation: self
+                .staging_documentation
+                .state()?
+                .map(|_| rdf::FileReference::Path(self.staging_documentation.file_name().into())),
+            download_url: None,
+            git_repo: self.staging_git_repo.state().transpose()?,
+            icon,
+            id: None,
+            license: Some(self.staging_license.state()),
+            links: match self.staging_links.state().into_iter().flatten().collect::<Result<Vec<_>>>()? {
+                links if links.is_empty() => None,
+                links => Some(links),
+            },
+            maintainers: Some(self.staging_maintainers.state().into_iter().collect::<Result<_>>()?),
+            rdf_source: self.provenance.as_ref().and_then(RdfProvenance::published_source).cloned(),
+            source: None,
+            tags: Some(self.staging_tags.state()?),
+            version: Some(self.staging_version.state()?),
+            history: self.version_history.clone(),
+        })
+    }
+
+    fn config(&self) -> Result<Option<serde_yaml::Mapping>> {
+        if self.mode != EditorMode::Model {
+            return Ok(self.imported_config.clone());
+        }
+        let mut config = self.imported_config.clone().unwrap_or_default();
+        config.extend(self.staging_model_family.state()?.unwrap_or_default());
+        self.staging_partner_configs.apply_to(&mut config)?;
+        Ok((self.imported_config.is_some() || !config.is_empty()).then_some(config))
+    }
+
+    /// Records the current version in the history and bumps the version field
+    fn start_new_version(&mut self) {
+        let current = self.staging_version.state().ok();
+        let next = self
+            .version_history
+            .new_version(current.as_ref(), None, None, self.version_bump, chrono::Utc::now());
+        self.staging_version.set_raw(next);
+    }
+
+    /// Reads and hashes an existing resource in the background; see [TemplateApp::apply_import]
+    fn start_import(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.file_dialogs.open_dialog().add_filter(tr!("packages"), &["zip", "yaml"]).pick_file() else {
+            return;
+        };
+        self.file_dialogs.remember_opened(&path);
+        let description = tr!("importing", path = path.to_string_lossy().into_owned());
+        self.pending_import = Some(Task::spawn(description, ctx.clone(), move |status| {
+            let provenance = RdfProvenance::import(&path)?;
+            status.set_progress(0.5);
+            let yaml = provenance.original_yaml()?;
+            Ok((provenance, yaml))
+        }));
+    }
+
+    /// Downloads a published resource in the background and imports it like a local one, keeping
+    /// its url as the `rdf_source`
+    fn start_zoo_import(&mut self, ctx: &egui::Context, entry: ZooEntry) {
+        let client = match self.zoo_browser.client() {
+            Ok(client) => client,
+            Err(err) => return self.notifications.error(tr!("import-failed", error = err.to_string())),
+        };
+        let description = tr!("downloading", name = entry.name.clone());
+        self.pending_import = Some(Task::spawn(description, ctx.clone(), move |status| {
+            let path = client.download(&entry)?;
+            status.set_progress(0.5);
+            let mut provenance = RdfProvenance::import(&path)?;
+            provenance.rdf_source = rdf::FileReference::Url(entry.rdf_source);
+            let yaml = provenance.original_yaml()?;
+            Ok((provenance, yaml))
+        }));
+    }
+
+    /// Reads a model package in the background and unpacks its files; see [TemplateApp::apply_model]
+    fn start_open_model(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.file_dialogs.open_dialog().add_filter(tr!("packages"), &["zip", "yaml"]).pick_file() else {
+            return;
+        };
+        self.file_dialogs.remember_opened(&path);
+        self.open_model_at(ctx, path);
+    }
+
+    fn open_model_at(&mut self, ctx: &egui::Context, path: PathBuf) {
+        let description = tr!("opening-model", path = path.to_string_lossy().into_owned());
+        self.pending_model = Some(Task::spawn(description, ctx.clone(), move |status| {
+            let provenance = RdfProvenance::import(&path)?;
+            let yaml = provenance.original_yaml()?;
+            let package = match path.extension().is_some_and(|ext| ext == "yaml") {
+                true => ModelPackage::from_description_file(&path)?,
+                false => ModelPackage::open(&path)?,
+            };
+            status.set_progress(0.5);
+            // packages with the same contents share a directory, so opening one again doesn't pile up copies
+            let dir = std::env::temp_dir().join("bioimg_gui").join(provenance.sha256.to_string());
+            let files = package.unpack_to(&dir)?;
+            Ok(OpenedModel {
+                path,
+                provenance,
+                yaml,
+                rdf: package.rdf,
+                files,
+            })
+        }));
+    }
+
+    /// Replaces the resource being edited with an opened model, filling in every field the model has
+    fn apply_model(&mut self, ctx: &egui::Context, opened: OpenedModel) {
+        self.reset_resource();
+        self.apply_import(opened.provenance, opened.yaml);
+        let rdf = opened.rdf;
+        let files = PackageFiles {
+            ctx: ctx.clone(),
+            paths: opened.files,
+        };
+        self.staging_authors.set_value(rdf.authors.iter());
+        self.staging_citations.set_value(rdf.cite.iter());
+        self.staging_maintainers.set_value(&rdf.maintainers);
+        self.cover_images.staging = rdf.covers.iter().map(|cover| files.widget(cover)).collect();
+        self.staging_attachments.staging = rdf.attachments.iter().map(|file| files.widget(&file.source)).collect();
+        self.staging_git_repo.set_value(rdf.git_repo.as_ref());
+        if let Some(icon) = &rdf.icon {
+            self.staging_icon = StagingIcon::from_rdf(icon.as_str(), &files);
+        }
+        self.staging_links.staging = rdf.links.iter().map(|link| StagingOpt::from_raw(&link.to_string())).collect();
+        self.staging_badges.set_value(rdf.badges.iter().map(Some));
+        self.staging_tags.set_value(rdf.tags.iter().map(|tag| tag.as_str()));
+        self.version_history = rdf.history.clone();
+        self.staging_documentation = DocumentationWidget::from_reference(&rdf.documentation, &files);
+        self.staging_license.set_value(rdf.license);
+        if let Some(config) = &rdf.config {
+            self.staging_model_family = ModelFamilyWidget::from_config(config);
+            self.staging_partner_configs = PartnerConfigsWidget::from_config(config);
+        }
+        self.staging_parent.set_value(rdf.parent.as_ref());
+        self.staging_run_mode.set_value(rdf.run_mode.as_ref());
+        self.staging_training_data.set_value(rdf.training_data.as_ref());
+        self.staging_weights = WeightsWidget::from_descr(&rdf.weights, &files);
+        self.staging_inputs.staging = rdf.inputs.iter().map(|input| InputTensorWidget::from_descr(input, &files)).collect();
+        self.staging_outputs.staging = rdf.outputs.iter().map(|output| OutputTensorWidget::from_descr(output, &files)).collect();
+    }
+
+    /// Adds authors read from a spreadsheet after the authors there are, taking the place of empty ones
+    fn import_authors(&mut self, authors: Vec<rdf::author::Author2>) {
+        let count = authors.len();
+        self.staging_authors.staging.retain(|author| !author.is_empty());
+        self.staging_authors.staging.extend(authors.iter().map(StagingAuthor2::from));
+        self.notifications.success(tr!("authors-imported", count = count));
+    }
+
+    /// Adds references read from BibTeX after the citations there are, taking the place of empty ones
+    fn import_citations(&mut self, entries: Vec<rdf::cite_entry::CiteEntry2>) {
+        let count = entries.len();
+        self.staging_citations.staging.retain(|citation| !citation.is_empty());
+        self.staging_citations.staging.extend(entries.iter().map(StagingCiteEntry2::from));
+        self.notifications.success(tr!("bibtex-imported", count = count));
+    }
+
+    /// Replaces the resource being edited with a new model that has the tensors, tags and documentation of `template`
+    fn apply_template(&mut self, ctx: &egui::Context, template: ModelTemplate) {
+        self.reset_resource();
+        self.mode = EditorMode::Model;
+        let profile = self.profile.clone();
+        self.apply_profile(&profile);
+        let contents = template.contents();
+        let templated_tags = contents.tags.iter().map(String::as_str);
+        self.staging_tags.set_tags(profile.default_tags().chain(templated_tags));
+        // templates have no files, so the test tensors are left for the user to pick
+        let files = PackageFiles {
+            ctx: ctx.clone(),
+            paths: BTreeMap::new(),
+        };
+        let inputs = contents.inputs.iter().map(|input| InputTensorWidget::from_descr(input, &files));
+        self.staging_inputs.staging = inputs.collect();
+        let outputs = contents.outputs.iter().map(|output| OutputTensorWidget::from_descr(output, &files));
+        self.staging_outputs.staging = outputs.collect();
+        self.staging_documentation = DocumentationWidget::from_markdown(contents.documentation);
+        self.notifications.info(tr!("template-applied", template = tr!(template.label_id())));
+    }
+
+    /// Loads the general fields of an imported resource and remembers where it came from
+    fn apply_import(&mut self, provenance: RdfProvenance, yaml: serde_yaml::Value) {
+        let get_str = |field: &str| yaml.get(field).and_then(|v| v.as_str()).unwrap_or_default().to_owned();
+        self.mode = match get_str("type").as_str() {
+            "model" => EditorMode::Model,
+            "dataset" => EditorMode::Dataset,
+            "collection" => EditorMode::Collection,
+            _ => {
+                self.unknown_resource = Some(UnknownResourceViewer::new(yaml));
+                return;
+            }
+        };
+        self.staging_name.set_raw(get_str("name"));
+        self.staging_description.set_raw(get_str("description"));
+        self.staging_version.set_raw(get_str("version"));
+        self.imported_config = yaml.get("config").and_then(|config| config.as_mapping()).cloned();
+        // a resource that doesn't validate can't tell its unknown fields apart from its broken ones
+        self.imported_extra = match spec_error::from_value::<WithExtras<AnyResourceRdf>>(yaml) {
+            Ok(imported) => {
+                match &imported.rdf {
+                    AnyResourceRdf::Model(model) => {
+                        for entry in model.validate().entries {
+                            log::warn!("{entry}");
+                        }
+                    }
+                    AnyResourceRdf::Dataset(dataset) => self.staging_dataset_fields.set_value(dataset),
+                    _ => (),
+                }
+                imported.extra
+            }
+            Err(err) => {
+                self.notifications.error(tr!("imported-invalid", error = err.to_string()));
+                Default::default()
+            }
+        };
+        self.notifications.info(tr!("hash-computed", sha256 = provenance.sha256.to_string()));
+        self.provenance = Some(provenance);
+    }
+
+    /// The actions offered by the command palette in the current mode, with their labels
+    fn palette_commands(&self) -> Vec<(String, AppAction)> {
+        let mut commands = vec![
+            (tr!("palette-switch-mode", mode = tr!("model")), AppAction::SetMode(EditorMode::Model)),
+            (tr!("palette-switch-mode", mode = tr!("dataset")), AppAction::SetMode(EditorMode::Dataset)),
+            (tr!("palette-switch-mode", mode = tr!("collection")), AppAction::SetMode(EditorMode::Collection)),
+            (tr!("undo"), AppAction::Undo),
+            (tr!("redo"), AppAction::Redo),
+            (tr!("import"), AppAction::Import),
+            (tr!("open-model"), AppAction::OpenModel),
+            (tr!("save-draft"), AppAction::SaveDraft),
+            (tr!("open-draft"), AppAction::OpenDraft),
+            (tr!("open-from-zoo"), AppAction::ToggleZoo),
+            (tr!("new-version"), AppAction::NewVersion),
+            (tr!("validate-all"), AppAction::ValidateAll),
+            (tr!("vec-add-item", item = tr!("author")), AppAction::AddAuthor),
+            (tr!("vec-add-item", item = tr!("cite")), AppAction::AddCitation),
+            (tr!("vec-add-item", item = tr!("maintainer")), AppAction::AddMaintainer),
+            (tr!("vec-add-item", item = tr!("tag")), AppAction::AddTag),
+            (tr!("settings"), AppAction::ToggleSettings),
+            (tr!("tasks"), AppAction::ToggleTasks),
+            (tr!("session-log"), AppAction::ToggleLog),
+            (tr!("upload-package"), AppAction::ToggleUpload),
+            (tr!("error-summary"), AppAction::ToggleErrors),
+            (tr!("focus-first-error"), AppAction::FocusFirstError),
+        ];
+        if self.mode == EditorMode::Collection {
+            commands.push((tr!("export-collection"), AppAction::ExportCollection));
+        }
+        if self.mode == EditorMode::Dataset {
+            commands.push((tr!("export-dataset"), AppAction::ExportDataset));
+        }
+        if self.mode == EditorMode::Model {
+            commands.push((tr!("export-model"), AppAction::ExportModel));
+            commands.push((tr!("validate-model"), AppAction::ValidateModel));
+            commands.push((tr!("model-card"), AppAction::ToggleModelCard));
+            if self.can_run_test() {
+                commands.push((tr!("run-test"), AppAction::RunTest));
+            }
+        }
+        for template in <ModelTemplate as strum::VariantArray>::VARIANTS {
+            let label = tr!("palette-new-from-template", template = tr!(template.label_id()));
+            commands.push((label, AppAction::NewFromTemplate(*template)));
+        }
+        for section in <FormSection as strum::VariantArray>::VARIANTS {
+            if section.is_shown_in(self.mode) {
+                commands.push((tr!("palette-jump-to", section = section.name()), AppAction::JumpTo(*section)));
+            }
+        }
+        commands
+    }
+
+    fn run_action(&mut self, ctx: &egui::Context, action: AppAction) {
+        match action {
+            AppAction::SetMode(mode) => self.mode = mode,
+            AppAction::Import => {
+                if self.pending_import.is_none() {
+                    self.start_import(ctx);
+                }
+            }
+            AppAction::OpenModel => {
+                if self.pending_model.is_none() {
+                    self.start_open_model(ctx);
+                }
+            }
+            AppAction::SaveDraft => self.run_save_draft(),
+            AppAction::OpenDraft => self.run_open_draft(ctx),
+            AppAction::NewFromTemplate(template) => self.apply_template(ctx, template),
+            AppAction::ToggleZoo => self.show_zoo = !self.show_zoo,
+            AppAction::ExportCollection => self.run_collection_export(),
+            AppAction::ExportDataset => self.run_dataset_export(),
+            AppAction::ExportModel => {
+                if self.pending_export.is_none() {
+                    self.start_model_export(ctx)
+                }
+            }
+            AppAction::NewVersion => self.start_new_version(),
+            AppAction::ValidateAll => self.validate_all(ctx),
+            AppAction::ValidateModel => self.validate_model(ctx),
+            AppAction::RunTest => {
+                if !self.self_test.is_running() {
+                    self.start_self_test(ctx);
+                }
+            }
+            AppAction::AddAuthor => self.staging_authors.staging.push(Default::default()),
+            AppAction::AddCitation => self.staging_citations.staging.push(Default::default()),
+            AppAction::AddMaintainer => self.staging_maintainers.staging.push(Default::default()),
+            AppAction::AddTag => {
+                self.staging_tags.focus_input();
+                self.scroll_target = Some(FormSection::Tags);
+            }
+            AppAction::JumpTo(section) => self.scroll_target = Some(section),
+            AppAction::ToggleSettings => self.show_settings = !self.show_settings,
+            AppAction::ToggleTasks => self.show_tasks = !self.show_tasks,
+            AppAction::ToggleLog => self.show_log = !self.show_log,
+            AppAction::ToggleUpload => self.show_upload = !self.show_upload,
+            AppAction::ToggleErrors => self.show_errors = !self.show_errors,
+            AppAction::ToggleModelCard => self.show_model_card = !self.show_model_card,
+            AppAction::FocusFirstError => {
+                reveal_all_errors(ctx);
+                focus_first_error(ctx);
+            }
+            AppAction::Undo => self.undo(ctx),
+            AppAction::Redo => self.redo(ctx),
+        }
+    }
+
+    fn undo(&mut self, ctx: &egui::Context) {
+        let current = self.dump();
+        if let Some(snapshot) = self.undo_history.undo(current) {
+            self.apply_snapshot(ctx, &snapshot);
+        }
+    }
+
+    fn redo(&mut self, ctx: &egui::Context) {
+        if let Some(snapshot) = self.undo_history.redo() {
+            self.apply_snapshot(ctx, &snapshot);
+        }
+    }
+
+    /// Puts the resource back the way it was when `snapshot` was taken. Where it was imported from
+    /// and what is still running in the background are kept.
+    fn apply_snapshot(&mut self, ctx: &egui::Context, snapshot: &serde_yaml::Value) {
+        let provenance = self.provenance.take();
+        let pending_import = self.pending_import.take();
+        let pending_model = self.pending_model.take();
+        let pending_export = self.pending_export.take();
+        let undo_history = std::mem::take(&mut self.undo_history);
+        self.reset_resource();
+        self.restore(ctx, snapshot);
+        self.provenance = provenance;
+        self.pending_import = pending_import;
+        self.pending_model = pending_model;
+        self.pending_export = pending_export;
+        self.undo_history = undo_history;
+        self.undo_history.rebase(self.dump());
+    }
+
+    /// Brings `section` into view if the command palette asked to jump to it, and outlines it for
+    /// a moment if it was jumped to from the error summary
+    fn scroll_to_section(&mut self, section: FormSection, response: &egui::Response) {
+        if self.scroll_target == Some(section) {
+            response.scroll_to_me(Some(egui::Align::TOP));
+            self.scroll_target = None;
+        }
+        let Some((highlighted, since)) = self.highlighted else {
+            return;
+        };
+        if highlighted != section {
+            return;
+        }
+        if response.ctx.input(|i| i.time) - since > Self::HIGHLIGHT_SECONDS {
+            self.highlighted = None;
+            return;
+        }
+        let stroke = egui::Stroke::new(2.0, response.ctx.style().visuals.error_fg_color);
+        response.ctx.layer_painter(response.layer_id).rect_stroke(response.rect.expand(4.0), 4.0, stroke);
+        response.ctx.request_repaint();
+    }
+
+    /// The search box that highlights the fields whose label matches what is typed into it, and jumps to them
+    fn show_field_search(&mut self, ui: &mut egui::Ui) {
+        field_search::begin_frame(ui.ctx());
+        let matches = field_search::match_count(ui.ctx());
+        let mut scroll = false;
+        ui.horizontal(|ui| {
+            let search_id = egui::Id::new("Field Search");
+            if ui.input_mut(|i| i.consume_shortcut(&Self::FIELD_SEARCH_SHORTCUT)) {
+                ui.memory_mut(|memory| memory.request_focus(search_id));
+            }
+            let hint = tr!("field-search-hint", shortcut = ui.ctx().format_shortcut(&Self::FIELD_SEARCH_SHORTCUT));
+            let search = ui.add(egui::TextEdit::singleline(&mut self.field_query).id(search_id).hint_text(hint));
+            if search.changed() {
+                self.field_match = 0;
+                scroll = true;
+            }
+            if self.field_query.trim().is_empty() {
+                return;
+            }
+            ui.weak(tr!("field-search-matches", count = matches));
+            let entered = search.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let next = ui.add_enabled(matches > 0, egui::Button::new("⬇")).on_hover_text(tr!("field-search-next"));
+            if (next.clicked() || entered) && matches > 0 {
+                self.field_match = (self.field_match + 1) % matches;
+                scroll = true;
+            }
+            if entered {
+                // so that pressing enter again goes on to the next match
+                search.request_focus();
+            }
+            if ui.button("🗙").on_hover_text(tr!("field-search-clear")).clicked() {
+                self.field_query.clear();
+            }
+        });
+        // fewer fields may match than when the current one was picked
+        self.field_match = self.field_match.min(matches.saturating_sub(1));
+        field_search::set_query(ui.ctx(), &self.field_query, self.field_match, scroll);
+    }
+
+    /// Draws a group of sections under a header that collapses them, and that counts the errors in them.
+    /// Collapsed groups open when one of their sections is jumped to.
+    fn form_group(
+        &mut self,
+        ui: &mut egui::Ui,
+        group: FormGroup,
+        section_errors: &[SectionErrors<FormSection>],
+        add_contents: impl FnOnce(&mut Self, &mut egui::Ui),
+    ) {
+        let id = ui.make_persistent_id(("Form Group", group));
+        let mut state = egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, true);
+        // searching looks through every group, so they are all open while there is a query
+        if self.scroll_target.is_some_and(|section| section.group() == group) || !self.field_query.trim().is_empty() {
+            state.set_open(true);
+        }
+        let error_count: usize = section_errors
+            .iter()
+            .filter(|section| section.section.group() == group)
+            .map(|section| section.errors.len())
+            .sum();
+        state
+            .show_header(ui, |ui| {
+                ui.strong(tr!(group.label_id()));
+                if error_count > 0 {
+                    let badge = egui::RichText::new(format!("⚠ {error_count}")).color(ui.visuals().error_fg_color);
+                    ui.label(badge).on_hover_text(tr!("form-group-errors", count = error_count));
+                }
+            })
+            .body(|ui| add_contents(self, ui));
+    }
+
+    /// The errors of every section of the form shown in the current mode, leaving out the sections without any
+    fn section_errors(&self) -> Vec<SectionErrors<FormSection>> {
+        fn errors<T>(result: Result<T>) -> Vec<GuiError> {
+            result.err().into_iter().collect()
+        }
+        fn item_errors<T>(item_id: &str, results: Vec<Result<T>>) -> Vec<GuiError> {
+            let errors = results.into_iter().enumerate().filter_map(|(idx, result)| {
+                let err = result.err()?;
+                Some(err.within(tr!("vec-item-number", item = tr!(item_id), number = idx + 1)))
+            });
+            errors.collect()
+        }
+        fn loaded_errors<T>(value: Option<&Result<T>>) -> Vec<GuiError> {
+            match value {
+                Some(Err(err)) => vec![err.clone()],
+                _ => vec![],
+            }
+        }
+
+        let documentation = self.staging_documentation.state().and_then(|markdown| match (self.mode, markdown) {
+            (EditorMode::Model, None) => Err(GuiError::new(tr!("documentation-missing"))),
+            _ => Ok(()),
+        });
+        let sections = <FormSection as strum::VariantArray>::VARIANTS.iter().filter(|section| section.is_shown_in(self.mode));
+        sections
+            .map(|section| {
+                let errors = match section {
+                    FormSection::Name => errors(self.staging_name.state()),
+                    FormSection::Description => errors(self.staging_description.state()),
+                    FormSection::CoverImages => {
+                        let covers = self.cover_images.state().into_iter().map(|cover| cover_package_files(vec![cover]));
+                        item_errors("cover-image", covers.collect())
+                    }
+                    FormSection::Attachments => {
+                        let attachments = self.staging_attachments.state().into_iter().map(attached_file);
+                        item_errors("attachment", attachments.collect())
+                    }
+                    FormSection::Authors => item_errors("author", self.staging_authors.state()),
+                    FormSection::Cite => item_errors("cite", self.staging_citations.state()),
+                    FormSection::GitRepo => errors(self.staging_git_repo.state().transpose()),
+                    FormSection::Icon => errors(self.staging_icon.packaged()),
+                    FormSection::Links => {
+                        let links = self.staging_links.state().into_iter().map(Option::transpose);
+                        item_errors("link", links.collect())
+                    }
+                    FormSection::Badges => {
+                        let badges = self.staging_badges.state().into_iter().map(Option::transpose);
+                        item_errors("badge", badges.collect())
+                    }
+                    FormSection::Maintainers => item_errors("maintainer", self.staging_maintainers.state()),
+                    FormSection::Tags => errors(self.staging_tags.state()),
+                    FormSection::Version => errors(self.staging_version.state()),
+                    FormSection::Documentation => errors(documentation.clone()),
+                    FormSection::License => vec![],
+                    FormSection::Collection => errors(self.staging_collection.state()),
+                    FormSection::Dataset => errors(self.staging_dataset_fields.state()),
+                    FormSection::ExampleTensor => loaded_errors(self.staging_example_tensor.loaded_value()),
+                    FormSection::ModelFamily => errors(self.staging_model_family.state()),
+                    FormSection::PartnerConfigs => errors(self.staging_partner_configs.state()),
+                    FormSection::Parent => errors(self.staging_parent.state().transpose()),
+                    FormSection::RunMode => errors(self.staging_run_mode.state().transpose()),
+                    FormSection::TrainingData => errors(self.staging_training_data.state().transpose()),
+                    FormSection::TensorData => errors(self.staging_tensor_data.state().transpose()),
+                    FormSection::Weights => errors(self.staging_weights.state()),
+                    FormSection::Inputs => item_errors("input-tensor", self.staging_inputs.state()),
+                    FormSection::Outputs => item_errors("output-tensor", self.staging_outputs.state()),
+                };
+                SectionErrors {
+                    section: *section,
+                    label: section.name(),
+                    errors,
+                }
+            })
+            .filter(|section| !section.errors.is_empty())
+            .collect()
+    }
+
+    /// The ids of the input and output tensors declared so far, for processing steps to refer to
+    fn tensor_ids(&self) -> Vec<TensorId> {
+        let inputs = self.staging_inputs.staging.iter().map(|input| input.staging_id.parsed());
+        let outputs = self.staging_outputs.staging.iter().map(|output| output.staging_id.parsed());
+        inputs.chain(outputs).filter_map(Result::ok).cloned().collect()
+    }
+
+    /// Everything that would stop the current resource from being exported
+    fn validate(&self) -> Result<()> {
+        self.base_rdf()?;
+        self.staging_authors.state().into_iter().collect::<Result<Vec<_>>>()?;
+        self.staging_citations.state().into_iter().collect::<Result<Vec<_>>>()?;
+        match self.mode {
+            EditorMode::Model => {
+                self.staging_parent.state().transpose()?;
+                self.staging_run_mode.state().transpose()?;
+                self.staging_training_data.state().transpose()?;
+                self.staging_tensor_data.state().transpose()?;
+                self.staging_weights.state()?;
+                self.staging_inputs.state().into_iter().collect::<Result<Vec<_>>>()?;
+                self.staging_outputs.state().into_iter().collect::<Result<Vec<_>>>()?;
+                match self.staging_example_tensor.loaded_value() {
+                    Some(Err(err)) => Err(err.clone()),
+                    _ => Ok(()),
+                }
+            }
+            EditorMode::Dataset => self.staging_dataset_fields.state().map(|_| ()),
+            EditorMode::Collection => self.staging_collection.state().map(|_| ()),
+        }
+    }
+
+    /// Shows the errors of every field, including untouched ones, and sums them up in a toast
+    fn validate_all(&mut self, ctx: &egui::Context) {
+        reveal_all_errors(ctx);
+        match self.validate() {
+            Ok(()) => self.notifications.success(tr!("validation-passed")),
+            Err(err) => {
+                log::debug!("validation failed with {:?}: {:?}", err.code(), err.source());
+                self.notifications.error(tr!("validation-failed", error = err.with_field_path()))
+            }
+        }
+    }
+
+    /// Puts the model together and runs every check of the spec on it, files included
+    fn validate_model(&mut self, ctx: &egui::Context) {
+        reveal_all_errors(ctx);
+        self.model_validation = Some(match self.assemble_model_package() {
+            Ok(package) => ModelValidation {
+                report: package.validate(),
+                complete: true,
+            },
+            Err(errors) => {
+                let mut report = ValidationReport::default();
+                for err in errors {
+                    report.push(Severity::Error, vec![], err);
+                }
+                ModelValidation { report, complete: false }
+            }
+        });
+    }
+
+    /// Whether there is anything to run the model on: loaded weights, and a test tensor for every input
+    fn can_run_test(&self) -> bool {
+        let loaded = |file: &AttachmentWidget| file.loaded_value().is_some_and(|value| value.is_ok());
+        let weights = self.staging_weights.attachments();
+        let inputs = &self.staging_inputs.staging;
+        !weights.is_empty()
+            && weights.into_iter().all(loaded)
+            && !inputs.is_empty()
+            && inputs.iter().all(|input| loaded(&input.test_tensor))
+    }
+
+    /// Why the button to run the test is disabled, if it is: because none of the weights can be run in this build,
+    /// or because there is nothing to run the model on yet
+    fn run_test_blocker(&self) -> Option<String> {
+        if !self.staging_weights.formats().into_iter().any(has_runtime) {
+            let runnable = <WeightsFormat as strum::VariantArray>::VARIANTS.iter().filter(|format| has_runtime(**format));
+            let runnable: Vec<String> = runnable.map(ToString::to_string).collect();
+            return Some(match runnable.is_empty() {
+                true => tr!("run-test-no-runtime"),
+                false => tr!("run-test-unsupported-weights", formats = runnable.join(", ")),
+            });
+        }
+        (!self.can_run_test()).then(|| tr!("run-test-missing-files"))
+    }
+
+    /// Runs the model on its test inputs in the background, to compare what it produces to the test outputs
+    fn start_self_test(&mut self, ctx: &egui::Context) {
+        reveal_all_errors(ctx);
+        let config = match self.backend_config.state() {
+            Ok(config) => config,
+            Err(err) => return self.self_test.fail(vec![err.to_string()]),
+        };
+        match self.assemble_model_package() {
+            Ok(package) => self.self_test.start(ctx, package, config),
+            Err(errors) => self.self_test.fail(errors),
+        }
+    }
+
+    fn run_save_draft(&mut self) {
+        let file_name = match self.staging_name.state() {
+            Ok(name) => format!("{name}.{DRAFT_EXTENSION}"),
+            Err(_) => format!("draft.{DRAFT_EXTENSION}"),
+        };
+        let Some(path) = self
+            .file_dialogs
+            .save_dialog()
+            .add_filter(tr!("drafts"), &[DRAFT_EXTENSION])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+        self.file_dialogs.remember_saved(&path);
+        match save_draft(&path, self.dump()) {
+            Ok(()) => {
+                self.recent_files.add(RecentKind::Draft, &path);
+                self.notifications.success(tr!("saved-to", path = path.to_string_lossy().into_owned()));
+            }
+            Err(err) => self.notifications.error(tr!("draft-save-failed", error = err.to_string())),
+        }
+    }
+
+    /// Replaces the resource being edited with a saved draft. Settings and open windows stay as they are.
+    fn run_open_draft(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.file_dialogs.open_dialog().add_filter(tr!("drafts"), &[DRAFT_EXTENSION]).pick_file() else {
+            return;
+        };
+        self.file_dialogs.remember_opened(&path);
+        self.open_draft_at(ctx, &path);
+    }
+
+    fn open_draft_at(&mut self, ctx: &egui::Context, path: &Path) {
+        let contents = match open_draft(path) {
+            Ok(contents) => contents,
+            Err(err) => return self.notifications.error(tr!("draft-open-failed", error = err.to_string())),
+        };
+        self.reset_resource();
+        self.restore(ctx, &contents);
+        self.recent_files.add(RecentKind::Draft, path);
+        self.notifications.info(tr!("draft-opened", path = path.to_string_lossy().into_owned()));
+    }
+
+    /// Opens a file picked from the recent ones the way it was opened before
+    fn open_recent(&mut self, ctx: &egui::Context, file: RecentFile) {
+        self.show_welcome = false;
+        match file.kind {
+            RecentKind::Draft => self.open_draft_at(ctx, &file.path),
+            RecentKind::Model if self.pending_model.is_none() => self.open_model_at(ctx, file.path),
+            RecentKind::Model => (),
+        }
+    }
+
+    /// Forgets the resource being edited. Settings and open windows stay as they are.
+    fn reset_resource(&mut self) {
+        let previous = std::mem::take(self);
+        *self = Self {
+            backend_config: previous.backend_config,
+            appearance: previous.appearance,
+            profile: previous.profile,
+            editor_settings: previous.editor_settings,
+            file_dialogs: previous.file_dialogs,
+            network: previous.network,
+            applied_appearance: previous.applied_appearance,
+            show_settings: previous.show_settings,
+            recent_files: previous.recent_files,
+            show_welcome: previous.show_welcome,
+            autosave: previous.autosave,
+            offer_recovery: previous.offer_recovery,
+            show_tasks: previous.show_tasks,
+            show_log: previous.show_log,
+            log_viewer: previous.log_viewer,
+            show_upload: previous.show_upload,
+            upload_dialog: previous.upload_dialog,
+            show_errors: previous.show_errors,
+            show_zoo: previous.show_zoo,
+            zoo_browser: previous.zoo_browser,
+            show_model_card: previous.show_model_card,
+            notifications: previous.notifications,
+            command_palette: previous.command_palette,
+            ..Default::default()
+        };
+    }
+
+    fn run_collection_export(&mut self) {
+        let exported = self.export_collection();
+        self.notify_export(exported);
+    }
+
+    fn run_dataset_export(&mut self) {
+        let exported = self.export_dataset();
+        self.notify_export(exported);
+    }
+
+    fn notify_export(&mut self, exported: Result<PathBuf>) {
+        match exported {
+            Ok(path) => self.notifications.success(tr!("saved-to", path = path.to_string_lossy().into_owned())),
+            Err(err) => self.notifications.error(tr!("export-failed", error = err.to_string())),
+        }
+    }
+
+    fn export_collection(&mut self) -> Result<PathBuf> {
+        let base = self.base_rdf()?;
+        let drafts = self.staging_collection.state()?;
+        self.write_resource(move |dir| {
+            let mut collection = CollectionRdf::new(base);
+            let entries = drafts.into_iter().map(|draft| draft.into_entry(dir)).collect::<Result<_>>()?;
+            collection.set_entries(entries)?;
+            Ok(AnyResourceRdf::Collection(collection))
+        })
+    }
+
+    fn export_dataset(&mut self) -> Result<PathBuf> {
+        let (source, size) = self.staging_dataset_fields.state()?;
+        let mut base = self.base_rdf()?;
+        base.source = source;
+        self.write_resource(|_| {
+            Ok(AnyResourceRdf::Dataset(DatasetRdf {
+                rdf_type: DatasetType::Dataset,
+                base,
+                size,
+            }))
+        })
+    }
+
+    /// The files the general fields refer to by name: the covers, the icon image, the documentation and the
+    /// attachments
+    fn general_files(&self) -> Result<Vec<(PathBuf, PackageFile)>> {
+        let mut files = cover_package_files(self.cover_images.state())?;
+        files.extend(self.staging_icon.packaged()?.and_then(|(_, file)| file));
+        if let Some(markdown) = self.staging_documentation.state()? {
+            let documentation = PathBuf::from(self.staging_documentation.file_name());
+            files.push((documentation, PackageFile::Bytes(markdown.as_bytes().to_vec())));
+        }
+        files.extend(attachment_package_files(self.staging_attachments.staging.iter()));
+        Ok(files)
+    }
+
+    /// Writes a resource that isn't packaged as an `rdf.yaml` picked by the user, with the files it refers to
+    /// next to it
+    /// Saves the description `rdf` makes for the directory it is saved in, along with the files of the general fields
+    fn write_resource(&mut self, rdf: impl FnOnce(&Path) -> Result<AnyResourceRdf>) -> Result<PathBuf> {
+        let files = self.general_files()?;
+        let Some(path) = self.file_dialogs.save_dialog().set_file_name("rdf.yaml").save_file() else {
+            return Err(GuiError::new(tr!("no-file-selected")));
+        };
+        self.file_dialogs.remember_saved(&path);
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let yaml = WithExtras {
+            rdf: rdf(dir)?,
+            extra: self.imported_extra.clone(),
+        }
+        .to_yaml()?;
+        for (name, file) in files {
+            match file {
+                PackageFile::Path(source) if source == dir.join(&name) => (),
+                PackageFile::Path(source) => std::fs::copy(source, dir.join(name)).map(|_| ())?,
+                PackageFile::Bytes(bytes) => std::fs::write(dir.join(name), bytes)?,
+            }
+        }
+        std::fs::write(&path, yaml)?;
+        Ok(path)
+    }
+
+    /// The model being edited along with every file it refers to, checked as a whole. Fails with
+    /// everything that stops it from being exported.
+    fn model_package(&self) -> std::result::Result<ModelPackage, Vec<String>> {
+        let package = self.assemble_model_package()?;
+        let report = package.validate();
+        for warning in report.warnings() {
+            log::warn!("{warning}");
+        }
+        let errors: Vec<String> = report.errors().map(ToString::to_string).collect();
+        match errors.is_empty() {
+            true => Ok(package),
+            false => Err(errors),
+        }
+    }
+
+    /// The model being edited along with every file it refers to, before the checks that look at all of it.
+    /// Fails with whatever stops it from being put together.
+    fn assemble_model_package(&self) -> std::result::Result<ModelPackage, Vec<String>> {
+        fn check<T>(errors: &mut Vec<String>, result: Result<T>) -> Option<T> {
+            result.map_err(|err| errors.push(err.to_string())).ok()
+        }
+        fn non_empty<T>(items: Vec<T>, item_id: &str) -> Result<NonEmptyList<T>> {
+            NonEmptyList::try_from(items).map_err(|_| GuiError::new(tr!("model-needs-one", item = tr!(item_id))))
+        }
+
+        let mut errors = vec![];
+        let base = check(&mut errors, self.base_rdf());
+        let authors = self.staging_authors.state().into_iter().collect::<Result<Vec<_>>>();
+        let authors = check(&mut errors, authors.and_then(|authors| non_empty(authors, "author")));
+        let cite = self.staging_citations.state().into_iter().collect::<Result<Vec<_>>>();
+        let cite = check(&mut errors, cite.and_then(|cite| non_empty(cite, "cite")));
+        let inputs = self.staging_inputs.state().into_iter().collect::<Result<Vec<_>>>();
+        let inputs = check(&mut errors, inputs.and_then(|inputs| non_empty(inputs, "input-tensor")));
+        let outputs = self.staging_outputs.state().into_iter().collect::<Result<Vec<_>>>();
+        let outputs = check(&mut errors, outputs.and_then(|outputs| non_empty(outputs, "output-tensor")));
+        let weights = check(&mut errors, self.staging_weights.state());
+        let parent = check(&mut errors, self.staging_parent.state().transpose());
+        let run_mode = check(&mut errors, self.staging_run_mode.state().transpose());
+        let training_data = check(&mut errors, self.staging_training_data.state().transpose());
+        let covers = check(&mut errors, cover_package_files(self.cover_images.state()));
+        let icon = check(&mut errors, self.staging_icon.packaged());
+        let markdown = self.staging_documentation.state().and_then(|markdown| {
+            markdown.ok_or_else(|| GuiError::new(tr!("documentation-missing")))
+        });
+        let markdown = check(&mut errors, markdown);
+        let (
+            Some(base),
+            Some(authors),
+            Some(cite),
+            Some(inputs),
+            Some(outputs),
+            Some(weights),
+            Some(parent),
+            Some(run_mode),
+            Some(training_data),
+            Some(covers),
+            Some(icon),
+            Some(markdown),
+        ) = (base, authors, cite, inputs, outputs, weights, parent, run_mode, training_data, covers, icon, markdown)
+        else {
+            // the general fields have the authors, citations, covers and icon too, whose errors are listed once
+            let mut listed = HashSet::new();
+            errors.retain(|error| listed.insert(error.clone()));
+            return Err(errors);
+        };
+
+        let cover_paths: Vec<_> = covers.iter().map(|(path, _)| rdf::FileReference::Path(path.clone())).collect();
+        let mut files = covers;
+        let documentation = PathBuf::from(self.staging_documentation.file_name());
+        files.push((documentation.clone(), PackageFile::Bytes(markdown.as_bytes().to_vec())));
+        let (icon, icon_file) = icon.unzip();
+        files.extend(icon_file.flatten());
+        let attachments = self.staging_attachments.staging.iter();
+        let inputs_files = self.staging_inputs.staging.iter().flat_map(|input| input.attachments());
+        let output_files = self.staging_outputs.staging.iter().flat_map(|output| output.attachments());
+        let attachments = attachments.chain(inputs_files).chain(output_files).chain(self.staging_weights.attachments());
+        files.extend(attachment_package_files(attachments));
+        files.extend(self.staging_weights.environment_files());
+
+        let rdf = ModelRdf {
+            format_version: UPGRADED_FORMAT_VERSION.try_into().expect("the upgraded format version is 0.5"),
+            description: base.description,
+            name: base.name,
+            attachments: base.attachments.map(|attachments| attachments.files).unwrap_or_default(),
+            authors,
+            cite,
+            config: base.config,
+            covers: cover_paths,
+            documentation: rdf::FileReference::Path(documentation),
+            git_repo: base.git_repo,
+            icon: match icon.map(BoundedString::try_from).transpose() {
+                Ok(icon) => icon,
+                Err(err) => return Err(vec![err.to_string()]),
+            },
+            id: None,
+            license: self.staging_license.state(),
+            links: base.links.unwrap_or_default(),
+            badges: base.badges.unwrap_or_default(),
+            maintainers: base.maintainers.unwrap_or_default(),
+            parent,
+            tags: base.tags.unwrap_or_default(),
+            rdf_type: ModelType::Model,
+            run_mode,
+            training_data,
+            version: base.version,
+            inputs,
+            outputs,
+            weights,
+            packaged_by: vec![],
+            history: self.version_history.clone(),
+        };
+        // runs the checks that span tensors, like dangling size references
+        let rdf = serde_yaml::to_value(&rdf)
+            .map_err(GuiError::from)
+            .and_then(|value| ModelRdf::try_from(value).map_err(GuiError::from));
+        let mut package = ModelPackage::new(rdf.map_err(|err| vec![err.to_string()])?);
+
+        let mut added: BTreeMap<PathBuf, Option<PathBuf>> = BTreeMap::new();
+        for (package_path, file) in files {
+            let source = match &file {
+                PackageFile::Path(path) => Some(path.clone()),
+                PackageFile::Bytes(_) => None,
+            };
+            match added.get(&package_path) {
+                // the same file can be referred to more than once
+                Some(Some(previous)) if source.as_ref() == Some(previous) => continue,
+                Some(_) => {
+                    let name = package_path.to_string_lossy().into_owned();
+                    errors.push(tr!("package-duplicate-name", name = name));
+                    continue;
+                }
+                None => (),
+            }
+            added.insert(package_path.clone(), source);
+            package.add_file(package_path, file);
+        }
+        if let Err(err) = package.fill_hashes() {
+            errors.push(err.to_string());
+        }
+        match errors.is_empty() {
+            true => Ok(package),
+            false => Err(errors),
+        }
+    }
+
+    /// Checks the whole model and, if nothing is wrong with it, writes it as a zip in the background
+    fn start_model_export(&mut self, ctx: &egui::Context) {
+        reveal_all_errors(ctx);
+        let mut package = match self.model_package() {
+            Ok(package) => package,
+            Err(errors) => return self.export_errors = errors,
+        };
+        let file_name = format!("{}.zip", package.rdf.name);
+        let Some(path) = self
+            .file_dialogs
+            .save_dialog()
+            .add_filter(tr!("packages"), &["zip"])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+        self.file_dialogs.remember_saved(&path);
+        if self.staging_version.is_exported() {
+            let version = package.rdf.version.as_ref().map(ToString::to_string).unwrap_or_default();
+            self.notifications.info(tr!("version-already-exported", version = version));
+        }
+        let version = package.rdf.version.clone();
+        let description = tr!("exporting-model", path = path.to_string_lossy().into_owned());
+        self.pending_export = Some(Task::spawn(description, ctx.clone(), move |status| {
+            let written = package.write_to_path_with_progress(&path, &mut |progress| status.report(0.9 * progress.fraction()));
+            if let Err(err) = written {
+                // a zip that was cancelled or failed halfway is of no use to anybody
+                let _ = std::fs::remove_file(&path);
+                return Err(err.into());
+            }
+            let sha256 = rdf::Sha256::of_file_with_progress(&path, &mut |progress| {
+                status.report(0.9 + 0.1 * progress.fraction())
+            })?;
+            Ok((path, sha256, version))
+        }));
     }
 }
 
+restore_with_serde!(EditorMode, VersionHistory, serde_yaml::Mapping);
+// what the resource was imported from is left out, since its hash would not match the draft anyway
+restore_fields!(TemplateApp {
+    mode,
+    staging_name,
+    staging_description,
+    cover_images,
+    staging_authors,
+    staging_attachments,
+    staging_citations,
+    staging_git_repo,
+    staging_icon,
+    staging_links,
+    staging_badges,
+    staging_maintainers,
+    staging_tags,
+    staging_version,
+    version_history,
+    staging_documentation,
+    staging_license,
+    staging_example_tensor,
+    staging_model_family,
+    staging_partner_configs,
+    staging_parent,
+    staging_run_mode,
+    staging_training_data,
+    staging_tensor_data,
+    staging_weights,
+    staging_inputs,
+    staging_outputs,
+    staging_index_axis,
+    staging_dataset_fields,
+    staging_collection,
+    imported_config,
+    imported_extra,
+});
+
 impl eframe::App for TemplateApp {
-    fn save(&mut self, _storage: &mut dyn eframe::Storage) {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.autosave.stop();
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
         // eframe::set_value(storage, eframe::APP_KEY, self);
+        if let Ok(config) = self.backend_config.state() {
+            eframe::set_value(storage, Self::BACKEND_CONFIG_KEY, &config);
+        }
+        eframe::set_value(storage, AppearanceSettings::STORAGE_KEY, &self.appearance);
+        eframe::set_value(storage, EditorSettings::STORAGE_KEY, &self.editor_settings);
+        eframe::set_value(storage, FileDialogSettings::STORAGE_KEY, &self.file_dialogs);
+        eframe::set_value(storage, NetworkSettings::STORAGE_KEY, &self.network);
+        eframe::set_value(storage, RecentFiles::STORAGE_KEY, &self.recent_files);
+        eframe::set_value(storage, AuthorProfile::STORAGE_KEY, &self.profile);
     }
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.style_mut().spacing.item_spacing = egui::Vec2 { x: 10.0, y: 10.0 };
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                ui.heading("Model Properties");
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let system_theme = frame.info().system_theme;
+        if self.applied_appearance != Some((self.appearance, system_theme)) {
+            self.appearance.apply(ctx, system_theme);
+            self.applied_appearance = Some((self.appearance, system_theme));
+        }
+        cross_checks::check_authors(&mut self.staging_authors.staging);
+        renames::propagate_renames(&mut self.staging_inputs.staging, &mut self.staging_outputs.staging);
+        cross_checks::check_tensors(&mut self.staging_inputs.staging, &mut self.staging_outputs.staging);
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Name: ");
-                    self.staging_name.draw_and_parse(ui, egui::Id::from("Name"));
-                    let name_result = self.staging_name.state();
-                });
-                ui.add_space(10.0);
+        if self.pending_import.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_import.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok((provenance, yaml)) => self.apply_import(provenance, yaml),
+                Err(err) => self.notifications.error(tr!("import-failed", error = err.to_string())),
+            }
+        }
+        if self.pending_model.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_model.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok(opened) => {
+                    self.recent_files.add(RecentKind::Model, &opened.path);
+                    self.apply_model(ctx, opened);
+                }
+                Err(err) => self.notifications.error(tr!("open-model-failed", error = err.to_string())),
+            }
+        }
+        if self.pending_export.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_export.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok((path, sha256, version)) => {
+                    if let Some(version) = version {
+                        self.staging_version.mark_exported(version);
+                    }
+                    self.notifications.success(tr!(
+                        "model-exported",
+                        path = path.to_string_lossy().into_owned(),
+                        sha256 = sha256.to_string(),
+                    ))
+                }
+                Err(err) => self.notifications.error(tr!("export-failed", error = err.to_string())),
+            }
+        }
+        match self.upload_dialog.finished_upload() {
+            Some(Ok(uploaded)) => {
+                let location = uploaded.location.unwrap_or_default();
+                self.notifications.success(tr!("upload-finished", location = location))
+            }
+            Some(Err(err)) => self.notifications.error(tr!("upload-failed", error = err.to_string())),
+            None => (),
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Description: ");
-                    self.staging_description.draw_and_parse(ui, egui::Id::from("Name"));
-                    let description_result = self.staging_description.state();
-                });
-                ui.add_space(10.0);
+        if ctx.input_mut(|i| i.consume_shortcut(&Self::FOCUS_ERROR_SHORTCUT)) {
+            self.run_action(ctx, AppAction::FocusFirstError);
+        }
+        // text fields undo their own edits while they have the focus
+        if ctx.memory(|memory| memory.focus().is_none()) {
+            // checked first, since Ctrl+Shift+Z also matches Ctrl+Z
+            if ctx.input_mut(|i| i.consume_shortcut(&Self::REDO_SHORTCUT)) {
+                self.redo(ctx);
+            } else if ctx.input_mut(|i| i.consume_shortcut(&Self::UNDO_SHORTCUT)) {
+                self.undo(ctx);
+            }
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Cover Images: ");
-                    self.cover_images.draw_and_parse(ui, egui::Id::from("Cover Images"));
-                    // let cover_img_results = self.cover_images.state();
-                });
-                ui.add_space(10.0);
+        let commands = self.palette_commands();
+        if let Some(action) = self.command_palette.show(ctx, &commands) {
+            self.run_action(ctx, action);
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Authors: ");
-                    self.staging_authors.draw_and_parse(ui, egui::Id::from("Authors"));
-                    // let author_results = self.staging_authors.state();
+        let mut recent_pick = None;
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.mode, EditorMode::Model, tr!("model"));
+                ui.selectable_value(&mut self.mode, EditorMode::Dataset, tr!("dataset"));
+                ui.selectable_value(&mut self.mode, EditorMode::Collection, tr!("collection"));
+                ui.separator();
+                let undo_button = egui::Button::new("⟲").shortcut_text(ctx.format_shortcut(&Self::UNDO_SHORTCUT));
+                if ui.add_enabled(self.undo_history.can_undo(), undo_button).on_hover_text(tr!("undo")).clicked() {
+                    self.undo(ctx);
+                }
+                let redo_button = egui::Button::new("⟳").shortcut_text(ctx.format_shortcut(&Self::REDO_SHORTCUT));
+                if ui.add_enabled(self.undo_history.can_redo(), redo_button).on_hover_text(tr!("redo")).clicked() {
+                    self.redo(ctx);
+                }
+                ui.separator();
+                if ui.add_enabled(self.pending_import.is_none(), egui::Button::new(tr!("import"))).clicked() {
+                    self.start_import(ctx);
+                }
+                if ui.add_enabled(self.pending_model.is_none(), egui::Button::new(tr!("open-model"))).clicked() {
+                    self.start_open_model(ctx);
+                }
+                if ui.button(tr!("open-from-zoo")).clicked() {
+                    self.show_zoo = !self.show_zoo;
+                }
+                if ui.button(tr!("save-draft")).clicked() {
+                    self.run_save_draft();
+                }
+                if ui.button(tr!("open-draft")).clicked() {
+                    self.run_open_draft(ctx);
+                }
+                if let Some(template) = show_template_menu(ui) {
+                    self.apply_template(ctx, template);
+                }
+                ui.menu_button(tr!("recent"), |ui| {
+                    if let Some(file) = self.recent_files.show(ui) {
+                        recent_pick = Some(file);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.add_enabled(!self.recent_files.is_empty(), egui::Button::new(tr!("clear-recent"))).clicked() {
+                        self.recent_files.clear();
+                        ui.close_menu();
+                    }
                 });
-                ui.add_space(10.0);
+                if let Some(provenance) = &self.provenance {
+                    ui.weak(tr!(
+                        "imported-from",
+                        source = format!("{:?}", provenance.rdf_source),
+                        sha256 = provenance.sha256.to_string()[..8].to_owned(),
+                    ));
+                }
+                ui.separator();
+                if self.mode == EditorMode::Collection && ui.button(tr!("export-collection")).clicked() {
+                    self.run_collection_export();
+                }
+                if self.mode == EditorMode::Dataset && ui.button(tr!("export-dataset")).clicked() {
+                    self.run_dataset_export();
+                }
+                if self.mode == EditorMode::Model
+                    && ui.add_enabled(self.pending_export.is_none(), egui::Button::new(tr!("export-model"))).clicked()
+                {
+                    self.start_model_export(ctx);
+                }
+                if self.mode == EditorMode::Model && ui.button(tr!("validate-model")).clicked() {
+                    self.validate_model(ctx);
+                }
+                if self.mode == EditorMode::Model {
+                    ui.toggle_value(&mut self.show_model_card, tr!("model-card"));
+                }
+                if self.mode == EditorMode::Model {
+                    let blocker = self.run_test_blocker();
+                    let can_run_test = blocker.is_none() && !self.self_test.is_running();
+                    let button = ui.add_enabled(can_run_test, egui::Button::new(tr!("run-test")));
+                    if button.on_disabled_hover_text(blocker.unwrap_or_else(|| tr!("running-test"))).clicked() {
+                        self.start_self_test(ctx);
+                    }
+                }
+                if ui.button(tr!("validate-all")).clicked() {
+                    self.validate_all(ctx);
+                }
+                ui.toggle_value(&mut self.show_errors, tr!("error-summary"));
+                if ui.button(tr!("upload-package")).clicked() {
+                    self.show_upload = !self.show_upload;
+                }
+                if ui.button(tr!("settings")).clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+            });
+        });
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Cite: ");
-                    self.staging_citations.draw_and_parse(ui, egui::Id::from("Cite"));
-                    // let citation_results = self.staging_citations.state();
+        let mut recovery_choice = None;
+        egui::Window::new(tr!("recover-title"))
+            .id(egui::Id::new("Recover Autosave"))
+            .open(&mut self.offer_recovery)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(tr!("recover-prompt"));
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("restore-autosave")).clicked() {
+                        recovery_choice = Some(true);
+                    }
+                    if ui.button(tr!("discard-autosave")).clicked() {
+                        recovery_choice = Some(false);
+                    }
                 });
-                ui.add_space(10.0);
+            });
+        match recovery_choice {
+            Some(true) => {
+                self.offer_recovery = false;
+                match Autosave::recover() {
+                    Ok(contents) => {
+                        self.reset_resource();
+                        self.restore(ctx, &contents);
+                        self.notifications.info(tr!("autosave-restored"));
+                    }
+                    Err(err) => self.notifications.error(tr!("autosave-restore-failed", error = err.to_string())),
+                }
+            }
+            Some(false) => {
+                self.offer_recovery = false;
+                self.autosave.discard_recovered();
+            }
+            None => (),
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Git Repo: ");
-                    self.staging_git_repo.draw_and_parse(ui, egui::Id::from("Git Repo"));
-                    // let git_repo_result = self.staging_git_repo.state();
+        let mut welcome_open = self.show_welcome;
+        let mut welcome_action = None;
+        let mut start_new = false;
+        egui::Window::new(tr!("welcome"))
+            .id(egui::Id::new("Welcome"))
+            .open(&mut welcome_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(tr!("welcome-recent"));
+                if let Some(file) = self.recent_files.show(ui) {
+                    recent_pick = Some(file);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(self.pending_model.is_none(), egui::Button::new(tr!("open-model"))).clicked() {
+                        welcome_action = Some(AppAction::OpenModel);
+                    }
+                    if ui.button(tr!("open-draft")).clicked() {
+                        welcome_action = Some(AppAction::OpenDraft);
+                    }
+                    start_new = ui.button(tr!("start-new")).clicked();
+                    if let Some(template) = show_template_menu(ui) {
+                        welcome_action = Some(AppAction::NewFromTemplate(template));
+                    }
                 });
-                ui.add_space(10.0);
+            });
+        self.show_welcome = welcome_open && !start_new && welcome_action.is_none() && recent_pick.is_none();
+        if let Some(action) = welcome_action {
+            self.run_action(ctx, action);
+        }
+        if let Some(file) = recent_pick {
+            self.open_recent(ctx, file);
+        }
+
+        show_status_bar(ctx, &mut self.show_tasks, &mut self.show_log);
+        self.notifications.show(ctx);
+
+        egui::Window::new(tr!("tasks"))
+            .id(egui::Id::new("Tasks"))
+            .open(&mut self.show_tasks)
+            .show(ctx, show_task_list);
+
+        egui::Window::new(tr!("session-log"))
+            .id(egui::Id::new("Session Log"))
+            .open(&mut self.show_log)
+            .show(ctx, |ui| self.log_viewer.show(ui));
+
+        let mut picked_entry = None;
+        egui::Window::new(tr!("open-from-zoo"))
+            .id(egui::Id::new("Zoo"))
+            .open(&mut self.show_zoo)
+            .show(ctx, |ui| picked_entry = self.zoo_browser.show(ui));
+        if let Some(entry) = picked_entry {
+            if self.pending_import.is_none() {
+                self.show_zoo = false;
+                self.start_zoo_import(ctx, entry);
+            }
+        }
+
+        let mut upload_error = None;
+        let mut show_model_card = self.show_model_card && self.mode == EditorMode::Model;
+        if show_model_card && self.model_card.needs_model() {
+            let model = self.assemble_model_package().map(|package| package.rdf);
+            self.model_card.set_model(model);
+        }
+        let mut card_error = None;
+        egui::Window::new(tr!("model-card"))
+            .id(egui::Id::new("Model Card"))
+            .open(&mut show_model_card)
+            .default_size([640.0, 480.0])
+            .show(ctx, |ui| card_error = self.model_card.show(ui, &mut self.file_dialogs).err());
+        if self.mode == EditorMode::Model {
+            self.show_model_card = show_model_card;
+        }
+        if let Some(err) = card_error {
+            self.notifications.error(tr!("model-card-save-failed", error = err.to_string()));
+        }
+
+        egui::Window::new(tr!("upload-package"))
+            .id(egui::Id::new("Upload"))
+            .open(&mut self.show_upload)
+            .show(ctx, |ui| upload_error = self.upload_dialog.show(ui).err());
+        if let Some(err) = upload_error {
+            self.notifications.error(tr!("upload-failed", error = err.to_string()));
+        }
+
+        let mut apply_profile = false;
+        egui::Window::new(tr!("settings"))
+            .id(egui::Id::new("Settings"))
+            .open(&mut self.show_settings)
+            .show(ctx, |ui| {
+                ui.heading(tr!("inference"));
+                self.backend_config.draw_and_parse(ui, egui::Id::from("Backend Config"));
+                ui.separator();
+                ui.heading(tr!("appearance"));
+                self.appearance.draw(ui, egui::Id::from("Appearance"));
+                ui.separator();
+                ui.heading(tr!("editing"));
+                self.editor_settings.draw(ui, egui::Id::from("Editor Settings"));
+                ui.separator();
+                ui.heading(tr!("file-dialogs"));
+                self.file_dialogs.draw(ui, egui::Id::from("File Dialogs"));
+                ui.separator();
+                ui.heading(tr!("network"));
+                self.network.draw(ui, egui::Id::from("Network"));
+                ui.separator();
+                ui.heading(tr!("author-profile"));
+                self.profile.draw(ui, egui::Id::from("Author Profile"));
+                if ui.button(tr!("apply-profile")).clicked() {
+                    apply_profile = true;
+                }
+            });
+        if apply_profile {
+            let profile = self.profile.clone();
+            self.apply_profile(&profile);
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Icon: ");
-                    group_frame(ui, |ui| {
-                        self.staging_icon.draw_and_parse(ui, egui::Id::from("Icon"));
+        let mut viewer_open = self.unknown_resource.is_some();
+        if let Some(viewer) = &self.unknown_resource {
+            egui::Window::new(tr!("unsupported-resource"))
+                .id(egui::Id::new("Unsupported Resource"))
+                .open(&mut viewer_open)
+                .show(ctx, |ui| viewer.show(ui, egui::Id::from("Unknown Resource")));
+        }
+        if !viewer_open {
+            self.unknown_resource = None;
+        }
+
+        let mut jump_to = None;
+        if self.show_errors {
+            let sections = self.section_errors();
+            egui::SidePanel::right("Error Summary").resizable(true).show(ctx, |ui| {
+                ui.heading(tr!("error-summary"));
+                jump_to = show_error_summary(ui, &sections);
+            });
+        }
+        if let Some(section) = jump_to {
+            // untouched fields only show their errors once they are revealed
+            reveal_all_errors(ctx);
+            self.scroll_target = Some(section);
+            self.highlighted = Some((section, ctx.input(|i| i.time)));
+        }
+
+        if !self.export_errors.is_empty() {
+            let mut dismissed = false;
+            egui::Window::new(tr!("export-blocked"))
+                .id(egui::Id::new("Export Errors"))
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(tr!("export-blocked-summary"));
+                    egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                        for err in &self.export_errors {
+                            show_error(ui, err);
+                        }
                     });
+                    dismissed = ui.button(tr!("dismiss")).clicked();
                 });
-                ui.add_space(10.0);
+            if dismissed {
+                self.export_errors.clear();
+            }
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Maintainers: ");
-                    self.staging_maintainers.draw_and_parse(ui, egui::Id::from("Maintainers"));
+        if let Some(validation) = &self.model_validation {
+            let mut open = true;
+            egui::Window::new(tr!("validate-model"))
+                .id(egui::Id::new("Model Validation"))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    if !validation.complete {
+                        ui.label(tr!("validation-report-incomplete"));
+                    }
+                    self.validation_view.show(ui, &validation.report);
                 });
-                ui.add_space(10.0);
+            if !open {
+                self.model_validation = None;
+            }
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Tags: ");
-                    self.staging_tags.draw_and_parse(ui, egui::Id::from("Tags"));
-                });
-                ui.add_space(10.0);
+        if self.self_test.is_active() {
+            let mut open = true;
+            egui::Window::new(tr!("run-test"))
+                .id(egui::Id::new("Self Test"))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| self.self_test.show(ui));
+            if !open {
+                self.self_test.clear();
+            }
+        }
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Resource Version: ");
-                    self.staging_version.draw_and_parse(ui, egui::Id::from("Version"));
-                });
-                ui.add_space(10.0);
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // the form stays as it is until the export errors were looked at
+            ui.set_enabled(self.export_errors.is_empty());
+            ui.style_mut().spacing.item_spacing = egui::Vec2 { x: 10.0, y: 10.0 };
+            self.show_field_search(ui);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading(tr!(match self.mode {
+                    EditorMode::Model => "model-properties",
+                    EditorMode::Dataset => "dataset-properties",
+                    EditorMode::Collection => "collection-properties",
+                }));
 
-                ui.horizontal_top(|ui| {
-                    ui.strong("Documentation (markdown): ");
-                    self.staging_documentation.draw_and_parse(ui, egui::Id::from("Documentation"));
-                });
+                let section_errors = self.section_errors();
+                self.form_group(ui, FormGroup::General, &section_errors, |app, ui| {
+                    let response = ui.horizontal_top(|ui| {
+                        required_field_label_with_help(ui, &tr!("name-label"), "name");
+                        app.staging_name.draw_and_parse(ui, egui::Id::from("Name"));
+                        let name_result = app.staging_name.state();
+                    });
+                    app.scroll_to_section(FormSection::Name, &response.response);
+                    ui.add_space(10.0);
 
-                ui.horizontal(|ui| {
-                    ui.strong("License: ");
-                    self.staging_license.draw_and_parse(ui, egui::Id::from("License"));
+                    let response = ui.horizontal_top(|ui| {
+                        required_field_label_with_help(ui, &tr!("description-label"), "description");
+                        app.staging_description.draw_and_parse(ui, egui::Id::from("Name"));
+                        let description_result = app.staging_description.state();
+                    });
+                    app.scroll_to_section(FormSection::Description, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("tags-label"), "tags");
+                        app.staging_tags.draw_and_parse(ui, egui::Id::from("Tags"));
+                    });
+                    app.scroll_to_section(FormSection::Tags, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("resource-version-label"), "version");
+                        app.staging_version.draw_and_parse(ui, egui::Id::from("Version"));
+                        if let Some(bump) = app.staging_version.take_bump() {
+                            // remembered for the next version started from the command palette
+                            app.version_bump = bump;
+                            app.start_new_version();
+                        }
+                        if let Some(number) = app.version_history.version_number {
+                            ui.weak(tr!(
+                                "upload-count",
+                                number = number.get(),
+                                previous = app.version_history.previous_versions.len(),
+                            ));
+                        }
+                    });
+                    app.scroll_to_section(FormSection::Version, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal(|ui| {
+                        field_label_with_help(ui, &tr!("license-label"), "license");
+                        app.staging_license.draw_and_parse(ui, egui::Id::from("License"));
+                    });
+                    app.scroll_to_section(FormSection::License, &response.response);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label(ui, &tr!("links-label"));
+                        app.staging_links.draw_and_parse(ui, egui::Id::from("Links"));
+                    });
+                    app.scroll_to_section(FormSection::Links, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("badges-label"), "badges");
+                        app.staging_badges.draw_and_parse(ui, egui::Id::from("Badges"));
+                    });
+                    app.scroll_to_section(FormSection::Badges, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("git-repo-label"), "git_repo");
+                        app.staging_git_repo.draw_and_parse(ui, egui::Id::from("Git Repo"));
+                        // let git_repo_result = app.staging_git_repo.state();
+                    });
+                    app.scroll_to_section(FormSection::GitRepo, &response.response);
+                    ui.add_space(10.0);
+
+                    if app.mode == EditorMode::Collection {
+                        let response = ui.horizontal_top(|ui| {
+                            field_label(ui, &tr!("collection-label"));
+                            app.staging_collection.draw_and_parse(ui, egui::Id::from("Collection"));
+                        });
+                        app.scroll_to_section(FormSection::Collection, &response.response);
+                    }
+
+                    if app.mode == EditorMode::Dataset {
+                        let response = ui.horizontal_top(|ui| {
+                            field_label(ui, &tr!("dataset-label"));
+                            app.staging_dataset_fields.draw_and_parse(ui, egui::Id::from("Dataset"));
+                        });
+                        app.scroll_to_section(FormSection::Dataset, &response.response);
+                    }
+
+                    if app.mode == EditorMode::Model {
+                        let response = ui.horizontal_top(|ui| {
+                            field_label(ui, &tr!("model-family-label"));
+                            group_frame(ui, |ui| {
+                                app.staging_model_family.draw_and_parse(ui, egui::Id::from("Model Family"));
+                            });
+                        });
+                        app.scroll_to_section(FormSection::ModelFamily, &response.response);
+
+                        let response = ui.horizontal_top(|ui| {
+                            field_label(ui, &tr!("partner-configs-label"));
+                            group_frame(ui, |ui| {
+                                app.staging_partner_configs.draw_and_parse(ui, egui::Id::from("Partner Configs"));
+                            });
+                        });
+                        app.scroll_to_section(FormSection::PartnerConfigs, &response.response);
+
+                        let response = ui.horizontal_top(|ui| {
+                            field_label_with_help(ui, &tr!("parent-label"), "parent");
+                            app.staging_parent.draw_and_parse(ui, egui::Id::from("Parent"));
+                        });
+                        app.scroll_to_section(FormSection::Parent, &response.response);
+
+                        let response = ui.horizontal_top(|ui| {
+                            field_label(ui, &tr!("run-mode-label"));
+                            app.staging_run_mode.draw_and_parse(ui, egui::Id::from("Run Mode"));
+                        });
+                        app.scroll_to_section(FormSection::RunMode, &response.response);
+
+                        let response = ui.horizontal_top(|ui| {
+                            field_label_with_help(ui, &tr!("training-data-label"), "training_data");
+                            app.staging_training_data.draw_and_parse(ui, egui::Id::from("Training Data"));
+                        });
+                        app.scroll_to_section(FormSection::TrainingData, &response.response);
+
+                        let response = ui.horizontal_top(|ui| {
+                            field_label(ui, &tr!("tensor-data-label"));
+                            app.staging_tensor_data.draw_and_parse(ui, egui::Id::from("Tensor Data"));
+                        });
+                        app.scroll_to_section(FormSection::TensorData, &response.response);
+                    }
                 });
 
-                ui.horizontal(|ui| {
-                    ui.strong("Example tensor: ");
-                    self.staging_example_tensor
-                        .draw_and_parse(ui, egui::Id::from("Example Tensor"));
+                self.form_group(ui, FormGroup::People, &section_errors, |app, ui| {
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("authors-label"), "authors");
+                        app.staging_authors.draw_and_parse(ui, egui::Id::from("Authors"));
+                        // let author_results = app.staging_authors.state();
+                    });
+                    app.scroll_to_section(FormSection::Authors, &response.response);
+                    let author_table_id = egui::Id::from("Author Table Import");
+                    if let Some(authors) = app.author_table_import.show(ui, author_table_id, &mut app.file_dialogs) {
+                        app.import_authors(authors);
+                    }
+                    let groups = duplicate_groups(app.staging_authors.state());
+                    if let Some(group) = show_duplicate_people(ui, &groups, "author") {
+                        merge_group(&mut app.staging_authors.staging, group, StagingAuthor2::state, rdf::author::Author2::merge);
+                    }
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("cite-label"), "cite");
+                        app.staging_citations.draw_and_parse(ui, egui::Id::from("Cite"));
+                        // let citation_results = app.staging_citations.state();
+                    });
+                    app.scroll_to_section(FormSection::Cite, &response.response);
+                    let bibtex_id = egui::Id::from("Bibtex Import");
+                    if let Some(entries) = app.bibtex_import.show(ui, bibtex_id, &mut app.file_dialogs) {
+                        app.import_citations(entries);
+                    }
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("maintainers-label"), "maintainers");
+                        app.staging_maintainers.draw_and_parse(ui, egui::Id::from("Maintainers"));
+                    });
+                    app.scroll_to_section(FormSection::Maintainers, &response.response);
+                    let groups = duplicate_groups(app.staging_maintainers.state());
+                    if let Some(group) = show_duplicate_people(ui, &groups, "maintainer") {
+                        let staging = &mut app.staging_maintainers.staging;
+                        merge_group(staging, group, StagingMaintainer::state, rdf::maintainer::Maintainer::merge);
+                    }
+                    ui.add_space(10.0);
                 });
 
-                ui.horizontal(|ui| {
-                    ui.strong("Test axis size: ");
-                    self.staging_index_axis.draw_and_parse(ui, egui::Id::from("test size"));
+                self.form_group(ui, FormGroup::Files, &section_errors, |app, ui| {
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("cover-images-label"), "covers");
+                        ui.vertical(|ui| {
+                            show_cover_gallery(ui, &mut app.cover_images);
+                            app.cover_images.draw_and_parse(ui, egui::Id::from("Cover Images"));
+                        });
+                    });
+                    app.scroll_to_section(FormSection::CoverImages, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("attachments-label"), "attachments");
+                        app.staging_attachments.draw_and_parse(ui, egui::Id::from("Attachments"));
+                    });
+                    app.scroll_to_section(FormSection::Attachments, &response.response);
+                    ui.add_space(10.0);
+
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("icon-label"), "icon");
+                        group_frame(ui, |ui| {
+                            app.staging_icon.draw_and_parse(ui, egui::Id::from("Icon"));
+                        });
+                    });
+                    app.scroll_to_section(FormSection::Icon, &response.response);
+                    ui.add_space(10.0);
+
+                    let attachment_paths = app
+                        .staging_attachments
+                        .staging
+                        .iter()
+                        .filter_map(|widget| Some(widget.loaded_value()?.as_ref().ok()?.path().to_owned()))
+                        .collect();
+                    app.staging_documentation.set_attachments(attachment_paths);
+                    let response = ui.horizontal_top(|ui| {
+                        field_label_with_help(ui, &tr!("documentation-markdown-label"), "documentation");
+                        app.staging_documentation.draw_and_parse(ui, egui::Id::from("Documentation"));
+                    });
+                    app.scroll_to_section(FormSection::Documentation, &response.response);
+
+                    if app.mode == EditorMode::Model {
+                        let response = ui.horizontal(|ui| {
+                            field_label(ui, &tr!("example-tensor-label"));
+                            app.staging_example_tensor
+                                .draw_and_parse(ui, egui::Id::from("Example Tensor"));
+                        });
+                        app.scroll_to_section(FormSection::ExampleTensor, &response.response);
+                    }
                 });
+
+                if self.mode == EditorMode::Model {
+                    self.form_group(ui, FormGroup::Weights, &section_errors, |app, ui| {
+                        let response = ui.horizontal_top(|ui| {
+                            required_field_label_with_help(ui, &tr!("weights-label"), "weights");
+                            app.staging_weights.draw_and_parse(ui, egui::Id::from("Weights"));
+                        });
+                        app.scroll_to_section(FormSection::Weights, &response.response);
+                    });
+
+                    self.form_group(ui, FormGroup::Inputs, &section_errors, |app, ui| {
+                        let tensor_ids = app.tensor_ids();
+                        for input in &mut app.staging_inputs.staging {
+                            input.staging_preprocessing.set_tensor_ids(&tensor_ids);
+                        }
+                        for output in &mut app.staging_outputs.staging {
+                            output.staging_postprocessing.set_tensor_ids(&tensor_ids);
+                        }
+
+                        let response = ui.horizontal_top(|ui| {
+                            required_field_label_with_help(ui, &tr!("inputs-label"), "inputs");
+                            app.staging_inputs.draw_and_parse(ui, egui::Id::from("Inputs"));
+                        });
+                        app.scroll_to_section(FormSection::Inputs, &response.response);
+
+                        ui.horizontal(|ui| {
+                            field_label(ui, &tr!("test-axis-size-label"));
+                            app.staging_index_axis.draw_and_parse(ui, egui::Id::from("test size"));
+                        });
+                    });
+
+                    self.form_group(ui, FormGroup::Outputs, &section_errors, |app, ui| {
+                        let response = ui.horizontal_top(|ui| {
+                            required_field_label_with_help(ui, &tr!("outputs-label"), "outputs");
+                            app.staging_outputs.draw_and_parse(ui, egui::Id::from("Outputs"));
+                        });
+                        app.scroll_to_section(FormSection::Outputs, &response.response);
+                    });
+                }
             });
         });
+
+        let snapshot = self.dump();
+        self.autosave.tick(ctx, &snapshot);
+        self.undo_history.record(ctx, snapshot, self.editor_settings.undo_depth);
     }
 }
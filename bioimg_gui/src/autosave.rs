@@ -0,0 +1,90 @@
+//! Saving the resource being edited every now and then, so that it can be recovered after a crash. A marker file
+//! exists for as long as the app runs; finding it on launch means the last run didn't shut down cleanly.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::draft::{open_draft, save_draft, DRAFT_EXTENSION};
+use crate::result::Result;
+
+/// How often the resource is saved, if it changed
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+fn autosave_dir() -> PathBuf {
+    std::env::temp_dir().join("bioimg_gui")
+}
+
+fn autosave_path() -> PathBuf {
+    autosave_dir().join(format!("autosave.{DRAFT_EXTENSION}"))
+}
+
+fn running_marker_path() -> PathBuf {
+    autosave_dir().join("running")
+}
+
+pub struct Autosave {
+    last_save: Instant,
+    /// What was saved last, so that nothing is written while nothing changes. The contents the app
+    /// starts out with count as saved, since there is nothing in them worth recovering.
+    saved: Option<serde_yaml::Value>,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self {
+            last_save: Instant::now(),
+            saved: None,
+        }
+    }
+}
+
+impl Autosave {
+    /// Marks the app as running. Returns whether the last run crashed with an autosave left behind,
+    /// which can be restored with [Autosave::recover]; any other autosave is deleted.
+    pub fn start() -> (Self, bool) {
+        let crashed = running_marker_path().exists() && autosave_path().exists();
+        if !crashed {
+            let _ = std::fs::remove_file(autosave_path());
+        }
+        if let Err(err) = std::fs::create_dir_all(autosave_dir()).and_then(|_| std::fs::write(running_marker_path(), "")) {
+            log::warn!("Could not mark the app as running, crashes won't be noticed: {err}");
+        }
+        (Self::default(), crashed)
+    }
+
+    /// The contents of the draft the last run saved before it crashed
+    pub fn recover() -> Result<serde_yaml::Value> {
+        open_draft(&autosave_path())
+    }
+
+    /// Forgets the draft of a run that crashed, if it wasn't wanted back
+    pub fn discard_recovered(&self) {
+        let _ = std::fs::remove_file(autosave_path());
+    }
+
+    /// Saves `contents` if they changed and it's been a while since the last save
+    pub fn tick(&mut self, ctx: &egui::Context, contents: &serde_yaml::Value) {
+        if self.saved.is_none() {
+            self.saved = Some(contents.clone());
+        }
+        if self.last_save.elapsed() < AUTOSAVE_INTERVAL {
+            // nothing else might wake the app up again once the user stops typing
+            ctx.request_repaint_after(AUTOSAVE_INTERVAL - self.last_save.elapsed());
+            return;
+        }
+        self.last_save = Instant::now();
+        if self.saved.as_ref() == Some(contents) {
+            return;
+        }
+        match save_draft(&autosave_path(), contents.clone()) {
+            Ok(()) => self.saved = Some(contents.clone()),
+            Err(err) => log::warn!("Could not autosave to {}: {err}", autosave_path().to_string_lossy()),
+        }
+    }
+
+    /// Removes the autosave and the marker, since nothing needs recovering after a clean shutdown
+    pub fn stop(&self) {
+        let _ = std::fs::remove_file(autosave_path());
+        let _ = std::fs::remove_file(running_marker_path());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::i18n::tr;
+
+const SHORTCUTS: [egui::KeyboardShortcut; 2] = [
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::P),
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT), egui::Key::P),
+];
+
+/// Scores how well `query` matches `candidate` as a case-insensitive subsequence, higher being
+/// better. Runs of consecutive characters and matches at the start of words score extra.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + candidate[position..].iter().position(|c| *c == query_char)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    Some(score - candidate.len() as i32 / 10)
+}
+
+/// A searchable list of actions, opened with Ctrl+P or Ctrl+Shift+P
+#[derive(Default)]
+pub struct CommandPalette {
+    open: bool,
+    search: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// Shows the palette if it is open, returning the action picked this frame
+    pub fn show<A: Clone>(&mut self, ctx: &egui::Context, commands: &[(String, A)]) -> Option<A> {
+        if ctx.input_mut(|i| SHORTCUTS.iter().any(|shortcut| i.consume_shortcut(shortcut))) {
+            self.open = !self.open;
+            self.search.clear();
+            self.selected = 0;
+        }
+        if !self.open {
+            return None;
+        }
+
+        let mut matches: Vec<(i32, &(String, A))> = commands
+            .iter()
+            .filter_map(|command| Some((fuzzy_score(&self.search, &command.0)?, command)))
+            .collect();
+        matches.sort_by_key(|(score, _)| -score);
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if escape {
+            self.open = false;
+            return None;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down && self.selected + 1 < matches.len() {
+            self.selected += 1;
+        }
+
+        let mut picked = enter.then(|| matches.get(self.selected).map(|(_, (_, action))| action.clone())).flatten();
+        egui::Window::new(tr!("command-palette"))
+            .id(egui::Id::new("Command Palette"))
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .show(ctx, |ui| {
+                let search_response = ui.add(egui::TextEdit::singleline(&mut self.search).hint_text(tr!("type-a-command")));
+                search_response.request_focus();
+                if search_response.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+                if matches.is_empty() {
+                    ui.weak(tr!("no-matching-commands"));
+                }
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (idx, (_, (label, action))) in matches.iter().enumerate() {
+                        let response = ui.selectable_label(idx == self.selected, label);
+                        if idx == self.selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            picked = Some(action.clone());
+                        }
+                    }
+                });
+            });
+        if picked.is_some() {
+            self.open = false;
+        }
+        picked
+    }
+}
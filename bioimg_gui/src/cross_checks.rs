@@ -0,0 +1,87 @@
+//! Checks of constraints that span several fields, like tensor ids having to be unique. They run before every
+//! frame and mark each field involved, so that the error shows next to all of them and not just the one edited last.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::i18n::tr;
+use crate::widgets::author_widget::StagingAuthor2;
+use crate::widgets::input_tensor_widget::InputTensorWidget;
+use crate::widgets::output_tensor_widget::OutputTensorWidget;
+use crate::widgets::StagingString;
+
+/// Marks every field among `fields` whose text is also typed into another one. Empty fields don't clash,
+/// since they already get an error of their own if they are required.
+fn flag_duplicates<'f, T>(fields: impl IntoIterator<Item = &'f mut StagingString<T>>, message: impl Fn(&str) -> String)
+where
+    T: TryFrom<String> + 'f,
+    T::Error: std::fmt::Display + 'static,
+{
+    let mut fields: Vec<_> = fields.into_iter().collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for field in &fields {
+        *counts.entry(field.raw().trim().to_owned()).or_default() += 1;
+    }
+    for field in &mut fields {
+        let raw = field.raw().trim().to_owned();
+        let duplicated = !raw.is_empty() && counts[&raw] > 1;
+        field.set_conflict(duplicated.then(|| message(&raw)));
+    }
+}
+
+/// Tensor ids have to be unique across inputs and outputs, axis ids within each tensor,
+/// and sizes can only be taken from axes that exist
+pub fn check_tensors(inputs: &mut [InputTensorWidget], outputs: &mut [OutputTensorWidget]) {
+    let tensor_ids = inputs
+        .iter_mut()
+        .map(|input| &mut input.staging_id)
+        .chain(outputs.iter_mut().map(|output| &mut output.staging_id));
+    flag_duplicates(tensor_ids, |id| tr!("duplicate-tensor-id", id = id.to_owned()));
+
+    for input in inputs.iter_mut() {
+        let axis_ids = input.staging_axes.staging.iter_mut().map(|axis| axis.id_field_mut());
+        flag_duplicates(axis_ids, |id| tr!("duplicate-axis-id", id = id.to_owned()));
+    }
+    for output in outputs.iter_mut() {
+        let axis_ids = output.staging_axes.staging.iter_mut().map(|axis| axis.id_field_mut());
+        flag_duplicates(axis_ids, |id| tr!("duplicate-axis-id", id = id.to_owned()));
+    }
+
+    let input_axes = inputs.iter().map(|input| {
+        let axis_ids = input.staging_axes.staging.iter().map(|axis| axis.raw_id().trim().to_owned()).collect();
+        (input.staging_id.raw().trim().to_owned(), axis_ids)
+    });
+    let output_axes = outputs.iter().map(|output| {
+        let axis_ids = output.staging_axes.staging.iter().map(|axis| axis.raw_id().trim().to_owned()).collect();
+        (output.staging_id.raw().trim().to_owned(), axis_ids)
+    });
+    let axes: HashMap<String, HashSet<String>> = input_axes.chain(output_axes).collect();
+
+    let references = inputs
+        .iter_mut()
+        .flat_map(|input| input.staging_axes.staging.iter_mut().filter_map(|axis| axis.size_reference_mut()))
+        .chain(
+            outputs
+                .iter_mut()
+                .flat_map(|output| output.staging_axes.staging.iter_mut().filter_map(|axis| axis.size_reference_mut())),
+        );
+    for reference in references {
+        let tensor_id = reference.staging_tensor_id.raw().trim().to_owned();
+        let axis_id = reference.staging_axis_id.raw().trim().to_owned();
+        let (tensor_conflict, axis_conflict) = match axes.get(&tensor_id) {
+            _ if tensor_id.is_empty() => (None, None),
+            None => (Some(tr!("unknown-reference-tensor", id = tensor_id)), None),
+            Some(axis_ids) if !axis_id.is_empty() && !axis_ids.contains(&axis_id) => {
+                (None, Some(tr!("unknown-reference-axis", tensor = tensor_id, id = axis_id)))
+            }
+            Some(_) => (None, None),
+        };
+        reference.staging_tensor_id.set_conflict(tensor_conflict);
+        reference.staging_axis_id.set_conflict(axis_conflict);
+    }
+}
+
+/// Two authors with the same name are most likely the same person entered twice
+pub fn check_authors(authors: &mut [StagingAuthor2]) {
+    let names = authors.iter_mut().map(|author| author.name_field_mut());
+    flag_duplicates(names, |name| tr!("duplicate-author-name", name = name.to_owned()));
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+/// Extension of the project files drafts are saved to
+pub const DRAFT_EXTENSION: &str = "bioimgproj";
+
+/// Version of the draft format this build writes. Adding a field to a widget doesn't need a new
+/// version, since older drafts just leave it empty; changing how a widget dumps itself does, along
+/// with an arm in [upgrade] that turns drafts of the previous version into the new layout.
+pub const DRAFT_FORMAT_VERSION: u32 = 1;
+
+/// Everything that was typed in and picked so far, whether it validates or not
+#[derive(Serialize, Deserialize)]
+struct Draft {
+    format_version: u32,
+    /// Version of the app that saved the draft, for telling users where a draft they can't open comes from
+    app_version: String,
+    contents: serde_yaml::Value,
+}
+
+/// Brings the contents of a draft saved with `format_version` up to [DRAFT_FORMAT_VERSION]
+fn upgrade(format_version: u32, app_version: &str, contents: serde_yaml::Value) -> Result<serde_yaml::Value> {
+    match format_version {
+        DRAFT_FORMAT_VERSION => Ok(contents),
+        newer if newer > DRAFT_FORMAT_VERSION => {
+            Err(GuiError::new(tr!("draft-too-new", app_version = app_version.to_owned())))
+        }
+        older => Err(GuiError::new(tr!("draft-unsupported", version = older))),
+    }
+}
+
+pub fn save_draft(path: &Path, contents: serde_yaml::Value) -> Result<()> {
+    let draft = Draft {
+        format_version: DRAFT_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_owned(),
+        contents,
+    };
+    std::fs::write(path, serde_yaml::to_string(&draft)?)?;
+    Ok(())
+}
+
+/// The contents of the draft at `path`, in the layout of the current format version
+pub fn open_draft(path: &Path) -> Result<serde_yaml::Value> {
+    let draft: Draft = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+    upgrade(draft.format_version, &draft.app_version, draft.contents)
+}
@@ -0,0 +1,52 @@
+use crate::i18n::tr;
+use crate::result::GuiError;
+
+/// The errors of one part of the resource, under the label that part has in the form
+pub struct SectionErrors<S> {
+    pub section: S,
+    pub label: String,
+    pub errors: Vec<GuiError>,
+}
+
+/// Lists every error of the resource being edited, grouped by section and then by the field they are in,
+/// returning the section of the error that was clicked this frame
+pub fn show_error_summary<S: Copy>(ui: &mut egui::Ui, sections: &[SectionErrors<S>]) -> Option<S> {
+    if sections.is_empty() {
+        ui.weak(tr!("no-errors"));
+        return None;
+    }
+    let mut picked = None;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for section in sections {
+            egui::CollapsingHeader::new(format!("{} ({})", section.label, section.errors.len()))
+                .id_source(&section.label)
+                .default_open(true)
+                .show(ui, |ui| {
+                    // errors of the same field end up together, in the order their fields first come up
+                    let mut fields: Vec<&[std::sync::Arc<str>]> = vec![];
+                    for err in &section.errors {
+                        if !fields.contains(&err.field()) {
+                            fields.push(err.field());
+                        }
+                    }
+                    for field in fields {
+                        if !field.is_empty() {
+                            ui.weak(field.join(" › "));
+                        }
+                        for err in section.errors.iter().filter(|err| err.field() == field) {
+                            let text = egui::RichText::new(err.message()).color(ui.visuals().error_fg_color);
+                            let button = egui::Button::new(text).frame(false).wrap(true);
+                            let hover = match err.code() {
+                                Some(code) => format!("{} ({code})", tr!("jump-to-field")),
+                                None => tr!("jump-to-field"),
+                            };
+                            if ui.add(button).on_hover_text(hover).clicked() {
+                                picked = Some(section.section);
+                            }
+                        }
+                    }
+                });
+        }
+    });
+    picked
+}
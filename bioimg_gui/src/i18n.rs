@@ -0,0 +1,182 @@
+//! User facing strings, looked up by message id in the fluent catalogs under `locales/`.
+//! Messages missing from the current language fall back to English, then to the id itself.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use bioimg_spec::rdf::bounded_string::BoundedStringParsingError;
+use bioimg_spec::rdf::doi::DoiParsingError;
+use bioimg_spec::rdf::identifier::IdentifierParsingError;
+use bioimg_spec::rdf::lowercase::LowercaseParsingError;
+use bioimg_spec::rdf::orcid::OrcidParsingError;
+use bioimg_spec::rdf::slashless_string::SlashlessStringError;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default, strum::VariantArray)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    fn catalog(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../locales/en-US/main.ftl"),
+            Self::German => include_str!("../locales/de/main.ftl"),
+        }
+    }
+
+    fn language_id(self) -> unic_langid::LanguageIdentifier {
+        match self {
+            Self::English => unic_langid::langid!("en-US"),
+            Self::German => unic_langid::langid!("de"),
+        }
+    }
+
+    /// The name of the language in that language, for the language picker
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+}
+
+static CURRENT_LANGUAGE: parking_lot::RwLock<Language> = parking_lot::const_rwlock(Language::English);
+
+fn bundle(language: Language) -> &'static FluentBundle<FluentResource> {
+    static BUNDLES: OnceLock<Vec<(Language, FluentBundle<FluentResource>)>> = OnceLock::new();
+    let bundles = BUNDLES.get_or_init(|| {
+        <Language as strum::VariantArray>::VARIANTS
+            .iter()
+            .map(|language| {
+                let resource = FluentResource::try_new(language.catalog().to_owned())
+                    .unwrap_or_else(|(resource, errors)| {
+                        log::error!("Bad {language:?} catalog: {errors:?}");
+                        resource
+                    });
+                let mut bundle = FluentBundle::new_concurrent(vec![language.language_id()]);
+                // egui would render the unicode isolation marks around placeables as boxes
+                bundle.set_use_isolating(false);
+                if let Err(errors) = bundle.add_resource(resource) {
+                    log::error!("Bad {language:?} catalog: {errors:?}");
+                }
+                (*language, bundle)
+            })
+            .collect()
+    });
+    &bundles.iter().find(|(lang, _)| *lang == language).unwrap().1
+}
+
+pub fn current_language() -> Language {
+    *CURRENT_LANGUAGE.read()
+}
+
+pub fn set_language(language: Language) {
+    *CURRENT_LANGUAGE.write() = language;
+}
+
+fn format_message(language: Language, id: &str, args: Option<&FluentArgs<'_>>) -> Option<String> {
+    let bundle = bundle(language);
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = vec![];
+    let formatted = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        log::warn!("Could not format message '{id}': {errors:?}");
+    }
+    Some(formatted.into_owned())
+}
+
+pub fn translate(id: &str, args: Option<&FluentArgs<'_>>) -> String {
+    format_message(current_language(), id, args)
+        .or_else(|| format_message(Language::English, id, args))
+        .unwrap_or_else(|| id.to_owned())
+}
+
+/// Looks up a message in the current language, e.g. `tr!("name-label")` or `tr!("entry-number", number = 3)`
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+/// `error` in the current language if it is one of the parsing errors of the spec's string types,
+/// and the way it words itself otherwise
+pub fn localized_error<E: Display + 'static>(error: &E) -> String {
+    localized_spec_error(error).unwrap_or_else(|| error.to_string())
+}
+
+/// Like [localized_error], for the errors the spec's types wrap in one another
+fn localized_source(error: &(dyn Error + 'static)) -> String {
+    let known = if let Some(error) = error.downcast_ref::<BoundedStringParsingError>() {
+        localized_spec_error(error)
+    } else if let Some(error) = error.downcast_ref::<IdentifierParsingError>() {
+        localized_spec_error(error)
+    } else {
+        None
+    };
+    known.unwrap_or_else(|| error.to_string())
+}
+
+fn localized_spec_error(error: &dyn Any) -> Option<String> {
+    if let Some(error) = error.downcast_ref::<BoundedStringParsingError>() {
+        let BoundedStringParsingError::BadLength { length, allowed, .. } = error;
+        return Some(tr!("error-length", min = *allowed.start(), max = *allowed.end(), length = *length));
+    }
+    if let Some(error) = error.downcast_ref::<IdentifierParsingError>() {
+        return Some(match error {
+            IdentifierParsingError::BadString { source } => {
+                tr!("error-bad-identifier", error = localized_source(&**source))
+            }
+            IdentifierParsingError::EmptyString => tr!("error-empty-identifier"),
+            IdentifierParsingError::MustStartWithAlphabeticalOrUnderscore { value } => {
+                tr!("error-identifier-start", value = value.clone())
+            }
+            IdentifierParsingError::ContainsbadCharacter { value, .. } => {
+                tr!("error-identifier-character", value = value.clone())
+            }
+            IdentifierParsingError::IsPythonKeyword { value } => tr!("error-python-keyword", value = value.clone()),
+        });
+    }
+    if let Some(error) = error.downcast_ref::<LowercaseParsingError>() {
+        return Some(match error {
+            LowercaseParsingError::BadString { source } => localized_source(&**source),
+            LowercaseParsingError::IsNotLowercase { value, idx } => {
+                tr!("error-not-lowercase", value = value.clone(), index = *idx)
+            }
+        });
+    }
+    if let Some(error) = error.downcast_ref::<SlashlessStringError>() {
+        return Some(match error {
+            SlashlessStringError::BoundedStringParsingError(error) => localized_error(error),
+            SlashlessStringError::ContainsSlashes(value) => tr!("error-has-slashes", value = value.clone()),
+        });
+    }
+    if let Some(error) = error.downcast_ref::<OrcidParsingError>() {
+        return Some(match error {
+            OrcidParsingError::BadCode(code) => tr!("error-orcid-code", code = code.clone()),
+            OrcidParsingError::BadChar(found) => tr!("error-orcid-char", found = found.to_string()),
+            OrcidParsingError::BadChecksumChar(found) => tr!("error-orcid-checksum-char", found = found.to_string()),
+            OrcidParsingError::ChecksumMismatch { code, expected, found } => tr!(
+                "error-orcid-check-digit",
+                code = code.clone(),
+                expected = expected.to_string(),
+                found = found.to_string(),
+            ),
+        });
+    }
+    if let Some(DoiParsingError::BadSyntax(value)) = error.downcast_ref::<DoiParsingError>() {
+        return Some(tr!("error-doi-syntax", value = value.clone()));
+    }
+    None
+}
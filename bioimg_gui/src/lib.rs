@@ -1,7 +1,23 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
 mod app;
+mod autosave;
+mod command_palette;
+mod cross_checks;
+mod draft;
+mod error_summary;
+mod i18n;
+mod model_card_preview;
+mod notifications;
+mod renames;
 mod result;
+mod session_log;
+mod settings;
 mod task;
+mod templates;
+mod undo;
+mod upload_dialog;
 mod widgets;
+mod zoo_browser;
 pub use app::TemplateApp;
+pub use session_log::SessionLogger;
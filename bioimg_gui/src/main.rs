@@ -4,12 +4,17 @@
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
-    env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+    // Log to stderr (if you run with `RUST_LOG=debug`), keeping recent messages for the in-app log
+    let stderr_logger = env_logger::Builder::from_default_env().build();
+    let stderr_level = stderr_logger.filter();
+    bioimg_gui::SessionLogger::install(Box::new(stderr_logger), stderr_level).expect("Could not install logger");
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([400.0, 300.0])
             .with_min_inner_size([300.0, 220.0]),
+        // the app applies the system theme itself, unless the user picked one in the settings
+        follow_system_theme: false,
         ..Default::default()
     };
     eframe::run_native(
@@ -23,7 +28,8 @@ fn main() -> eframe::Result<()> {
 #[cfg(target_arch = "wasm32")]
 fn main() {
     // Redirect `log` message to `console.log` and friends:
-    eframe::WebLogger::init(log::LevelFilter::Debug).ok();
+    let web_logger = eframe::WebLogger::new(log::LevelFilter::Debug);
+    bioimg_gui::SessionLogger::install(Box::new(web_logger), log::LevelFilter::Debug).ok();
 
     let web_options = eframe::WebOptions::default();
 
@@ -0,0 +1,89 @@
+use bioimg_spec::rdf::model::ModelRdf;
+use bioimg_spec::runtime::{model_card, CardFormat};
+
+use crate::i18n::tr;
+use crate::result::Result;
+use crate::settings::FileDialogSettings;
+
+/// The model card of the model being edited. The model is only put together when the preview is opened or
+/// refreshed, since that takes a while for big models.
+#[derive(Default)]
+pub struct ModelCardPreview {
+    format: CardFormat,
+    /// The model the card is of, or why it couldn't be put together
+    model: Option<std::result::Result<ModelRdf, Vec<String>>>,
+    card: String,
+}
+
+impl ModelCardPreview {
+    /// Whether the model has to be put together and given to [Self::set_model] before the card can be shown
+    pub fn needs_model(&self) -> bool {
+        self.model.is_none()
+    }
+
+    pub fn set_model(&mut self, model: std::result::Result<ModelRdf, Vec<String>>) {
+        self.model = Some(model);
+        self.render();
+    }
+
+    fn render(&mut self) {
+        self.card = match &self.model {
+            Some(Ok(model)) => model_card(model, self.format),
+            _ => String::new(),
+        };
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, file_dialogs: &mut FileDialogSettings) -> Result<()> {
+        let mut result = Ok(());
+        ui.horizontal(|ui| {
+            let format = self.format;
+            ui.radio_value(&mut self.format, CardFormat::Markdown, tr!("markdown"));
+            ui.radio_value(&mut self.format, CardFormat::Html, tr!("html"));
+            if self.format != format {
+                self.render();
+            }
+            ui.separator();
+            if ui.button(tr!("refresh")).clicked() {
+                self.model = None;
+            }
+            let has_card = !self.card.is_empty();
+            if ui.add_enabled(has_card, egui::Button::new(tr!("copy"))).clicked() {
+                ui.output_mut(|output| output.copied_text = self.card.clone());
+            }
+            if ui.add_enabled(has_card, egui::Button::new(tr!("save-as"))).clicked() {
+                result = self.save(file_dialogs);
+            }
+        });
+        ui.separator();
+        match &self.model {
+            Some(Err(errors)) => {
+                ui.label(tr!("model-card-incomplete"));
+                for error in errors {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            }
+            Some(Ok(_)) => {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    // shown as it is written out; editing it would only change this copy
+                    let mut card = self.card.as_str();
+                    ui.add(egui::TextEdit::multiline(&mut card).code_editor().desired_width(f32::INFINITY));
+                });
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        result
+    }
+
+    fn save(&self, file_dialogs: &mut FileDialogSettings) -> Result<()> {
+        let extension = self.format.extension();
+        let dialog = file_dialogs.save_dialog().add_filter(extension, &[extension]);
+        let Some(path) = dialog.set_file_name(format!("README.{extension}")).save_file() else {
+            return Ok(());
+        };
+        file_dialogs.remember_saved(&path);
+        std::fs::write(&path, &self.card)?;
+        Ok(())
+    }
+}
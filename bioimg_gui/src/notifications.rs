@@ -0,0 +1,110 @@
+use crate::i18n::tr;
+use crate::task::running_tasks;
+
+/// How long a toast stays on screen, in seconds
+const TOAST_DURATION: f64 = 5.0;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Error,
+}
+
+struct Toast {
+    level: ToastLevel,
+    message: String,
+    /// Value of `egui::InputState::time` when the toast was first drawn
+    shown_at: Option<f64>,
+}
+
+/// Short-lived messages stacked in the bottom right corner, on top of everything else
+#[derive(Default)]
+pub struct Notifications {
+    toasts: Vec<Toast>,
+}
+
+impl Notifications {
+    /// Shows `message` as a toast and records it in the session log
+    pub fn push(&mut self, level: ToastLevel, message: impl Into<String>) {
+        let message = message.into();
+        match level {
+            ToastLevel::Error => log::warn!("{message}"),
+            ToastLevel::Info | ToastLevel::Success => log::info!("{message}"),
+        }
+        self.toasts.push(Toast {
+            level,
+            message,
+            shown_at: None,
+        });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Info, message)
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Success, message)
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Error, message)
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        self.toasts
+            .retain(|toast| toast.shown_at.map(|shown_at| now - shown_at < TOAST_DURATION).unwrap_or(true));
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        let mut dismissed: Option<usize> = None;
+        egui::Area::new("Notifications")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -40.0))
+            .order(egui::Order::Foreground)
+            .interactable(true)
+            .show(ctx, |ui| {
+                for (idx, toast) in self.toasts.iter_mut().enumerate() {
+                    let shown_at = *toast.shown_at.get_or_insert(now);
+                    let color = match toast.level {
+                        ToastLevel::Info => ui.visuals().text_color(),
+                        ToastLevel::Success => egui::Color32::from_rgb(0x4c, 0xaf, 0x50),
+                        ToastLevel::Error => ui.visuals().error_fg_color,
+                    };
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(&toast.message).color(color));
+                            if ui.small_button("🗙").on_hover_text(tr!("dismiss")).clicked() {
+                                dismissed = Some(idx);
+                            }
+                        });
+                    });
+                    ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+                        (shown_at + TOAST_DURATION - now).max(0.0),
+                    ));
+                }
+            });
+        if let Some(idx) = dismissed {
+            self.toasts.remove(idx);
+        }
+    }
+}
+
+/// Summarizes the running background tasks, with buttons that toggle the task list and the log
+pub fn show_status_bar(ctx: &egui::Context, show_tasks: &mut bool, show_log: &mut bool) {
+    let tasks = running_tasks();
+    egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.toggle_value(show_log, tr!("session-log"));
+            ui.toggle_value(show_tasks, tr!("tasks-button", count = tasks.len()));
+            if tasks.is_empty() {
+                ui.weak(tr!("status-idle"));
+                return;
+            }
+            ui.spinner();
+            let descriptions: Vec<&str> = tasks.iter().map(|task| task.description()).collect();
+            ui.label(descriptions.join(", "));
+        });
+    });
+}
@@ -0,0 +1,92 @@
+//! Keeping size references and processing steps pointed at the tensors and axes they refer to while their ids are
+//! edited. Like the cross checks, this runs before every frame, following every id that changed since the last one.
+
+use bioimg_spec::rdf::model::{axes::AxisId, references::IdRename, tensor_id::TensorId};
+
+use crate::widgets::input_tensor_widget::InputTensorWidget;
+use crate::widgets::output_tensor_widget::OutputTensorWidget;
+use crate::widgets::StatefulWidget;
+
+/// The id of a tensor or axis as of when the references to it were last updated
+pub struct CommittedId<T>(Option<T>);
+
+impl<T> Default for CommittedId<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T: Clone + PartialEq> CommittedId<T> {
+    /// Takes on `current`, the id as typed in right now, unless it doesn't parse or is the id of one of the other
+    /// `siblings` as well. Returns the previous id if references to it should follow, which is unless another sibling
+    /// still has it, e.g. because this one had been typed in as a duplicate of it.
+    fn update(&mut self, current: Option<T>, siblings: &[Option<T>]) -> Option<(T, T)> {
+        let current = current?;
+        let count = |id: &T| siblings.iter().filter(|sibling| sibling.as_ref() == Some(id)).count();
+        if count(&current) > 1 {
+            return None;
+        }
+        let previous = self.0.replace(current.clone())?;
+        (previous != current && count(&previous) == 0).then_some((previous, current))
+    }
+}
+
+/// The id an axis would parse to
+fn parsed_axis_id(raw: &str) -> Option<AxisId> {
+    AxisId::try_from(raw.to_owned()).ok()
+}
+
+/// Renames whatever refers to a tensor or axis whose id was changed in one of `inputs` or `outputs`
+pub fn propagate_renames(inputs: &mut [InputTensorWidget], outputs: &mut [OutputTensorWidget]) {
+    let tensor_ids: Vec<Option<TensorId>> = inputs
+        .iter()
+        .map(|input| input.staging_id.state().ok())
+        .chain(outputs.iter().map(|output| output.staging_id.state().ok()))
+        .collect();
+    let (input_ids, output_ids) = tensor_ids.split_at(inputs.len());
+
+    let mut renames = vec![];
+    for (input, tensor_id) in inputs.iter_mut().zip(input_ids) {
+        let renamed = input.committed_id.update(tensor_id.clone(), &tensor_ids);
+        renames.extend(renamed.map(|(from, to)| IdRename::Tensor { from, to }));
+        let Some(tensor_id) = tensor_id else {
+            continue;
+        };
+        let axis_ids: Vec<_> = input.staging_axes.staging.iter().map(|axis| parsed_axis_id(axis.raw_id())).collect();
+        for (axis, axis_id) in input.staging_axes.staging.iter_mut().zip(&axis_ids) {
+            let renamed = axis.committed_id.update(axis_id.clone(), &axis_ids);
+            renames.extend(renamed.map(|(from, to)| IdRename::Axis { tensor_id: tensor_id.clone(), from, to }));
+        }
+    }
+    for (output, tensor_id) in outputs.iter_mut().zip(output_ids) {
+        let renamed = output.committed_id.update(tensor_id.clone(), &tensor_ids);
+        renames.extend(renamed.map(|(from, to)| IdRename::Tensor { from, to }));
+        let Some(tensor_id) = tensor_id else {
+            continue;
+        };
+        let axis_ids: Vec<_> = output.staging_axes.staging.iter().map(|axis| parsed_axis_id(axis.raw_id())).collect();
+        for (axis, axis_id) in output.staging_axes.staging.iter_mut().zip(&axis_ids) {
+            let renamed = axis.committed_id.update(axis_id.clone(), &axis_ids);
+            renames.extend(renamed.map(|(from, to)| IdRename::Axis { tensor_id: tensor_id.clone(), from, to }));
+        }
+    }
+
+    for rename in &renames {
+        for (input, tensor_id) in inputs.iter_mut().zip(input_ids) {
+            for reference in input.staging_axes.staging.iter_mut().filter_map(|axis| axis.size_reference_mut()) {
+                reference.apply_rename(rename);
+            }
+            if let Some(tensor_id) = tensor_id {
+                input.staging_preprocessing.apply_rename(tensor_id, rename);
+            }
+        }
+        for (output, tensor_id) in outputs.iter_mut().zip(output_ids) {
+            for reference in output.staging_axes.staging.iter_mut().filter_map(|axis| axis.size_reference_mut()) {
+                reference.apply_rename(rename);
+            }
+            if let Some(tensor_id) = tensor_id {
+                output.staging_postprocessing.apply_rename(tensor_id, rename);
+            }
+        }
+    }
+}
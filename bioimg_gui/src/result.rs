@@ -1,27 +1,89 @@
 use std::{fmt::Display, sync::Arc};
 
+use crate::i18n::localized_error;
+
 pub type Result<T, E = GuiError> = std::result::Result<T, E>;
 
+/// An error as the GUI shows it: a message in the current language, with where in the form it comes from and
+/// what caused it
 #[derive(Debug, Clone)]
-pub struct GuiError(Arc<str>);
+pub struct GuiError {
+    message: Arc<str>,
+    /// Stays the same whatever the language, e.g. to tell errors apart in logs. Errors converted from other errors
+    /// take the name of their type.
+    code: Option<&'static str>,
+    /// The labels of the fields the error is in, the outermost first
+    field: Vec<Arc<str>>,
+    source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+}
 
 impl Display for GuiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
+        self.message.fmt(f)
     }
 }
 
 impl<E> From<E> for GuiError
 where
-    E: std::error::Error,
+    E: std::error::Error + Send + Sync + 'static,
 {
     fn from(error: E) -> Self {
-        Self::new(error.to_string())
+        let type_name = std::any::type_name::<E>();
+        let code = type_name.split('<').next().unwrap_or(type_name).rsplit("::").next().unwrap_or(type_name);
+        let mut gui_error = Self::localized(&error).with_code(code);
+        gui_error.source = Some(Arc::new(error));
+        gui_error
     }
 }
 
 impl GuiError {
     pub fn new(message: String) -> Self {
-        return Self(Arc::from(message));
+        Self {
+            message: Arc::from(message),
+            code: None,
+            field: vec![],
+            source: None,
+        }
+    }
+
+    /// `error` in the current language, if it is one of the spec's parsing errors that have translations
+    pub fn localized<E: Display + 'static>(error: &E) -> Self {
+        Self::new(localized_error(error))
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// The error as coming from the field labeled `label`, inside whatever field it was already in
+    pub fn within(mut self, label: impl Into<Arc<str>>) -> Self {
+        self.field.insert(0, label.into());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn code(&self) -> Option<&'static str> {
+        self.code
+    }
+
+    pub fn field(&self) -> &[Arc<str>] {
+        &self.field
+    }
+
+    /// The error this one was converted from, whose own sources can be followed from there
+    pub fn source(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
+        self.source.as_deref()
+    }
+
+    /// The message, preceded by the fields it is in, e.g. `Author 2 › Name: ...`
+    pub fn with_field_path(&self) -> String {
+        if self.field.is_empty() {
+            return self.message.to_string();
+        }
+        format!("{}: {}", self.field.join(" › "), self.message)
     }
 }
@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::i18n::tr;
+
+/// How many records the session log keeps before dropping the oldest ones
+const CAPACITY: usize = 1000;
+
+/// Records below this level are only passed on to the wrapped logger
+const CAPTURED_LEVEL: log::LevelFilter = log::LevelFilter::Info;
+
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub level: log::Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:<5} [{}] {}", self.time.format("%H:%M:%S%.3f"), self.level, self.target, self.message)
+    }
+}
+
+static ENTRIES: Mutex<VecDeque<LogEntry>> = const_mutex(VecDeque::new());
+
+/// Keeps the most recent log records in memory so they can be shown and copied from within the
+/// app, passing every record on to the logger it wraps
+pub struct SessionLogger {
+    inner: Box<dyn log::Log>,
+    inner_level: log::LevelFilter,
+}
+
+impl SessionLogger {
+    /// Installs the session log as the global logger, in front of `inner`
+    pub fn install(inner: Box<dyn log::Log>, inner_level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+        log::set_max_level(inner_level.max(CAPTURED_LEVEL));
+        log::set_boxed_logger(Box::new(Self { inner, inner_level }))
+    }
+}
+
+impl log::Log for SessionLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.level() <= CAPTURED_LEVEL || (metadata.level() <= self.inner_level && self.inner.enabled(metadata))
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if record.level() <= CAPTURED_LEVEL {
+            let mut entries = ENTRIES.lock();
+            if entries.len() == CAPACITY {
+                entries.pop_front();
+            }
+            entries.push_back(LogEntry {
+                time: chrono::Local::now(),
+                level: record.level(),
+                target: record.target().to_owned(),
+                message: record.args().to_string(),
+            });
+        }
+        if record.level() <= self.inner_level && self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
+
+/// A report to paste into bug reports: versions, platform and the whole session log
+pub fn diagnostics() -> String {
+    let mut out = format!(
+        "{} {}\nos: {} ({})\n\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+    for entry in ENTRIES.lock().iter() {
+        out += &entry.to_string();
+        out.push('\n');
+    }
+    out
+}
+
+/// Lists the captured log records, most recent last
+#[derive(Default)]
+pub struct SessionLogViewer {
+    warnings_only: bool,
+}
+
+impl SessionLogViewer {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.warnings_only, tr!("warnings-only"));
+            if ui.button(tr!("copy-diagnostics")).clicked() {
+                ui.output_mut(|o| o.copied_text = diagnostics());
+            }
+            if ui.button(tr!("clear")).clicked() {
+                ENTRIES.lock().clear();
+            }
+        });
+        ui.separator();
+        let entries: Vec<LogEntry> = ENTRIES
+            .lock()
+            .iter()
+            .filter(|entry| !self.warnings_only || entry.level <= log::Level::Warn)
+            .cloned()
+            .collect();
+        if entries.is_empty() {
+            ui.weak(tr!("log-is-empty"));
+            return;
+        }
+        egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+            for entry in entries {
+                let text = egui::RichText::new(entry.to_string()).monospace();
+                ui.label(match entry.level {
+                    log::Level::Error => text.color(ui.visuals().error_fg_color),
+                    log::Level::Warn => text.color(ui.visuals().warn_fg_color),
+                    _ => text,
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,397 @@
+use std::path::{Path, PathBuf};
+
+use bioimg_spec::rdf::SpdxLicense;
+use bioimg_spec::runtime::network::{self, parse_tokens, NetworkConfig, NetworkConfigError};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{self, tr, Language};
+
+/// Size of text in egui's default style, which `font_size` is relative to
+const DEFAULT_BODY_FONT_SIZE: f32 = 12.5;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ThemePreference {
+    #[default]
+    FollowSystem,
+    Dark,
+    Light,
+}
+
+impl ThemePreference {
+    /// The theme to use, given the one the OS reports (if it reports any)
+    pub fn resolve(self, system_theme: Option<eframe::Theme>) -> eframe::Theme {
+        match self {
+            Self::FollowSystem => system_theme.unwrap_or(eframe::Theme::Dark),
+            Self::Dark => eframe::Theme::Dark,
+            Self::Light => eframe::Theme::Light,
+        }
+    }
+}
+
+/// Look and sizing of the whole interface, persisted between runs
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub struct AppearanceSettings {
+    /// Multiplies the pixels per point reported by the OS
+    pub ui_scale: f32,
+    /// Size of body text; all other text styles are scaled along with it
+    pub font_size: f32,
+    #[serde(default)]
+    pub theme: ThemePreference,
+    #[serde(default)]
+    pub language: Language,
+}
+
+impl Default for AppearanceSettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            font_size: DEFAULT_BODY_FONT_SIZE,
+            theme: ThemePreference::default(),
+            language: Language::default(),
+        }
+    }
+}
+
+impl AppearanceSettings {
+    pub const STORAGE_KEY: &'static str = "appearance";
+
+    pub fn apply(&self, ctx: &egui::Context, system_theme: Option<eframe::Theme>) {
+        i18n::set_language(self.language);
+        ctx.set_zoom_factor(self.ui_scale);
+        let ratio = self.font_size / DEFAULT_BODY_FONT_SIZE;
+        let mut style = (*ctx.style()).clone();
+        style.visuals = self.theme.resolve(system_theme).egui_visuals();
+        style.text_styles = egui::style::default_text_styles()
+            .into_iter()
+            .map(|(text_style, mut font_id)| {
+                font_id.size *= ratio;
+                (text_style, font_id)
+            })
+            .collect();
+        ctx.set_style(style);
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            ui.strong(tr!("ui-scale-label"));
+            ui.add(egui::Slider::new(&mut self.ui_scale, 0.5..=3.0).step_by(0.05));
+            ui.end_row();
+
+            ui.strong(tr!("font-size-label"));
+            ui.add(egui::Slider::new(&mut self.font_size, 8.0..=32.0).step_by(0.5));
+            ui.end_row();
+
+            ui.strong(tr!("theme-label"));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.theme, ThemePreference::FollowSystem, tr!("follow-system"));
+                ui.radio_value(&mut self.theme, ThemePreference::Dark, tr!("dark"));
+                ui.radio_value(&mut self.theme, ThemePreference::Light, tr!("light"));
+            });
+            ui.end_row();
+
+            ui.strong(tr!("language-label"));
+            ui.horizontal(|ui| {
+                for language in <Language as strum::VariantArray>::VARIANTS {
+                    ui.radio_value(&mut self.language, *language, language.native_name());
+                }
+            });
+            ui.end_row();
+        });
+        if ui.button(tr!("reset")).clicked() {
+            *self = Self::default();
+        }
+    }
+}
+
+/// How editing behaves, persisted between runs
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct EditorSettings {
+    /// How many edits can be undone
+    pub undo_depth: usize,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self { undo_depth: 100 }
+    }
+}
+
+impl EditorSettings {
+    pub const STORAGE_KEY: &'static str = "editor";
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            ui.strong(tr!("undo-depth-label"));
+            ui.add(egui::Slider::new(&mut self.undo_depth, 1..=1000).logarithmic(true));
+            ui.end_row();
+        });
+    }
+}
+
+/// Where file dialogs start out, persisted between runs
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(default)]
+pub struct FileDialogSettings {
+    /// Where packages, drafts and collections are saved to, as typed in. Empty to start where the last file was saved.
+    pub default_save_dir: String,
+    /// The directory of the file that was last opened
+    pub last_open_dir: Option<PathBuf>,
+    /// The directory of the file that was last saved
+    pub last_save_dir: Option<PathBuf>,
+}
+
+impl FileDialogSettings {
+    pub const STORAGE_KEY: &'static str = "file_dialogs";
+
+    /// A dialog for picking a file to open, starting where the last one was picked
+    pub fn open_dialog(&self) -> rfd::FileDialog {
+        start_in(rfd::FileDialog::new(), self.last_open_dir.as_deref())
+    }
+
+    /// A dialog for picking where to save, starting in the default directory if there is one
+    pub fn save_dialog(&self) -> rfd::FileDialog {
+        let default_dir = Some(Path::new(self.default_save_dir.trim())).filter(|dir| !dir.as_os_str().is_empty());
+        start_in(rfd::FileDialog::new(), default_dir.or(self.last_save_dir.as_deref()))
+    }
+
+    pub fn remember_opened(&mut self, path: &Path) {
+        self.last_open_dir = path.parent().map(Path::to_owned);
+    }
+
+    pub fn remember_saved(&mut self, path: &Path) {
+        self.last_save_dir = path.parent().map(Path::to_owned);
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            ui.strong(tr!("default-save-dir-label"));
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.default_save_dir).hint_text(tr!("default-save-dir-hint")));
+                if ui.button(tr!("browse-dir")).clicked() {
+                    if let Some(dir) = start_in(rfd::FileDialog::new(), self.last_save_dir.as_deref()).pick_folder() {
+                        self.default_save_dir = dir.to_string_lossy().into_owned();
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.strong(tr!("last-used-dirs-label"));
+            ui.horizontal(|ui| {
+                let dirs = [&self.last_open_dir, &self.last_save_dir];
+                let shown: Vec<String> = dirs.into_iter().flatten().map(|dir| dir.to_string_lossy().into_owned()).collect();
+                match shown.as_slice() {
+                    [] => ui.weak(tr!("none")),
+                    dirs => ui.label(dirs.join(", ")),
+                };
+                let forget = ui.add_enabled(!shown.is_empty(), egui::Button::new(tr!("forget-dirs")));
+                if forget.clicked() {
+                    self.last_open_dir = None;
+                    self.last_save_dir = None;
+                }
+            });
+            ui.end_row();
+        });
+    }
+}
+
+/// Proxy, certificates and tokens for every request the app makes, persisted between runs except for the tokens
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// As typed in; empty to use the one from the environment
+    pub proxy: String,
+    /// Path to a PEM file, as typed in
+    pub ca_bundle: String,
+    /// `host=token` pairs separated by commas. Never saved, like the api token for uploads.
+    #[serde(skip)]
+    pub tokens: String,
+    /// Why the settings could not be applied the last time
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl NetworkSettings {
+    pub const STORAGE_KEY: &'static str = "network";
+
+    /// Makes every request from now on use these settings, in place of the `BIOIMG_*` ones in the environment
+    pub fn apply(&mut self) -> Result<(), NetworkConfigError> {
+        let result = self.config().and_then(|config| network::configure(&config));
+        self.error = result.as_ref().err().map(ToString::to_string);
+        result
+    }
+
+    fn config(&self) -> Result<NetworkConfig, NetworkConfigError> {
+        let typed = |value: &str| Some(value.trim()).filter(|value| !value.is_empty()).map(str::to_owned);
+        let config = NetworkConfig {
+            proxy: typed(&self.proxy),
+            ca_bundle: typed(&self.ca_bundle).map(PathBuf::from),
+            bearer_tokens: parse_tokens(&self.tokens)?,
+        };
+        Ok(NetworkConfig::from_env()?.overridden_by(config))
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            ui.strong(tr!("proxy-label"));
+            ui.add(egui::TextEdit::singleline(&mut self.proxy).hint_text(tr!("proxy-hint")));
+            ui.end_row();
+
+            ui.strong(tr!("ca-bundle-label"));
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.ca_bundle).hint_text(tr!("ca-bundle-hint")));
+                if ui.button(tr!("browse-dir")).clicked() {
+                    if let Some(path) = rfd::FileDialog::new().add_filter("PEM", &["pem", "crt", "cer"]).pick_file() {
+                        self.ca_bundle = path.to_string_lossy().into_owned();
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.strong(tr!("bearer-tokens-label")).on_hover_text(tr!("bearer-tokens-help"));
+            ui.add(egui::TextEdit::singleline(&mut self.tokens).password(true).hint_text(tr!("bearer-tokens-hint")));
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            if ui.button(tr!("apply-network")).clicked() {
+                self.apply().ok();
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, tr!("network-settings-failed", error = error.clone()));
+            }
+        });
+    }
+}
+
+/// Opens `dialog` in `dir`, unless it no longer exists
+fn start_in(dialog: rfd::FileDialog, dir: Option<&Path>) -> rfd::FileDialog {
+    match dir.filter(|dir| dir.is_dir()) {
+        Some(dir) => dialog.set_directory(dir),
+        None => dialog,
+    }
+}
+
+/// What a recently used file holds, which decides how it is opened again
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecentKind {
+    Draft,
+    Model,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct RecentFile {
+    pub kind: RecentKind,
+    pub path: PathBuf,
+}
+
+/// Drafts and model packages that were recently opened or saved, most recent first, persisted between runs
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RecentFiles {
+    files: Vec<RecentFile>,
+}
+
+impl RecentFiles {
+    pub const STORAGE_KEY: &'static str = "recent_files";
+    /// How many files are remembered; older ones are forgotten
+    const MAX_FILES: usize = 10;
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Moves `path` to the front, adding it if it wasn't there yet
+    pub fn add(&mut self, kind: RecentKind, path: &Path) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        self.files.retain(|file| file.path != path);
+        self.files.insert(0, RecentFile { kind, path });
+        self.files.truncate(Self::MAX_FILES);
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    /// Lists the files as buttons, returning the one that was clicked. Files that are gone can't be clicked.
+    pub fn show(&self, ui: &mut egui::Ui) -> Option<RecentFile> {
+        if self.files.is_empty() {
+            ui.weak(tr!("no-recent-files"));
+            return None;
+        }
+        let mut picked = None;
+        for file in &self.files {
+            let kind = match file.kind {
+                RecentKind::Draft => tr!("recent-draft"),
+                RecentKind::Model => tr!("recent-model"),
+            };
+            let name = file.path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+            let button = egui::Button::new(format!("{name} ({kind})")).wrap(false);
+            let response = ui.add_enabled(file.path.exists(), button);
+            let response = response
+                .on_hover_text(file.path.to_string_lossy())
+                .on_disabled_hover_text(tr!("recent-file-missing", path = file.path.to_string_lossy().into_owned()));
+            if response.clicked() {
+                picked = Some(file.clone());
+            }
+        }
+        picked
+    }
+}
+
+/// Details of whoever uses the app, pre-filled into every new resource
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+#[serde(default)]
+pub struct AuthorProfile {
+    pub name: String,
+    pub orcid: String,
+    pub github_user: String,
+    pub affiliation: String,
+    pub default_license: Option<SpdxLicense>,
+    /// Comma separated, as typed in
+    pub default_tags: String,
+}
+
+impl AuthorProfile {
+    pub const STORAGE_KEY: &'static str = "author_profile";
+
+    pub fn default_tags(&self) -> impl Iterator<Item = &str> {
+        self.default_tags.split(',').map(str::trim).filter(|tag| !tag.is_empty())
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            let fields = [
+                (tr!("name-label"), &mut self.name),
+                (tr!("orcid-label"), &mut self.orcid),
+                (tr!("github-user-label"), &mut self.github_user),
+                (tr!("affiliation-label"), &mut self.affiliation),
+                (tr!("default-tags-label"), &mut self.default_tags),
+            ];
+            for (label, value) in fields {
+                ui.strong(label);
+                ui.text_edit_singleline(value);
+                ui.end_row();
+            }
+
+            ui.strong(tr!("default-license-label"));
+            egui::ComboBox::from_id_source(id.with("Default License"))
+                .selected_text(match &self.default_license {
+                    Some(license) => license.to_string(),
+                    None => tr!("none"),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.default_license, None, tr!("none"));
+                    for license in SpdxLicense::COMMON {
+                        ui.selectable_value(&mut self.default_license, Some(license), license.to_string());
+                    }
+                    ui.separator();
+                    for license in <SpdxLicense as strum::VariantArray>::VARIANTS {
+                        if !SpdxLicense::COMMON.contains(license) {
+                            ui.selectable_value(&mut self.default_license, Some(*license), license.to_string());
+                        }
+                    }
+                });
+            ui.end_row();
+        });
+    }
+}
@@ -1,12 +1,155 @@
-// use std::{time::Instant, sync::Mutex};
+use std::{
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
 
+use parking_lot::{const_mutex, Mutex};
 
-pub fn run_task(target: impl FnOnce() + 'static + Send){
-    #[cfg(not(target_arch = "wasm32"))]
-    std::thread::Builder::new()
-        .name("model_builder_background_task".into())
-        .spawn(target)
-        .expect("Could not spawn a thread");
+use crate::i18n::tr;
+
+#[derive(thiserror::Error, Debug)]
+pub enum TaskError {
+    #[error("Task was cancelled")]
+    Cancelled,
+    #[error("Task panicked")]
+    Panicked,
+}
+
+/// The part of a task that is shared between its thread, its owner and the task list
+pub struct TaskStatus {
+    description: String,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    /// Bits of an f32 in [0, 1], or NaN if the task doesn't report progress
+    progress: AtomicU32,
+}
+
+impl TaskStatus {
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Asks the task to stop. Long running jobs should check [TaskStatus::is_cancelled]
+    /// every now and then; whatever they produce after being cancelled is discarded.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_progress(&self, fraction: f32) {
+        self.progress.store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed)
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        Some(f32::from_bits(self.progress.load(Ordering::Relaxed))).filter(|progress| !progress.is_nan())
+    }
+
+    /// Sets the progress, and tells the job to stop if it was cancelled. Fits the `on_progress` callbacks of the spec.
+    pub fn report(&self, fraction: f32) -> ControlFlow<()> {
+        self.set_progress(fraction);
+        match self.is_cancelled() {
+            true => ControlFlow::Break(()),
+            false => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Every task that hasn't finished yet, in the order they were spawned
+static RUNNING_TASKS: Mutex<Vec<Arc<TaskStatus>>> = const_mutex(Vec::new());
+
+pub fn running_tasks() -> Vec<Arc<TaskStatus>> {
+    let mut tasks = RUNNING_TASKS.lock();
+    tasks.retain(|task| !task.finished.load(Ordering::Relaxed));
+    tasks.clone()
+}
+
+pub struct Task<T> {
+    status: Arc<TaskStatus>,
+    handle: JoinHandle<T>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    /// Runs `job` on a background thread, listing it in the task list until it finishes.
+    /// `ctx` is repainted once the job is done so the owner can pick up the result.
+    pub fn spawn(
+        description: impl Into<String>,
+        ctx: egui::Context,
+        job: impl FnOnce(&TaskStatus) -> T + Send + 'static,
+    ) -> Self {
+        let status = Arc::new(TaskStatus {
+            description: description.into(),
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            progress: AtomicU32::new(f32::NAN.to_bits()),
+        });
+        RUNNING_TASKS.lock().push(Arc::clone(&status));
+        let thread_status = Arc::clone(&status);
+        let handle = std::thread::Builder::new()
+            .name("model_builder_background_task".into())
+            .spawn(move || {
+                let out = job(&thread_status);
+                thread_status.finished.store(true, Ordering::Relaxed);
+                ctx.request_repaint();
+                out
+            })
+            .expect("Could not spawn a thread");
+        Self { status, handle }
+    }
+}
+
+impl<T> Task<T> {
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    pub fn status(&self) -> &TaskStatus {
+        &self.status
+    }
+
+    /// Like [TaskStatus::cancel], for owners that give up on a task without waiting for it
+    pub fn cancel(&self) {
+        self.status.cancel()
+    }
+
+    /// Waits for the task, which should only be done once [Task::is_finished] is true
+    pub fn join(self) -> Result<T, TaskError> {
+        let out = self.handle.join().map_err(|_| TaskError::Panicked)?;
+        if self.status.is_cancelled() {
+            return Err(TaskError::Cancelled);
+        }
+        Ok(out)
+    }
+}
+
+/// Lists the running tasks with their progress and a button to cancel each of them
+pub fn show_task_list(ui: &mut egui::Ui) {
+    let tasks = running_tasks();
+    if tasks.is_empty() {
+        ui.weak(tr!("no-running-tasks"));
+        return;
+    }
+    egui::Grid::new("Task List").num_columns(3).show(ui, |ui| {
+        for task in tasks {
+            ui.label(task.description());
+            match task.progress() {
+                Some(progress) => ui.add(egui::ProgressBar::new(progress).show_percentage()),
+                None => ui.spinner(),
+            };
+            if task.is_cancelled() {
+                ui.weak(tr!("cancelling"));
+            } else if ui.button(tr!("cancel")).clicked() {
+                task.cancel();
+            }
+            ui.end_row();
+        }
+    });
 }
 
 // pub struct GenerationalMutex<T>(Mutex<(T, Instant)>);
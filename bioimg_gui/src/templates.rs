@@ -0,0 +1,69 @@
+//! Starting points for common kinds of models, so that a new model doesn't start out as an empty form.
+//! Each template is a yaml file in `templates/` with the fields it fills in.
+
+use bioimg_spec::rdf::model::{InputTensorDescr2, OutputTensorDescr};
+
+use crate::i18n::tr;
+
+/// The kinds of models a new model can be started from
+#[derive(PartialEq, Eq, Copy, Clone, strum::VariantArray)]
+pub enum ModelTemplate {
+    Segmentation2d,
+    Denoising3d,
+    Classification2d,
+}
+
+/// What a template fills in. Test tensors are only placeholders, so their file widgets start out empty.
+#[derive(serde::Deserialize)]
+pub struct TemplateContents {
+    pub tags: Vec<String>,
+    pub inputs: Vec<InputTensorDescr2>,
+    pub outputs: Vec<OutputTensorDescr>,
+    /// Markdown with a heading for everything the documentation should cover
+    pub documentation: String,
+}
+
+impl ModelTemplate {
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Self::Segmentation2d => "template-segmentation-2d",
+            Self::Denoising3d => "template-denoising-3d",
+            Self::Classification2d => "template-classification-2d",
+        }
+    }
+
+    pub fn hint_id(self) -> &'static str {
+        match self {
+            Self::Segmentation2d => "template-segmentation-2d-hint",
+            Self::Denoising3d => "template-denoising-3d-hint",
+            Self::Classification2d => "template-classification-2d-hint",
+        }
+    }
+
+    fn yaml(self) -> &'static str {
+        match self {
+            Self::Segmentation2d => include_str!("../templates/segmentation_2d.yaml"),
+            Self::Denoising3d => include_str!("../templates/denoising_3d.yaml"),
+            Self::Classification2d => include_str!("../templates/classification_2d.yaml"),
+        }
+    }
+
+    pub fn contents(self) -> TemplateContents {
+        // the templates are part of the binary, so one that doesn't parse is a bug rather than a user error
+        serde_yaml::from_str(self.yaml()).expect("templates should be valid")
+    }
+}
+
+/// A "New from template" menu, returning the template that was picked
+pub fn show_template_menu(ui: &mut egui::Ui) -> Option<ModelTemplate> {
+    let mut picked = None;
+    ui.menu_button(tr!("new-from-template"), |ui| {
+        for template in <ModelTemplate as strum::VariantArray>::VARIANTS {
+            if ui.button(tr!(template.label_id())).on_hover_text(tr!(template.hint_id())).clicked() {
+                picked = Some(*template);
+                ui.close_menu();
+            }
+        }
+    });
+    picked
+}
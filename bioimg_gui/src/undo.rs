@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+
+/// Edits made within this many seconds of the first one become a single undo step, so that
+/// typing a word is undone as a whole rather than letter by letter
+const SETTLE_SECONDS: f64 = 1.0;
+
+/// Snapshots of the resource being edited, as dumped by [crate::widgets::Restore], taken whenever it changes
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: VecDeque<serde_yaml::Value>,
+    redo: Vec<serde_yaml::Value>,
+    /// The newest snapshot in the history, which the resource is at unless it was edited since
+    committed: Option<serde_yaml::Value>,
+    /// When the resource first differed from `committed`
+    changed_at: Option<f64>,
+}
+
+impl UndoHistory {
+    /// Compares the resource with the last snapshot, recording an undo step once an edit has
+    /// settled. Keeps at most `depth` steps.
+    pub fn record(&mut self, ctx: &egui::Context, snapshot: serde_yaml::Value, depth: usize) {
+        let Some(committed) = &self.committed else {
+            self.committed = Some(snapshot);
+            return;
+        };
+        if *committed == snapshot {
+            self.changed_at = None;
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        let changed_at = *self.changed_at.get_or_insert(now);
+        let settling = SETTLE_SECONDS - (now - changed_at);
+        if settling > 0.0 {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(settling));
+            return;
+        }
+        self.commit(snapshot, depth);
+    }
+
+    fn commit(&mut self, snapshot: serde_yaml::Value, depth: usize) {
+        if let Some(previous) = self.committed.replace(snapshot) {
+            self.undo.push_back(previous);
+        }
+        while self.undo.len() > depth {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+        self.changed_at = None;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.changed_at.is_some() || !self.undo.is_empty()
+    }
+
+    /// Edits that weren't recorded yet replace whatever was undone, like recorded ones do
+    pub fn can_redo(&self) -> bool {
+        self.changed_at.is_none() && !self.redo.is_empty()
+    }
+
+    /// The snapshot to go back to from `current`: the last one taken if `current` has edits that weren't
+    /// recorded yet, and the one before otherwise
+    pub fn undo(&mut self, current: serde_yaml::Value) -> Option<serde_yaml::Value> {
+        if self.changed_at.take().is_some() {
+            self.redo.push(current);
+            return self.committed.clone();
+        }
+        let previous = self.undo.pop_back()?;
+        self.redo.extend(self.committed.replace(previous.clone()));
+        Some(previous)
+    }
+
+    /// The snapshot that was last undone
+    pub fn redo(&mut self) -> Option<serde_yaml::Value> {
+        if !self.can_redo() {
+            return None;
+        }
+        let next = self.redo.pop()?;
+        self.undo.extend(self.committed.replace(next.clone()));
+        Some(next)
+    }
+
+    /// Takes `snapshot` as what the resource is at now, e.g. after restoring to a snapshot changed
+    /// how some widget dumps itself
+    pub fn rebase(&mut self, snapshot: serde_yaml::Value) {
+        self.committed = Some(snapshot);
+        self.changed_at = None;
+    }
+}
@@ -0,0 +1,123 @@
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+
+use bioimg_spec::runtime::upload::{UploadTarget, Uploaded, Uploader};
+
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::Task;
+
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+enum TargetKind {
+    #[default]
+    BioimageIo,
+    Zenodo,
+    ZenodoSandbox,
+}
+
+/// Picks a packaged model and uploads it in the background, listing the upload in the task list
+#[derive(Default)]
+pub struct UploadDialog {
+    zip_path: Option<PathBuf>,
+    target: TargetKind,
+    endpoint: String,
+    /// Only kept for this session, so it never ends up in the app's storage
+    token: String,
+    pending: Option<Task<Result<Uploaded>>>,
+}
+
+impl UploadDialog {
+    pub fn is_uploading(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn target(&self) -> Result<UploadTarget> {
+        Ok(match self.target {
+            TargetKind::BioimageIo => UploadTarget::BioimageIo {
+                endpoint: url::Url::parse(self.endpoint.trim())?,
+            },
+            TargetKind::Zenodo => UploadTarget::zenodo(),
+            TargetKind::ZenodoSandbox => UploadTarget::zenodo_sandbox(),
+        })
+    }
+
+    fn start_upload(&mut self, ctx: &egui::Context) -> Result<()> {
+        let Some(zip_path) = self.zip_path.clone() else {
+            return Err(GuiError::new(tr!("no-file-selected")));
+        };
+        let target = self.target()?;
+        let uploader = Uploader::new(self.token.trim());
+        let description = tr!("uploading", path = zip_path.to_string_lossy().into_owned());
+        self.pending = Some(Task::spawn(description, ctx.clone(), move |status| {
+            let uploaded = uploader.upload(&target, &zip_path, &mut |progress| {
+                status.set_progress(progress.fraction());
+                match status.is_cancelled() {
+                    true => ControlFlow::Break(()),
+                    false => ControlFlow::Continue(()),
+                }
+            })?;
+            Ok(uploaded)
+        }));
+        Ok(())
+    }
+
+    /// The outcome of the upload, once it is done
+    pub fn finished_upload(&mut self) -> Option<Result<Uploaded>> {
+        if !self.pending.as_ref().is_some_and(|task| task.is_finished()) {
+            return None;
+        }
+        let task = self.pending.take().expect("checked above");
+        Some(task.join().map_err(GuiError::from).and_then(|result| result))
+    }
+
+    /// Returns an error if the upload couldn't be started
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Result<()> {
+        let mut start = false;
+        ui.add_enabled_ui(!self.is_uploading(), |ui| {
+            egui::Grid::new("Upload Dialog").num_columns(2).show(ui, |ui| {
+                ui.strong(tr!("package-label"));
+                ui.horizontal(|ui| {
+                    match &self.zip_path {
+                        Some(path) => ui.label(path.to_string_lossy()),
+                        None => ui.weak(tr!("no-file-selected")),
+                    };
+                    if ui.button(tr!("open")).clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter(tr!("packages"), &["zip"]).pick_file() {
+                            self.zip_path = Some(path);
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.strong(tr!("upload-target-label"));
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.target, TargetKind::BioimageIo, "bioimage.io");
+                    ui.selectable_value(&mut self.target, TargetKind::Zenodo, "Zenodo");
+                    ui.selectable_value(&mut self.target, TargetKind::ZenodoSandbox, tr!("zenodo-sandbox"));
+                });
+                ui.end_row();
+
+                if self.target == TargetKind::BioimageIo {
+                    ui.strong(tr!("upload-endpoint-label"));
+                    ui.text_edit_singleline(&mut self.endpoint);
+                    ui.end_row();
+                }
+
+                ui.strong(tr!("api-token-label"));
+                ui.add(egui::TextEdit::singleline(&mut self.token).password(true));
+                ui.end_row();
+            });
+            start = ui.button(tr!("upload")).clicked();
+        });
+        if self.is_uploading() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.weak(tr!("upload-in-progress"));
+            });
+        }
+        match start {
+            true => self.start_upload(ui.ctx()),
+            false => Ok(()),
+        }
+    }
+}
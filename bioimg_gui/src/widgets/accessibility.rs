@@ -0,0 +1,120 @@
+use std::{fmt::Display, sync::Arc};
+
+use bioimg_spec::rdf::field_help::{field_help, Constraint, FieldHelp};
+
+use super::field_search;
+use crate::i18n::tr;
+
+/// The label drawn by [field_label], waiting for the next form field to claim it
+#[derive(Clone)]
+struct PendingLabel {
+    id: egui::Id,
+    text: Arc<str>,
+    required: bool,
+}
+
+fn pending_label_id() -> egui::Id {
+    egui::Id::new("bioimg_gui::pending_field_label")
+}
+
+fn draw_label(ui: &mut egui::Ui, text: &str, required: bool) -> egui::Response {
+    let bare_text = text.trim_end().trim_end_matches(':');
+    let response = if required {
+        ui.strong(format!("{bare_text}*: ")).on_hover_text(tr!("required"))
+    } else {
+        ui.strong(text)
+    };
+    let pending = PendingLabel {
+        id: response.id,
+        text: Arc::from(bare_text),
+        required,
+    };
+    ui.data_mut(|d| d.insert_temp(pending_label_id(), pending));
+    field_search::note_label(ui, &response, bare_text);
+    response
+}
+
+/// Draws the label of a form field. The next field drawn uses it as its accessible name.
+pub fn field_label(ui: &mut egui::Ui, text: &str) -> egui::Response {
+    draw_label(ui, text, false)
+}
+
+/// Like [field_label], but marks the field as required both visually and for screen readers
+pub fn required_field_label(ui: &mut egui::Ui, text: &str) -> egui::Response {
+    draw_label(ui, text, true)
+}
+
+/// Like [field_label], followed by an icon that shows the spec's help on `field` when hovered.
+/// `field` is named like [FieldHelp::field].
+pub fn field_label_with_help(ui: &mut egui::Ui, text: &str, field: &str) -> egui::Response {
+    ui.horizontal(|ui| {
+        let response = draw_label(ui, text, false);
+        draw_help_icon(ui, field);
+        response
+    })
+    .inner
+}
+
+/// Like [required_field_label], with the help icon of [field_label_with_help]
+pub fn required_field_label_with_help(ui: &mut egui::Ui, text: &str, field: &str) -> egui::Response {
+    ui.horizontal(|ui| {
+        let response = draw_label(ui, text, true);
+        draw_help_icon(ui, field);
+        response
+    })
+    .inner
+}
+
+fn draw_help_icon(ui: &mut egui::Ui, field: &str) {
+    let Some(help) = field_help(field) else {
+        log::warn!("No help on field {field}");
+        return;
+    };
+    ui.weak("ℹ").on_hover_ui(|ui| show_field_help(ui, help));
+}
+
+fn show_field_help(ui: &mut egui::Ui, help: &FieldHelp) {
+    ui.set_max_width(320.0);
+    ui.label(help.description);
+    for constraint in help.constraints {
+        let text = match *constraint {
+            Constraint::Length { min: 0, max } => tr!("help-max-length", max = max),
+            Constraint::Length { min, max } => tr!("help-length", min = min, max = max),
+            Constraint::Identifier => tr!("help-identifier"),
+            Constraint::Lowercase => tr!("help-lowercase"),
+            Constraint::NoSlashes => tr!("help-no-slashes"),
+            Constraint::Unique => tr!("help-unique"),
+            Constraint::Orcid => tr!("help-orcid"),
+            Constraint::Doi => tr!("help-doi"),
+            Constraint::Version => tr!("help-version"),
+            Constraint::Url => tr!("help-url"),
+        };
+        ui.label(format!("• {text}"));
+    }
+    if let Some(example) = help.example {
+        ui.weak(tr!("help-example", example = example));
+    }
+}
+
+/// Exposes a form field to assistive technologies, named after the last [field_label] drawn and
+/// mentioning its validation error, if any, so that it gets announced when the field is focused
+pub fn describe_field(ui: &egui::Ui, response: &egui::Response, mut info: egui::WidgetInfo, error: Option<&dyn Display>) {
+    let pending = ui.data(|d| d.get_temp::<PendingLabel>(pending_label_id()));
+    let mut label = match pending {
+        Some(pending) => {
+            ui.data_mut(|d| d.remove::<PendingLabel>(pending_label_id()));
+            let _ = response.clone().labelled_by(pending.id);
+            if pending.required {
+                tr!("a11y-required-field", label = pending.text.to_string())
+            } else {
+                pending.text.to_string()
+            }
+        }
+        None => info.label.take().unwrap_or_default(),
+    };
+    if let Some(error) = error {
+        label = tr!("a11y-invalid-field", label = label, error = error.to_string());
+    }
+    info.label = Some(label);
+    response.widget_info(|| info.clone());
+}
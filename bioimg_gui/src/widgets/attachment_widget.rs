@@ -0,0 +1,109 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use bioimg_spec::rdf::{FileDescr, FileReference, Sha256};
+use bioimg_spec::runtime::model_package::PackageFile;
+
+use super::{
+    error_display::show_error,
+    file_widget::{FileWidget, FileWidgetState, ParsedFile},
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::TaskStatus;
+
+/// A file to be shipped along with the resource, hashed when it was picked
+pub struct GuiAttachment {
+    path: PathBuf,
+    sha256: Sha256,
+}
+
+impl GuiAttachment {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The description of this file once it is copied next to the rdf, under its own name
+    pub fn descr(&self) -> Result<FileDescr> {
+        if !self.path.is_file() {
+            return Err(GuiError::new(tr!("attachment-missing", path = self.path.to_string_lossy().into_owned())));
+        }
+        Ok(FileDescr {
+            source: FileReference::Path(self.package_path()),
+            sha256: Some(self.sha256),
+        })
+    }
+
+    /// The file to put into the package, under the name [Self::descr] refers to it by
+    pub fn package_file(&self) -> (PathBuf, PackageFile) {
+        (self.package_path(), PackageFile::Path(self.path.clone()))
+    }
+
+    fn package_path(&self) -> PathBuf {
+        self.path.file_name().map(PathBuf::from).unwrap_or_default()
+    }
+}
+
+impl ParsedFile for Result<GuiAttachment> {
+    fn parse(path: PathBuf, _ctx: egui::Context, status: &TaskStatus) -> Self {
+        let sha256 = Sha256::of_file_with_progress(&path, &mut |progress| status.report(progress.fraction()))?;
+        Ok(GuiAttachment { path, sha256 })
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        if let Err(err) = self {
+            show_error(ui, err.to_string());
+        }
+    }
+
+    fn sha256(&self) -> Option<Sha256> {
+        self.as_ref().ok().map(|attachment| attachment.sha256)
+    }
+}
+
+pub type AttachmentWidget = FileWidget<Result<GuiAttachment>>;
+
+/// The picked file, or `None` if no file was picked. Fails if the file can't be used (yet).
+pub fn attached_file(state: &FileWidgetState<Result<GuiAttachment>>) -> Result<Option<FileDescr>> {
+    match state {
+        FileWidgetState::Empty => Ok(None),
+        FileWidgetState::Finished { value, .. } => Ok(Some(value.as_ref().map_err(Clone::clone)?.descr()?)),
+        FileWidgetState::Loading { path, .. } => {
+            Err(GuiError::new(tr!("attachment-still-loading", path = path.to_string_lossy().into_owned())))
+        }
+        FileWidgetState::Failed { path, .. } => {
+            Err(GuiError::new(tr!("attachment-not-loaded", path = path.to_string_lossy().into_owned())))
+        }
+    }
+}
+
+/// The attachments picked so far, skipping the empty slots. Fails if any of them can't be used
+/// or if two of them would end up with the same name in the package.
+pub fn attached_files(states: Vec<&FileWidgetState<Result<GuiAttachment>>>) -> Result<Vec<FileDescr>> {
+    let mut names = HashSet::new();
+    let mut files = vec![];
+    for state in states {
+        let Some(descr) = attached_file(state)? else {
+            continue;
+        };
+        let name = String::from(descr.source.clone());
+        if !names.insert(name.clone()) {
+            return Err(GuiError::new(tr!("attachment-duplicate-name", name = name)));
+        }
+        files.push(descr);
+    }
+    Ok(files)
+}
+
+/// The files of the attachments that finished loading, to put into the package
+pub fn attachment_package_files<'a>(widgets: impl IntoIterator<Item = &'a AttachmentWidget>) -> Vec<(PathBuf, PackageFile)> {
+    widgets
+        .into_iter()
+        .filter_map(|widget| match widget.loaded_value() {
+            Some(Ok(attachment)) => Some(attachment.package_file()),
+            _ => None,
+        })
+        .collect()
+}
@@ -1,26 +1,93 @@
 use bioimg_spec::rdf::{author::Author2, bounded_string::BoundedString, orcid::Orcid};
+use bioimg_spec::runtime::orcid::{OrcidClient, OrcidPerson};
+use bioimg_spec::runtime::{read_author_table, AuthorRowError};
 
-use super::{StagingOpt, StagingString, StatefulWidget};
-use crate::result::Result;
+use super::{
+    accessibility::{field_label_with_help, required_field_label_with_help},
+    error_display::show_error,
+    paste_widget::PasteBox,
+    restore_fields, set_value_from, StagingOpt, StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::settings::{AuthorProfile, FileDialogSettings};
+use crate::task::Task;
 
 pub type ConfString = BoundedString<1, 1023>;
 
+#[derive(Default)]
 pub struct StagingAuthor2 {
     staging_name: StagingString<ConfString>,                    // (Name→String) Full name.
     staging_affiliation: StagingOpt<StagingString<ConfString>>, // (String) Affiliation.
     staging_email: StagingOpt<StagingString<ConfString>>,       // FIXME: make a parser here (Email) E-Mail
     staging_github_user: StagingOpt<StagingString<ConfString>>, // (String) GitHub user name.
     staging_orcid: StagingOpt<StagingString<Orcid>>,
+    /// The public record of the entered ORCID iD, being fetched to fill in the name and affiliation
+    pending_orcid: Option<Task<Result<OrcidPerson>>>,
+    orcid_error: Option<GuiError>,
+    paste: PasteBox<Author2>,
 }
 
-impl Default for StagingAuthor2 {
-    fn default() -> Self {
+impl From<&AuthorProfile> for StagingAuthor2 {
+    fn from(profile: &AuthorProfile) -> Self {
+        let mut staging_name = StagingString::default();
+        staging_name.set_raw(profile.name.trim());
         Self {
-            staging_name: Default::default(),
-            staging_affiliation: Default::default(),
+            staging_name,
+            staging_affiliation: StagingOpt::from_raw(&profile.affiliation),
             staging_email: Default::default(),
-            staging_github_user: Default::default(),
-            staging_orcid: Default::default(),
+            staging_github_user: StagingOpt::from_raw(&profile.github_user),
+            staging_orcid: StagingOpt::from_raw(&profile.orcid),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Author2> for StagingAuthor2 {
+    fn from(author: &Author2) -> Self {
+        let mut staging_name = StagingString::default();
+        staging_name.set_raw(author.name.to_string());
+        Self {
+            staging_name,
+            staging_affiliation: StagingOpt::from_raw(&author.affiliation.as_ref().map(ToString::to_string).unwrap_or_default()),
+            staging_email: StagingOpt::from_raw(&author.email.as_ref().map(ToString::to_string).unwrap_or_default()),
+            staging_github_user: StagingOpt::from_raw(&author.github_user.as_ref().map(ToString::to_string).unwrap_or_default()),
+            staging_orcid: StagingOpt::from_raw(&author.orcid.clone().map(Into::<String>::into).unwrap_or_default()),
+            ..Default::default()
+        }
+    }
+}
+
+set_value_from!(StagingAuthor2: Author2);
+
+impl StagingAuthor2 {
+    /// The field the full name is typed into
+    pub fn name_field_mut(&mut self) -> &mut StagingString<ConfString> {
+        &mut self.staging_name
+    }
+
+    /// Whether nothing was filled in, like in the item a new list starts out with
+    pub fn is_empty(&self) -> bool {
+        let fields = [&self.staging_affiliation, &self.staging_email, &self.staging_github_user];
+        self.staging_name.raw().trim().is_empty()
+            && fields.iter().all(|field| field.inner().is_none())
+            && self.staging_orcid.inner().is_none()
+    }
+
+    fn fetch_orcid(&mut self, ctx: &egui::Context, orcid: Orcid) {
+        self.orcid_error = None;
+        self.pending_orcid = Some(Task::spawn(tr!("fetching-orcid"), ctx.clone(), move |_| {
+            Ok(OrcidClient::public().lookup(&orcid)?)
+        }));
+    }
+
+    /// Fills in what the record has, keeping whatever the record doesn't make public
+    fn apply_orcid_record(&mut self, person: OrcidPerson) {
+        if let Some(name) = person.name {
+            self.staging_name.set_raw(name);
+        }
+        if let Some(affiliation) = person.affiliation {
+            self.staging_affiliation = StagingOpt::from_raw(&affiliation);
         }
     }
 }
@@ -29,27 +96,54 @@ impl StatefulWidget for StagingAuthor2 {
     type Value<'p> = Result<Author2>;
 
     fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, id: egui::Id) {
+        if let Some(author) = self.paste.show(ui, id.with("Paste")) {
+            *self = Self::from(&author);
+        }
         egui::Grid::new(id).num_columns(2).show(ui, |ui| {
-            ui.strong("Name: ");
+            required_field_label_with_help(ui, &tr!("name-label"), "authors.name");
             self.staging_name.draw_and_parse(ui, id.with("Name"));
             ui.end_row();
 
-            ui.strong("Affiliation: ");
+            field_label_with_help(ui, &tr!("affiliation-label"), "authors.affiliation");
             self.staging_affiliation.draw_and_parse(ui, id.with("Affiliation"));
             ui.end_row();
 
-            ui.strong("Email: ");
+            field_label_with_help(ui, &tr!("email-label"), "authors.email");
             self.staging_email.draw_and_parse(ui, id.with("Email"));
             ui.end_row();
 
-            ui.strong("Github User: ");
+            field_label_with_help(ui, &tr!("github-user-label"), "authors.github_user");
             self.staging_github_user.draw_and_parse(ui, id.with("Github User"));
             ui.end_row();
 
-            ui.strong("Orcid: ");
-            self.staging_orcid.draw_and_parse(ui, id.with("Orcid"));
+            field_label_with_help(ui, &tr!("orcid-label"), "authors.orcid");
+            ui.horizontal(|ui| {
+                self.staging_orcid.draw_and_parse(ui, id.with("Orcid"));
+                if self.pending_orcid.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let orcid = self.staging_orcid.state().and_then(|orcid| orcid.ok());
+                let fetch = ui
+                    .add_enabled(orcid.is_some(), egui::Button::new(tr!("fetch-orcid")))
+                    .on_hover_text(tr!("fetch-orcid-hint"));
+                if let (true, Some(orcid)) = (fetch.clicked(), orcid) {
+                    self.fetch_orcid(ui.ctx(), orcid);
+                }
+            });
             ui.end_row();
         });
+
+        if self.pending_orcid.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_orcid.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok(person) => self.apply_orcid_record(person),
+                Err(err) => self.orcid_error = Some(GuiError::new(tr!("orcid-fetch-failed", error = err.to_string()))),
+            }
+        }
+        if let Some(err) = &self.orcid_error {
+            show_error(ui, err);
+        }
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
@@ -62,3 +156,92 @@ impl StatefulWidget for StagingAuthor2 {
         })
     }
 }
+
+restore_fields!(StagingAuthor2 { staging_name, staging_affiliation, staging_email, staging_github_user, staging_orcid });
+
+/// A box to paste a spreadsheet of authors into or open one from, as CSV or TSV
+#[derive(Default)]
+pub struct AuthorTableImport {
+    open: bool,
+    raw: String,
+    error: Option<GuiError>,
+    /// The rows of the last import that didn't make valid authors
+    row_errors: Vec<AuthorRowError>,
+}
+
+impl AuthorTableImport {
+    /// Returns the authors of the rows that are valid once they are read. The box closes unless some rows weren't.
+    pub fn show(&mut self, ui: &mut egui::Ui, id: egui::Id, file_dialogs: &mut FileDialogSettings) -> Option<Vec<Author2>> {
+        let text_id = id.with("table");
+        if !self.open {
+            if ui.button(tr!("import-author-table")).on_hover_text(tr!("import-author-table-hint")).clicked() {
+                self.open = true;
+                self.raw.clear();
+                self.error = None;
+                self.row_errors.clear();
+                ui.memory_mut(|mem| mem.request_focus(text_id));
+            }
+            return None;
+        }
+
+        let mut imported = None;
+        ui.vertical(|ui| {
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut self.raw)
+                    .id(text_id)
+                    .code_editor()
+                    .desired_rows(6)
+                    .hint_text(tr!("author-table-placeholder")),
+            );
+            if response.changed() {
+                self.error = None;
+            }
+            ui.horizontal(|ui| {
+                let can_import = !self.raw.trim().is_empty();
+                if ui.add_enabled(can_import, egui::Button::new(tr!("import-author-rows"))).clicked() {
+                    imported = self.read(&self.raw.clone());
+                }
+                if ui.button(tr!("open-author-table")).clicked() {
+                    let dialog = file_dialogs.open_dialog().add_filter("CSV / TSV", &["csv", "tsv", "txt"]);
+                    if let Some(path) = dialog.pick_file() {
+                        file_dialogs.remember_opened(&path);
+                        match std::fs::read_to_string(&path) {
+                            Ok(raw) => imported = self.read(&raw),
+                            Err(err) => self.error = Some(GuiError::from(err)),
+                        }
+                    }
+                }
+                if ui.button(tr!("cancel")).clicked() {
+                    self.open = false;
+                }
+            });
+            if let Some(err) = &self.error {
+                show_error(ui, err);
+            }
+            if !self.row_errors.is_empty() {
+                ui.label(tr!("author-rows-skipped", count = self.row_errors.len()));
+                for err in &self.row_errors {
+                    ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+                }
+            }
+        });
+        if imported.is_some() && self.row_errors.is_empty() {
+            self.open = false;
+        }
+        imported
+    }
+
+    fn read(&mut self, raw: &str) -> Option<Vec<Author2>> {
+        match read_author_table(raw) {
+            Ok(rows) => {
+                let (authors, errors): (Vec<_>, Vec<_>) = rows.into_iter().partition(|row| row.is_ok());
+                self.row_errors = errors.into_iter().filter_map(|row| row.err()).collect();
+                Some(authors.into_iter().filter_map(|row| row.ok()).collect())
+            }
+            Err(err) => {
+                self.error = Some(GuiError::new(tr!("author-table-failed", error = err.to_string())));
+                None
+            }
+        }
+    }
+}
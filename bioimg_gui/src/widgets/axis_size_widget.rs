@@ -1,10 +1,15 @@
 use std::num::NonZeroUsize;
 
+use crate::i18n::tr;
 use crate::result::Result;
 use bioimg_spec::rdf::model as modelrdf;
-use bioimg_spec::rdf::model::{axes::AxisId, tensor_id::TensorId};
+use bioimg_spec::rdf::model::{axes::AxisId, references::IdRename, tensor_id::TensorId};
+use serde::{Deserialize, Serialize};
 
-use super::{StagingNum, StagingString, StatefulWidget};
+use super::{
+    accessibility::field_label, restore_fields, restore_with_serde, set_value_from, StagingNum, StagingString,
+    StatefulWidget,
+};
 
 #[derive(Default)]
 pub struct AxisSizeReferenceWidget {
@@ -13,23 +18,36 @@ pub struct AxisSizeReferenceWidget {
     pub staging_offset: StagingNum<usize, usize>,
 }
 
+impl AxisSizeReferenceWidget {
+    /// Points the reference at the renamed tensor or axis, if it is filled in well enough to tell
+    pub fn apply_rename(&mut self, rename: &IdRename) {
+        let Ok(mut reference) = self.state() else {
+            return;
+        };
+        if reference.apply_rename(rename) {
+            self.staging_tensor_id.set_raw(reference.tensor_id.to_string());
+            self.staging_axis_id.set_raw(reference.axis_id.to_string());
+        }
+    }
+}
+
 impl StatefulWidget for AxisSizeReferenceWidget {
     type Value<'p> = Result<modelrdf::AxisSizeReference>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Tensor Id: ");
+                field_label(ui, &tr!("tensor-id-label"));
                 self.staging_tensor_id.draw_and_parse(ui, id.with("Tensor Id"));
             });
 
             ui.horizontal(|ui| {
-                ui.strong("Axis Id: ");
+                field_label(ui, &tr!("axis-id-label"));
                 self.staging_axis_id.draw_and_parse(ui, id.with("Axis Id"));
             });
 
             ui.horizontal(|ui| {
-                ui.strong("Offset: ");
+                field_label(ui, &tr!("offset-label"));
                 self.staging_offset.draw_and_parse(ui, id.with("Offset"));
             });
         });
@@ -56,12 +74,12 @@ impl StatefulWidget for ParameterizedAxisSizeWidget {
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Min: ");
+                field_label(ui, &tr!("min-label"));
                 self.staging_min.draw_and_parse(ui, id.with("Min"));
             });
 
             ui.horizontal(|ui| {
-                ui.strong("Step: ");
+                field_label(ui, &tr!("step-label"));
                 self.staging_step.draw_and_parse(ui, id.with("Step"));
             });
         });
@@ -75,7 +93,7 @@ impl StatefulWidget for ParameterizedAxisSizeWidget {
     }
 }
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum AxisSizeMode {
     Fixed,
     Reference,
@@ -97,21 +115,58 @@ pub struct AnyAxisSizeWidget {
     pub staging_parameterized: ParameterizedAxisSizeWidget,
 }
 
+impl From<&modelrdf::AnyAxisSize> for AnyAxisSizeWidget {
+    fn from(size: &modelrdf::AnyAxisSize) -> Self {
+        let mut widget = Self::default();
+        match size {
+            modelrdf::AnyAxisSize::Fixed(extent) => {
+                widget.mode = AxisSizeMode::Fixed;
+                widget.staging_fixed_size = StagingNum::new(extent.get());
+            }
+            modelrdf::AnyAxisSize::Parameterized(parameterized) => {
+                widget.mode = AxisSizeMode::Parameterized;
+                widget.staging_parameterized = ParameterizedAxisSizeWidget {
+                    staging_min: StagingNum::new(parameterized.min.get()),
+                    staging_step: StagingNum::new(parameterized.step.get()),
+                };
+            }
+            modelrdf::AnyAxisSize::Reference(reference) => {
+                widget.mode = AxisSizeMode::Reference;
+                widget.staging_size_ref = AxisSizeReferenceWidget {
+                    staging_tensor_id: StagingString::default().with_raw(reference.tensor_id.to_string()),
+                    staging_axis_id: StagingString::default().with_raw(reference.axis_id.to_string()),
+                    staging_offset: StagingNum::new(reference.offset),
+                };
+            }
+        }
+        widget
+    }
+}
+
+set_value_from!(AnyAxisSizeWidget: modelrdf::AnyAxisSize);
+
+impl AnyAxisSizeWidget {
+    /// The tensor and axis the size is taken from, if it is set to be taken from another axis
+    pub fn size_reference_mut(&mut self) -> Option<&mut AxisSizeReferenceWidget> {
+        matches!(self.mode, AxisSizeMode::Reference).then_some(&mut self.staging_size_ref)
+    }
+}
+
 impl StatefulWidget for AnyAxisSizeWidget {
     type Value<'p> = Result<modelrdf::AnyAxisSize>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.selectable_value(&mut self.mode, AxisSizeMode::Fixed, "Fixed Size");
-                ui.selectable_value(&mut self.mode, AxisSizeMode::Parameterized, "Parameterized");
-                ui.selectable_value(&mut self.mode, AxisSizeMode::Reference, "Reference");
+                ui.selectable_value(&mut self.mode, AxisSizeMode::Fixed, tr!("fixed-size"));
+                ui.selectable_value(&mut self.mode, AxisSizeMode::Parameterized, tr!("parameterized"));
+                ui.selectable_value(&mut self.mode, AxisSizeMode::Reference, tr!("reference"));
             });
 
             match self.mode {
                 AxisSizeMode::Fixed => {
                     ui.horizontal(|ui| {
-                        ui.strong("Extent: ");
+                        field_label(ui, &tr!("extent-label"));
                         self.staging_fixed_size.draw_and_parse(ui, id.with("Fixed"));
                     });
                 }
@@ -133,3 +188,8 @@ impl StatefulWidget for AnyAxisSizeWidget {
         })
     }
 }
+
+restore_with_serde!(AxisSizeMode);
+restore_fields!(AxisSizeReferenceWidget { staging_tensor_id, staging_axis_id, staging_offset });
+restore_fields!(ParameterizedAxisSizeWidget { staging_min, staging_step });
+restore_fields!(AnyAxisSizeWidget { mode, staging_fixed_size, staging_size_ref, staging_parameterized });
@@ -0,0 +1,44 @@
+use std::num::NonZeroUsize;
+
+use bioimg_spec::runtime::inference::BackendConfig;
+
+use super::{accessibility::field_label, StagingNum, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::Result;
+
+pub struct BackendConfigWidget {
+    pub staging_num_threads: StagingNum<usize, NonZeroUsize>,
+}
+
+impl Default for BackendConfigWidget {
+    fn default() -> Self {
+        Self::from(BackendConfig::default())
+    }
+}
+
+impl From<BackendConfig> for BackendConfigWidget {
+    fn from(config: BackendConfig) -> Self {
+        Self {
+            staging_num_threads: StagingNum::new(config.num_threads.get()).with_speed(0.1),
+        }
+    }
+}
+
+impl StatefulWidget for BackendConfigWidget {
+    type Value<'p> = Result<BackendConfig>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("threads-label"));
+            self.staging_num_threads.draw_and_parse(ui, id.with("Threads"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(BackendConfig {
+            num_threads: self.staging_num_threads.state()?,
+            ..Default::default()
+        })
+    }
+}
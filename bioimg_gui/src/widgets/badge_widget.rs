@@ -0,0 +1,127 @@
+use bioimg_spec::rdf::{badge::Badge, bounded_string::BoundedString};
+use bioimg_spec::runtime::ResourceFetcher;
+use url::Url;
+
+use super::{
+    accessibility::required_field_label_with_help,
+    restore_fields, set_value_from,
+    url_widget::StagingUrl,
+    util::DynamicImageExt,
+    StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::Task;
+
+/// Height in points badge icons are previewed at; most are drawn about as tall as a line of text
+const ICON_PREVIEW_HEIGHT: f32 = 20.0;
+/// Size in pixels of the longest side of the texture icons are previewed with. Badges tend to be several
+/// times as wide as they are tall, and this keeps them sharp at twice the size they are drawn at.
+const ICON_TEXTURE_SIZE: u32 = 240;
+
+/// The icon of a badge, downloaded to show what the badge will look like
+#[derive(Default)]
+struct IconPreview {
+    /// The url [Self::texture] was downloaded from
+    url: Option<Url>,
+    pending: Option<Task<Result<egui::TextureHandle>>>,
+    texture: Option<Result<egui::TextureHandle>>,
+}
+
+impl IconPreview {
+    fn show(&mut self, ui: &mut egui::Ui, url: Option<Url>) {
+        if url != self.url {
+            if let Some(task) = self.pending.take() {
+                task.cancel();
+            }
+            self.texture = None;
+            self.url = url.clone();
+            if let Some(url) = url {
+                let ctx = ui.ctx().clone();
+                self.pending = Some(Task::spawn(tr!("loading-badge-icon"), ctx.clone(), move |_| {
+                    let path = ResourceFetcher::with_user_cache()?.fetch(&url, None)?;
+                    // image can't draw svg icons, which are common for badges
+                    let image = image::open(&path).map_err(|_| GuiError::new(tr!("badge-icon-not-image")))?;
+                    Ok(image.to_egui_thumbnail_handle(url.as_str(), ICON_TEXTURE_SIZE, &ctx))
+                }));
+            }
+        }
+        if self.pending.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending.take().expect("checked above");
+            self.texture = Some(task.join().map_err(GuiError::from).and_then(|result| result));
+        }
+        if self.pending.is_some() {
+            ui.spinner();
+            return;
+        }
+        match &self.texture {
+            None => (),
+            Some(Ok(texture)) => {
+                let size = texture.size_vec2();
+                ui.add(egui::Image::new((texture.id(), size * (ICON_PREVIEW_HEIGHT / size.y.max(1.0)))));
+            }
+            Some(Err(err)) => {
+                let warning = egui::RichText::new("⚠").color(ui.visuals().warn_fg_color);
+                ui.label(warning).on_hover_text(tr!("badge-icon-preview-failed", error = err.to_string()));
+            }
+        }
+    }
+}
+
+/// A button linking somewhere, e.g. "Open in Colab", with a preview of its icon
+#[derive(Default)]
+pub struct StagingBadge {
+    label: StagingString<BoundedString<1, 1023>>,
+    icon: StagingUrl,
+    url: StagingUrl,
+    preview: IconPreview,
+}
+
+impl From<&Badge> for StagingBadge {
+    fn from(badge: &Badge) -> Self {
+        let mut label = StagingString::default();
+        label.set_raw(badge.label.to_string());
+        Self {
+            label,
+            icon: StagingUrl::from_reference(&badge.icon),
+            url: StagingUrl::from_reference(&badge.url),
+            preview: Default::default(),
+        }
+    }
+}
+
+set_value_from!(StagingBadge: Badge);
+
+impl StatefulWidget for StagingBadge {
+    type Value<'p> = Result<Badge>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            required_field_label_with_help(ui, &tr!("badge-label-label"), "badges.label");
+            self.label.draw_and_parse(ui, id.with("label"));
+            ui.end_row();
+
+            required_field_label_with_help(ui, &tr!("badge-icon-label"), "badges.icon");
+            ui.horizontal(|ui| {
+                self.icon.draw_and_parse(ui, id.with("icon"));
+                // waits for the url to be checked, which happens once it's done being typed in
+                self.preview.show(ui, self.icon.state().ok().filter(|_| self.icon.is_reachable()));
+            });
+            ui.end_row();
+
+            required_field_label_with_help(ui, &tr!("badge-url-label"), "badges.url");
+            self.url.draw_and_parse(ui, id.with("url"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(Badge {
+            label: self.label.state()?,
+            icon: self.icon.state()?.into(),
+            url: self.url.state()?.into(),
+        })
+    }
+}
+
+restore_fields!(StagingBadge { label, icon, url });
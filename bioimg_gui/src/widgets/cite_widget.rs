@@ -1,62 +1,104 @@
-use crate::result::Result;
-use bioimg_spec::rdf::{
-    bounded_string::{BoundedString, BoundedStringParsingError},
-    cite_entry::CiteEntry2,
-};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::settings::FileDialogSettings;
+use crate::task::Task;
+use bioimg_spec::rdf::{bounded_string::BoundedString, cite_entry::CiteEntry2, Doi};
+use bioimg_spec::runtime::citation::{Citation, CitationClient};
+use bioimg_spec::runtime::read_bibtex;
 
-use super::{url_widget::StagingUrl, StagingOpt, StagingString, StatefulWidget};
+use super::{
+    accessibility::{field_label_with_help, required_field_label_with_help},
+    error_display::show_error,
+    restore_fields, set_value_from,
+    url_widget::StagingUrl,
+    StagingOpt, StagingString, StatefulWidget,
+};
 
 pub type ConfString = BoundedString<1, 1023>;
 
-#[derive(thiserror::Error, Debug, Clone)]
-pub enum CiteEntry2ParsingError {
-    #[error("Empty")]
-    Empty,
-    #[error("{0}")]
-    FieldError(
-        #[from]
-        #[source]
-        BoundedStringParsingError,
-    ),
-    #[error("{0}")]
-    BadUrl(#[from] url::ParseError),
-}
-
+#[derive(Default)]
 pub struct StagingCiteEntry2 {
     staging_text: StagingString<ConfString>,
-    staging_doi: StagingOpt<StagingString<ConfString>>,
+    staging_doi: StagingOpt<StagingString<Doi>>,
     staging_url: StagingOpt<StagingUrl>,
-    parsed: Result<CiteEntry2, CiteEntry2ParsingError>,
+    /// The reference the entered DOI points to, being looked up to fill in the text and url
+    pending_lookup: Option<Task<Result<Citation>>>,
+    lookup_error: Option<GuiError>,
 }
 
-impl Default for StagingCiteEntry2 {
-    fn default() -> Self {
+impl From<&CiteEntry2> for StagingCiteEntry2 {
+    fn from(cite: &CiteEntry2) -> Self {
         Self {
-            staging_text: Default::default(),
-            staging_doi: Default::default(),
-            staging_url: Default::default(),
-            parsed: Err(CiteEntry2ParsingError::Empty), //FIXME: could we eliminate "Empty"
+            staging_text: StagingString::default().with_raw(cite.text.to_string()),
+            staging_doi: StagingOpt::from_raw(&cite.doi.as_ref().map(ToString::to_string).unwrap_or_default()),
+            staging_url: cite.url.as_ref().map(StagingUrl::from).into(),
+            ..Default::default()
         }
     }
 }
 
+set_value_from!(StagingCiteEntry2: CiteEntry2);
+
+impl StagingCiteEntry2 {
+    fn look_up_doi(&mut self, ctx: &egui::Context, doi: Doi) {
+        self.lookup_error = None;
+        self.pending_lookup = Some(Task::spawn(tr!("looking-up-doi"), ctx.clone(), move |_| {
+            Ok(CitationClient::doi_org().lookup(&doi)?)
+        }));
+    }
+
+    /// Whether nothing was filled in, like in the item a new list starts out with
+    pub fn is_empty(&self) -> bool {
+        self.staging_text.raw().trim().is_empty() && self.staging_doi.inner().is_none() && self.staging_url.inner().is_none()
+    }
+
+    fn apply_citation(&mut self, citation: Citation) {
+        self.staging_text.set_raw(citation.text);
+        self.staging_url = Some(StagingUrl::from(&citation.url)).into();
+    }
+}
+
 impl StatefulWidget for StagingCiteEntry2 {
     type Value<'p> = Result<CiteEntry2>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         egui::Grid::new(id).show(ui, |ui| {
-            ui.strong("Text: ");
+            required_field_label_with_help(ui, &tr!("text-label"), "cite.text");
             self.staging_text.draw_and_parse(ui, id.with("Text"));
             ui.end_row();
 
-            ui.strong("Doi: ");
-            self.staging_doi.draw_and_parse(ui, id.with("Doi"));
+            field_label_with_help(ui, &tr!("doi-label"), "cite.doi");
+            ui.horizontal(|ui| {
+                self.staging_doi.draw_and_parse(ui, id.with("Doi"));
+                if self.pending_lookup.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let doi = self.staging_doi.state().and_then(|doi| doi.ok());
+                let look_up = ui
+                    .add_enabled(doi.is_some(), egui::Button::new(tr!("look-up-doi")))
+                    .on_hover_text(tr!("look-up-doi-hint"));
+                if let (true, Some(doi)) = (look_up.clicked(), doi) {
+                    self.look_up_doi(ui.ctx(), doi);
+                }
+            });
             ui.end_row();
 
-            ui.strong("Url: ");
+            field_label_with_help(ui, &tr!("url-label"), "cite.url");
             self.staging_url.draw_and_parse(ui, id.with("Url"));
             ui.end_row();
         });
+
+        if self.pending_lookup.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_lookup.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok(citation) => self.apply_citation(citation),
+                Err(err) => self.lookup_error = Some(GuiError::new(tr!("doi-lookup-failed", error = err.to_string()))),
+            }
+        }
+        if let Some(err) = &self.lookup_error {
+            show_error(ui, err);
+        }
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
@@ -67,3 +109,79 @@ impl StatefulWidget for StagingCiteEntry2 {
         })
     }
 }
+
+restore_fields!(StagingCiteEntry2 { staging_text, staging_doi, staging_url });
+
+/// An "Import from BibTeX" button that opens a box to paste entries of a `.bib` file into, or to open one
+#[derive(Default)]
+pub struct BibtexImport {
+    open: bool,
+    raw: String,
+    error: Option<GuiError>,
+}
+
+impl BibtexImport {
+    /// Returns the references once they are read, which closes the box again
+    pub fn show(&mut self, ui: &mut egui::Ui, id: egui::Id, file_dialogs: &mut FileDialogSettings) -> Option<Vec<CiteEntry2>> {
+        let text_id = id.with("bibtex");
+        if !self.open {
+            if ui.button(tr!("import-bibtex")).on_hover_text(tr!("import-bibtex-hint")).clicked() {
+                self.open = true;
+                self.raw.clear();
+                self.error = None;
+                ui.memory_mut(|mem| mem.request_focus(text_id));
+            }
+            return None;
+        }
+
+        let mut imported = None;
+        ui.vertical(|ui| {
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut self.raw)
+                    .id(text_id)
+                    .code_editor()
+                    .desired_rows(6)
+                    .hint_text(tr!("bibtex-placeholder")),
+            );
+            if response.changed() {
+                self.error = None;
+            }
+            ui.horizontal(|ui| {
+                let can_import = !self.raw.trim().is_empty();
+                if ui.add_enabled(can_import, egui::Button::new(tr!("import-bibtex-entries"))).clicked() {
+                    imported = self.read(&self.raw.clone());
+                }
+                if ui.button(tr!("open-bib-file")).clicked() {
+                    let dialog = file_dialogs.open_dialog().add_filter("BibTeX", &["bib"]);
+                    if let Some(path) = dialog.pick_file() {
+                        file_dialogs.remember_opened(&path);
+                        match std::fs::read_to_string(&path) {
+                            Ok(raw) => imported = self.read(&raw),
+                            Err(err) => self.error = Some(GuiError::from(err)),
+                        }
+                    }
+                }
+                if ui.button(tr!("cancel")).clicked() {
+                    self.open = false;
+                }
+            });
+            if let Some(err) = &self.error {
+                show_error(ui, err);
+            }
+        });
+        if imported.is_some() {
+            self.open = false;
+        }
+        imported
+    }
+
+    fn read(&mut self, raw: &str) -> Option<Vec<CiteEntry2>> {
+        match read_bibtex(raw) {
+            Ok(entries) => Some(entries),
+            Err(err) => {
+                self.error = Some(GuiError::new(tr!("bibtex-failed", error = err.to_string())));
+                None
+            }
+        }
+    }
+}
@@ -1,23 +1,287 @@
-use super::StatefulWidget;
+use std::ops::Range;
+
+use egui::text::{LayoutJob, TextFormat};
+use egui::TextBuffer;
+
+use super::{restore_fields, StatefulWidget};
+use crate::i18n::tr;
+
+/// What the text in a code editor is written in
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub enum CodeLanguage {
+    /// Shown as it is, wrapped at the edge of the editor, like markdown
+    #[default]
+    Text,
+    /// Highlighted, with line numbers and without wrapping, so that the indentation stays readable
+    Python,
+}
 
 #[derive(Default)]
 pub struct CodeEditorWidget {
     raw: String,
 }
 
+impl CodeEditorWidget {
+    pub fn with_raw(raw: impl Into<String>) -> Self {
+        Self { raw: raw.into() }
+    }
+}
+
 impl StatefulWidget for CodeEditorWidget {
     type Value<'p> = &'p str;
 
-    fn draw_and_parse(&mut self, ui: &mut egui::Ui, _id: egui::Id) {
-        ui.add(
-            egui::TextEdit::multiline(&mut self.raw)
-                .desired_rows(15)
-                .desired_width(f32::INFINITY)
-                .code_editor(),
-        );
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        draw_code(ui, id, &mut self.raw, CodeLanguage::Text, 15);
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
         &self.raw
     }
 }
+
+restore_fields!(CodeEditorWidget { raw });
+
+/// Shows `code` the way the editor would, but without letting it be changed
+pub fn show_code(ui: &mut egui::Ui, id: egui::Id, code: &str, language: CodeLanguage) {
+    let rows = code.lines().count().clamp(1, 30);
+    draw_code(ui, id, &mut { code }, language, rows);
+}
+
+fn draw_code(ui: &mut egui::Ui, id: egui::Id, code: &mut dyn TextBuffer, language: CodeLanguage, rows: usize) {
+    match language {
+        CodeLanguage::Text => {
+            ui.add(egui::TextEdit::multiline(code).id(id).desired_rows(rows).desired_width(f32::INFINITY).code_editor());
+        }
+        CodeLanguage::Python => {
+            let mut layouter = |ui: &egui::Ui, text: &str, _wrap_width: f32| {
+                let job = python_layout_job(ui, text);
+                ui.fonts(|fonts| fonts.layout_job(job))
+            };
+            ui.horizontal_top(|ui| {
+                let line_count = code.as_str().split('\n').count();
+                let numbers = (1..=line_count).map(|line| line.to_string()).collect::<Vec<_>>().join("\n");
+                let digits = line_count.to_string().len() as f32;
+                let digit_width = ui.fonts(|fonts| fonts.glyph_width(&egui::TextStyle::Monospace.resolve(ui.style()), '0'));
+                ui.add(
+                    egui::TextEdit::multiline(&mut numbers.as_str())
+                        .id(id.with("line numbers"))
+                        .font(egui::TextStyle::Monospace)
+                        .desired_rows(rows)
+                        .desired_width(digits * digit_width)
+                        .frame(false)
+                        .interactive(false),
+                );
+                let editor_width = ui.available_width();
+                egui::ScrollArea::horizontal().id_source(id.with("scroll")).show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(code)
+                            .id(id)
+                            .desired_rows(rows)
+                            .desired_width(editor_width)
+                            .code_editor()
+                            .layouter(&mut layouter),
+                    );
+                });
+            });
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum PythonToken {
+    Plain,
+    Keyword,
+    /// The name after `def` or `class`
+    Definition,
+    Decorator,
+    String,
+    Number,
+    Comment,
+}
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or",
+    "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+fn is_identifier_byte(byte: u8) -> bool {
+    // bytes of multi-byte characters are all >= 0x80, so identifiers are never split mid-character
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
+
+/// The end of the string literal whose opening quote is at `start`. Strings that aren't closed end with their
+/// line, or with the code if they are triple quoted.
+fn string_end(code: &str, start: usize) -> usize {
+    let bytes = code.as_bytes();
+    let quote = bytes[start];
+    let triple = bytes[start..].starts_with(&[quote; 3]);
+    let mut i = start + if triple { 3 } else { 1 };
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            byte if byte == quote && (!triple || bytes[i..].starts_with(&[quote; 3])) => {
+                return i + if triple { 3 } else { 1 };
+            }
+            b'\n' if !triple => return i,
+            _ => (),
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Splits python `code` into its tokens, or at least those that are worth telling apart. This is no parser:
+/// it only needs to recognize strings and comments well enough that brackets in them are left alone.
+fn python_tokens(code: &str) -> Vec<(PythonToken, Range<usize>)> {
+    let bytes = code.as_bytes();
+    let mut tokens = vec![];
+    let mut after_definition_keyword = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let token = match bytes[i] {
+            b'#' => {
+                i = code[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                PythonToken::Comment
+            }
+            b'"' | b'\'' => {
+                i = string_end(code, i);
+                PythonToken::String
+            }
+            b'@' => {
+                i += 1;
+                while i < bytes.len() && (is_identifier_byte(bytes[i]) || bytes[i] == b'.') {
+                    i += 1;
+                }
+                PythonToken::Decorator
+            }
+            byte if byte.is_ascii_digit() => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.' || bytes[i] == b'_') {
+                    i += 1;
+                }
+                PythonToken::Number
+            }
+            byte if is_identifier_byte(byte) => {
+                while i < bytes.len() && is_identifier_byte(bytes[i]) {
+                    i += 1;
+                }
+                let word = &code[start..i];
+                let is_string_prefix = word.len() <= 2 && word.chars().all(|c| "rRbBfFuU".contains(c));
+                if is_string_prefix && i < bytes.len() && matches!(bytes[i], b'"' | b'\'') {
+                    i = string_end(code, i);
+                    PythonToken::String
+                } else if after_definition_keyword {
+                    PythonToken::Definition
+                } else if PYTHON_KEYWORDS.contains(&word) {
+                    PythonToken::Keyword
+                } else {
+                    PythonToken::Plain
+                }
+            }
+            byte if byte.is_ascii_whitespace() => {
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                tokens.push((PythonToken::Plain, start..i));
+                continue;
+            }
+            _ => {
+                i += 1;
+                PythonToken::Plain
+            }
+        };
+        after_definition_keyword = token == PythonToken::Keyword && matches!(&code[start..i], "def" | "class");
+        tokens.push((token, start..i));
+    }
+    tokens
+}
+
+fn python_layout_job(ui: &egui::Ui, code: &str) -> LayoutJob {
+    let dark = ui.visuals().dark_mode;
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let mut job = LayoutJob::default();
+    job.wrap.max_width = f32::INFINITY;
+    for (token, range) in python_tokens(code) {
+        let color = match (token, dark) {
+            (PythonToken::Plain, _) => ui.visuals().text_color(),
+            (PythonToken::Comment, _) => ui.visuals().weak_text_color(),
+            (PythonToken::Keyword, true) => egui::Color32::from_rgb(0xc6, 0x78, 0xdd),
+            (PythonToken::Keyword, false) => egui::Color32::from_rgb(0xa6, 0x26, 0xa4),
+            (PythonToken::Definition, true) => egui::Color32::from_rgb(0x61, 0xaf, 0xef),
+            (PythonToken::Definition, false) => egui::Color32::from_rgb(0x40, 0x78, 0xf2),
+            (PythonToken::Decorator, true) | (PythonToken::Number, true) => egui::Color32::from_rgb(0xd1, 0x9a, 0x66),
+            (PythonToken::Decorator, false) | (PythonToken::Number, false) => egui::Color32::from_rgb(0x98, 0x68, 0x01),
+            (PythonToken::String, true) => egui::Color32::from_rgb(0x98, 0xc3, 0x79),
+            (PythonToken::String, false) => egui::Color32::from_rgb(0x50, 0xa1, 0x4f),
+        };
+        job.append(&code[range], 0.0, TextFormat::simple(font_id.clone(), color));
+    }
+    job
+}
+
+fn line_of(code: &str, offset: usize) -> usize {
+    code[..offset].matches('\n').count() + 1
+}
+
+/// The first bracket in `code` that isn't matched by another one
+fn bracket_problem(code: &str, tokens: &[(PythonToken, Range<usize>)]) -> Option<String> {
+    let mut open: Vec<(char, usize)> = vec![];
+    let plain = tokens.iter().filter(|(token, _)| *token == PythonToken::Plain);
+    for (_, range) in plain {
+        for (offset, bracket) in code[range.clone()].char_indices() {
+            let line = line_of(code, range.start + offset);
+            let expected_opening = match bracket {
+                '(' | '[' | '{' => {
+                    open.push((bracket, line));
+                    continue;
+                }
+                ')' => '(',
+                ']' => '[',
+                '}' => '{',
+                _ => continue,
+            };
+            match open.pop() {
+                Some((opening, _)) if opening == expected_opening => (),
+                Some((opening, opening_line)) => {
+                    return Some(tr!(
+                        "python-mismatched-bracket",
+                        opening = opening.to_string(),
+                        opening_line = opening_line,
+                        closing = bracket.to_string(),
+                        line = line
+                    ))
+                }
+                None => return Some(tr!("python-unopened-bracket", bracket = bracket.to_string(), line = line)),
+            }
+        }
+    }
+    open.first().map(|(bracket, line)| tr!("python-unclosed-bracket", bracket = bracket.to_string(), line = *line))
+}
+
+/// Whether `name` is something that `code` defines, assigns to or imports at the start of a line
+fn defines(code: &str, tokens: &[(PythonToken, Range<usize>)], name: &str) -> bool {
+    let defined = tokens.iter().any(|(token, range)| *token == PythonToken::Definition && &code[range.clone()] == name);
+    defined
+        || code.lines().any(|line| {
+            let words: Vec<&str> = line.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|w| !w.is_empty()).collect();
+            let assigned = line.strip_prefix(name).is_some_and(|rest| {
+                let rest = rest.trim_start();
+                rest.starts_with('=') && !rest.starts_with("==") || rest.starts_with(':')
+            });
+            let imported = (line.starts_with("from ") || line.starts_with("import ")) && words.contains(&name);
+            assigned || imported
+        })
+}
+
+/// Problems worth pointing out in python `code` that is meant to provide `callable`, before the
+/// package is tested and the python interpreter finds them. Empty if nothing looks wrong.
+pub fn check_python(code: &str, callable: &str) -> Vec<String> {
+    let tokens = python_tokens(code);
+    let mut problems: Vec<String> = bracket_problem(code, &tokens).into_iter().collect();
+    let callable = callable.trim();
+    if !callable.is_empty() && !defines(code, &tokens, callable) {
+        problems.push(tr!("python-callable-missing", callable = callable.to_owned()));
+    }
+    problems
+}
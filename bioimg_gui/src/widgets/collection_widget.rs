@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use bioimg_spec::rdf::{bounded_string::BoundedString, collection::CollectionEntry, file_reference::FileReference};
+use bioimg_spec::runtime::package_reader::PackageSummary;
+
+use super::{
+    accessibility::{describe_field, field_label, required_field_label},
+    error_display::show_error,
+    focus::handle_form_focus,
+    restore_fields,
+    util::group_frame,
+    InputLines, Restore, StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+/// Where the description of an entry is, as typed in or picked
+pub enum EntrySource {
+    /// A url, or a path relative to where the collection is saved
+    Reference(FileReference),
+    /// A package anywhere on disk, which is only referred to relative to the collection once that is saved
+    Package(PathBuf),
+}
+
+/// A collection entry as edited, before the collection is saved
+pub struct CollectionEntryDraft {
+    pub id: BoundedString<1, 1023>,
+    pub source: Option<EntrySource>,
+}
+
+impl CollectionEntryDraft {
+    /// The entry of a collection saved in `export_dir`, which has to contain the packages it refers to
+    pub fn into_entry(self, export_dir: &Path) -> Result<CollectionEntry> {
+        let rdf_source = match self.source {
+            None => None,
+            Some(EntrySource::Reference(reference)) => Some(reference),
+            Some(EntrySource::Package(path)) => Some(FileReference::local(&path, export_dir)?),
+        };
+        Ok(CollectionEntry {
+            id: self.id,
+            rdf_source,
+            overrides: Default::default(),
+        })
+    }
+}
+
+/// The files that can be dropped onto a collection
+const PACKAGE_EXTENSIONS: [&str; 3] = ["zip", "yaml", "yml"];
+
+#[derive(Default)]
+pub struct CollectionEntryWidget {
+    pub staging_id: StagingString<BoundedString<1, 1023>>,
+    pub raw_source: String,
+    pub summary: Option<Result<PackageSummary>>,
+}
+
+impl CollectionEntryWidget {
+    pub fn from_package(path: PathBuf) -> Self {
+        let summary = PackageSummary::read(&path).map_err(GuiError::from);
+        let id = match &summary {
+            Ok(PackageSummary { id: Some(id), .. }) => id.clone(),
+            _ => path.file_stem().map(|stem| stem.to_string_lossy().into()).unwrap_or_default(),
+        };
+        Self {
+            staging_id: StagingString {
+                raw: id.clone(),
+                parsed: BoundedString::try_from(id).map_err(GuiError::from),
+                input_lines: InputLines::SingleLine,
+                touched: true,
+                conflict: None,
+            },
+            raw_source: path.to_string_lossy().into(),
+            summary: Some(summary),
+        }
+    }
+
+    fn source(&self) -> Result<Option<EntrySource>> {
+        let raw = self.raw_source.trim();
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        if Path::new(raw).is_absolute() {
+            return Ok(Some(EntrySource::Package(raw.into())));
+        }
+        Ok(Some(EntrySource::Reference(FileReference::try_from(raw.to_owned())?)))
+    }
+}
+
+impl StatefulWidget for CollectionEntryWidget {
+    type Value<'p> = Result<CollectionEntryDraft>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            required_field_label(ui, &tr!("id-label"));
+            self.staging_id.draw_and_parse(ui, id.with("Id"));
+            ui.end_row();
+
+            field_label(ui, &tr!("rdf-source-label"));
+            let response = ui.text_edit_singleline(&mut self.raw_source);
+            handle_form_focus(ui, &response, true);
+            describe_field(ui, &response, egui::WidgetInfo::text_edit(&self.raw_source, &self.raw_source), None);
+            ui.end_row();
+
+            match &self.summary {
+                None => (),
+                Some(Ok(summary)) => {
+                    field_label(ui, &tr!("summary-label"));
+                    ui.weak(format!("{} ({}): {}", summary.name, summary.rdf_type, summary.description));
+                    ui.end_row();
+                }
+                Some(Err(err)) => {
+                    field_label(ui, &tr!("summary-label"));
+                    show_error(ui, err);
+                    ui.end_row();
+                }
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(CollectionEntryDraft {
+            id: self.staging_id.state()?,
+            source: self.source()?,
+        })
+    }
+}
+
+/// Entries of a collection, filled by dropping model packages onto the window or by typing ids
+#[derive(Default)]
+pub struct CollectionWidget {
+    pub entries: Vec<CollectionEntryWidget>,
+}
+
+impl StatefulWidget for CollectionWidget {
+    type Value<'p> = Result<Vec<CollectionEntryDraft>>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        let response = ui.vertical(|ui| {
+            ui.weak(tr!("collection-drop-hint"));
+            let mut move_up: Option<usize> = None;
+            let mut remove: Option<usize> = None;
+            let num_entries = self.entries.len();
+            self.entries.iter_mut().enumerate().for_each(|(idx, entry)| {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("entry-number", number = idx + 1));
+                    if ui.add_enabled(idx > 0, egui::Button::new("⬆")).clicked() {
+                        move_up = Some(idx);
+                    }
+                    if ui.add_enabled(idx + 1 < num_entries, egui::Button::new("⬇")).clicked() {
+                        move_up = Some(idx + 1);
+                    }
+                    if ui.button("🗙").clicked() {
+                        remove = Some(idx);
+                    }
+                });
+                group_frame(ui, |ui| {
+                    entry.draw_and_parse(ui, id.with(idx));
+                });
+            });
+            if let Some(idx) = move_up {
+                self.entries.swap(idx - 1, idx);
+            }
+            if let Some(idx) = remove {
+                self.entries.remove(idx);
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button(tr!("add-entry")).clicked() {
+                    self.entries.push(Default::default());
+                }
+                if ui.button(tr!("add-packages")).clicked() {
+                    let paths = rfd::FileDialog::new().add_filter(tr!("model-packages"), &PACKAGE_EXTENSIONS).pick_files();
+                    self.entries
+                        .extend(paths.unwrap_or_default().into_iter().map(CollectionEntryWidget::from_package));
+                }
+            });
+        });
+        self.handle_drop(ui, response.response.rect);
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.entries.iter().map(|entry| entry.state()).collect()
+    }
+}
+
+impl Restore for CollectionEntryWidget {
+    fn dump(&self) -> serde_yaml::Value {
+        let mut fields = serde_yaml::Mapping::new();
+        fields.insert("staging_id".into(), self.staging_id.dump());
+        fields.insert("raw_source".into(), self.raw_source.clone().into());
+        fields.into()
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        if let Some(id) = value.get("staging_id") {
+            self.staging_id.restore(ctx, id);
+        }
+        if let Some(source) = value.get("raw_source").and_then(|source| source.as_str()) {
+            self.raw_source = source.to_owned();
+            // the summary is read again from packages that are still where they were
+            let path = PathBuf::from(source);
+            self.summary = path.is_file().then(|| PackageSummary::read(&path).map_err(GuiError::from));
+        }
+    }
+}
+
+impl CollectionWidget {
+    /// Adds the packages dropped onto `rect`, leaving any other dropped files to the widgets they were meant for
+    fn handle_drop(&mut self, ui: &egui::Ui, rect: egui::Rect) {
+        let (dragging, pointer) = ui.ctx().input(|i| (!i.raw.hovered_files.is_empty(), i.pointer.latest_pos()));
+        if !pointer.is_some_and(|pos| rect.contains(pos)) {
+            return;
+        }
+        if dragging {
+            ui.painter().rect_stroke(rect.expand(2.0), 2.0, ui.visuals().selection.stroke);
+        }
+        let is_package = |path: &Path| {
+            path.extension()
+                .is_some_and(|ext| PACKAGE_EXTENSIONS.iter().any(|package_ext| ext.eq_ignore_ascii_case(package_ext)))
+        };
+        let dropped: Vec<PathBuf> = ui.ctx().input_mut(|i| {
+            let (packages, others) = std::mem::take(&mut i.raw.dropped_files)
+                .into_iter()
+                .partition(|file| file.path.as_deref().is_some_and(is_package));
+            i.raw.dropped_files = others;
+            packages.into_iter().filter_map(|file: egui::DroppedFile| file.path).collect()
+        });
+        self.entries.extend(dropped.into_iter().map(CollectionEntryWidget::from_package));
+    }
+}
+
+restore_fields!(CollectionWidget { entries });
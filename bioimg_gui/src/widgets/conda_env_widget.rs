@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use bioimg_spec::runtime::CondaEnv;
+
+use super::{
+    error_display::show_error,
+    file_widget::{FileWidget, FileWidgetState, ParsedFile},
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::TaskStatus;
+
+impl ParsedFile for Result<CondaEnv> {
+    fn parse(path: PathBuf, _ctx: egui::Context, _status: &TaskStatus) -> Self {
+        let raw = std::fs::read_to_string(&path)?;
+        Ok(CondaEnv::from_yaml(&raw)?)
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        match self {
+            Ok(env) => {
+                ui.vertical(|ui| {
+                    let summary = tr!("conda-env-summary", conda = env.dependencies.len(), pip = env.pip.len());
+                    ui.weak(summary).on_hover_text(env.to_yaml());
+                    if env.channels.is_empty() {
+                        ui.label(egui::RichText::new(tr!("conda-env-no-channels")).color(ui.visuals().warn_fg_color));
+                    } else {
+                        ui.label(tr!("conda-env-channels", channels = env.channels.join(", ")));
+                    }
+                    for dependency in &env.dependencies {
+                        ui.monospace(format!("• {dependency}"));
+                    }
+                    if !env.pip.is_empty() {
+                        ui.label(tr!("conda-env-pip"));
+                        for dependency in &env.pip {
+                            ui.monospace(format!("    • {dependency}"));
+                        }
+                    }
+                });
+            }
+            Err(err) => show_error(ui, err.to_string()),
+        }
+    }
+}
+
+/// An `environment.yaml` with the dependencies of the model weights
+pub type CondaEnvWidget = FileWidget<Result<CondaEnv>>;
+
+/// The picked environment, or `None` if none was picked. Fails if the file can't be used (yet).
+pub fn picked_env(state: &FileWidgetState<Result<CondaEnv>>) -> Result<Option<&CondaEnv>> {
+    match state {
+        FileWidgetState::Empty => Ok(None),
+        FileWidgetState::Finished { value, .. } => Ok(Some(value.as_ref().map_err(Clone::clone)?)),
+        FileWidgetState::Loading { path, .. } => {
+            Err(GuiError::new(tr!("attachment-still-loading", path = path.to_string_lossy().into_owned())))
+        }
+        FileWidgetState::Failed { path, .. } => {
+            Err(GuiError::new(tr!("attachment-not-loaded", path = path.to_string_lossy().into_owned())))
+        }
+    }
+}
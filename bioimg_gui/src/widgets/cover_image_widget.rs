@@ -1,22 +1,197 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use crate::result::Result;
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::{Task, TaskStatus};
 use bioimg_spec::runtime as rt;
+use bioimg_spec::runtime::model_package::PackageFile;
+use bioimg_spec::util::progress;
 use egui::{load::SizedTexture, ImageSource};
 
 use super::{
     error_display::show_error,
-    file_widget::{FileWidget, ParsedFile},
-    util::DynamicImageExt,
+    file_widget::{FileWidget, FileWidgetState, ParsedFile},
+    util::{format_size, DynamicImageExt},
+    StagingVec,
 };
 
+/// Size in pixels of the texture covers are previewed with, twice the size they are drawn at so
+/// that they stay sharp on high density screens
+const THUMBNAIL_SIZE: u32 = 100;
+/// Size in points of the longest side of the image shown by [CoverCropEditor]
+const CROP_EDITOR_SIZE: f32 = 300.0;
+/// Height in points of the covers in [show_cover_gallery]
+const GALLERY_HEIGHT: f32 = 60.0;
+
+/// Picks the part of an image that isn't a valid cover to use instead: a rectangle with one of the allowed
+/// ratios, which is dragged around the image and resized. The part picked is re-encoded until it is small enough.
+struct CoverCropEditor {
+    image: Arc<image::DynamicImage>,
+    texture: egui::TextureHandle,
+    open: bool,
+    ratio: f32,
+    /// Size of the crop relative to the biggest one with `ratio`
+    scale: f32,
+    /// Center of the crop, relative to the size of the image
+    center: egui::Pos2,
+    pending_fit: Option<Task<Result<rt::CoverImage>>>,
+    error: Option<GuiError>,
+}
+
+impl CoverCropEditor {
+    fn new(image: image::DynamicImage, name: String, ctx: &egui::Context) -> Self {
+        let crop = rt::CoverImage::nearest_allowed_crop(image.width(), image.height());
+        let size = (CROP_EDITOR_SIZE * ctx.pixels_per_point()).round() as u32;
+        Self {
+            texture: image.to_egui_thumbnail_handle(name, size, ctx),
+            image: Arc::new(image),
+            open: true,
+            ratio: crop.ratio,
+            scale: 1.0,
+            center: egui::pos2(0.5, 0.5),
+            pending_fit: None,
+            error: None,
+        }
+    }
+
+    /// The top left corner and size, in pixels of the image, of the part picked
+    fn region(&self) -> (u32, u32, rt::CoverCrop) {
+        let (width, height) = (self.image.width(), self.image.height());
+        let crop = rt::CoverCrop::largest(self.ratio, width, height).scaled(self.scale);
+        let corner = |center: f32, size: u32, crop_size: u32| {
+            (center * size as f32 - crop_size as f32 / 2.0).round().clamp(0.0, size.saturating_sub(crop_size) as f32) as u32
+        };
+        (corner(self.center.x, width, crop.width), corner(self.center.y, height, crop.height), crop)
+    }
+
+    /// Returns the cover cut out of the picked region, once it has been re-encoded
+    fn show(&mut self, ui: &mut egui::Ui) -> Option<rt::CoverImage> {
+        let mut fitted = None;
+        if self.pending_fit.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_fit.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok(cover) => {
+                    fitted = Some(cover);
+                    self.open = false;
+                }
+                Err(err) => self.error = Some(err),
+            }
+        }
+        if !self.open {
+            if ui.button(tr!("adjust-crop")).clicked() {
+                self.open = true;
+            }
+            return fitted;
+        }
+
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                for ratio in rt::CoverImage::ALLOWED_WIDTH_TO_HEIGHT_RATIOS {
+                    ui.radio_value(&mut self.ratio, ratio, format!("{ratio}:1"));
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.scale, 0.1..=1.0)
+                        .text(tr!("crop-size"))
+                        .custom_formatter(|scale, _| format!("{:.0}%", scale * 100.0)),
+                );
+            });
+
+            let (width, height) = (self.image.width() as f32, self.image.height() as f32);
+            let display = egui::vec2(width, height) * (CROP_EDITOR_SIZE / width.max(height));
+            let (rect, response) = ui.allocate_exact_size(display, egui::Sense::drag());
+            let response = response.on_hover_text(tr!("crop-hint"));
+            if response.dragged() {
+                self.center += response.drag_delta() / rect.size();
+            }
+            // keeps the center where the clamped region actually is, so that dragging past an edge doesn't build up
+            let (x, y, crop) = self.region();
+            let uv = egui::Rect::from_min_size(
+                egui::pos2(x as f32 / width, y as f32 / height),
+                egui::vec2(crop.width as f32 / width, crop.height as f32 / height),
+            );
+            self.center = uv.center();
+
+            let painter = ui.painter_at(rect);
+            let full = egui::Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(1.0, 1.0));
+            painter.image(self.texture.id(), rect, full, egui::Color32::from_gray(90));
+            let crop_rect = egui::Rect::from_min_size(rect.min + uv.min.to_vec2() * rect.size(), uv.size() * rect.size());
+            painter.image(self.texture.id(), crop_rect, uv, egui::Color32::WHITE);
+            painter.rect_stroke(crop_rect, 0.0, ui.visuals().selection.stroke);
+            ui.weak(tr!("crop-region", width = crop.width, height = crop.height));
+
+            ui.horizontal(|ui| {
+                if self.pending_fit.is_some() {
+                    ui.spinner();
+                    ui.weak(tr!("fitting-cover-image"));
+                    return;
+                }
+                if ui.button(tr!("use-crop")).clicked() {
+                    self.error = None;
+                    let image = Arc::clone(&self.image);
+                    self.pending_fit = Some(Task::spawn(tr!("fitting-cover-image"), ui.ctx().clone(), move |_| {
+                        Ok(rt::CoverImage::fit_region(&image, x, y, crop)?)
+                    }));
+                }
+                if ui.button(tr!("cancel")).clicked() {
+                    self.open = false;
+                }
+            });
+            if let Some(err) = &self.error {
+                show_error(ui, err);
+            }
+        });
+        fitted
+    }
+}
+
 pub struct GuiCoverImage {
     path: PathBuf,
     contents: rt::CoverImage,
+    /// Whether the picked file was cropped or shrunk to be usable as a cover
+    fitted: bool,
+    /// Lets the user pick another crop of a picked file that had to be fitted
+    crop_editor: Option<CoverCropEditor>,
     context: egui::Context,
     texture_handle: egui::TextureHandle,
 }
 
+impl GuiCoverImage {
+    /// The cover as it goes into the package, fitted if it had to be, named after the picked file
+    pub fn package_file(&self) -> (PathBuf, PackageFile) {
+        let encoded = self.contents.encoded();
+        let extension = image::guess_format(encoded)
+            .ok()
+            .and_then(|format| format.extensions_str().first().copied())
+            .unwrap_or("png");
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        (format!("{stem}.{extension}").into(), PackageFile::Bytes(encoded.to_vec()))
+    }
+
+    /// The thumbnail scaled to `height`, keeping its aspect ratio
+    fn thumbnail(&self, height: f32) -> egui::Image<'static> {
+        let size = self.texture_handle.size_vec2();
+        egui::Image::new(ImageSource::Texture(SizedTexture {
+            id: self.texture_handle.id(),
+            size: size * (height / size.y.max(1.0)),
+        }))
+    }
+
+    /// The size, ratio and encoded size of the cover, next to the limits the spec sets
+    fn show_details(&self, ui: &mut egui::Ui) {
+        let (width, height) = (self.contents.width(), self.contents.height());
+        let ratio = width as f32 / height as f32;
+        ui.weak(tr!(
+            "cover-image-details",
+            width = width,
+            height = height,
+            ratio = format!("{ratio:.1}"),
+            size = format_size(self.contents.encoded().len() as u64),
+            max_size = format_size(rt::CoverImage::MAX_SIZE_IN_BYTES as u64),
+        ));
+    }
+}
+
 impl Drop for GuiCoverImage {
     fn drop(&mut self) {
         self.context.forget_image(&self.path.to_string_lossy());
@@ -25,27 +200,48 @@ impl Drop for GuiCoverImage {
 
 impl ParsedFile for Result<GuiCoverImage> {
     //FIXME: specific error?
-    fn parse(path: PathBuf, ctx: egui::Context) -> Self {
-        let contents = std::fs::read(&path)?;
-        let cover_image = rt::CoverImage::try_from(contents.as_slice())?;
-        let texture_handle = cover_image.to_egui_texture_handle(path.to_string_lossy(), &ctx);
+    fn parse(path: PathBuf, ctx: egui::Context, status: &TaskStatus) -> Self {
+        // decoding can't tell how far it got, so reading the file is the first half and decoding the second
+        let contents = progress::read_file(&path, &mut |progress| status.report(0.5 * progress.fraction()))?;
+        let (cover_image, crop_editor) = match rt::CoverImage::try_from(contents.as_slice()) {
+            Err(rt::CoverImageParsingError::TooBig { .. } | rt::CoverImageParsingError::BadAspectRatio { .. }) => {
+                // fitted right away, so that the cover can be used even if the crop is never adjusted
+                let original = image::load_from_memory(&contents)?;
+                let fitted = rt::CoverImage::fit(original.clone())?;
+                let editor = CoverCropEditor::new(original, format!("{} (crop)", path.to_string_lossy()), &ctx);
+                (fitted, Some(editor))
+            }
+            cover_image => (cover_image?, None),
+        };
+        let texture_handle = cover_image.to_egui_thumbnail_handle(path.to_string_lossy(), THUMBNAIL_SIZE, &ctx);
         Ok(GuiCoverImage {
             path: path.clone(),
             contents: cover_image,
+            fitted: crop_editor.is_some(),
+            crop_editor,
             context: ctx,
             texture_handle: texture_handle.clone(),
         })
     }
 
-    fn render(&self, ui: &mut egui::Ui, id: egui::Id) {
+    fn render(&mut self, ui: &mut egui::Ui) {
         match self {
             Ok(loaded_cover_image) => {
-                let image_source = ImageSource::Texture(SizedTexture {
-                    id: loaded_cover_image.texture_handle.id(),
-                    size: egui::Vec2 { x: 50.0, y: 50.0 },
-                });
-                let ui_img = egui::Image::new(image_source);
-                ui.add(ui_img);
+                ui.add(loaded_cover_image.thumbnail(50.0));
+                loaded_cover_image.show_details(ui);
+                if loaded_cover_image.fitted {
+                    let (width, height) = (loaded_cover_image.contents.width(), loaded_cover_image.contents.height());
+                    ui.weak(tr!("cover-image-fitted", width = width, height = height));
+                }
+                let cropped = loaded_cover_image.crop_editor.as_mut().and_then(|editor| editor.show(ui));
+                if let Some(cover) = cropped {
+                    loaded_cover_image.texture_handle = cover.to_egui_thumbnail_handle(
+                        loaded_cover_image.path.to_string_lossy(),
+                        THUMBNAIL_SIZE,
+                        &loaded_cover_image.context,
+                    );
+                    loaded_cover_image.contents = cover;
+                }
             }
             Err(err) => show_error(ui, err.to_string()),
         }
@@ -53,3 +249,47 @@ impl ParsedFile for Result<GuiCoverImage> {
 }
 
 pub type CoverImageWidget = FileWidget<Result<GuiCoverImage>>;
+
+/// The covers picked so far, skipping the empty slots. Fails if any of them can't be used (yet).
+pub fn cover_package_files(states: Vec<&FileWidgetState<Result<GuiCoverImage>>>) -> Result<Vec<(PathBuf, PackageFile)>> {
+    let mut files = vec![];
+    for state in states {
+        match state {
+            FileWidgetState::Empty => (),
+            FileWidgetState::Finished { value, .. } => files.push(value.as_ref().map_err(Clone::clone)?.package_file()),
+            FileWidgetState::Loading { path, .. } | FileWidgetState::Failed { path, .. } => {
+                return Err(GuiError::new(tr!("cover-image-not-loaded", path = path.to_string_lossy().into_owned())))
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// A row with every cover that was loaded, in the order they go into the package. The first one is the
+/// primary cover, which model zoos show; clicking another one makes it the primary cover.
+pub fn show_cover_gallery(ui: &mut egui::Ui, covers: &mut StagingVec<CoverImageWidget>) {
+    let loaded = covers.staging.iter().enumerate().filter_map(|(idx, widget)| match widget.loaded_value() {
+        Some(Ok(cover)) => Some((idx, cover)),
+        _ => None,
+    });
+    let mut make_primary = None;
+    ui.horizontal_wrapped(|ui| {
+        for (position, (idx, cover)) in loaded.enumerate() {
+            ui.vertical(|ui| {
+                if position == 0 {
+                    ui.add(cover.thumbnail(GALLERY_HEIGHT));
+                    ui.strong(tr!("cover-image-primary"));
+                } else {
+                    let response = ui.add(cover.thumbnail(GALLERY_HEIGHT).sense(egui::Sense::click()));
+                    if response.on_hover_text(tr!("cover-image-make-primary")).clicked() {
+                        make_primary = Some(idx);
+                    }
+                    ui.weak(tr!("vec-item-number", item = tr!("cover-image"), number = idx + 1));
+                }
+            });
+        }
+    });
+    if let Some(idx) = make_primary {
+        covers.staging[..=idx].rotate_right(1);
+    }
+}
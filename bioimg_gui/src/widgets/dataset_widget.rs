@@ -0,0 +1,71 @@
+use std::num::NonZeroUsize;
+
+use bioimg_spec::rdf::{
+    dataset::{DatasetRdf, DatasetSize},
+    file_reference::FileReference,
+};
+
+use super::{
+    accessibility::field_label, restore_fields, set_value_from, url_widget::StagingUrl, StagingNum, StagingOpt,
+    StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::Result;
+
+/// Fields that only make sense when authoring a dataset resource
+#[derive(Default)]
+pub struct DatasetFieldsWidget {
+    pub staging_source: StagingOpt<StagingUrl>,
+    pub staging_num_samples: StagingOpt<StagingNum<usize, NonZeroUsize>>,
+    pub staging_size_in_bytes: StagingOpt<StagingNum<u64, u64>>,
+}
+
+impl From<&DatasetRdf> for DatasetFieldsWidget {
+    fn from(rdf: &DatasetRdf) -> Self {
+        let size = rdf.size.as_ref();
+        Self {
+            staging_source: rdf.base.source.as_ref().map(StagingUrl::from_reference).into(),
+            staging_num_samples: size.and_then(|size| size.num_samples).map(|num| StagingNum::new(num.get())).into(),
+            staging_size_in_bytes: size.and_then(|size| size.size_in_bytes).map(StagingNum::new).into(),
+        }
+    }
+}
+
+set_value_from!(DatasetFieldsWidget: DatasetRdf);
+
+impl StatefulWidget for DatasetFieldsWidget {
+    type Value<'p> = Result<(Option<FileReference>, Option<DatasetSize>)>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("source-label"));
+            self.staging_source.draw_and_parse(ui, id.with("Source"));
+            ui.end_row();
+
+            field_label(ui, &tr!("number-of-samples-label"));
+            self.staging_num_samples.draw_and_parse(ui, id.with("Number of samples"));
+            ui.end_row();
+
+            field_label(ui, &tr!("size-in-bytes-label"));
+            self.staging_size_in_bytes.draw_and_parse(ui, id.with("Size in bytes"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let source = self.staging_source.state().transpose()?.map(FileReference::from);
+        let num_samples = self.staging_num_samples.state().transpose()?;
+        let size_in_bytes = self.staging_size_in_bytes.state().transpose()?;
+        let size = if num_samples.is_none() && size_in_bytes.is_none() {
+            None
+        } else {
+            Some(DatasetSize {
+                num_samples,
+                size_in_bytes,
+            })
+        };
+        Ok((source, size))
+    }
+}
+
+restore_fields!(DatasetFieldsWidget { staging_source, staging_num_samples, staging_size_in_bytes });
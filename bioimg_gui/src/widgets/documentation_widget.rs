@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use bioimg_spec::rdf::FileReference;
+use egui::{load::SizedTexture, ImageSource, RichText};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    code_editor_widget::CodeEditorWidget,
+    error_display::show_error,
+    file_widget::{FileWidget, PackageFiles, ParsedFile},
+    restore_fields, restore_with_serde,
+    util::DynamicImageExt,
+    StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::Result;
+use crate::task::TaskStatus;
+
+/// Name of the documentation file when it is written next to the rdf
+pub const DEFAULT_DOCUMENTATION_FILE_NAME: &str = "README.md";
+
+/// Images wider or taller than this are shrunk for the preview
+const MAX_PREVIEW_IMAGE_SIZE: u32 = 800;
+
+pub struct GuiMarkdownFile {
+    path: PathBuf,
+    contents: String,
+}
+
+impl ParsedFile for Result<GuiMarkdownFile> {
+    fn parse(path: PathBuf, _ctx: egui::Context, _status: &TaskStatus) -> Self {
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(GuiMarkdownFile { path, contents })
+    }
+
+    fn render(&mut self, ui: &mut egui::Ui) {
+        match self {
+            Ok(file) => {
+                ui.weak(tr!("markdown-line-count", lines = file.contents.lines().count()));
+            }
+            Err(err) => show_error(ui, err.to_string()),
+        }
+    }
+}
+
+enum Block {
+    Heading { level: usize, text: String },
+    Paragraph(String),
+    Bullet { indent: usize, text: String },
+    Numbered { number: String, text: String },
+    Quote(String),
+    Code(String),
+    Rule,
+}
+
+/// Splits markdown into the blocks the preview knows how to show. Anything else ends up in a paragraph.
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut code: Option<Vec<&str>> = None;
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            match code.take() {
+                Some(code_lines) => blocks.push(Block::Code(code_lines.join("\n"))),
+                None => {
+                    flush(&mut paragraph, &mut blocks);
+                    code = Some(vec![]);
+                }
+            }
+            continue;
+        }
+        if let Some(code_lines) = &mut code {
+            code_lines.push(line);
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        let numbered = trimmed
+            .split_once(". ")
+            .filter(|(number, _)| number.chars().all(|c| c.is_ascii_digit()));
+        let block = if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+            continue;
+        } else if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            Block::Heading {
+                level: hashes,
+                text: trimmed[hashes..].trim().to_owned(),
+            }
+        } else if ["---", "***", "___"].contains(&trimmed.trim_end()) {
+            Block::Rule
+        } else if let Some(text) = ["- ", "* ", "+ "].iter().find_map(|bullet| trimmed.strip_prefix(bullet)) {
+            Block::Bullet {
+                indent,
+                text: text.to_owned(),
+            }
+        } else if let Some((number, text)) = numbered.filter(|(number, _)| !number.is_empty()) {
+            Block::Numbered {
+                number: number.to_owned(),
+                text: text.to_owned(),
+            }
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            Block::Quote(text.trim().to_owned())
+        } else {
+            paragraph.push(trimmed);
+            continue;
+        };
+        flush(&mut paragraph, &mut blocks);
+        blocks.push(block);
+    }
+    flush(&mut paragraph, &mut blocks);
+    if let Some(code_lines) = code {
+        blocks.push(Block::Code(code_lines.join("\n")));
+    }
+    blocks
+}
+
+enum Span<'t> {
+    Text { text: &'t str, strong: bool, italics: bool },
+    Code(&'t str),
+    Link { text: &'t str, url: &'t str },
+    Image { alt: &'t str, src: &'t str },
+}
+
+/// `[text](target)` at the start of `rest`, and what comes after it
+fn split_link(rest: &str) -> Option<(&str, &str, &str)> {
+    let (text, after_text) = rest.strip_prefix('[')?.split_once("](")?;
+    let (target, after) = after_text.split_once(')')?;
+    Some((text, target.trim(), after))
+}
+
+fn parse_spans(text: &str) -> Vec<Span<'_>> {
+    let mut spans = vec![];
+    let mut rest = text;
+    let mut plain_start = 0;
+    while !rest.is_empty() {
+        let offset = text.len() - rest.len();
+        let parsed = if let Some(link) = rest.strip_prefix('!').and_then(split_link) {
+            Some((
+                Span::Image {
+                    alt: link.0,
+                    src: link.1,
+                },
+                link.2,
+            ))
+        } else if let Some((link_text, url, after)) = split_link(rest) {
+            Some((Span::Link { text: link_text, url }, after))
+        } else if let Some((code, after)) = rest.strip_prefix('`').and_then(|r| r.split_once('`')) {
+            Some((Span::Code(code), after))
+        } else if let Some((strong, after)) = rest.strip_prefix("**").and_then(|r| r.split_once("**")) {
+            Some((
+                Span::Text {
+                    text: strong,
+                    strong: true,
+                    italics: false,
+                },
+                after,
+            ))
+        } else if let Some((italics, after)) = rest.strip_prefix('*').and_then(|r| r.split_once('*')) {
+            Some((
+                Span::Text {
+                    text: italics,
+                    strong: false,
+                    italics: true,
+                },
+                after,
+            ))
+        } else {
+            None
+        };
+        match parsed {
+            Some((span, after)) => {
+                if plain_start < offset {
+                    spans.push(Span::Text {
+                        text: &text[plain_start..offset],
+                        strong: false,
+                        italics: false,
+                    });
+                }
+                spans.push(span);
+                rest = after;
+                plain_start = text.len() - rest.len();
+            }
+            None => {
+                let next_char_len = rest.chars().next().map_or(1, char::len_utf8);
+                rest = &rest[next_char_len..];
+            }
+        }
+    }
+    if plain_start < text.len() {
+        spans.push(Span::Text {
+            text: &text[plain_start..],
+            strong: false,
+            italics: false,
+        });
+    }
+    spans
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+enum DocumentationSource {
+    #[default]
+    Text,
+    File,
+}
+
+/// The markdown documentation of the resource, typed in or picked from a file, next to a preview of it.
+/// Relative image paths in the preview are looked up among the attachments.
+#[derive(Default)]
+pub struct DocumentationWidget {
+    source: DocumentationSource,
+    editor: CodeEditorWidget,
+    file: FileWidget<Result<GuiMarkdownFile>>,
+    /// Paths of the files attached to the resource
+    attachments: Vec<PathBuf>,
+    images: HashMap<PathBuf, Result<egui::TextureHandle, String>>,
+}
+
+impl DocumentationWidget {
+    /// The documentation of an imported resource, read from where `files` were unpacked. Documentation
+    /// that is only linked to starts out empty.
+    pub fn from_reference(reference: &FileReference, files: &PackageFiles) -> Self {
+        match files.path(reference) {
+            Some(_) => Self {
+                source: DocumentationSource::File,
+                file: files.widget(reference),
+                ..Default::default()
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Documentation typed in as `markdown`
+    pub fn from_markdown(markdown: impl Into<String>) -> Self {
+        Self {
+            editor: CodeEditorWidget::with_raw(markdown),
+            ..Default::default()
+        }
+    }
+
+    /// The files images can be found in. Has to be called before drawing, since the attachments can change any frame.
+    pub fn set_attachments(&mut self, attachments: Vec<PathBuf>) {
+        self.attachments = attachments;
+    }
+
+    /// Name of the documentation, once it is written next to the rdf
+    pub fn file_name(&self) -> String {
+        let picked_name = match (self.source, self.file.loaded_value()) {
+            (DocumentationSource::File, Some(Ok(file))) => file.path.file_name(),
+            _ => None,
+        };
+        picked_name.map_or_else(
+            || DEFAULT_DOCUMENTATION_FILE_NAME.to_owned(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    fn markdown(&self) -> Result<&str> {
+        match (self.source, self.file.loaded_value()) {
+            (DocumentationSource::Text, _) => Ok(self.editor.state()),
+            (DocumentationSource::File, Some(Ok(file))) => Ok(&file.contents),
+            (DocumentationSource::File, Some(Err(err))) => Err(err.clone()),
+            (DocumentationSource::File, None) => Ok(""),
+        }
+    }
+
+    /// Where the image at `src` is on disk: next to the picked markdown file, or among the attachments
+    fn resolve_image(&self, src: &str) -> Option<PathBuf> {
+        if src.contains("://") {
+            return None;
+        }
+        let relative = Path::new(src.trim_start_matches("./"));
+        if let (DocumentationSource::File, Some(Ok(file))) = (self.source, self.file.loaded_value()) {
+            let next_to_file = file.path.parent().map(|dir| dir.join(relative));
+            if let Some(path) = next_to_file.filter(|path| path.is_file()) {
+                return Some(path);
+            }
+        }
+        // attachments are shipped next to the rdf, under their own name
+        let file_name = relative.file_name()?;
+        self.attachments
+            .iter()
+            .find(|path| path.ends_with(relative) || path.file_name() == Some(file_name))
+            .cloned()
+    }
+
+    fn texture(&mut self, ctx: &egui::Context, path: PathBuf) -> &Result<egui::TextureHandle, String> {
+        self.images.entry(path).or_insert_with_key(|path| {
+            let image = image::open(path).map_err(|err| err.to_string())?;
+            let name = format!("documentation {}", path.to_string_lossy());
+            Ok(image.to_egui_thumbnail_handle(name, MAX_PREVIEW_IMAGE_SIZE, ctx))
+        })
+    }
+
+    fn show_spans(&mut self, ui: &mut egui::Ui, text: &str, style: impl Fn(RichText) -> RichText) {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            for span in parse_spans(text) {
+                match span {
+                    Span::Text { text, strong, italics } => {
+                        let mut rich_text = style(RichText::new(text));
+                        if strong {
+                            rich_text = rich_text.strong();
+                        }
+                        if italics {
+                            rich_text = rich_text.italics();
+                        }
+                        ui.label(rich_text);
+                    }
+                    Span::Code(code) => {
+                        ui.code(code);
+                    }
+                    Span::Link { text, url } => {
+                        ui.hyperlink_to(style(RichText::new(text)), url);
+                    }
+                    Span::Image { alt, src } => self.show_image(ui, alt, src),
+                }
+            }
+        });
+    }
+
+    fn show_image(&mut self, ui: &mut egui::Ui, alt: &str, src: &str) {
+        let Some(path) = self.resolve_image(src) else {
+            ui.weak(tr!("markdown-image-not-found", alt = alt.to_owned(), src = src.to_owned()));
+            return;
+        };
+        let max_width = ui.available_width().max(32.0);
+        match self.texture(ui.ctx(), path) {
+            Ok(texture) => {
+                let size = texture.size_vec2();
+                let size = match size.x > max_width {
+                    true => size * (max_width / size.x),
+                    false => size,
+                };
+                ui.add(egui::Image::new(ImageSource::Texture(SizedTexture {
+                    id: texture.id(),
+                    size,
+                })))
+                .on_hover_text(alt);
+            }
+            Err(err) => show_error(ui, tr!("markdown-image-failed", src = src.to_owned(), error = err.clone())),
+        }
+    }
+
+    fn show_preview(&mut self, ui: &mut egui::Ui, markdown: &str) {
+        for block in parse_blocks(markdown) {
+            match block {
+                Block::Heading { level, text } => {
+                    let size = match level {
+                        1 => 24.0,
+                        2 => 20.0,
+                        3 => 17.0,
+                        _ => 15.0,
+                    };
+                    ui.add_space(4.0);
+                    self.show_spans(ui, &text, |rich_text| rich_text.size(size).strong());
+                }
+                Block::Paragraph(text) => {
+                    self.show_spans(ui, &text, |rich_text| rich_text);
+                    ui.add_space(4.0);
+                }
+                Block::Bullet { indent, text } => {
+                    ui.horizontal_top(|ui| {
+                        ui.add_space(indent as f32 * 4.0);
+                        ui.label("•");
+                        self.show_spans(ui, &text, |rich_text| rich_text);
+                    });
+                }
+                Block::Numbered { number, text } => {
+                    ui.horizontal_top(|ui| {
+                        ui.label(format!("{number}."));
+                        self.show_spans(ui, &text, |rich_text| rich_text);
+                    });
+                }
+                Block::Quote(text) => {
+                    ui.horizontal_top(|ui| {
+                        ui.separator();
+                        self.show_spans(ui, &text, |rich_text| rich_text.weak());
+                    });
+                }
+                Block::Code(code) => {
+                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                        ui.add(egui::Label::new(RichText::new(code).monospace()).wrap(false));
+                    });
+                }
+                Block::Rule => {
+                    ui.separator();
+                }
+            }
+        }
+    }
+}
+
+impl StatefulWidget for DocumentationWidget {
+    /// The markdown, or `None` if there is no documentation yet
+    type Value<'p> = Result<Option<&'p str>>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.source, DocumentationSource::Text, tr!("documentation-type-in"));
+                ui.selectable_value(&mut self.source, DocumentationSource::File, tr!("documentation-from-file"));
+            });
+            let markdown = match self.markdown() {
+                Ok(markdown) => markdown.to_owned(),
+                Err(err) => {
+                    show_error(ui, err);
+                    String::new()
+                }
+            };
+            ui.columns(2, |columns| {
+                match self.source {
+                    DocumentationSource::Text => self.editor.draw_and_parse(&mut columns[0], id.with("editor")),
+                    DocumentationSource::File => self.file.draw_and_parse(&mut columns[0], id.with("file")),
+                }
+                columns[1].strong(tr!("preview"));
+                egui::ScrollArea::vertical()
+                    .id_source(id.with("preview"))
+                    .max_height(400.0)
+                    .show(&mut columns[1], |ui| {
+                        if markdown.trim().is_empty() {
+                            ui.weak(tr!("nothing-to-preview"));
+                        }
+                        self.show_preview(ui, &markdown);
+                    });
+            });
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let markdown = self.markdown()?;
+        Ok(Some(markdown).filter(|markdown| !markdown.trim().is_empty()))
+    }
+}
+
+restore_with_serde!(DocumentationSource);
+restore_fields!(DocumentationWidget { source, editor, file });
@@ -0,0 +1,60 @@
+use bioimg_spec::rdf::person::{find_duplicates, Person};
+
+use crate::i18n::tr;
+use crate::result::Result;
+
+/// Groups of entries of a person list that look like the same person. Entries that don't parse
+/// yet are left out, but the indices still point into the whole list.
+pub fn duplicate_groups<P: Person>(states: Vec<Result<P>>) -> Vec<Vec<usize>> {
+    let (indices, people): (Vec<usize>, Vec<P>) = states
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, state)| Some((idx, state.ok()?)))
+        .unzip();
+    find_duplicates(&people)
+        .into_iter()
+        .map(|group| group.into_iter().map(|idx| indices[idx]).collect())
+        .collect()
+}
+
+/// Points out each group of duplicates with a button to merge it, returning the group that
+/// should be merged, if any
+pub fn show_duplicate_people<'g>(ui: &mut egui::Ui, groups: &'g [Vec<usize>], item_name: &str) -> Option<&'g [usize]> {
+    let mut merged = None;
+    for group in groups {
+        ui.horizontal(|ui| {
+            let entries: Vec<String> = group.iter().map(|idx| format!("#{}", idx + 1)).collect();
+            ui.label(
+                egui::RichText::new(tr!(
+                    "possible-duplicates",
+                    item = tr!(item_name),
+                    entries = entries.join(", ")
+                ))
+                .color(ui.visuals().warn_fg_color),
+            );
+            if ui.button(tr!("merge")).clicked() {
+                merged = Some(group.as_slice());
+            }
+        });
+    }
+    merged
+}
+
+/// Folds the entries in `group` into the first of them and removes the others
+pub fn merge_group<W, P>(staging: &mut Vec<W>, group: &[usize], state: impl Fn(&W) -> Result<P>, merge: impl Fn(P, P) -> P)
+where
+    W: for<'p> From<&'p P>,
+{
+    let Ok(people) = group.iter().map(|idx| state(&staging[*idx])).collect::<Result<Vec<P>>>() else {
+        return;
+    };
+    let Some(merged) = people.into_iter().reduce(merge) else {
+        return;
+    };
+    staging[group[0]] = W::from(&merged);
+    let mut removed = group[1..].to_vec();
+    removed.sort_unstable();
+    for idx in removed.into_iter().rev() {
+        staging.remove(idx);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::i18n::tr;
+
+/// Size in points emoji are drawn at in the picker and in the icon preview, the size image icons are drawn at
+pub const ICON_SIZE: f32 = 20.0;
+
+/// Emoji offered by [EmojiPicker], by category, each with the words it can be found by
+const EMOJI_CATEGORIES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "emoji-category-science",
+        &[
+            ("🔬", "microscope science lab"),
+            ("🧬", "dna genetics helix"),
+            ("🦠", "microbe bacteria virus cell"),
+            ("🧫", "petri dish culture bacteria"),
+            ("🧪", "test tube chemistry lab"),
+            ("⚗", "alembic chemistry distill"),
+            ("🔭", "telescope astronomy"),
+            ("🧲", "magnet physics"),
+            ("⚛", "atom physics"),
+            ("🩺", "stethoscope medicine"),
+            ("🩻", "x-ray xray radiology bones"),
+            ("💉", "syringe injection medicine"),
+            ("💊", "pill medicine drug"),
+            ("🩸", "blood drop"),
+            ("🧠", "brain neuro"),
+            ("🫀", "heart anatomy organ"),
+            ("🫁", "lungs anatomy organ"),
+            ("🦷", "tooth dental"),
+            ("🦴", "bone skeleton"),
+            ("👁", "eye vision retina"),
+        ],
+    ),
+    (
+        "emoji-category-nature",
+        &[
+            ("🌱", "seedling plant sprout"),
+            ("🌿", "herb plant leaf"),
+            ("🍃", "leaves leaf"),
+            ("🌳", "tree forest"),
+            ("🌵", "cactus plant"),
+            ("🌸", "blossom flower"),
+            ("🌻", "sunflower flower"),
+            ("🍄", "mushroom fungus"),
+            ("🌍", "earth globe world"),
+            ("🌊", "wave water ocean sea"),
+            ("❄", "snowflake ice cold"),
+            ("🔥", "fire flame"),
+            ("⭐", "star"),
+            ("🌙", "moon night"),
+            ("☀", "sun"),
+            ("💧", "droplet water"),
+        ],
+    ),
+    (
+        "emoji-category-animals",
+        &[
+            ("🐭", "mouse face rodent"),
+            ("🐁", "mouse rodent"),
+            ("🐀", "rat rodent"),
+            ("🐟", "fish zebrafish"),
+            ("🐠", "tropical fish"),
+            ("🪰", "fly drosophila insect"),
+            ("🐝", "bee insect"),
+            ("🦋", "butterfly insect"),
+            ("🐛", "bug worm caterpillar"),
+            ("🪱", "worm elegans nematode"),
+            ("🐌", "snail"),
+            ("🐸", "frog xenopus"),
+            ("🐙", "octopus"),
+            ("🦑", "squid"),
+            ("🦀", "crab"),
+            ("🐢", "turtle"),
+            ("🐒", "monkey primate"),
+            ("🐶", "dog"),
+            ("🐱", "cat"),
+            ("🦕", "dinosaur"),
+        ],
+    ),
+    (
+        "emoji-category-objects",
+        &[
+            ("📷", "camera photo imaging"),
+            ("🎥", "movie camera video"),
+            ("🖼", "picture frame image"),
+            ("🎨", "palette art color"),
+            ("💻", "laptop computer"),
+            ("🖥", "desktop computer"),
+            ("🧮", "abacus count"),
+            ("📊", "bar chart statistics"),
+            ("📈", "chart increasing graph"),
+            ("📐", "triangle ruler measure"),
+            ("📏", "ruler measure"),
+            ("🔍", "magnifying glass search zoom"),
+            ("🧩", "puzzle piece segmentation"),
+            ("⚙", "gear settings"),
+            ("🔧", "wrench tool"),
+            ("📦", "package box"),
+            ("🗂", "dividers folders"),
+            ("📚", "books"),
+            ("🏷", "label tag"),
+            ("🎯", "target bullseye detection"),
+        ],
+    ),
+    (
+        "emoji-category-symbols",
+        &[
+            ("🔴", "red circle"),
+            ("🟠", "orange circle"),
+            ("🟡", "yellow circle"),
+            ("🟢", "green circle"),
+            ("🔵", "blue circle"),
+            ("🟣", "purple circle"),
+            ("⚫", "black circle"),
+            ("⚪", "white circle"),
+            ("🟥", "red square"),
+            ("🟩", "green square"),
+            ("🟦", "blue square"),
+            ("🔶", "orange diamond"),
+            ("🔷", "blue diamond"),
+            ("✨", "sparkles"),
+            ("💡", "light bulb idea"),
+            ("⚡", "lightning voltage"),
+            ("♻", "recycle"),
+            ("➕", "plus add"),
+            ("✅", "check done"),
+            ("❓", "question"),
+        ],
+    ),
+    (
+        "emoji-category-faces",
+        &[
+            ("😀", "grinning smile happy"),
+            ("😎", "sunglasses cool"),
+            ("🤓", "nerd glasses"),
+            ("🤔", "thinking"),
+            ("🤖", "robot ai"),
+            ("👾", "alien monster"),
+            ("👻", "ghost"),
+            ("🙂", "slightly smiling"),
+        ],
+    ),
+];
+
+/// A searchable grid of emoji, by category, shown in a window next to the button that opens it
+#[derive(Default)]
+pub struct EmojiPicker {
+    open: bool,
+    search: String,
+}
+
+impl EmojiPicker {
+    /// Draws the button that opens the picker, and the picker if it is open. Returns the emoji picked this frame.
+    pub fn show(&mut self, ui: &mut egui::Ui, id: egui::Id) -> Option<&'static str> {
+        let button = ui.button("☺").on_hover_text(tr!("pick-emoji"));
+        if button.clicked() {
+            self.open = !self.open;
+        }
+        if !self.open {
+            return None;
+        }
+
+        let mut open = self.open;
+        let mut picked = None;
+        egui::Window::new(tr!("pick-emoji"))
+            .id(id.with("emoji picker"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_pos(button.rect.left_bottom())
+            .show(ui.ctx(), |ui| {
+                let search = ui.add(egui::TextEdit::singleline(&mut self.search).hint_text(tr!("search-emoji")));
+                if button.clicked() {
+                    search.request_focus();
+                }
+                let words: Vec<String> = self.search.split_whitespace().map(str::to_lowercase).collect();
+                let matches = |keywords: &str| {
+                    words.iter().all(|word| keywords.split(' ').any(|keyword| keyword.starts_with(word)))
+                };
+
+                egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    let mut any = false;
+                    for (category, emoji) in EMOJI_CATEGORIES {
+                        let found: Vec<_> = emoji.iter().filter(|(_, keywords)| matches(keywords)).collect();
+                        if found.is_empty() {
+                            continue;
+                        }
+                        any = true;
+                        ui.strong(tr!(category));
+                        ui.horizontal_wrapped(|ui| {
+                            let emoji_width = ICON_SIZE + ui.spacing().button_padding.x * 2.0 + ui.spacing().item_spacing.x;
+                            ui.set_max_width(10.0 * emoji_width);
+                            for (emoji, keywords) in found {
+                                let button = egui::Button::new(egui::RichText::new(*emoji).size(ICON_SIZE)).frame(false);
+                                if ui.add(button).on_hover_text(*keywords).clicked() {
+                                    picked = Some(*emoji);
+                                }
+                            }
+                        });
+                    }
+                    if !any {
+                        ui.weak(tr!("no-emoji-found"));
+                    }
+                });
+            });
+        self.open = open && picked.is_none();
+        picked
+    }
+}
@@ -1,12 +1,18 @@
 use std::fmt::Display;
 
-use super::StatefulWidget;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{accessibility::describe_field, Restore, SetValue, StatefulWidget};
+use crate::i18n::tr;
 
 pub struct EnumWidget<E> {
     value: E,
     search: String,
     popup_open: bool,
+    focus_search: bool,
     lower_case_display_names: Vec<String>,
+    /// Indices of the variants listed above all others
+    pinned: Vec<usize>,
 }
 
 impl<E> Default for EnumWidget<E>
@@ -18,11 +24,53 @@ where
             value: Default::default(),
             search: String::with_capacity(64),
             popup_open: false,
+            focus_search: false,
             lower_case_display_names: <E as strum::VariantNames>::VARIANTS.iter().map(|dn| dn.to_lowercase()).collect(),
+            pinned: vec![],
         }
     }
 }
 
+impl<E> From<E> for EnumWidget<E>
+where
+    E: strum::VariantArray + Default + strum::VariantNames
+{
+    fn from(value: E) -> Self {
+        let mut widget = Self::default();
+        widget.set_value(value);
+        widget
+    }
+}
+
+impl<E> SetValue<E> for EnumWidget<E> {
+    fn set_value(&mut self, value: E) {
+        self.value = value;
+    }
+}
+
+impl<E> EnumWidget<E> {
+    /// Indices of the variants whose name contains `lower_search`, pinned ones first
+    fn matches<'s>(&'s self, lower_search: &'s str) -> impl Iterator<Item = usize> + 's {
+        let unpinned = (0..self.lower_case_display_names.len()).filter(|idx| !self.pinned.contains(idx));
+        self.pinned
+            .iter()
+            .copied()
+            .chain(unpinned)
+            .filter(move |idx| self.lower_case_display_names[*idx].contains(lower_search))
+    }
+}
+
+impl<E: strum::VariantArray + PartialEq> EnumWidget<E> {
+    /// Lists `pinned` above all other variants, in that order
+    pub fn with_pinned(mut self, pinned: &[E]) -> Self {
+        self.pinned = pinned
+            .iter()
+            .filter_map(|value| <E as strum::VariantArray>::VARIANTS.iter().position(|variant| variant == value))
+            .collect();
+        self
+    }
+}
+
 impl<E> StatefulWidget for EnumWidget<E>
 where
     E: strum::VariantArray + strum::VariantNames + Display + Clone
@@ -30,8 +78,11 @@ where
     type Value<'p> = E where E: 'p;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
-        if ui.button(&self.value.to_string()).clicked() {
+        let response = ui.button(self.value.to_string());
+        describe_field(ui, &response, egui::WidgetInfo::labeled(egui::WidgetType::ComboBox, &self.value), None);
+        if response.clicked() {
             self.popup_open = !self.popup_open;
+            self.focus_search = self.popup_open;
         }
         if !self.popup_open {
             return;
@@ -48,31 +99,51 @@ where
                 }
                 egui::Frame::popup(&ui.ctx().style()).show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        ui.heading("Pick one");
+                        ui.heading(tr!("pick-one"));
                         if ui.button("🗙").clicked() {
                             self.popup_open = false;
                         }
                     });
                     ui.horizontal(|ui| {
                         ui.label("🔎 ");
-                        ui.text_edit_singleline(&mut self.search);
+                        let search_response = ui.text_edit_singleline(&mut self.search);
+                        if self.focus_search {
+                            search_response.request_focus();
+                            self.focus_search = false;
+                        }
+                        // Enter on the search field picks the first match
+                        if search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            let lower_search = self.search.to_lowercase();
+                            let first_match = self.matches(&lower_search).next();
+                            if let Some(idx) = first_match {
+                                self.popup_open = false;
+                                self.value = <E as strum::VariantArray>::VARIANTS[idx].clone();
+                                self.search.clear();
+                            }
+                        }
                     });
                     ui.separator();
                     ui.add_space(10.0);
 
                     let lower_search = self.search.to_lowercase();
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        self.lower_case_display_names
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, lower_variant_name)| lower_variant_name.contains(&lower_search))
-                            .for_each(|(idx, _)| {
-                                if ui.button(<E as strum::VariantNames>::VARIANTS[idx]).clicked() {
-                                    self.popup_open = false;
-                                    self.value = <E as strum::VariantArray>::VARIANTS[idx].clone();
-                                    self.search.clear();
-                                }
-                            });
+                        let mut picked = None;
+                        let mut listed_pinned = false;
+                        for idx in self.matches(&lower_search) {
+                            let is_pinned = self.pinned.contains(&idx);
+                            if listed_pinned && !is_pinned {
+                                ui.separator();
+                            }
+                            listed_pinned = is_pinned;
+                            if ui.button(<E as strum::VariantNames>::VARIANTS[idx]).clicked() {
+                                picked = Some(idx);
+                            }
+                        }
+                        if let Some(idx) = picked {
+                            self.popup_open = false;
+                            self.value = <E as strum::VariantArray>::VARIANTS[idx].clone();
+                            self.search.clear();
+                        }
                     })
                 });
             });
@@ -82,3 +153,15 @@ where
         self.value.clone()
     }
 }
+
+impl<E: Serialize + DeserializeOwned> Restore for EnumWidget<E> {
+    fn dump(&self) -> serde_yaml::Value {
+        serde_yaml::to_value(&self.value).unwrap_or_default()
+    }
+
+    fn restore(&mut self, _ctx: &egui::Context, value: &serde_yaml::Value) {
+        if let Ok(restored) = serde_yaml::from_value(value.clone()) {
+            self.value = restored;
+        }
+    }
+}
@@ -1,10 +1,23 @@
 use std::fmt::Display;
 
 pub fn show_error(ui: &mut egui::Ui, message: impl Display){
-    ui.label(egui::RichText::new(message.to_string()).color(egui::Color32::RED));
+    ui.label(egui::RichText::new(message.to_string()).color(ui.visuals().error_fg_color));
 }
-pub fn show_if_error<T, E: Display>(ui: &mut egui::Ui, result: &Result<T, E>){
-    if let Err(ref err) = result{
-        show_error(ui, err)
+fn errors_revealed_id() -> egui::Id {
+    egui::Id::new("Errors Revealed")
+}
+
+/// Makes every field show its error, including the ones nobody has edited yet
+pub fn reveal_all_errors(ctx: &egui::Context) {
+    ctx.data_mut(|data| data.insert_temp(errors_revealed_id(), true));
+}
+
+/// The error a field should show: none until it has been `touched`, unless all errors were revealed
+pub fn visible_error<'r, T, E>(ui: &egui::Ui, touched: bool, result: &'r Result<T, E>) -> Option<&'r E> {
+    let revealed = ui.ctx().data(|data| data.get_temp::<bool>(errors_revealed_id()).unwrap_or(false));
+    if touched || revealed {
+        result.as_ref().err()
+    } else {
+        None
     }
-}
\ No newline at end of file
+}
@@ -3,10 +3,20 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use bioimg_spec::rdf::model::data_type::DataType;
+use bioimg_spec::rdf::Sha256;
+use bioimg_spec::util::progress;
+use bioimg_spec::runtime::{image_to_array, ImageAxis, ImageConversionError};
 use egui::{load::SizedTexture, ImageSource};
 
-use super::{error_display::show_error, file_widget::ParsedFile};
+use super::{
+    accessibility::field_label, enum_widget::EnumWidget, error_display::show_error, file_widget::ParsedFile,
+    tensor_axis_widget::AxisType,
+    SetValue, StatefulWidget,
+};
+use crate::i18n::tr;
 use crate::result::{GuiError, Result};
+use crate::task::{Task, TaskStatus};
 
 macro_rules! impl_NpyArray_try_read {
     ($($element_type:ident),+) => {
@@ -19,9 +29,10 @@ macro_rules! impl_NpyArray_try_read {
             }
 
             impl NpyArray {
-                fn try_read(npy_path: &Path) -> Result<Self> {
+                /// Reads the contents of an npy file, whose `npy_path` is only used to report errors with
+                pub fn try_from_bytes(bytes: &[u8], npy_path: &Path) -> Result<Self> {
                     $(
-                        match ndarray_npy::read_npy::<_, ndarray::ArrayD<$element_type>>(npy_path) {
+                        match <ndarray::ArrayD<$element_type> as ndarray_npy::ReadNpyExt>::read_npy(bytes) {
                             Ok(arr) => return Ok(Self::[<Array $element_type:upper>](arr)),
                             Err(err) => match err {
                                 ndarray_npy::ReadNpyError::WrongDescriptor(_) => (),
@@ -51,6 +62,8 @@ impl_NpyArray_try_read!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
 pub struct GuiNpyArray {
     path: PathBuf,
     contents: NpyArray,
+    /// Hashed when loaded, for the `sha256` of the test tensor
+    sha256: Sha256,
     context: egui::Context,
     texture_handle: Option<egui::TextureHandle>,
 }
@@ -71,17 +84,20 @@ impl Drop for GuiNpyArray {
 }
 
 impl ParsedFile for Result<GuiNpyArray> {
-    fn parse(path: PathBuf, ctx: egui::Context) -> Self {
-        let npy_array = NpyArray::try_read(&path)?;
+    fn parse(path: PathBuf, ctx: egui::Context, status: &TaskStatus) -> Self {
+        let bytes = progress::read_file(&path, &mut |progress| status.report(progress.fraction()))?;
+        let npy_array = NpyArray::try_from_bytes(&bytes, &path)?;
+        let sha256 = Sha256::digest(&bytes);
         Ok(GuiNpyArray {
             path: path.clone(),
             contents: npy_array,
+            sha256,
             context: ctx,
             texture_handle: None, //FIXME: try to make it into an image
         })
     }
 
-    fn render(&self, ui: &mut egui::Ui, _id: egui::Id) {
+    fn render(&mut self, ui: &mut egui::Ui) {
         let loaded_cover_image = match self {
             Ok(loaded_cover_image) => loaded_cover_image,
             Err(err) => {
@@ -113,6 +129,285 @@ impl ParsedFile for Result<GuiNpyArray> {
                     }
                     acc
                 });
-        ui.weak(format!("C-order shape: [{shape_str}]"));
+        ui.weak(tr!("c-order-shape", shape = shape_str));
+    }
+
+    fn sha256(&self) -> Option<Sha256> {
+        self.as_ref().ok().map(|array| array.sha256)
+    }
+}
+
+const IMAGE_AXES: [ImageAxis; 4] = [ImageAxis::Y, ImageAxis::X, ImageAxis::Channel, ImageAxis::Singleton];
+
+fn image_axis_label(axis: ImageAxis) -> String {
+    match axis {
+        ImageAxis::Y => tr!("image-dimension-y"),
+        ImageAxis::X => tr!("image-dimension-x"),
+        ImageAxis::Channel => tr!("image-dimension-channel"),
+        ImageAxis::Singleton => tr!("image-dimension-singleton"),
+    }
+}
+
+/// Guesses what each of the declared `axes` takes from the image: the channel axis the channels, the space
+/// axes named y and x (or else the last two space axes) the rows and columns, and every other axis nothing
+fn guess_image_axes(axes: &[(AxisType, String)]) -> Vec<ImageAxis> {
+    let space: Vec<usize> = (0..axes.len()).filter(|idx| axes[*idx].0 == AxisType::Space).collect();
+    let named = |name: &str| space.iter().copied().find(|idx| axes[*idx].1.trim().eq_ignore_ascii_case(name));
+    let x = named("x").or(space.last().copied());
+    let y = named("y").or(space.iter().rev().copied().find(|idx| Some(*idx) != x));
+    let channel = axes.iter().position(|(axis_type, _)| *axis_type == AxisType::Channel);
+    (0..axes.len())
+        .map(|idx| match Some(idx) {
+            i if i == y => ImageAxis::Y,
+            i if i == x => ImageAxis::X,
+            i if i == channel => ImageAxis::Channel,
+            _ => ImageAxis::Singleton,
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    #[default]
+    None,
+    /// Scaled so that the lowest value becomes 0 and the highest 1
+    MinMax,
+    /// Shifted and scaled to a mean of 0 and a standard deviation of 1
+    ZeroMeanUnitVariance,
+}
+
+impl Normalization {
+    const ALL: [Self; 3] = [Self::None, Self::MinMax, Self::ZeroMeanUnitVariance];
+
+    fn label(self) -> String {
+        match self {
+            Self::None => tr!("normalization-none"),
+            Self::MinMax => tr!("normalization-min-max"),
+            Self::ZeroMeanUnitVariance => tr!("normalization-zero-mean-unit-variance"),
+        }
+    }
+
+    fn apply(self, values: &mut ndarray::ArrayD<f32>) {
+        match self {
+            Self::None => (),
+            Self::MinMax => {
+                let (min, max) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| (min.min(*v), max.max(*v)));
+                let range = if max > min { max - min } else { 1.0 };
+                values.mapv_inplace(|v| (v - min) / range);
+            }
+            Self::ZeroMeanUnitVariance => {
+                let mean = values.mean().unwrap_or(0.0);
+                let std = values.std(0.0);
+                let std = if std > 0.0 { std } else { 1.0 };
+                values.mapv_inplace(|v| (v - mean) / std);
+            }
+        }
+    }
+}
+
+/// An example image, with the values it was stored with, e.g. up to 65535 for 16 bit tiffs
+struct ExampleImage {
+    path: PathBuf,
+    image: image::DynamicImage,
+}
+
+impl ExampleImage {
+    fn open(path: PathBuf) -> Result<Self> {
+        let image = image::open(&path)?;
+        Ok(Self { path, image })
+    }
+
+    fn has_alpha(&self) -> bool {
+        self.image.color().has_alpha()
+    }
+}
+
+/// Makes a test tensor out of an example image: its rows, columns and channels go to the declared axes, and the
+/// values are normalized and cast to the declared data type before being saved as `.npy`
+#[derive(Default)]
+pub struct TestTensorGenerator {
+    open: bool,
+    pending_image: Option<Task<Result<ExampleImage>>>,
+    image: Option<Result<ExampleImage>>,
+    dimensions: Vec<ImageAxis>,
+    drop_alpha: bool,
+    normalization: Normalization,
+    data_type: EnumWidget<DataType>,
+    pending_save: Option<Task<Result<PathBuf>>>,
+    error: Option<GuiError>,
+}
+
+impl TestTensorGenerator {
+    /// The values of `image` laid out along the declared axes, as picked in `dimensions`, and normalized
+    fn tensor(&self, image: &ExampleImage) -> Result<ndarray::ArrayD<f32>> {
+        let color = image.image.color();
+        let channel_names: &[&str] = match (image.has_alpha() && self.drop_alpha, color.has_color()) {
+            (true, true) => &["r", "g", "b"],
+            (true, false) => &["gray"],
+            (false, _) => &[],
+        };
+        let mut values = image_to_array(&image.image, &self.dimensions, channel_names).map_err(|err| match err {
+            ImageConversionError::BadAxisMapping => GuiError::new(tr!("image-dimensions-needed")),
+            err => GuiError::from(err),
+        })?;
+        self.normalization.apply(&mut values);
+        Ok(values)
+    }
+
+    /// Draws the button that opens the generator, and the generator if it's open. Returns the path of the `.npy`
+    /// once one is saved. `axes` are the declared axes, by type and the id typed in, and `data_type` the declared one.
+    pub fn show(&mut self, ui: &mut egui::Ui, axes: &[(AxisType, String)], data_type: Option<DataType>) -> Option<PathBuf> {
+        let mut saved = None;
+        if self.pending_image.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_image.take().expect("checked above");
+            self.image = Some(task.join().map_err(GuiError::from).and_then(|result| result));
+        }
+        if self.pending_save.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_save.take().expect("checked above");
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok(path) => {
+                    saved = Some(path);
+                    self.open = false;
+                }
+                Err(err) => self.error = Some(err),
+            }
+        }
+
+        ui.vertical(|ui| {
+            if !self.open {
+                if ui.button(tr!("generate-test-tensor")).clicked() {
+                    self.open = true;
+                    self.drop_alpha = true;
+                    self.dimensions = guess_image_axes(axes);
+                    self.data_type.set_value(data_type.unwrap_or_default());
+                }
+                return;
+            }
+            if self.dimensions.len() != axes.len() {
+                self.dimensions = guess_image_axes(axes);
+            }
+
+            ui.horizontal(|ui| {
+                match &self.image {
+                    _ if self.pending_image.is_some() => {
+                        ui.spinner();
+                    }
+                    Some(Ok(image)) => {
+                        let (width, height) = (image.image.width(), image.image.height());
+                        let channels = image.image.color().channel_count();
+                        ui.label(image.path.to_string_lossy());
+                        ui.weak(tr!("example-image-size", width = width, height = height, channels = channels));
+                    }
+                    Some(Err(err)) => show_error(ui, err),
+                    None => {
+                        ui.label(tr!("none"));
+                    }
+                }
+                if ui.button(tr!("open-example-image")).clicked() {
+                    let picked = rfd::FileDialog::new()
+                        .add_filter(tr!("images"), &["png", "tif", "tiff", "jpg", "jpeg", "bmp"])
+                        .pick_file();
+                    if let Some(path) = picked {
+                        self.pending_image = Some(Task::spawn(
+                            tr!("loading-file", path = path.to_string_lossy().into_owned()),
+                            ui.ctx().clone(),
+                            move |_| ExampleImage::open(path),
+                        ));
+                    }
+                }
+            });
+
+            egui::Grid::new(ui.id().with("dimensions")).num_columns(2).show(ui, |ui| {
+                for (idx, ((_, axis_id), dimension)) in axes.iter().zip(self.dimensions.iter_mut()).enumerate() {
+                    let name = if axis_id.trim().is_empty() { idx.to_string() } else { axis_id.clone() };
+                    ui.strong(&name);
+                    egui::ComboBox::new(ui.id().with(idx), "")
+                        .selected_text(image_axis_label(*dimension))
+                        .show_ui(ui, |ui| {
+                            for option in IMAGE_AXES {
+                                ui.selectable_value(dimension, option, image_axis_label(option));
+                            }
+                        });
+                    ui.end_row();
+                }
+            });
+            if self.image.as_ref().is_some_and(|image| image.as_ref().is_ok_and(ExampleImage::has_alpha)) {
+                ui.checkbox(&mut self.drop_alpha, tr!("drop-alpha-channel"));
+            }
+            ui.horizontal(|ui| {
+                field_label(ui, &tr!("normalization-label"));
+                for option in Normalization::ALL {
+                    ui.radio_value(&mut self.normalization, option, option.label());
+                }
+            });
+            ui.horizontal(|ui| {
+                field_label(ui, &tr!("data-type-label"));
+                self.data_type.draw_and_parse(ui, ui.id().with("data type"));
+            });
+
+            ui.horizontal(|ui| {
+                if self.pending_save.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                let image = self.image.as_ref().and_then(|image| image.as_ref().ok());
+                if ui.add_enabled(image.is_some(), egui::Button::new(tr!("save-test-tensor"))).clicked() {
+                    self.error = None;
+                    match image.map(|image| self.tensor(image)) {
+                        Some(Ok(tensor)) => self.start_save(ui.ctx(), tensor, image.map(|image| image.path.clone())),
+                        Some(Err(err)) => self.error = Some(err),
+                        None => (),
+                    }
+                }
+                if ui.button(tr!("cancel")).clicked() {
+                    self.open = false;
+                }
+            });
+            if let Some(err) = &self.error {
+                show_error(ui, err);
+            }
+        });
+        saved
+    }
+
+    fn start_save(&mut self, ctx: &egui::Context, tensor: ndarray::ArrayD<f32>, image_path: Option<PathBuf>) {
+        let stem = image_path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "test_tensor".into());
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("npy", &["npy"])
+            .set_file_name(format!("{stem}.npy"))
+            .save_file()
+        else {
+            return;
+        };
+        let data_type = self.data_type.state();
+        let description = tr!("saving-test-tensor", path = path.to_string_lossy().into_owned());
+        self.pending_save = Some(Task::spawn(description, ctx.clone(), move |_| {
+            write_as(&path, &tensor, data_type)?;
+            Ok(path)
+        }));
+    }
+}
+
+/// Writes `tensor` to `path` as an `.npy` of `data_type`, rounding and clamping the values to what it can hold
+fn write_as(path: &Path, tensor: &ndarray::ArrayD<f32>, data_type: DataType) -> Result<()> {
+    let (min, max) = data_type.value_range();
+    let clamped = |value: f32| (value as f64).round().clamp(min, max);
+    match data_type {
+        DataType::Bool => ndarray_npy::write_npy(path, &tensor.mapv(|v| v != 0.0))?,
+        DataType::Float32 => ndarray_npy::write_npy(path, tensor)?,
+        DataType::Float64 => ndarray_npy::write_npy(path, &tensor.mapv(f64::from))?,
+        DataType::Uint8 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as u8))?,
+        DataType::Uint16 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as u16))?,
+        DataType::Uint32 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as u32))?,
+        DataType::Uint64 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as u64))?,
+        DataType::Int8 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as i8))?,
+        DataType::Int16 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as i16))?,
+        DataType::Int32 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as i32))?,
+        DataType::Int64 => ndarray_npy::write_npy(path, &tensor.mapv(|v| clamped(v) as i64))?,
     }
+    Ok(())
 }
@@ -0,0 +1,68 @@
+//! Finding form fields by their label. Labels drawn with [super::accessibility::field_label] and its
+//! siblings are compared to the query as they are drawn, so the search sees every field that is on screen.
+
+#[derive(Clone, Default)]
+struct SearchState {
+    /// Lowercase, so that matching ignores case
+    query: String,
+    /// The match that is highlighted the most
+    current: usize,
+    /// Whether to scroll to the current match while drawing this frame
+    scroll: bool,
+    /// The matches found so far this frame
+    found: usize,
+    /// How many matches the previous frame found
+    last_found: usize,
+}
+
+fn state_id() -> egui::Id {
+    egui::Id::new("bioimg_gui::field_search")
+}
+
+/// Starts counting the matches of this frame
+pub fn begin_frame(ctx: &egui::Context) {
+    ctx.data_mut(|d| {
+        let state = d.get_temp_mut_or_default::<SearchState>(state_id());
+        state.last_found = std::mem::take(&mut state.found);
+    });
+}
+
+/// Searches for `query` with the match number `current` highlighted, scrolling to that match once it is drawn if `scroll`
+pub fn set_query(ctx: &egui::Context, query: &str, current: usize, scroll: bool) {
+    ctx.data_mut(|d| {
+        let state = d.get_temp_mut_or_default::<SearchState>(state_id());
+        state.query = query.trim().to_lowercase();
+        state.current = current;
+        state.scroll = scroll;
+    });
+}
+
+/// How many labels matched the query in the previous frame
+pub fn match_count(ctx: &egui::Context) -> usize {
+    ctx.data(|d| d.get_temp::<SearchState>(state_id()).map_or(0, |state| state.last_found))
+}
+
+/// Highlights the label in `response` if `text` matches the query
+pub fn note_label(ui: &egui::Ui, response: &egui::Response, text: &str) {
+    let matched = ui.data_mut(|d| {
+        let state = d.get_temp_mut_or_default::<SearchState>(state_id());
+        if state.query.is_empty() || !text.to_lowercase().contains(&state.query) {
+            return None;
+        }
+        state.found += 1;
+        let current = state.current == state.found - 1;
+        Some((current, current && state.scroll))
+    });
+    let Some((current, scroll)) = matched else {
+        return;
+    };
+    if scroll {
+        response.scroll_to_me(Some(egui::Align::Center));
+    }
+    let stroke = if current {
+        egui::Stroke::new(2.0, ui.visuals().warn_fg_color)
+    } else {
+        ui.visuals().selection.stroke
+    };
+    ui.painter().rect_stroke(response.rect.expand(2.0), 2.0, stroke);
+}
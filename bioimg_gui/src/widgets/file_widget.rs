@@ -1,24 +1,142 @@
-use std::{path::PathBuf, thread::JoinHandle};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use super::StatefulWidget;
+use bioimg_spec::rdf::{FileReference, Sha256};
+
+use super::{util::format_size, Restore, StatefulWidget};
+use crate::i18n::tr;
+use crate::task::{Task, TaskError, TaskStatus};
 
 pub trait ParsedFile: Send + 'static {
-    fn parse(path: PathBuf, ctx: egui::Context) -> Self;
-    fn render(&self, ui: &mut egui::Ui, id: egui::Id);
+    /// Runs on a background task, whose `status` long parses report their progress to
+    fn parse(path: PathBuf, ctx: egui::Context, status: &TaskStatus) -> Self;
+    /// Shows the parsed file next to its path, letting the user correct it where that is possible
+    fn render(&mut self, ui: &mut egui::Ui);
+    /// The hash of the file, for files that were hashed while parsing
+    fn sha256(&self) -> Option<Sha256> {
+        None
+    }
 }
 
 pub enum FileWidgetState<V> {
     Empty,
-    Loading { path: PathBuf, promise: JoinHandle<V> },
+    Loading { path: PathBuf, task: Task<V> },
     Finished { path: PathBuf, value: V },
     Failed { path: PathBuf, reason: String },
 }
 
+/// What is known about a file without reading it, to tell whether it changed since it was read
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct FileMetadata {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileMetadata {
+    /// The size and modification time of the file at `path`, or `None` if it can't be found anymore
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// The file behind the state of a [FileWidget], as it was when it started loading
+struct LoadedFile {
+    path: PathBuf,
+    metadata: Option<FileMetadata>,
+    /// When the file on disk was last compared to `metadata`, in seconds of ui time
+    checked_at: f64,
+    changed_on_disk: bool,
+}
+
+impl LoadedFile {
+    /// How often the file on disk is looked at again, in seconds
+    const CHECK_INTERVAL: f64 = 2.0;
+
+    fn show_metadata(&self, ui: &mut egui::Ui) {
+        let Some(metadata) = &self.metadata else {
+            return;
+        };
+        let size = format_size(metadata.size);
+        match metadata.modified {
+            Some(modified) => {
+                let modified = chrono::DateTime::<chrono::Local>::from(modified).format("%Y-%m-%d %H:%M").to_string();
+                ui.weak(tr!("file-metadata", size = size, modified = modified))
+            }
+            None => ui.weak(size),
+        };
+    }
+
+    /// Notices files that were rewritten on disk after they were loaded, e.g. by retraining a model
+    fn check_disk(&mut self, ui: &egui::Ui) {
+        if self.changed_on_disk || self.metadata.map_or(true, |metadata| metadata.modified.is_none()) {
+            return;
+        }
+        let now = ui.input(|i| i.time);
+        if now - self.checked_at >= Self::CHECK_INTERVAL {
+            self.checked_at = now;
+            self.changed_on_disk = FileMetadata::of(&self.path) != self.metadata;
+        }
+        ui.ctx().request_repaint_after(std::time::Duration::from_secs_f64(Self::CHECK_INTERVAL));
+    }
+}
+
 pub struct FileWidget<PF: ParsedFile> {
     state: FileWidgetState<PF>,
+    loaded_file: Option<LoadedFile>,
 }
 
 impl<PF: ParsedFile> FileWidget<PF> {
+    /// Starts parsing the file at `path` in the background, as if it had just been picked
+    pub fn load(&mut self, path: PathBuf, ctx: &egui::Context) {
+        // a file picked while the previous one is still parsing replaces it
+        if let FileWidgetState::Loading { task, .. } = &self.state {
+            task.cancel();
+        }
+        let context = ctx.clone();
+        let task_path = path.clone();
+        self.loaded_file = Some(LoadedFile {
+            metadata: FileMetadata::of(&path),
+            path: path.clone(),
+            checked_at: ctx.input(|i| i.time),
+            changed_on_disk: false,
+        });
+        self.state = FileWidgetState::Loading {
+            task: Task::spawn(
+                tr!("loading-file", path = path.to_string_lossy().into_owned()),
+                context.clone(),
+                move |status| PF::parse(task_path, context, status),
+            ),
+            path,
+        };
+    }
+
+    /// Loads a file dropped from the OS onto `rect` as if it had been picked, and highlights `rect`
+    /// while files are dragged over it. A dropped file is taken by one widget only.
+    fn handle_drop(&mut self, ui: &egui::Ui, rect: egui::Rect) {
+        let (dragging, pointer) = ui.ctx().input(|i| (!i.raw.hovered_files.is_empty(), i.pointer.latest_pos()));
+        if !pointer.is_some_and(|pos| rect.contains(pos)) {
+            return;
+        }
+        if dragging {
+            ui.painter().rect_stroke(rect.expand(2.0), 2.0, ui.visuals().selection.stroke);
+        }
+        let dropped = ui.ctx().input_mut(|i| {
+            let path = i.raw.dropped_files.iter().find_map(|file| file.path.clone());
+            if path.is_some() {
+                i.raw.dropped_files.clear();
+            }
+            path
+        });
+        if let Some(path) = dropped {
+            self.load(path, ui.ctx());
+        }
+    }
+
     pub fn loaded_value(&self) -> Option<&PF> {
         if let FileWidgetState::Finished { value, .. } = &self.state {
             Some(value)
@@ -28,10 +146,37 @@ impl<PF: ParsedFile> FileWidget<PF> {
     }
 }
 
+/// Where the files of an opened package were unpacked to, for loading them back into file widgets
+pub struct PackageFiles {
+    pub ctx: egui::Context,
+    /// The unpacked file of each path in the package
+    pub paths: BTreeMap<PathBuf, PathBuf>,
+}
+
+impl PackageFiles {
+    /// Where the file at `reference` was unpacked; urls and files missing from the package have no path
+    pub fn path(&self, reference: &FileReference) -> Option<PathBuf> {
+        match reference {
+            FileReference::Path(path) => self.paths.get(path).cloned(),
+            FileReference::Url(_) => None,
+        }
+    }
+
+    /// A widget that starts loading the file at `reference`, or an empty one if there is no such file
+    pub fn widget<PF: ParsedFile>(&self, reference: &FileReference) -> FileWidget<PF> {
+        let mut widget = FileWidget::default();
+        if let Some(path) = self.path(reference) {
+            widget.load(path, &self.ctx);
+        }
+        widget
+    }
+}
+
 impl<PF: ParsedFile> Default for FileWidget<PF> {
     fn default() -> Self {
         Self {
             state: FileWidgetState::Empty,
+            loaded_file: None,
         }
     }
 }
@@ -39,56 +184,110 @@ impl<PF: ParsedFile> Default for FileWidget<PF> {
 impl<PF: ParsedFile> StatefulWidget for FileWidget<PF> {
     type Value<'p> = &'p FileWidgetState<PF>;
 
-    fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, id: egui::Id) {
-        ui.horizontal(|ui| {
+    fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, _id: egui::Id) {
+        let mut reload = false;
+        let response = ui.horizontal(|ui| {
             self.state = match std::mem::replace(&mut self.state, FileWidgetState::Empty) {
                 FileWidgetState::Empty => {
-                    ui.label("None");
+                    ui.label(tr!("none"));
                     FileWidgetState::Empty
                 }
                 FileWidgetState::Failed { path, reason } => {
-                    ui.label(format!("Could not load file")); //FIMXE: tooltip with reason?
+                    ui.label(tr!("could-not-load-file")).on_hover_text(&reason);
                     FileWidgetState::Failed { path, reason }
                 }
-                FileWidgetState::Finished { path, value } => {
+                FileWidgetState::Finished { path, mut value } => {
                     ui.label(path.to_string_lossy());
-                    value.render(ui, id.with("value"));
+                    if let Some(loaded_file) = &mut self.loaded_file {
+                        loaded_file.show_metadata(ui);
+                        loaded_file.check_disk(ui);
+                    }
+                    if let Some(sha256) = value.sha256() {
+                        let hash = sha256.to_string();
+                        ui.weak(tr!("file-hash", hash = hash[..12].to_owned())).on_hover_text(hash);
+                    }
+                    value.render(ui);
+                    if self.loaded_file.as_ref().is_some_and(|loaded_file| loaded_file.changed_on_disk) {
+                        let warning = format!("⚠ {}", tr!("file-changed-on-disk"));
+                        ui.label(egui::RichText::new(warning).color(ui.visuals().warn_fg_color));
+                        reload = ui.button(tr!("reload-file")).clicked();
+                    }
                     FileWidgetState::Finished { path, value }
                 }
-                FileWidgetState::Loading { path, promise } => {
-                    ui.ctx().request_repaint();
-                    if promise.is_finished() {
-                        match promise.join() {
-                            Err(_) => FileWidgetState::Failed {
-                                path,
-                                reason: "Could not join thread".into(),
-                            },
+                FileWidgetState::Loading { path, task } => {
+                    if task.is_finished() {
+                        match task.join() {
+                            Err(TaskError::Cancelled) => FileWidgetState::Empty,
+                            Err(err) => {
+                                log::warn!("Loading {} failed: {err}", path.to_string_lossy());
+                                FileWidgetState::Failed {
+                                    path,
+                                    reason: err.to_string(),
+                                }
+                            }
                             Ok(value) => FileWidgetState::Finished { path, value },
                         }
                     } else {
-                        ui.label("Loading...");
-                        FileWidgetState::Loading { path, promise }
+                        // parsing happens on the task's thread; the task repaints once it is done
+                        match task.status().progress() {
+                            Some(progress) => {
+                                ui.add(egui::ProgressBar::new(progress).desired_width(120.0).show_percentage());
+                                ui.ctx().request_repaint();
+                            }
+                            None => {
+                                ui.spinner();
+                            }
+                        }
+                        ui.label(tr!("loading")).on_hover_text(path.to_string_lossy());
+                        if let Some(loaded_file) = &self.loaded_file {
+                            loaded_file.show_metadata(ui);
+                        }
+                        if task.status().is_cancelled() {
+                            ui.weak(tr!("cancelling"));
+                        } else if ui.button(tr!("cancel")).clicked() {
+                            task.cancel();
+                        }
+                        FileWidgetState::Loading { path, task }
                     }
                 }
             };
 
-            if !ui.button("Open...").clicked() {
-                return;
-            }
-            let context = ui.ctx().clone();
-            let path_buf = rfd::FileDialog::new().pick_file(); //FIXME: web? async?
-            self.state = if let Some(pth) = path_buf {
-                FileWidgetState::Loading {
-                    path: pth.clone(),
-                    promise: std::thread::spawn(move || PF::parse(pth, context)),
+            if ui.button(tr!("open")).on_hover_text(tr!("file-drop-hint")).clicked() {
+                match rfd::FileDialog::new().pick_file() { //FIXME: web? async?
+                    Some(path) => self.load(path, ui.ctx()),
+                    None => self.state = FileWidgetState::Empty,
                 }
-            } else {
-                FileWidgetState::Empty
-            };
+            }
         });
+        if let (true, Some(loaded_file)) = (reload, &self.loaded_file) {
+            self.load(loaded_file.path.clone(), ui.ctx());
+        }
+        if matches!(self.state, FileWidgetState::Empty) {
+            self.loaded_file = None;
+        }
+        self.handle_drop(ui, response.response.rect);
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
         &self.state
     }
 }
+
+impl<PF: ParsedFile> Restore for FileWidget<PF> {
+    /// Only the path is kept; the file is parsed again when the draft is opened, and fails then if it is gone
+    fn dump(&self) -> serde_yaml::Value {
+        match &self.state {
+            FileWidgetState::Empty => serde_yaml::Value::Null,
+            FileWidgetState::Loading { path, .. }
+            | FileWidgetState::Finished { path, .. }
+            | FileWidgetState::Failed { path, .. } => path.to_string_lossy().into_owned().into(),
+        }
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        match value.as_str() {
+            Some(path) => self.load(PathBuf::from(path), ctx),
+            None => self.state = FileWidgetState::Empty,
+        }
+    }
+}
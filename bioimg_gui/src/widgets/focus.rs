@@ -0,0 +1,79 @@
+#[derive(Clone, Copy)]
+enum FocusAdvance {
+    /// Enter was pressed on this field last frame
+    From(egui::Id),
+    /// The next field to be drawn should take focus
+    Armed,
+}
+
+fn advance_marker_id() -> egui::Id {
+    egui::Id::new("bioimg_gui::focus_advance")
+}
+
+/// Keyboard handling shared by all form fields: fields scroll into view when tabbed into, and pressing
+/// Enter on a single line field moves focus to the next field drawn, the same way Tab would.
+/// egui only ever moves focus on Tab, so the field that saw the Enter leaves a marker for the next one.
+pub fn handle_form_focus(ui: &egui::Ui, response: &egui::Response, advance_on_enter: bool) {
+    if response.gained_focus() {
+        response.scroll_to_me(None);
+    }
+    if response.has_focus() {
+        ui.data_mut(|d| d.insert_temp(focused_field_id(), (response.id, response.rect)));
+    }
+
+    let marker_id = advance_marker_id();
+    match ui.data(|d| d.get_temp::<FocusAdvance>(marker_id)) {
+        Some(FocusAdvance::Armed) => {
+            response.request_focus();
+            ui.data_mut(|d| d.remove::<FocusAdvance>(marker_id));
+        }
+        Some(FocusAdvance::From(id)) if id == response.id => {
+            ui.data_mut(|d| d.insert_temp(marker_id, FocusAdvance::Armed));
+        }
+        _ => (),
+    }
+
+    if advance_on_enter && response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+        ui.data_mut(|d| d.insert_temp(marker_id, FocusAdvance::From(response.id)));
+        ui.ctx().request_repaint();
+    }
+}
+
+fn focused_field_id() -> egui::Id {
+    egui::Id::new("bioimg_gui::focused_field")
+}
+
+fn error_focus_request_id() -> egui::Id {
+    egui::Id::new("bioimg_gui::focus_first_error")
+}
+
+/// Where the form field that has the keyboard focus was drawn, if one has it
+pub fn focused_field_rect(ui: &egui::Ui) -> Option<egui::Rect> {
+    let (id, rect) = ui.data(|d| d.get_temp::<(egui::Id, egui::Rect)>(focused_field_id()))?;
+    ui.memory(|memory| memory.has_focus(id)).then_some(rect)
+}
+
+/// Moves the focus to the next form field that is drawn, e.g. the first field of an item that was just added
+pub fn focus_next_field(ui: &egui::Ui) {
+    ui.data_mut(|d| d.insert_temp(advance_marker_id(), FocusAdvance::Armed));
+}
+
+/// Moves the focus to the first field drawn this frame that shows an error; see [note_field_error]
+pub fn focus_first_error(ctx: &egui::Context) {
+    let frame_nr = ctx.frame_nr();
+    ctx.data_mut(|d| d.insert_temp(error_focus_request_id(), frame_nr));
+}
+
+/// Lets a field that is showing an error take the focus if [focus_first_error] asked for it. Fields are drawn
+/// in the order they are laid out in, so the first one to call this is the first error in the form.
+pub fn note_field_error(ui: &egui::Ui, response: &egui::Response) {
+    let Some(frame_nr) = ui.data(|d| d.get_temp::<u64>(error_focus_request_id())) else {
+        return;
+    };
+    ui.data_mut(|d| d.remove::<u64>(error_focus_request_id()));
+    // requests nobody answered in the frame they were made in are dropped, so they don't fire on a later error
+    if frame_nr == ui.ctx().frame_nr() {
+        response.request_focus();
+        response.scroll_to_me(Some(egui::Align::Center));
+    }
+}
@@ -1,18 +1,27 @@
 use bioimg_spec::rdf;
+use serde::{Deserialize, Serialize};
 
-use super::{util::DynamicImageExt, StagingString, StatefulWidget};
+use super::{restore_fields, restore_with_serde, util::DynamicImageExt, StagingString, StatefulWidget};
 
-use crate::result::Result;
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::TaskStatus;
 use std::path::PathBuf;
 
 use bioimg_spec::runtime as rt;
+use bioimg_spec::runtime::model_package::PackageFile;
+use bioimg_spec::util::progress;
 use egui::{load::SizedTexture, ImageSource};
 
 use super::{
+    emoji_picker::{EmojiPicker, ICON_SIZE},
     error_display::show_error,
-    file_widget::{FileWidget, ParsedFile},
+    file_widget::{FileWidget, PackageFiles, ParsedFile},
 };
 
+/// Size in pixels of the texture icons are previewed with, twice the size they are drawn at
+const THUMBNAIL_SIZE: u32 = 40;
+
 pub struct GuiIconImage {
     path: PathBuf,
     contents: rt::Icon,
@@ -27,10 +36,11 @@ impl Drop for GuiIconImage {
 }
 
 impl ParsedFile for Result<GuiIconImage> {
-    fn parse(path: PathBuf, ctx: egui::Context) -> Self {
-        let img = image::io::Reader::open(&path)?.decode()?;
+    fn parse(path: PathBuf, ctx: egui::Context, status: &TaskStatus) -> Self {
+        let contents = progress::read_file(&path, &mut |progress| status.report(0.5 * progress.fraction()))?;
+        let img = image::load_from_memory(&contents)?;
         let icon = rt::Icon::try_from(img.clone())?;
-        let texture_handle = img.to_egui_texture_handle(path.to_string_lossy(), &ctx);
+        let texture_handle = img.to_egui_thumbnail_handle(path.to_string_lossy(), THUMBNAIL_SIZE, &ctx);
         Ok(GuiIconImage {
             path: path.clone(),
             contents: icon,
@@ -39,12 +49,12 @@ impl ParsedFile for Result<GuiIconImage> {
         })
     }
 
-    fn render(&self, ui: &mut egui::Ui, id: egui::Id) {
+    fn render(&mut self, ui: &mut egui::Ui) {
         match self {
             Ok(loaded_cover_image) => {
                 let image_source = ImageSource::Texture(SizedTexture {
                     id: loaded_cover_image.texture_handle.id(),
-                    size: egui::Vec2 { x: 20.0, y: 20.0 },
+                    size: egui::Vec2 { x: ICON_SIZE, y: ICON_SIZE },
                 });
                 let ui_img = egui::Image::new(image_source);
                 ui.add(ui_img);
@@ -54,7 +64,7 @@ impl ParsedFile for Result<GuiIconImage> {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 enum InputMode {
     Emoji,
     File,
@@ -66,11 +76,51 @@ impl Default for InputMode {
     }
 }
 
+/// How the rdf refers to an icon, along with the image that goes into the package if it isn't an emoji
+pub type PackagedIcon = (String, Option<(PathBuf, PackageFile)>);
+
 #[derive(Default)]
 pub struct StagingIcon {
-    emoji_icon_widget: StagingString<rdf::Icon>,
+    emoji_icon_widget: StagingString<rdf::EmojiIcon>,
     image_icon_widget: FileWidget<Result<GuiIconImage>>,
     input_mode: InputMode,
+    emoji_picker: EmojiPicker,
+}
+
+impl StagingIcon {
+    /// The icon of an imported resource: an image if `icon` is a file in the package, and an emoji otherwise
+    pub fn from_rdf(icon: &str, files: &PackageFiles) -> Self {
+        let reference = rdf::FileReference::Path(icon.into());
+        match files.path(&reference) {
+            Some(_) => Self {
+                image_icon_widget: files.widget(&reference),
+                input_mode: InputMode::File,
+                ..Default::default()
+            },
+            None => Self {
+                emoji_icon_widget: StagingString::default().with_raw(icon),
+                input_mode: InputMode::Emoji,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// The icon as it is packaged. An emoji field left empty means the resource has no icon.
+    pub fn packaged(&self) -> Result<Option<PackagedIcon>> {
+        match self.input_mode {
+            InputMode::Emoji if self.emoji_icon_widget.raw.trim().is_empty() => Ok(None),
+            InputMode::Emoji => Ok(Some((String::from(self.emoji_icon_widget.state()?), None))),
+            InputMode::File => {
+                self.state()?;
+                let Some(Ok(icon_image)) = self.image_icon_widget.loaded_value() else {
+                    return Err(GuiError::new(tr!("icon-not-loaded")));
+                };
+                let name = icon_image.path.file_name().map(PathBuf::from).unwrap_or_default();
+                let file = PackageFile::Path(icon_image.path.clone());
+                Ok(Some((name.to_string_lossy().into_owned(), Some((name, file)))))
+            }
+        }
+    }
 }
 
 impl StatefulWidget for StagingIcon {
@@ -79,11 +129,19 @@ impl StatefulWidget for StagingIcon {
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.radio_value(&mut self.input_mode, InputMode::Emoji, "Emoji Icon");
-                ui.radio_value(&mut self.input_mode, InputMode::File, "Image File Icon");
+                ui.radio_value(&mut self.input_mode, InputMode::Emoji, tr!("emoji-icon"));
+                ui.radio_value(&mut self.input_mode, InputMode::File, tr!("image-file-icon"));
             });
             if self.input_mode == InputMode::Emoji {
-                self.emoji_icon_widget.draw_and_parse(ui, id.with("Emoji Icon"));
+                ui.horizontal(|ui| {
+                    self.emoji_icon_widget.draw_and_parse(ui, id.with("Emoji Icon"));
+                    if let Some(emoji) = self.emoji_picker.show(ui, id.with("Emoji Picker")) {
+                        self.emoji_icon_widget.set_raw(emoji);
+                    }
+                    if let Ok(emoji) = self.emoji_icon_widget.state() {
+                        ui.label(egui::RichText::new(String::from(emoji)).size(ICON_SIZE));
+                    }
+                });
             }
             if self.input_mode == InputMode::File {
                 self.image_icon_widget.draw_and_parse(ui, id.with("Image File Icon"));
@@ -92,6 +150,16 @@ impl StatefulWidget for StagingIcon {
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
-        unimplemented!("Create a rt::Icon")
+        match self.input_mode {
+            InputMode::Emoji => Ok(rt::Icon::Text(self.emoji_icon_widget.state()?)),
+            InputMode::File => match self.image_icon_widget.loaded_value() {
+                Some(Ok(icon_image)) => Ok(icon_image.contents.clone()),
+                Some(Err(err)) => Err(err.clone()),
+                None => Err(GuiError::new(tr!("icon-not-loaded"))),
+            },
+        }
     }
 }
+
+restore_with_serde!(InputMode);
+restore_fields!(StagingIcon { emoji_icon_widget, image_icon_widget, input_mode });
@@ -1,7 +1,338 @@
 use bioimg_spec::rdf::bounded_string::BoundedString;
+use bioimg_spec::rdf::model::axes::{AxisId, InputAxis};
+use bioimg_spec::rdf::model::tensor_data_descr::TensorData;
+use bioimg_spec::rdf::model::tensor_id::TensorId;
+use bioimg_spec::rdf::model::InputTensorDescr2;
+use bioimg_spec::rdf::non_empty_list::NonEmptyList;
+use bioimg_spec::runtime::NpyHeader;
 
-use super::StagingString;
+use super::{
+    accessibility::{field_label, field_label_with_help, required_field_label_with_help},
+    axis_size_widget::AxisSizeReferenceWidget,
+    attachment_widget::{attached_file, AttachmentWidget},
+    example_tensor_widget::TestTensorGenerator,
+    error_display::{show_error, visible_error},
+    file_widget::{FileWidgetState, PackageFiles},
+    processing_widget::PreprocessingWidget,
+    restore_fields, set_value_from, Restore,
+    tensor_axis_widget::{
+        axis_type_picker, AxisType, BatchAxisWidget, ChannelAxisWidget, IndexAxisWidget, SpaceInputAxisWidget,
+        TimeInputAxisWidget,
+    },
+    tensor_data_widget::TensorDataWidget,
+    tensor_preview::TensorPreview,
+    util::group_frame,
+    InputLines, StagingOpt, StagingString, StagingVec, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::renames::CommittedId;
+use crate::result::{GuiError, Result};
 
+#[derive(Default)]
+pub struct InputAxisWidget {
+    pub axis_type: AxisType,
+    pub batch_widget: BatchAxisWidget,
+    pub channel_widget: ChannelAxisWidget,
+    pub index_widget: IndexAxisWidget,
+    pub time_widget: TimeInputAxisWidget,
+    pub space_widget: SpaceInputAxisWidget,
+    /// The id references to this axis were last pointed at
+    pub committed_id: CommittedId<AxisId>,
+}
+
+impl From<&InputAxis> for InputAxisWidget {
+    fn from(axis: &InputAxis) -> Self {
+        match axis {
+            InputAxis::Batch(axis) => Self {
+                axis_type: AxisType::Batch,
+                batch_widget: axis.into(),
+                ..Default::default()
+            },
+            InputAxis::Channel(axis) => Self {
+                axis_type: AxisType::Channel,
+                channel_widget: axis.into(),
+                ..Default::default()
+            },
+            InputAxis::Index(axis) => Self {
+                axis_type: AxisType::Index,
+                index_widget: axis.into(),
+                ..Default::default()
+            },
+            InputAxis::Time(axis) => Self {
+                axis_type: AxisType::Time,
+                time_widget: axis.into(),
+                ..Default::default()
+            },
+            InputAxis::Space(axis) => Self {
+                axis_type: AxisType::Space,
+                space_widget: axis.into(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+set_value_from!(InputAxisWidget: InputAxis);
+
+impl InputAxisWidget {
+    /// The id typed in for the axis, whether it parses or not
+    pub fn raw_id(&self) -> &str {
+        match self.axis_type {
+            AxisType::Batch => &self.batch_widget.staging_id.raw,
+            AxisType::Channel => &self.channel_widget.staging_id.raw,
+            AxisType::Index => &self.index_widget.staging_id.raw,
+            AxisType::Time => &self.time_widget.staging_id.raw,
+            AxisType::Space => &self.space_widget.staging_id.raw,
+        }
+    }
+
+    /// The field the id of the axis is typed into
+    pub fn id_field_mut(&mut self) -> &mut StagingString<AxisId> {
+        match self.axis_type {
+            AxisType::Batch => &mut self.batch_widget.staging_id,
+            AxisType::Channel => &mut self.channel_widget.staging_id,
+            AxisType::Index => &mut self.index_widget.staging_id,
+            AxisType::Time => &mut self.time_widget.staging_id,
+            AxisType::Space => &mut self.space_widget.staging_id,
+        }
+    }
+
+    /// The tensor and axis the size of the axis is taken from, if it is set to be taken from another one
+    pub fn size_reference_mut(&mut self) -> Option<&mut AxisSizeReferenceWidget> {
+        match self.axis_type {
+            AxisType::Batch | AxisType::Channel => None,
+            AxisType::Index => self.index_widget.staging_size.size_reference_mut(),
+            AxisType::Time => self.time_widget.size_widget.size_reference_mut(),
+            AxisType::Space => self.space_widget.size_widget.size_reference_mut(),
+        }
+    }
+}
+
+impl StatefulWidget for InputAxisWidget {
+    type Value<'p> = Result<InputAxis>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            axis_type_picker(ui, &mut self.axis_type);
+            match self.axis_type {
+                AxisType::Batch => self.batch_widget.draw_and_parse(ui, id.with("batch")),
+                AxisType::Channel => self.channel_widget.draw_and_parse(ui, id.with("channel")),
+                AxisType::Index => self.index_widget.draw_and_parse(ui, id.with("index")),
+                AxisType::Time => self.time_widget.draw_and_parse(ui, id.with("time")),
+                AxisType::Space => self.space_widget.draw_and_parse(ui, id.with("space")),
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(match self.axis_type {
+            AxisType::Batch => InputAxis::Batch(self.batch_widget.state()?),
+            AxisType::Channel => InputAxis::Channel(self.channel_widget.state()?),
+            AxisType::Index => InputAxis::Index(self.index_widget.state()?),
+            AxisType::Time => InputAxis::Time(self.time_widget.state()?),
+            AxisType::Space => InputAxis::Space(self.space_widget.state()?),
+        })
+    }
+}
+
+/// Describes one tensor the model takes, with the steps that prepare it for the network
 pub struct InputTensorWidget {
-    staging_id: StagingString<BoundedString<1, 1023>>,
+    pub staging_id: StagingString<TensorId>,
+    pub staging_description: StagingString<BoundedString<0, 128>>,
+    pub staging_axes: StagingVec<InputAxisWidget>,
+    pub test_tensor: AttachmentWidget,
+    pub sample_tensor: StagingOpt<AttachmentWidget>,
+    pub test_tensor_preview: TensorPreview,
+    pub test_tensor_generator: TestTensorGenerator,
+    axes_guess_error: Option<GuiError>,
+    pub optional: bool,
+    pub staging_data: StagingOpt<TensorDataWidget>,
+    pub staging_preprocessing: PreprocessingWidget,
+    /// The id references to this tensor were last pointed at
+    pub committed_id: CommittedId<TensorId>,
+}
+
+impl Default for InputTensorWidget {
+    fn default() -> Self {
+        Self {
+            staging_id: StagingString::new(InputLines::SingleLine),
+            staging_description: StagingString::new(InputLines::Multiline),
+            staging_axes: StagingVec::new("axis"),
+            test_tensor: Default::default(),
+            sample_tensor: Default::default(),
+            test_tensor_preview: Default::default(),
+            test_tensor_generator: Default::default(),
+            axes_guess_error: None,
+            optional: false,
+            staging_data: Default::default(),
+            staging_preprocessing: Default::default(),
+            committed_id: Default::default(),
+        }
+    }
+}
+
+impl InputTensorWidget {
+    /// The widget for an imported tensor, with its test and sample tensors loading from where `files` were unpacked
+    pub fn from_descr(descr: &InputTensorDescr2, files: &PackageFiles) -> Self {
+        let data = descr.data.as_ref().and_then(|data| {
+            let descrs = data.descrs();
+            if descrs.len() > 1 {
+                log::warn!("Only the first per-channel data description of tensor {} was imported", descr.id);
+            }
+            descrs.first().map(|first| TensorDataWidget::from(*first))
+        });
+        Self {
+            staging_id: StagingString::new(InputLines::SingleLine).with_raw(descr.id.to_string()),
+            staging_description: StagingString::new(InputLines::Multiline).with_raw(descr.description.to_string()),
+            staging_axes: StagingVec {
+                item_name: "axis".into(),
+                staging: descr.axes.iter().map(InputAxisWidget::from).collect(),
+            },
+            test_tensor: files.widget(&descr.test_tensor.source),
+            sample_tensor: descr.sample_tensor.as_ref().map(|sample| files.widget(&sample.source)).into(),
+            test_tensor_preview: Default::default(),
+            test_tensor_generator: Default::default(),
+            axes_guess_error: None,
+            optional: descr.optional,
+            staging_data: data.into(),
+            staging_preprocessing: PreprocessingWidget::from_steps(&descr.preprocessing),
+            committed_id: Default::default(),
+        }
+    }
+
+    /// The test tensor and, if there is one, the sample tensor
+    pub fn attachments(&self) -> impl Iterator<Item = &AttachmentWidget> {
+        std::iter::once(&self.test_tensor).chain(self.sample_tensor.inner())
+    }
+
+    /// Whether the axes are still as a new tensor has them, so that guessing them from the test tensor loses nothing
+    fn axes_unconfigured(&self) -> bool {
+        let untouched = InputAxisWidget::default().dump();
+        self.staging_axes.staging.iter().all(|axis| axis.dump() == untouched)
+    }
+
+    /// Replaces the axes with those guessed from the shape of the test tensor, with its sizes as fixed sizes
+    fn guess_axes(&mut self) {
+        let Some(Ok(test_tensor)) = self.test_tensor.loaded_value() else {
+            return;
+        };
+        match NpyHeader::open(test_tensor.path()) {
+            Ok(header) => {
+                let axes: Vec<InputAxis> = header.guess_axes();
+                self.staging_axes.staging = axes.iter().map(InputAxisWidget::from).collect();
+                self.axes_guess_error = None;
+            }
+            Err(err) => self.axes_guess_error = Some(GuiError::new(tr!("axes-guess-failed", error = err.to_string()))),
+        }
+    }
 }
+
+impl StatefulWidget for InputTensorWidget {
+    type Value<'p> = Result<InputTensorDescr2>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            required_field_label_with_help(ui, &tr!("id-label"), "inputs.id");
+            self.staging_id.draw_and_parse(ui, id.with("id"));
+            ui.end_row();
+
+            field_label_with_help(ui, &tr!("description-label"), "inputs.description");
+            self.staging_description.draw_and_parse(ui, id.with("description"));
+            ui.end_row();
+
+            required_field_label_with_help(ui, &tr!("axes-label"), "inputs.axes");
+            group_frame(ui, |ui| self.staging_axes.draw_and_parse(ui, id.with("axes")));
+            ui.end_row();
+
+            required_field_label_with_help(ui, &tr!("test-tensor-label"), "inputs.test_tensor");
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    self.test_tensor.draw_and_parse(ui, id.with("test tensor"));
+                    let loaded = matches!(self.test_tensor.loaded_value(), Some(Ok(_)));
+                    if loaded
+                        && self.axes_unconfigured()
+                        && ui.button(tr!("infer-axes")).on_hover_text(tr!("infer-axes-hint")).clicked()
+                    {
+                        self.guess_axes();
+                    }
+                });
+                if let Some(err) = &self.axes_guess_error {
+                    show_error(ui, err);
+                }
+            });
+            ui.end_row();
+
+            let axes: Vec<(AxisType, String)> =
+                self.staging_axes.staging.iter().map(|axis| (axis.axis_type, axis.raw_id().to_owned())).collect();
+            if let Some(Ok(test_tensor)) = self.test_tensor.loaded_value() {
+                field_label(ui, &tr!("test-tensor-preview-label"));
+                let axis_names: Vec<String> = axes.iter().map(|(_, id)| id.clone()).collect();
+                self.test_tensor_preview.show(ui, test_tensor.path(), &axis_names);
+                ui.end_row();
+            }
+
+            field_label(ui, &tr!("test-tensor-from-image-label"));
+            let data_type = self.staging_data.inner().map(|data| data.staging_data_type.state());
+            if let Some(path) = self.test_tensor_generator.show(ui, &axes, data_type) {
+                self.test_tensor.load(path, ui.ctx());
+            }
+            ui.end_row();
+
+            field_label_with_help(ui, &tr!("sample-tensor-label"), "inputs.sample_tensor");
+            self.sample_tensor.draw_and_parse(ui, id.with("sample tensor"));
+            ui.end_row();
+
+            field_label(ui, &tr!("optional-input-label"));
+            ui.checkbox(&mut self.optional, tr!("optional-input"));
+            ui.end_row();
+
+            field_label(ui, &tr!("tensor-data-label"));
+            self.staging_data.draw_and_parse(ui, id.with("data"));
+            ui.end_row();
+
+            field_label_with_help(ui, &tr!("preprocessing-label"), "inputs.preprocessing");
+            group_frame(ui, |ui| self.staging_preprocessing.draw_and_parse(ui, id.with("preprocessing")));
+            ui.end_row();
+        });
+        // a picked test tensor counts as editing the tensor, so that what else is missing shows up
+        let touched = !matches!(self.test_tensor.state(), FileWidgetState::Empty);
+        if let Some(err) = visible_error(ui, touched, &self.state()) {
+            show_error(ui, err);
+        }
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let axes = self.staging_axes.state().into_iter().collect::<Result<Vec<_>>>()?;
+        let axes = NonEmptyList::try_from(axes).map_err(|_| GuiError::new(tr!("tensor-no-axes")))?;
+        let test_tensor = attached_file(self.test_tensor.state())?
+            .ok_or_else(|| GuiError::new(tr!("test-tensor-missing")))?;
+        let sample_tensor = match self.sample_tensor.state() {
+            Some(state) => attached_file(state)?,
+            None => None,
+        };
+        let descr = InputTensorDescr2 {
+            id: self.staging_id.state()?,
+            description: self.staging_description.state()?,
+            axes,
+            test_tensor,
+            sample_tensor,
+            optional: self.optional,
+            preprocessing: self.staging_preprocessing.state()?,
+            data: self.staging_data.state().transpose()?.map(TensorData::Whole),
+        };
+        descr.check().map_err(|err| GuiError::new(err.to_string()))?;
+        Ok(descr)
+    }
+}
+
+restore_fields!(InputAxisWidget { axis_type, batch_widget, channel_widget, index_widget, time_widget, space_widget });
+restore_fields!(InputTensorWidget {
+    staging_id,
+    staging_description,
+    staging_axes,
+    test_tensor,
+    sample_tensor,
+    optional,
+    staging_data,
+    staging_preprocessing,
+});
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use bioimg_spec::rdf::identifier::Identifier;
+
+use super::{error_display::show_error, restore_fields, InputLines, StagingString, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+/// One keyword argument: its name and its value, typed in as yaml
+struct KwargRow {
+    staging_key: StagingString<Identifier<String>>,
+    raw_value: String,
+}
+
+impl Default for KwargRow {
+    fn default() -> Self {
+        Self {
+            staging_key: StagingString::new(InputLines::SingleLine),
+            raw_value: String::new(),
+        }
+    }
+}
+
+impl KwargRow {
+    fn is_empty(&self) -> bool {
+        self.staging_key.raw().trim().is_empty() && self.raw_value.trim().is_empty()
+    }
+
+    /// An empty value is yaml for `null`, the same as leaving the value out in a yaml mapping
+    fn value(&self) -> Result<serde_yaml::Value> {
+        serde_yaml::from_str(&self.raw_value).map_err(|err| GuiError::new(tr!("kwarg-bad-value", reason = err.to_string())))
+    }
+}
+
+restore_fields!(KwargRow { staging_key, raw_value });
+
+/// Keyword arguments, one row per argument. Values are yaml, so `3` is a number, `[1, 2]` a list and `abc` a string.
+#[derive(Default)]
+pub struct KwargsWidget {
+    rows: Vec<KwargRow>,
+}
+
+impl From<&serde_yaml::Mapping> for KwargsWidget {
+    fn from(kwargs: &serde_yaml::Mapping) -> Self {
+        let rows = kwargs.iter().map(|(key, value)| {
+            let key = match key {
+                serde_yaml::Value::String(key) => key.clone(),
+                key => serde_yaml::to_string(key).unwrap_or_default().trim_end().to_owned(),
+            };
+            KwargRow {
+                staging_key: StagingString::new(InputLines::SingleLine).with_raw(key),
+                raw_value: serde_yaml::to_string(value).unwrap_or_default().trim_end().to_owned(),
+            }
+        });
+        Self { rows: rows.collect() }
+    }
+}
+
+impl KwargsWidget {
+    fn duplicate_keys(&self) -> Result<()> {
+        let mut keys = HashSet::new();
+        for row in &self.rows {
+            let key = row.staging_key.raw().trim();
+            if !key.is_empty() && !keys.insert(key) {
+                return Err(GuiError::new(tr!("kwarg-duplicate", name = key.to_owned())));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StatefulWidget for KwargsWidget {
+    type Value<'p> = Result<serde_yaml::Mapping>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        let mut remove: Option<usize> = None;
+        ui.vertical(|ui| {
+            if !self.rows.is_empty() {
+                egui::Grid::new(id).num_columns(3).show(ui, |ui| {
+                    ui.weak(tr!("kwarg-name"));
+                    ui.weak(tr!("kwarg-value"));
+                    ui.end_row();
+                    for (idx, row) in self.rows.iter_mut().enumerate() {
+                        row.staging_key.draw_and_parse(ui, id.with(idx).with("key"));
+                        ui.vertical(|ui| {
+                            ui.add(egui::TextEdit::multiline(&mut row.raw_value).desired_rows(1).code_editor());
+                            if let Err(err) = row.value() {
+                                show_error(ui, err);
+                            }
+                        });
+                        if ui.button("🗙").on_hover_text(tr!("kwarg-remove")).clicked() {
+                            remove = Some(idx);
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+            if ui.button(tr!("kwarg-add")).clicked() {
+                self.rows.push(KwargRow::default());
+            }
+            if let Err(err) = self.duplicate_keys() {
+                show_error(ui, err);
+            }
+        });
+        if let Some(idx) = remove {
+            self.rows.remove(idx);
+        }
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.duplicate_keys()?;
+        let mut kwargs = serde_yaml::Mapping::new();
+        for row in self.rows.iter().filter(|row| !row.is_empty()) {
+            let key = row.staging_key.state()?;
+            kwargs.insert(serde_yaml::Value::String(key.to_string()), row.value()?);
+        }
+        Ok(kwargs)
+    }
+}
+
+restore_fields!(KwargsWidget { rows });
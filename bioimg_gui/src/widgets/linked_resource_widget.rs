@@ -0,0 +1,54 @@
+use bioimg_spec::rdf::{LinkedResource, ResourceId, Sha256};
+
+use super::{accessibility::field_label, restore_fields, set_value_from, StagingNum, StagingOpt, StagingString, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::Result;
+
+#[derive(Default)]
+pub struct LinkedResourceWidget {
+    staging_id: StagingString<ResourceId>,
+    staging_sha256: StagingOpt<StagingString<Sha256>>,
+    staging_version_number: StagingOpt<StagingNum<u32, u32>>,
+}
+
+impl From<&LinkedResource> for LinkedResourceWidget {
+    fn from(resource: &LinkedResource) -> Self {
+        Self {
+            staging_id: StagingString::default().with_raw(resource.id.to_string()),
+            staging_sha256: StagingOpt::from_raw(&resource.sha256.as_ref().map(ToString::to_string).unwrap_or_default()),
+            staging_version_number: resource.version_number.map(StagingNum::new).into(),
+        }
+    }
+}
+
+set_value_from!(LinkedResourceWidget: LinkedResource);
+
+impl StatefulWidget for LinkedResourceWidget {
+    type Value<'p> = Result<LinkedResource>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("resource-id-label"));
+            self.staging_id.draw_and_parse(ui, id.with("id"));
+            ui.end_row();
+
+            field_label(ui, &tr!("sha256-label"));
+            self.staging_sha256.draw_and_parse(ui, id.with("sha256"));
+            ui.end_row();
+
+            field_label(ui, &tr!("version-number-label"));
+            self.staging_version_number.draw_and_parse(ui, id.with("version_number"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(LinkedResource {
+            id: self.staging_id.state()?,
+            sha256: self.staging_sha256.state().transpose()?,
+            version_number: self.staging_version_number.state().transpose()?,
+        })
+    }
+}
+
+restore_fields!(LinkedResourceWidget { staging_id, staging_sha256, staging_version_number });
@@ -1,24 +1,141 @@
-use bioimg_spec::rdf::{bounded_string::BoundedString, maintainer::Maintainer, orcid::Orcid, slashless_string::SlashlessString};
+use bioimg_spec::rdf::{
+    bounded_string::BoundedString, maintainer::Maintainer, orcid::Orcid, slashless_string::SlashlessString, GithubUser,
+};
+use bioimg_spec::runtime::{GithubAccount, GithubClient};
 
-use super::{StagingOpt, StagingString, StatefulWidget};
-use crate::result::Result;
+use super::{
+    accessibility::{field_label, field_label_with_help},
+    paste_widget::PasteBox,
+    restore_fields, set_value_from,
+    util::DynamicImageExt,
+    StagingOpt, StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::settings::AuthorProfile;
+use crate::task::Task;
 
+/// Size in points of the avatar shown next to a GitHub user that exists
+const AVATAR_SIZE: f32 = 20.0;
+
+/// A GitHub account that was found, with its avatar if that could be downloaded
+struct FoundAccount {
+    account: GithubAccount,
+    avatar: Option<egui::TextureHandle>,
+}
+
+fn find_account(user: GithubUser, ctx: egui::Context) -> Result<Option<FoundAccount>> {
+    let client = GithubClient::public();
+    let Some(account) = client.lookup(&user)? else {
+        return Ok(None);
+    };
+    // the avatar is only a preview, so an account whose avatar can't be shown is still found
+    let avatar = client
+        .avatar(&account)
+        .ok()
+        .flatten()
+        .and_then(|bytes| image::load_from_memory(&bytes).ok())
+        // twice the size it is drawn at, so that it stays sharp on high density screens
+        .map(|image| image.to_egui_thumbnail_handle(format!("github avatar {}", account.login), 2 * AVATAR_SIZE as u32, &ctx));
+    Ok(Some(FoundAccount { account, avatar }))
+}
+
+#[derive(Default)]
 pub struct StagingMaintainer {
-    github_user: StagingString<BoundedString<1, 1023>>, //FIXME validate this somehow}
+    github_user: StagingString<GithubUser>,
     affiliation: StagingOpt<StagingString<BoundedString<1, 1023>>>,
     email: StagingOpt<StagingString<BoundedString<1, 1023>>>, //FIXME
     orcid: StagingOpt<StagingString<Orcid>>,
     name: StagingOpt<StagingString<SlashlessString<1, 1023>>>,
+    paste: PasteBox<Maintainer>,
+    /// The user name whose account was last looked up on GitHub
+    checked_user: Option<GithubUser>,
+    pending_account: Option<Task<Result<Option<FoundAccount>>>>,
+    account: Option<Result<Option<FoundAccount>>>,
 }
 
-impl Default for StagingMaintainer {
-    fn default() -> Self {
+impl From<&AuthorProfile> for StagingMaintainer {
+    fn from(profile: &AuthorProfile) -> Self {
+        let mut github_user = StagingString::default();
+        github_user.set_raw(profile.github_user.trim());
         Self {
-            github_user: Default::default(),
-            affiliation: Default::default(),
+            github_user,
+            affiliation: StagingOpt::from_raw(&profile.affiliation),
             email: Default::default(),
-            orcid: Default::default(),
-            name: Default::default(),
+            orcid: StagingOpt::from_raw(&profile.orcid),
+            name: StagingOpt::from_raw(&profile.name),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Maintainer> for StagingMaintainer {
+    fn from(maintainer: &Maintainer) -> Self {
+        let mut github_user = StagingString::default();
+        github_user.set_raw(maintainer.github_user.to_string());
+        Self {
+            github_user,
+            affiliation: StagingOpt::from_raw(&maintainer.affiliation.as_ref().map(ToString::to_string).unwrap_or_default()),
+            email: StagingOpt::from_raw(&maintainer.email.as_ref().map(ToString::to_string).unwrap_or_default()),
+            orcid: StagingOpt::from_raw(&maintainer.orcid.clone().map(Into::<String>::into).unwrap_or_default()),
+            name: StagingOpt::from_raw(&maintainer.name.as_ref().map(|name| name.as_str().to_owned()).unwrap_or_default()),
+            ..Default::default()
+        }
+    }
+}
+
+set_value_from!(StagingMaintainer: Maintainer);
+
+impl StagingMaintainer {
+    /// A button that looks the user name up on GitHub, and whether an account with that name was found
+    fn show_github_account(&mut self, ui: &mut egui::Ui) {
+        let user = self.github_user.state().ok();
+        if self.checked_user.is_some() && user != self.checked_user {
+            if let Some(task) = self.pending_account.take() {
+                task.cancel();
+            }
+            self.checked_user = None;
+            self.account = None;
+        }
+        if self.pending_account.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_account.take().expect("checked above");
+            self.account = Some(task.join().map_err(GuiError::from).and_then(|result| result));
+        }
+        if self.pending_account.is_some() {
+            ui.spinner().on_hover_text(tr!("checking-github-user"));
+            return;
+        }
+
+        let warn = |ui: &mut egui::Ui, hint: String| {
+            ui.label(egui::RichText::new("⚠").color(ui.visuals().warn_fg_color)).on_hover_text(hint);
+        };
+        match &self.account {
+            Some(Ok(Some(found))) => {
+                if let Some(avatar) = &found.avatar {
+                    ui.add(egui::Image::new((avatar.id(), egui::vec2(AVATAR_SIZE, AVATAR_SIZE))).rounding(AVATAR_SIZE / 2.0));
+                }
+                let account = &found.account;
+                let name = account.name.clone().unwrap_or_else(|| account.login.clone());
+                let hint = match account.is_organization() {
+                    true => tr!("github-organization-exists", login = account.login.clone(), name = name),
+                    false => tr!("github-user-exists", login = account.login.clone(), name = name),
+                };
+                ui.label("✔").on_hover_text(hint);
+            }
+            Some(Ok(None)) => {
+                let login = user.map(|user| user.as_str().to_owned()).unwrap_or_default();
+                warn(ui, tr!("github-user-missing", login = login));
+            }
+            Some(Err(err)) => warn(ui, tr!("github-user-check-failed", error = err.to_string())),
+            None => {
+                let check = ui.add_enabled(user.is_some(), egui::Button::new(tr!("check-github-user")));
+                if let (true, Some(user)) = (check.on_hover_text(tr!("check-github-user-hint")).clicked(), user) {
+                    let ctx = ui.ctx().clone();
+                    self.checked_user = Some(user.clone());
+                    let task = Task::spawn(tr!("checking-github-user"), ctx.clone(), move |_| find_account(user, ctx));
+                    self.pending_account = Some(task);
+                }
+            }
         }
     }
 }
@@ -27,24 +144,30 @@ impl StatefulWidget for StagingMaintainer {
     type Value<'p> = Result<Maintainer>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        if let Some(maintainer) = self.paste.show(ui, id.with("paste")) {
+            *self = Self::from(&maintainer);
+        }
         egui::Grid::new(id).num_columns(2).show(ui, |ui| {
-            ui.strong("Github User: ");
-            self.github_user.draw_and_parse(ui, id.with("github_user"));
+            field_label(ui, &tr!("github-user-label"));
+            ui.horizontal(|ui| {
+                self.github_user.draw_and_parse(ui, id.with("github_user"));
+                self.show_github_account(ui);
+            });
             ui.end_row();
 
-            ui.strong("Affiliation: ");
+            field_label(ui, &tr!("affiliation-label"));
             self.affiliation.draw_and_parse(ui, id.with("affiliation"));
             ui.end_row();
 
-            ui.strong("Email: ");
+            field_label(ui, &tr!("email-label"));
             self.email.draw_and_parse(ui, id.with("email"));
             ui.end_row();
 
-            ui.strong("Orcid: ");
+            field_label(ui, &tr!("orcid-label"));
             self.orcid.draw_and_parse(ui, id.with("orcid"));
             ui.end_row();
 
-            ui.strong("Name: ");
+            field_label_with_help(ui, &tr!("name-label"), "maintainers.name");
             self.name.draw_and_parse(ui, id.with("name"));
             ui.end_row();
         });
@@ -60,3 +183,5 @@ impl StatefulWidget for StagingMaintainer {
         })
     }
 }
+
+restore_fields!(StagingMaintainer { github_user, affiliation, email, orcid, name });
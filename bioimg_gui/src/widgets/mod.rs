@@ -1,48 +1,235 @@
 use std::fmt::Display;
+use std::ops::RangeInclusive;
 
-use self::{error_display::show_if_error, util::group_frame};
+use serde::{de::DeserializeOwned, Serialize};
+
+use self::{
+    accessibility::describe_field,
+    error_display::{show_error, visible_error},
+    focus::{focus_next_field, focused_field_rect, handle_form_focus, note_field_error},
+    util::{group_frame, text_input_min_size},
+};
+use crate::i18n::tr;
 use crate::result::{GuiError, Result};
 
+pub mod accessibility;
+pub mod attachment_widget;
 pub mod author_widget;
+pub mod badge_widget;
+pub mod backend_config_widget;
 pub mod axis_size_widget;
 pub mod cite_widget;
 pub mod code_editor_widget;
+pub mod collection_widget;
+pub mod conda_env_widget;
 pub mod cover_image_widget;
+pub mod dataset_widget;
+pub mod documentation_widget;
+pub mod duplicate_people_widget;
+pub mod emoji_picker;
 pub mod error_display;
 pub mod example_tensor_widget;
+pub mod field_search;
 pub mod file_widget;
-pub mod functional;
+pub mod focus;
 pub mod icon_widget;
 pub mod input_tensor_widget;
+pub mod kwargs_widget;
+pub mod linked_resource_widget;
 pub mod maintainer_widget;
+pub mod model_family_widget;
+pub mod output_tensor_widget;
+pub mod paste_widget;
+pub mod processing_widget;
+pub mod run_mode_widget;
+pub mod self_test_widget;
+pub mod tags_widget;
 pub mod tensor_axis_widget;
+pub mod tensor_data_widget;
+pub mod tensor_preview;
+pub mod training_data_widget;
+pub mod unknown_resource_widget;
 pub mod url_widget;
 pub mod util;
+pub mod validation_report_widget;
+pub mod version_widget;
+pub mod weights_widget;
 pub mod enum_widget;
+pub mod partner_config_widget;
 
 pub trait StatefulWidget {
     type Value<'p>
     where
         Self: 'p;
+    /// Runs every frame, so widgets only parse their input again once it changed
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id);
+    /// Widgets made of other widgets build this from their children's state on demand instead
+    /// of storing a parsed copy of their own
     fn state<'p>(&'p self) -> Self::Value<'p>;
 }
 
+/// Widgets whose raw contents can be saved to a draft and put back later, whether they parse yet or not
+pub trait Restore {
+    fn dump(&self) -> serde_yaml::Value;
+    /// Puts back what [Restore::dump] wrote. Whatever `value` lacks, e.g. because the draft was saved
+    /// before the field existed, is left as it is.
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value);
+}
+
+/// Widgets that can be filled in from a value, e.g. a field of an imported description, as if it had been
+/// typed in. Unlike [Restore], the value is the parsed one rather than what a draft holds.
+pub trait SetValue<V> {
+    fn set_value(&mut self, value: V);
+}
+
+/// Implements [SetValue] for widgets that are built from references to values with [From]
+macro_rules! set_value_from {
+    ($($widget:ty: $value:ty),+ $(,)?) => {
+        $(impl<'v> $crate::widgets::SetValue<&'v $value> for $widget {
+            fn set_value(&mut self, value: &'v $value) {
+                *self = Self::from(value);
+            }
+        })+
+    };
+}
+pub(crate) use set_value_from;
+
+/// Implements [Restore] for a widget made of other widgets, dumping each listed field under its own name
+macro_rules! restore_fields {
+    ($widget:ty { $($field:ident),+ $(,)? }) => {
+        impl $crate::widgets::Restore for $widget {
+            fn dump(&self) -> serde_yaml::Value {
+                let mut fields = serde_yaml::Mapping::new();
+                $(fields.insert(stringify!($field).into(), $crate::widgets::Restore::dump(&self.$field));)+
+                fields.into()
+            }
+
+            fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+                $(if let Some(field) = value.get(stringify!($field)) {
+                    $crate::widgets::Restore::restore(&mut self.$field, ctx, field);
+                })+
+            }
+        }
+    };
+}
+pub(crate) use restore_fields;
+
+/// Implements [Restore] for plain values that are written to the draft as they are
+macro_rules! restore_with_serde {
+    ($($value:ty),+ $(,)?) => {
+        $(impl $crate::widgets::Restore for $value {
+            fn dump(&self) -> serde_yaml::Value {
+                serde_yaml::to_value(self).unwrap_or_default()
+            }
+
+            fn restore(&mut self, _ctx: &egui::Context, value: &serde_yaml::Value) {
+                if let Ok(restored) = serde_yaml::from_value(value.clone()) {
+                    *self = restored;
+                }
+            }
+        })+
+    };
+}
+pub(crate) use restore_with_serde;
+
+restore_with_serde!(bool, String, Option<String>, Option<serde_yaml::Mapping>);
+
+impl<W: Restore + Default> Restore for Vec<W> {
+    fn dump(&self) -> serde_yaml::Value {
+        self.iter().map(Restore::dump).collect()
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        let Some(items) = value.as_sequence() else {
+            return;
+        };
+        self.resize_with(items.len(), W::default);
+        for (widget, item) in self.iter_mut().zip(items) {
+            widget.restore(ctx, item);
+        }
+    }
+}
+
+/// A number typed or dragged in, parsed into `T` on every frame. This is the widget to use
+/// for any new numeric field; tune how fast dragging changes it with [StagingNum::with_speed].
 pub struct StagingNum<N, T> {
     pub raw: N,
     pub parsed: Result<T>,
+    /// How much the value changes per pixel dragged
+    pub speed: f64,
+    /// The values that can be picked, both included. Bounded numbers get a slider next to the number.
+    pub range: Option<RangeInclusive<N>>,
+    /// What the value is rounded to a multiple of
+    pub step: Option<f64>,
+    /// Shown after the number, like the unit it is in
+    pub suffix: String,
+    /// Whether the user has changed the value; errors are only shown after that
+    touched: bool,
+    /// The raw value [Self::parsed] was parsed from, since `raw` can be set from outside
+    parsed_from: N,
+}
+
+impl<N, T> StagingNum<N, T>
+where
+    N: Clone,
+    T: TryFrom<N>,
+    T::Error: Display + 'static,
+{
+    pub fn new(raw: N) -> Self {
+        Self {
+            parsed: T::try_from(raw.clone()).map_err(|err| GuiError::localized(&err)),
+            parsed_from: raw.clone(),
+            raw,
+            speed: 1.0,
+            range: None,
+            step: None,
+            suffix: String::new(),
+            touched: false,
+        }
+    }
+
+    fn reparse(&mut self) {
+        self.parsed = T::try_from(self.raw.clone()).map_err(|err| GuiError::localized(&err));
+        self.parsed_from = self.raw.clone();
+    }
+
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_range(mut self, range: RangeInclusive<N>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
 }
 
 impl<N, T> Default for StagingNum<N, T>
 where
     N: Default,
     T: TryFrom<N>,
-    T::Error: Display,
+    T::Error: Display + 'static,
 {
     fn default() -> Self {
         Self {
             raw: N::default(),
-            parsed: T::try_from(N::default()).map_err(|err| GuiError::new(err.to_string())),
+            parsed: T::try_from(N::default()).map_err(|err| GuiError::localized(&err)),
+            parsed_from: N::default(),
+            speed: 1.0,
+            range: None,
+            step: None,
+            suffix: String::new(),
+            touched: false,
         }
     }
 }
@@ -51,14 +238,43 @@ impl<N, T> StatefulWidget for StagingNum<N, T>
 where
     N: egui::emath::Numeric,
     T: TryFrom<N> + Clone,
-    T::Error: Display + Clone,
+    T::Error: Display + Clone + 'static,
 {
     type Value<'p> = Result<T> where T: 'p;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, _id: egui::Id) {
-        ui.add(egui::widgets::DragValue::new(&mut self.raw));
-        self.parsed = T::try_from(self.raw.clone()).map_err(|err| GuiError::new(err.to_string()));
-        show_if_error(ui, &self.parsed);
+        let response = match &self.range {
+            Some(range) => {
+                let mut slider = egui::Slider::new(&mut self.raw, range.clone())
+                    .suffix(&self.suffix)
+                    .drag_value_speed(self.speed);
+                if let Some(step) = self.step {
+                    slider = slider.step_by(step);
+                }
+                ui.add(slider)
+            }
+            None => ui.add(egui::widgets::DragValue::new(&mut self.raw).speed(self.speed).suffix(&self.suffix)),
+        };
+        handle_form_focus(ui, &response, false);
+        if response.changed() {
+            // typed in values can be off the step, or outside of the range
+            if let Some(step) = self.step.filter(|step| *step > 0.0) {
+                self.raw = N::from_f64((self.raw.to_f64() / step).round() * step);
+            }
+            if let Some(range) = &self.range {
+                self.raw = N::from_f64(self.raw.to_f64().clamp(range.start().to_f64(), range.end().to_f64()));
+            }
+        }
+        self.touched |= response.changed();
+        if self.raw != self.parsed_from {
+            self.reparse();
+        }
+        let error = visible_error(ui, self.touched, &self.parsed);
+        describe_field(ui, &response, egui::WidgetInfo::drag_value(self.raw.to_f64()), error.map(|err| err as &dyn Display));
+        if let Some(err) = error {
+            note_field_error(ui, &response);
+            show_error(ui, err);
+        }
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
@@ -66,6 +282,37 @@ where
     }
 }
 
+impl<N, T> Restore for StagingNum<N, T>
+where
+    N: Serialize + DeserializeOwned + Clone,
+    T: TryFrom<N>,
+    T::Error: Display + 'static,
+{
+    fn dump(&self) -> serde_yaml::Value {
+        serde_yaml::to_value(&self.raw).unwrap_or_default()
+    }
+
+    fn restore(&mut self, _ctx: &egui::Context, value: &serde_yaml::Value) {
+        if let Ok(raw) = serde_yaml::from_value::<N>(value.clone()) {
+            self.raw = raw;
+            self.reparse();
+        }
+    }
+}
+
+impl<N, T> SetValue<N> for StagingNum<N, T>
+where
+    N: Clone,
+    T: TryFrom<N>,
+    T::Error: Display + 'static,
+{
+    fn set_value(&mut self, value: N) {
+        self.raw = value;
+        self.reparse();
+        self.touched = true;
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum InputLines {
     SingleLine,
@@ -77,19 +324,25 @@ pub struct StagingString<T> {
     raw: String,
     parsed: Result<T>,
     input_lines: InputLines,
+    /// Whether the user has edited the text; errors are only shown after that
+    touched: bool,
+    /// A clash with other fields, e.g. an id that is already taken, set by [crate::cross_checks] before each frame
+    conflict: Option<String>,
 }
 
 impl<T> Default for StagingString<T>
 where
     T: TryFrom<String>,
-    T::Error: Display,
+    T::Error: Display + 'static,
 {
     fn default() -> Self {
         let raw = String::default();
         Self {
             raw: raw.clone(),
-            parsed: T::try_from(raw).map_err(|err| GuiError::new(err.to_string())),
+            parsed: T::try_from(raw).map_err(|err| GuiError::localized(&err)),
             input_lines: InputLines::SingleLine,
+            touched: false,
+            conflict: None,
         }
     }
 }
@@ -97,50 +350,161 @@ where
 impl<T> StagingString<T>
 where
     T: TryFrom<String>,
-    T::Error: Display,
+    T::Error: Display + 'static,
 {
     pub fn new(input_lines: InputLines) -> Self {
         let raw = String::default();
         Self {
             raw: raw.clone(),
-            parsed: T::try_from(raw).map_err(|err| GuiError::new(err.to_string())),
+            parsed: T::try_from(raw).map_err(|err| GuiError::localized(&err)),
             input_lines,
+            touched: false,
+            conflict: None,
         }
     }
+
+    /// Replaces the text as if the user had typed it, so any error shows right away
+    pub fn set_raw(&mut self, raw: impl Into<String>) {
+        self.raw = raw.into();
+        self.touched = true;
+        self.parsed = T::try_from(self.raw.clone()).map_err(|err| GuiError::localized(&err));
+    }
+
+    /// Starts out filled in with `raw`, e.g. the text of an imported value
+    pub fn with_raw(mut self, raw: impl Into<String>) -> Self {
+        self.set_raw(raw);
+        self
+    }
+
+    /// The text as typed in, whether it parses or not
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Like [StatefulWidget::state], without cloning the parsed value
+    pub fn parsed(&self) -> Result<&T> {
+        match (&self.conflict, &self.parsed) {
+            (Some(conflict), Ok(_)) => Err(GuiError::new(conflict.clone())),
+            (_, parsed) => parsed.as_ref().map_err(Clone::clone),
+        }
+    }
+
+    /// Marks the text as clashing with other fields. It is shown and reported as an error for as long as it is set,
+    /// whether the field was touched or not, since it is usually the other field that changed.
+    pub fn set_conflict(&mut self, conflict: Option<String>) {
+        self.conflict = conflict;
+    }
 }
 
 impl<T> StatefulWidget for StagingString<T>
 where
     T: TryFrom<String> + Clone,
-    T::Error: Display,
+    T::Error: Display + 'static,
 {
     type Value<'p> = Result<T> where T: 'p;
 
     fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, _id: egui::Id) {
         ui.horizontal(|ui| {
-            match self.input_lines {
+            let response = match self.input_lines {
                 InputLines::SingleLine => {
-                    ui.add(
-                        //FIXME: any way we can not hardcode this? at least use font size?
-                        egui::TextEdit::singleline(&mut self.raw).min_size(egui::Vec2 { x: 200.0, y: 10.0 }),
-                    );
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.raw).min_size(text_input_min_size(ui)));
+                    handle_form_focus(ui, &response, true);
+                    response
                 }
                 InputLines::Multiline => {
-                    ui.text_edit_multiline(&mut self.raw);
+                    let response = ui.text_edit_multiline(&mut self.raw);
+                    handle_form_focus(ui, &response, false);
+                    response
                 }
+            };
+            // every other way of changing the text parses it right away
+            if response.changed() {
+                self.touched = true;
+                self.parsed = T::try_from(self.raw.clone()).map_err(|err| GuiError::localized(&err));
+            }
+            let conflict = self.conflict.as_ref().filter(|_| self.parsed.is_ok()).map(|conflict| conflict as &dyn Display);
+            let error = visible_error(ui, self.touched, &self.parsed).map(|err| err as &dyn Display).or(conflict);
+            describe_field(ui, &response, egui::WidgetInfo::text_edit(&self.raw, &self.raw), error);
+            if let Some(err) = error {
+                note_field_error(ui, &response);
+                show_error(ui, err);
             }
-            self.parsed = T::try_from(self.raw.clone()).map_err(|err| GuiError::new(err.to_string()));
-            show_if_error(ui, &self.parsed);
         });
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
-        self.parsed.clone()
+        self.parsed().cloned()
+    }
+}
+
+impl<T> Restore for StagingString<T>
+where
+    T: TryFrom<String>,
+    T::Error: Display + 'static,
+{
+    fn dump(&self) -> serde_yaml::Value {
+        self.raw.clone().into()
+    }
+
+    fn restore(&mut self, _ctx: &egui::Context, value: &serde_yaml::Value) {
+        if let Some(raw) = value.as_str() {
+            // restored text counts as typed in, unless there is none
+            self.touched = !raw.trim().is_empty();
+            self.raw = raw.to_owned();
+            self.parsed = T::try_from(self.raw.clone()).map_err(|err| GuiError::localized(&err));
+        }
+    }
+}
+
+impl<T> SetValue<&str> for StagingString<T>
+where
+    T: TryFrom<String>,
+    T::Error: Display + 'static,
+{
+    fn set_value(&mut self, value: &str) {
+        self.set_raw(value);
     }
 }
 
+/// A field that can be left out. Removing it only hides the inner widget, so that adding the field
+/// again brings back what was typed into it.
 #[derive(Clone, Debug, Default)]
-pub struct StagingOpt<Stg: StatefulWidget>(Option<Stg>);
+pub struct StagingOpt<Stg: StatefulWidget> {
+    inner: Option<Stg>,
+    enabled: bool,
+}
+
+impl<Stg: StatefulWidget> StagingOpt<Stg> {
+    /// The inner widget, if the field is filled in
+    pub fn inner(&self) -> Option<&Stg> {
+        self.inner.as_ref().filter(|_| self.enabled)
+    }
+}
+
+impl<T> StagingOpt<StagingString<T>>
+where
+    T: TryFrom<String> + Clone,
+    T::Error: Display + 'static,
+{
+    /// Starts out filled in with `raw`, or empty if `raw` is blank
+    pub fn from_raw(raw: &str) -> Self {
+        if raw.trim().is_empty() {
+            return Self::from(None);
+        }
+        let mut inner = StagingString::default();
+        inner.set_raw(raw.trim());
+        Self::from(Some(inner))
+    }
+}
+
+impl<Stg: StatefulWidget> From<Option<Stg>> for StagingOpt<Stg> {
+    fn from(inner: Option<Stg>) -> Self {
+        Self {
+            enabled: inner.is_some(),
+            inner,
+        }
+    }
+}
 
 impl<Stg> StatefulWidget for StagingOpt<Stg>
 where
@@ -153,32 +517,68 @@ where
 
     fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.horizontal(|ui| {
-            if self.0.is_none() {
-                ui.label("None");
-                if ui.button("Add").clicked() {
-                    self.0 = Some(Stg::default())
+            if !self.enabled {
+                ui.label(tr!("none"));
+                if ui.button(tr!("add")).clicked() {
+                    self.inner.get_or_insert_with(Stg::default);
+                    self.enabled = true;
                 }
             } else {
                 let x_clicked = ui.button("🗙").clicked();
                 group_frame(ui, |ui| {
-                    self.0.as_mut().unwrap().draw_and_parse(ui, id);
+                    self.inner.get_or_insert_with(Stg::default).draw_and_parse(ui, id);
                 });
                 if x_clicked {
-                    self.0.take();
+                    self.enabled = false;
                 }
             }
         });
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
-        self.0.as_ref().map(|inner_widget| inner_widget.state())
+        self.inner().map(|inner_widget| inner_widget.state())
+    }
+}
+
+impl<Stg: StatefulWidget + Restore + Default> Restore for StagingOpt<Stg> {
+    /// A removed field is dumped as left out, without what it would bring back
+    fn dump(&self) -> serde_yaml::Value {
+        self.inner().map(Restore::dump).unwrap_or_default()
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        self.enabled = !value.is_null();
+        if self.enabled {
+            let mut inner = Stg::default();
+            inner.restore(ctx, value);
+            self.inner = Some(inner);
+        }
     }
 }
 
+/// `None` removes the field, forgetting what was in it
+impl<Stg, V> SetValue<Option<V>> for StagingOpt<Stg>
+where
+    Stg: StatefulWidget + SetValue<V> + Default,
+{
+    fn set_value(&mut self, value: Option<V>) {
+        self.enabled = value.is_some();
+        self.inner = value.map(|value| {
+            let mut inner = Stg::default();
+            inner.set_value(value);
+            inner
+        });
+    }
+}
+
+/// Adds an item after the one that has the focus
+const ADD_ITEM_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Enter);
+
 pub struct StagingVec<Stg>
 where
     Stg: StatefulWidget,
 {
+    /// Message id of the name of each item
     pub item_name: String,
     pub staging: Vec<Stg>,
 }
@@ -194,7 +594,7 @@ impl<Stg: StatefulWidget + Default> StagingVec<Stg> {
 
 impl<Stg: StatefulWidget> StatefulWidget for StagingVec<Stg>
 where
-    Stg: Default,
+    Stg: Default + Restore,
 {
     type Value<'p> = Vec<Stg::Value<'p>>
     where
@@ -202,26 +602,108 @@ where
         Stg::Value<'p>: 'p;
 
     fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, id: egui::Id) {
-        let item_name = &self.item_name;
+        let item_name = tr!(&self.item_name);
+        let num_items = self.staging.len();
+        let mut move_up: Option<usize> = None;
+        let mut insert: Option<usize> = None;
+        let mut remove: Option<usize> = None;
+        let mut duplicate: Option<usize> = None;
+        // the item added last frame, whose first field takes the focus
+        let focus_item_id = id.with("focus item");
+        let focus_item = ui.data(|d| d.get_temp::<usize>(focus_item_id));
+        ui.data_mut(|d| d.remove::<usize>(focus_item_id));
+        let focused_rect = focused_field_rect(ui);
         ui.vertical(|ui| {
             self.staging.iter_mut().enumerate().for_each(|(idx, staging_item)| {
-                ui.label(format!("{item_name} #{}", idx + 1));
-                group_frame(ui, |ui| {
-                    staging_item.draw_and_parse(ui, id.with(idx));
+                ui.horizontal(|ui| {
+                    ui.label(tr!("vec-item-number", item = item_name.clone(), number = idx + 1));
+                    if ui.add_enabled(idx > 0, egui::Button::new("⬆")).on_hover_text(tr!("vec-move-up")).clicked() {
+                        move_up = Some(idx);
+                    }
+                    let down_button = egui::Button::new("⬇");
+                    if ui.add_enabled(idx + 1 < num_items, down_button).on_hover_text(tr!("vec-move-down")).clicked() {
+                        move_up = Some(idx + 1);
+                    }
+                    if ui.button("⤒").on_hover_text(tr!("vec-insert-above", item = item_name.clone())).clicked() {
+                        insert = Some(idx);
+                    }
+                    if ui.button("⧉").on_hover_text(tr!("vec-duplicate-item", item = item_name.clone())).clicked() {
+                        duplicate = Some(idx);
+                    }
+                    // there is always one item to fill in, even if it may be left empty
+                    let remove_button = egui::Button::new("🗙");
+                    let remove_hint = tr!("vec-remove-item", item = item_name.clone());
+                    if ui.add_enabled(num_items > 1, remove_button).on_hover_text(remove_hint).clicked() {
+                        remove = Some(idx);
+                    }
                 });
-            });
-            ui.horizontal(|ui| {
-                if ui.button(format!("+ Add {item_name}")).clicked() {
-                    self.staging.resize_with(self.staging.len() + 1, Stg::default);
+                if focus_item == Some(idx) {
+                    focus_next_field(ui);
                 }
-                if ui.button(format!("- Remove {item_name}")).clicked() && self.staging.len() > 1 {
-                    self.staging.resize_with(self.staging.len() - 1, Stg::default);
+                let item_rect = group_frame(ui, |ui| {
+                    staging_item.draw_and_parse(ui, id.with(idx));
+                })
+                .response
+                .rect;
+                // lists within this item had their chance at the shortcut first, since they were drawn first
+                let item_focused = focused_rect.is_some_and(|rect| item_rect.contains_rect(rect));
+                if item_focused && ui.input_mut(|i| i.consume_shortcut(&ADD_ITEM_SHORTCUT)) {
+                    insert = Some(idx + 1);
                 }
             });
+            let add_hint = tr!("vec-add-item-hint", shortcut = ui.ctx().format_shortcut(&ADD_ITEM_SHORTCUT));
+            if ui.button(tr!("vec-add-item", item = item_name.clone())).on_hover_text(add_hint).clicked() {
+                insert = Some(self.staging.len());
+            }
         });
+        if let Some(idx) = move_up {
+            self.staging.swap(idx - 1, idx);
+        }
+        if let Some(idx) = insert {
+            self.staging.insert(idx, Stg::default());
+            ui.data_mut(|d| d.insert_temp(focus_item_id, idx));
+        }
+        if let Some(idx) = duplicate {
+            // goes through a dump like drafts do, so files are picked again and parsed anew instead of shared
+            let mut copy = Stg::default();
+            copy.restore(ui.ctx(), &self.staging[idx].dump());
+            self.staging.insert(idx + 1, copy);
+            ui.data_mut(|d| d.insert_temp(focus_item_id, idx + 1));
+        }
+        if let Some(idx) = remove {
+            self.staging.remove(idx);
+        }
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
         self.staging.iter().map(|item_widget| item_widget.state()).collect()
     }
 }
+
+/// One item per value, in their order
+impl<Stg, I, V> SetValue<I> for StagingVec<Stg>
+where
+    Stg: StatefulWidget + SetValue<V> + Default,
+    I: IntoIterator<Item = V>,
+{
+    fn set_value(&mut self, values: I) {
+        self.staging = values
+            .into_iter()
+            .map(|value| {
+                let mut item = Stg::default();
+                item.set_value(value);
+                item
+            })
+            .collect();
+    }
+}
+
+impl<Stg: StatefulWidget + Restore + Default> Restore for StagingVec<Stg> {
+    fn dump(&self) -> serde_yaml::Value {
+        self.staging.dump()
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        self.staging.restore(ctx, value);
+    }
+}
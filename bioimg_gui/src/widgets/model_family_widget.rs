@@ -0,0 +1,203 @@
+use bioimg_spec::rdf::partner_config::{cellpose::CellposeConfig, stardist::StarDistConfig, stardist::StarDistThresholds};
+use serde::{Deserialize, Serialize};
+
+use super::{accessibility::field_label, restore_fields, restore_with_serde, StagingNum, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::Result;
+
+#[derive(PartialEq, Eq, Copy, Clone, Default, Serialize, Deserialize)]
+pub enum ModelFamily {
+    #[default]
+    Other,
+    StarDist,
+    Cellpose,
+}
+
+/// Drag speed for fields that only make sense between 0 and 1
+const THRESHOLD_SPEED: f64 = 0.01;
+
+pub struct StarDistConfigWidget {
+    pub staging_prob: StagingNum<f64, f64>,
+    pub staging_nms: StagingNum<f64, f64>,
+    preset_config: Option<serde_yaml::Mapping>,
+}
+
+impl From<StarDistConfig> for StarDistConfigWidget {
+    fn from(config: StarDistConfig) -> Self {
+        Self {
+            staging_prob: StagingNum::new(config.thresholds.prob()).with_speed(THRESHOLD_SPEED),
+            staging_nms: StagingNum::new(config.thresholds.nms()).with_speed(THRESHOLD_SPEED),
+            preset_config: config.config,
+        }
+    }
+}
+
+impl Default for StarDistConfigWidget {
+    fn default() -> Self {
+        Self::from(StarDistConfig::versatile_fluo_2d())
+    }
+}
+
+impl StatefulWidget for StarDistConfigWidget {
+    type Value<'p> = Result<StarDistConfig>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("preset-label"));
+            ui.horizontal(|ui| {
+                if ui.button("2D versatile fluo").clicked() {
+                    *self = StarDistConfig::versatile_fluo_2d().into();
+                }
+                if ui.button("2D versatile H&E").clicked() {
+                    *self = StarDistConfig::versatile_he_2d().into();
+                }
+            });
+            ui.end_row();
+
+            field_label(ui, &tr!("probability-threshold-label"));
+            self.staging_prob.draw_and_parse(ui, id.with("Probability threshold"));
+            ui.end_row();
+
+            field_label(ui, &tr!("nms-threshold-label"));
+            self.staging_nms.draw_and_parse(ui, id.with("NMS threshold"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(StarDistConfig {
+            stardist_version: None,
+            thresholds: StarDistThresholds::new(self.staging_prob.state()?, self.staging_nms.state()?)?,
+            config: self.preset_config.clone(),
+        })
+    }
+}
+
+pub struct CellposeConfigWidget {
+    pub model_type: Option<String>,
+    pub staging_diameter: StagingNum<f64, f64>,
+    pub staging_flow_threshold: StagingNum<f64, f64>,
+    pub staging_cellprob_threshold: StagingNum<f64, f64>,
+}
+
+impl From<CellposeConfig> for CellposeConfigWidget {
+    fn from(config: CellposeConfig) -> Self {
+        Self {
+            staging_diameter: StagingNum::new(config.diameter()),
+            staging_flow_threshold: StagingNum::new(config.flow_threshold).with_speed(THRESHOLD_SPEED),
+            staging_cellprob_threshold: StagingNum::new(config.cellprob_threshold).with_speed(THRESHOLD_SPEED),
+            model_type: config.model_type,
+        }
+    }
+}
+
+impl Default for CellposeConfigWidget {
+    fn default() -> Self {
+        Self::from(CellposeConfig::cyto())
+    }
+}
+
+impl StatefulWidget for CellposeConfigWidget {
+    type Value<'p> = Result<CellposeConfig>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("preset-label"));
+            ui.horizontal(|ui| {
+                if ui.button("cyto").clicked() {
+                    *self = CellposeConfig::cyto().into();
+                }
+                if ui.button("nuclei").clicked() {
+                    *self = CellposeConfig::nuclei().into();
+                }
+            });
+            ui.end_row();
+
+            field_label(ui, &tr!("cellpose-diameter-label"));
+            self.staging_diameter.draw_and_parse(ui, id.with("Diameter"));
+            ui.end_row();
+
+            field_label(ui, &tr!("flow-threshold-label"));
+            self.staging_flow_threshold.draw_and_parse(ui, id.with("Flow threshold"));
+            ui.end_row();
+
+            field_label(ui, &tr!("cell-probability-threshold-label"));
+            self.staging_cellprob_threshold.draw_and_parse(ui, id.with("Cell probability threshold"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let mut config = CellposeConfig::new(self.staging_diameter.state()?)?;
+        config.model_type = self.model_type.clone();
+        config.flow_threshold = self.staging_flow_threshold.state()?;
+        config.cellprob_threshold = self.staging_cellprob_threshold.state()?;
+        Ok(config)
+    }
+}
+
+/// Lets users pick a well known model family and fills in its `config.<partner>` section
+#[derive(Default)]
+pub struct ModelFamilyWidget {
+    pub family: ModelFamily,
+    pub stardist: StarDistConfigWidget,
+    pub cellpose: CellposeConfigWidget,
+}
+
+impl ModelFamilyWidget {
+    /// Picks the family whose section the imported `config` has, if it is one that parses
+    pub fn from_config(config: &serde_yaml::Mapping) -> Self {
+        let section = |partner_id: &str| config.get(partner_id).cloned();
+        if let Some(Ok(stardist)) = section("stardist").map(serde_yaml::from_value::<StarDistConfig>) {
+            return Self {
+                family: ModelFamily::StarDist,
+                stardist: stardist.into(),
+                ..Default::default()
+            };
+        }
+        if let Some(Ok(cellpose)) = section("cellpose").map(serde_yaml::from_value::<CellposeConfig>) {
+            return Self {
+                family: ModelFamily::Cellpose,
+                cellpose: cellpose.into(),
+                ..Default::default()
+            };
+        }
+        Self::default()
+    }
+}
+
+impl StatefulWidget for ModelFamilyWidget {
+    /// The entries to add to the rdf `config`, if any
+    type Value<'p> = Result<Option<serde_yaml::Mapping>>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.family, ModelFamily::Other, tr!("other"));
+                ui.radio_value(&mut self.family, ModelFamily::StarDist, "StarDist");
+                ui.radio_value(&mut self.family, ModelFamily::Cellpose, "Cellpose");
+            });
+            match self.family {
+                ModelFamily::Other => (),
+                ModelFamily::StarDist => self.stardist.draw_and_parse(ui, id.with("StarDist")),
+                ModelFamily::Cellpose => self.cellpose.draw_and_parse(ui, id.with("Cellpose")),
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let (partner_id, section) = match self.family {
+            ModelFamily::Other => return Ok(None),
+            ModelFamily::StarDist => ("stardist", serde_yaml::to_value(self.stardist.state()?)?),
+            ModelFamily::Cellpose => ("cellpose", serde_yaml::to_value(self.cellpose.state()?)?),
+        };
+        let mut config = serde_yaml::Mapping::new();
+        config.insert(partner_id.into(), section);
+        Ok(Some(config))
+    }
+}
+
+restore_with_serde!(ModelFamily);
+restore_fields!(StarDistConfigWidget { staging_prob, staging_nms, preset_config });
+restore_fields!(CellposeConfigWidget { model_type, staging_diameter, staging_flow_threshold, staging_cellprob_threshold });
+restore_fields!(ModelFamilyWidget { family, stardist, cellpose });
@@ -0,0 +1,354 @@
+use bioimg_spec::rdf::bounded_string::BoundedString;
+use bioimg_spec::rdf::model::axes::{AxisId, OutputAxis, SpaceOutputAxis, TimeOutputAxis};
+use bioimg_spec::rdf::model::tensor_data_descr::TensorData;
+use bioimg_spec::rdf::model::tensor_id::TensorId;
+use bioimg_spec::rdf::model::OutputTensorDescr;
+use bioimg_spec::rdf::non_empty_list::NonEmptyList;
+use bioimg_spec::runtime::NpyHeader;
+
+use super::{
+    accessibility::{field_label, field_label_with_help, required_field_label, required_field_label_with_help},
+    axis_size_widget::AxisSizeReferenceWidget,
+    attachment_widget::{attached_file, AttachmentWidget},
+    example_tensor_widget::TestTensorGenerator,
+    error_display::{show_error, visible_error},
+    file_widget::{FileWidgetState, PackageFiles},
+    processing_widget::PostprocessingWidget,
+    restore_fields, set_value_from, Restore,
+    tensor_axis_widget::{
+        axis_type_picker, AxisType, BatchAxisWidget, ChannelAxisWidget, IndexAxisWidget, SpaceInputAxisWidget,
+        TimeInputAxisWidget,
+    },
+    tensor_data_widget::TensorDataWidget,
+    tensor_preview::TensorPreview,
+    util::group_frame,
+    InputLines, StagingNum, StagingOpt, StagingString, StagingVec, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::renames::CommittedId;
+use crate::result::{GuiError, Result};
+
+/// One axis of an output tensor. Space and time axes can have a halo, which only makes sense
+/// when their size refers to an input axis.
+#[derive(Default)]
+pub struct OutputAxisWidget {
+    pub axis_type: AxisType,
+    pub batch_widget: BatchAxisWidget,
+    pub channel_widget: ChannelAxisWidget,
+    pub index_widget: IndexAxisWidget,
+    pub time_widget: TimeInputAxisWidget,
+    pub space_widget: SpaceInputAxisWidget,
+    /// The id references to this axis were last pointed at
+    pub committed_id: CommittedId<AxisId>,
+    pub staging_halo: StagingNum<usize, usize>,
+}
+
+impl From<&OutputAxis> for OutputAxisWidget {
+    fn from(axis: &OutputAxis) -> Self {
+        match axis {
+            OutputAxis::Batch(axis) => Self {
+                axis_type: AxisType::Batch,
+                batch_widget: axis.into(),
+                ..Default::default()
+            },
+            OutputAxis::Channel(axis) => Self {
+                axis_type: AxisType::Channel,
+                channel_widget: axis.into(),
+                ..Default::default()
+            },
+            OutputAxis::Index(axis) => Self {
+                axis_type: AxisType::Index,
+                index_widget: axis.into(),
+                ..Default::default()
+            },
+            OutputAxis::Time(axis) => Self {
+                axis_type: AxisType::Time,
+                time_widget: (&axis.base).into(),
+                staging_halo: StagingNum::new(axis.halo),
+                ..Default::default()
+            },
+            OutputAxis::Space(axis) => Self {
+                axis_type: AxisType::Space,
+                space_widget: (&axis.base).into(),
+                staging_halo: StagingNum::new(axis.halo),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+set_value_from!(OutputAxisWidget: OutputAxis);
+
+impl OutputAxisWidget {
+    /// The id typed in for the axis, whether it parses or not
+    pub fn raw_id(&self) -> &str {
+        match self.axis_type {
+            AxisType::Batch => &self.batch_widget.staging_id.raw,
+            AxisType::Channel => &self.channel_widget.staging_id.raw,
+            AxisType::Index => &self.index_widget.staging_id.raw,
+            AxisType::Time => &self.time_widget.staging_id.raw,
+            AxisType::Space => &self.space_widget.staging_id.raw,
+        }
+    }
+
+    /// The field the id of the axis is typed into
+    pub fn id_field_mut(&mut self) -> &mut StagingString<AxisId> {
+        match self.axis_type {
+            AxisType::Batch => &mut self.batch_widget.staging_id,
+            AxisType::Channel => &mut self.channel_widget.staging_id,
+            AxisType::Index => &mut self.index_widget.staging_id,
+            AxisType::Time => &mut self.time_widget.staging_id,
+            AxisType::Space => &mut self.space_widget.staging_id,
+        }
+    }
+
+    /// The tensor and axis the size of the axis is taken from, if it is set to be taken from another one
+    pub fn size_reference_mut(&mut self) -> Option<&mut AxisSizeReferenceWidget> {
+        match self.axis_type {
+            AxisType::Batch | AxisType::Channel => None,
+            AxisType::Index => self.index_widget.staging_size.size_reference_mut(),
+            AxisType::Time => self.time_widget.size_widget.size_reference_mut(),
+            AxisType::Space => self.space_widget.size_widget.size_reference_mut(),
+        }
+    }
+}
+
+impl StatefulWidget for OutputAxisWidget {
+    type Value<'p> = Result<OutputAxis>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            axis_type_picker(ui, &mut self.axis_type);
+            match self.axis_type {
+                AxisType::Batch => self.batch_widget.draw_and_parse(ui, id.with("batch")),
+                AxisType::Channel => self.channel_widget.draw_and_parse(ui, id.with("channel")),
+                AxisType::Index => self.index_widget.draw_and_parse(ui, id.with("index")),
+                AxisType::Time => self.time_widget.draw_and_parse(ui, id.with("time")),
+                AxisType::Space => self.space_widget.draw_and_parse(ui, id.with("space")),
+            }
+            if matches!(self.axis_type, AxisType::Time | AxisType::Space) {
+                ui.horizontal(|ui| {
+                    field_label(ui, &tr!("halo-label"));
+                    self.staging_halo.draw_and_parse(ui, id.with("halo"));
+                });
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(match self.axis_type {
+            AxisType::Batch => OutputAxis::Batch(self.batch_widget.state()?),
+            AxisType::Channel => OutputAxis::Channel(self.channel_widget.state()?),
+            AxisType::Index => OutputAxis::Index(self.index_widget.state()?),
+            AxisType::Time => OutputAxis::Time(TimeOutputAxis {
+                base: self.time_widget.state()?,
+                halo: self.staging_halo.state()?,
+            }),
+            AxisType::Space => OutputAxis::Space(SpaceOutputAxis {
+                base: self.space_widget.state()?,
+                halo: self.staging_halo.state()?,
+            }),
+        })
+    }
+}
+
+/// Describes one tensor the model produces, with the steps that turn the output of the network into it
+pub struct OutputTensorWidget {
+    pub staging_id: StagingString<TensorId>,
+    pub staging_description: StagingString<BoundedString<0, 128>>,
+    pub staging_axes: StagingVec<OutputAxisWidget>,
+    pub test_tensor: AttachmentWidget,
+    pub sample_tensor: StagingOpt<AttachmentWidget>,
+    pub test_tensor_preview: TensorPreview,
+    pub test_tensor_generator: TestTensorGenerator,
+    axes_guess_error: Option<GuiError>,
+    pub staging_data: StagingOpt<TensorDataWidget>,
+    pub staging_postprocessing: PostprocessingWidget,
+    /// The id references to this tensor were last pointed at
+    pub committed_id: CommittedId<TensorId>,
+}
+
+impl Default for OutputTensorWidget {
+    fn default() -> Self {
+        Self {
+            staging_id: StagingString::new(InputLines::SingleLine),
+            staging_description: StagingString::new(InputLines::Multiline),
+            staging_axes: StagingVec::new("axis"),
+            test_tensor: Default::default(),
+            sample_tensor: Default::default(),
+            test_tensor_preview: Default::default(),
+            test_tensor_generator: Default::default(),
+            axes_guess_error: None,
+            staging_data: Default::default(),
+            staging_postprocessing: Default::default(),
+            committed_id: Default::default(),
+        }
+    }
+}
+
+impl OutputTensorWidget {
+    /// The widget for an imported tensor, with its test and sample tensors loading from where `files` were unpacked
+    pub fn from_descr(descr: &OutputTensorDescr, files: &PackageFiles) -> Self {
+        let data = descr.data.as_ref().and_then(|data| {
+            let descrs = data.descrs();
+            if descrs.len() > 1 {
+                log::warn!("Only the first per-channel data description of tensor {} was imported", descr.id);
+            }
+            descrs.first().map(|first| TensorDataWidget::from(*first))
+        });
+        Self {
+            staging_id: StagingString::new(InputLines::SingleLine).with_raw(descr.id.to_string()),
+            staging_description: StagingString::new(InputLines::Multiline).with_raw(descr.description.to_string()),
+            staging_axes: StagingVec {
+                item_name: "axis".into(),
+                staging: descr.axes.iter().map(OutputAxisWidget::from).collect(),
+            },
+            test_tensor: files.widget(&descr.test_tensor.source),
+            sample_tensor: descr.sample_tensor.as_ref().map(|sample| files.widget(&sample.source)).into(),
+            test_tensor_preview: Default::default(),
+            test_tensor_generator: Default::default(),
+            axes_guess_error: None,
+            staging_data: data.into(),
+            staging_postprocessing: PostprocessingWidget::from_steps(&descr.postprocessing),
+            committed_id: Default::default(),
+        }
+    }
+
+    /// The test tensor and, if there is one, the sample tensor
+    pub fn attachments(&self) -> impl Iterator<Item = &AttachmentWidget> {
+        std::iter::once(&self.test_tensor).chain(self.sample_tensor.inner())
+    }
+
+    /// Whether the axes are still as a new tensor has them, so that guessing them from the test tensor loses nothing
+    fn axes_unconfigured(&self) -> bool {
+        let untouched = OutputAxisWidget::default().dump();
+        self.staging_axes.staging.iter().all(|axis| axis.dump() == untouched)
+    }
+
+    /// Replaces the axes with those guessed from the shape of the test tensor, with its sizes as fixed sizes
+    fn guess_axes(&mut self) {
+        let Some(Ok(test_tensor)) = self.test_tensor.loaded_value() else {
+            return;
+        };
+        match NpyHeader::open(test_tensor.path()) {
+            Ok(header) => {
+                let axes: Vec<OutputAxis> = header.guess_axes();
+                self.staging_axes.staging = axes.iter().map(OutputAxisWidget::from).collect();
+                self.axes_guess_error = None;
+            }
+            Err(err) => self.axes_guess_error = Some(GuiError::new(tr!("axes-guess-failed", error = err.to_string()))),
+        }
+    }
+}
+
+impl StatefulWidget for OutputTensorWidget {
+    type Value<'p> = Result<OutputTensorDescr>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            required_field_label_with_help(ui, &tr!("id-label"), "outputs.id");
+            self.staging_id.draw_and_parse(ui, id.with("id"));
+            ui.end_row();
+
+            field_label(ui, &tr!("description-label"));
+            self.staging_description.draw_and_parse(ui, id.with("description"));
+            ui.end_row();
+
+            required_field_label(ui, &tr!("axes-label"));
+            group_frame(ui, |ui| self.staging_axes.draw_and_parse(ui, id.with("axes")));
+            ui.end_row();
+
+            required_field_label(ui, &tr!("test-tensor-label"));
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    self.test_tensor.draw_and_parse(ui, id.with("test tensor"));
+                    let loaded = matches!(self.test_tensor.loaded_value(), Some(Ok(_)));
+                    if loaded
+                        && self.axes_unconfigured()
+                        && ui.button(tr!("infer-axes")).on_hover_text(tr!("infer-axes-hint")).clicked()
+                    {
+                        self.guess_axes();
+                    }
+                });
+                if let Some(err) = &self.axes_guess_error {
+                    show_error(ui, err);
+                }
+            });
+            ui.end_row();
+
+            let axes: Vec<(AxisType, String)> =
+                self.staging_axes.staging.iter().map(|axis| (axis.axis_type, axis.raw_id().to_owned())).collect();
+            if let Some(Ok(test_tensor)) = self.test_tensor.loaded_value() {
+                field_label(ui, &tr!("test-tensor-preview-label"));
+                let axis_names: Vec<String> = axes.iter().map(|(_, id)| id.clone()).collect();
+                self.test_tensor_preview.show(ui, test_tensor.path(), &axis_names);
+                ui.end_row();
+            }
+
+            field_label(ui, &tr!("test-tensor-from-image-label"));
+            let data_type = self.staging_data.inner().map(|data| data.staging_data_type.state());
+            if let Some(path) = self.test_tensor_generator.show(ui, &axes, data_type) {
+                self.test_tensor.load(path, ui.ctx());
+            }
+            ui.end_row();
+
+            field_label(ui, &tr!("sample-tensor-label"));
+            self.sample_tensor.draw_and_parse(ui, id.with("sample tensor"));
+            ui.end_row();
+
+            field_label(ui, &tr!("tensor-data-label"));
+            self.staging_data.draw_and_parse(ui, id.with("data"));
+            ui.end_row();
+
+            field_label_with_help(ui, &tr!("postprocessing-label"), "outputs.postprocessing");
+            group_frame(ui, |ui| self.staging_postprocessing.draw_and_parse(ui, id.with("postprocessing")));
+            ui.end_row();
+        });
+        // a picked test tensor counts as editing the tensor, so that what else is missing shows up
+        let touched = !matches!(self.test_tensor.state(), FileWidgetState::Empty);
+        if let Some(err) = visible_error(ui, touched, &self.state()) {
+            show_error(ui, err);
+        }
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let axes = self.staging_axes.state().into_iter().collect::<Result<Vec<_>>>()?;
+        let axes = NonEmptyList::try_from(axes).map_err(|_| GuiError::new(tr!("tensor-no-axes")))?;
+        let test_tensor = attached_file(self.test_tensor.state())?
+            .ok_or_else(|| GuiError::new(tr!("test-tensor-missing")))?;
+        let sample_tensor = match self.sample_tensor.state() {
+            Some(state) => attached_file(state)?,
+            None => None,
+        };
+        let descr = OutputTensorDescr {
+            id: self.staging_id.state()?,
+            description: self.staging_description.state()?,
+            axes,
+            test_tensor,
+            sample_tensor,
+            postprocessing: self.staging_postprocessing.state()?,
+            data: self.staging_data.state().transpose()?.map(TensorData::Whole),
+        };
+        descr.check().map_err(|err| GuiError::new(err.to_string()))?;
+        Ok(descr)
+    }
+}
+
+restore_fields!(OutputAxisWidget {
+    axis_type,
+    batch_widget,
+    channel_widget,
+    index_widget,
+    time_widget,
+    space_widget,
+    staging_halo,
+});
+restore_fields!(OutputTensorWidget {
+    staging_id,
+    staging_description,
+    staging_axes,
+    test_tensor,
+    sample_tensor,
+    staging_data,
+    staging_postprocessing,
+});
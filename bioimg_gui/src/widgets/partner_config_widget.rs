@@ -0,0 +1,174 @@
+use bioimg_spec::rdf::partner_config::{deepimagej, deepimagej::DeepImageJConfig, ilastik, ilastik::IlastikConfig};
+
+use super::{
+    accessibility::field_label, restore_fields, restore_with_serde, SetValue, StagingOpt, StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::Result;
+
+pub struct DeepImageJConfigWidget {
+    pub pyramidal_model: bool,
+    pub allow_tiling: bool,
+    /// The imported block, for the fields the form doesn't show
+    imported: DeepImageJConfig,
+}
+
+impl From<DeepImageJConfig> for DeepImageJConfigWidget {
+    fn from(config: DeepImageJConfig) -> Self {
+        Self {
+            pyramidal_model: config.pyramidal_model,
+            allow_tiling: config.allow_tiling,
+            imported: config,
+        }
+    }
+}
+
+impl Default for DeepImageJConfigWidget {
+    fn default() -> Self {
+        Self::from(DeepImageJConfig {
+            allow_tiling: true,
+            ..Default::default()
+        })
+    }
+}
+
+impl StatefulWidget for DeepImageJConfigWidget {
+    type Value<'p> = DeepImageJConfig;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, _id: egui::Id) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.pyramidal_model, tr!("pyramidal-model"));
+            ui.checkbox(&mut self.allow_tiling, tr!("allow-tiling"));
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        DeepImageJConfig {
+            pyramidal_model: self.pyramidal_model,
+            allow_tiling: self.allow_tiling,
+            ..self.imported.clone()
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct IlastikConfigWidget {
+    pub staging_min_version: StagingOpt<StagingString<String>>,
+    /// The imported block, for the fields the form doesn't show
+    imported: IlastikConfig,
+}
+
+impl From<IlastikConfig> for IlastikConfigWidget {
+    fn from(config: IlastikConfig) -> Self {
+        let mut widget = Self::default();
+        widget.staging_min_version.set_value(config.min_ilastik_version.as_deref());
+        widget.imported = config;
+        widget
+    }
+}
+
+impl StatefulWidget for IlastikConfigWidget {
+    type Value<'p> = Result<IlastikConfig>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.horizontal(|ui| {
+            field_label(ui, &tr!("min-ilastik-version-label"));
+            self.staging_min_version.draw_and_parse(ui, id.with("Min version"));
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(IlastikConfig {
+            min_ilastik_version: self.staging_min_version.state().transpose()?,
+            ..self.imported.clone()
+        })
+    }
+}
+
+/// The `config.<partner>` sections of tools that run models whatever family they are of, each of which can be
+/// switched on or off
+#[derive(Default)]
+pub struct PartnerConfigsWidget {
+    pub deepimagej_enabled: bool,
+    pub deepimagej: DeepImageJConfigWidget,
+    pub ilastik_enabled: bool,
+    pub ilastik: IlastikConfigWidget,
+    /// Partners whose imported section didn't parse, which are left in `config` as they were
+    unparsed: Vec<String>,
+}
+
+impl PartnerConfigsWidget {
+    const PARTNER_IDS: [&'static str; 2] = ["deepimagej", "ilastik"];
+
+    pub fn from_config(config: &serde_yaml::Mapping) -> Self {
+        let mut widget = Self::default();
+        for partner_id in Self::PARTNER_IDS {
+            let Some(section) = config.get(partner_id) else {
+                continue;
+            };
+            let parsed = match partner_id {
+                "deepimagej" => deepimagej::VALIDATOR.parse(section).map(|parsed| {
+                    widget.deepimagej_enabled = true;
+                    widget.deepimagej = parsed.into();
+                }),
+                _ => ilastik::VALIDATOR.parse(section).map(|parsed| {
+                    widget.ilastik_enabled = true;
+                    widget.ilastik = parsed.into();
+                }),
+            };
+            if parsed.is_err() {
+                widget.unparsed.push(partner_id.to_owned());
+            }
+        }
+        widget
+    }
+
+    /// Replaces the sections of `config` this widget is for with the ones switched on
+    pub fn apply_to(&self, config: &mut serde_yaml::Mapping) -> Result<()> {
+        let sections = self.state()?;
+        for partner_id in Self::PARTNER_IDS {
+            if !self.unparsed.iter().any(|unparsed| unparsed == partner_id) {
+                config.remove(partner_id);
+            }
+        }
+        config.extend(sections);
+        Ok(())
+    }
+}
+
+impl StatefulWidget for PartnerConfigsWidget {
+    /// The sections that are switched on
+    type Value<'p> = Result<serde_yaml::Mapping>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.checkbox(&mut self.deepimagej_enabled, "deepImageJ");
+            if self.deepimagej_enabled {
+                ui.indent(id.with("deepImageJ"), |ui| self.deepimagej.draw_and_parse(ui, id.with("deepImageJ")));
+            }
+            ui.checkbox(&mut self.ilastik_enabled, "ilastik");
+            if self.ilastik_enabled {
+                ui.indent(id.with("ilastik"), |ui| self.ilastik.draw_and_parse(ui, id.with("ilastik")));
+            }
+            if !self.unparsed.is_empty() {
+                ui.weak(tr!("partner-configs-unparsed", partners = self.unparsed.join(", ")));
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let mut config = serde_yaml::Mapping::new();
+        if self.deepimagej_enabled {
+            config.insert("deepimagej".into(), serde_yaml::to_value(self.deepimagej.state())?);
+        }
+        if self.ilastik_enabled {
+            config.insert("ilastik".into(), serde_yaml::to_value(self.ilastik.state()?)?);
+        }
+        Ok(config)
+    }
+}
+
+restore_with_serde!(DeepImageJConfig, IlastikConfig);
+restore_fields!(DeepImageJConfigWidget { pyramidal_model, allow_tiling, imported });
+restore_fields!(IlastikConfigWidget { staging_min_version, imported });
+restore_fields!(PartnerConfigsWidget { deepimagej_enabled, deepimagej, ilastik_enabled, ilastik, unparsed });
@@ -0,0 +1,91 @@
+//! Filling in a widget from a snippet of an rdf, e.g. an author copied out of another model's `rdf.yaml`.
+//! egui can't read the clipboard when asked to, so the snippet is pasted into a box that opens on request.
+
+use std::marker::PhantomData;
+
+use bioimg_spec::rdf::spec_error;
+use serde::de::DeserializeOwned;
+
+use super::error_display::show_error;
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+/// A "Paste" button that opens a box to paste a JSON or YAML `T` into
+pub struct PasteBox<T> {
+    open: bool,
+    raw: String,
+    error: Option<GuiError>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for PasteBox<T> {
+    fn default() -> Self {
+        Self {
+            open: false,
+            raw: String::new(),
+            error: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> PasteBox<T> {
+    /// Returns what was pasted once it parses, which closes the box again
+    pub fn show(&mut self, ui: &mut egui::Ui, id: egui::Id) -> Option<T> {
+        let text_id = id.with("snippet");
+        if !self.open {
+            if ui.button(tr!("paste-snippet")).on_hover_text(tr!("paste-snippet-hint")).clicked() {
+                self.open = true;
+                self.raw.clear();
+                self.error = None;
+                ui.memory_mut(|mem| mem.request_focus(text_id));
+            }
+            return None;
+        }
+
+        let mut pasted = None;
+        ui.vertical(|ui| {
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut self.raw)
+                    .id(text_id)
+                    .code_editor()
+                    .desired_rows(4)
+                    .hint_text(tr!("paste-snippet-placeholder")),
+            );
+            if response.changed() {
+                self.error = None;
+                if !self.raw.trim().is_empty() {
+                    match parse_snippet(&self.raw) {
+                        Ok(value) => pasted = Some(value),
+                        Err(err) => self.error = Some(err),
+                    }
+                }
+            }
+            if ui.button(tr!("cancel")).clicked() {
+                self.open = false;
+            }
+            if let Some(err) = &self.error {
+                show_error(ui, err);
+            }
+        });
+        if pasted.is_some() {
+            self.open = false;
+        }
+        pasted
+    }
+}
+
+/// Parses a single `T` out of JSON or YAML. A list with only one entry is taken to be that entry, since
+/// snippets are often copied together with the dash in front of them.
+pub fn parse_snippet<T: DeserializeOwned>(raw: &str) -> Result<T> {
+    let value: serde_yaml::Value = serde_yaml::from_str(raw)
+        .map_err(|err| GuiError::new(tr!("paste-snippet-not-yaml", error = err.to_string())))?;
+    let value = match value {
+        serde_yaml::Value::Sequence(mut entries) if entries.len() == 1 => entries.remove(0),
+        serde_yaml::Value::Sequence(entries) => {
+            return Err(GuiError::new(tr!("paste-snippet-many", count = entries.len())));
+        }
+        value => value,
+    };
+    spec_error::from_value(value).map_err(|err| GuiError::new(tr!("paste-snippet-mismatch", error = err.to_string())))
+}
@@ -0,0 +1,725 @@
+use bioimg_spec::rdf::model::axes::AxisId;
+use bioimg_spec::rdf::model::data_type::DataType;
+use bioimg_spec::rdf::model::preprocessing::{
+    BinarizeKwargs, ClipKwargs, EnsureDtypeKwargs, FixedZeroMeanUnitVarianceKwargs, ScaleLinearKwargs,
+    ScaleMeanVarianceKwargs, ScaleRangeKwargs, ZeroMeanUnitVarianceKwargs,
+};
+use bioimg_spec::rdf::model::references::IdRename;
+use bioimg_spec::rdf::model::tensor_id::TensorId;
+use bioimg_spec::rdf::model::{Postprocessing, Preprocessing};
+use bioimg_spec::util::SingleOrMultiple;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    accessibility::{field_label, required_field_label},
+    enum_widget::EnumWidget,
+    error_display::show_error,
+    restore_fields, restore_with_serde, set_value_from,
+    util::group_frame,
+    InputLines, Restore, SetValue, StagingNum, StagingOpt, StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+/// A single number, or a comma separated list of them with one per entry of an axis
+#[derive(Clone, Debug)]
+pub struct Floats(pub SingleOrMultiple<f64>);
+
+impl TryFrom<String> for Floats {
+    type Error = GuiError;
+    fn try_from(raw: String) -> Result<Self> {
+        let values = raw
+            .split(',')
+            .map(|value| value.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| GuiError::new(tr!("bad-floats", value = raw.clone())))?;
+        Ok(Self(match <[f64; 1]>::try_from(values) {
+            Ok([value]) => SingleOrMultiple::Single(value),
+            Err(values) => SingleOrMultiple::Multiple(values),
+        }))
+    }
+}
+
+/// A comma separated list of axis ids
+#[derive(Clone, Debug)]
+pub struct AxisIds(pub Vec<AxisId>);
+
+impl TryFrom<String> for AxisIds {
+    type Error = GuiError;
+    fn try_from(raw: String) -> Result<Self> {
+        let ids = raw
+            .split(',')
+            .map(|id| AxisId::try_from(id.trim().to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(ids))
+    }
+}
+
+/// Kwargs are put together field by field, then checked the way the spec checks them when parsing
+fn checked<T: Serialize + DeserializeOwned>(value: T) -> Result<T> {
+    Ok(serde_yaml::from_value(serde_yaml::to_value(&value)?)?)
+}
+
+fn staging_floats(raw: &str) -> StagingString<Floats> {
+    let mut staging = StagingString::new(InputLines::SingleLine);
+    staging.set_raw(raw);
+    staging
+}
+
+/// A percentile, in steps of a tenth of a percent
+fn staging_percentile(value: f64) -> StagingNum<f64, f64> {
+    StagingNum::new(value).with_speed(0.1).with_range(0.0..=100.0).with_step(0.1).with_suffix("%")
+}
+
+/// The text `values` are typed in as, the inverse of parsing [Floats]
+fn floats_raw(values: &SingleOrMultiple<f64>) -> String {
+    match values {
+        SingleOrMultiple::Single(value) => value.to_string(),
+        SingleOrMultiple::Multiple(values) => values.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+    }
+}
+
+#[derive(
+    PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, strum::VariantArray, strum::VariantNames, strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PreprocessingOp {
+    #[strum(serialize = "binarize")]
+    Binarize,
+    #[strum(serialize = "clip")]
+    Clip,
+    #[strum(serialize = "ensure_dtype")]
+    EnsureDtype,
+    #[strum(serialize = "scale_linear")]
+    ScaleLinear,
+    #[strum(serialize = "scale_range")]
+    ScaleRange,
+    #[strum(serialize = "sigmoid")]
+    Sigmoid,
+    #[default]
+    #[strum(serialize = "zero_mean_unit_variance")]
+    ZeroMeanUnitVariance,
+    #[strum(serialize = "fixed_zero_mean_unit_variance")]
+    FixedZeroMeanUnitVariance,
+}
+
+/// The postprocessing operations: every preprocessing one, plus those that need another tensor to compare against
+#[derive(
+    PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, strum::VariantArray, strum::VariantNames, strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PostprocessingOp {
+    #[strum(serialize = "binarize")]
+    Binarize,
+    #[strum(serialize = "clip")]
+    Clip,
+    #[strum(serialize = "ensure_dtype")]
+    EnsureDtype,
+    #[strum(serialize = "scale_linear")]
+    ScaleLinear,
+    #[strum(serialize = "scale_range")]
+    ScaleRange,
+    #[default]
+    #[strum(serialize = "sigmoid")]
+    Sigmoid,
+    #[strum(serialize = "zero_mean_unit_variance")]
+    ZeroMeanUnitVariance,
+    #[strum(serialize = "fixed_zero_mean_unit_variance")]
+    FixedZeroMeanUnitVariance,
+    #[strum(serialize = "scale_mean_variance")]
+    ScaleMeanVariance,
+}
+
+impl PostprocessingOp {
+    /// The same operation as preprocessing, if it can be one
+    fn preprocessing_op(self) -> Option<PreprocessingOp> {
+        Some(match self {
+            Self::Binarize => PreprocessingOp::Binarize,
+            Self::Clip => PreprocessingOp::Clip,
+            Self::EnsureDtype => PreprocessingOp::EnsureDtype,
+            Self::ScaleLinear => PreprocessingOp::ScaleLinear,
+            Self::ScaleRange => PreprocessingOp::ScaleRange,
+            Self::Sigmoid => PreprocessingOp::Sigmoid,
+            Self::ZeroMeanUnitVariance => PreprocessingOp::ZeroMeanUnitVariance,
+            Self::FixedZeroMeanUnitVariance => PreprocessingOp::FixedZeroMeanUnitVariance,
+            Self::ScaleMeanVariance => return None,
+        })
+    }
+}
+
+impl From<PreprocessingOp> for PostprocessingOp {
+    fn from(op: PreprocessingOp) -> Self {
+        match op {
+            PreprocessingOp::Binarize => Self::Binarize,
+            PreprocessingOp::Clip => Self::Clip,
+            PreprocessingOp::EnsureDtype => Self::EnsureDtype,
+            PreprocessingOp::ScaleLinear => Self::ScaleLinear,
+            PreprocessingOp::ScaleRange => Self::ScaleRange,
+            PreprocessingOp::Sigmoid => Self::Sigmoid,
+            PreprocessingOp::ZeroMeanUnitVariance => Self::ZeroMeanUnitVariance,
+            PreprocessingOp::FixedZeroMeanUnitVariance => Self::FixedZeroMeanUnitVariance,
+        }
+    }
+}
+
+/// The kwargs of every operation at once, so that switching to another operation and back keeps what was typed in
+pub struct ProcessingKwargsWidget {
+    pub staging_axis: StagingOpt<StagingString<AxisId>>,
+    pub staging_axes: StagingOpt<StagingString<AxisIds>>,
+    pub staging_threshold: StagingString<Floats>,
+    pub staging_min: StagingNum<f64, f64>,
+    pub staging_max: StagingNum<f64, f64>,
+    pub staging_dtype: EnumWidget<DataType>,
+    pub staging_gain: StagingString<Floats>,
+    pub staging_offset: StagingString<Floats>,
+    pub staging_eps: StagingNum<f64, f64>,
+    pub staging_min_percentile: StagingNum<f64, f64>,
+    pub staging_max_percentile: StagingNum<f64, f64>,
+    /// Picked from `tensor_ids`, so it always refers to a tensor of the model
+    pub reference_tensor: Option<TensorId>,
+    /// The ids of the tensors of the model, as declared right now
+    pub tensor_ids: Vec<TensorId>,
+    pub staging_mean: StagingString<Floats>,
+    pub staging_std: StagingString<Floats>,
+}
+
+impl Default for ProcessingKwargsWidget {
+    fn default() -> Self {
+        Self {
+            staging_axis: Default::default(),
+            staging_axes: Default::default(),
+            staging_threshold: staging_floats("0.5"),
+            staging_min: StagingNum::new(0.0).with_speed(0.01),
+            staging_max: StagingNum::new(1.0).with_speed(0.01),
+            staging_dtype: Default::default(),
+            staging_gain: staging_floats("1.0"),
+            staging_offset: staging_floats("0.0"),
+            staging_eps: StagingNum::new(1e-6).with_speed(1e-7),
+            staging_min_percentile: staging_percentile(0.0),
+            staging_max_percentile: staging_percentile(100.0),
+            reference_tensor: None,
+            tensor_ids: vec![],
+            staging_mean: staging_floats("0.0"),
+            staging_std: staging_floats("1.0"),
+        }
+    }
+}
+
+impl ProcessingKwargsWidget {
+    fn draw_axis(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        field_label(ui, &tr!("processing-axis-label"));
+        self.staging_axis.draw_and_parse(ui, id.with("axis"));
+        ui.end_row();
+    }
+
+    fn draw_axes(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        field_label(ui, &tr!("processing-axes-label"));
+        self.staging_axes.draw_and_parse(ui, id.with("axes"));
+        ui.end_row();
+    }
+
+    fn draw_eps(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        required_field_label(ui, &tr!("eps-label"));
+        self.staging_eps.draw_and_parse(ui, id.with("eps"));
+        ui.end_row();
+    }
+
+    fn draw_reference_tensor(&mut self, ui: &mut egui::Ui, id: egui::Id, required: bool) {
+        match required {
+            true => required_field_label(ui, &tr!("reference-tensor-label")),
+            false => field_label(ui, &tr!("reference-tensor-label")),
+        };
+        let selected_text = self.reference_tensor.as_ref().map_or_else(|| tr!("none"), ToString::to_string);
+        egui::ComboBox::from_id_source(id.with("reference tensor")).selected_text(selected_text).show_ui(ui, |ui| {
+            if !required {
+                ui.selectable_value(&mut self.reference_tensor, None, tr!("none"));
+            }
+            for tensor_id in &self.tensor_ids {
+                ui.selectable_value(&mut self.reference_tensor, Some(tensor_id.clone()), tensor_id.to_string());
+            }
+        });
+        ui.end_row();
+    }
+
+    /// Forgets the reference tensor if it is not among `tensor_ids` anymore, e.g. after it was removed
+    pub fn set_tensor_ids(&mut self, tensor_ids: &[TensorId]) {
+        if self.reference_tensor.as_ref().is_some_and(|reference| !tensor_ids.contains(reference)) {
+            self.reference_tensor = None;
+        }
+        self.tensor_ids = tensor_ids.to_vec();
+    }
+
+    fn draw_floats(ui: &mut egui::Ui, id: egui::Id, label_id: &str, staging: &mut StagingString<Floats>) {
+        required_field_label(ui, &tr!(label_id));
+        staging.draw_and_parse(ui, id.with(label_id));
+        ui.end_row();
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id, op: PreprocessingOp) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| match op {
+            PreprocessingOp::Binarize => {
+                Self::draw_floats(ui, id, "threshold-label", &mut self.staging_threshold);
+                self.draw_axis(ui, id);
+            }
+            PreprocessingOp::Clip => {
+                required_field_label(ui, &tr!("min-label"));
+                self.staging_min.draw_and_parse(ui, id.with("min"));
+                ui.end_row();
+                required_field_label(ui, &tr!("max-label"));
+                self.staging_max.draw_and_parse(ui, id.with("max"));
+                ui.end_row();
+            }
+            PreprocessingOp::EnsureDtype => {
+                required_field_label(ui, &tr!("data-type-label"));
+                self.staging_dtype.draw_and_parse(ui, id.with("dtype"));
+                ui.end_row();
+            }
+            PreprocessingOp::ScaleLinear => {
+                Self::draw_floats(ui, id, "gain-label", &mut self.staging_gain);
+                Self::draw_floats(ui, id, "offset-label", &mut self.staging_offset);
+                self.draw_axis(ui, id);
+            }
+            PreprocessingOp::ScaleRange => {
+                required_field_label(ui, &tr!("min-percentile-label"));
+                self.staging_min_percentile.draw_and_parse(ui, id.with("min percentile"));
+                ui.end_row();
+                required_field_label(ui, &tr!("max-percentile-label"));
+                self.staging_max_percentile.draw_and_parse(ui, id.with("max percentile"));
+                ui.end_row();
+                self.draw_axes(ui, id);
+                self.draw_eps(ui, id);
+                self.draw_reference_tensor(ui, id, false);
+            }
+            PreprocessingOp::Sigmoid => {
+                ui.weak(tr!("no-kwargs"));
+                ui.end_row();
+            }
+            PreprocessingOp::ZeroMeanUnitVariance => {
+                self.draw_axes(ui, id);
+                self.draw_eps(ui, id);
+            }
+            PreprocessingOp::FixedZeroMeanUnitVariance => {
+                Self::draw_floats(ui, id, "mean-label", &mut self.staging_mean);
+                Self::draw_floats(ui, id, "std-label", &mut self.staging_std);
+                self.draw_axis(ui, id);
+            }
+        });
+    }
+
+    fn set_axis(&mut self, axis: &Option<AxisId>) {
+        self.staging_axis = StagingOpt::from_raw(&axis.as_ref().map(ToString::to_string).unwrap_or_default());
+    }
+
+    fn set_axes(&mut self, axes: &Option<Vec<AxisId>>) {
+        let raw = axes.iter().flatten().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+        self.staging_axes = StagingOpt::from_raw(&raw);
+    }
+
+    /// Fills in the kwargs of `step`, returning which operation it is
+    pub fn set_preprocessing(&mut self, step: &Preprocessing) -> PreprocessingOp {
+        match step {
+            Preprocessing::Binarize(kwargs) => {
+                self.staging_threshold = staging_floats(&floats_raw(&kwargs.threshold));
+                self.set_axis(&kwargs.axis);
+                PreprocessingOp::Binarize
+            }
+            Preprocessing::Clip(kwargs) => {
+                self.staging_min = StagingNum::new(kwargs.min).with_speed(0.01);
+                self.staging_max = StagingNum::new(kwargs.max).with_speed(0.01);
+                PreprocessingOp::Clip
+            }
+            Preprocessing::EnsureDtype(kwargs) => {
+                self.staging_dtype.set_value(kwargs.dtype);
+                PreprocessingOp::EnsureDtype
+            }
+            Preprocessing::ScaleLinear(kwargs) => {
+                self.staging_gain = staging_floats(&floats_raw(&kwargs.gain));
+                self.staging_offset = staging_floats(&floats_raw(&kwargs.offset));
+                self.set_axis(&kwargs.axis);
+                PreprocessingOp::ScaleLinear
+            }
+            Preprocessing::ScaleRange(kwargs) => {
+                self.set_axes(&kwargs.axes);
+                self.staging_eps = StagingNum::new(kwargs.eps).with_speed(1e-7);
+                self.staging_min_percentile = staging_percentile(kwargs.min_percentile);
+                self.staging_max_percentile = staging_percentile(kwargs.max_percentile);
+                self.reference_tensor = kwargs.reference_tensor.clone();
+                PreprocessingOp::ScaleRange
+            }
+            Preprocessing::Sigmoid => PreprocessingOp::Sigmoid,
+            Preprocessing::ZeroMeanUnitVariance(kwargs) => {
+                self.set_axes(&kwargs.axes);
+                self.staging_eps = StagingNum::new(kwargs.eps).with_speed(1e-7);
+                PreprocessingOp::ZeroMeanUnitVariance
+            }
+            Preprocessing::FixedZeroMeanUnitVariance(kwargs) => {
+                self.staging_mean = staging_floats(&floats_raw(&kwargs.mean));
+                self.staging_std = staging_floats(&floats_raw(&kwargs.std));
+                self.set_axis(&kwargs.axis);
+                PreprocessingOp::FixedZeroMeanUnitVariance
+            }
+        }
+    }
+
+    /// Fills in the kwargs of `step`, returning which operation it is
+    pub fn set_postprocessing(&mut self, step: &Postprocessing) -> PostprocessingOp {
+        let step = match step.clone() {
+            Postprocessing::Binarize(kwargs) => Preprocessing::Binarize(kwargs),
+            Postprocessing::Clip(kwargs) => Preprocessing::Clip(kwargs),
+            Postprocessing::EnsureDtype(kwargs) => Preprocessing::EnsureDtype(kwargs),
+            Postprocessing::ScaleLinear(kwargs) => Preprocessing::ScaleLinear(kwargs),
+            Postprocessing::ScaleRange(kwargs) => Preprocessing::ScaleRange(kwargs),
+            Postprocessing::Sigmoid => Preprocessing::Sigmoid,
+            Postprocessing::ZeroMeanUnitVariance(kwargs) => Preprocessing::ZeroMeanUnitVariance(kwargs),
+            Postprocessing::FixedZeroMeanUnitVariance(kwargs) => Preprocessing::FixedZeroMeanUnitVariance(kwargs),
+            Postprocessing::ScaleMeanVariance(kwargs) => {
+                self.set_axes(&kwargs.axes);
+                self.staging_eps = StagingNum::new(kwargs.eps).with_speed(1e-7);
+                self.reference_tensor = Some(kwargs.reference_tensor);
+                return PostprocessingOp::ScaleMeanVariance;
+            }
+        };
+        self.set_preprocessing(&step).into()
+    }
+
+    fn axis(&self) -> Result<Option<AxisId>> {
+        self.staging_axis.state().transpose()
+    }
+
+    fn axes(&self) -> Result<Option<Vec<AxisId>>> {
+        Ok(self.staging_axes.state().transpose()?.map(|axes| axes.0))
+    }
+
+    pub fn preprocessing(&self, op: PreprocessingOp) -> Result<Preprocessing> {
+        let step = match op {
+            PreprocessingOp::Binarize => Preprocessing::Binarize(BinarizeKwargs {
+                threshold: self.staging_threshold.state()?.0,
+                axis: self.axis()?,
+            }),
+            PreprocessingOp::Clip => Preprocessing::Clip(ClipKwargs {
+                min: self.staging_min.state()?,
+                max: self.staging_max.state()?,
+            }),
+            PreprocessingOp::EnsureDtype => Preprocessing::EnsureDtype(EnsureDtypeKwargs {
+                dtype: self.staging_dtype.state(),
+            }),
+            PreprocessingOp::ScaleLinear => Preprocessing::ScaleLinear(ScaleLinearKwargs {
+                axis: self.axis()?,
+                gain: self.staging_gain.state()?.0,
+                offset: self.staging_offset.state()?.0,
+            }),
+            PreprocessingOp::ScaleRange => Preprocessing::ScaleRange(ScaleRangeKwargs {
+                axes: self.axes()?,
+                eps: self.staging_eps.state()?,
+                max_percentile: self.staging_max_percentile.state()?,
+                min_percentile: self.staging_min_percentile.state()?,
+                reference_tensor: self.reference_tensor.clone(),
+            }),
+            PreprocessingOp::Sigmoid => Preprocessing::Sigmoid,
+            PreprocessingOp::ZeroMeanUnitVariance => Preprocessing::ZeroMeanUnitVariance(ZeroMeanUnitVarianceKwargs {
+                axes: self.axes()?,
+                eps: self.staging_eps.state()?,
+            }),
+            PreprocessingOp::FixedZeroMeanUnitVariance => {
+                Preprocessing::FixedZeroMeanUnitVariance(FixedZeroMeanUnitVarianceKwargs {
+                    axis: self.axis()?,
+                    mean: self.staging_mean.state()?.0,
+                    std: self.staging_std.state()?.0,
+                })
+            }
+        };
+        checked(step)
+    }
+
+    pub fn draw_postprocessing(&mut self, ui: &mut egui::Ui, id: egui::Id, op: PostprocessingOp) {
+        let Some(op) = op.preprocessing_op() else {
+            // scale_mean_variance
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                self.draw_reference_tensor(ui, id, true);
+                self.draw_axes(ui, id);
+                self.draw_eps(ui, id);
+            });
+            return;
+        };
+        self.draw(ui, id, op)
+    }
+
+    pub fn postprocessing(&self, op: PostprocessingOp) -> Result<Postprocessing> {
+        if let Some(op) = op.preprocessing_op() {
+            return self.preprocessing(op).map(Postprocessing::from);
+        }
+        let reference_tensor = self.reference_tensor.clone().ok_or_else(|| GuiError::new(tr!("reference-tensor-missing")))?;
+        checked(Postprocessing::ScaleMeanVariance(ScaleMeanVarianceKwargs {
+            reference_tensor,
+            axes: self.axes()?,
+            eps: self.staging_eps.state()?,
+        }))
+    }
+}
+
+/// A widget for one step of a [PipelineWidget]
+pub trait PipelineStepWidget: StatefulWidget + Default {
+    type Step: Serialize;
+    fn step(&self) -> Result<Self::Step>;
+    fn set_tensor_ids(&mut self, tensor_ids: &[TensorId]);
+    /// Points the step, which is one of tensor `tensor_id`, at the renamed tensor or axis. Steps that don't parse
+    /// are left as they are.
+    fn apply_rename(&mut self, tensor_id: &TensorId, rename: &IdRename);
+}
+
+#[derive(Default)]
+pub struct PreprocessingStepWidget {
+    pub op_widget: EnumWidget<PreprocessingOp>,
+    pub kwargs_widget: ProcessingKwargsWidget,
+}
+
+impl From<&Preprocessing> for PreprocessingStepWidget {
+    fn from(step: &Preprocessing) -> Self {
+        let mut widget = Self::default();
+        let op = widget.kwargs_widget.set_preprocessing(step);
+        widget.op_widget.set_value(op);
+        widget
+    }
+}
+
+set_value_from!(PreprocessingStepWidget: Preprocessing);
+
+impl StatefulWidget for PreprocessingStepWidget {
+    type Value<'p> = Result<Preprocessing>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                field_label(ui, &tr!("operation-label"));
+                self.op_widget.draw_and_parse(ui, id.with("op"));
+            });
+            self.kwargs_widget.draw(ui, id.with("kwargs"), self.op_widget.state());
+            if let Err(err) = self.state() {
+                show_error(ui, err);
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.kwargs_widget.preprocessing(self.op_widget.state())
+    }
+}
+
+impl PipelineStepWidget for PreprocessingStepWidget {
+    type Step = Preprocessing;
+    fn step(&self) -> Result<Preprocessing> {
+        self.state()
+    }
+    fn set_tensor_ids(&mut self, tensor_ids: &[TensorId]) {
+        self.kwargs_widget.set_tensor_ids(tensor_ids);
+    }
+    fn apply_rename(&mut self, tensor_id: &TensorId, rename: &IdRename) {
+        let Ok(mut step) = self.state() else {
+            return;
+        };
+        if step.apply_rename(tensor_id, rename) > 0 {
+            self.kwargs_widget.set_preprocessing(&step);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PostprocessingStepWidget {
+    pub op_widget: EnumWidget<PostprocessingOp>,
+    pub kwargs_widget: ProcessingKwargsWidget,
+}
+
+impl From<&Postprocessing> for PostprocessingStepWidget {
+    fn from(step: &Postprocessing) -> Self {
+        let mut widget = Self::default();
+        let op = widget.kwargs_widget.set_postprocessing(step);
+        widget.op_widget.set_value(op);
+        widget
+    }
+}
+
+set_value_from!(PostprocessingStepWidget: Postprocessing);
+
+impl StatefulWidget for PostprocessingStepWidget {
+    type Value<'p> = Result<Postprocessing>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                field_label(ui, &tr!("operation-label"));
+                self.op_widget.draw_and_parse(ui, id.with("op"));
+            });
+            self.kwargs_widget.draw_postprocessing(ui, id.with("kwargs"), self.op_widget.state());
+            if let Err(err) = self.state() {
+                show_error(ui, err);
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.kwargs_widget.postprocessing(self.op_widget.state())
+    }
+}
+
+impl PipelineStepWidget for PostprocessingStepWidget {
+    type Step = Postprocessing;
+    fn step(&self) -> Result<Postprocessing> {
+        self.state()
+    }
+    fn set_tensor_ids(&mut self, tensor_ids: &[TensorId]) {
+        self.kwargs_widget.set_tensor_ids(tensor_ids);
+    }
+    fn apply_rename(&mut self, tensor_id: &TensorId, rename: &IdRename) {
+        let Ok(mut step) = self.state() else {
+            return;
+        };
+        if step.apply_rename(tensor_id, rename) > 0 {
+            self.kwargs_widget.set_postprocessing(&step);
+        }
+    }
+}
+
+/// An ordered list of processing steps, reordered by dragging their handles, with the yaml they make up shown below
+#[derive(Default)]
+pub struct PipelineWidget<S> {
+    pub steps: Vec<S>,
+    /// The step being dragged by its handle
+    dragged: Option<usize>,
+}
+
+pub type PreprocessingWidget = PipelineWidget<PreprocessingStepWidget>;
+pub type PostprocessingWidget = PipelineWidget<PostprocessingStepWidget>;
+
+impl<S: PipelineStepWidget> PipelineWidget<S> {
+    pub fn from_steps<'s, T: 's>(steps: impl IntoIterator<Item = &'s T>) -> Self
+    where
+        S: From<&'s T>,
+    {
+        Self {
+            steps: steps.into_iter().map(S::from).collect(),
+            dragged: None,
+        }
+    }
+
+    /// The tensors steps can refer to. Has to be called before drawing, since the tensors declared can change any frame.
+    pub fn set_tensor_ids(&mut self, tensor_ids: &[TensorId]) {
+        for step in &mut self.steps {
+            step.set_tensor_ids(tensor_ids);
+        }
+    }
+
+    /// Points the steps of tensor `tensor_id` at the renamed tensor or axis
+    pub fn apply_rename(&mut self, tensor_id: &TensorId, rename: &IdRename) {
+        for step in &mut self.steps {
+            step.apply_rename(tensor_id, rename);
+        }
+    }
+
+    /// Moves the dragged step to where the pointer is, past the middle of the steps around it
+    fn drag(&mut self, ui: &egui::Ui, row_rects: &[egui::Rect]) {
+        let Some(from) = self.dragged else {
+            return;
+        };
+        if !ui.input(|i| i.pointer.any_down()) {
+            self.dragged = None;
+            return;
+        }
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        let Some(pointer) = ui.input(|i| i.pointer.interact_pos()) else {
+            return;
+        };
+        let to = row_rects
+            .iter()
+            .enumerate()
+            .filter(|(idx, rect)| *idx != from && rect.center().y < pointer.y)
+            .count();
+        if to != from {
+            let step = self.steps.remove(from);
+            self.steps.insert(to, step);
+            self.dragged = Some(to);
+        }
+    }
+}
+
+impl<S: PipelineStepWidget> StatefulWidget for PipelineWidget<S> {
+    type Value<'p> = Result<Vec<S::Step>> where S: 'p;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            let mut row_rects = Vec::with_capacity(self.steps.len());
+            let mut removed = None;
+            for (idx, step) in self.steps.iter_mut().enumerate() {
+                let row = ui.horizontal_top(|ui| {
+                    let handle = ui.add(egui::Label::new("☰").sense(egui::Sense::drag()));
+                    if handle.on_hover_cursor(egui::CursorIcon::Grab).on_hover_text(tr!("drag-to-reorder")).drag_started() {
+                        self.dragged = Some(idx);
+                    }
+                    ui.strong(format!("{}", idx + 1));
+                    group_frame(ui, |ui| step.draw_and_parse(ui, id.with(idx)));
+                    if ui.button("🗙").on_hover_text(tr!("remove-step")).clicked() {
+                        removed = Some(idx);
+                    }
+                });
+                row_rects.push(row.response.rect);
+            }
+            if let Some(idx) = removed {
+                self.steps.remove(idx);
+                self.dragged = None;
+            }
+            self.drag(ui, &row_rects);
+            if ui.button(tr!("add-step")).clicked() {
+                self.steps.push(S::default());
+            }
+            if let Ok(steps) = self.state() {
+                if !steps.is_empty() {
+                    egui::CollapsingHeader::new(tr!("yaml-preview")).id_source(id.with("yaml")).show(ui, |ui| {
+                        let yaml = serde_yaml::to_string(&steps).unwrap_or_else(|err| err.to_string());
+                        ui.add(egui::Label::new(egui::RichText::new(yaml).monospace()).wrap(false));
+                    });
+                }
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| {
+                step.step()
+                    .map_err(|err| GuiError::new(tr!("step-error", number = idx + 1, error = err.to_string())))
+            })
+            .collect()
+    }
+}
+
+restore_with_serde!(PreprocessingOp, PostprocessingOp, Option<TensorId>);
+// the tensor ids are set anew every frame
+restore_fields!(ProcessingKwargsWidget {
+    staging_axis,
+    staging_axes,
+    staging_threshold,
+    staging_min,
+    staging_max,
+    staging_dtype,
+    staging_gain,
+    staging_offset,
+    staging_eps,
+    staging_min_percentile,
+    staging_max_percentile,
+    reference_tensor,
+    staging_mean,
+    staging_std,
+});
+restore_fields!(PreprocessingStepWidget { op_widget, kwargs_widget });
+restore_fields!(PostprocessingStepWidget { op_widget, kwargs_widget });
+
+impl<S: Restore + Default> Restore for PipelineWidget<S> {
+    fn dump(&self) -> serde_yaml::Value {
+        self.steps.dump()
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        self.steps.restore(ctx, value);
+    }
+}
@@ -0,0 +1,70 @@
+use bioimg_spec::rdf::{bounded_string::BoundedString, model::RunMode};
+
+use super::{accessibility::field_label, error_display::show_error, restore_fields, set_value_from, StagingString, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+/// The name of a run mode and its kwargs, which are typed in as a yaml mapping
+#[derive(Default)]
+pub struct RunModeWidget {
+    staging_name: StagingString<BoundedString<1, 1023>>,
+    raw_kwargs: String,
+}
+
+impl RunModeWidget {
+    fn kwargs(&self) -> Result<serde_yaml::Mapping> {
+        if self.raw_kwargs.trim().is_empty() {
+            return Ok(Default::default());
+        }
+        serde_yaml::from_str(&self.raw_kwargs).map_err(|err| GuiError::new(tr!("run-mode-bad-kwargs", reason = err.to_string())))
+    }
+}
+
+impl From<&RunMode> for RunModeWidget {
+    fn from(run_mode: &RunMode) -> Self {
+        Self {
+            staging_name: StagingString::default().with_raw(run_mode.name.to_string()),
+            raw_kwargs: match run_mode.kwargs.is_empty() {
+                true => String::new(),
+                false => serde_yaml::to_string(&run_mode.kwargs).unwrap_or_default(),
+            },
+        }
+    }
+}
+
+set_value_from!(RunModeWidget: RunMode);
+
+impl StatefulWidget for RunModeWidget {
+    type Value<'p> = Result<RunMode>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("run-mode-name-label"));
+            ui.horizontal(|ui| {
+                self.staging_name.draw_and_parse(ui, id.with("name"));
+                if ui.button(RunMode::DEEPIMAGEJ).clicked() {
+                    self.staging_name.set_raw(RunMode::DEEPIMAGEJ);
+                }
+            });
+            ui.end_row();
+
+            field_label(ui, &tr!("run-mode-kwargs-label"));
+            ui.vertical(|ui| {
+                ui.add(egui::TextEdit::multiline(&mut self.raw_kwargs).desired_rows(3).code_editor());
+                if let Err(err) = self.kwargs() {
+                    show_error(ui, err.to_string());
+                }
+            });
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(RunMode {
+            name: self.staging_name.state()?,
+            kwargs: self.kwargs()?,
+        })
+    }
+}
+
+restore_fields!(RunModeWidget { staging_name, raw_kwargs });
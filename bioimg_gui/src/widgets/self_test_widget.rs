@@ -0,0 +1,151 @@
+use bioimg_spec::runtime::{
+    inference::{load_backend, BackendConfig},
+    self_test::OutputComparison,
+    ModelPackage, SelfTestReport, Tolerance,
+};
+
+use super::{error_display::show_error, tensor_preview::TensorPreview};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::Task;
+
+/// One output of a test run, with what running the model produced next to the test output
+struct OutputPreview {
+    comparison: OutputComparison,
+    axis_names: Vec<String>,
+    found: TensorPreview,
+    expected: TensorPreview,
+}
+
+/// Runs a model on its test inputs in the background, and shows what it produced next to the test outputs
+#[derive(Default)]
+pub struct SelfTestWidget {
+    /// The axis names of every output, to label the previews with
+    axis_names: Vec<Vec<String>>,
+    pending: Option<Task<Result<SelfTestReport>>>,
+    outcome: Option<std::result::Result<Vec<OutputPreview>, Vec<String>>>,
+}
+
+impl SelfTestWidget {
+    pub fn is_running(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Whether there is a run to show, or one that is still going
+    pub fn is_active(&self) -> bool {
+        self.pending.is_some() || self.outcome.is_some()
+    }
+
+    /// Forgets the last run, and gives up on the current one
+    pub fn clear(&mut self) {
+        if let Some(task) = self.pending.take() {
+            task.cancel();
+        }
+        *self = Self::default();
+    }
+
+    pub fn start(&mut self, ctx: &egui::Context, package: ModelPackage, config: BackendConfig) {
+        self.clear();
+        self.axis_names = package
+            .rdf
+            .outputs
+            .iter()
+            .map(|output| output.axes.iter().map(|axis| axis.id().to_string()).collect())
+            .collect();
+        self.pending = Some(Task::spawn(tr!("running-test"), ctx.clone(), move |_| {
+            let mut backend = load_backend(&package, config)?;
+            Ok(package.self_test(&mut *backend, Tolerance::default())?)
+        }));
+    }
+
+    /// Shows `errors` instead of a run, for when the model couldn't even be put together
+    pub fn fail(&mut self, errors: Vec<String>) {
+        self.clear();
+        self.outcome = Some(Err(errors));
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if self.pending.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending.take().expect("checked above");
+            let report = task.join().map_err(GuiError::from).and_then(|result| result);
+            let axis_names = std::mem::take(&mut self.axis_names);
+            self.outcome = Some(match report {
+                Ok(report) => Ok(report
+                    .outputs
+                    .into_iter()
+                    .zip(axis_names.into_iter().chain(std::iter::repeat(vec![])))
+                    .map(|(mut comparison, axis_names)| OutputPreview {
+                        // the previews keep the values, so the comparison doesn't need its own copy
+                        found: TensorPreview::of_array(std::mem::take(&mut comparison.found)),
+                        expected: TensorPreview::of_array(std::mem::take(&mut comparison.expected)),
+                        comparison,
+                        axis_names,
+                    })
+                    .collect()),
+                Err(err) => Err(vec![err.to_string()]),
+            });
+        }
+
+        let outputs = match &mut self.outcome {
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr!("running-test"));
+                });
+                return;
+            }
+            Some(Err(errors)) => {
+                ui.label(tr!("self-test-not-run"));
+                for err in errors.iter() {
+                    show_error(ui, err);
+                }
+                return;
+            }
+            Some(Ok(outputs)) => outputs,
+        };
+        if outputs.iter().all(|output| output.comparison.passed()) {
+            ui.label(tr!("self-test-passed"));
+        } else {
+            ui.label(egui::RichText::new(tr!("self-test-failed")).color(ui.visuals().error_fg_color));
+        }
+        egui::ScrollArea::vertical().max_height(600.0).show(ui, |ui| {
+            for (idx, output) in outputs.iter_mut().enumerate() {
+                let comparison = &output.comparison;
+                ui.separator();
+                ui.strong(comparison.tensor_id.to_string());
+                if comparison.expected_shape != comparison.found_shape {
+                    let shape = |shape: &[usize]| shape.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                    show_error(
+                        ui,
+                        tr!(
+                            "self-test-shape-mismatch",
+                            found = shape(&comparison.found_shape),
+                            expected = shape(&comparison.expected_shape)
+                        ),
+                    );
+                } else {
+                    ui.label(tr!("self-test-max-difference", difference = format!("{:.3e}", comparison.max_difference)));
+                    ui.label(tr!(
+                        "self-test-mismatched",
+                        mismatched = comparison.mismatched,
+                        total = comparison.expected_shape.iter().product::<usize>()
+                    ));
+                }
+                ui.horizontal_top(|ui| {
+                    ui.push_id((idx, "produced"), |ui| {
+                        ui.vertical(|ui| {
+                            ui.weak(tr!("self-test-produced"));
+                            output.found.show_loaded(ui, &output.axis_names);
+                        });
+                    });
+                    ui.push_id((idx, "expected"), |ui| {
+                        ui.vertical(|ui| {
+                            ui.weak(tr!("self-test-expected"));
+                            output.expected.show_loaded(ui, &output.axis_names);
+                        });
+                    });
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,255 @@
+use bioimg_spec::rdf::bounded_string::BoundedString;
+use bioimg_spec::runtime::{TagUsage, ZooClient};
+
+use super::{error_display::show_error, Restore, SetValue, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::Task;
+
+pub type Tag = BoundedString<1, 1023>;
+
+/// More than this many tags don't help anyone find the resource
+pub const MAX_TAGS: usize = 64;
+
+/// How many of the published tags are offered for what is being typed
+const MAX_SUGGESTIONS: usize = 6;
+
+fn parse_tag(raw: &str) -> Result<Tag> {
+    if let Some(c) = raw.chars().find(|c| c.is_control()) {
+        return Err(GuiError::new(tr!("tag-bad-character", character = c.escape_default().to_string())));
+    }
+    Ok(Tag::try_from(raw.to_owned())?)
+}
+
+struct TagChip {
+    raw: String,
+    parsed: Result<Tag>,
+}
+
+/// Tags shown as chips that can be removed one by one. New tags are typed in and added with Enter or a comma.
+#[derive(Default)]
+pub struct TagsWidget {
+    chips: Vec<TagChip>,
+    raw_input: String,
+    /// Why the last tag typed in was not added
+    input_error: Option<String>,
+    focus_input: bool,
+    /// The tags used across the published collection, the most used first, to complete the input with.
+    /// They are only fetched once the input is first used.
+    published_tags: Vec<TagUsage>,
+    pending_published_tags: Option<Task<Result<Vec<TagUsage>>>>,
+    requested_published_tags: bool,
+}
+
+impl TagsWidget {
+    /// Replaces all tags, e.g. with the default ones of the author profile
+    pub fn set_tags<'t>(&mut self, tags: impl IntoIterator<Item = &'t str>) {
+        self.chips.clear();
+        for tag in tags {
+            let _ = self.add_tag(tag);
+        }
+    }
+
+    /// Moves the keyboard focus to the field new tags are typed into
+    pub fn focus_input(&mut self) {
+        self.focus_input = true;
+    }
+
+    fn add_tag(&mut self, raw: &str) -> Result<()> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return Ok(());
+        }
+        if self.chips.iter().any(|chip| chip.raw == raw) {
+            return Err(GuiError::new(tr!("tag-duplicate", tag = raw.to_owned())));
+        }
+        if self.chips.len() >= MAX_TAGS {
+            return Err(GuiError::new(tr!("too-many-tags", max = MAX_TAGS)));
+        }
+        self.chips.push(TagChip {
+            raw: raw.to_owned(),
+            parsed: parse_tag(raw),
+        });
+        Ok(())
+    }
+
+    fn request_published_tags(&mut self, ctx: &egui::Context) {
+        self.requested_published_tags = true;
+        self.pending_published_tags = Some(Task::spawn(tr!("loading-published-tags"), ctx.clone(), |_| {
+            Ok(ZooClient::bioimage_io()?.tag_usage()?)
+        }));
+    }
+
+    /// Published tags that complete what is being typed, those starting with it first, leaving out the ones
+    /// that were added already
+    fn suggestions(&self) -> Vec<&TagUsage> {
+        let typed = self.raw_input.rsplit(',').next().unwrap_or_default().trim().to_lowercase();
+        if typed.is_empty() {
+            return vec![];
+        }
+        let candidates = self.published_tags.iter().filter(|usage| {
+            let tag = usage.tag.to_lowercase();
+            tag != typed && !self.chips.iter().any(|chip| chip.raw.to_lowercase() == tag)
+        });
+        let (mut starting, containing): (Vec<_>, Vec<_>) = candidates
+            .filter(|usage| usage.tag.to_lowercase().contains(&typed))
+            .partition(|usage| usage.tag.to_lowercase().starts_with(&typed));
+        starting.extend(containing);
+        starting.truncate(MAX_SUGGESTIONS);
+        starting
+    }
+
+    /// Adds `tag` in place of what is being typed
+    fn accept_suggestion(&mut self, tag: &str) {
+        let kept = self.raw_input.rsplit_once(',').map(|(kept, _)| kept.to_owned()).unwrap_or_default();
+        self.raw_input = kept;
+        self.commit_input(true);
+        if let Err(err) = self.add_tag(tag) {
+            self.input_error = Some(err.to_string());
+        }
+        self.focus_input = true;
+    }
+
+    /// Adds every complete tag in the input, leaving what is still being typed
+    fn commit_input(&mut self, everything: bool) {
+        let input = std::mem::take(&mut self.raw_input);
+        let mut parts: Vec<&str> = input.split(',').collect();
+        let pending = match everything {
+            true => "",
+            false => parts.pop().unwrap_or_default(),
+        };
+        self.input_error = None;
+        let mut rejected = vec![];
+        for part in parts {
+            if let Err(err) = self.add_tag(part) {
+                self.input_error = Some(err.to_string());
+                rejected.push(part.trim());
+            }
+        }
+        rejected.push(pending);
+        self.raw_input = rejected.into_iter().filter(|part| !part.is_empty()).collect::<Vec<_>>().join(", ");
+    }
+}
+
+impl<'t, I: IntoIterator<Item = &'t str>> SetValue<I> for TagsWidget {
+    fn set_value(&mut self, tags: I) {
+        self.set_tags(tags)
+    }
+}
+
+impl StatefulWidget for TagsWidget {
+    type Value<'p> = Result<Vec<Tag>>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        let mut removed = None;
+        ui.vertical(|ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (idx, chip) in self.chips.iter().enumerate() {
+                    let stroke_color = match chip.parsed {
+                        Ok(_) => ui.visuals().widgets.inactive.bg_stroke.color,
+                        Err(_) => ui.visuals().error_fg_color,
+                    };
+                    let frame = egui::Frame::none()
+                        .fill(ui.visuals().faint_bg_color)
+                        .stroke(egui::Stroke::new(1.0, stroke_color))
+                        .rounding(8.0)
+                        .inner_margin(egui::Margin::symmetric(6.0, 2.0));
+                    let response = frame
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(&chip.raw);
+                                let remove_button = egui::Button::new("🗙").small().frame(false);
+                                if ui.add(remove_button).on_hover_text(tr!("remove-tag")).clicked() {
+                                    removed = Some(idx);
+                                }
+                            });
+                        })
+                        .response;
+                    if let Err(err) = &chip.parsed {
+                        response.on_hover_text(err.to_string());
+                    }
+                }
+
+                let input = egui::TextEdit::singleline(&mut self.raw_input)
+                    .id(id.with("input"))
+                    .hint_text(tr!("add-tag-hint"))
+                    .desired_width(120.0);
+                let response = ui.add_enabled(self.chips.len() < MAX_TAGS, input);
+                if self.focus_input {
+                    response.request_focus();
+                    self.focus_input = false;
+                }
+                if response.gained_focus() && !self.requested_published_tags {
+                    self.request_published_tags(ui.ctx());
+                }
+                if response.changed() && self.raw_input.contains(',') {
+                    self.commit_input(false);
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.commit_input(true);
+                    response.request_focus();
+                }
+            });
+            let mut accepted = None;
+            let suggestions = self.suggestions();
+            if !suggestions.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.weak(tr!("suggested-tags"));
+                    for usage in suggestions {
+                        let hint = tr!("suggested-tag-hint", count = usage.count);
+                        if ui.small_button(&usage.tag).on_hover_text(hint).clicked() {
+                            accepted = Some(usage.tag.clone());
+                        }
+                    }
+                });
+            }
+            if let Some(tag) = accepted {
+                self.accept_suggestion(&tag);
+            }
+            if let Some(err) = &self.input_error {
+                show_error(ui, err);
+            }
+            let invalid: Vec<_> = self.chips.iter().filter_map(|chip| Some((&chip.raw, chip.parsed.as_ref().err()?))).collect();
+            for (raw, err) in invalid {
+                show_error(ui, format!("{raw}: {err}"));
+            }
+        });
+        if let Some(idx) = removed {
+            self.chips.remove(idx);
+        }
+        if self.pending_published_tags.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_published_tags.take().expect("checked above");
+            // completions are only a help, so the tags are typed in by hand when there are none
+            match task.join().map_err(GuiError::from).and_then(|result| result) {
+                Ok(tags) => self.published_tags = tags,
+                Err(err) => log::warn!("{}", tr!("published-tags-failed", error = err.to_string())),
+            }
+        }
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.chips
+            .iter()
+            .map(|chip| chip.parsed.clone().map_err(|err| GuiError::new(format!("{}: {err}", chip.raw))))
+            .collect()
+    }
+}
+
+impl Restore for TagsWidget {
+    fn dump(&self) -> serde_yaml::Value {
+        let mut fields = serde_yaml::Mapping::new();
+        let tags: Vec<_> = self.chips.iter().map(|chip| chip.raw.clone()).collect();
+        fields.insert("tags".into(), tags.into());
+        fields.insert("input".into(), self.raw_input.clone().into());
+        fields.into()
+    }
+
+    fn restore(&mut self, _ctx: &egui::Context, value: &serde_yaml::Value) {
+        if let Some(tags) = value.get("tags").and_then(|tags| tags.as_sequence()) {
+            self.set_tags(tags.iter().filter_map(|tag| tag.as_str()));
+        }
+        if let Some(input) = value.get("input").and_then(|input| input.as_str()) {
+            self.raw_input = input.to_owned();
+        }
+    }
+}
@@ -3,13 +3,41 @@ use std::num::NonZeroUsize;
 use bioimg_spec::rdf;
 use bioimg_spec::rdf::bounded_string::BoundedString;
 use bioimg_spec::rdf::model as modelrdf;
+use serde::{Deserialize, Serialize};
 
+use super::accessibility::{field_label, field_label_with_help};
 use super::axis_size_widget::AnyAxisSizeWidget;
 use super::enum_widget::EnumWidget;
+use super::error_display::show_error;
 use super::util::group_frame;
-use super::{InputLines, StagingNum, StagingOpt, StagingString, StagingVec, StatefulWidget};
+use super::{
+    restore_fields, restore_with_serde, set_value_from, InputLines, StagingNum, StagingOpt, StagingString, StagingVec,
+    StatefulWidget,
+};
+use crate::i18n::tr;
 use crate::result::{GuiError, Result};
 
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum AxisType {
+    Batch,
+    Channel,
+    Index,
+    Time,
+    #[default]
+    Space,
+}
+
+pub fn axis_type_picker(ui: &mut egui::Ui, axis_type: &mut AxisType) {
+    ui.horizontal(|ui| {
+        field_label(ui, &tr!("axis-type-label"));
+        ui.selectable_value(axis_type, AxisType::Batch, tr!("batch-axis"));
+        ui.selectable_value(axis_type, AxisType::Channel, tr!("channel-axis"));
+        ui.selectable_value(axis_type, AxisType::Index, tr!("index-axis"));
+        ui.selectable_value(axis_type, AxisType::Time, tr!("time-axis"));
+        ui.selectable_value(axis_type, AxisType::Space, tr!("space-axis"));
+    });
+}
+
 pub struct BatchAxisWidget {
     pub staging_id: StagingString<modelrdf::axes::AxisId>,
     pub staging_description: StagingString<BoundedString<0, { 128 - 1 }>>,
@@ -23,6 +51,8 @@ impl Default for BatchAxisWidget {
                 raw: "batch".into(),
                 parsed: modelrdf::axes::AxisId::try_from("batch".to_owned()).map_err(GuiError::from),
                 input_lines: InputLines::SingleLine,
+                touched: false,
+                conflict: None,
             },
             staging_description: Default::default(),
             staging_allow_auto_size: true,
@@ -30,21 +60,33 @@ impl Default for BatchAxisWidget {
     }
 }
 
+impl From<&modelrdf::axes::BatchAxis> for BatchAxisWidget {
+    fn from(axis: &modelrdf::axes::BatchAxis) -> Self {
+        Self {
+            staging_id: StagingString::default().with_raw(axis.id.to_string()),
+            staging_description: StagingString::default().with_raw(axis.description.to_string()),
+            staging_allow_auto_size: axis.size.is_none(),
+        }
+    }
+}
+
+set_value_from!(BatchAxisWidget: modelrdf::axes::BatchAxis);
+
 impl StatefulWidget for BatchAxisWidget {
     type Value<'p> = Result<modelrdf::axes::BatchAxis>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Id: ");
+                field_label_with_help(ui, &tr!("id-label"), "inputs.axes.id");
                 self.staging_id.draw_and_parse(ui, id.with("id"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Description: ");
+                field_label(ui, &tr!("description-label"));
                 self.staging_description.draw_and_parse(ui, id.with("description"));
             });
             ui.horizontal(|ui| {
-                ui.checkbox(&mut self.staging_allow_auto_size, "Allow auto size");
+                ui.checkbox(&mut self.staging_allow_auto_size, tr!("allow-auto-size"));
             });
         });
     }
@@ -69,23 +111,35 @@ pub struct IndexAxisWidget {
     pub staging_size: AnyAxisSizeWidget,
 }
 
+impl From<&modelrdf::axes::IndexAxis> for IndexAxisWidget {
+    fn from(axis: &modelrdf::axes::IndexAxis) -> Self {
+        Self {
+            staging_id: StagingString::default().with_raw(axis.id.to_string()),
+            staging_description: StagingString::default().with_raw(axis.description.to_string()),
+            staging_size: AnyAxisSizeWidget::from(&axis.size),
+        }
+    }
+}
+
+set_value_from!(IndexAxisWidget: modelrdf::axes::IndexAxis);
+
 impl StatefulWidget for IndexAxisWidget {
     type Value<'p> = Result<modelrdf::axes::IndexAxis>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Id: ");
+                field_label_with_help(ui, &tr!("id-label"), "inputs.axes.id");
                 self.staging_id.draw_and_parse(ui, id.with("Id"));
             });
 
             ui.horizontal(|ui| {
-                ui.strong("Description: ");
+                field_label(ui, &tr!("description-label"));
                 self.staging_description.draw_and_parse(ui, id.with("Description"));
             });
 
             ui.horizontal(|ui| {
-                ui.strong("Size: ");
+                field_label(ui, &tr!("size-label"));
                 group_frame(ui, |ui| {
                     self.staging_size.draw_and_parse(ui, id.with("Size: "));
                 });
@@ -102,7 +156,7 @@ impl StatefulWidget for IndexAxisWidget {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Default)]
+#[derive(Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ChannelNamesMode {
     #[default]
     Explicit,
@@ -120,6 +174,8 @@ pub struct ChannelAxisWidget {
     pub staging_pattern_suffix: StagingString<String>,
 
     pub staging_explicit_names: StagingVec<StagingString<rdf::Identifier<String>>>,
+    /// Optional for explicit names, which then have to be this many
+    pub staging_explicit_size: StagingOpt<StagingNum<usize, NonZeroUsize>>,
 }
 
 impl Default for ChannelAxisWidget {
@@ -135,55 +191,101 @@ impl Default for ChannelAxisWidget {
             staging_pattern_suffix: Default::default(),
 
             staging_explicit_names: StagingVec {
-                item_name: "Channel Name".into(),
+                item_name: "channel-name".into(),
                 staging: vec![],
             },
+            staging_explicit_size: Default::default(),
         }
     }
 }
 
+impl From<&modelrdf::ChannelAxis> for ChannelAxisWidget {
+    /// Imported channels are always listed explicitly, even if their names follow a pattern
+    fn from(axis: &modelrdf::ChannelAxis) -> Self {
+        let mut widget = Self {
+            staging_id: StagingString::default().with_raw(axis.id.to_string()),
+            staging_description: StagingString::default().with_raw(axis.description.to_string()),
+            channel_names_mode: ChannelNamesMode::Explicit,
+            ..Default::default()
+        };
+        widget.staging_explicit_names.staging = axis
+            .channel_names
+            .iter()
+            .map(|name| StagingString::default().with_raw(name.to_string()))
+            .collect();
+        widget
+    }
+}
+
+set_value_from!(ChannelAxisWidget: modelrdf::ChannelAxis);
+
 impl StatefulWidget for ChannelAxisWidget {
     type Value<'p> = Result<modelrdf::ChannelAxis>;
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Id: ");
+                field_label_with_help(ui, &tr!("id-label"), "inputs.axes.id");
                 self.staging_id.draw_and_parse(ui, id.with("id"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Description: ");
+                field_label(ui, &tr!("description-label"));
                 self.staging_description.draw_and_parse(ui, id.with("description"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Channel Names: ");
-                ui.radio_value(&mut self.channel_names_mode, ChannelNamesMode::Pattern, "Pattern");
-                ui.radio_value(&mut self.channel_names_mode, ChannelNamesMode::Explicit, "Explicit");
+                field_label(ui, &tr!("channel-names-label"));
+                ui.radio_value(&mut self.channel_names_mode, ChannelNamesMode::Pattern, tr!("pattern"));
+                ui.radio_value(&mut self.channel_names_mode, ChannelNamesMode::Explicit, tr!("explicit"));
             });
             match self.channel_names_mode {
                 ChannelNamesMode::Pattern => {
                     ui.horizontal(|ui| {
-                        ui.strong("Extent: ");
+                        field_label(ui, &tr!("extent-label"));
                         self.staging_pattern_extent.draw_and_parse(ui, id.with("extent"));
 
-                        ui.strong("Prefix: ");
+                        field_label(ui, &tr!("prefix-label"));
                         self.staging_pattern_prefix.draw_and_parse(ui, id.with("prefix"));
 
-                        ui.strong("Suffix: ");
+                        field_label(ui, &tr!("suffix-label"));
                         self.staging_pattern_suffix.draw_and_parse(ui, id.with("suffix"));
                     });
                 }
                 ChannelNamesMode::Explicit => {
+                    ui.horizontal(|ui| {
+                        field_label(ui, &tr!("size-label"));
+                        self.staging_explicit_size.draw_and_parse(ui, id.with("explicit size"));
+                    });
                     self.staging_explicit_names.draw_and_parse(ui, id.with("explicit"));
                 }
             };
+            if let (Ok(channel_names), Ok(size)) = (self.channel_names(), self.declared_size()) {
+                if let Err(err) = modelrdf::ChannelAxis::check_size(&channel_names, size) {
+                    show_error(ui, err.to_string());
+                }
+            }
         });
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
-        let id = self.staging_id.state()?;
-        let description = self.staging_description.state()?;
+        modelrdf::ChannelAxis::new(
+            self.staging_id.state()?,
+            self.staging_description.state()?,
+            self.channel_names()?,
+            self.declared_size()?,
+        )
+        .map_err(|err| GuiError::new(err.to_string()))
+    }
+}
+
+impl ChannelAxisWidget {
+    fn declared_size(&self) -> Result<Option<usize>> {
+        Ok(match self.channel_names_mode {
+            ChannelNamesMode::Pattern => Some(self.staging_pattern_extent.state()?.get()),
+            ChannelNamesMode::Explicit => self.staging_explicit_size.state().transpose()?.map(NonZeroUsize::get),
+        })
+    }
 
-        let channel_names = match self.channel_names_mode {
+    fn channel_names(&self) -> Result<Vec<rdf::Identifier<String>>> {
+        Ok(match self.channel_names_mode {
             ChannelNamesMode::Pattern => {
                 let extent: usize = self.staging_pattern_extent.state()?.into();
                 (0..extent)
@@ -200,12 +302,6 @@ impl StatefulWidget for ChannelAxisWidget {
                     self.staging_explicit_names.state().into_iter().collect();
                 channel_names_result?
             }
-        };
-
-        Ok(modelrdf::ChannelAxis {
-            id,
-            description,
-            channel_names,
         })
     }
 }
@@ -218,28 +314,55 @@ pub struct TimeInputAxisWidget {
     pub size_widget: AnyAxisSizeWidget,
 }
 
+impl Default for TimeInputAxisWidget {
+    fn default() -> Self {
+        Self {
+            staging_id: Default::default(),
+            staging_description: Default::default(),
+            unit_widget: Default::default(),
+            scale_widget: StagingNum::new(1.0).with_speed(0.01),
+            size_widget: Default::default(),
+        }
+    }
+}
+
+impl From<&modelrdf::TimeInputAxis> for TimeInputAxisWidget {
+    fn from(axis: &modelrdf::TimeInputAxis) -> Self {
+        Self {
+            staging_id: StagingString::default().with_raw(axis.id.to_string()),
+            staging_description: StagingString::default().with_raw(axis.description.to_string()),
+            unit_widget: axis.unit.map(EnumWidget::from).into(),
+            scale_widget: StagingNum::new(axis.scale.get()).with_speed(0.01),
+            size_widget: AnyAxisSizeWidget::from(&axis.size),
+        }
+    }
+}
+
+set_value_from!(TimeInputAxisWidget: modelrdf::TimeInputAxis);
+
 impl StatefulWidget for TimeInputAxisWidget {
     type Value<'p> = Result<modelrdf::TimeInputAxis>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Id: ");
+                field_label_with_help(ui, &tr!("id-label"), "inputs.axes.id");
                 self.staging_id.draw_and_parse(ui, id.with("id"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Description: ");
+                field_label(ui, &tr!("description-label"));
                 self.staging_description.draw_and_parse(ui, id.with("description"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Unit: ");
+                field_label(ui, &tr!("unit-label"));
                 self.unit_widget.draw_and_parse(ui, id.with("unit"));
 
-                ui.strong("Scale: ");
-                self.unit_widget.draw_and_parse(ui, id.with("scale"));
+                field_label(ui, &tr!("scale-label"));
+                self.scale_widget.suffix = self.unit_widget.state().map(|unit| format!(" {}", unit.symbol())).unwrap_or_default();
+                self.scale_widget.draw_and_parse(ui, id.with("scale"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Size: ");
+                field_label(ui, &tr!("size-label"));
                 self.size_widget.draw_and_parse(ui, id.with("size"));
             });
         });
@@ -264,28 +387,55 @@ pub struct SpaceInputAxisWidget {
     pub size_widget: AnyAxisSizeWidget,
 }
 
+impl Default for SpaceInputAxisWidget {
+    fn default() -> Self {
+        Self {
+            staging_id: Default::default(),
+            staging_description: Default::default(),
+            unit_widget: Default::default(),
+            scale_widget: StagingNum::new(1.0).with_speed(0.01),
+            size_widget: Default::default(),
+        }
+    }
+}
+
+impl From<&modelrdf::SpaceInputAxis> for SpaceInputAxisWidget {
+    fn from(axis: &modelrdf::SpaceInputAxis) -> Self {
+        Self {
+            staging_id: StagingString::default().with_raw(axis.id.to_string()),
+            staging_description: StagingString::default().with_raw(axis.description.to_string()),
+            unit_widget: axis.unit.map(EnumWidget::from).into(),
+            scale_widget: StagingNum::new(axis.scale.get()).with_speed(0.01),
+            size_widget: AnyAxisSizeWidget::from(&axis.size),
+        }
+    }
+}
+
+set_value_from!(SpaceInputAxisWidget: modelrdf::SpaceInputAxis);
+
 impl StatefulWidget for SpaceInputAxisWidget {
     type Value<'p> = Result<modelrdf::SpaceInputAxis>;
 
     fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
-                ui.strong("Id: ");
+                field_label_with_help(ui, &tr!("id-label"), "inputs.axes.id");
                 self.staging_id.draw_and_parse(ui, id.with("id"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Description: ");
+                field_label(ui, &tr!("description-label"));
                 self.staging_description.draw_and_parse(ui, id.with("description"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Unit: ");
+                field_label(ui, &tr!("unit-label"));
                 self.unit_widget.draw_and_parse(ui, id.with("unit"));
 
-                ui.strong("Scale: ");
-                self.unit_widget.draw_and_parse(ui, id.with("scale"));
+                field_label(ui, &tr!("scale-label"));
+                self.scale_widget.suffix = self.unit_widget.state().map(|unit| format!(" {}", unit.symbol())).unwrap_or_default();
+                self.scale_widget.draw_and_parse(ui, id.with("scale"));
             });
             ui.horizontal(|ui| {
-                ui.strong("Size: ");
+                field_label(ui, &tr!("size-label"));
                 self.size_widget.draw_and_parse(ui, id.with("size"));
             });
         });
@@ -301,3 +451,19 @@ impl StatefulWidget for SpaceInputAxisWidget {
         })
     }
 }
+
+restore_with_serde!(AxisType, ChannelNamesMode);
+restore_fields!(BatchAxisWidget { staging_id, staging_description, staging_allow_auto_size });
+restore_fields!(IndexAxisWidget { staging_id, staging_description, staging_size });
+restore_fields!(ChannelAxisWidget {
+    staging_id,
+    staging_description,
+    channel_names_mode,
+    staging_pattern_extent,
+    staging_pattern_prefix,
+    staging_pattern_suffix,
+    staging_explicit_names,
+    staging_explicit_size,
+});
+restore_fields!(TimeInputAxisWidget { staging_id, staging_description, unit_widget, scale_widget, size_widget });
+restore_fields!(SpaceInputAxisWidget { staging_id, staging_description, unit_widget, scale_widget, size_widget });
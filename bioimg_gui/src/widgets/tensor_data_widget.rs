@@ -0,0 +1,183 @@
+use bioimg_spec::rdf::model::{
+    data_range::DataRange,
+    data_type::DataType,
+    tensor_data_descr::{TVs, TensorDataDescr, TensorDataUnit},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accessibility::field_label, enum_widget::EnumWidget, error_display::show_error, restore_fields, restore_with_serde,
+    set_value_from, SetValue, StagingNum, StagingOpt, StagingString, StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum TensorDataKind {
+    #[default]
+    IntervalOrRatio,
+    NominalOrOrdinal,
+}
+
+/// What the values of a tensor mean. Interval data gets a range, unit, scale and offset, while
+/// categorical data lists its values as yaml, e.g. `[background, nucleus]`.
+pub struct TensorDataWidget {
+    pub kind: TensorDataKind,
+    pub staging_data_type: EnumWidget<DataType>,
+    pub staging_min: StagingOpt<StagingNum<f64, f64>>,
+    pub staging_max: StagingOpt<StagingNum<f64, f64>>,
+    /// Left empty for an arbitrary unit
+    pub staging_unit: StagingOpt<StagingString<TensorDataUnit>>,
+    pub staging_scale: StagingNum<f64, f64>,
+    pub staging_offset: StagingOpt<StagingNum<f64, f64>>,
+    pub raw_values: String,
+}
+
+impl Default for TensorDataWidget {
+    fn default() -> Self {
+        Self {
+            kind: Default::default(),
+            staging_data_type: Default::default(),
+            staging_min: Default::default(),
+            staging_max: Default::default(),
+            staging_unit: Default::default(),
+            staging_scale: StagingNum::new(1.0).with_speed(0.01),
+            staging_offset: Default::default(),
+            raw_values: String::new(),
+        }
+    }
+}
+
+/// The values as a one line yaml list, the way they are typed in
+fn flow_yaml(values: &TVs) -> String {
+    let Ok(serde_yaml::Value::Sequence(items)) = serde_yaml::to_value(values) else {
+        return String::new();
+    };
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| serde_yaml::to_string(item).unwrap_or_default().trim_end().to_owned())
+        .collect();
+    format!("[{}]", items.join(", "))
+}
+
+impl From<&TensorDataDescr> for TensorDataWidget {
+    fn from(descr: &TensorDataDescr) -> Self {
+        let mut widget = Self::default();
+        match descr {
+            TensorDataDescr::IntervalOrRatio(interval) => {
+                widget.kind = TensorDataKind::IntervalOrRatio;
+                widget.staging_data_type.set_value(interval.data_type);
+                widget.staging_min = interval.range.0.map(StagingNum::new).into();
+                widget.staging_max = interval.range.1.map(StagingNum::new).into();
+                widget.staging_unit = match &interval.unit {
+                    TensorDataUnit::ArbitraryUnit => StagingOpt::default(),
+                    unit => StagingOpt::from_raw(&String::from(unit.clone())),
+                };
+                widget.staging_scale = StagingNum::new(interval.scale).with_speed(0.01);
+                widget.staging_offset = interval.offset.map(StagingNum::new).into();
+            }
+            TensorDataDescr::NominalOrOrdinal(nominal) => {
+                widget.kind = TensorDataKind::NominalOrOrdinal;
+                widget.staging_data_type.set_value(nominal.data_type);
+                widget.raw_values = flow_yaml(&nominal.values);
+            }
+        }
+        widget
+    }
+}
+
+set_value_from!(TensorDataWidget: TensorDataDescr);
+
+impl TensorDataWidget {
+    fn values(&self) -> Result<TVs> {
+        serde_yaml::from_str(&self.raw_values)
+            .map_err(|err| GuiError::new(tr!("tensor-data-bad-values", reason = err.to_string())))
+    }
+
+    fn interval_or_ratio(&self) -> Result<TensorDataDescr> {
+        let range = DataRange(self.staging_min.state().transpose()?, self.staging_max.state().transpose()?);
+        let unit = self.staging_unit.state().transpose()?.unwrap_or_default();
+        let mut descr = TensorDataDescr::interval_or_ratio(self.staging_data_type.state(), range, unit)
+            .map_err(|err| GuiError::new(err.to_string()))?;
+        if let TensorDataDescr::IntervalOrRatio(interval) = &mut descr {
+            interval.scale = self.staging_scale.state()?;
+            interval.offset = self.staging_offset.state().transpose()?;
+        }
+        Ok(descr)
+    }
+
+    fn nominal_or_ordinal(&self) -> Result<TensorDataDescr> {
+        TensorDataDescr::nominal_or_ordinal(self.values()?, self.staging_data_type.state())
+            .map_err(|err| GuiError::new(err.to_string()))
+    }
+}
+
+impl StatefulWidget for TensorDataWidget {
+    type Value<'p> = Result<TensorDataDescr>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("tensor-data-kind-label"));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.kind, TensorDataKind::IntervalOrRatio, tr!("interval-or-ratio"));
+                ui.radio_value(&mut self.kind, TensorDataKind::NominalOrOrdinal, tr!("nominal-or-ordinal"));
+            });
+            ui.end_row();
+
+            field_label(ui, &tr!("data-type-label"));
+            self.staging_data_type.draw_and_parse(ui, id.with("Data Type"));
+            ui.end_row();
+
+            match self.kind {
+                TensorDataKind::IntervalOrRatio => {
+                    field_label(ui, &tr!("min-label"));
+                    self.staging_min.draw_and_parse(ui, id.with("Min"));
+                    ui.end_row();
+
+                    field_label(ui, &tr!("max-label"));
+                    self.staging_max.draw_and_parse(ui, id.with("Max"));
+                    ui.end_row();
+
+                    field_label(ui, &tr!("unit-label"));
+                    self.staging_unit.draw_and_parse(ui, id.with("Unit"));
+                    ui.end_row();
+
+                    field_label(ui, &tr!("scale-label"));
+                    self.staging_scale.draw_and_parse(ui, id.with("Scale"));
+                    ui.end_row();
+
+                    field_label(ui, &tr!("offset-label"));
+                    self.staging_offset.draw_and_parse(ui, id.with("Offset"));
+                    ui.end_row();
+                }
+                TensorDataKind::NominalOrOrdinal => {
+                    field_label(ui, &tr!("tensor-data-values-label"));
+                    ui.add(egui::TextEdit::singleline(&mut self.raw_values).code_editor());
+                    ui.end_row();
+                }
+            }
+        });
+        if let Err(err) = self.state() {
+            show_error(ui, err.to_string());
+        }
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        match self.kind {
+            TensorDataKind::IntervalOrRatio => self.interval_or_ratio(),
+            TensorDataKind::NominalOrOrdinal => self.nominal_or_ordinal(),
+        }
+    }
+}
+
+restore_with_serde!(TensorDataKind);
+restore_fields!(TensorDataWidget {
+    kind,
+    staging_data_type,
+    staging_min,
+    staging_max,
+    staging_unit,
+    staging_scale,
+    staging_offset,
+    raw_values,
+});
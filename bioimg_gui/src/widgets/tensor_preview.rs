@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use bioimg_spec::runtime::{array_to_image, ImageAxis, MappedNpy};
+use ndarray::{ArrayD, Axis};
+
+use super::error_display::show_error;
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::Task;
+
+/// Size in points of the longest side of the previewed slice
+const PREVIEW_SIZE: f32 = 256.0;
+/// Fraction of the values at either end of the range that are clipped when scaling the contrast,
+/// so that a few outliers don't leave the rest of the slice in one shade of gray
+const CONTRAST_CLIP: f32 = 0.005;
+
+enum TensorValues {
+    /// Values with at least two dimensions, so that there is always a slice to show
+    InMemory(ArrayD<f32>),
+    /// A file of at least two dimensions that is mapped rather than read, so that only the slices that are
+    /// shown are loaded, even when the tensor takes several GB
+    Mapped(MappedNpy),
+}
+
+impl TensorValues {
+    fn in_memory(mut data: ArrayD<f32>) -> Self {
+        while data.ndim() < 2 {
+            data.insert_axis_inplace(Axis(0));
+        }
+        Self::InMemory(data)
+    }
+
+    fn shape(&self) -> &[usize] {
+        match self {
+            Self::InMemory(data) => data.shape(),
+            Self::Mapped(npy) => npy.shape(),
+        }
+    }
+
+    /// The values along the axes `selection` shows, at the picked index along every other one
+    fn slice(&self, selection: &SliceSelection) -> ArrayD<f32> {
+        let ndim = self.shape().len();
+        let shown = |axis: usize| axis == selection.rows || axis == selection.columns || Some(axis) == selection.color;
+        match self {
+            Self::InMemory(data) => {
+                let mut view = data.view();
+                for axis in (0..ndim).rev().filter(|axis| !shown(*axis)) {
+                    view = view.index_axis_move(Axis(axis), selection.indices[axis]);
+                }
+                view.to_owned()
+            }
+            Self::Mapped(npy) => {
+                let indices: Vec<Option<usize>> =
+                    (0..ndim).map(|axis| (!shown(axis)).then_some(selection.indices[axis])).collect();
+                npy.read_slice(&indices).expect("the sliders stay within the shape")
+            }
+        }
+    }
+}
+
+struct LoadedTensor {
+    dtype: String,
+    shape: Vec<usize>,
+    values: TensorValues,
+}
+
+impl LoadedTensor {
+    fn read(path: &Path) -> Result<Self> {
+        let npy = MappedNpy::open(path)?;
+        let shape = npy.shape().to_vec();
+        Ok(Self {
+            dtype: npy.header().data_type.to_string(),
+            values: if shape.len() < 2 { TensorValues::in_memory(npy.read_all()) } else { TensorValues::Mapped(npy) },
+            shape,
+        })
+    }
+}
+
+/// Which slice of the tensor is shown: the axes that are its rows and columns, and the index along every other one
+#[derive(Clone, PartialEq, Eq)]
+struct SliceSelection {
+    rows: usize,
+    columns: usize,
+    /// The channel axis, if the slice is shown in color
+    color: Option<usize>,
+    indices: Vec<usize>,
+}
+
+impl SliceSelection {
+    /// The last two axes, which are y and x in the usual C ordered layouts, at the first index of every other one
+    fn new(ndim: usize) -> Self {
+        Self {
+            rows: ndim - 2,
+            columns: ndim - 1,
+            color: None,
+            indices: vec![0; ndim],
+        }
+    }
+}
+
+/// Shows a slice of an `.npy` tensor as a grayscale or color image, with sliders to move along the axes that aren't
+/// shown, so that it's easy to see whether the right tensor was picked
+#[derive(Default)]
+pub struct TensorPreview {
+    path: Option<PathBuf>,
+    pending: Option<Task<Result<LoadedTensor>>>,
+    loaded: Option<Result<LoadedTensor>>,
+    selection: Option<SliceSelection>,
+    texture: Option<(SliceSelection, egui::TextureHandle)>,
+}
+
+impl TensorPreview {
+    /// Previews `data`, which is already loaded, like a tensor a model produced
+    pub fn of_array(data: ArrayD<f32>) -> Self {
+        let tensor = LoadedTensor {
+            dtype: "float32".into(),
+            shape: data.shape().to_vec(),
+            values: TensorValues::in_memory(data),
+        };
+        Self {
+            loaded: Some(Ok(tensor)),
+            ..Default::default()
+        }
+    }
+
+    /// Previews the file at `path`, loading it in the background if it changed. `axis_names` label the sliders.
+    pub fn show(&mut self, ui: &mut egui::Ui, path: &Path, axis_names: &[String]) {
+        if self.path.as_deref() != Some(path) {
+            if let Some(task) = self.pending.take() {
+                task.cancel();
+            }
+            *self = Self::default();
+            self.path = Some(path.to_owned());
+            let task_path = path.to_owned();
+            let description = tr!("loading-file", path = path.to_string_lossy().into_owned());
+            self.pending = Some(Task::spawn(description, ui.ctx().clone(), move |_| LoadedTensor::read(&task_path)));
+        }
+        if self.pending.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending.take().expect("checked above");
+            self.loaded = Some(task.join().map_err(GuiError::from).and_then(|result| result));
+        }
+        self.show_loaded(ui, axis_names);
+    }
+
+    /// Previews what was loaded, or a spinner while it is being loaded
+    pub fn show_loaded(&mut self, ui: &mut egui::Ui, axis_names: &[String]) {
+        let tensor = match &self.loaded {
+            None => {
+                ui.spinner();
+                return;
+            }
+            Some(Err(err)) => {
+                show_error(ui, err);
+                return;
+            }
+            Some(Ok(tensor)) => tensor,
+        };
+
+        ui.vertical(|ui| {
+            let shape = tensor.shape.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+            ui.weak(tr!("tensor-preview-info", dtype = tensor.dtype.as_str(), shape = shape));
+
+            let ndim = tensor.values.shape().len();
+            let selection = self.selection.get_or_insert_with(|| SliceSelection::new(ndim));
+            let name = |axis: usize| {
+                axis_names.get(axis).filter(|name| !name.is_empty()).cloned().unwrap_or_else(|| axis.to_string())
+            };
+            let before = (selection.rows, selection.columns);
+            ui.horizontal(|ui| {
+                let pickers = [(tr!("preview-rows"), &mut selection.rows), (tr!("preview-columns"), &mut selection.columns)];
+                for (label, picked) in pickers {
+                    egui::ComboBox::new(ui.id().with(&label), &label)
+                        .selected_text(name(*picked))
+                        .show_ui(ui, |ui| {
+                            for axis in 0..ndim {
+                                ui.selectable_value(picked, axis, name(axis));
+                            }
+                        });
+                }
+            });
+            // picking the axis that is already shown the other way swaps the two
+            if selection.rows == selection.columns {
+                if selection.rows != before.0 {
+                    selection.columns = before.0;
+                } else {
+                    selection.rows = before.1;
+                }
+            }
+            let channel_axis = (0..ndim)
+                .filter(|axis| *axis != selection.rows && *axis != selection.columns)
+                .filter(|axis| matches!(tensor.values.shape()[*axis], 3 | 4))
+                .find(|axis| ["channel", "c"].iter().any(|channel| name(*axis).eq_ignore_ascii_case(channel)));
+            if selection.color.is_some() && selection.color != channel_axis {
+                selection.color = None;
+            }
+            if let Some(channel_axis) = channel_axis {
+                let mut in_color = selection.color.is_some();
+                if ui.checkbox(&mut in_color, tr!("preview-in-color")).changed() {
+                    selection.color = in_color.then_some(channel_axis);
+                }
+            }
+            for axis in 0..ndim {
+                let size = tensor.values.shape()[axis];
+                if axis == selection.rows || axis == selection.columns || Some(axis) == selection.color || size <= 1 {
+                    continue;
+                }
+                ui.add(egui::Slider::new(&mut selection.indices[axis], 0..=size - 1).text(name(axis)));
+            }
+
+            let selection = selection.clone();
+            if self.texture.as_ref().map_or(true, |(shown, _)| *shown != selection) {
+                let image = slice_image(tensor.values.slice(&selection), &selection);
+                let name = match &self.path {
+                    Some(path) => format!("{} (preview)", path.to_string_lossy()),
+                    None => "tensor preview".to_owned(),
+                };
+                let texture = ui.ctx().load_texture(name, image, egui::TextureOptions::NEAREST);
+                self.texture = Some((selection, texture));
+            }
+            let (_, texture) = self.texture.as_ref().expect("set above");
+            let size = texture.size_vec2();
+            let size = size * (PREVIEW_SIZE / size.x.max(size.y));
+            ui.image(egui::load::SizedTexture::new(texture.id(), size));
+        });
+    }
+}
+
+/// The slice picked by `selection`, with its contrast stretched over the range of its values. Alpha channels are
+/// left out, so that the values under them can be seen.
+fn slice_image(slice: ArrayD<f32>, selection: &SliceSelection) -> egui::ColorImage {
+    let mapping: Vec<ImageAxis> = (0..selection.indices.len())
+        .filter_map(|axis| match axis {
+            _ if axis == selection.rows => Some(ImageAxis::Y),
+            _ if axis == selection.columns => Some(ImageAxis::X),
+            _ if Some(axis) == selection.color => Some(ImageAxis::Channel),
+            _ => None,
+        })
+        .collect();
+    let image = array_to_image(&slice, &mapping, &[]).expect("the slice has rows, columns and 3 or 4 channels");
+    let rgb = image.to_rgb32f();
+
+    let mut finite: Vec<f32> = rgb.iter().copied().filter(|value| value.is_finite()).collect();
+    let mut quantile = |fraction: f32| {
+        if finite.is_empty() {
+            return 0.0;
+        }
+        let index = ((finite.len() - 1) as f32 * fraction).round() as usize;
+        *finite.select_nth_unstable_by(index, f32::total_cmp).1
+    };
+    let (low, high) = (quantile(CONTRAST_CLIP), quantile(1.0 - CONTRAST_CLIP));
+    let range = if high > low { high - low } else { 1.0 };
+    let pixels: Vec<u8> = rgb
+        .iter()
+        .map(|value| if value.is_finite() { ((value - low) / range * 255.0).clamp(0.0, 255.0) as u8 } else { 0 })
+        .collect();
+    egui::ColorImage::from_rgb([rgb.width() as usize, rgb.height() as usize], &pixels)
+}
@@ -0,0 +1,109 @@
+use bioimg_spec::rdf::{
+    bounded_string::BoundedString,
+    dataset::{DatasetRdf, DatasetType},
+    model::TrainingData,
+    Rdf,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accessibility::required_field_label_with_help, dataset_widget::DatasetFieldsWidget,
+    linked_resource_widget::LinkedResourceWidget, restore_fields, restore_with_serde, set_value_from, InputLines, StagingString,
+    StatefulWidget,
+};
+use crate::i18n::tr;
+use crate::result::Result;
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+enum TrainingDataSource {
+    /// A dataset that is in the zoo already
+    #[default]
+    Linked,
+    Inline,
+}
+
+/// The dataset a model was trained on, either referred to by its id in the zoo or described in place
+pub struct TrainingDataWidget {
+    source: TrainingDataSource,
+    linked: LinkedResourceWidget,
+    name: StagingString<BoundedString<1, 1023>>,
+    description: StagingString<BoundedString<1, 1023>>,
+    dataset: DatasetFieldsWidget,
+}
+
+impl Default for TrainingDataWidget {
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+            linked: Default::default(),
+            name: Default::default(),
+            description: StagingString::new(InputLines::Multiline),
+            dataset: Default::default(),
+        }
+    }
+}
+
+impl From<&TrainingData> for TrainingDataWidget {
+    fn from(training_data: &TrainingData) -> Self {
+        let mut widget = Self::default();
+        match training_data {
+            TrainingData::Linked(linked) => widget.linked = LinkedResourceWidget::from(linked),
+            TrainingData::Inline(dataset) => {
+                widget.source = TrainingDataSource::Inline;
+                widget.name.set_raw(dataset.base.name.to_string());
+                widget.description.set_raw(dataset.base.description.to_string());
+                widget.dataset = DatasetFieldsWidget::from(&**dataset);
+            }
+        }
+        widget
+    }
+}
+
+set_value_from!(TrainingDataWidget: TrainingData);
+
+impl StatefulWidget for TrainingDataWidget {
+    type Value<'p> = Result<TrainingData>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.source, TrainingDataSource::Linked, tr!("training-data-linked"));
+                ui.selectable_value(&mut self.source, TrainingDataSource::Inline, tr!("training-data-inline"));
+            });
+            match self.source {
+                TrainingDataSource::Linked => self.linked.draw_and_parse(ui, id.with("linked")),
+                TrainingDataSource::Inline => {
+                    egui::Grid::new(id.with("inline")).num_columns(2).show(ui, |ui| {
+                        required_field_label_with_help(ui, &tr!("name-label"), "name");
+                        self.name.draw_and_parse(ui, id.with("name"));
+                        ui.end_row();
+
+                        required_field_label_with_help(ui, &tr!("description-label"), "description");
+                        self.description.draw_and_parse(ui, id.with("description"));
+                        ui.end_row();
+                    });
+                    self.dataset.draw_and_parse(ui, id.with("dataset"));
+                }
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        Ok(match self.source {
+            TrainingDataSource::Linked => TrainingData::Linked(self.linked.state()?),
+            TrainingDataSource::Inline => {
+                let (source, size) = self.dataset.state()?;
+                let mut base = Rdf::new(self.name.state()?, self.description.state()?);
+                base.source = source;
+                TrainingData::Inline(Box::new(DatasetRdf {
+                    rdf_type: DatasetType::Dataset,
+                    base,
+                    size,
+                }))
+            }
+        })
+    }
+}
+
+restore_with_serde!(TrainingDataSource);
+restore_fields!(TrainingDataWidget { source, linked, name, description, dataset });
@@ -0,0 +1,69 @@
+use bioimg_spec::rdf::resource_description::UnknownResourceRdf;
+
+use super::error_display::show_error;
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+
+fn scalar_text(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Null => "null".into(),
+        serde_yaml::Value::Bool(b) => b.to_string(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim().to_owned(),
+    }
+}
+
+/// Read-only, collapsible view of a yaml document
+pub fn show_yaml_tree(ui: &mut egui::Ui, id: egui::Id, label: &str, value: &serde_yaml::Value) {
+    let children: Vec<(String, &serde_yaml::Value)> = match value {
+        serde_yaml::Value::Mapping(mapping) => mapping.iter().map(|(k, v)| (scalar_text(k), v)).collect(),
+        serde_yaml::Value::Sequence(items) => items.iter().enumerate().map(|(idx, v)| (format!("[{idx}]"), v)).collect(),
+        serde_yaml::Value::Tagged(tagged) => vec![(tagged.tag.to_string(), &tagged.value)],
+        scalar => {
+            ui.horizontal(|ui| {
+                ui.strong(format!("{label}: "));
+                ui.label(scalar_text(scalar));
+            });
+            return;
+        }
+    };
+    egui::CollapsingHeader::new(label).id_source(id).default_open(true).show(ui, |ui| {
+        for (child_label, child) in children {
+            show_yaml_tree(ui, id.with(&child_label), &child_label, child);
+        }
+    });
+}
+
+/// Shows a resource whose type can't be edited yet, checking only the general fields
+pub struct UnknownResourceViewer {
+    pub yaml: serde_yaml::Value,
+    pub general_fields: Result<UnknownResourceRdf>,
+}
+
+impl UnknownResourceViewer {
+    pub fn new(yaml: serde_yaml::Value) -> Self {
+        let general_fields = serde_yaml::from_value(yaml.clone()).map_err(GuiError::from);
+        Self { yaml, general_fields }
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui, id: egui::Id) {
+        match &self.general_fields {
+            Ok(rdf) => {
+                ui.label(tr!("unsupported-resource-type-notice", rdf_type = rdf.rdf_type.clone()));
+                ui.label(tr!("general-fields-are-valid"));
+            }
+            Err(err) => {
+                ui.label(tr!("unsupported-resource-notice"));
+                ui.horizontal(|ui| {
+                    ui.strong(tr!("general-fields-label"));
+                    show_error(ui, err);
+                });
+            }
+        }
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            show_yaml_tree(ui, id, "rdf", &self.yaml);
+        });
+    }
+}
@@ -1,11 +1,29 @@
+use bioimg_spec::rdf::FileReference;
+use bioimg_spec::runtime::{LinkChecker, Reachability};
 use url::Url;
 
-use super::{error_display::show_if_error, StatefulWidget};
+use super::{
+    accessibility::describe_field,
+    error_display::{show_error, visible_error},
+    focus::{handle_form_focus, note_field_error},
+    set_value_from,
+    util::text_input_min_size,
+    Restore, StatefulWidget,
+};
+use crate::i18n::tr;
 use crate::result::{GuiError, Result};
+use crate::task::Task;
 
+/// A url field. Web urls are requested in the background once they are typed in, to show whether they lead anywhere.
 pub struct StagingUrl {
     raw: String,
     parsed: Result<Url>,
+    /// Whether the user has edited the url; errors are only shown after that
+    touched: bool,
+    /// The url that [Self::reachability] is about
+    checked_url: Option<Url>,
+    pending_check: Option<Task<Reachability>>,
+    reachability: Option<Reachability>,
 }
 
 impl Default for StagingUrl {
@@ -14,6 +32,91 @@ impl Default for StagingUrl {
         Self {
             raw: raw.clone(),
             parsed: Url::try_from(raw.as_str()).map_err(|err| GuiError::new(err.to_string())),
+            touched: false,
+            checked_url: None,
+            pending_check: None,
+            reachability: None,
+        }
+    }
+}
+
+impl From<&Url> for StagingUrl {
+    fn from(url: &Url) -> Self {
+        Self {
+            raw: url.to_string(),
+            parsed: Ok(url.clone()),
+            touched: true,
+            checked_url: None,
+            pending_check: None,
+            reachability: None,
+        }
+    }
+}
+
+set_value_from!(StagingUrl: Url);
+
+impl StagingUrl {
+    /// A url field filled in with `reference`. Paths are kept as typed, so that they show up as invalid urls.
+    pub fn from_reference(reference: &FileReference) -> Self {
+        match reference {
+            FileReference::Url(url) => Self::from(url),
+            FileReference::Path(path) => {
+                let mut field = Self::default();
+                field.set_raw(path.to_string_lossy());
+                field
+            }
+        }
+    }
+
+    pub fn set_raw(&mut self, raw: impl Into<String>) {
+        self.raw = raw.into();
+        self.touched = !self.raw.trim().is_empty();
+        self.parsed = Url::try_from(self.raw.as_str()).map_err(|err| GuiError::new(err.to_string()));
+    }
+
+    /// Whether the url that is typed in was found to lead somewhere
+    pub fn is_reachable(&self) -> bool {
+        let checked = self.parsed.as_ref().ok() == self.checked_url.as_ref();
+        checked && self.reachability.as_ref().is_some_and(Reachability::is_reachable)
+    }
+
+    /// Starts checking the parsed url if it is a web url that wasn't checked yet
+    fn update_check(&mut self, ctx: &egui::Context) {
+        if self.pending_check.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_check.take().expect("checked above");
+            self.reachability = Some(task.join().unwrap_or_else(|err| Reachability::Unreachable(err.to_string())));
+        }
+        let url = self.parsed.as_ref().ok().filter(|url| matches!(url.scheme(), "http" | "https"));
+        if url == self.checked_url.as_ref() {
+            return;
+        }
+        if let Some(task) = self.pending_check.take() {
+            task.cancel();
+        }
+        self.reachability = None;
+        self.checked_url = url.cloned();
+        if let Some(url) = url.cloned() {
+            let check = move |_: &_| LinkChecker::default().check(&url);
+            self.pending_check = Some(Task::spawn(tr!("checking-link"), ctx.clone(), check));
+        }
+    }
+
+    fn show_reachability(&self, ui: &mut egui::Ui) {
+        if self.pending_check.is_some() {
+            ui.spinner().on_hover_text(tr!("checking-link"));
+            return;
+        }
+        let warn = |ui: &mut egui::Ui, icon: &str, hint: String| {
+            ui.label(egui::RichText::new(icon).color(ui.visuals().warn_fg_color)).on_hover_text(hint);
+        };
+        match &self.reachability {
+            None => (),
+            Some(Reachability::Reachable) => {
+                ui.weak("✔").on_hover_text(tr!("link-reachable"));
+            }
+            Some(Reachability::Status(status)) => warn(ui, "⚠", tr!("link-error-status", status = status.to_string())),
+            Some(Reachability::TimedOut) => warn(ui, "⌛", tr!("link-timed-out")),
+            Some(Reachability::Unreachable(reason)) => warn(ui, "⚠", tr!("link-unreachable", reason = reason.clone())),
         }
     }
 }
@@ -22,12 +125,44 @@ impl StatefulWidget for StagingUrl {
     type Value<'p> = Result<Url>;
 
     fn draw_and_parse<'p>(&'p mut self, ui: &mut egui::Ui, _id: egui::Id) {
-        ui.add(egui::TextEdit::singleline(&mut self.raw).min_size(egui::Vec2 { x: 200.0, y: 10.0 }));
-        self.parsed = Url::try_from(self.raw.as_str()).map_err(|err| GuiError::new(err.to_string()));
-        show_if_error(ui, &self.parsed);
+        let response = ui
+            .horizontal(|ui| {
+                let response = ui.add(egui::TextEdit::singleline(&mut self.raw).min_size(text_input_min_size(ui)));
+                if response.changed() {
+                    self.parsed = Url::try_from(self.raw.as_str()).map_err(|err| GuiError::new(err.to_string()));
+                }
+                // only checked once typing is done, not for every keystroke
+                if !response.has_focus() {
+                    self.update_check(ui.ctx());
+                }
+                self.show_reachability(ui);
+                response
+            })
+            .inner;
+        handle_form_focus(ui, &response, true);
+        self.touched |= response.changed();
+        let error = visible_error(ui, self.touched, &self.parsed);
+        let description = error.map(|err| err as &dyn std::fmt::Display);
+        describe_field(ui, &response, egui::WidgetInfo::text_edit(&self.raw, &self.raw), description);
+        if let Some(err) = error {
+            note_field_error(ui, &response);
+            show_error(ui, err);
+        }
     }
 
     fn state<'p>(&'p self) -> Self::Value<'p> {
         self.parsed.clone()
     }
 }
+
+impl Restore for StagingUrl {
+    fn dump(&self) -> serde_yaml::Value {
+        self.raw.clone().into()
+    }
+
+    fn restore(&mut self, _ctx: &egui::Context, value: &serde_yaml::Value) {
+        if let Some(raw) = value.as_str() {
+            self.set_raw(raw);
+        }
+    }
+}
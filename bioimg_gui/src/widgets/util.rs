@@ -2,6 +2,10 @@ use egui::InnerResponse;
 
 pub trait DynamicImageExt {
     fn to_egui_texture_handle(&self, name: impl Into<String>, ctx: &egui::Context) -> egui::TextureHandle;
+
+    /// Like [DynamicImageExt::to_egui_texture_handle], shrunk to fit `max_size` first, so that only
+    /// a preview sized copy of a large image ends up in GPU memory
+    fn to_egui_thumbnail_handle(&self, name: impl Into<String>, max_size: u32, ctx: &egui::Context) -> egui::TextureHandle;
 }
 
 impl DynamicImageExt for image::DynamicImage {
@@ -19,6 +23,39 @@ impl DynamicImageExt for image::DynamicImage {
             },
         )
     }
+
+    fn to_egui_thumbnail_handle(&self, name: impl Into<String>, max_size: u32, ctx: &egui::Context) -> egui::TextureHandle {
+        if self.width().max(self.height()) <= max_size {
+            return self.to_egui_texture_handle(name, ctx);
+        }
+        self.thumbnail(max_size, max_size).to_egui_texture_handle(name, ctx)
+    }
+}
+
+/// A size in bytes the way file managers show it, e.g. `1.5 GiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Minimum size of single line text inputs, enough for a couple dozen characters of the current body font
+pub fn text_input_min_size(ui: &egui::Ui) -> egui::Vec2 {
+    const MIN_CHARS: f32 = 28.0;
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let char_width = ui.fonts(|fonts| fonts.glyph_width(&font_id, '0'));
+    egui::Vec2 {
+        x: char_width * MIN_CHARS,
+        y: ui.text_style_height(&egui::TextStyle::Body),
+    }
 }
 
 pub fn group_frame<R>(ui: &mut egui::Ui, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> InnerResponse<R> {
@@ -0,0 +1,77 @@
+use bioimg_spec::rdf::{Severity, ValidationReport};
+
+use crate::i18n::tr;
+
+fn severity_label(severity: Severity) -> String {
+    match severity {
+        Severity::Error => tr!("severity-error"),
+        Severity::Warning => tr!("severity-warning"),
+        Severity::Info => tr!("severity-info"),
+    }
+}
+
+/// How a report is looked at: which entries are listed, and from which severity on the model counts as failing
+pub struct ValidationReportView {
+    pub show: Severity,
+    pub fail_on: Severity,
+}
+
+impl Default for ValidationReportView {
+    fn default() -> Self {
+        Self {
+            show: Severity::Info,
+            fail_on: Severity::Error,
+        }
+    }
+}
+
+impl ValidationReportView {
+    /// Lists the entries of `report` with their severity and the field they are about
+    pub fn show(&mut self, ui: &mut egui::Ui, report: &ValidationReport) {
+        if report.entries.is_empty() {
+            ui.label(tr!("validation-report-clean"));
+            return;
+        }
+        ui.label(tr!(
+            "validation-report-summary",
+            errors = report.errors().count(),
+            warnings = report.warnings().count(),
+            infos = report.infos().count()
+        ));
+        ui.horizontal(|ui| {
+            ui.label(tr!("validation-report-show"));
+            for severity in <Severity as strum::VariantArray>::VARIANTS {
+                ui.selectable_value(&mut self.show, *severity, severity_label(*severity));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr!("validation-report-fail-on"));
+            egui::ComboBox::from_id_source("validation fail on")
+                .selected_text(severity_label(self.fail_on))
+                .show_ui(ui, |ui| {
+                    for severity in <Severity as strum::VariantArray>::VARIANTS {
+                        ui.selectable_value(&mut self.fail_on, *severity, severity_label(*severity));
+                    }
+                });
+            match report.fails_at(self.fail_on) {
+                true => ui.colored_label(ui.visuals().error_fg_color, tr!("validation-report-fails")),
+                false => ui.label(tr!("validation-report-passes")),
+            };
+        });
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            egui::Grid::new("validation report").num_columns(3).striped(true).show(ui, |ui| {
+                for entry in report.at_least(self.show) {
+                    let color = match entry.severity {
+                        Severity::Error => ui.visuals().error_fg_color,
+                        Severity::Warning => ui.visuals().warn_fg_color,
+                        Severity::Info => ui.visuals().weak_text_color(),
+                    };
+                    ui.label(egui::RichText::new(severity_label(entry.severity)).color(color));
+                    ui.monospace(entry.path_string());
+                    ui.label(&entry.message);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
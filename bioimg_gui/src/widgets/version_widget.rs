@@ -0,0 +1,84 @@
+use bioimg_spec::rdf::version::{Version, VersionBump};
+
+use super::{restore_fields, restore_with_serde, InputLines, SetValue, StagingString, StatefulWidget};
+use crate::i18n::tr;
+use crate::result::Result;
+
+/// The `version` of the resource, with buttons that start its next version
+pub struct VersionWidget {
+    staging: StagingString<Version>,
+    /// The version the model was last exported with, which should change before it is exported again
+    exported: Option<Version>,
+    /// The bump clicked this frame, for the caller to record in the version history
+    clicked_bump: Option<VersionBump>,
+}
+
+impl Default for VersionWidget {
+    fn default() -> Self {
+        Self {
+            staging: StagingString::new(InputLines::SingleLine),
+            exported: None,
+            clicked_bump: None,
+        }
+    }
+}
+
+impl VersionWidget {
+    pub fn set_raw(&mut self, raw: impl Into<String>) {
+        self.staging.set_raw(raw)
+    }
+
+    /// The bump the user asked for since the last call
+    pub fn take_bump(&mut self) -> Option<VersionBump> {
+        self.clicked_bump.take()
+    }
+
+    pub fn mark_exported(&mut self, version: Version) {
+        self.exported = Some(version);
+    }
+
+    /// Whether the current version was already used for an export
+    pub fn is_exported(&self) -> bool {
+        self.exported.is_some() && self.staging.state().ok() == self.exported
+    }
+}
+
+impl SetValue<&str> for VersionWidget {
+    fn set_value(&mut self, value: &str) {
+        self.set_raw(value)
+    }
+}
+
+impl StatefulWidget for VersionWidget {
+    type Value<'p> = Result<Version>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                self.staging.draw_and_parse(ui, id.with("version"));
+                let current = self.staging.state().ok();
+                for (bump, label) in [
+                    (VersionBump::Major, tr!("major")),
+                    (VersionBump::Minor, tr!("minor")),
+                    (VersionBump::Patch, tr!("patch")),
+                ] {
+                    let next = current.as_ref().map_or_else(|| "0.1.0".to_owned(), |current| current.bumped(bump).to_string());
+                    if ui.button(format!("+ {label}")).on_hover_text(tr!("bump-version-hint", next = next)).clicked() {
+                        self.clicked_bump = Some(bump);
+                    }
+                }
+            });
+            if self.is_exported() {
+                let warning = tr!("version-already-exported", version = self.staging.raw().trim().to_owned());
+                ui.label(egui::RichText::new(format!("⚠ {warning}")).color(ui.visuals().warn_fg_color));
+            }
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        self.staging.state()
+    }
+}
+
+restore_with_serde!(Option<Version>);
+restore_fields!(VersionWidget { staging, exported });
@@ -0,0 +1,586 @@
+use std::path::PathBuf;
+
+use bioimg_spec::rdf::{
+    bounded_string::BoundedString,
+    identifier::Identifier,
+    model::weights::{
+        ArchitectureDescr, FrameworkVersion, KerasHdf5Weights, OnnxWeights, PytorchStateDictWeights, TensorflowJsWeights,
+        TensorflowSavedModelBundleWeights, TorchscriptWeights, WeightsDescr, WeightsEntry, WeightsFormat,
+    },
+    FileReference, Sha256,
+};
+use bioimg_spec::runtime::{model_package::PackageFile, CondaEnv};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accessibility::{field_label, required_field_label},
+    attachment_widget::{attached_file, AttachmentWidget},
+    code_editor_widget::{check_python, show_code, CodeLanguage},
+    conda_env_widget::{picked_env, CondaEnvWidget},
+    error_display::{show_error, visible_error},
+    file_widget::{FileWidgetState, PackageFiles},
+    kwargs_widget::KwargsWidget,
+    restore_fields, restore_with_serde,
+    url_widget::StagingUrl,
+    util::group_frame,
+    InputLines, Restore, StagingNum, StagingOpt, StagingString, StatefulWidget,
+};
+use crate::i18n::{localized_error, tr};
+use crate::result::{GuiError, Result};
+
+/// Formats whose weights need a description of the network they are loaded into
+fn needs_architecture(format: WeightsFormat) -> bool {
+    format == WeightsFormat::PytorchStateDict
+}
+
+/// Formats whose weights can come with a conda environment to run them in
+fn has_dependencies(format: WeightsFormat) -> bool {
+    matches!(format, WeightsFormat::PytorchStateDict | WeightsFormat::TensorflowSavedModelBundle)
+}
+
+/// Where the environment of weights in `format` is written in the package, so that each format gets a file of its own
+fn environment_file_name(format: WeightsFormat) -> PathBuf {
+    match format {
+        WeightsFormat::PytorchStateDict => PathBuf::from(CondaEnv::FILE_NAME),
+        format => PathBuf::from(format!("{format}_{}", CondaEnv::FILE_NAME)),
+    }
+}
+
+/// Message id of the label of the framework version field, if the format has one
+fn version_label_id(format: WeightsFormat) -> Option<&'static str> {
+    match format {
+        WeightsFormat::KerasHdf5 | WeightsFormat::TensorflowJs | WeightsFormat::TensorflowSavedModelBundle => {
+            Some("tensorflow-version-label")
+        }
+        WeightsFormat::PytorchStateDict | WeightsFormat::Torchscript => Some("pytorch-version-label"),
+        WeightsFormat::Onnx => None,
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+enum ArchitectureSource {
+    #[default]
+    File,
+    Url,
+    Library,
+}
+
+/// Where the python callable that builds the network comes from, and the kwargs it is called with
+pub struct ArchitectureWidget {
+    source: ArchitectureSource,
+    file: AttachmentWidget,
+    staging_url: StagingUrl,
+    staging_sha256: StagingOpt<StagingString<Sha256>>,
+    staging_import_from: StagingString<BoundedString<1, 1023>>,
+    staging_callable: StagingString<Identifier<String>>,
+    kwargs: KwargsWidget,
+    /// The contents of the picked source file, as read from the path next to them
+    source_code: Option<(PathBuf, Result<String>)>,
+}
+
+impl Default for ArchitectureWidget {
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+            file: Default::default(),
+            staging_url: Default::default(),
+            staging_sha256: Default::default(),
+            staging_import_from: StagingString::new(InputLines::SingleLine),
+            staging_callable: StagingString::new(InputLines::SingleLine),
+            kwargs: Default::default(),
+            source_code: None,
+        }
+    }
+}
+
+impl ArchitectureWidget {
+    fn from_descr(descr: &ArchitectureDescr, files: &PackageFiles) -> Self {
+        let (callable, kwargs) = match descr {
+            ArchitectureDescr::FromFile { callable, kwargs, .. } | ArchitectureDescr::FromLibrary { callable, kwargs, .. } => {
+                (callable, kwargs)
+            }
+        };
+        let mut widget = Self {
+            staging_callable: StagingString::new(InputLines::SingleLine).with_raw(callable.to_string()),
+            kwargs: KwargsWidget::from(kwargs),
+            ..Default::default()
+        };
+        match descr {
+            ArchitectureDescr::FromFile { source: FileReference::Url(url), sha256, .. } => {
+                widget.source = ArchitectureSource::Url;
+                widget.staging_url = StagingUrl::from(url);
+                widget.staging_sha256 = StagingOpt::from_raw(&sha256.map(|sha256| sha256.to_string()).unwrap_or_default());
+            }
+            ArchitectureDescr::FromFile { source, .. } => {
+                widget.source = ArchitectureSource::File;
+                widget.file = files.widget(source);
+            }
+            ArchitectureDescr::FromLibrary { import_from, .. } => {
+                widget.source = ArchitectureSource::Library;
+                widget.staging_import_from.set_raw(import_from.to_string());
+            }
+        }
+        widget
+    }
+
+    /// The module to import the callable from, which has to be a dotted path of python identifiers like `torchvision.models`
+    fn import_from(&self) -> Result<BoundedString<1, 1023>> {
+        let import_from = self.staging_import_from.state()?;
+        for part in import_from.as_str().split('.') {
+            Identifier::<String>::try_from(part.to_owned()).map_err(|err| {
+                let reason = localized_error(&err);
+                GuiError::new(tr!("architecture-bad-import-from", part = part.to_owned(), reason = reason))
+            })?;
+        }
+        Ok(import_from)
+    }
+}
+
+impl StatefulWidget for ArchitectureWidget {
+    type Value<'p> = Result<ArchitectureDescr>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            field_label(ui, &tr!("architecture-source-label"));
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.source, ArchitectureSource::File, tr!("architecture-from-file"));
+                ui.selectable_value(&mut self.source, ArchitectureSource::Url, tr!("architecture-from-url"));
+                ui.selectable_value(&mut self.source, ArchitectureSource::Library, tr!("architecture-from-library"));
+            });
+            ui.end_row();
+
+            match self.source {
+                ArchitectureSource::File => {
+                    required_field_label(ui, &tr!("architecture-file-label"));
+                    self.file.draw_and_parse(ui, id.with("file"));
+                }
+                ArchitectureSource::Url => {
+                    required_field_label(ui, &tr!("architecture-url-label"));
+                    self.staging_url.draw_and_parse(ui, id.with("url"));
+                    ui.end_row();
+
+                    field_label(ui, &tr!("architecture-sha256-label"));
+                    self.staging_sha256.draw_and_parse(ui, id.with("sha256"));
+                }
+                ArchitectureSource::Library => {
+                    required_field_label(ui, &tr!("architecture-import-from-label"));
+                    ui.vertical(|ui| {
+                        self.staging_import_from.draw_and_parse(ui, id.with("import from"));
+                        if let (Ok(_), Err(err)) = (self.staging_import_from.state(), self.import_from()) {
+                            show_error(ui, err);
+                        }
+                    });
+                }
+            }
+            ui.end_row();
+
+            required_field_label(ui, &tr!("architecture-callable-label"));
+            self.staging_callable.draw_and_parse(ui, id.with("callable"));
+            ui.end_row();
+
+            if let (ArchitectureSource::File, Some(Ok(attachment))) = (self.source, self.file.loaded_value()) {
+                if self.source_code.as_ref().map_or(true, |(path, _)| path != attachment.path()) {
+                    // python sources are small enough to be read while drawing
+                    let code = std::fs::read_to_string(attachment.path()).map_err(GuiError::from);
+                    self.source_code = Some((attachment.path().to_owned(), code));
+                }
+                field_label(ui, &tr!("architecture-code-label"));
+                ui.vertical(|ui| match &self.source_code {
+                    Some((_, Ok(code))) => {
+                        for problem in check_python(code, self.staging_callable.raw()) {
+                            ui.label(egui::RichText::new(problem).color(ui.visuals().warn_fg_color));
+                        }
+                        egui::CollapsingHeader::new(tr!("architecture-show-code")).id_source(id.with("code")).show(ui, |ui| {
+                            show_code(ui, id.with("code view"), code, CodeLanguage::Python);
+                        });
+                    }
+                    Some((_, Err(err))) => show_error(ui, err),
+                    None => (),
+                });
+                ui.end_row();
+            }
+
+            field_label(ui, &tr!("architecture-kwargs-label"));
+            self.kwargs.draw_and_parse(ui, id.with("kwargs"));
+            ui.end_row();
+        });
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let callable = self.staging_callable.state()?;
+        let kwargs = self.kwargs.state()?;
+        Ok(match self.source {
+            ArchitectureSource::File => {
+                let file = attached_file(self.file.state())?
+                    .ok_or_else(|| GuiError::new(tr!("architecture-file-missing")))?;
+                ArchitectureDescr::FromFile {
+                    source: file.source,
+                    sha256: file.sha256,
+                    callable,
+                    kwargs,
+                }
+            }
+            ArchitectureSource::Url => ArchitectureDescr::FromFile {
+                source: FileReference::Url(self.staging_url.state()?),
+                sha256: self.staging_sha256.state().transpose()?,
+                callable,
+                kwargs,
+            },
+            ArchitectureSource::Library => ArchitectureDescr::FromLibrary {
+                import_from: self.import_from()?,
+                callable,
+                kwargs,
+            },
+        })
+    }
+}
+
+/// The weights of one format. Only the fields that format has are shown.
+pub struct WeightsEntryWidget {
+    format: WeightsFormat,
+    file: AttachmentWidget,
+    parent: Option<WeightsFormat>,
+    staging_framework_version: StagingString<FrameworkVersion>,
+    staging_opset_version: StagingNum<u32, u32>,
+    architecture: ArchitectureWidget,
+    dependencies: CondaEnvWidget,
+    staging_comment: StagingOpt<StagingString<BoundedString<1, 1023>>>,
+}
+
+impl WeightsEntryWidget {
+    pub fn new(format: WeightsFormat) -> Self {
+        Self {
+            format,
+            file: Default::default(),
+            parent: None,
+            staging_framework_version: StagingString::new(InputLines::SingleLine),
+            staging_opset_version: StagingNum::new(15),
+            architecture: Default::default(),
+            dependencies: Default::default(),
+            staging_comment: Default::default(),
+        }
+    }
+
+    /// The widget for imported weights of `format`, with the file loading from where `files` were unpacked
+    fn from_entry(format: WeightsFormat, entry: &WeightsEntry, files: &PackageFiles) -> Self {
+        Self {
+            file: files.widget(&entry.source),
+            parent: entry.parent,
+            staging_comment: StagingOpt::from_raw(&entry.comment.as_ref().map(ToString::to_string).unwrap_or_default()),
+            ..Self::new(format)
+        }
+    }
+
+    fn with_framework_version(mut self, version: &FrameworkVersion) -> Self {
+        self.staging_framework_version.set_raw(version.to_string());
+        self
+    }
+
+    fn with_dependencies(mut self, dependencies: Option<&FileReference>, files: &PackageFiles) -> Self {
+        if let Some(dependencies) = dependencies {
+            self.dependencies = files.widget(dependencies);
+        }
+        self
+    }
+
+    /// The environment file to put into the package, if one was picked
+    fn environment_file(&self) -> Option<(PathBuf, PackageFile)> {
+        let env = picked_env(self.dependencies.state()).ok().flatten()?;
+        Some((environment_file_name(self.format), PackageFile::Bytes(env.to_yaml().into_bytes())))
+    }
+
+    fn dependencies(&self) -> Result<Option<FileReference>> {
+        let env = picked_env(self.dependencies.state())?;
+        Ok(env.map(|_| FileReference::Path(environment_file_name(self.format))))
+    }
+
+    /// `other_formats` are the ones these weights could have been converted from
+    fn draw(&mut self, ui: &mut egui::Ui, id: egui::Id, other_formats: &[WeightsFormat]) {
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            required_field_label(ui, &tr!("weights-file-label"));
+            self.file.draw_and_parse(ui, id.with("file"));
+            ui.end_row();
+
+            match version_label_id(self.format) {
+                Some(label_id) => {
+                    required_field_label(ui, &tr!(label_id));
+                    self.staging_framework_version.draw_and_parse(ui, id.with("framework version"));
+                }
+                None => {
+                    required_field_label(ui, &tr!("opset-version-label"));
+                    self.staging_opset_version.draw_and_parse(ui, id.with("opset version"));
+                }
+            }
+            ui.end_row();
+
+            if needs_architecture(self.format) {
+                required_field_label(ui, &tr!("architecture-label"));
+                self.architecture.draw_and_parse(ui, id.with("architecture"));
+                ui.end_row();
+            }
+
+            if has_dependencies(self.format) {
+                field_label(ui, &tr!("dependencies-label"));
+                self.dependencies.draw_and_parse(ui, id.with("dependencies"));
+                ui.end_row();
+            }
+
+            field_label(ui, &tr!("weights-parent-label"));
+            let parent_text = self.parent.map_or_else(|| tr!("none"), |parent| parent.to_string());
+            egui::ComboBox::from_id_source(id.with("parent")).selected_text(parent_text).show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.parent, None, tr!("none"));
+                for format in other_formats {
+                    ui.selectable_value(&mut self.parent, Some(*format), format.to_string());
+                }
+            });
+            ui.end_row();
+
+            field_label(ui, &tr!("weights-comment-label"));
+            self.staging_comment.draw_and_parse(ui, id.with("comment"));
+            ui.end_row();
+        });
+        // a picked file counts as editing the entry, so that what else is missing shows up
+        let touched = !matches!(self.file.state(), FileWidgetState::Empty);
+        if let Some(err) = visible_error(ui, touched, &self.entry()) {
+            show_error(ui, err);
+        }
+    }
+
+    fn entry(&self) -> Result<WeightsEntry> {
+        let file = attached_file(self.file.state())?.ok_or_else(|| GuiError::new(tr!("weights-file-missing")))?;
+        Ok(WeightsEntry {
+            source: file.source,
+            sha256: file.sha256,
+            authors: vec![],
+            parent: self.parent,
+            comment: self.staging_comment.state().transpose()?,
+        })
+    }
+
+    /// Fills in the weights of this format
+    fn add_to(&self, weights: &mut WeightsDescr) -> Result<()> {
+        let entry = self.entry()?;
+        match self.format {
+            WeightsFormat::KerasHdf5 => {
+                weights.keras_hdf5 = Some(KerasHdf5Weights {
+                    entry,
+                    tensorflow_version: self.staging_framework_version.state()?,
+                })
+            }
+            WeightsFormat::Onnx => {
+                weights.onnx = Some(OnnxWeights {
+                    entry,
+                    opset_version: self.staging_opset_version.state()?,
+                })
+            }
+            WeightsFormat::PytorchStateDict => {
+                weights.pytorch_state_dict = Some(PytorchStateDictWeights {
+                    entry,
+                    architecture: self.architecture.state()?,
+                    pytorch_version: self.staging_framework_version.state()?,
+                    dependencies: self.dependencies()?,
+                })
+            }
+            WeightsFormat::TensorflowJs => {
+                weights.tensorflow_js = Some(TensorflowJsWeights {
+                    entry,
+                    tensorflow_version: self.staging_framework_version.state()?,
+                })
+            }
+            WeightsFormat::TensorflowSavedModelBundle => {
+                weights.tensorflow_saved_model_bundle = Some(TensorflowSavedModelBundleWeights {
+                    entry,
+                    tensorflow_version: self.staging_framework_version.state()?,
+                    dependencies: self.dependencies()?,
+                })
+            }
+            WeightsFormat::Torchscript => {
+                weights.torchscript = Some(TorchscriptWeights {
+                    entry,
+                    pytorch_version: self.staging_framework_version.state()?,
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One entry per weights format, added and removed as needed
+#[derive(Default)]
+pub struct WeightsWidget {
+    entries: Vec<WeightsEntryWidget>,
+}
+
+impl WeightsWidget {
+    pub fn from_descr(weights: &WeightsDescr, files: &PackageFiles) -> Self {
+        let mut entries = vec![];
+        if let Some(weights) = &weights.keras_hdf5 {
+            entries.push(
+                WeightsEntryWidget::from_entry(WeightsFormat::KerasHdf5, &weights.entry, files)
+                    .with_framework_version(&weights.tensorflow_version),
+            );
+        }
+        if let Some(weights) = &weights.onnx {
+            let mut entry = WeightsEntryWidget::from_entry(WeightsFormat::Onnx, &weights.entry, files);
+            entry.staging_opset_version = StagingNum::new(weights.opset_version);
+            entries.push(entry);
+        }
+        if let Some(weights) = &weights.pytorch_state_dict {
+            let mut entry = WeightsEntryWidget::from_entry(WeightsFormat::PytorchStateDict, &weights.entry, files)
+                .with_framework_version(&weights.pytorch_version)
+                .with_dependencies(weights.dependencies.as_ref(), files);
+            entry.architecture = ArchitectureWidget::from_descr(&weights.architecture, files);
+            entries.push(entry);
+        }
+        if let Some(weights) = &weights.tensorflow_js {
+            entries.push(
+                WeightsEntryWidget::from_entry(WeightsFormat::TensorflowJs, &weights.entry, files)
+                    .with_framework_version(&weights.tensorflow_version),
+            );
+        }
+        if let Some(weights) = &weights.tensorflow_saved_model_bundle {
+            entries.push(
+                WeightsEntryWidget::from_entry(WeightsFormat::TensorflowSavedModelBundle, &weights.entry, files)
+                    .with_framework_version(&weights.tensorflow_version)
+                    .with_dependencies(weights.dependencies.as_ref(), files),
+            );
+        }
+        if let Some(weights) = &weights.torchscript {
+            entries.push(
+                WeightsEntryWidget::from_entry(WeightsFormat::Torchscript, &weights.entry, files)
+                    .with_framework_version(&weights.pytorch_version),
+            );
+        }
+        Self { entries }
+    }
+
+    /// The weights files, along with the architecture files of the formats that need one
+    pub fn attachments(&self) -> Vec<&AttachmentWidget> {
+        let mut attachments = vec![];
+        for entry in &self.entries {
+            attachments.push(&entry.file);
+            if needs_architecture(entry.format) && entry.architecture.source == ArchitectureSource::File {
+                attachments.push(&entry.architecture.file);
+            }
+        }
+        attachments
+    }
+
+    /// The environment files of the entries that have one
+    pub fn environment_files(&self) -> Vec<(PathBuf, PackageFile)> {
+        self.entries.iter().filter(|entry| has_dependencies(entry.format)).filter_map(|entry| entry.environment_file()).collect()
+    }
+
+    pub fn formats(&self) -> Vec<WeightsFormat> {
+        self.entries.iter().map(|entry| entry.format).collect()
+    }
+}
+
+impl StatefulWidget for WeightsWidget {
+    type Value<'p> = Result<WeightsDescr>;
+
+    fn draw_and_parse(&mut self, ui: &mut egui::Ui, id: egui::Id) {
+        let formats = self.formats();
+        let mut removed = None;
+        ui.vertical(|ui| {
+            for (idx, entry) in self.entries.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.strong(entry.format.to_string());
+                    if ui.button("🗙").on_hover_text(tr!("remove-weights")).clicked() {
+                        removed = Some(idx);
+                    }
+                });
+                let others: Vec<_> = formats.iter().copied().filter(|format| *format != entry.format).collect();
+                group_frame(ui, |ui| entry.draw(ui, id.with(idx), &others));
+            }
+            if self.entries.is_empty() {
+                if let Some(err) = visible_error(ui, false, &self.state()) {
+                    show_error(ui, err);
+                }
+            }
+            let missing: Vec<_> = <WeightsFormat as strum::VariantArray>::VARIANTS
+                .iter()
+                .filter(|format| !formats.contains(format))
+                .collect();
+            if !missing.is_empty() {
+                egui::ComboBox::from_id_source(id.with("add")).selected_text(tr!("add-weights")).show_ui(ui, |ui| {
+                    for format in missing {
+                        if ui.selectable_label(false, format.to_string()).clicked() {
+                            self.entries.push(WeightsEntryWidget::new(*format));
+                        }
+                    }
+                });
+            }
+        });
+        if let Some(idx) = removed {
+            let format = self.entries.remove(idx).format;
+            // nothing can have been converted from weights that aren't there anymore
+            for entry in &mut self.entries {
+                if entry.parent == Some(format) {
+                    entry.parent = None;
+                }
+            }
+        }
+    }
+
+    fn state<'p>(&'p self) -> Self::Value<'p> {
+        let mut weights = WeightsDescr {
+            keras_hdf5: None,
+            onnx: None,
+            pytorch_state_dict: None,
+            tensorflow_js: None,
+            tensorflow_saved_model_bundle: None,
+            torchscript: None,
+        };
+        for entry in &self.entries {
+            entry
+                .add_to(&mut weights)
+                .map_err(|err| GuiError::new(format!("{}: {err}", entry.format)))?;
+        }
+        weights.check()?;
+        Ok(weights)
+    }
+}
+
+restore_with_serde!(ArchitectureSource, WeightsFormat, Option<WeightsFormat>);
+restore_fields!(ArchitectureWidget {
+    source,
+    file,
+    staging_url,
+    staging_sha256,
+    staging_import_from,
+    staging_callable,
+    kwargs,
+});
+restore_fields!(WeightsEntryWidget {
+    format,
+    file,
+    parent,
+    staging_framework_version,
+    staging_opset_version,
+    architecture,
+    dependencies,
+    staging_comment,
+});
+
+impl Restore for WeightsWidget {
+    fn dump(&self) -> serde_yaml::Value {
+        self.entries.iter().map(Restore::dump).collect()
+    }
+
+    fn restore(&mut self, ctx: &egui::Context, value: &serde_yaml::Value) {
+        let Some(entries) = value.as_sequence() else {
+            return;
+        };
+        // each entry starts out with the defaults of its own format
+        self.entries = entries
+            .iter()
+            .filter_map(|dumped| {
+                let format = serde_yaml::from_value(dumped.get("format")?.clone()).ok()?;
+                let mut entry = WeightsEntryWidget::new(format);
+                entry.restore(ctx, dumped);
+                Some(entry)
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,114 @@
+use bioimg_spec::runtime::zoo::{ZooClient, ZooEntry, ZooIndex, ZooQuery};
+
+use crate::i18n::tr;
+use crate::result::{GuiError, Result};
+use crate::task::Task;
+
+/// Lists the models published on bioimage.io so that one of them can be opened for editing
+#[derive(Default)]
+pub struct ZooBrowser {
+    client: Option<ZooClient>,
+    index: Option<Result<ZooIndex>>,
+    pending_index: Option<Task<Result<ZooIndex>>>,
+    search: String,
+    /// Comma separated, like the default tags of the author profile
+    tags: String,
+}
+
+impl ZooBrowser {
+    pub fn client(&mut self) -> Result<ZooClient> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let client = ZooClient::bioimage_io()?;
+        self.client = Some(client.clone());
+        Ok(client)
+    }
+
+    fn refresh(&mut self, ctx: &egui::Context) {
+        let client = match self.client() {
+            Ok(client) => client,
+            Err(err) => {
+                self.index = Some(Err(err));
+                return;
+            }
+        };
+        self.pending_index = Some(Task::spawn(tr!("loading-zoo"), ctx.clone(), move |_| Ok(client.fetch_index()?)));
+    }
+
+    fn query(&self) -> ZooQuery {
+        ZooQuery {
+            text: self.search.clone(),
+            tags: self.tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_owned).collect(),
+            ..ZooQuery::models()
+        }
+    }
+
+    /// Returns the entry to open, if one was picked. The index is loaded the first time this is shown.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<ZooEntry> {
+        if self.pending_index.as_ref().is_some_and(|task| task.is_finished()) {
+            let task = self.pending_index.take().expect("checked above");
+            self.index = Some(task.join().map_err(GuiError::from).and_then(|result| result));
+        }
+        if self.index.is_none() && self.pending_index.is_none() {
+            self.refresh(ui.ctx());
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text(tr!("zoo-search")));
+            ui.strong(tr!("tags-label"));
+            ui.text_edit_singleline(&mut self.tags);
+            if ui.add_enabled(self.pending_index.is_none(), egui::Button::new(tr!("refresh"))).clicked() {
+                self.refresh(ui.ctx());
+            }
+        });
+        ui.separator();
+
+        let index = match &self.index {
+            _ if self.pending_index.is_some() => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.weak(tr!("loading-zoo"));
+                });
+                return None;
+            }
+            Some(Ok(index)) => index,
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, tr!("zoo-failed", error = err.to_string()));
+                return None;
+            }
+            None => return None,
+        };
+        let query = self.query();
+        let mut picked = None;
+        let mut clicked_tag = None;
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            let mut found = false;
+            for entry in index.search(&query) {
+                found = true;
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("open")).clicked() {
+                        picked = Some(entry.clone());
+                    }
+                    ui.strong(&entry.name).on_hover_text(&entry.description);
+                    ui.weak(&entry.id);
+                    for tag in &entry.tags {
+                        if ui.small_button(tag).clicked() {
+                            clicked_tag = Some(tag.clone());
+                        }
+                    }
+                });
+            }
+            if !found {
+                ui.weak(tr!("zoo-no-matches"));
+            }
+        });
+        if let Some(tag) = clicked_tag {
+            if !self.tags.trim().is_empty() {
+                self.tags.push_str(", ");
+            }
+            self.tags.push_str(&tag);
+        }
+        picked
+    }
+}
@@ -0,0 +1,33 @@
+from os import PathLike
+from typing import Any, Dict, List, Optional, Union
+
+StrPath = Union[str, PathLike]
+
+class PackagingError(Exception): ...
+
+class ValidationEntry:
+    severity: str
+    path: str
+    message: str
+
+class ValidationReport:
+    entries: List[ValidationEntry]
+    valid: bool
+    errors: List[ValidationEntry]
+    warnings: List[ValidationEntry]
+    infos: List[ValidationEntry]
+    def __bool__(self) -> bool: ...
+
+class PackagedFile:
+    path: str
+    size: int
+    sha256: str
+
+def validate(path: StrPath) -> ValidationReport: ...
+def load(path: StrPath) -> Dict[str, Any]: ...
+def package(
+    rdf_path: StrPath,
+    output: StrPath = "model.zip",
+    compression_level: Optional[int] = None,
+    recompute_hashes: bool = False,
+) -> List[PackagedFile]: ...
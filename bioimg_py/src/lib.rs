@@ -0,0 +1,177 @@
+//! Python bindings to the validator and packager, e.g. `bioimg.validate("rdf.yaml")`, so that python tools can
+//! check descriptions the same way the command line and the gui do.
+
+use std::path::PathBuf;
+
+use bioimg_spec::rdf::{model::ModelRdf, Severity};
+use bioimg_spec::runtime::{self, package_reader::read_rdf_text, ModelPackage};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(bioimg, PackagingError, PyException, "A package could not be written");
+
+/// One problem with a description, e.g. `ValidationEntry(severity='error', path='inputs[0].axes', ...)`
+#[pyclass(frozen, get_all, module = "bioimg")]
+#[derive(Clone)]
+struct ValidationEntry {
+    /// `"error"`, `"warning"` or `"info"`
+    severity: String,
+    /// Where in the description the problem is, as it would be written in code
+    path: String,
+    message: String,
+}
+
+#[pymethods]
+impl ValidationEntry {
+    fn __repr__(&self) -> String {
+        format!("ValidationEntry(severity={:?}, path={:?}, message={:?})", self.severity, self.path, self.message)
+    }
+
+    fn __str__(&self) -> String {
+        match self.path.as_str() {
+            "" => format!("{}: {}", self.severity, self.message),
+            path => format!("{}: {path}: {}", self.severity, self.message),
+        }
+    }
+}
+
+/// Everything found while validating. It is truthy when there are no errors, warnings notwithstanding.
+#[pyclass(frozen, module = "bioimg")]
+struct ValidationReport {
+    #[pyo3(get)]
+    entries: Vec<ValidationEntry>,
+}
+
+impl From<bioimg_spec::rdf::ValidationReport> for ValidationReport {
+    fn from(report: bioimg_spec::rdf::ValidationReport) -> Self {
+        let entries = report.entries.iter().map(|entry| ValidationEntry {
+            severity: entry.severity.to_string(),
+            path: entry.path_string(),
+            message: entry.message.clone(),
+        });
+        Self { entries: entries.collect() }
+    }
+}
+
+impl ValidationReport {
+    fn with_severity(&self, severity: Severity) -> Vec<ValidationEntry> {
+        let severity = severity.to_string();
+        self.entries.iter().filter(|entry| entry.severity == severity).cloned().collect()
+    }
+}
+
+#[pymethods]
+impl ValidationReport {
+    #[getter]
+    fn valid(&self) -> bool {
+        self.errors().is_empty()
+    }
+
+    #[getter]
+    fn errors(&self) -> Vec<ValidationEntry> {
+        self.with_severity(Severity::Error)
+    }
+
+    #[getter]
+    fn warnings(&self) -> Vec<ValidationEntry> {
+        self.with_severity(Severity::Warning)
+    }
+
+    #[getter]
+    fn infos(&self) -> Vec<ValidationEntry> {
+        self.with_severity(Severity::Info)
+    }
+
+    fn __bool__(&self) -> bool {
+        self.valid()
+    }
+
+    fn __repr__(&self) -> String {
+        let (errors, warnings) = (self.errors().len(), self.warnings().len());
+        format!("<ValidationReport: {errors} errors, {warnings} warnings>")
+    }
+}
+
+/// A file written into a package
+#[pyclass(frozen, get_all, module = "bioimg")]
+struct PackagedFile {
+    /// Where the file is inside the zip
+    path: PathBuf,
+    size: u64,
+    sha256: String,
+}
+
+#[pymethods]
+impl PackagedFile {
+    fn __repr__(&self) -> String {
+        format!("PackagedFile(path={:?}, size={}, sha256={:?})", self.path, self.size, self.sha256)
+    }
+}
+
+/// Validates a description, or a packaged model (a zip or a directory) along with its files
+#[pyfunction]
+fn validate(py: Python<'_>, path: PathBuf) -> ValidationReport {
+    py.allow_threads(|| runtime::validate_path(&path)).into()
+}
+
+/// Reads the description of a packaged or unpackaged model into a dict, with every field filled in the way
+/// format 0.5 spells it. Raises a ValueError if it isn't a valid 0.5 description.
+#[pyfunction]
+fn load(py: Python<'_>, path: PathBuf) -> PyResult<PyObject> {
+    let rdf = py.allow_threads(|| {
+        let raw = read_rdf_text(&path).map_err(|err| err.to_string())?;
+        let rdf = ModelRdf::from_yaml(&raw).map_err(|err| err.to_string())?;
+        serde_json::to_string(&rdf).map_err(|err| err.to_string())
+    });
+    let rdf = rdf.map_err(|err| PyValueError::new_err(format!("Could not load {}: {err}", path.display())))?;
+    Ok(py.import("json")?.call_method1("loads", (rdf,))?.into())
+}
+
+/// Writes a description and the local files it refers to into a zip, returning what was written. Hashes already in
+/// the description must match the files, unless `recompute_hashes` is set.
+#[pyfunction]
+#[pyo3(signature = (rdf_path, output = PathBuf::from("model.zip"), compression_level = None, recompute_hashes = false))]
+fn package(
+    py: Python<'_>,
+    rdf_path: PathBuf,
+    output: PathBuf,
+    compression_level: Option<i32>,
+    recompute_hashes: bool,
+) -> PyResult<Vec<PackagedFile>> {
+    if let Some(level) = compression_level.filter(|level| !(0..=9).contains(level)) {
+        return Err(PyValueError::new_err(format!("{level} is not a compression level from 0 to 9")));
+    }
+    let manifest = py.allow_threads(|| {
+        let mut package = ModelPackage::from_description_file(&rdf_path).map_err(|err| err.to_string())?;
+        package.set_compression_level(compression_level);
+        if recompute_hashes {
+            package.clear_hashes();
+        }
+        let manifest = package.write_to_path(&output).map_err(|err| err.to_string());
+        if manifest.is_err() {
+            // don't leave a broken zip behind
+            std::fs::remove_file(&output).ok();
+        }
+        manifest
+    });
+    let manifest = manifest.map_err(|err| PackagingError::new_err(format!("Could not package {}: {err}", rdf_path.display())))?;
+    let files = manifest.entries.into_iter().map(|entry| PackagedFile {
+        path: entry.path,
+        size: entry.size,
+        sha256: entry.sha256.to_string(),
+    });
+    Ok(files.collect())
+}
+
+#[pymodule]
+fn bioimg(py: Python<'_>, module: &PyModule) -> PyResult<()> {
+    module.add("PackagingError", py.get_type::<PackagingError>())?;
+    module.add_class::<ValidationEntry>()?;
+    module.add_class::<ValidationReport>()?;
+    module.add_class::<PackagedFile>()?;
+    module.add_function(wrap_pyfunction!(validate, module)?)?;
+    module.add_function(wrap_pyfunction!(load, module)?)?;
+    module.add_function(wrap_pyfunction!(package, module)?)?;
+    Ok(())
+}
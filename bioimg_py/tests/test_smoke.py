@@ -0,0 +1,32 @@
+from pathlib import Path
+
+import bioimg
+
+CORPUS = Path(__file__).parents[2] / "bioimg_spec" / "corpus"
+
+
+def test_valid_description():
+    report = bioimg.validate(CORPUS / "valid" / "model_affable_shark.yaml")
+    assert report
+    assert report.valid
+    assert report.errors == []
+
+
+def test_reported_entries():
+    report = bioimg.validate(str(CORPUS / "valid" / "model_without_cover_or_repo.yaml"))
+    assert report.valid
+    assert [(entry.severity, entry.path) for entry in report.entries] == [("warning", "covers"), ("info", "git_repo")]
+    assert str(report.warnings[0]).startswith("warning: covers: Model has no cover images")
+
+
+def test_invalid_description():
+    report = bioimg.validate(CORPUS / "invalid" / "model_halo_too_large.yaml")
+    assert not report
+    assert report.errors
+    assert all(entry.severity == "error" for entry in report.errors)
+
+
+def test_missing_file(tmp_path):
+    report = bioimg.validate(tmp_path / "missing.yaml")
+    assert not report
+    assert len(report.errors) == 1
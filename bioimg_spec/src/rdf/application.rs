@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::Rdf;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ApplicationType {
+    #[default]
+    #[serde(rename = "application")]
+    Application,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ApplicationRdf {
+    #[serde(rename = "type")]
+    pub rdf_type: ApplicationType,
+    #[serde(flatten)]
+    pub base: Rdf,
+}
@@ -1,14 +1,122 @@
-use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
 
-use super::file_reference::FileReference;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
-pub struct Attachments{
-    files: Option<Vec<FileReference>>,
+use super::{
+    file_reference::{FileDescr, FileReference},
+    sha256::Sha256,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum AttachmentError {
+    #[error("Attachment '{}' is not in the package", path.to_string_lossy())]
+    Missing { path: PathBuf },
+    #[error("Attachment '{}' has hash {found}, but {expected} was expected", path.to_string_lossy())]
+    HashMismatch { path: PathBuf, expected: Sha256, found: Sha256 },
+    #[error("Could not read attachment '{}': {source}", path.to_string_lossy())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// Files that aren't part of the description proper but are shipped along with it
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+#[serde(from = "RawAttachments")]
+pub struct Attachments {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileDescr>,
+}
+
+#[derive(Deserialize)]
+struct RawAttachments {
+    #[serde(default)]
+    files: Vec<RawAttachedFile>,
+}
+
+/// Older descriptions list the bare file references, without a hash
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawAttachedFile {
+    Bare(FileReference),
+    Described(FileDescr),
+}
+
+impl From<RawAttachments> for Attachments {
+    fn from(raw: RawAttachments) -> Self {
+        let files = raw
+            .files
+            .into_iter()
+            .map(|file| match file {
+                RawAttachedFile::Bare(source) => FileDescr { source, sha256: None },
+                RawAttachedFile::Described(descr) => descr,
+            })
+            .collect();
+        Self { files }
+    }
+}
+
+/// Checks that every attachment given as a path exists inside `package_dir` and has the hash
+/// it claims to have. Attachments given as urls are not downloaded and so not checked.
+pub fn validate_attachments(files: &[FileDescr], package_dir: &Path) -> Result<(), AttachmentError> {
+    for file in files {
+        let FileReference::Path(relative_path) = &file.source else {
+            continue;
+        };
+        let path = package_dir.join(relative_path);
+        if !path.is_file() {
+            return Err(AttachmentError::Missing { path: relative_path.clone() });
+        }
+        let Some(expected) = file.sha256 else {
+            continue;
+        };
+        let found = std::fs::File::open(&path)
+            .and_then(Sha256::compute)
+            .map_err(|source| AttachmentError::Io { path: relative_path.clone(), source })?;
+        if found != expected {
+            return Err(AttachmentError::HashMismatch { path: relative_path.clone(), expected, found });
+        }
+    }
+    Ok(())
 }
 
 #[test]
-fn test_attachment_serialization(){
+fn test_attachment_serialization() {
+    let attachments: Attachments = serde_yaml::from_str(
+        "
+        files:
+          - weights_info.txt
+          - {source: 'https://example.com/training_log.csv'}
+        ",
+    )
+    .unwrap();
+    assert_eq!(attachments.files.len(), 2);
+    assert!(attachments.files.iter().all(|file| file.sha256.is_none()));
 
+    let written = serde_yaml::to_string(&attachments).unwrap();
+    assert_eq!(serde_yaml::from_str::<Attachments>(&written).unwrap(), attachments);
+    assert!(serde_yaml::from_str::<Attachments>("files: [/etc/passwd]").is_err());
+}
 
-}
\ No newline at end of file
+#[test]
+fn test_attachment_validation() {
+    let dir = std::env::temp_dir().join(format!("bioimg_attachment_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("notes.txt"), "some notes").unwrap();
+
+    let attach = |source: &str, sha256: Option<Sha256>| FileDescr {
+        source: FileReference::try_from(source.to_owned()).unwrap(),
+        sha256,
+    };
+    let good = [
+        attach("notes.txt", Some(Sha256::digest("some notes"))),
+        attach("https://example.com/not_downloaded.txt", None),
+    ];
+    validate_attachments(&good, &dir).unwrap();
+    assert!(matches!(
+        validate_attachments(&[attach("other.txt", None)], &dir),
+        Err(AttachmentError::Missing { .. })
+    ));
+    assert!(matches!(
+        validate_attachments(&[attach("notes.txt", Some(Sha256::digest("other notes")))], &dir),
+        Err(AttachmentError::HashMismatch { .. })
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -16,9 +16,13 @@ pub struct Author {
 #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Author2 {
     pub name: BoundedString<1, 1023>,                // (Name→String) Full name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub affiliation: Option<BoundedString<1, 1023>>, // (String) Affiliation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub email: Option<BoundedString<1, 1023>>,       // FIXME: make a parser here (Email) E-Mail
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub github_user: Option<BoundedString<1, 1023>>, // (String) GitHub user name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub orcid: Option<Orcid>,
 }
 
@@ -4,9 +4,21 @@ use crate::rdf::BoundedString;
 
 use super::file_reference::FileReference;
 
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+/// A button on the page of the resource, e.g. to open it in a notebook service
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct Badge {
     pub label: BoundedString<1, 1023>, // (String) e.g. 'Open in Colab'
     pub icon: FileReference, // (String) e.g. 'https://colab.research.google.com/assets/colab-badge.svg'
     pub url: FileReference,  // (Union[URL→URI | Path→String])
 }
+
+#[test]
+fn test_badge_yaml_roundtrip() {
+    let raw = "label: Open in Colab\nicon: https://colab.research.google.com/assets/colab-badge.svg\nurl: notebooks/demo.ipynb\n";
+    let badge: Badge = serde_yaml::from_str(raw).unwrap();
+    assert_eq!(badge.label.to_string(), "Open in Colab");
+    assert!(matches!(badge.icon, FileReference::Url(_)));
+    assert!(matches!(badge.url, FileReference::Path(_)));
+    assert_eq!(serde_yaml::from_str::<Badge>(&serde_yaml::to_string(&badge).unwrap()).unwrap(), badge);
+    assert!(serde_yaml::from_str::<Badge>("label: ''\nicon: a.png\nurl: https://example.com\n").is_err());
+}
@@ -1,64 +1,145 @@
-use std::{borrow::Borrow, fmt::Display, ops::RangeInclusive};
+use std::{borrow::Borrow, fmt::Display, marker::PhantomData, ops::RangeInclusive};
 
 use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(thiserror::Error, PartialEq, Eq, Debug, Clone)]
 pub enum BoundedStringParsingError {
-    #[error("Expected a string with length in {allowed:?}")]
-    BadLength { value: String, allowed: RangeInclusive<usize> },
+    #[error("Expected between {} and {} characters, found {length}", allowed.start(), allowed.end())]
+    BadLength {
+        value: String,
+        length: usize,
+        allowed: RangeInclusive<usize>,
+    },
 }
 
+/// How [BoundedString] measures length
+pub mod counting {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    mod sealed {
+        pub trait Sealed {}
+    }
+
+    /// A way of counting the characters of a string. Sealed, so that only the modes below exist.
+    pub trait Counting: sealed::Sealed {
+        fn count(value: &str) -> usize;
+    }
+
+    /// User-perceived characters, so an emoji flag or an accented letter counts as one. This is the default.
+    #[derive(Debug, Eq, PartialEq, Clone, Copy)]
+    pub struct Graphemes;
+
+    /// Unicode scalar values, which is what python's `len` counts, for limits that have to match
+    /// the python implementation of the spec exactly
+    #[derive(Debug, Eq, PartialEq, Clone, Copy)]
+    pub struct CodePoints;
+
+    impl sealed::Sealed for Graphemes {}
+    impl sealed::Sealed for CodePoints {}
+
+    impl Counting for Graphemes {
+        fn count(value: &str) -> usize {
+            value.graphemes(true).count()
+        }
+    }
+
+    impl Counting for CodePoints {
+        fn count(value: &str) -> usize {
+            value.chars().count()
+        }
+    }
+}
+
+use counting::Counting;
+
+/// A string whose length, counted in user-perceived characters (grapheme clusters) unless `C`
+/// says otherwise, is in `MIN_CHARS..=MIN_CHARS + EXTRA_CHARS`. Values are normalized to NFC when
+/// parsed, so the same text is accepted and stored the same way whether it was typed in or deserialized.
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(try_from = "String")]
 #[serde(into = "String")]
-pub struct BoundedString<const MIN_CHARS: usize, const EXTRA_CHARS: usize>(String);
+#[serde(bound = "C: Counting + Clone")]
+pub struct BoundedString<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting = counting::Graphemes>(
+    String,
+    PhantomData<C>,
+);
 
-impl<const EXTRA_CHARS: usize> Default for BoundedString<0, EXTRA_CHARS> {
+impl<const EXTRA_CHARS: usize, C: Counting> Default for BoundedString<0, EXTRA_CHARS, C> {
     fn default() -> Self {
-        Self(String::new())
+        Self(String::new(), PhantomData)
     }
 }
 
-impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> TryFrom<String> for BoundedString<MIN_CHARS, EXTRA_CHARS> {
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> TryFrom<String> for BoundedString<MIN_CHARS, EXTRA_CHARS, C> {
     type Error = BoundedStringParsingError;
     fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value: String = value.nfc().collect();
+        let length = C::count(&value);
         let allowed = MIN_CHARS..=MIN_CHARS + EXTRA_CHARS;
-        if allowed.contains(&value.len()) {
-            Ok(BoundedString(value))
+        if allowed.contains(&length) {
+            Ok(BoundedString(value, PhantomData))
         } else {
-            Err(BoundedStringParsingError::BadLength { value, allowed })
+            Err(BoundedStringParsingError::BadLength { value, length, allowed })
         }
     }
 }
 
-impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> Display for BoundedString<MIN_CHARS, EXTRA_CHARS> {
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> Display for BoundedString<MIN_CHARS, EXTRA_CHARS, C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
 
-impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> Borrow<str> for BoundedString<MIN_CHARS, EXTRA_CHARS> {
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> Borrow<str> for BoundedString<MIN_CHARS, EXTRA_CHARS, C> {
     fn borrow(&self) -> &str {
         return &self.0;
     }
 }
 
-impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> BoundedString<MIN_CHARS, EXTRA_CHARS> {
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> BoundedString<MIN_CHARS, EXTRA_CHARS, C> {
     pub fn as_str(&self) -> &str {
         return &self.0;
     }
 }
 
-impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> Into<String> for BoundedString<MIN_CHARS, EXTRA_CHARS> {
-    fn into(self) -> String {
-        return self.0;
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> From<BoundedString<MIN_CHARS, EXTRA_CHARS, C>> for String {
+    fn from(value: BoundedString<MIN_CHARS, EXTRA_CHARS, C>) -> Self {
+        value.0
     }
 }
 
-impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> TryFrom<&str> for BoundedString<MIN_CHARS, EXTRA_CHARS> {
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> TryFrom<&str> for BoundedString<MIN_CHARS, EXTRA_CHARS, C> {
     type Error = BoundedStringParsingError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         String::from(value).try_into()
     }
 }
+
+#[test]
+fn test_bounded_string_counts_graphemes() {
+    // 'e' followed by a combining acute accent is normalized into a single 'é'
+    let decomposed = "caf\u{65}\u{301}";
+    let name = BoundedString::<1, 3>::try_from(decomposed).unwrap();
+    assert_eq!(name.as_str(), "caf\u{e9}");
+
+    // each of these takes 3 bytes in utf-8 but is a single character
+    assert!(BoundedString::<1, 2>::try_from("細胞核").is_ok());
+    assert!(BoundedString::<1, 1>::try_from("細胞核").is_err());
+
+    // flags are made of two code points but count as one character
+    assert!(BoundedString::<1, 0>::try_from("\u{1F1E9}\u{1F1EA}").is_ok());
+    assert_eq!(
+        BoundedString::<3, 0>::try_from("ab"),
+        Err(BoundedStringParsingError::BadLength {
+            value: "ab".into(),
+            length: 2,
+            allowed: 3..=3
+        })
+    );
+
+    // the flag is two code points
+    assert!(BoundedString::<1, 0, counting::CodePoints>::try_from("\u{1F1E9}\u{1F1EA}").is_err());
+    assert!(BoundedString::<2, 0, counting::CodePoints>::try_from("\u{1F1E9}\u{1F1EA}").is_ok());
+}
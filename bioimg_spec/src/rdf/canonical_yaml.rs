@@ -0,0 +1,249 @@
+use serde_yaml::Value;
+
+/// Top level fields in the order the spec documents them: the required ones first, then the
+/// optional ones alphabetically. Fields not listed here keep their order and go after these.
+pub const FIELD_ORDER: &[&str] = &[
+    "format_version",
+    "description",
+    "name",
+    "attachments",
+    "authors",
+    "badges",
+    "cite",
+    "config",
+    "covers",
+    "documentation",
+    "download_url",
+    "git_repo",
+    "icon",
+    "id",
+    "license",
+    "links",
+    "maintainers",
+    "rdf_source",
+    "source",
+    "tags",
+    "type",
+    "version",
+];
+
+const INDENT: &str = "  ";
+
+/// Writes `value` as block style yaml that doesn't depend on how serde_yaml happens to format
+/// things: top level fields in [FIELD_ORDER] and without the unset (`null`) ones, lists indented
+/// under their key, strings left unquoted only when they can't be read back as anything else.
+/// Nested `null`s are kept, since in free-form mappings like `config` they can mean something.
+pub fn to_canonical_string(value: &Value) -> String {
+    let value = match value {
+        Value::Mapping(mapping) => Value::Mapping(ordered_mapping(mapping)),
+        other => other.clone(),
+    };
+    let mut out = String::new();
+    for line in block_lines(&value) {
+        out += &line;
+        out.push('\n');
+    }
+    out
+}
+
+fn field_rank(key: &Value) -> usize {
+    key.as_str()
+        .and_then(|key| FIELD_ORDER.iter().position(|field| *field == key))
+        .unwrap_or(FIELD_ORDER.len())
+}
+
+fn ordered_mapping(mapping: &serde_yaml::Mapping) -> serde_yaml::Mapping {
+    let mut fields: Vec<(&Value, &Value)> = mapping.iter().filter(|(_, value)| !value.is_null()).collect();
+    // stable, so the unlisted fields stay in the order they were serialized in
+    fields.sort_by_key(|(key, _)| field_rank(key));
+    fields.into_iter().map(|(key, value)| (key.clone(), value.clone())).collect()
+}
+
+/// Empty lines stay empty, so that blank lines in literal blocks don't get trailing whitespace
+fn indented(line: String) -> String {
+    if line.is_empty() {
+        line
+    } else {
+        format!("{INDENT}{line}")
+    }
+}
+
+fn is_inline(value: &Value) -> bool {
+    match value {
+        Value::Sequence(items) => items.is_empty(),
+        Value::Mapping(fields) => fields.is_empty(),
+        Value::Tagged(tagged) => is_inline(&tagged.value),
+        Value::String(s) => !use_literal_block(s),
+        _ => true,
+    }
+}
+
+/// Lines of `value` when it is written on its own, without indentation
+fn block_lines(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(items) if !items.is_empty() => items.iter().flat_map(sequence_item_lines).collect(),
+        Value::Mapping(fields) if !fields.is_empty() => {
+            fields.iter().flat_map(|(key, value)| field_lines(key, value)).collect()
+        }
+        Value::String(s) if use_literal_block(s) => literal_block_lines(s),
+        Value::Tagged(tagged) => {
+            let mut lines = block_lines(&tagged.value);
+            if is_inline(&tagged.value) {
+                lines[0] = format!("{} {}", tagged.tag, lines[0]);
+            } else {
+                lines.insert(0, tagged.tag.to_string());
+            }
+            lines
+        }
+        other => vec![inline(other)],
+    }
+}
+
+fn sequence_item_lines(item: &Value) -> Vec<String> {
+    let mut lines = block_lines(item);
+    if matches!(item, Value::String(_)) && !is_inline(item) {
+        // a literal block header has to stay on the dash line
+        lines[0] = format!("- {}", lines[0]);
+        return lines;
+    }
+    for (idx, line) in lines.iter_mut().enumerate() {
+        *line = if idx == 0 {
+            format!("- {line}")
+        } else {
+            indented(std::mem::take(line))
+        };
+    }
+    lines
+}
+
+fn field_lines(key: &Value, value: &Value) -> Vec<String> {
+    let key = inline(key);
+    if is_inline(value) {
+        return vec![format!("{key}: {}", inline(value))];
+    }
+    let nested = block_lines(value);
+    if let Value::String(_) = value {
+        let mut lines = vec![format!("{key}: {}", nested[0])];
+        lines.extend(nested.into_iter().skip(1));
+        return lines;
+    }
+    let mut lines = vec![format!("{key}:")];
+    lines.extend(nested.into_iter().map(indented));
+    lines
+}
+
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Null => "null".into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quoted_if_needed(s),
+        Value::Sequence(_) => "[]".into(),
+        Value::Mapping(_) => "{}".into(),
+        Value::Tagged(tagged) => format!("{} {}", tagged.tag, inline(&tagged.value)),
+    }
+}
+
+/// Multi-line text, like a description, reads best as a literal block, as long as nothing about
+/// its whitespace would get lost
+fn use_literal_block(s: &str) -> bool {
+    let body = s.strip_suffix('\n').unwrap_or(s);
+    body.contains('\n')
+        && !body.ends_with('\n')
+        && !body.starts_with([' ', '\n'])
+        && body.lines().all(|line| line.trim_end() == line)
+        && !body.chars().any(|c| c.is_control() && c != '\n')
+}
+
+fn literal_block_lines(s: &str) -> Vec<String> {
+    let (header, body) = match s.strip_suffix('\n') {
+        Some(body) => ("|", body),
+        None => ("|-", s),
+    };
+    let mut lines = vec![header.to_owned()];
+    lines.extend(body.lines().map(|line| indented(line.to_owned())));
+    lines
+}
+
+/// Whether `s` can be written without quotes and still be read back as the same string
+fn is_plain_safe(s: &str) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    let safe_char = |c: char| c.is_alphanumeric() || " _-./:@+()=,;'!?~%$^".contains(c);
+    (first.is_alphanumeric() || "_./(".contains(first))
+        && s.chars().all(safe_char)
+        && s.trim() == s
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && serde_yaml::from_str::<Value>(s).ok() == Some(Value::String(s.to_owned()))
+}
+
+fn quoted_if_needed(s: &str) -> String {
+    if is_plain_safe(s) {
+        return s.to_owned();
+    }
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\t' => quoted += "\\t",
+            '\r' => quoted += "\\r",
+            c if c.is_control() => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn test_canonical_yaml() {
+    let raw = "
+type: dataset
+name: my dataset
+tags: [a, 'true', '1.0', 'key: value', '']
+version: null
+description: |
+  first line
+
+  third line
+format_version: 0.2.3
+authors:
+  - name: Jane
+config: {threshold: null}
+size: {num_samples: 3}
+";
+    let value: Value = serde_yaml::from_str(raw).unwrap();
+    let canonical = to_canonical_string(&value);
+    assert_eq!(
+        canonical,
+        r#"format_version: 0.2.3
+description: |
+  first line
+
+  third line
+name: my dataset
+authors:
+  - name: Jane
+config:
+  threshold: null
+tags:
+  - a
+  - "true"
+  - "1.0"
+  - "key: value"
+  - ""
+type: dataset
+size:
+  num_samples: 3
+"#
+    );
+    let reparsed: Value = serde_yaml::from_str(&canonical).unwrap();
+    assert_eq!(reparsed.get("tags"), value.get("tags"));
+    assert_eq!(reparsed.get("description"), value.get("description"));
+    assert_eq!(to_canonical_string(&reparsed), canonical);
+}
@@ -1,19 +1,21 @@
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::rdf::BoundedString;
+use crate::rdf::{BoundedString, Doi};
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct CiteEntry {
     pub text: BoundedString<1, 1023>, //(String) free text description
-    pub doi: BoundedString<1, 1023>, // FIXME: make it stricter (DOI→String) digital object identifier, see https://www.doi.org/ (alternatively specify url)
+    pub doi: Doi,
     pub url: Url,
 }
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
 pub struct CiteEntry2 {
     pub text: BoundedString<1, 1023>,        //(String) free text description
-    pub doi: Option<BoundedString<1, 1023>>, // FIXME: make it stricter (DOI→String) digital object identifier, see https://www.doi.org/ (alternatively specify url)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<Doi>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub url: Option<Url>,
 }
 
@@ -5,7 +5,7 @@ pub struct Clamped<const MIN: usize, const MAX: usize, T>(T);
 #[derive(thiserror::Error, Debug)]
 pub enum ClampedValueParsingError{
     #[error("{source}")]
-    BadValue{source: Box<dyn Error + 'static>},
+    BadValue{source: Box<dyn Error + Send + Sync + 'static>},
     #[error("Value '{value}' is not in range [{min}, {max}]")]
     ValueNotInRange{value: usize, min: usize, max: usize},
 }
@@ -20,7 +20,7 @@ Borrow<usize> for Clamped<MIN, MAX, T>{
 impl<const MIN: usize, const MAX: usize, T, E>
 TryFrom<usize> for Clamped<MIN, MAX, T>
 where
-E: Error + 'static,
+E: Error + Send + Sync + 'static,
 T: TryFrom<usize, Error = E>,
 T: Borrow<usize>,
 {
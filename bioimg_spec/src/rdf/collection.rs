@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bounded_string::BoundedString,
+    file_reference::FileReference,
+    partner_config::PartnerConfigRegistry,
+    validation::{spec_path, Severity, ValidationReport},
+    Rdf,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum CollectionType {
+    #[default]
+    #[serde(rename = "collection")]
+    Collection,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CollectionRdfParsingError {
+    #[error("Collection entry id '{0}' is used more than once")]
+    DuplicateEntryId(String),
+}
+
+/// Problems that only show up when looking at how the entries of a collection refer to each other
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CollectionLinkError {
+    #[error("Entry '{entry}' links to '{target}', which is not in the collection")]
+    DanglingLink { entry: String, target: String },
+    #[error("Entry '{entry}' has parent '{parent}', which is not in the collection")]
+    DanglingParent { entry: String, parent: String },
+    #[error("Entries form a parent cycle: {}", .0.join(" -> "))]
+    ParentCycle(Vec<String>),
+}
+
+/// A resource in a collection, either described inline or linked via `rdf_source`.
+/// Any other field overrides the corresponding field of the linked resource.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct CollectionEntry {
+    pub id: BoundedString<1, 1023>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rdf_source: Option<FileReference>,
+    #[serde(flatten)]
+    pub overrides: serde_yaml::Mapping,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(try_from = "RawCollectionRdf")]
+pub struct CollectionRdf {
+    #[serde(rename = "type")]
+    pub rdf_type: CollectionType,
+    #[serde(flatten)]
+    pub base: Rdf,
+    collection: Vec<CollectionEntry>,
+}
+
+#[derive(Deserialize)]
+struct RawCollectionRdf {
+    #[serde(rename = "type")]
+    rdf_type: CollectionType,
+    #[serde(flatten)]
+    base: Rdf,
+    collection: Vec<CollectionEntry>,
+}
+
+impl TryFrom<RawCollectionRdf> for CollectionRdf {
+    type Error = CollectionRdfParsingError;
+    fn try_from(raw: RawCollectionRdf) -> Result<Self, Self::Error> {
+        let mut rdf = Self::new(raw.base);
+        rdf.rdf_type = raw.rdf_type;
+        for entry in raw.collection {
+            rdf.push_entry(entry)?;
+        }
+        Ok(rdf)
+    }
+}
+
+impl CollectionRdf {
+    pub fn new(base: Rdf) -> Self {
+        Self {
+            rdf_type: CollectionType::Collection,
+            base,
+            collection: vec![],
+        }
+    }
+
+    pub fn entries(&self) -> &[CollectionEntry] {
+        &self.collection
+    }
+
+    pub fn push_entry(&mut self, entry: CollectionEntry) -> Result<(), CollectionRdfParsingError> {
+        if self.collection.iter().any(|e| e.id == entry.id) {
+            return Err(CollectionRdfParsingError::DuplicateEntryId(entry.id.into()));
+        }
+        self.collection.push(entry);
+        Ok(())
+    }
+
+    pub fn remove_entry(&mut self, id: &str) -> Option<CollectionEntry> {
+        let idx = self.collection.iter().position(|e| e.id.as_str() == id)?;
+        Some(self.collection.remove(idx))
+    }
+
+    /// Replaces all entries at once, e.g. after reordering them
+    pub fn set_entries(&mut self, entries: Vec<CollectionEntry>) -> Result<(), CollectionRdfParsingError> {
+        let mut seen = HashSet::new();
+        if let Some(dup) = entries.iter().find(|e| !seen.insert(e.id.as_str())) {
+            return Err(CollectionRdfParsingError::DuplicateEntryId(dup.id.to_string()));
+        }
+        self.collection = entries;
+        Ok(())
+    }
+
+    /// Checks the whole collection, collecting every problem instead of stopping at the first one
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let entry_path = |id: &str, field: &str| match self.collection.iter().position(|e| e.id.as_str() == id) {
+            Some(idx) => spec_path!["collection", idx, field],
+            None => spec_path!["collection"],
+        };
+        for error in self.validate_links() {
+            let path = match &error {
+                CollectionLinkError::DanglingLink { entry, .. } => entry_path(entry, "links"),
+                CollectionLinkError::DanglingParent { entry, .. } => entry_path(entry, "parent"),
+                CollectionLinkError::ParentCycle(_) => spec_path!["collection"],
+            };
+            report.push(Severity::Error, path, error);
+        }
+
+        let registry = PartnerConfigRegistry::default();
+        if let Some(config) = &self.base.config {
+            registry.check(config, spec_path!["config"], &mut report);
+        }
+        for (idx, entry) in self.collection.iter().enumerate() {
+            if let Some(config) = entry.overrides.get("config").and_then(|config| config.as_mapping()) {
+                registry.check(config, spec_path!["collection", idx, "config"], &mut report);
+            }
+        }
+        report
+    }
+
+    /// Checks the `links` and `parent` fields of every entry against the other entries of this collection,
+    /// returning every dangling reference and every cycle in the parent graph. Links are allowed to be mutual,
+    /// so only parents are checked for cycles.
+    pub fn validate_links(&self) -> Vec<CollectionLinkError> {
+        let collection_id = self.base.id.as_ref().map(|id| id.as_str());
+        let resolve = |target: &str| -> Option<usize> {
+            let local_target = match collection_id.and_then(|cid| target.strip_prefix(cid)) {
+                Some(rest) => rest.strip_prefix('/').unwrap_or(target),
+                None => target,
+            };
+            self.collection.iter().position(|e| e.id.as_str() == local_target)
+        };
+
+        let mut errors = vec![];
+        let mut parents: HashMap<usize, usize> = HashMap::new();
+        for (idx, entry) in self.collection.iter().enumerate() {
+            for target in entry.links() {
+                if resolve(target).is_none() {
+                    errors.push(CollectionLinkError::DanglingLink {
+                        entry: entry.id.to_string(),
+                        target: target.into(),
+                    });
+                }
+            }
+            if let Some(parent) = entry.parent() {
+                match resolve(parent) {
+                    Some(parent_idx) => {
+                        parents.insert(idx, parent_idx);
+                    }
+                    None => errors.push(CollectionLinkError::DanglingParent {
+                        entry: entry.id.to_string(),
+                        parent: parent.into(),
+                    }),
+                }
+            }
+        }
+
+        // every entry has at most one parent, so following parents from each entry either ends or loops
+        let mut done: HashSet<usize> = HashSet::new();
+        for start in 0..self.collection.len() {
+            let mut path: Vec<usize> = vec![];
+            let mut current = Some(start);
+            while let Some(idx) = current {
+                if done.contains(&idx) {
+                    break;
+                }
+                if let Some(cycle_start) = path.iter().position(|visited| *visited == idx) {
+                    let mut cycle: Vec<String> = path[cycle_start..]
+                        .iter()
+                        .map(|i| self.collection[*i].id.to_string())
+                        .collect();
+                    cycle.push(self.collection[idx].id.to_string());
+                    errors.push(CollectionLinkError::ParentCycle(cycle));
+                    break;
+                }
+                path.push(idx);
+                current = parents.get(&idx).copied();
+            }
+            done.extend(path);
+        }
+        errors
+    }
+}
+
+impl CollectionEntry {
+    /// Ids of other resources listed in this entry's `links` field
+    pub fn links(&self) -> impl Iterator<Item = &str> {
+        self.overrides
+            .get("links")
+            .and_then(|links| links.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|link| link.as_str())
+    }
+
+    /// The id of this entry's parent, which is given either on its own or as the `id` of a [super::LinkedResource]
+    pub fn parent(&self) -> Option<&str> {
+        let parent = self.overrides.get("parent")?;
+        parent.as_str().or_else(|| parent.get("id")?.as_str())
+    }
+}
+
+#[test]
+fn test_collection_rdf_serde() {
+    let raw = "
+type: collection
+format_version: 0.2.3
+name: my collection
+description: a bunch of models
+collection:
+  - id: first
+    rdf_source: https://example.com/first/rdf.yaml
+    name: renamed first
+  - id: second
+    rdf_source: second/rdf.yaml
+";
+    let collection: CollectionRdf = serde_yaml::from_str(raw).unwrap();
+    assert_eq!(collection.entries().len(), 2);
+    assert_eq!(
+        collection.entries()[0].overrides.get("name").and_then(|n| n.as_str()),
+        Some("renamed first")
+    );
+    let reserialized: CollectionRdf = serde_yaml::from_str(&serde_yaml::to_string(&collection).unwrap()).unwrap();
+    assert_eq!(reserialized, collection);
+
+    assert!(serde_yaml::from_str::<CollectionRdf>(&raw.replace("id: second", "id: first")).is_err());
+
+    // entries for packages next to the collection are written so that they can be read back
+    let dir = std::path::Path::new("/data/collections");
+    let mut collection = CollectionRdf::new(reserialized.base);
+    let entry = CollectionEntry {
+        id: BoundedString::try_from("unet").unwrap(),
+        rdf_source: Some(FileReference::local(&dir.join("unet/model.zip"), dir).unwrap()),
+        overrides: Default::default(),
+    };
+    collection.set_entries(vec![entry]).unwrap();
+    let written = serde_yaml::to_string(&collection).unwrap();
+    assert!(written.contains("rdf_source: unet/model.zip"));
+    assert_eq!(serde_yaml::from_str::<CollectionRdf>(&written).unwrap(), collection);
+}
+
+#[test]
+fn test_collection_link_validation() {
+    let raw = "
+type: collection
+format_version: 0.2.3
+name: my collection
+description: a bunch of models
+id: my-collection
+collection:
+  - id: a
+    links: [b, my-collection/c, nowhere]
+  - id: b
+    parent: c
+    links: [a]
+  - id: c
+    parent: my-collection/b
+  - id: d
+    parent: {id: a, version_number: 1}
+  - id: e
+    parent: missing
+";
+    let collection: CollectionRdf = serde_yaml::from_str(raw).unwrap();
+    let errors = collection.validate_links();
+    assert_eq!(
+        errors,
+        vec![
+            CollectionLinkError::DanglingLink {
+                entry: "a".into(),
+                target: "nowhere".into()
+            },
+            CollectionLinkError::DanglingParent {
+                entry: "e".into(),
+                parent: "missing".into()
+            },
+            CollectionLinkError::ParentCycle(vec!["b".into(), "c".into(), "b".into()]),
+        ]
+    );
+    let report = collection.validate();
+    let paths: Vec<_> = report.errors().map(|entry| entry.path_string()).collect();
+    assert_eq!(paths, vec!["collection[0].links", "collection[4].parent", "collection"]);
+    assert_eq!(crate::runtime::validate_yaml(raw), report);
+
+    let with_configs = raw.replace(
+        "  - id: e\n",
+        "  - id: e\n    config:\n      stardist: {thresholds: {prob: high}}\n      some_unknown_partner: 1\n",
+    );
+    let collection: CollectionRdf = serde_yaml::from_str(&with_configs).unwrap();
+    let paths: Vec<_> = collection.validate().errors().map(|entry| entry.path_string()).collect();
+    assert_eq!(paths.last().map(String::as_str), Some("collection[4].config.stardist"));
+    assert_eq!(paths.len(), 4);
+}
@@ -0,0 +1,82 @@
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+
+use super::Rdf;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum DatasetType {
+    #[default]
+    #[serde(rename = "dataset")]
+    Dataset,
+}
+
+/// Rough size of the data behind `source`, so consumers know what they're about to download
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct DatasetSize {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_samples: Option<NonZeroUsize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_in_bytes: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct DatasetRdf {
+    #[serde(rename = "type")]
+    pub rdf_type: DatasetType,
+    #[serde(flatten)]
+    pub base: Rdf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<DatasetSize>,
+}
+
+#[test]
+fn test_dataset_rdf_serde() {
+    use std::path::PathBuf;
+
+    use super::{file_reference::FileReference, SpdxLicense};
+
+    let raw = serde_json::json!({
+        "type": "dataset",
+        "format_version": "0.2.3",
+        "description": "Some nuclei",
+        "name": "nuclei dataset",
+        "license": "CC-BY-4.0",
+        "source": "data/nuclei.zip",
+        "size": {"num_samples": 3},
+    });
+    let parsed: DatasetRdf = serde_json::from_value(raw).unwrap();
+    assert_eq!(parsed.base.source, Some(FileReference::Path(PathBuf::from("data/nuclei.zip"))));
+    assert_eq!(parsed.base.license, Some(SpdxLicense::CC_BY_4_0));
+    assert_eq!(parsed.size.as_ref().and_then(|s| s.num_samples), NonZeroUsize::new(3));
+
+    let bad_type = serde_json::json!({
+        "type": "model",
+        "format_version": "0.2.3",
+        "description": "Some nuclei",
+        "name": "nuclei dataset",
+    });
+    assert!(serde_json::from_value::<DatasetRdf>(bad_type).is_err());
+}
+
+#[test]
+fn test_dataset_rdf_round_trip() {
+    use super::{file_reference::FileReference, resource_description::AnyResourceRdf, SpdxLicense};
+
+    let mut base = Rdf::new("nuclei dataset".try_into().unwrap(), "Some nuclei".try_into().unwrap());
+    base.source = Some(FileReference::Url("https://example.com/nuclei.zip".parse().unwrap()));
+    base.license = Some(SpdxLicense::CC_BY_4_0);
+    base.covers = Some(vec![FileReference::Path("cover.png".into())]);
+    base.documentation = Some(FileReference::Path("README.md".into()));
+    let dataset = AnyResourceRdf::Dataset(DatasetRdf {
+        rdf_type: DatasetType::Dataset,
+        base,
+        size: Some(DatasetSize {
+            num_samples: NonZeroUsize::new(120),
+            size_in_bytes: Some(3 << 30),
+        }),
+    });
+    let yaml = dataset.to_yaml().unwrap();
+    assert!(yaml.contains("type: dataset"));
+    assert_eq!(AnyResourceRdf::from_yaml(&yaml).unwrap(), dataset);
+}
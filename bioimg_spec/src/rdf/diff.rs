@@ -0,0 +1,235 @@
+//! What changed between two versions of a description, field by field, e.g. to review an update to a published
+//! model before uploading it again.
+
+use std::fmt::Display;
+
+use serde_yaml::Value;
+
+use super::spec_error::{path_string, PathSegment};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added { path: Vec<PathSegment>, new: Value },
+    Removed { path: Vec<PathSegment>, old: Value },
+    Changed { path: Vec<PathSegment>, old: Value, new: Value },
+}
+
+impl Change {
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Self::Added { path, .. } | Self::Removed { path, .. } | Self::Changed { path, .. } => path,
+        }
+    }
+
+    pub fn old(&self) -> Option<&Value> {
+        match self {
+            Self::Added { .. } => None,
+            Self::Removed { old, .. } | Self::Changed { old, .. } => Some(old),
+        }
+    }
+
+    pub fn new_value(&self) -> Option<&Value> {
+        match self {
+            Self::Removed { .. } => None,
+            Self::Added { new, .. } | Self::Changed { new, .. } => Some(new),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Added { .. } => "added",
+            Self::Removed { .. } => "removed",
+            Self::Changed { .. } => "changed",
+        }
+    }
+}
+
+/// A value on one line, as JSON
+fn inline(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{value:?}"))
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = path_string(self.path());
+        match self {
+            Self::Added { new, .. } => write!(f, "+ {path}: {}", inline(new)),
+            Self::Removed { old, .. } => write!(f, "- {path}: {}", inline(old)),
+            Self::Changed { old, new, .. } => write!(f, "~ {path}: {} -> {}", inline(old), inline(new)),
+        }
+    }
+}
+
+/// Every change from an older description to a newer one, in the order of the fields they are in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Changelist {
+    pub changes: Vec<Change>,
+}
+
+impl Changelist {
+    /// The changes from `old` to `new`. Items of lists in which every item has its own `id`, like tensors and
+    /// axes, are matched by id, and their paths have their index in the list they are in; other lists are
+    /// compared item by item.
+    pub fn between(old: &Value, new: &Value) -> Self {
+        let mut changelist = Self::default();
+        changelist.compare(&mut vec![], old, new);
+        changelist
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn compare(&mut self, path: &mut Vec<PathSegment>, old: &Value, new: &Value) {
+        match (old, new) {
+            _ if old == new => (),
+            (Value::Mapping(old), Value::Mapping(new)) => {
+                for (key, old_value) in old {
+                    path.push(PathSegment::Field(key_name(key)));
+                    match new.get(key) {
+                        Some(new_value) => self.compare(path, old_value, new_value),
+                        None => self.changes.push(Change::Removed {
+                            path: path.clone(),
+                            old: old_value.clone(),
+                        }),
+                    }
+                    path.pop();
+                }
+                for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                    path.push(PathSegment::Field(key_name(key)));
+                    self.changes.push(Change::Added {
+                        path: path.clone(),
+                        new: new_value.clone(),
+                    });
+                    path.pop();
+                }
+            }
+            (Value::Sequence(old), Value::Sequence(new)) => match (item_ids(old), item_ids(new)) {
+                (Some(old_ids), Some(new_ids)) => self.compare_by_id(path, (old, &old_ids), (new, &new_ids)),
+                _ => self.compare_by_index(path, old, new),
+            },
+            (Value::Tagged(old), Value::Tagged(new)) if old.tag == new.tag => self.compare(path, &old.value, &new.value),
+            _ => self.changes.push(Change::Changed {
+                path: path.clone(),
+                old: old.clone(),
+                new: new.clone(),
+            }),
+        }
+    }
+
+    fn compare_by_index(&mut self, path: &mut Vec<PathSegment>, old: &[Value], new: &[Value]) {
+        for index in 0..old.len().max(new.len()) {
+            path.push(PathSegment::Index(index));
+            match (old.get(index), new.get(index)) {
+                (Some(old), Some(new)) => self.compare(path, old, new),
+                (Some(old), None) => self.changes.push(Change::Removed {
+                    path: path.clone(),
+                    old: old.clone(),
+                }),
+                (None, Some(new)) => self.changes.push(Change::Added {
+                    path: path.clone(),
+                    new: new.clone(),
+                }),
+                (None, None) => unreachable!("index is below the length of one of the lists"),
+            }
+            path.pop();
+        }
+    }
+
+    fn compare_by_id(&mut self, path: &mut Vec<PathSegment>, old: (&[Value], &[&str]), new: (&[Value], &[&str])) {
+        let ((old, old_ids), (new, new_ids)) = (old, new);
+        for (old_index, id) in old_ids.iter().enumerate() {
+            let Some(new_index) = new_ids.iter().position(|new_id| new_id == id) else {
+                path.push(PathSegment::Index(old_index));
+                self.changes.push(Change::Removed {
+                    path: path.clone(),
+                    old: old[old_index].clone(),
+                });
+                path.pop();
+                continue;
+            };
+            path.push(PathSegment::Index(new_index));
+            self.compare(path, &old[old_index], &new[new_index]);
+            path.pop();
+        }
+        for (new_index, id) in new_ids.iter().enumerate() {
+            if !old_ids.contains(id) {
+                path.push(PathSegment::Index(new_index));
+                self.changes.push(Change::Added {
+                    path: path.clone(),
+                    new: new[new_index].clone(),
+                });
+                path.pop();
+            }
+        }
+    }
+
+    /// The changes as `[{"change", "path", "old", "new"}]`, leaving out `old` for added fields and `new` for
+    /// removed ones
+    pub fn to_json(&self) -> serde_json::Value {
+        let changes = self.changes.iter().map(|change| {
+            let mut entry = serde_json::json!({
+                "change": change.kind(),
+                "path": path_string(change.path()),
+            });
+            if let Some(old) = change.old() {
+                entry["old"] = serde_json::to_value(old).unwrap_or_default();
+            }
+            if let Some(new) = change.new_value() {
+                entry["new"] = serde_json::to_value(new).unwrap_or_default();
+            }
+            entry
+        });
+        serde_json::Value::Array(changes.collect())
+    }
+}
+
+fn key_name(key: &Value) -> String {
+    match key {
+        Value::String(key) => key.clone(),
+        other => inline(other),
+    }
+}
+
+/// The `id` of every item, if they all have a different one
+fn item_ids(items: &[Value]) -> Option<Vec<&str>> {
+    let ids = items.iter().map(|item| item.get("id")?.as_str()).collect::<Option<Vec<_>>>()?;
+    let all_different = ids.iter().enumerate().all(|(index, id)| !ids[..index].contains(id));
+    (!ids.is_empty() && all_different).then_some(ids)
+}
+
+#[test]
+fn test_changelist() {
+    use super::model::ModelRdf;
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_affable_shark.yaml");
+    let raw = std::fs::read_to_string(path).unwrap();
+    let old = ModelRdf::from_yaml(&raw).unwrap();
+    assert!(old.diff(&old).unwrap().is_empty());
+
+    let mut value = serde_yaml::to_value(&old).unwrap();
+    value["name"] = "Renamed model".into();
+    value["tags"].as_sequence_mut().unwrap().push("new-tag".into());
+    value.as_mapping_mut().unwrap().remove("git_repo");
+    let inputs = value["inputs"].as_sequence_mut().unwrap();
+    let mut second_input = inputs[0].clone();
+    second_input["id"] = "mask".into();
+    inputs.insert(0, second_input);
+    let new: ModelRdf = serde_yaml::from_value(value).unwrap();
+
+    let changelist = old.diff(&new).unwrap();
+    let tags = old.tags.len();
+    let summary: Vec<_> = changelist.changes.iter().map(|change| (change.kind(), path_string(change.path()))).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("changed", "name".to_owned()),
+            ("removed", "git_repo".to_owned()),
+            ("added", format!("tags[{tags}]")),
+            ("added", "inputs[0]".to_owned()),
+        ]
+    );
+    assert!(changelist.changes[0].to_string().starts_with("~ name: \""));
+    assert_eq!(changelist.to_json()[1]["change"], "removed");
+    assert!(changelist.to_json()[1].get("new").is_none());
+}
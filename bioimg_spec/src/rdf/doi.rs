@@ -0,0 +1,107 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Prefixes people paste along with a DOI, which are dropped when parsing
+const DOI_PREFIXES: [&str; 5] = [
+    "https://doi.org/",
+    "http://doi.org/",
+    "https://dx.doi.org/",
+    "http://dx.doi.org/",
+    "doi:",
+];
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DoiParsingError {
+    #[error("Expected a DOI like '10.1000/xyz123', found '{0}'")]
+    BadSyntax(String),
+}
+
+/// A digital object identifier, like `10.5281/zenodo.5108853`. See https://www.doi.org/
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct Doi(String);
+
+impl Doi {
+    /// The registrant code, e.g. `10.5281`
+    pub fn prefix(&self) -> &str {
+        self.0.split_once('/').map_or("", |(prefix, _)| prefix)
+    }
+
+    /// Where the DOI resolves to
+    pub fn url(&self) -> String {
+        format!("https://doi.org/{}", self.0)
+    }
+}
+
+impl TryFrom<String> for Doi {
+    type Error = DoiParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let trimmed = value.trim();
+        let doi = DOI_PREFIXES
+            .iter()
+            .find_map(|prefix| {
+                trimmed
+                    .get(..prefix.len())
+                    .filter(|p| p.eq_ignore_ascii_case(prefix))
+                    .map(|p| &trimmed[p.len()..])
+            })
+            .unwrap_or(trimmed);
+        let Some((prefix, suffix)) = doi.split_once('/') else {
+            return Err(DoiParsingError::BadSyntax(value));
+        };
+        let registrant = prefix.strip_prefix("10.").unwrap_or("");
+        let registrant_ok = !registrant.is_empty()
+            && registrant
+                .split('.')
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+        let suffix_ok = !suffix.is_empty() && !suffix.chars().any(char::is_whitespace);
+        if !registrant_ok || !suffix_ok {
+            return Err(DoiParsingError::BadSyntax(value));
+        }
+        Ok(Self(doi.to_owned()))
+    }
+}
+
+impl FromStr for Doi {
+    type Err = DoiParsingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_owned())
+    }
+}
+
+impl Display for Doi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<Doi> for String {
+    fn from(value: Doi) -> Self {
+        value.0
+    }
+}
+
+#[test]
+fn test_doi_parsing() {
+    let doi = Doi::from_str("10.1007/978-3-319-24574-4_28").unwrap();
+    assert_eq!(doi.prefix(), "10.1007");
+    assert_eq!(doi.url(), "https://doi.org/10.1007/978-3-319-24574-4_28");
+    assert_eq!(Doi::from_str(" https://doi.org/10.1007/978-3-319-24574-4_28").unwrap(), doi);
+    assert_eq!(Doi::from_str("DOI:10.1007/978-3-319-24574-4_28").unwrap(), doi);
+    assert!(Doi::from_str("10.1000.10/abc").is_ok());
+
+    for bad in [
+        "",
+        "blabla",
+        "10.1000",
+        "10./abc",
+        "11.1000/abc",
+        "10.10a/abc",
+        "10.1000/",
+        "10.1000/a b",
+    ] {
+        assert!(Doi::from_str(bad).is_err(), "{bad} should be rejected");
+    }
+}
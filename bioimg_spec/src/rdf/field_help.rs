@@ -0,0 +1,349 @@
+//! What the fields of a resource description are for and what they accept, for editors to show next to each
+//! field. Length limits are read off the types that validate the fields, so the help can't drift from validation.
+
+use super::bounded_string::{counting::Counting, BoundedString};
+use super::identifier::Identifier;
+use super::lowercase::Lowercase;
+use super::model::axes::AxisId;
+use super::slashless_string::SlashlessString;
+
+/// How many characters a string type accepts
+pub trait LengthLimits {
+    const MIN_LENGTH: usize;
+    const MAX_LENGTH: usize;
+}
+
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize, C: Counting> LengthLimits for BoundedString<MIN_CHARS, EXTRA_CHARS, C> {
+    const MIN_LENGTH: usize = MIN_CHARS;
+    const MAX_LENGTH: usize = MIN_CHARS + EXTRA_CHARS;
+}
+
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> LengthLimits for SlashlessString<MIN_CHARS, EXTRA_CHARS> {
+    const MIN_LENGTH: usize = MIN_CHARS;
+    const MAX_LENGTH: usize = MIN_CHARS + EXTRA_CHARS;
+}
+
+impl<T: LengthLimits> LengthLimits for Identifier<T> {
+    const MIN_LENGTH: usize = T::MIN_LENGTH;
+    const MAX_LENGTH: usize = T::MAX_LENGTH;
+}
+
+impl<T: LengthLimits> LengthLimits for Lowercase<T> {
+    const MIN_LENGTH: usize = T::MIN_LENGTH;
+    const MAX_LENGTH: usize = T::MAX_LENGTH;
+}
+
+/// A rule a field has to follow to validate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    /// Between `min` and `max` characters, both included
+    Length { min: usize, max: usize },
+    /// Starts with a letter or `_`, has nothing but letters, digits and `_`, and isn't a python keyword
+    Identifier,
+    /// Has no uppercase letters
+    Lowercase,
+    /// Has no `/`
+    NoSlashes,
+    /// Differs from the value of the same field of every other tensor, author, or whatever else the field is part of
+    Unique,
+    /// An ORCID iD, whose last digit checks the others
+    Orcid,
+    /// A DOI, like `10.1000/xyz123`
+    Doi,
+    /// A version of three numbers, like `1.2.3`
+    Version,
+    /// An http or https url
+    Url,
+}
+
+impl Constraint {
+    /// The length limits of `T`, e.g. of the type of a field
+    pub const fn length_of<T: LengthLimits>() -> Self {
+        Self::Length {
+            min: T::MIN_LENGTH,
+            max: T::MAX_LENGTH,
+        }
+    }
+}
+
+/// Help on one field, as shown next to it
+#[derive(Debug, Clone, Copy)]
+pub struct FieldHelp {
+    /// The field in [FIELD_HELP] notation: its path in the description, with list indices left out, like `inputs.axes.id`
+    pub field: &'static str,
+    pub description: &'static str,
+    pub constraints: &'static [Constraint],
+    pub example: Option<&'static str>,
+}
+
+pub static FIELD_HELP: &[FieldHelp] = &[
+    FieldHelp {
+        field: "name",
+        description: "A human-friendly name of the resource, ideally short enough to fit into a list of resources",
+        constraints: &[],
+        example: Some("UNet 2D Nuclei Broad"),
+    },
+    FieldHelp {
+        field: "description",
+        description: "A string containing a brief description of the resource",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: Some("A 2d U-Net trained on the nuclei of the DSB2018 challenge"),
+    },
+    FieldHelp {
+        field: "covers",
+        description: "Images shown as thumbnails of the resource, ideally with an aspect ratio of 2:1",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "attachments",
+        description: "Files that come with the resource, like scripts or configuration the resource needs",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "authors",
+        description: "The authors are the creators of the resource and the primary points of contact",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "authors.name",
+        description: "Full name",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>(), Constraint::Unique],
+        example: Some("Jane Doe"),
+    },
+    FieldHelp {
+        field: "authors.affiliation",
+        description: "Affiliation",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: Some("EMBL Heidelberg"),
+    },
+    FieldHelp {
+        field: "authors.email",
+        description: "E-mail address",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: None,
+    },
+    FieldHelp {
+        field: "authors.github_user",
+        description: "GitHub user name",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: None,
+    },
+    FieldHelp {
+        field: "authors.orcid",
+        description: "An ORCID iD, which identifies researchers across publications",
+        constraints: &[Constraint::Orcid],
+        example: Some("0000-0002-1825-0097"),
+    },
+    FieldHelp {
+        field: "badges",
+        description: "Buttons shown on the page of the resource, e.g. to open it in a notebook service",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "badges.label",
+        description: "The text of the badge, which is also shown when its icon can't be",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: Some("Open in Colab"),
+    },
+    FieldHelp {
+        field: "badges.icon",
+        description: "The image of the badge",
+        constraints: &[Constraint::Url],
+        example: Some("https://colab.research.google.com/assets/colab-badge.svg"),
+    },
+    FieldHelp {
+        field: "badges.url",
+        description: "Where clicking the badge leads to",
+        constraints: &[Constraint::Url],
+        example: None,
+    },
+    FieldHelp {
+        field: "cite",
+        description: "How to cite the resource, e.g. the paper it was published in",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "cite.text",
+        description: "Free text description of the reference",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: Some("Ronneberger et al. U-Net: Convolutional Networks for Biomedical Image Segmentation"),
+    },
+    FieldHelp {
+        field: "cite.doi",
+        description: "A digital object identifier of the reference; a DOI or a url is required",
+        constraints: &[Constraint::Doi],
+        example: Some("10.1007/978-3-319-24574-4_28"),
+    },
+    FieldHelp {
+        field: "cite.url",
+        description: "Where the reference can be read; a DOI or a url is required",
+        constraints: &[Constraint::Url],
+        example: None,
+    },
+    FieldHelp {
+        field: "maintainers",
+        description: "Who maintains the resource, and is meant to be contacted about it. Most of the time, \
+                      the authors also maintain it.",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "maintainers.name",
+        description: "Full name",
+        constraints: &[Constraint::length_of::<SlashlessString<1, 1023>>(), Constraint::NoSlashes],
+        example: None,
+    },
+    FieldHelp {
+        field: "git_repo",
+        description: "A url to the git repository with the source code that produced the resource",
+        constraints: &[Constraint::Url],
+        example: Some("https://github.com/bioimage-io/spec-bioimage-io"),
+    },
+    FieldHelp {
+        field: "icon",
+        description: "An icon for illustration, either a single emoji or an image file",
+        constraints: &[],
+        example: Some("🔬"),
+    },
+    FieldHelp {
+        field: "license",
+        description: "An SPDX license identifier. Only standard licenses can be picked, so that anybody can tell \
+                      what they are allowed to do with the resource.",
+        constraints: &[],
+        example: Some("MIT, CC-BY-4.0"),
+    },
+    FieldHelp {
+        field: "tags",
+        description: "Associated tags, which make the resource easier to find",
+        constraints: &[Constraint::length_of::<BoundedString<1, 1023>>()],
+        example: Some("unet2d, pytorch, nucleus, segmentation, dsb2018"),
+    },
+    FieldHelp {
+        field: "version",
+        description: "The version of the resource following semantic versioning",
+        constraints: &[Constraint::Version],
+        example: Some("0.1.0"),
+    },
+    FieldHelp {
+        field: "documentation",
+        description: "A markdown file with documentation on how to use the resource, and on what data it was made for",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "inputs",
+        description: "Describes the input tensors the model expects",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "inputs.id",
+        description: "Input tensor id. No duplicates are allowed across all inputs and outputs.",
+        constraints: &[Constraint::Identifier, Constraint::Lowercase, Constraint::Unique],
+        example: Some("raw"),
+    },
+    FieldHelp {
+        field: "inputs.description",
+        description: "Free text description of the tensor",
+        constraints: &[Constraint::length_of::<BoundedString<0, 128>>()],
+        example: None,
+    },
+    FieldHelp {
+        field: "inputs.axes",
+        description: "The axes of the tensor, in the order its data is laid out in",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "inputs.axes.id",
+        description: "An axis id unique across all axes of one tensor",
+        constraints: &[
+            Constraint::length_of::<AxisId>(),
+            Constraint::Identifier,
+            Constraint::Lowercase,
+            Constraint::Unique,
+        ],
+        example: Some("x"),
+    },
+    FieldHelp {
+        field: "inputs.test_tensor",
+        description: "An example tensor to use for testing. Running the model on it should produce the test tensors \
+                      of the outputs.",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "inputs.sample_tensor",
+        description: "A sample tensor to illustrate a possible input, like a cropped image of typical data",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "inputs.preprocessing",
+        description: "Steps that turn the data into what the network expects, applied in the order listed",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "outputs",
+        description: "Describes the output tensors the model produces",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "outputs.id",
+        description: "Output tensor id. No duplicates are allowed across all inputs and outputs.",
+        constraints: &[Constraint::Identifier, Constraint::Lowercase, Constraint::Unique],
+        example: Some("mask"),
+    },
+    FieldHelp {
+        field: "outputs.postprocessing",
+        description: "Steps applied to what the network produces, in the order listed",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "weights",
+        description: "The weights of the model, in one or more formats that must all produce the same outputs",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "parent",
+        description: "The model this one was derived from, e.g. by fine-tuning it",
+        constraints: &[],
+        example: None,
+    },
+    FieldHelp {
+        field: "training_data",
+        description: "The dataset the model was trained on, either the id of a dataset in the zoo or a description of it",
+        constraints: &[],
+        example: Some("id: ilastik/covid_if_training_data"),
+    },
+];
+
+/// The help on `field`, in the notation of [FieldHelp::field]
+pub fn field_help(field: &str) -> Option<&'static FieldHelp> {
+    FIELD_HELP.iter().find(|help| help.field == field)
+}
+
+#[test]
+fn test_field_help() {
+    let mut fields: Vec<_> = FIELD_HELP.iter().map(|help| help.field).collect();
+    fields.sort();
+    fields.dedup();
+    assert_eq!(fields.len(), FIELD_HELP.len(), "every field has help once");
+
+    let Some(Constraint::Length { min, max }) = field_help("inputs.axes.id").unwrap().constraints.first().copied() else {
+        panic!("axis ids have a length limit");
+    };
+    assert_eq!((min, max), (1, 16));
+    assert!(AxisId::try_from("x".repeat(max)).is_ok());
+    assert!(AxisId::try_from("x".repeat(max + 1)).is_err());
+    assert!(field_help("inputs.axes.size").is_none());
+}
@@ -1,15 +1,41 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use super::sha256::Sha256;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum FileReferenceParsingError {
+    #[error("File reference is empty")]
+    Empty,
+    #[error("Bad url '{value}': {reason}")]
+    BadUrl { value: String, reason: String },
+    #[error("Path '{0}' must be relative to the package")]
+    AbsolutePath(String),
+    #[error("Path '{0}' points outside of the package")]
+    EscapesPackage(String),
+    #[error("Path '{0}' is the package itself rather than a file in it")]
+    PackageRoot(String),
+}
+
+/// Either a url or a path relative to the package that holds the resource description
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
-#[serde(untagged)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
 pub enum FileReference {
     Url(Url),
     Path(PathBuf),
 }
 
+/// A file along with the hash it is expected to have
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct FileDescr {
+    pub source: FileReference,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Sha256>,
+}
+
 impl From<Url> for FileReference {
     fn from(value: Url) -> Self {
         Self::Url(value)
@@ -22,6 +48,69 @@ impl From<PathBuf> for FileReference {
     }
 }
 
+impl FileReference {
+    /// Refers to the file at `path` from a description saved in `package_dir`, which must contain it
+    pub fn local(path: &Path, package_dir: &Path) -> Result<Self, FileReferenceParsingError> {
+        let outside = || FileReferenceParsingError::EscapesPackage(path.to_string_lossy().into());
+        let relative = path.strip_prefix(package_dir).map_err(|_| outside())?;
+        let raw: Vec<_> = relative.components().map(|component| component.as_os_str().to_string_lossy()).collect();
+        Self::try_from(raw.join("/"))
+    }
+}
+
+fn is_windows_absolute(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    raw.starts_with('\\') || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+impl TryFrom<String> for FileReference {
+    type Error = FileReferenceParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let raw = value.trim();
+        if raw.is_empty() {
+            return Err(FileReferenceParsingError::Empty);
+        }
+        // a drive letter would otherwise parse as a one-letter url scheme
+        if is_windows_absolute(raw) {
+            return Err(FileReferenceParsingError::AbsolutePath(value));
+        }
+        if raw.contains("://") {
+            return match Url::parse(raw) {
+                Ok(url) => Ok(Self::Url(url)),
+                Err(err) => Err(FileReferenceParsingError::BadUrl { value, reason: err.to_string() }),
+            };
+        }
+        // normalized, so that e.g. `./a/../weights.pt` and `weights.pt` are the same file when packaging
+        let mut path = PathBuf::new();
+        for component in Path::new(raw).components() {
+            match component {
+                Component::RootDir | Component::Prefix(_) => return Err(FileReferenceParsingError::AbsolutePath(value)),
+                Component::ParentDir if !path.pop() => return Err(FileReferenceParsingError::EscapesPackage(value)),
+                Component::ParentDir | Component::CurDir => (),
+                Component::Normal(name) => path.push(name),
+            }
+        }
+        if path.as_os_str().is_empty() {
+            return Err(FileReferenceParsingError::PackageRoot(value));
+        }
+        Ok(Self::Path(path))
+    }
+}
+
+impl From<FileReference> for String {
+    fn from(value: FileReference) -> Self {
+        match value {
+            FileReference::Url(url) => url.into(),
+            // always written with forward slashes, so packages made on windows work elsewhere
+            FileReference::Path(path) => path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+}
+
 #[test]
 fn test_file_reference() {
     use serde_json::json;
@@ -33,4 +122,28 @@ fn test_file_reference() {
     let raw_path = "lalala/lelele";
     let deserialized_path: FileReference = serde_json::from_value(json!(raw_path)).unwrap();
     assert_eq!(FileReference::Path(PathBuf::from(raw_path)), deserialized_path,);
+    assert_eq!(serde_json::to_value(&deserialized_path).unwrap(), json!(raw_path));
+
+    let parse = |raw: &str| FileReference::try_from(raw.to_owned());
+    assert_eq!(parse("./a/../weights.pt"), parse("weights.pt"));
+    assert_eq!(parse("a/./b//c.npy"), Ok(FileReference::Path(PathBuf::from("a/b/c.npy"))));
+    assert_eq!(parse("a/.."), Err(FileReferenceParsingError::PackageRoot("a/..".into())));
+    assert_eq!(parse("/etc/passwd"), Err(FileReferenceParsingError::AbsolutePath("/etc/passwd".into())));
+    assert_eq!(parse("C:\\data\\x.npy"), Err(FileReferenceParsingError::AbsolutePath("C:\\data\\x.npy".into())));
+    assert_eq!(parse("a/../../x.npy"), Err(FileReferenceParsingError::EscapesPackage("a/../../x.npy".into())));
+    assert_eq!(parse(" "), Err(FileReferenceParsingError::Empty));
+    assert!(matches!(parse("http://"), Err(FileReferenceParsingError::BadUrl { .. })));
+}
+
+#[test]
+fn test_local_file_reference() {
+    let dir = Path::new("/data/collections");
+    let model = FileReference::local(&dir.join("models/unet.zip"), dir).unwrap();
+    assert_eq!(model, FileReference::Path(PathBuf::from("models/unet.zip")));
+    assert_eq!(FileReference::try_from(String::from(model.clone())), Ok(model));
+    assert_eq!(
+        FileReference::local(Path::new("/elsewhere/unet.zip"), dir),
+        Err(FileReferenceParsingError::EscapesPackage("/elsewhere/unet.zip".into()))
+    );
+    assert!(FileReference::local(dir, dir).is_err());
 }
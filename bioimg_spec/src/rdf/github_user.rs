@@ -0,0 +1,82 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// GitHub doesn't allow longer user names
+const MAX_LENGTH: usize = 39;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum GithubUserParsingError {
+    #[error("GitHub user names have between 1 and {MAX_LENGTH} characters, found {0}")]
+    BadLength(usize),
+    #[error("GitHub user name '{user}' has character '{character}', but only letters, digits and '-' are allowed")]
+    BadCharacter { user: String, character: char },
+    #[error("GitHub user name '{0}' can't start or end with '-' or have two in a row")]
+    BadHyphen(String),
+}
+
+/// A syntactically valid GitHub user name. Whether the account exists is not checked.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct GithubUser(String);
+
+impl GithubUser {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for GithubUser {
+    type Error = GithubUserParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let user = value.trim();
+        let length = user.chars().count();
+        if length == 0 || length > MAX_LENGTH {
+            return Err(GithubUserParsingError::BadLength(length));
+        }
+        if let Some(character) = user.chars().find(|c| !c.is_ascii_alphanumeric() && *c != '-') {
+            return Err(GithubUserParsingError::BadCharacter { user: user.to_owned(), character });
+        }
+        if user.starts_with('-') || user.ends_with('-') || user.contains("--") {
+            return Err(GithubUserParsingError::BadHyphen(user.to_owned()));
+        }
+        Ok(Self(user.to_owned()))
+    }
+}
+
+impl FromStr for GithubUser {
+    type Err = GithubUserParsingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_owned())
+    }
+}
+
+impl Display for GithubUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<GithubUser> for String {
+    fn from(value: GithubUser) -> Self {
+        value.0
+    }
+}
+
+#[test]
+fn test_github_user_parsing() {
+    assert_eq!(GithubUser::from_str(" constantinpape ").unwrap().as_str(), "constantinpape");
+    assert!(GithubUser::from_str("octo-cat-42").is_ok());
+    assert!(GithubUser::from_str(&"a".repeat(MAX_LENGTH)).is_ok());
+
+    assert_eq!(GithubUser::from_str(""), Err(GithubUserParsingError::BadLength(0)));
+    assert_eq!(GithubUser::from_str(&"a".repeat(MAX_LENGTH + 1)), Err(GithubUserParsingError::BadLength(MAX_LENGTH + 1)));
+    assert!(matches!(
+        GithubUser::from_str("john_doe"),
+        Err(GithubUserParsingError::BadCharacter { character: '_', .. })
+    ));
+    for bad in ["-octocat", "octocat-", "octo--cat"] {
+        assert!(matches!(GithubUser::from_str(bad), Err(GithubUserParsingError::BadHyphen(_))), "{bad}");
+    }
+}
@@ -1,9 +1,13 @@
+use unicode_segmentation::UnicodeSegmentation;
+
 use super::file_reference::FileReference;
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum IconParsingError {
     #[error("Not emoji: '{0}'")]
     NotEmoji(String),
+    #[error("Icon must be one or two emoji, found {graphemes} in '{value}'")]
+    BadEmojiCount { value: String, graphemes: usize },
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -25,15 +29,51 @@ pub struct EmojiIcon(String);
 
 impl TryFrom<String> for EmojiIcon {
     type Error = IconParsingError;
-    //FIXME: check that characters/glyphs,graphemes/whatever are emoji
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        if !(1..=2).contains(&value.chars().count()) {
+        let graphemes = value.graphemes(true).count();
+        if !(1..=2).contains(&graphemes) {
+            return Err(IconParsingError::BadEmojiCount { value, graphemes });
+        }
+        if !value.graphemes(true).all(is_emoji_grapheme) {
             return Err(IconParsingError::NotEmoji(value));
         }
         return Ok(Self(value));
     }
 }
 
+/// Whether `c` is a pictograph, in the unicode blocks where emoji are
+fn is_pictograph(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // pictographs, emoticons, transport, flags (regional indicators), etc
+            | 0x2600..=0x27BF // miscellaneous symbols and dingbats
+            | 0x2300..=0x23FF // miscellaneous technical, e.g. ⌚ and ⏰
+            | 0x2B00..=0x2BFF // e.g. ⭐ and ⬆
+            | 0x2190..=0x21FF // arrows
+            | 0x25A0..=0x25FF // geometric shapes
+            | 0x00A9 | 0x00AE | 0x203C | 0x2049 | 0x2122 | 0x2139 | 0x24C2 | 0x3030 | 0x303D | 0x3297 | 0x3299
+    )
+}
+
+/// Whether `c` changes the look of a pictograph: joiners, variation selectors, keycaps and tags
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0x200D | 0xFE0E | 0xFE0F | 0x20E3 | 0xE0020..=0xE007F)
+}
+
+/// Whether `grapheme` is a single emoji, possibly made of several code points, like 👩‍🔬 or 1️⃣
+fn is_emoji_grapheme(grapheme: &str) -> bool {
+    let is_keycap = grapheme.ends_with('\u{20E3}');
+    let mut has_pictograph = false;
+    for c in grapheme.chars() {
+        if is_pictograph(c) || (is_keycap && matches!(c, '0'..='9' | '#' | '*')) {
+            has_pictograph = true;
+        } else if !is_emoji_modifier(c) {
+            return false;
+        }
+    }
+    has_pictograph
+}
+
 impl TryFrom<String> for Icon {
     type Error = IconParsingError;
     fn try_from(value: String) -> Result<Self, Self::Error> {
@@ -46,3 +86,18 @@ impl From<EmojiIcon> for String {
         return value.0;
     }
 }
+
+#[test]
+fn test_emoji_icon_validation() {
+    for valid in ["🦠", "🔬🦠", "👩‍🔬", "⭐", "❤️", "1️⃣", "🇩🇪", "👍🏽"] {
+        assert!(EmojiIcon::try_from(valid.to_owned()).is_ok(), "{valid} should be valid");
+    }
+    for invalid in ["a", "ab", "é", "🦠a"] {
+        assert!(matches!(EmojiIcon::try_from(invalid.to_owned()), Err(IconParsingError::NotEmoji(_))), "{invalid}");
+    }
+    assert!(matches!(
+        EmojiIcon::try_from("🔬🦠🧫".to_owned()),
+        Err(IconParsingError::BadEmojiCount { graphemes: 3, .. })
+    ));
+    assert!(EmojiIcon::try_from(String::new()).is_err());
+}
@@ -1,6 +1,6 @@
 use std::{borrow::Borrow, error::Error, fmt::Display};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 const PYTHON_KEYWORDS: [&'static str; 35] = [
     "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
@@ -8,10 +8,22 @@ const PYTHON_KEYWORDS: [&'static str; 35] = [
     "return", "try", "while", "with", "yield",
 ];
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// A name that could be used as a python identifier, as the spec requires of ids and channel names
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(transparent)]
 pub struct Identifier<T>(T);
 
+// Not derived, so that parsed identifiers go through the same checks as `try_from`
+impl<'de, T> Deserialize<'de> for Identifier<T>
+where
+    Self: TryFrom<String, Error = IdentifierParsingError>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::try_from(raw).map_err(serde::de::Error::custom)
+    }
+}
+
 impl Identifier<String> {
     pub fn appended_with(&self, suffix: &str) -> Self {
         return Self(format!("{}{suffix}", self.0));
@@ -33,12 +45,12 @@ impl<T: Display> Display for Identifier<T> {
 #[derive(thiserror::Error, Debug)]
 pub enum IdentifierParsingError {
     #[error("Bad identifier: {source}")]
-    BadString { source: Box<dyn Error + 'static> },
+    BadString { source: Box<dyn Error + Send + Sync + 'static> },
     #[error("Empty string can't be an identifier")]
     EmptyString,
     #[error("Expected first character to be alphabetic or _: '{value}'")]
     MustStartWithAlphabeticalOrUnderscore { value: String },
-    #[error("Identifiers cannot contain whitespace: '{value}'")]
+    #[error("Identifiers can only contain letters, digits and '_': '{value}'")]
     ContainsbadCharacter { value: String, position: usize },
     #[error("Value '{value}' is a python keyword")]
     IsPythonKeyword { value: String },
@@ -47,7 +59,7 @@ pub enum IdentifierParsingError {
 impl<T, E> TryFrom<String> for Identifier<T>
 where
     T: Borrow<str>,
-    E: Error + 'static,
+    E: Error + Send + Sync + 'static,
     T: TryFrom<String, Error = E>,
 {
     type Error = IdentifierParsingError;
@@ -84,3 +96,17 @@ impl<T: Into<String>> From<Identifier<T>> for String {
         return value.0.into();
     }
 }
+
+#[test]
+fn test_identifiers_are_validated_when_parsed() {
+    use crate::rdf::model::{axes::AxisId, tensor_id::TensorId};
+
+    assert!(serde_yaml::from_str::<TensorId>("raw_image").is_ok());
+    for bad in ["raw image", "Raw", "1raw", "lambda", "raw-image", "''"] {
+        assert!(serde_yaml::from_str::<TensorId>(bad).is_err(), "{bad} should not be a tensor id");
+    }
+    assert!(serde_yaml::from_str::<AxisId>("channel").is_ok());
+    assert!(serde_yaml::from_str::<AxisId>("a very long axis id").is_err());
+    assert!(serde_yaml::from_str::<AxisId>("y.x").is_err());
+    assert!(serde_yaml::from_str::<Identifier<String>>("channel 0").is_err());
+}
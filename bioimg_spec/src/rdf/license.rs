@@ -1708,3 +1708,17 @@ pub enum SpdxLicense {
     #[strum(to_string = "ZPL-2.1")]
     ZPL_2_1,
 }
+
+impl SpdxLicense {
+    /// The licenses most resources on bioimage.io are published under
+    pub const COMMON: [SpdxLicense; 8] = [
+        Self::CC_BY_4_0,
+        Self::MIT,
+        Self::Apache_2_0,
+        Self::BSD_3_Clause,
+        Self::CC0_1_0,
+        Self::CC_BY_SA_4_0,
+        Self::GPL_3_0_only,
+        Self::MPL_2_0,
+    ];
+}
@@ -1,18 +1,30 @@
 use std::{borrow::Borrow, error::Error, ops::Deref, fmt::Display};
 
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 
 #[derive(thiserror::Error, Debug)]
 pub enum LowercaseParsingError{
     #[error("{source}")]
-    BadString{source: Box<dyn Error + 'static>},
+    BadString{source: Box<dyn Error + Send + Sync + 'static>},
     #[error("Character at {idx} is not lowercase: {value}")]
     IsNotLowercase{value: String, idx: usize}
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(transparent)]
 pub struct Lowercase<T>(T);
 
+// Not derived, so that parsed values go through the same checks as `try_from`
+impl<'de, T> Deserialize<'de> for Lowercase<T>
+where
+    Self: TryFrom<String, Error = LowercaseParsingError>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::try_from(raw).map_err(serde::de::Error::custom)
+    }
+}
+
 impl<T: Borrow<str>> Borrow<str> for Lowercase<T>{
     fn borrow(&self) -> &str {
         return self.0.borrow()
@@ -34,7 +46,7 @@ impl<T: Borrow<str>> Deref for Lowercase<T>{
 
 impl<T, E> TryFrom<String> for Lowercase<T>
 where
-    E: Error + 'static,
+    E: Error + Send + Sync + 'static,
     T: TryFrom<String, Error = E>,
     T: Borrow<str>,
 {
@@ -1,12 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use super::{bounded_string::BoundedString, orcid::Orcid, slashless_string::SlashlessString};
+use super::{bounded_string::BoundedString, github_user::GithubUser, orcid::Orcid, slashless_string::SlashlessString};
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub struct Maintainer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub affiliation: Option<BoundedString<1, 1023>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub email: Option<BoundedString<1, 1023>>, //FIXME
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub orcid: Option<Orcid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<SlashlessString<1, 1023>>,
-    pub github_user: BoundedString<1, 1023>, //FIXME validate this somehow
+    pub github_user: GithubUser,
 }
@@ -4,17 +4,25 @@ use url::Url;
 use crate::rdf::bounded_string::BoundedString;
 
 use self::{
-    attachment::Attachments, author::Author, badge::Badge, cite_entry::CiteEntry, file_reference::FileReference,
-    maintainer::Maintainer,
+    attachment::Attachments, author::Author2, badge::Badge, cite_entry::CiteEntry2, maintainer::Maintainer,
+    version_history::VersionHistory,
 };
 
+pub mod application;
 pub mod attachment;
 pub mod author;
 pub mod badge;
 pub mod bounded_string;
+pub mod canonical_yaml;
 pub mod cite_entry;
 pub mod clamped;
+pub mod collection;
+pub mod dataset;
+pub mod diff;
+pub mod doi;
+pub mod field_help;
 pub mod file_reference;
+pub mod github_user;
 pub mod icon;
 pub mod identifier;
 pub mod license;
@@ -23,15 +31,33 @@ pub mod lowercase;
 pub mod maintainer;
 pub mod model;
 pub mod non_empty_list;
+pub mod notebook;
 pub mod orcid;
+pub mod partner_config;
+pub mod person;
+pub mod resource_description;
+pub mod resource_id;
+pub mod sha256;
 pub mod si_units;
 pub mod slashless_string;
+pub mod spec_error;
+pub mod validation;
 pub mod version;
+pub mod version_history;
 
+pub use diff::{Change, Changelist};
+pub use doi::Doi;
+pub use file_reference::{FileDescr, FileReference};
+pub use github_user::GithubUser;
 pub use icon::{EmojiIcon, Icon, IconParsingError};
 pub use license::SpdxLicense;
+pub use sha256::Sha256;
+pub use spec_error::SpecError;
+pub use validation::{Severity, ValidationEntry, ValidationReport};
 pub use version::Version;
 pub use literal::LiteralInt;
+pub use orcid::Orcid;
+pub use resource_id::{LinkedResource, ResourceId};
 pub use identifier::Identifier;
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -40,9 +66,10 @@ pub struct Rdf {
     pub description: BoundedString<1, 1023>, //FIXME: double chekc lengrhs
     pub name: BoundedString<1, 1023>,
     pub attachments: Option<Attachments>,
-    pub authors: Option<Vec<Author>>,
+    pub authors: Option<Vec<Author2>>,
     pub badges: Option<Vec<Badge>>,
-    pub cite: Option<Vec<CiteEntry>>,
+    pub cite: Option<Vec<CiteEntry2>>,
+    pub config: Option<serde_yaml::Mapping>,
     pub covers: Option<Vec<FileReference>>,
     pub documentation: Option<FileReference>,
     pub download_url: Option<FileReference>,
@@ -50,12 +77,44 @@ pub struct Rdf {
     pub icon: Option<BoundedString<1, 1023>>,
     pub id: Option<BoundedString<1, 1023>>,
     pub license: Option<SpdxLicense>,
-    pub links: Option<Vec<FileReference>>,
+    pub links: Option<Vec<ResourceId>>,
     pub maintainers: Option<Vec<Maintainer>>,
     pub rdf_source: Option<FileReference>,
     pub source: Option<FileReference>,
     pub tags: Option<Vec<BoundedString<1, 1023>>>,
     pub version: Option<Version>,
+    #[serde(flatten)]
+    pub history: VersionHistory,
+}
+
+impl Rdf {
+    /// A description of format 0.2.3 with nothing but a name and description
+    pub fn new(name: BoundedString<1, 1023>, description: BoundedString<1, 1023>) -> Self {
+        Self {
+            format_version: Version { major: 0, minor: 2, patch: 3 },
+            description,
+            name,
+            attachments: None,
+            authors: None,
+            badges: None,
+            cite: None,
+            config: None,
+            covers: None,
+            documentation: None,
+            download_url: None,
+            git_repo: None,
+            icon: None,
+            id: None,
+            license: None,
+            links: None,
+            maintainers: None,
+            rdf_source: None,
+            source: None,
+            tags: None,
+            version: None,
+            history: Default::default(),
+        }
+    }
 }
 
 #[test]
@@ -73,7 +132,7 @@ fn test_model_rdf_serde() {
                 "affiliation": "Some University",
                 "email": "john.doe@some_university.com" ,
                 "github_user": "john_doe",
-                "orcid": "111-111-111", //FIXME
+                "orcid": "0000-0002-8205-121X",
             },
         ],
         "badges": [
@@ -86,7 +145,7 @@ fn test_model_rdf_serde() {
         "cite": [
             {
                 "text": "Plz cite eme",
-                "doi": "blabla",
+                "doi": "10.1000/xyz123",
                 "url": "https://blas/bla",
 
             }
@@ -117,23 +176,24 @@ fn test_model_rdf_serde() {
         name: "my cool model".try_into().unwrap(),
 
         attachments: None,
-        authors: Some(vec![Author {
+        authors: Some(vec![Author2 {
             name: "John Doe".try_into().unwrap(),
-            affiliation: "Some University".try_into().unwrap(),
-            email: "john.doe@some_university.com".try_into().unwrap(),
-            github_user: "john_doe".try_into().unwrap(),
-            orcid: "0000-0002-8205-121X".to_owned().try_into().unwrap(), //FIXME
+            affiliation: Some("Some University".try_into().unwrap()),
+            email: Some("john.doe@some_university.com".try_into().unwrap()),
+            github_user: Some("john_doe".try_into().unwrap()),
+            orcid: Some("0000-0002-8205-121X".to_owned().try_into().unwrap()), //FIXME
         }]),
         badges: Some(vec![Badge {
             label: "x".try_into().unwrap(),
             icon: Url::parse("http://some.icon/bla").unwrap().into(),
             url: Url::parse("http://some.url/to/icon").unwrap().into(),
         }]),
-        cite: Some(vec![CiteEntry {
+        cite: Some(vec![CiteEntry2 {
             text: "Plz cite eme".try_into().unwrap(),
-            doi: "blabla".try_into().unwrap(),
-            url: Url::parse("https://blas/bla").unwrap(),
+            doi: Some("10.1000/xyz123".parse().unwrap()),
+            url: Some(Url::parse("https://blas/bla").unwrap()),
         }]),
+        config: None,
         covers: None,
         documentation: Some(Url::parse("http://example.com/docs").unwrap().into()),
         download_url: Some(Url::parse("http://blas.blus/blis").unwrap().into()),
@@ -151,6 +211,7 @@ fn test_model_rdf_serde() {
             minor: 5,
             patch: 6,
         }),
+        history: Default::default(),
     };
 
     assert_eq!(parsed_rdf, expected_rdf);
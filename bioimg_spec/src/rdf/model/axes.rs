@@ -8,11 +8,17 @@ use super::{
 };
 use crate::rdf::{bounded_string::BoundedString, identifier::Identifier, literal::LiteralInt, lowercase::Lowercase};
 
-pub type AxisId = Lowercase<BoundedString<1, { 16 - 1 }>>;
+pub type AxisId = Lowercase<Identifier<BoundedString<1, { 16 - 1 }>>>;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct AxisScale(f32);
 
+impl AxisScale {
+    pub fn get(self) -> f32 {
+        self.0
+    }
+}
+
 impl Default for AxisScale {
     fn default() -> Self {
         Self(1.0)
@@ -36,7 +42,7 @@ impl TryFrom<f32> for AxisScale {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct BatchAxis {
     #[serde(default = "_default_batch_axis_id")]
     pub id: AxisId,
@@ -46,19 +52,80 @@ pub struct BatchAxis {
     pub size: Option<LiteralInt<1>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(thiserror::Error, PartialEq, Eq, Clone, Debug)]
+pub enum ChannelAxisParsingError {
+    #[error("Channel axis has no channel names")]
+    NoChannelNames,
+    #[error("Channel axis has size {size}, but {names} channel name(s)")]
+    SizeMismatch { size: usize, names: usize },
+}
+
+/// The size of a channel axis is the number of its channel names; a `size` field, if given, must agree with it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawChannelAxis")]
 pub struct ChannelAxis {
     #[serde(default = "_default_channel_axis_id")]
     pub id: AxisId,
     #[serde(default)]
     pub description: BoundedString<0, { 128 - 1 }>,
-    // pub size: FixedAxisSize,
     pub channel_names: Vec<Identifier<String>>,
     // #[serde(default)]
     // pub channel_names: ChannelNames, // FIXME: do we need to handle "#channel_names" ?
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Deserialize)]
+struct RawChannelAxis {
+    #[serde(default = "_default_channel_axis_id")]
+    id: AxisId,
+    #[serde(default)]
+    description: BoundedString<0, { 128 - 1 }>,
+    #[serde(default)]
+    size: Option<FixedAxisSize>,
+    channel_names: Vec<Identifier<String>>,
+}
+
+impl TryFrom<RawChannelAxis> for ChannelAxis {
+    type Error = ChannelAxisParsingError;
+    fn try_from(raw: RawChannelAxis) -> Result<Self, Self::Error> {
+        Self::new(raw.id, raw.description, raw.channel_names, raw.size.map(FixedAxisSize::get))
+    }
+}
+
+impl ChannelAxis {
+    pub fn new(
+        id: AxisId,
+        description: BoundedString<0, { 128 - 1 }>,
+        channel_names: Vec<Identifier<String>>,
+        size: Option<usize>,
+    ) -> Result<Self, ChannelAxisParsingError> {
+        Self::check_size(&channel_names, size)?;
+        Ok(Self {
+            id,
+            description,
+            channel_names,
+        })
+    }
+
+    /// Checks that there are channel names, and as many as `size` if that is given
+    pub fn check_size(channel_names: &[Identifier<String>], size: Option<usize>) -> Result<(), ChannelAxisParsingError> {
+        if channel_names.is_empty() {
+            return Err(ChannelAxisParsingError::NoChannelNames);
+        }
+        match size {
+            Some(size) if size != channel_names.len() => Err(ChannelAxisParsingError::SizeMismatch {
+                size,
+                names: channel_names.len(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.channel_names.len()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct IndexAxis {
     #[serde(default = "_default_index_axis_id")]
     pub id: AxisId,
@@ -67,7 +134,7 @@ pub struct IndexAxis {
     pub size: AnyAxisSize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TimeInputAxis {
     #[serde(default = "_default_time_axis_id")]
     pub id: AxisId,
@@ -80,7 +147,7 @@ pub struct TimeInputAxis {
     pub size: AnyAxisSize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TimeOutputAxis {
     #[serde(flatten)]
     pub base: TimeInputAxis,
@@ -88,7 +155,7 @@ pub struct TimeOutputAxis {
     pub halo: usize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SpaceInputAxis {
     #[serde(default = "_default_space_axis_id")]
     pub id: AxisId,
@@ -101,7 +168,7 @@ pub struct SpaceInputAxis {
     pub size: AnyAxisSize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SpaceOutputAxis {
     #[serde(flatten)]
     pub base: SpaceInputAxis,
@@ -109,7 +176,7 @@ pub struct SpaceOutputAxis {
     pub halo: usize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type")]
 pub enum InputAxis {
     #[serde(rename = "batch")]
@@ -124,7 +191,7 @@ pub enum InputAxis {
     Space(SpaceInputAxis),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type")]
 pub enum OutputAxis {
     #[serde(rename = "batch")]
@@ -139,6 +206,26 @@ pub enum OutputAxis {
     Space(SpaceOutputAxis),
 }
 
+impl InputAxis {
+    /// The number of channels, if this is the channel axis
+    pub fn channel_count(&self) -> Option<usize> {
+        match self {
+            Self::Channel(axis) => Some(axis.size()),
+            _ => None,
+        }
+    }
+}
+
+impl OutputAxis {
+    /// The number of channels, if this is the channel axis
+    pub fn channel_count(&self) -> Option<usize> {
+        match self {
+            Self::Channel(axis) => Some(axis.size()),
+            _ => None,
+        }
+    }
+}
+
 fn _default_batch_axis_id() -> AxisId {
     String::from("batch").try_into().unwrap()
 }
@@ -157,3 +244,15 @@ fn _default_space_axis_id() -> AxisId {
 fn _default_axis_scale() -> f32 {
     1.0
 }
+
+#[test]
+fn test_channel_axis_size() {
+    let parse = |raw: &str| serde_yaml::from_str::<ChannelAxis>(raw);
+    assert_eq!(parse("{channel_names: [r, g, b]}").unwrap().size(), 3);
+    assert_eq!(parse("{channel_names: [r, g, b], size: 3}").unwrap().size(), 3);
+    assert!(parse("{channel_names: [r, g, b], size: 2}")
+        .unwrap_err()
+        .to_string()
+        .contains("size 2, but 3 channel name(s)"));
+    assert!(parse("{channel_names: []}").is_err());
+}
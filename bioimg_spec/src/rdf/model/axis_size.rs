@@ -6,22 +6,80 @@ use super::{axes::AxisId, tensor_id::TensorId};
 
 pub type FixedAxisSize = NonZeroUsize;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct AxisSizeReference {
     pub tensor_id: TensorId,
     pub axis_id: AxisId,
+    #[serde(default)]
     pub offset: usize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ParameterizedAxisSize {
     pub min: NonZeroUsize,
     pub step: NonZeroUsize,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl ParameterizedAxisSize {
+    /// The `n`th allowed size, `min + n * step`
+    pub fn resolve(&self, n: usize) -> usize {
+        self.min.get() + n * self.step.get()
+    }
+
+    pub fn is_valid(&self, size: usize) -> bool {
+        size >= self.min.get() && (size - self.min.get()).is_multiple_of(self.step.get())
+    }
+
+    /// The smallest allowed size that is at least `target`
+    pub fn smallest_valid_at_least(&self, target: usize) -> usize {
+        let (min, step) = (self.min.get(), self.step.get());
+        if target <= min {
+            return min;
+        }
+        min + (target - min).div_ceil(step) * step
+    }
+}
+
+/// Written as a plain number, a `{min, step}` mapping or a `{tensor_id, axis_id, offset}` mapping
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
 pub enum AnyAxisSize {
     Fixed(FixedAxisSize),
     Reference(AxisSizeReference),
     Parameterized(ParameterizedAxisSize),
 }
+
+impl AnyAxisSize {
+    /// The size for the parameter `n` of a parameterized size. References are `None`, since their size depends on
+    /// another axis; see [ModelRdf::resolve_axis_size](super::ModelRdf::resolve_axis_size) for those.
+    pub fn resolve(&self, n: usize) -> Option<usize> {
+        match self {
+            Self::Fixed(size) => Some(size.get()),
+            Self::Parameterized(size) => Some(size.resolve(n)),
+            Self::Reference(_) => None,
+        }
+    }
+
+    /// The smallest size that is allowed and at least `target`. A fixed size is picked even if it is smaller.
+    pub fn smallest_valid_at_least(&self, target: usize) -> Option<usize> {
+        match self {
+            Self::Fixed(size) => Some(size.get()),
+            Self::Parameterized(size) => Some(size.smallest_valid_at_least(target)),
+            Self::Reference(_) => None,
+        }
+    }
+}
+
+#[test]
+fn test_parameterized_sizes() {
+    let size = ParameterizedAxisSize {
+        min: NonZeroUsize::new(16).unwrap(),
+        step: NonZeroUsize::new(8).unwrap(),
+    };
+    assert_eq!((size.resolve(0), size.resolve(3)), (16, 40));
+    assert!(size.is_valid(24) && !size.is_valid(20) && !size.is_valid(8));
+    assert_eq!([1, 16, 17, 24, 25].map(|target| size.smallest_valid_at_least(target)), [16, 16, 24, 24, 32]);
+
+    let fixed = AnyAxisSize::Fixed(NonZeroUsize::new(5).unwrap());
+    assert_eq!((fixed.resolve(7), fixed.smallest_valid_at_least(100)), (Some(5), Some(5)));
+}
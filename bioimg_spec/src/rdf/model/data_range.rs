@@ -1,4 +1,72 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
-pub struct DataRange(f64, f64);
+use super::data_type::DataType;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum DataRangeError {
+    #[error("Data range bounds can't be NaN")]
+    NanBound,
+    #[error("Data range minimum {min} is bigger than its maximum {max}")]
+    Reversed { min: f64, max: f64 },
+    #[error("Data range bound {bound} is outside of what '{data_type}' can hold ({lowest} to {highest})")]
+    OutOfRange { data_type: DataType, bound: f64, lowest: f64, highest: f64 },
+}
+
+/// The smallest and largest values a tensor may contain, where a missing or infinite bound means
+/// the tensor is unbounded on that side
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+pub struct DataRange(pub Option<f64>, pub Option<f64>);
+
+impl DataRange {
+    pub fn min(&self) -> Option<f64> {
+        self.0
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.1
+    }
+
+    /// Checks that the bounds are in order and that every finite bound can be held by `data_type`
+    pub fn validate_for(&self, data_type: DataType) -> Result<(), DataRangeError> {
+        let bounds = [self.0, self.1];
+        if bounds.iter().flatten().any(|bound| bound.is_nan()) {
+            return Err(DataRangeError::NanBound);
+        }
+        if let (Some(min), Some(max)) = (self.0, self.1) {
+            if min > max {
+                return Err(DataRangeError::Reversed { min, max });
+            }
+        }
+        let (lowest, highest) = data_type.value_range();
+        let out_of_range = bounds
+            .into_iter()
+            .flatten()
+            .find(|bound| bound.is_finite() && (*bound < lowest || *bound > highest));
+        match out_of_range {
+            Some(bound) => Err(DataRangeError::OutOfRange { data_type, bound, lowest, highest }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_data_range_validation() {
+    let range = |min: Option<f64>, max: Option<f64>| DataRange(min, max);
+
+    assert_eq!(range(Some(0.0), Some(255.0)).validate_for(DataType::Uint8), Ok(()));
+    assert_eq!(range(Some(f64::NEG_INFINITY), Some(f64::INFINITY)).validate_for(DataType::Uint8), Ok(()));
+    assert_eq!(range(None, Some(-3.5)).validate_for(DataType::Float32), Ok(()));
+    assert!(matches!(
+        range(Some(-1.0), None).validate_for(DataType::Uint8),
+        Err(DataRangeError::OutOfRange { bound, .. }) if bound == -1.0
+    ));
+    assert!(range(Some(0.0), Some(2.0)).validate_for(DataType::Bool).is_err());
+    assert_eq!(range(Some(f64::NAN), None).validate_for(DataType::Int32), Err(DataRangeError::NanBound));
+    assert_eq!(
+        range(Some(1.0), Some(0.0)).validate_for(DataType::Float64),
+        Err(DataRangeError::Reversed { min: 1.0, max: 0.0 })
+    );
+
+    let parsed: DataRange = serde_yaml::from_str("[0, null]").unwrap();
+    assert_eq!(parsed, range(Some(0.0), None));
+}
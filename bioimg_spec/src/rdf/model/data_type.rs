@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, strum::Display, strum::VariantArray, strum::VariantNames,
+)]
+#[strum(serialize_all = "lowercase")]
 pub enum DataType {
     #[serde(rename = "bool")]
     Bool,
+    #[default]
     #[serde(rename = "float32")]
     Float32,
     #[serde(rename = "float64")]
@@ -25,3 +29,22 @@ pub enum DataType {
     #[serde(rename = "int64")]
     Int64,
 }
+
+impl DataType {
+    /// The lowest and highest values this type can hold. The 64 bit integer limits are rounded to the nearest f64.
+    pub fn value_range(&self) -> (f64, f64) {
+        match self {
+            Self::Bool => (0.0, 1.0),
+            Self::Float32 => (f32::MIN.into(), f32::MAX.into()),
+            Self::Float64 => (f64::MIN, f64::MAX),
+            Self::Uint8 => (u8::MIN.into(), u8::MAX.into()),
+            Self::Uint16 => (u16::MIN.into(), u16::MAX.into()),
+            Self::Uint32 => (u32::MIN.into(), u32::MAX.into()),
+            Self::Uint64 => (0.0, u64::MAX as f64),
+            Self::Int8 => (i8::MIN.into(), i8::MAX.into()),
+            Self::Int16 => (i16::MIN.into(), i16::MAX.into()),
+            Self::Int32 => (i32::MIN.into(), i32::MAX.into()),
+            Self::Int64 => (i64::MIN as f64, i64::MAX as f64),
+        }
+    }
+}
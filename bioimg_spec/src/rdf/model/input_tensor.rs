@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
 
-use crate::rdf::{bounded_string::BoundedString, file_reference::FileReference, non_empty_list::NonEmptyList};
+use crate::rdf::{bounded_string::BoundedString, file_reference::FileDescr, non_empty_list::NonEmptyList, spec_error};
 
-use super::{axes::InputAxis, data_range::DataRange, data_type::DataType, preprocessing::Preprocessing, tensor_id::TensorId};
+use super::{
+    axes::InputAxis,
+    data_range::DataRange,
+    data_type::DataType,
+    legacy::{check_input_tensor_fields, LegacyFieldError},
+    preprocessing::Preprocessing,
+    tensor_data_descr::{TensorData, TensorDataParsingError},
+    tensor_id::TensorId,
+};
 
 pub struct InputTensorDescr {
     // pub axes: AxisSequence,
@@ -13,23 +21,102 @@ pub struct InputTensorDescr {
     pub preprocessing: Vec<Preprocessing>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum InputTensorParsingError {
+    #[error("{0}")]
+    LegacyField(#[from] LegacyFieldError),
+    #[error("'scale_mean_variance' can only be used as postprocessing")]
+    PostprocessingOnlyStep,
+    #[error("{0}")]
+    BadData(#[from] TensorDataParsingError),
+    #[error("{0}")]
+    BadYaml(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(try_from = "serde_yaml::Value")]
 pub struct InputTensorDescr2 {
     pub id: TensorId,
     #[serde(default = "_default_description")]
     pub description: BoundedString<0, 128>,
     pub axes: NonEmptyList<InputAxis>,
-    pub test_tensor: FileReference,
+    pub test_tensor: FileDescr,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_tensor: Option<FileDescr>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preprocessing: Vec<Preprocessing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<TensorData>,
+}
+
+#[derive(Deserialize)]
+struct RawInputTensorDescr2 {
+    id: TensorId,
+    #[serde(default = "_default_description")]
+    description: BoundedString<0, 128>,
+    axes: NonEmptyList<InputAxis>,
+    test_tensor: FileDescr,
+    #[serde(default)]
+    sample_tensor: Option<FileDescr>,
+    #[serde(default)]
+    optional: bool,
     #[serde(default)]
-    pub sample_tensor: Option<FileReference>,
-    // #[serde(default = "_default_data_description")]
-    // pub data: SingleOrMultiple,
-    // pub data_type: DataType,
-    // #[serde(default = "_default_input_name")]
-    // pub name: PeggedString<1, 1023>,
-    // pub shape: Vec<usize>,
-    // pub data_range: DataRange,
-    // pub preprocessing: Vec<Preprocessing>,
+    preprocessing: Vec<Preprocessing>,
+    #[serde(default)]
+    data: Option<TensorData>,
+}
+
+impl TryFrom<serde_yaml::Value> for InputTensorDescr2 {
+    type Error = InputTensorParsingError;
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        // 0.4 descriptions would otherwise just fail with a missing or mistyped field
+        check_input_tensor_fields(&value)?;
+        // would otherwise be reported as an unknown variant
+        let is_postprocessing_only =
+            |step: &serde_yaml::Value| step.get("id").and_then(|id| id.as_str()) == Some("scale_mean_variance");
+        let steps = value.get("preprocessing").and_then(|steps| steps.as_sequence());
+        if steps.is_some_and(|steps| steps.iter().any(is_postprocessing_only)) {
+            return Err(InputTensorParsingError::PostprocessingOnlyStep);
+        }
+        let raw: RawInputTensorDescr2 =
+            spec_error::from_value(value).map_err(|err| InputTensorParsingError::BadYaml(err.to_string()))?;
+        let descr = Self {
+            id: raw.id,
+            description: raw.description,
+            axes: raw.axes,
+            test_tensor: raw.test_tensor,
+            sample_tensor: raw.sample_tensor,
+            optional: raw.optional,
+            preprocessing: raw.preprocessing,
+            data: raw.data,
+        };
+        descr.check()?;
+        Ok(descr)
+    }
+}
+
+impl InputTensorDescr2 {
+    /// Checks that the data matches the channels. This is done when parsing, and should be done
+    /// again for tensors put together by hand.
+    pub fn check(&self) -> Result<(), InputTensorParsingError> {
+        if let Some(data) = &self.data {
+            data.check_channel_count(self.axes.iter().find_map(InputAxis::channel_count))?;
+        }
+        Ok(())
+    }
+}
+
+impl InputAxis {
+    /// Physical size of one step along space and time axes, 1 for the others
+    pub fn scale(&self) -> f32 {
+        match self {
+            Self::Space(axis) => axis.scale.get(),
+            Self::Time(axis) => axis.scale.get(),
+            Self::Batch(_) | Self::Channel(_) | Self::Index(_) => 1.0,
+        }
+    }
 }
 
 fn _default_description() -> BoundedString<0, 128> {
@@ -0,0 +1,148 @@
+use serde_yaml::Value;
+
+use super::{axes::InputAxis, AnyAxisSize, ParameterizedAxisSize};
+
+/// A tensor field of format 0.4 that 0.5 replaced, together with the 0.5 axes it migrates to,
+/// when that can be worked out from the 0.4 fields alone
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("'{field}' is a format 0.4 field; in 0.5 {replacement}{}", if .migrated_axes.is_some() {
+    " (this one can be migrated automatically)"
+} else {
+    ""
+})]
+pub struct LegacyFieldError {
+    pub field: &'static str,
+    pub replacement: &'static str,
+    pub migrated_axes: Option<Vec<InputAxis>>,
+}
+
+/// Looks for 0.4-only fields in a raw input tensor description
+pub fn check_input_tensor_fields(raw: &Value) -> Result<(), LegacyFieldError> {
+    let legacy_field = |field, replacement| {
+        Err(LegacyFieldError {
+            field,
+            replacement,
+            migrated_axes: migrate_input_axes(raw),
+        })
+    };
+    if raw.get("shape").is_some() {
+        return legacy_field("shape", "every entry of 'axes' has its own 'size'");
+    }
+    if raw.get("axes").is_some_and(Value::is_string) {
+        return legacy_field(
+            "axes",
+            "'axes' is a list of axis descriptions instead of a string like 'bcyx'",
+        );
+    }
+    if raw.get("halo").is_some() {
+        return Err(LegacyFieldError {
+            field: "halo",
+            replacement: "'halo' is set on the individual axes of output tensors",
+            migrated_axes: None,
+        });
+    }
+    if raw.get("name").is_some() && raw.get("id").is_none() {
+        return legacy_field("name", "tensors are identified by 'id'");
+    }
+    Ok(())
+}
+
+fn shape_sizes(shape: &Value, num_axes: usize) -> Option<Vec<AnyAxisSize>> {
+    let as_sizes =
+        |values: &Value| -> Option<Vec<usize>> { values.as_sequence()?.iter().map(|v| v.as_u64().map(|v| v as usize)).collect() };
+    let sizes = if let Some(fixed) = as_sizes(shape) {
+        fixed
+            .into_iter()
+            .map(|size| Some(AnyAxisSize::Fixed(size.try_into().ok()?)))
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        // a shape that refers to another tensor also scales it, which 0.5 size references don't do
+        let mins = as_sizes(shape.get("min")?)?;
+        let steps = as_sizes(shape.get("step")?)?;
+        if mins.len() != steps.len() {
+            return None;
+        }
+        mins.into_iter()
+            .zip(steps)
+            .map(|(min, step)| {
+                let min = min.try_into().ok()?;
+                Some(match step.try_into() {
+                    Ok(step) => AnyAxisSize::Parameterized(ParameterizedAxisSize { min, step }),
+                    Err(_) => AnyAxisSize::Fixed(min),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?
+    };
+    (sizes.len() == num_axes).then_some(sizes)
+}
+
+/// The 0.5 axes equivalent to the axes string and shape of a 0.4 tensor description
+pub fn migrate_input_axes(raw: &Value) -> Option<Vec<InputAxis>> {
+    let letters = raw.get("axes")?.as_str()?;
+    let sizes = shape_sizes(raw.get("shape")?, letters.chars().count())?;
+    letters
+        .chars()
+        .zip(sizes)
+        .map(|(letter, size)| {
+            let mut axis = serde_yaml::Mapping::new();
+            let axis_type = match letter {
+                'b' => "batch",
+                'c' => "channel",
+                'i' => "index",
+                't' => "time",
+                'x' | 'y' | 'z' => "space",
+                _ => return None,
+            };
+            axis.insert("type".into(), axis_type.into());
+            match (letter, size) {
+                ('b', _) => (),
+                ('c', AnyAxisSize::Fixed(channels)) => {
+                    let names = (0..channels.get()).map(|idx| Value::from(format!("channel{idx}"))).collect();
+                    axis.insert("channel_names".into(), Value::Sequence(names));
+                }
+                ('c', _) => return None,
+                (_, size) => {
+                    axis.insert("size".into(), serde_yaml::to_value(size).ok()?);
+                }
+            }
+            if axis_type == "space" {
+                axis.insert("id".into(), letter.to_string().into());
+            }
+            serde_yaml::from_value(Value::Mapping(axis)).ok()
+        })
+        .collect()
+}
+
+#[test]
+fn test_legacy_input_tensor_fields() {
+    use super::input_tensor::InputTensorDescr2;
+
+    let raw_v04: Value = serde_yaml::from_str(
+        "
+name: raw
+axes: bcyx
+data_type: float32
+shape:
+  min: [1, 1, 64, 64]
+  step: [0, 0, 16, 16]
+test_tensor: test_input.npy
+",
+    )
+    .unwrap();
+    let err = check_input_tensor_fields(&raw_v04).unwrap_err();
+    assert_eq!(err.field, "shape");
+    let migrated = err.migrated_axes.unwrap();
+    assert_eq!(migrated.len(), 4);
+    assert!(matches!(&migrated[1], InputAxis::Channel(channel) if channel.channel_names.len() == 1));
+    assert!(matches!(&migrated[3], InputAxis::Space(space) if matches!(space.size, AnyAxisSize::Parameterized(_))));
+
+    let message = serde_yaml::from_value::<InputTensorDescr2>(raw_v04).unwrap_err().to_string();
+    assert!(message.contains("'shape' is a format 0.4 field"), "{message}");
+
+    let halo: Value = serde_yaml::from_str("{id: raw, halo: [0, 0, 8, 8], axes: [], test_tensor: t.npy}").unwrap();
+    assert!(check_input_tensor_fields(&halo).is_err_and(|err| err.field == "halo" && err.migrated_axes.is_none()));
+
+    let scaled_reference: Value =
+        serde_yaml::from_str("{axes: byx, shape: {reference_tensor: raw, scale: [1, 2, 2], offset: [0, 0, 0]}}").unwrap();
+    assert!(migrate_input_axes(&scaled_reference).is_none());
+}
@@ -1,4 +1,19 @@
-use super::Rdf;
+use std::{borrow::Borrow, collections::HashSet};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{
+    author::Author2, badge::Badge, bounded_string::BoundedString, canonical_yaml, cite_entry::CiteEntry2, diff::Changelist,
+    file_reference::FileDescr, file_reference::FileReference, maintainer::Maintainer, non_empty_list::NonEmptyList,
+    version_history::VersionHistory, spec_error, version::MinimumVersion, LinkedResource, ResourceId, SpdxLicense, SpecError,
+    Version,
+};
+use output_tensor::OutputTensorParsingError;
+use references::{resolve_axis_size, scaled_size_errors, size_reference_errors, ScaledSizeError, SizeReferenceError};
+use references::SizeResolutionError;
+use axes::AxisId;
+use tensor_id::TensorId;
 
 pub mod axes;
 pub mod axis_size;
@@ -6,20 +21,298 @@ pub mod channel_name;
 pub mod data_range;
 pub mod data_type;
 pub mod input_tensor;
+pub mod legacy;
+pub mod output_tensor;
+pub mod postprocessing;
 pub mod preprocessing;
+pub mod references;
+pub mod run_mode;
 pub mod space_unit;
 pub mod tensor_data_descr;
 pub mod tensor_id;
 pub mod time_unit;
+pub mod training_data;
+pub mod v04;
+pub mod validation;
+pub mod weights;
 
 pub use axis_size::{AnyAxisSize, AxisSizeReference, FixedAxisSize, ParameterizedAxisSize};
 pub use space_unit::SpaceUnit;
 pub use time_unit::TimeUnit;
-pub use axes::{BatchAxis, ChannelAxis, IndexAxis, TimeInputAxis, SpaceInputAxis, AxisScale};
+pub use training_data::TrainingData;
+pub use input_tensor::InputTensorDescr2;
+pub use output_tensor::OutputTensorDescr;
+pub use postprocessing::Postprocessing;
+pub use preprocessing::Preprocessing;
+pub use run_mode::RunMode;
+pub use weights::{WeightsDescr, WeightsFormat};
+pub use v04::{ModelRdfV04, ModelUpgradeError};
+
+pub type ModelRdfV05 = ModelRdf;
+pub use axes::{BatchAxis, ChannelAxis, ChannelAxisParsingError, IndexAxis, TimeInputAxis, SpaceInputAxis, AxisScale};
 
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ModelType {
+    #[default]
+    #[serde(rename = "model")]
+    Model,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ModelRdfParsingError {
+    #[error("Only format 0.4 and 0.5 model descriptions are supported, found format {0}")]
+    UnsupportedFormatVersion(Version),
+    #[error("Could not upgrade format 0.4 model description: {0}")]
+    Upgrade(#[from] v04::ModelUpgradeError),
+    #[error("Tensor id '{0}' is used more than once")]
+    DuplicateTensorId(String),
+    #[error("Processing of tensor '{tensor_id}' refers to tensor '{reference}', which doesn't exist")]
+    UnknownReferenceTensor { tensor_id: String, reference: String },
+    #[error("{0}")]
+    BadSizeReference(#[from] SizeReferenceError),
+    #[error("{0}")]
+    BadScaledSize(#[from] ScaledSizeError),
+    #[error("Bad halo in output '{tensor_id}': {source}")]
+    BadHalo { tensor_id: String, source: OutputTensorParsingError },
+    #[error("{0}")]
+    BadYaml(String),
+}
+
+/// Any 0.5 format version; 0.4 models are upgraded, and older ones rejected
+pub type ModelFormatVersion = MinimumVersion<0, 5, 0>;
+
+/// A model description of format 0.5. Format 0.4 descriptions are upgraded when parsed.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(try_from = "serde_yaml::Value")]
 pub struct ModelRdf {
-    pub base: Rdf,
-    // inputs: u32
+    pub format_version: ModelFormatVersion,
+    pub description: BoundedString<1, 1023>,
+    pub name: BoundedString<1, 1023>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<FileDescr>,
+    pub authors: NonEmptyList<Author2>,
+    /// Buttons like "Open in Colab" that partner websites show next to the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub badges: Vec<Badge>,
+    pub cite: NonEmptyList<CiteEntry2>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_yaml::Mapping>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub covers: Vec<FileReference>,
+    pub documentation: FileReference,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_repo: Option<Url>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<BoundedString<1, 1023>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<BoundedString<1, 1023>>,
+    pub license: SpdxLicense,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<ResourceId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maintainers: Vec<Maintainer>,
+    /// The model this one was derived from, e.g. by fine-tuning it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<LinkedResource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<BoundedString<1, 1023>>,
+    #[serde(rename = "type")]
+    pub rdf_type: ModelType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_mode: Option<RunMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub training_data: Option<TrainingData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    pub inputs: NonEmptyList<InputTensorDescr2>,
+    pub outputs: NonEmptyList<OutputTensorDescr>,
+    pub weights: WeightsDescr,
+    /// Who packaged the model for the zoo, if that is not who trained it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packaged_by: Vec<Author2>,
+    #[serde(flatten)]
+    pub history: VersionHistory,
 }
 
-pub struct ModelRdfV05 {}
+#[derive(Deserialize)]
+struct RawModelRdf {
+    format_version: ModelFormatVersion,
+    description: BoundedString<1, 1023>,
+    name: BoundedString<1, 1023>,
+    #[serde(default)]
+    attachments: Vec<FileDescr>,
+    authors: NonEmptyList<Author2>,
+    #[serde(default)]
+    badges: Vec<Badge>,
+    cite: NonEmptyList<CiteEntry2>,
+    #[serde(default)]
+    config: Option<serde_yaml::Mapping>,
+    #[serde(default)]
+    covers: Vec<FileReference>,
+    documentation: FileReference,
+    #[serde(default)]
+    git_repo: Option<Url>,
+    #[serde(default)]
+    icon: Option<BoundedString<1, 1023>>,
+    #[serde(default)]
+    id: Option<BoundedString<1, 1023>>,
+    license: SpdxLicense,
+    #[serde(default)]
+    links: Vec<ResourceId>,
+    #[serde(default)]
+    maintainers: Vec<Maintainer>,
+    #[serde(default)]
+    parent: Option<LinkedResource>,
+    #[serde(default)]
+    tags: Vec<BoundedString<1, 1023>>,
+    #[serde(rename = "type")]
+    rdf_type: ModelType,
+    #[serde(default)]
+    run_mode: Option<RunMode>,
+    #[serde(default)]
+    training_data: Option<TrainingData>,
+    #[serde(default)]
+    version: Option<Version>,
+    inputs: NonEmptyList<InputTensorDescr2>,
+    outputs: NonEmptyList<OutputTensorDescr>,
+    weights: WeightsDescr,
+    #[serde(default)]
+    packaged_by: Vec<Author2>,
+    #[serde(flatten)]
+    history: VersionHistory,
+}
+
+impl TryFrom<serde_yaml::Value> for ModelRdf {
+    type Error = ModelRdfParsingError;
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        // checked up front, since older models would otherwise fail on whichever field changed first
+        let format_version = value.get("format_version").and_then(|v| Version::try_from(v.as_str()?).ok());
+        match format_version {
+            Some(Version { major: 0, minor: 5, .. }) | None => (),
+            Some(Version { major: 0, minor: 4, .. }) => {
+                let v04: v04::ModelRdfV04 =
+                    spec_error::from_value(value).map_err(|err| ModelRdfParsingError::BadYaml(err.to_string()))?;
+                return Ok(Self::try_from(v04)?);
+            }
+            Some(format_version) => return Err(ModelRdfParsingError::UnsupportedFormatVersion(format_version)),
+        }
+        let raw: RawModelRdf =
+            spec_error::from_value(value).map_err(|err| ModelRdfParsingError::BadYaml(err.to_string()))?;
+        let mut tensor_ids = HashSet::new();
+        let all_ids = raw.inputs.iter().map(|t| &t.id).chain(raw.outputs.iter().map(|t| &t.id));
+        for id in all_ids {
+            if !tensor_ids.insert(id.to_string()) {
+                return Err(ModelRdfParsingError::DuplicateTensorId(id.to_string()));
+            }
+        }
+        let input_references = raw
+            .inputs
+            .iter()
+            .flat_map(|t| t.preprocessing.iter().map(|step| (&t.id, step.reference_tensor())));
+        let output_references = raw
+            .outputs
+            .iter()
+            .flat_map(|t| t.postprocessing.iter().map(|step| (&t.id, step.reference_tensor())));
+        for (tensor_id, reference) in input_references.chain(output_references) {
+            match reference {
+                Some(reference) if !tensor_ids.contains(&reference.to_string()) => {
+                    return Err(ModelRdfParsingError::UnknownReferenceTensor {
+                        tensor_id: tensor_id.to_string(),
+                        reference: reference.to_string(),
+                    })
+                }
+                _ => (),
+            }
+        }
+        if let Some(error) = size_reference_errors(raw.inputs.borrow(), raw.outputs.borrow()).into_iter().next() {
+            return Err(error.into());
+        }
+        if let Some(error) = scaled_size_errors(raw.inputs.borrow(), raw.outputs.borrow()).into_iter().next() {
+            return Err(error.into());
+        }
+        for output in raw.outputs.iter() {
+            output.check_halos(raw.inputs.borrow()).map_err(|source| ModelRdfParsingError::BadHalo {
+                tensor_id: output.id.to_string(),
+                source,
+            })?;
+        }
+        Ok(Self {
+            format_version: raw.format_version,
+            description: raw.description,
+            name: raw.name,
+            attachments: raw.attachments,
+            authors: raw.authors,
+            badges: raw.badges,
+            cite: raw.cite,
+            config: raw.config,
+            covers: raw.covers,
+            documentation: raw.documentation,
+            git_repo: raw.git_repo,
+            icon: raw.icon,
+            id: raw.id,
+            license: raw.license,
+            links: raw.links,
+            maintainers: raw.maintainers,
+            parent: raw.parent,
+            tags: raw.tags,
+            rdf_type: raw.rdf_type,
+            run_mode: raw.run_mode,
+            training_data: raw.training_data,
+            version: raw.version,
+            inputs: raw.inputs,
+            outputs: raw.outputs,
+            weights: raw.weights,
+            packaged_by: raw.packaged_by,
+            history: raw.history,
+        })
+    }
+}
+
+impl ModelRdf {
+    /// Size references that don't resolve to the size of an axis, e.g. after a tensor was removed
+    pub fn size_reference_errors(&self) -> Vec<SizeReferenceError> {
+        size_reference_errors(self.inputs.borrow(), self.outputs.borrow())
+    }
+
+    /// Size references that would scale the size they refer to to a fraction, e.g. after a scale was edited
+    pub fn scaled_size_errors(&self) -> Vec<ScaledSizeError> {
+        scaled_size_errors(self.inputs.borrow(), self.outputs.borrow())
+    }
+
+    /// The size of an axis of one of the tensors when every parameterized size is `min + n * step`, following size
+    /// references to the axes they point at
+    pub fn resolve_axis_size(&self, tensor_id: &TensorId, axis_id: &AxisId, n: usize) -> Result<usize, SizeResolutionError> {
+        resolve_axis_size(self.inputs.borrow(), self.outputs.borrow(), tensor_id, axis_id, n)
+    }
+
+    pub fn from_yaml(raw: &str) -> Result<Self, SpecError> {
+        spec_error::from_yaml_str(raw)
+    }
+
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        Ok(canonical_yaml::to_canonical_string(&serde_yaml::to_value(self)?))
+    }
+
+    /// What changed from this description to `newer`
+    pub fn diff(&self, newer: &ModelRdf) -> Result<Changelist, serde_yaml::Error> {
+        Ok(Changelist::between(&serde_yaml::to_value(self)?, &serde_yaml::to_value(newer)?))
+    }
+}
+
+#[test]
+fn test_model_rdf_yaml_roundtrip() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_affable_shark.yaml");
+    let model = ModelRdf::from_yaml(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(model.inputs.iter().count(), 1);
+    assert!(matches!(model.outputs.iter().next().unwrap().axes.iter().nth(2), Some(axes::OutputAxis::Space(space))
+        if matches!(space.base.size, AnyAxisSize::Reference(_)) && space.halo == 16));
+    let formats: Vec<WeightsFormat> = model.weights.entries().into_iter().map(|(format, _)| format).collect();
+    assert_eq!(formats, [WeightsFormat::Onnx, WeightsFormat::PytorchStateDict, WeightsFormat::Torchscript]);
+    let thumbnails = |model: &ModelRdf| model.config.as_ref()?.get("bioimageio")?.get("thumbnails").cloned();
+    assert!(thumbnails(&model).is_some());
+
+    let written = model.to_yaml().unwrap();
+    assert_eq!(ModelRdf::from_yaml(&written).unwrap(), model);
+    assert_eq!(thumbnails(&ModelRdf::from_yaml(&written).unwrap()), thumbnails(&model));
+    assert_eq!(ModelRdf::from_yaml(&written).unwrap().to_yaml().unwrap(), written);
+}
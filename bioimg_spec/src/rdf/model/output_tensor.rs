@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdf::{bounded_string::BoundedString, file_reference::FileDescr, non_empty_list::NonEmptyList};
+
+use super::{
+    axes::{AxisId, InputAxis, OutputAxis},
+    axis_size::AnyAxisSize,
+    input_tensor::InputTensorDescr2,
+    postprocessing::Postprocessing,
+    tensor_data_descr::{TensorData, TensorDataParsingError},
+    tensor_id::TensorId,
+};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum OutputTensorParsingError {
+    #[error("Halo {halo} of axis '{axis_id}' must be less than half of its size {size}")]
+    HaloTooLarge { axis_id: AxisId, halo: usize, size: usize },
+    #[error("Axis '{axis_id}' has a halo, so its size must refer to an input axis")]
+    HaloWithoutInputReference { axis_id: AxisId },
+    #[error("{0}")]
+    BadData(#[from] TensorDataParsingError),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(try_from = "RawOutputTensorDescr")]
+pub struct OutputTensorDescr {
+    pub id: TensorId,
+    #[serde(default = "_default_description")]
+    pub description: BoundedString<0, 128>,
+    pub axes: NonEmptyList<OutputAxis>,
+    pub test_tensor: FileDescr,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_tensor: Option<FileDescr>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub postprocessing: Vec<Postprocessing>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<TensorData>,
+}
+
+#[derive(Deserialize)]
+struct RawOutputTensorDescr {
+    id: TensorId,
+    #[serde(default = "_default_description")]
+    description: BoundedString<0, 128>,
+    axes: NonEmptyList<OutputAxis>,
+    test_tensor: FileDescr,
+    #[serde(default)]
+    sample_tensor: Option<FileDescr>,
+    #[serde(default)]
+    postprocessing: Vec<Postprocessing>,
+    #[serde(default)]
+    data: Option<TensorData>,
+}
+
+impl TryFrom<RawOutputTensorDescr> for OutputTensorDescr {
+    type Error = OutputTensorParsingError;
+    fn try_from(raw: RawOutputTensorDescr) -> Result<Self, Self::Error> {
+        let descr = Self {
+            id: raw.id,
+            description: raw.description,
+            axes: raw.axes,
+            test_tensor: raw.test_tensor,
+            sample_tensor: raw.sample_tensor,
+            postprocessing: raw.postprocessing,
+            data: raw.data,
+        };
+        descr.check()?;
+        Ok(descr)
+    }
+}
+
+impl OutputTensorDescr {
+    /// Checks what can be checked without the inputs: that axes with a halo have a reference size and that
+    /// the data matches the channels. This is done when parsing, and should be done again for tensors put
+    /// together by hand.
+    pub fn check(&self) -> Result<(), OutputTensorParsingError> {
+        // whether the reference is to an input, and the size it resolves to, are only known once the
+        // whole model is; see [OutputTensorDescr::check_halos]
+        for axis in self.axes.iter() {
+            if axis.halo() > 0 && axis.size_reference().is_none() {
+                return Err(OutputTensorParsingError::HaloWithoutInputReference { axis_id: axis.id().clone() });
+            }
+        }
+        if let Some(data) = &self.data {
+            data.check_channel_count(self.axes.iter().find_map(OutputAxis::channel_count))?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every axis with a halo refers to the size of an axis of one of `inputs`, and that
+    /// the halo is less than half the smallest size that gives, where that doesn't depend on further references
+    pub fn check_halos(&self, inputs: &[InputTensorDescr2]) -> Result<(), OutputTensorParsingError> {
+        for axis in self.axes.iter() {
+            let (halo, scale, Some(reference)) = (axis.halo(), axis.scale(), axis.size_reference()) else {
+                continue;
+            };
+            if halo == 0 {
+                continue;
+            }
+            let no_input_reference = || OutputTensorParsingError::HaloWithoutInputReference { axis_id: axis.id().clone() };
+            let input = inputs.iter().find(|input| input.id == reference.tensor_id).ok_or_else(no_input_reference)?;
+            let input_axis = input.axes.iter().find(|a| *a.id() == reference.axis_id).ok_or_else(no_input_reference)?;
+            let (input_size, input_scale) = match input_axis {
+                InputAxis::Space(a) => (&a.size, a.scale.get()),
+                InputAxis::Time(a) => (&a.size, a.scale.get()),
+                InputAxis::Index(a) => (&a.size, 1.0),
+                InputAxis::Batch(_) | InputAxis::Channel(_) => continue,
+            };
+            let smallest_input_size = match input_size {
+                AnyAxisSize::Fixed(size) => size.get(),
+                AnyAxisSize::Parameterized(size) => size.min.get(),
+                AnyAxisSize::Reference(_) => continue,
+            };
+            let size = (smallest_input_size as f64 * input_scale as f64 / scale as f64) as usize + reference.offset;
+            if 2 * halo >= size {
+                return Err(OutputTensorParsingError::HaloTooLarge {
+                    axis_id: axis.id().clone(),
+                    halo,
+                    size,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OutputAxis {
+    /// Physical size of one step along space and time axes, 1 for the others
+    pub fn scale(&self) -> f32 {
+        match self {
+            Self::Space(axis) => axis.base.scale.get(),
+            Self::Time(axis) => axis.base.scale.get(),
+            Self::Batch(_) | Self::Channel(_) | Self::Index(_) => 1.0,
+        }
+    }
+
+    /// How many pixels at each border of this axis should be cropped away, as they are not
+    /// reliable. Only space and time axes have a halo.
+    pub fn halo(&self) -> usize {
+        match self {
+            Self::Space(axis) => axis.halo,
+            Self::Time(axis) => axis.halo,
+            Self::Batch(_) | Self::Channel(_) | Self::Index(_) => 0,
+        }
+    }
+}
+
+fn _default_description() -> BoundedString<0, 128> {
+    BoundedString::try_from(String::from("")).unwrap()
+}
+
+#[test]
+fn test_output_halo_validation() {
+    let raw_input = "
+        id: raw
+        axes:
+          - {type: space, id: y, size: 64}
+          - {type: space, id: x, size: {min: 16, step: 8}, scale: 2.0}
+        test_tensor: {source: raw.npy}
+        ";
+    let inputs = [serde_yaml::from_str::<InputTensorDescr2>(raw_input).unwrap()];
+    let parse = |y_size: &str, x_halo: usize| {
+        serde_yaml::from_str::<OutputTensorDescr>(&format!(
+            "
+            id: mask
+            axes:
+              - {{type: channel, channel_names: [foreground]}}
+              - {{type: space, id: y, size: {y_size}, halo: 16}}
+              - {{type: space, id: x, size: {{tensor_id: raw, axis_id: x, offset: 2}}, halo: {x_halo}}}
+            test_tensor: {{source: mask.npy}}
+            postprocessing:
+              - {{id: sigmoid}}
+            "
+        ))
+    };
+    let output = parse("{tensor_id: raw, axis_id: y}", 16).unwrap();
+    assert_eq!(output.axes.iter().map(OutputAxis::halo).collect::<Vec<_>>(), [0, 16, 16]);
+    output.check_halos(&inputs).unwrap();
+    // x is at least 16 * 2.0 / 1.0 + 2 = 34 long
+    let too_large = parse("{tensor_id: raw, axis_id: y}", 17).unwrap().check_halos(&inputs).unwrap_err();
+    assert!(too_large.to_string().contains("less than half of its size 34"));
+    assert!(parse("{tensor_id: mask, axis_id: x}", 16).unwrap().check_halos(&inputs).is_err());
+    assert!(parse("64", 16).unwrap_err().to_string().contains("must refer to an input axis"));
+}
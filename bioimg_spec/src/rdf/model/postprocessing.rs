@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    preprocessing::{BinarizeKwargs, ClipKwargs, EnsureDtypeKwargs, FixedZeroMeanUnitVarianceKwargs, Preprocessing},
+    preprocessing::{ScaleLinearKwargs, ScaleMeanVarianceKwargs, ScaleRangeKwargs, ZeroMeanUnitVarianceKwargs},
+    tensor_id::TensorId,
+};
+
+/// A processing step applied to an output tensor, as written in its `postprocessing` list.
+/// These are the preprocessing steps, plus those that need another tensor to compare against.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "id", content = "kwargs")]
+pub enum Postprocessing {
+    #[serde(rename = "binarize")]
+    Binarize(BinarizeKwargs),
+    #[serde(rename = "clip")]
+    Clip(ClipKwargs),
+    #[serde(rename = "ensure_dtype")]
+    EnsureDtype(EnsureDtypeKwargs),
+    #[serde(rename = "scale_linear")]
+    ScaleLinear(ScaleLinearKwargs),
+    #[serde(rename = "scale_range")]
+    ScaleRange(ScaleRangeKwargs),
+    #[serde(rename = "sigmoid")]
+    Sigmoid,
+    #[serde(rename = "zero_mean_unit_variance")]
+    ZeroMeanUnitVariance(ZeroMeanUnitVarianceKwargs),
+    #[serde(rename = "fixed_zero_mean_unit_variance")]
+    FixedZeroMeanUnitVariance(FixedZeroMeanUnitVarianceKwargs),
+    /// Matches mean and variance to those of `reference_tensor`
+    #[serde(rename = "scale_mean_variance")]
+    ScaleMeanVariance(ScaleMeanVarianceKwargs),
+}
+
+impl Postprocessing {
+    /// The tensor this step takes its statistics from, if not the one being processed
+    pub fn reference_tensor(&self) -> Option<&TensorId> {
+        match self {
+            Self::ScaleRange(kwargs) => kwargs.reference_tensor.as_ref(),
+            Self::ScaleMeanVariance(kwargs) => Some(&kwargs.reference_tensor),
+            _ => None,
+        }
+    }
+}
+
+impl From<Preprocessing> for Postprocessing {
+    fn from(step: Preprocessing) -> Self {
+        match step {
+            Preprocessing::Binarize(kwargs) => Self::Binarize(kwargs),
+            Preprocessing::Clip(kwargs) => Self::Clip(kwargs),
+            Preprocessing::EnsureDtype(kwargs) => Self::EnsureDtype(kwargs),
+            Preprocessing::ScaleLinear(kwargs) => Self::ScaleLinear(kwargs),
+            Preprocessing::ScaleRange(kwargs) => Self::ScaleRange(kwargs),
+            Preprocessing::Sigmoid => Self::Sigmoid,
+            Preprocessing::ZeroMeanUnitVariance(kwargs) => Self::ZeroMeanUnitVariance(kwargs),
+            Preprocessing::FixedZeroMeanUnitVariance(kwargs) => Self::FixedZeroMeanUnitVariance(kwargs),
+        }
+    }
+}
+
+/// Fails with the step itself when it can only be used as postprocessing
+impl TryFrom<Postprocessing> for Preprocessing {
+    type Error = Postprocessing;
+    fn try_from(step: Postprocessing) -> Result<Self, Self::Error> {
+        Ok(match step {
+            Postprocessing::Binarize(kwargs) => Self::Binarize(kwargs),
+            Postprocessing::Clip(kwargs) => Self::Clip(kwargs),
+            Postprocessing::EnsureDtype(kwargs) => Self::EnsureDtype(kwargs),
+            Postprocessing::ScaleLinear(kwargs) => Self::ScaleLinear(kwargs),
+            Postprocessing::ScaleRange(kwargs) => Self::ScaleRange(kwargs),
+            Postprocessing::Sigmoid => Self::Sigmoid,
+            Postprocessing::ZeroMeanUnitVariance(kwargs) => Self::ZeroMeanUnitVariance(kwargs),
+            Postprocessing::FixedZeroMeanUnitVariance(kwargs) => Self::FixedZeroMeanUnitVariance(kwargs),
+            step @ Postprocessing::ScaleMeanVariance(_) => return Err(step),
+        })
+    }
+}
+
+#[test]
+fn test_postprocessing_serde() {
+    let steps: Vec<Postprocessing> = serde_yaml::from_str(
+        "
+        - {id: scale_mean_variance, kwargs: {reference_tensor: raw, axes: [y, x]}}
+        - {id: scale_range, kwargs: {min_percentile: 1, max_percentile: 99.8}}
+        - {id: binarize, kwargs: {threshold: 0.5}}
+        ",
+    )
+    .unwrap();
+    let references: Vec<_> = steps.iter().map(|step| step.reference_tensor().map(ToString::to_string)).collect();
+    assert_eq!(references, [Some("raw".to_owned()), None, None]);
+    assert!(Preprocessing::try_from(steps[0].clone()).is_err());
+    assert!(Preprocessing::try_from(steps[1].clone()).is_ok());
+    assert_eq!(serde_yaml::from_str::<Vec<Postprocessing>>(&serde_yaml::to_string(&steps).unwrap()).unwrap(), steps);
+}
@@ -1,61 +1,288 @@
-// use super::axes::AxisSequence;
 use serde::{Deserialize, Serialize};
 
 use crate::util::SingleOrMultiple;
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(tag = "name", content = "kwargs")]
+use super::{axes::AxisId, data_type::DataType, tensor_id::TensorId};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum PreprocessingParsingError {
+    #[error("Clip minimum {min} is not less than maximum {max}")]
+    EmptyClipRange { min: f64, max: f64 },
+    #[error("Percentiles must satisfy 0 <= min_percentile < max_percentile <= 100, found {min} and {max}")]
+    BadPercentiles { min: f64, max: f64 },
+    #[error("Epsilon must be in (0, 0.1], found {0}")]
+    BadEpsilon(f64),
+    #[error("Standard deviation must be positive, found {0}")]
+    NonPositiveStd(f64),
+    #[error("'{left}' has {left_len} values but '{right}' has {right_len}")]
+    LengthMismatch {
+        left: &'static str,
+        left_len: usize,
+        right: &'static str,
+        right_len: usize,
+    },
+    #[error("'{0}' has values per entry of an axis, but no 'axis' was given")]
+    MissingAxis(&'static str),
+}
+
+fn check_eps(eps: f64) -> Result<f64, PreprocessingParsingError> {
+    if eps > 0.0 && eps <= 0.1 {
+        Ok(eps)
+    } else {
+        Err(PreprocessingParsingError::BadEpsilon(eps))
+    }
+}
+
+/// Values given per entry of `axis` must come in the same number; a single value applies to all of them
+fn check_lengths(
+    axis: &Option<AxisId>,
+    (left, left_values): (&'static str, &SingleOrMultiple<f64>),
+    (right, right_values): (&'static str, &SingleOrMultiple<f64>),
+) -> Result<(), PreprocessingParsingError> {
+    if let (SingleOrMultiple::Multiple(l), SingleOrMultiple::Multiple(r)) = (left_values, right_values) {
+        if l.len() != r.len() {
+            return Err(PreprocessingParsingError::LengthMismatch {
+                left,
+                left_len: l.len(),
+                right,
+                right_len: r.len(),
+            });
+        }
+    }
+    for (name, values) in [(left, left_values), (right, right_values)] {
+        if axis.is_none() && matches!(values, SingleOrMultiple::Multiple(_)) {
+            return Err(PreprocessingParsingError::MissingAxis(name));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BinarizeKwargs {
+    /// A single threshold, or one per entry of `axis`
+    pub threshold: SingleOrMultiple<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axis: Option<AxisId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawClipKwargs")]
+pub struct ClipKwargs {
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Deserialize)]
+struct RawClipKwargs {
+    min: f64,
+    max: f64,
+}
+
+impl TryFrom<RawClipKwargs> for ClipKwargs {
+    type Error = PreprocessingParsingError;
+    fn try_from(raw: RawClipKwargs) -> Result<Self, Self::Error> {
+        if raw.min >= raw.max {
+            return Err(PreprocessingParsingError::EmptyClipRange { min: raw.min, max: raw.max });
+        }
+        Ok(Self { min: raw.min, max: raw.max })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnsureDtypeKwargs {
+    pub dtype: DataType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawScaleLinearKwargs")]
+pub struct ScaleLinearKwargs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axis: Option<AxisId>,
+    pub gain: SingleOrMultiple<f64>,
+    pub offset: SingleOrMultiple<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawScaleLinearKwargs {
+    #[serde(default)]
+    axis: Option<AxisId>,
+    #[serde(default = "_default_gain")]
+    gain: SingleOrMultiple<f64>,
+    #[serde(default = "_default_offset")]
+    offset: SingleOrMultiple<f64>,
+}
+
+impl TryFrom<RawScaleLinearKwargs> for ScaleLinearKwargs {
+    type Error = PreprocessingParsingError;
+    fn try_from(raw: RawScaleLinearKwargs) -> Result<Self, Self::Error> {
+        check_lengths(&raw.axis, ("gain", &raw.gain), ("offset", &raw.offset))?;
+        Ok(Self { axis: raw.axis, gain: raw.gain, offset: raw.offset })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawScaleRangeKwargs")]
+pub struct ScaleRangeKwargs {
+    /// The axes to compute the percentiles over; all of them if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axes: Option<Vec<AxisId>>,
+    pub eps: f64,
+    pub max_percentile: f64,
+    pub min_percentile: f64,
+    /// The tensor to compute the percentiles of, if not the one being processed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_tensor: Option<TensorId>,
+}
+
+#[derive(Deserialize)]
+struct RawScaleRangeKwargs {
+    #[serde(default)]
+    axes: Option<Vec<AxisId>>,
+    #[serde(default = "_default_eps")]
+    eps: f64,
+    #[serde(default = "_default_max_percentile")]
+    max_percentile: f64,
+    #[serde(default = "_default_min_percentile")]
+    min_percentile: f64,
+    #[serde(default)]
+    reference_tensor: Option<TensorId>,
+}
+
+impl TryFrom<RawScaleRangeKwargs> for ScaleRangeKwargs {
+    type Error = PreprocessingParsingError;
+    fn try_from(raw: RawScaleRangeKwargs) -> Result<Self, Self::Error> {
+        let (min, max) = (raw.min_percentile, raw.max_percentile);
+        if !(0.0 <= min && min < max && max <= 100.0) {
+            return Err(PreprocessingParsingError::BadPercentiles { min, max });
+        }
+        Ok(Self {
+            axes: raw.axes,
+            eps: check_eps(raw.eps)?,
+            max_percentile: max,
+            min_percentile: min,
+            reference_tensor: raw.reference_tensor,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawZeroMeanUnitVarianceKwargs")]
+pub struct ZeroMeanUnitVarianceKwargs {
+    /// The axes to compute mean and variance over; all of them if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axes: Option<Vec<AxisId>>,
+    pub eps: f64,
+}
+
+#[derive(Deserialize)]
+struct RawZeroMeanUnitVarianceKwargs {
+    #[serde(default)]
+    axes: Option<Vec<AxisId>>,
+    #[serde(default = "_default_eps")]
+    eps: f64,
+}
+
+impl TryFrom<RawZeroMeanUnitVarianceKwargs> for ZeroMeanUnitVarianceKwargs {
+    type Error = PreprocessingParsingError;
+    fn try_from(raw: RawZeroMeanUnitVarianceKwargs) -> Result<Self, Self::Error> {
+        Ok(Self { axes: raw.axes, eps: check_eps(raw.eps)? })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawFixedZeroMeanUnitVarianceKwargs")]
+pub struct FixedZeroMeanUnitVarianceKwargs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axis: Option<AxisId>,
+    pub mean: SingleOrMultiple<f64>,
+    pub std: SingleOrMultiple<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawFixedZeroMeanUnitVarianceKwargs {
+    #[serde(default)]
+    axis: Option<AxisId>,
+    mean: SingleOrMultiple<f64>,
+    std: SingleOrMultiple<f64>,
+}
+
+impl TryFrom<RawFixedZeroMeanUnitVarianceKwargs> for FixedZeroMeanUnitVarianceKwargs {
+    type Error = PreprocessingParsingError;
+    fn try_from(raw: RawFixedZeroMeanUnitVarianceKwargs) -> Result<Self, Self::Error> {
+        check_lengths(&raw.axis, ("mean", &raw.mean), ("std", &raw.std))?;
+        if let Some(std) = raw.std.as_slice().iter().find(|std| **std <= 0.0) {
+            return Err(PreprocessingParsingError::NonPositiveStd(*std));
+        }
+        Ok(Self { axis: raw.axis, mean: raw.mean, std: raw.std })
+    }
+}
+
+/// Only used as postprocessing, but kept here with the other kwargs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawScaleMeanVarianceKwargs")]
+pub struct ScaleMeanVarianceKwargs {
+    /// The tensor whose mean and variance are matched
+    pub reference_tensor: TensorId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub axes: Option<Vec<AxisId>>,
+    pub eps: f64,
+}
+
+#[derive(Deserialize)]
+struct RawScaleMeanVarianceKwargs {
+    reference_tensor: TensorId,
+    #[serde(default)]
+    axes: Option<Vec<AxisId>>,
+    #[serde(default = "_default_eps")]
+    eps: f64,
+}
+
+impl TryFrom<RawScaleMeanVarianceKwargs> for ScaleMeanVarianceKwargs {
+    type Error = PreprocessingParsingError;
+    fn try_from(raw: RawScaleMeanVarianceKwargs) -> Result<Self, Self::Error> {
+        Ok(Self { reference_tensor: raw.reference_tensor, axes: raw.axes, eps: check_eps(raw.eps)? })
+    }
+}
+
+/// A processing step applied to an input tensor, as written in its `preprocessing` list. Output
+/// tensors use [super::postprocessing::Postprocessing], which has a few more.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "id", content = "kwargs")]
 pub enum Preprocessing {
     #[serde(rename = "binarize")]
-    Binarize { threshold: f64 },
+    Binarize(BinarizeKwargs),
     #[serde(rename = "clip")]
-    Clip { min: f64, max: f64 },
+    Clip(ClipKwargs),
+    #[serde(rename = "ensure_dtype")]
+    EnsureDtype(EnsureDtypeKwargs),
     #[serde(rename = "scale_linear")]
-    ScaleLinear {
-        // axes: AxisSequence,
-        gain: SingleOrMultiple<f64>,
-        offset: SingleOrMultiple<f64>,
-    },
+    ScaleLinear(ScaleLinearKwargs),
     #[serde(rename = "scale_range")]
-    ScaleRange {
-        mode: ScaleRangeMode,
-        // axes: AxisSequence,
-        #[serde(default = "_default_eps")]
-        eps: f64,
-        #[serde(default = "_default_max_percentile")]
-        max_percentile: f64,
-        #[serde(default = "_default_min_percentile")]
-        min_percentile: f64,
-    },
+    ScaleRange(ScaleRangeKwargs),
     #[serde(rename = "sigmoid")]
     Sigmoid,
     #[serde(rename = "zero_mean_unit_variance")]
-    ZeroMeanUnitVariance(ZeroMeanUnitVariance),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(tag = "mode")]
-pub enum ZeroMeanUnitVariance {
-    #[serde(rename = "fixed")]
-    Fixed {
-        // axes: AxisSequence,
-        #[serde(default = "_default_eps")]
-        eps: f64,
-        mean: Vec<f64>,
-        std: Vec<f64>,
-    },
-    #[serde(rename = "per_dataset")]
-    PerDataset {
-        // axes: AxisSequence,
-        #[serde(default = "_default_eps")]
-        eps: f64,
-    },
-    #[serde(rename = "per_sample")]
-    PerSample {
-        // axes: AxisSequence,
-        #[serde(default = "_default_eps")]
-        eps: f64,
-    },
+    ZeroMeanUnitVariance(ZeroMeanUnitVarianceKwargs),
+    #[serde(rename = "fixed_zero_mean_unit_variance")]
+    FixedZeroMeanUnitVariance(FixedZeroMeanUnitVarianceKwargs),
+}
+
+impl Preprocessing {
+    /// The tensor this step takes its statistics from, if not the one being processed
+    pub fn reference_tensor(&self) -> Option<&TensorId> {
+        match self {
+            Self::ScaleRange(kwargs) => kwargs.reference_tensor.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+const fn _default_gain() -> SingleOrMultiple<f64> {
+    SingleOrMultiple::Single(1.0)
+}
+
+const fn _default_offset() -> SingleOrMultiple<f64> {
+    SingleOrMultiple::Single(0.0)
 }
 
 const fn _default_eps() -> f64 {
@@ -70,20 +297,31 @@ const fn _default_max_percentile() -> f64 {
     100f64
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ScaleRangeMode {
-    #[serde(rename = "per_dataset")]
-    PerDataset,
-    #[serde(rename = "per_sample")]
-    PerSample,
-}
+#[test]
+fn test_preprocessing_kwargs_validation() {
+    let parse = |raw: &str| serde_yaml::from_str::<Preprocessing>(raw).map_err(|err| err.to_string());
+
+    assert_eq!(
+        parse("{id: scale_range, kwargs: {axes: [y, x]}}").unwrap(),
+        Preprocessing::ScaleRange(ScaleRangeKwargs {
+            axes: Some(vec![AxisId::try_from("y".to_owned()).unwrap(), AxisId::try_from("x".to_owned()).unwrap()]),
+            eps: _default_eps(),
+            max_percentile: 100.0,
+            min_percentile: 0.0,
+            reference_tensor: None,
+        })
+    );
+    assert_eq!(parse("{id: sigmoid}").unwrap(), Preprocessing::Sigmoid);
+    assert!(parse("{id: ensure_dtype, kwargs: {dtype: uint8}}").is_ok());
+    assert!(parse("{id: binarize, kwargs: {threshold: [0.5, 0.2], axis: channel}}").is_ok());
 
-#[derive(Serialize, Deserialize, Debug)]
-pub enum ZeroMeanUnitVarianceMode {
-    #[serde(rename = "fixed")]
-    Fixed,
-    #[serde(rename = "per_dataset")]
-    PerDataset,
-    #[serde(rename = "per_sample")]
-    PerSample,
+    assert!(parse("{id: scale_range, kwargs: {min_percentile: 99, max_percentile: 1}}").unwrap_err().contains("Percentiles"));
+    assert!(parse("{id: scale_range, kwargs: {max_percentile: 101}}").unwrap_err().contains("Percentiles"));
+    assert!(parse("{id: zero_mean_unit_variance, kwargs: {eps: 0}}").unwrap_err().contains("Epsilon"));
+    assert!(parse("{id: clip, kwargs: {min: 1, max: 0}}").unwrap_err().contains("not less than"));
+    assert!(parse("{id: scale_linear, kwargs: {axis: channel, gain: [1, 2], offset: [0, 1, 2]}}")
+        .unwrap_err()
+        .contains("'gain' has 2 values but 'offset' has 3"));
+    assert!(parse("{id: scale_linear, kwargs: {gain: [1, 2]}}").unwrap_err().contains("no 'axis'"));
+    assert!(parse("{id: fixed_zero_mean_unit_variance, kwargs: {mean: 0, std: 0}}").unwrap_err().contains("positive"));
 }
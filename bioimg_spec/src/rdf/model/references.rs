@@ -0,0 +1,597 @@
+//! Keeping the places that point at a tensor or axis by id in sync with the ids themselves
+
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    axes::{AxisId, InputAxis, OutputAxis},
+    axis_size::{AnyAxisSize, AxisSizeReference},
+    input_tensor::InputTensorDescr2,
+    output_tensor::OutputTensorDescr,
+    postprocessing::Postprocessing,
+    preprocessing::Preprocessing,
+    tensor_id::TensorId,
+};
+
+/// A tensor id, or the id of one axis of a tensor, being changed from `from` to `to`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdRename {
+    Tensor { from: TensorId, to: TensorId },
+    Axis { tensor_id: TensorId, from: AxisId, to: AxisId },
+}
+
+impl AxisSizeReference {
+    /// Points this reference at the renamed tensor or axis, returning whether anything changed
+    pub fn apply_rename(&mut self, rename: &IdRename) -> bool {
+        match rename {
+            IdRename::Tensor { from, to } if self.tensor_id == *from => {
+                self.tensor_id = to.clone();
+                true
+            }
+            IdRename::Axis { tensor_id, from, to } if self.tensor_id == *tensor_id && self.axis_id == *from => {
+                self.axis_id = to.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl InputAxis {
+    pub fn id(&self) -> &AxisId {
+        match self {
+            Self::Batch(axis) => &axis.id,
+            Self::Channel(axis) => &axis.id,
+            Self::Index(axis) => &axis.id,
+            Self::Time(axis) => &axis.id,
+            Self::Space(axis) => &axis.id,
+        }
+    }
+
+    /// The size of index, space and time axes, which are the ones that can refer to other sizes
+    pub fn size(&self) -> Option<&AnyAxisSize> {
+        match self {
+            Self::Index(axis) => Some(&axis.size),
+            Self::Time(axis) => Some(&axis.size),
+            Self::Space(axis) => Some(&axis.size),
+            Self::Batch(_) | Self::Channel(_) => None,
+        }
+    }
+
+    pub fn size_reference(&self) -> Option<&AxisSizeReference> {
+        self.size().and_then(|size| match size {
+            AnyAxisSize::Reference(reference) => Some(reference),
+            _ => None,
+        })
+    }
+
+    pub fn size_reference_mut(&mut self) -> Option<&mut AxisSizeReference> {
+        match self {
+            Self::Index(axis) => Some(&mut axis.size),
+            Self::Time(axis) => Some(&mut axis.size),
+            Self::Space(axis) => Some(&mut axis.size),
+            Self::Batch(_) | Self::Channel(_) => None,
+        }
+        .and_then(|size| match size {
+            AnyAxisSize::Reference(reference) => Some(reference),
+            _ => None,
+        })
+    }
+}
+
+impl OutputAxis {
+    pub fn id(&self) -> &AxisId {
+        match self {
+            Self::Batch(axis) => &axis.id,
+            Self::Channel(axis) => &axis.id,
+            Self::Index(axis) => &axis.id,
+            Self::Time(axis) => &axis.base.id,
+            Self::Space(axis) => &axis.base.id,
+        }
+    }
+
+    /// The size of index, space and time axes, which are the ones that can refer to other sizes
+    pub fn size(&self) -> Option<&AnyAxisSize> {
+        match self {
+            Self::Index(axis) => Some(&axis.size),
+            Self::Time(axis) => Some(&axis.base.size),
+            Self::Space(axis) => Some(&axis.base.size),
+            Self::Batch(_) | Self::Channel(_) => None,
+        }
+    }
+
+    pub fn size_reference(&self) -> Option<&AxisSizeReference> {
+        self.size().and_then(|size| match size {
+            AnyAxisSize::Reference(reference) => Some(reference),
+            _ => None,
+        })
+    }
+
+    pub fn size_reference_mut(&mut self) -> Option<&mut AxisSizeReference> {
+        match self {
+            Self::Index(axis) => Some(&mut axis.size),
+            Self::Time(axis) => Some(&mut axis.base.size),
+            Self::Space(axis) => Some(&mut axis.base.size),
+            Self::Batch(_) | Self::Channel(_) => None,
+        }
+        .and_then(|size| match size {
+            AnyAxisSize::Reference(reference) => Some(reference),
+            _ => None,
+        })
+    }
+}
+
+/// Renames what a processing step of tensor `tensor_id` points at: the `axes` it works along, which are axes
+/// of that tensor, and the tensor it takes its statistics from. Returns how many ids were changed.
+fn rename_in_step(tensor_id: &TensorId, axes: Vec<&mut AxisId>, reference: Option<&mut TensorId>, rename: &IdRename) -> usize {
+    match rename {
+        IdRename::Tensor { from, to } => reference.into_iter().filter(|id| **id == *from).map(|id| *id = to.clone()).count(),
+        IdRename::Axis { tensor_id: renamed, from, to } if renamed == tensor_id => {
+            axes.into_iter().filter(|axis| **axis == *from).map(|axis| *axis = to.clone()).count()
+        }
+        IdRename::Axis { .. } => 0,
+    }
+}
+
+impl Preprocessing {
+    /// Points this step of tensor `tensor_id` at the renamed tensor or axis, returning how many ids changed
+    pub fn apply_rename(&mut self, tensor_id: &TensorId, rename: &IdRename) -> usize {
+        let (axes, reference) = match self {
+            Self::Binarize(kwargs) => (kwargs.axis.iter_mut().collect(), None),
+            Self::ScaleLinear(kwargs) => (kwargs.axis.iter_mut().collect(), None),
+            Self::FixedZeroMeanUnitVariance(kwargs) => (kwargs.axis.iter_mut().collect(), None),
+            Self::ZeroMeanUnitVariance(kwargs) => (kwargs.axes.iter_mut().flatten().collect(), None),
+            Self::ScaleRange(kwargs) => (kwargs.axes.iter_mut().flatten().collect(), kwargs.reference_tensor.as_mut()),
+            Self::Clip(_) | Self::EnsureDtype(_) | Self::Sigmoid => (vec![], None),
+        };
+        rename_in_step(tensor_id, axes, reference, rename)
+    }
+}
+
+impl Postprocessing {
+    /// Points this step of tensor `tensor_id` at the renamed tensor or axis, returning how many ids changed
+    pub fn apply_rename(&mut self, tensor_id: &TensorId, rename: &IdRename) -> usize {
+        let (axes, reference) = match self {
+            Self::Binarize(kwargs) => (kwargs.axis.iter_mut().collect(), None),
+            Self::ScaleLinear(kwargs) => (kwargs.axis.iter_mut().collect(), None),
+            Self::FixedZeroMeanUnitVariance(kwargs) => (kwargs.axis.iter_mut().collect(), None),
+            Self::ZeroMeanUnitVariance(kwargs) => (kwargs.axes.iter_mut().flatten().collect(), None),
+            Self::ScaleRange(kwargs) => (kwargs.axes.iter_mut().flatten().collect(), kwargs.reference_tensor.as_mut()),
+            Self::ScaleMeanVariance(kwargs) => (kwargs.axes.iter_mut().flatten().collect(), Some(&mut kwargs.reference_tensor)),
+            Self::Clip(_) | Self::EnsureDtype(_) | Self::Sigmoid => (vec![], None),
+        };
+        rename_in_step(tensor_id, axes, reference, rename)
+    }
+}
+
+/// Updates every size reference and processing step in `inputs` and `outputs` that pointed at the old id,
+/// returning how many ids were changed. The renamed tensor or axis itself is expected to have been updated already.
+pub fn propagate_rename(inputs: &mut [InputTensorDescr2], outputs: &mut [OutputTensorDescr], rename: &IdRename) -> usize {
+    let renamed_sizes = |references: Vec<&mut AxisSizeReference>| {
+        references.into_iter().map(|reference| reference.apply_rename(rename)).filter(|changed| *changed).count()
+    };
+    let in_inputs = inputs.iter_mut().map(|tensor| {
+        let sizes = renamed_sizes(tensor.axes.iter_mut().filter_map(InputAxis::size_reference_mut).collect());
+        let id = &tensor.id;
+        sizes + tensor.preprocessing.iter_mut().map(|step| step.apply_rename(id, rename)).sum::<usize>()
+    });
+    let in_outputs = outputs.iter_mut().map(|tensor| {
+        let sizes = renamed_sizes(tensor.axes.iter_mut().filter_map(OutputAxis::size_reference_mut).collect());
+        let id = &tensor.id;
+        sizes + tensor.postprocessing.iter_mut().map(|step| step.apply_rename(id, rename)).sum::<usize>()
+    });
+    in_inputs.sum::<usize>() + in_outputs.sum::<usize>()
+}
+
+/// Why a size reference can't be resolved to the size of another axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeReferenceProblem {
+    UnknownTensor,
+    UnknownAxis,
+    /// Following the references leads back to where they started, so no size is ever reached
+    Cycle,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Size of axis '{axis_id}' of tensor '{tensor_id}' {}", self.reason())]
+pub struct SizeReferenceError {
+    pub tensor_id: TensorId,
+    pub axis_id: AxisId,
+    pub reference: AxisSizeReference,
+    pub problem: SizeReferenceProblem,
+}
+
+impl SizeReferenceError {
+    fn reason(&self) -> String {
+        let (tensor_id, axis_id) = (&self.reference.tensor_id, &self.reference.axis_id);
+        match self.problem {
+            SizeReferenceProblem::UnknownTensor => format!("refers to tensor '{tensor_id}', which doesn't exist"),
+            SizeReferenceProblem::UnknownAxis => {
+                format!("refers to axis '{axis_id}' of tensor '{tensor_id}', which doesn't exist")
+            }
+            SizeReferenceProblem::Cycle => {
+                format!("refers to axis '{axis_id}' of tensor '{tensor_id}', whose size refers back to it")
+            }
+        }
+    }
+}
+
+/// Every axis of a model by tensor and axis id, with the reference its size is given by, if any
+type AxisIndex<'a> = HashMap<(&'a str, &'a str), Option<&'a AxisSizeReference>>;
+
+fn check_reference(axes: &AxisIndex, start: (&str, &str), reference: &AxisSizeReference) -> Option<SizeReferenceProblem> {
+    let mut visited = HashSet::from([start]);
+    let mut next = reference;
+    loop {
+        let key: (&str, &str) = (&next.tensor_id, &next.axis_id);
+        let Some(target) = axes.get(&key) else {
+            let tensor_exists = axes.keys().any(|(tensor_id, _)| *tensor_id == key.0);
+            return Some(if tensor_exists { SizeReferenceProblem::UnknownAxis } else { SizeReferenceProblem::UnknownTensor });
+        };
+        let Some(target_reference) = target else {
+            return None;
+        };
+        if !visited.insert(key) {
+            return Some(SizeReferenceProblem::Cycle);
+        }
+        next = target_reference;
+    }
+}
+
+/// Size references among `inputs` and `outputs` that don't resolve to the size of an axis.
+/// Inputs may only refer to inputs, while outputs may refer to either.
+pub fn size_reference_errors(inputs: &[InputTensorDescr2], outputs: &[OutputTensorDescr]) -> Vec<SizeReferenceError> {
+    let input_axes = inputs
+        .iter()
+        .flat_map(|tensor| tensor.axes.iter().map(move |axis| (&tensor.id, axis.id(), axis.size_reference())));
+    let output_axes = outputs
+        .iter()
+        .flat_map(|tensor| tensor.axes.iter().map(move |axis| (&tensor.id, axis.id(), axis.size_reference())));
+    fn index_entry<'a>(
+        (tensor_id, axis_id, reference): (&'a TensorId, &'a AxisId, Option<&'a AxisSizeReference>),
+    ) -> ((&'a str, &'a str), Option<&'a AxisSizeReference>) {
+        ((tensor_id, axis_id), reference)
+    }
+    let input_index: AxisIndex = input_axes.clone().map(index_entry).collect();
+    let mut full_index = input_index.clone();
+    full_index.extend(output_axes.clone().map(index_entry));
+
+    let checked_inputs = input_axes.map(|axis| (axis, &input_index));
+    let checked_outputs = output_axes.map(|axis| (axis, &full_index));
+    checked_inputs
+        .chain(checked_outputs)
+        .filter_map(|((tensor_id, axis_id, reference), index)| {
+            let reference = reference?;
+            let problem = check_reference(index, (&**tensor_id, &**axis_id), reference)?;
+            Some(SizeReferenceError {
+                tensor_id: tensor_id.clone(),
+                axis_id: axis_id.clone(),
+                reference: reference.clone(),
+                problem,
+            })
+        })
+        .collect()
+}
+
+/// A size reference whose referenced size, scaled by the ratio of the two axes' scales, isn't a whole number.
+/// The size of an axis referring to another one is `reference_size * reference_scale / scale + offset`.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error(
+    "Size of axis '{axis_id}' of tensor '{tensor_id}' would be {size} when axis '{}' of tensor '{}' has size {reference_size}, \
+     which is not a whole number",
+    reference.axis_id,
+    reference.tensor_id
+)]
+pub struct ScaledSizeError {
+    pub tensor_id: TensorId,
+    pub axis_id: AxisId,
+    pub reference: AxisSizeReference,
+    pub reference_size: usize,
+    pub size: f64,
+}
+
+/// The sizes that an axis of fixed or parameterized size can have, in as far as they matter for
+/// scaling: a parameterized size scales to whole numbers if its `min` and `min + step` do
+fn representative_sizes(size: &AnyAxisSize) -> Vec<usize> {
+    match size {
+        AnyAxisSize::Fixed(size) => vec![size.get()],
+        AnyAxisSize::Parameterized(size) => vec![size.min.get(), size.min.get() + size.step.get()],
+        AnyAxisSize::Reference(_) => vec![],
+    }
+}
+
+/// Size references among `inputs` and `outputs` that point at a fixed or parameterized size, but
+/// would scale it to one that isn't a whole number. References that don't resolve are left to
+/// [size_reference_errors].
+pub fn scaled_size_errors(inputs: &[InputTensorDescr2], outputs: &[OutputTensorDescr]) -> Vec<ScaledSizeError> {
+    // every axis with its scale, the reference its size is given by, if any, and the sizes it can have
+    let input_axes = inputs.iter().flat_map(|tensor| {
+        tensor.axes.iter().map(move |axis| {
+            let sizes = match axis {
+                InputAxis::Channel(channel) => vec![channel.size()],
+                _ => axis.size().map(representative_sizes).unwrap_or_default(),
+            };
+            (&tensor.id, axis.id(), axis.scale(), axis.size_reference(), sizes)
+        })
+    });
+    let output_axes = outputs.iter().flat_map(|tensor| {
+        tensor.axes.iter().map(move |axis| {
+            let sizes = match axis {
+                OutputAxis::Channel(channel) => vec![channel.size()],
+                _ => axis.size().map(representative_sizes).unwrap_or_default(),
+            };
+            (&tensor.id, axis.id(), axis.scale(), axis.size_reference(), sizes)
+        })
+    });
+    let all_axes: Vec<_> = input_axes.chain(output_axes).collect();
+    let index: HashMap<(&str, &str), (f32, &[usize])> = all_axes
+        .iter()
+        .map(|(tensor_id, axis_id, scale, _, sizes)| ((&***tensor_id, &***axis_id), (*scale, sizes.as_slice())))
+        .collect();
+
+    all_axes
+        .iter()
+        .filter_map(|(tensor_id, axis_id, scale, reference, _)| {
+            let reference = (*reference)?;
+            let (reference_scale, reference_sizes) = index.get(&(&*reference.tensor_id, &*reference.axis_id))?;
+            reference_sizes.iter().find_map(|&reference_size| {
+                let size = reference_size as f64 * *reference_scale as f64 / *scale as f64;
+                ((size - size.round()).abs() > 1e-6).then(|| ScaledSizeError {
+                    tensor_id: (*tensor_id).clone(),
+                    axis_id: (*axis_id).clone(),
+                    reference: reference.clone(),
+                    reference_size,
+                    size: size + reference.offset as f64,
+                })
+            })
+        })
+        .collect()
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum SizeResolutionError {
+    #[error("Tensor '{tensor_id}' has no axis '{axis_id}'")]
+    UnknownAxis { tensor_id: TensorId, axis_id: AxisId },
+    #[error("Size of axis '{axis_id}' of tensor '{tensor_id}' is up to whoever runs the model")]
+    FreeSize { tensor_id: TensorId, axis_id: AxisId },
+    #[error("Size of axis '{axis_id}' of tensor '{tensor_id}' refers back to itself")]
+    Cycle { tensor_id: TensorId, axis_id: AxisId },
+    #[error("{0}")]
+    NotWhole(#[from] ScaledSizeError),
+}
+
+/// What an axis says about its own size
+enum DeclaredSize<'a> {
+    Size(&'a AnyAxisSize),
+    Exactly(usize),
+    /// A batch axis without a size, which takes any number of samples
+    Free,
+}
+
+fn declared_size<'a>(
+    inputs: &'a [InputTensorDescr2],
+    outputs: &'a [OutputTensorDescr],
+    tensor_id: &TensorId,
+    axis_id: &AxisId,
+) -> Option<(f32, DeclaredSize<'a>)> {
+    let input = inputs.iter().filter(|tensor| tensor.id == *tensor_id).flat_map(|tensor| tensor.axes.iter());
+    let input = input.clone().find(|axis| axis.id() == axis_id).map(|axis| {
+        let declared = match (axis, axis.size()) {
+            (_, Some(size)) => DeclaredSize::Size(size),
+            (InputAxis::Channel(channel), None) => DeclaredSize::Exactly(channel.size()),
+            (InputAxis::Batch(batch), None) if batch.size.is_some() => DeclaredSize::Exactly(1),
+            _ => DeclaredSize::Free,
+        };
+        (axis.scale(), declared)
+    });
+    let output = || {
+        let mut axes = outputs.iter().filter(|tensor| tensor.id == *tensor_id).flat_map(|tensor| tensor.axes.iter());
+        axes.find(|axis| axis.id() == axis_id).map(|axis| {
+            let declared = match (axis, axis.size()) {
+                (_, Some(size)) => DeclaredSize::Size(size),
+                (OutputAxis::Channel(channel), None) => DeclaredSize::Exactly(channel.size()),
+                (OutputAxis::Batch(batch), None) if batch.size.is_some() => DeclaredSize::Exactly(1),
+                _ => DeclaredSize::Free,
+            };
+            (axis.scale(), declared)
+        })
+    };
+    input.or_else(output)
+}
+
+/// The size of an axis when every parameterized size among `inputs` and `outputs` is `min + n * step`. Size
+/// references are followed to the axis they point at, scaling its size by the ratio of the two axes' scales.
+pub fn resolve_axis_size(
+    inputs: &[InputTensorDescr2],
+    outputs: &[OutputTensorDescr],
+    tensor_id: &TensorId,
+    axis_id: &AxisId,
+    n: usize,
+) -> Result<usize, SizeResolutionError> {
+    let mut visited = HashSet::new();
+    resolve_following(inputs, outputs, tensor_id, axis_id, n, &mut visited)
+}
+
+fn resolve_following<'a>(
+    inputs: &'a [InputTensorDescr2],
+    outputs: &'a [OutputTensorDescr],
+    tensor_id: &'a TensorId,
+    axis_id: &'a AxisId,
+    n: usize,
+    visited: &mut HashSet<(&'a str, &'a str)>,
+) -> Result<usize, SizeResolutionError> {
+    let ids = || (tensor_id.clone(), axis_id.clone());
+    if !visited.insert((&**tensor_id, &**axis_id)) {
+        let (tensor_id, axis_id) = ids();
+        return Err(SizeResolutionError::Cycle { tensor_id, axis_id });
+    }
+    let Some((scale, declared)) = declared_size(inputs, outputs, tensor_id, axis_id) else {
+        let (tensor_id, axis_id) = ids();
+        return Err(SizeResolutionError::UnknownAxis { tensor_id, axis_id });
+    };
+    let reference = match declared {
+        DeclaredSize::Exactly(size) => return Ok(size),
+        DeclaredSize::Free => {
+            let (tensor_id, axis_id) = ids();
+            return Err(SizeResolutionError::FreeSize { tensor_id, axis_id });
+        }
+        DeclaredSize::Size(AnyAxisSize::Reference(reference)) => reference,
+        DeclaredSize::Size(size) => return Ok(size.resolve(n).expect("references are handled above")),
+    };
+    let reference_size = resolve_following(inputs, outputs, &reference.tensor_id, &reference.axis_id, n, visited)?;
+    // the referenced axis was found while resolving its size
+    let reference_axis = declared_size(inputs, outputs, &reference.tensor_id, &reference.axis_id);
+    let reference_scale = reference_axis.map_or(1.0, |(scale, _)| scale);
+    let size = reference_size as f64 * reference_scale as f64 / scale as f64;
+    if (size - size.round()).abs() > 1e-6 {
+        let (tensor_id, axis_id) = ids();
+        return Err(ScaledSizeError {
+            tensor_id,
+            axis_id,
+            reference: reference.clone(),
+            reference_size,
+            size: size + reference.offset as f64,
+        }
+        .into());
+    }
+    Ok(size.round() as usize + reference.offset)
+}
+
+/// Size references of input `tensors` that don't resolve to the size of an axis
+pub fn dangling_size_references(tensors: &[InputTensorDescr2]) -> Vec<SizeReferenceError> {
+    size_reference_errors(tensors, &[])
+}
+
+#[test]
+fn test_rename_propagation() {
+    let mut tensors: Vec<InputTensorDescr2> = serde_json::from_value(serde_json::json!([
+        {
+            "id": "raw",
+            "axes": [{"type": "space", "id": "x", "size": 64}],
+            "test_tensor": {"source": "raw.npy"},
+            "preprocessing": [{"id": "zero_mean_unit_variance", "kwargs": {"axes": ["x"]}}],
+        },
+        {
+            "id": "mask",
+            "axes": [{"type": "space", "id": "x", "size": {"tensor_id": "raw", "axis_id": "x"}}],
+            "test_tensor": {"source": "mask.npy"},
+            "preprocessing": [{"id": "binarize", "kwargs": {"threshold": 0.5, "axis": "x"}}],
+        },
+    ]))
+    .unwrap();
+    let mut outputs: Vec<OutputTensorDescr> = serde_json::from_value(serde_json::json!([{
+        "id": "labels",
+        "axes": [{"type": "space", "id": "x", "size": {"tensor_id": "raw", "axis_id": "x"}}],
+        "test_tensor": {"source": "labels.npy"},
+        "postprocessing": [{"id": "scale_range", "kwargs": {"axes": ["x"], "reference_tensor": "raw"}}],
+    }]))
+    .unwrap();
+    assert!(dangling_size_references(&tensors).is_empty());
+
+    tensors[0].id = TensorId::try_from("image".to_owned()).unwrap();
+    let dangling = dangling_size_references(&tensors);
+    assert_eq!(dangling.len(), 1);
+    assert_eq!(dangling[0].tensor_id, tensors[1].id);
+
+    let rename = IdRename::Tensor {
+        from: TensorId::try_from("raw".to_owned()).unwrap(),
+        to: TensorId::try_from("image".to_owned()).unwrap(),
+    };
+    // the size references of mask and labels, and the reference tensor of labels' postprocessing
+    assert_eq!(propagate_rename(&mut tensors, &mut outputs, &rename), 3);
+    assert!(size_reference_errors(&tensors, &outputs).is_empty());
+    assert_eq!(outputs[0].postprocessing[0].reference_tensor(), Some(&tensors[0].id));
+    assert_eq!(propagate_rename(&mut tensors, &mut outputs, &rename), 0);
+
+    let width = AxisId::try_from("width".to_owned()).unwrap();
+    let Some(InputAxis::Space(axis)) = tensors[0].axes.iter_mut().next() else { panic!("x is a space axis") };
+    axis.id = width.clone();
+    let rename = IdRename::Axis {
+        tensor_id: tensors[0].id.clone(),
+        from: AxisId::try_from("x".to_owned()).unwrap(),
+        to: width.clone(),
+    };
+    // the size references, and image's own preprocessing, but not the axes of the other tensors' steps
+    assert_eq!(propagate_rename(&mut tensors, &mut outputs, &rename), 3);
+    assert!(size_reference_errors(&tensors, &outputs).is_empty());
+    let image_step = &tensors[0].preprocessing[0];
+    assert!(matches!(image_step, Preprocessing::ZeroMeanUnitVariance(kwargs) if kwargs.axes == Some(vec![width.clone()])));
+    assert!(matches!(&tensors[1].preprocessing[0], Preprocessing::Binarize(kwargs) if kwargs.axis.as_ref() != Some(&width)));
+
+    let mut cyclic = tensors;
+    cyclic[0].axes = serde_json::from_value(serde_json::json!([
+        {"type": "space", "id": "width", "size": {"tensor_id": "mask", "axis_id": "x"}},
+    ]))
+    .unwrap();
+    let errors = dangling_size_references(&cyclic);
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|err| err.problem == SizeReferenceProblem::Cycle));
+    assert!(errors[0].to_string().contains("refers back to it"));
+}
+
+#[test]
+fn test_scaled_sizes() {
+    let inputs: Vec<InputTensorDescr2> = serde_json::from_value(serde_json::json!([{
+        "id": "raw",
+        "axes": [
+            {"type": "space", "id": "y", "size": 64, "scale": 1.0},
+            {"type": "space", "id": "x", "size": {"min": 32, "step": 3}},
+        ],
+        "test_tensor": {"source": "raw.npy"},
+    }]))
+    .unwrap();
+    let outputs = |scale: f32| -> Vec<OutputTensorDescr> {
+        serde_json::from_value(serde_json::json!([{
+            "id": "mask",
+            "axes": [
+                {"type": "space", "id": "y", "size": {"tensor_id": "raw", "axis_id": "y", "offset": 1}, "scale": scale},
+                {"type": "space", "id": "x", "size": {"tensor_id": "raw", "axis_id": "x"}, "scale": scale},
+            ],
+            "test_tensor": {"source": "mask.npy"},
+        }]))
+        .unwrap()
+    };
+    assert!(scaled_size_errors(&inputs, &outputs(1.0)).is_empty());
+    // 64 and 32 halve cleanly, but 35 doesn't
+    let errors = scaled_size_errors(&inputs, &outputs(2.0));
+    assert_eq!(errors.len(), 1);
+    assert_eq!((errors[0].axis_id.to_string(), errors[0].reference_size), ("x".to_owned(), 35));
+    assert!(errors[0].to_string().contains("would be 17.5"));
+    assert_eq!(scaled_size_errors(&inputs, &outputs(3.0)).len(), 2);
+}
+
+#[test]
+fn test_resolving_axis_sizes() {
+    let inputs: Vec<InputTensorDescr2> = serde_json::from_value(serde_json::json!([{
+        "id": "raw",
+        "axes": [
+            {"type": "batch"},
+            {"type": "channel", "channel_names": ["a", "b"]},
+            {"type": "space", "id": "x", "size": {"min": 32, "step": 16}},
+            {"type": "space", "id": "y", "size": {"tensor_id": "raw", "axis_id": "z"}},
+            {"type": "space", "id": "z", "size": {"tensor_id": "raw", "axis_id": "y"}},
+        ],
+        "test_tensor": {"source": "raw.npy"},
+    }]))
+    .unwrap();
+    let outputs: Vec<OutputTensorDescr> = serde_json::from_value(serde_json::json!([{
+        "id": "mask",
+        "axes": [
+            {"type": "space", "id": "x", "size": {"tensor_id": "raw", "axis_id": "x", "offset": 1}, "scale": 2.0},
+            {"type": "space", "id": "y", "size": {"tensor_id": "mask", "axis_id": "x"}, "scale": 5.0},
+        ],
+        "test_tensor": {"source": "mask.npy"},
+    }]))
+    .unwrap();
+    let resolve = |tensor_id: &str, axis_id: &str, n| {
+        let tensor_id = TensorId::try_from(tensor_id.to_owned()).unwrap();
+        resolve_axis_size(&inputs, &outputs, &tensor_id, &AxisId::try_from(axis_id.to_owned()).unwrap(), n)
+    };
+    assert_eq!(resolve("raw", "x", 2), Ok(64));
+    assert_eq!(resolve("raw", "channel", 2), Ok(2));
+    // 64 at a scale of 1 is 32 at a scale of 2, plus the offset
+    assert_eq!(resolve("mask", "x", 2), Ok(33));
+    assert!(matches!(resolve("mask", "y", 2), Err(SizeResolutionError::NotWhole(_))));
+    assert!(matches!(resolve("raw", "batch", 0), Err(SizeResolutionError::FreeSize { .. })));
+    assert!(matches!(resolve("raw", "y", 0), Err(SizeResolutionError::Cycle { .. })));
+    assert!(matches!(resolve("raw", "w", 0), Err(SizeResolutionError::UnknownAxis { .. })));
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdf::bounded_string::BoundedString;
+
+/// How a consumer software should run the model, for models that need more than plain inference
+/// on their inputs. The only name with a meaning in the spec is [RunMode::DEEPIMAGEJ].
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RunMode {
+    pub name: BoundedString<1, 1023>,
+    /// Arguments for the consumer, whose meaning depends on `name`
+    #[serde(default, skip_serializing_if = "serde_yaml::Mapping::is_empty")]
+    pub kwargs: serde_yaml::Mapping,
+}
+
+impl RunMode {
+    pub const DEEPIMAGEJ: &'static str = "deepimagej";
+
+    pub fn is_deepimagej(&self) -> bool {
+        self.name.as_str() == Self::DEEPIMAGEJ
+    }
+}
+
+#[test]
+fn test_run_mode_serde() {
+    let run_mode: RunMode = serde_yaml::from_str("{name: deepimagej, kwargs: {tiling: true}}").unwrap();
+    assert!(run_mode.is_deepimagej());
+    assert_eq!(run_mode.kwargs.get("tiling"), Some(&serde_yaml::Value::Bool(true)));
+
+    let bare: RunMode = serde_yaml::from_str("name: my_tool").unwrap();
+    assert!(!bare.is_deepimagej());
+    assert_eq!(serde_yaml::to_string(&bare).unwrap().trim(), "name: my_tool");
+    assert!(serde_yaml::from_str::<RunMode>("name: ''").is_err());
+}
@@ -4,6 +4,11 @@ use serde::{Serialize, Deserialize};
     Default, Serialize, Deserialize, Eq, PartialEq, Debug, Copy, Clone, strum::VariantArray, strum::VariantNames, strum::Display
 )]
 pub enum SpaceUnit{
+    /// A unit that isn't physical, e.g. for simulated data. The `scale` of the axis is then in
+    /// multiples of it, and it can't be converted to meters.
+    #[serde(rename = "arbitrary unit")]
+    #[strum(to_string = "arbitrary unit")]
+    ArbitraryUnit,
     #[serde(rename = "attometer")]
     #[strum(to_string = "attometer")]
     Attometer,
@@ -84,3 +89,83 @@ pub enum SpaceUnit{
     #[strum(to_string = "zettameter")]
     Zettameter,
 }
+
+impl SpaceUnit {
+    /// How many meters one of this unit is, or `None` for [Self::ArbitraryUnit]. Multiplied with
+    /// the `scale` of an axis, it gives the physical size of one step along it.
+    pub fn to_meters(self) -> Option<f64> {
+        Some(match self {
+            Self::ArbitraryUnit => return None,
+            Self::Attometer => 1e-18,
+            Self::Angstrom => 1e-10,
+            Self::Centimeter => 1e-2,
+            Self::Decimeter => 1e-1,
+            Self::Exameter => 1e18,
+            Self::Femtometer => 1e-15,
+            Self::Foot => 0.3048,
+            Self::Gigameter => 1e9,
+            Self::Hectometer => 1e2,
+            Self::Inch => 0.0254,
+            Self::Kilometer => 1e3,
+            Self::Megameter => 1e6,
+            Self::Meter => 1.0,
+            Self::Micrometer => 1e-6,
+            Self::Mile => 1609.344,
+            Self::Millimeter => 1e-3,
+            Self::Nanometer => 1e-9,
+            Self::Parsec => 3.085_677_581_491_367e16,
+            Self::Petameter => 1e15,
+            Self::Picometer => 1e-12,
+            Self::Terameter => 1e12,
+            Self::Yard => 0.9144,
+            Self::Yoctometer => 1e-24,
+            Self::Yottameter => 1e24,
+            Self::Zeptometer => 1e-21,
+            Self::Zettameter => 1e21,
+        })
+    }
+
+    /// The abbreviation of the unit, like the ones written after a number
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::ArbitraryUnit => "a.u.",
+            Self::Attometer => "am",
+            Self::Angstrom => "Å",
+            Self::Centimeter => "cm",
+            Self::Decimeter => "dm",
+            Self::Exameter => "Em",
+            Self::Femtometer => "fm",
+            Self::Foot => "ft",
+            Self::Gigameter => "Gm",
+            Self::Hectometer => "hm",
+            Self::Inch => "in",
+            Self::Kilometer => "km",
+            Self::Megameter => "Mm",
+            Self::Meter => "m",
+            Self::Micrometer => "µm",
+            Self::Mile => "mi",
+            Self::Millimeter => "mm",
+            Self::Nanometer => "nm",
+            Self::Parsec => "pc",
+            Self::Petameter => "Pm",
+            Self::Picometer => "pm",
+            Self::Terameter => "Tm",
+            Self::Yard => "yd",
+            Self::Yoctometer => "ym",
+            Self::Yottameter => "Ym",
+            Self::Zeptometer => "zm",
+            Self::Zettameter => "Zm",
+        }
+    }
+}
+
+#[test]
+fn test_space_unit_conversion() {
+    assert_eq!(SpaceUnit::Millimeter.to_meters(), Some(1e-3));
+    assert_eq!(SpaceUnit::Inch.to_meters(), Some(0.0254));
+    assert_eq!(SpaceUnit::ArbitraryUnit.to_meters(), None);
+    assert_eq!(SpaceUnit::Micrometer.symbol(), "µm");
+    let unit: SpaceUnit = serde_yaml::from_str("arbitrary unit").unwrap();
+    assert_eq!(unit, SpaceUnit::ArbitraryUnit);
+    assert_eq!(serde_yaml::to_string(&unit).unwrap().trim(), "arbitrary unit");
+}
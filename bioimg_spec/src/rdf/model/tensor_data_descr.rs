@@ -1,54 +1,302 @@
 use serde::{Deserialize, Serialize};
 
-use crate::rdf::{non_empty_list::NonEmptyList, si_units::SiUnit};
+use crate::rdf::{
+    non_empty_list::NonEmptyList,
+    si_units::{SiUnit, SiUnitParsingError},
+    spec_error::{self, SpecError},
+};
 
-use super::data_type::DataType;
+use super::{
+    data_range::{DataRange, DataRangeError},
+    data_type::DataType,
+};
 
+const ARBITRARY_UNIT: &str = "arbitrary unit";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum TensorDataParsingError {
+    #[error("{0}")]
+    BadRange(#[from] DataRangeError),
+    #[error("Interval or ratio data can't be of type 'bool', list its values instead")]
+    BoolInterval,
+    #[error("{kind} values can't be stored as '{data_type}'")]
+    ValuesDontFit { data_type: DataType, kind: &'static str },
+    #[error("Tensor has {channels} channel(s), but {descriptions} per-channel data descriptions")]
+    ChannelCountMismatch { channels: usize, descriptions: usize },
+    #[error("Per-channel data descriptions can't be an empty list")]
+    NoDescriptions,
+    #[error("{0}")]
+    BadYaml(String),
+}
+
+/// What the values of a tensor mean; see [NominalOrOrdinalDataDescr] and [IntervalOrRatioDataDescr]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+#[serde(try_from = "serde_yaml::Value")]
 pub enum TensorDataDescr {
-    NominalOrOrdinal,
-    IntervalOrRatio,
+    NominalOrOrdinal(NominalOrOrdinalDataDescr),
+    IntervalOrRatio(IntervalOrRatioDataDescr),
+}
+
+impl TensorDataDescr {
+    /// Data measured on a scale, whose `range` must be one `data_type` can hold
+    pub fn interval_or_ratio(
+        data_type: DataType,
+        range: DataRange,
+        unit: TensorDataUnit,
+    ) -> Result<Self, TensorDataParsingError> {
+        let raw = RawIntervalOrRatioDataDescr {
+            data_type,
+            range,
+            unit,
+            scale: _default_scale(),
+            offset: None,
+        };
+        Ok(Self::IntervalOrRatio(raw.try_into()?))
+    }
+
+    /// Categorical data, whose `values` must all be ones `data_type` can hold
+    pub fn nominal_or_ordinal(values: TVs, data_type: DataType) -> Result<Self, TensorDataParsingError> {
+        let raw = RawNominalOrOrdinalDataDescr {
+            values,
+            data_type,
+            unit: None,
+        };
+        Ok(Self::NominalOrOrdinal(raw.try_into()?))
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::NominalOrOrdinal(descr) => descr.data_type,
+            Self::IntervalOrRatio(descr) => descr.data_type,
+        }
+    }
+}
+
+impl TryFrom<serde_yaml::Value> for TensorDataDescr {
+    type Error = TensorDataParsingError;
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        // only categorical data lists its values, and untagged parsing would hide why either kind failed
+        let bad_yaml = |err: SpecError| TensorDataParsingError::BadYaml(err.to_string());
+        if value.get("values").is_some() {
+            Ok(Self::NominalOrOrdinal(spec_error::from_value(value).map_err(bad_yaml)?))
+        } else {
+            Ok(Self::IntervalOrRatio(spec_error::from_value(value).map_err(bad_yaml)?))
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The `data` field of a tensor: either one description for the whole tensor or one per channel
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+#[serde(try_from = "serde_yaml::Value")]
+pub enum TensorData {
+    Whole(TensorDataDescr),
+    PerChannel(NonEmptyList<TensorDataDescr>),
+}
+
+impl TensorData {
+    pub fn descrs(&self) -> Vec<&TensorDataDescr> {
+        match self {
+            Self::Whole(descr) => vec![descr],
+            Self::PerChannel(descrs) => descrs.iter().collect(),
+        }
+    }
+
+    /// Per-channel descriptions need one entry per channel; a tensor without a channel axis has one channel
+    pub fn check_channel_count(&self, channels: Option<usize>) -> Result<(), TensorDataParsingError> {
+        let Self::PerChannel(descrs) = self else {
+            return Ok(());
+        };
+        let channels = channels.unwrap_or(1);
+        let descriptions = descrs.iter().count();
+        if channels != descriptions {
+            return Err(TensorDataParsingError::ChannelCountMismatch { channels, descriptions });
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<serde_yaml::Value> for TensorData {
+    type Error = TensorDataParsingError;
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        let serde_yaml::Value::Sequence(descrs) = value else {
+            return Ok(Self::Whole(value.try_into()?));
+        };
+        let descrs = descrs.into_iter().map(TensorDataDescr::try_from).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::PerChannel(descrs.try_into().map_err(|_| TensorDataParsingError::NoDescriptions)?))
+    }
+}
+
+/// The values categorical data can take
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
 pub enum TVs {
     Ints(NonEmptyList<i64>),
-    Floats(NonEmptyList<f32>),
+    Floats(NonEmptyList<f64>),
     Bools(NonEmptyList<bool>),
     Strings(NonEmptyList<String>),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
 pub enum TensorDataUnit {
+    #[default]
     ArbitraryUnit,
     Si(SiUnit),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl TryFrom<String> for TensorDataUnit {
+    type Error = SiUnitParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.trim() == ARBITRARY_UNIT {
+            return Ok(Self::ArbitraryUnit);
+        }
+        Ok(Self::Si(value.try_into()?))
+    }
+}
+
+impl From<TensorDataUnit> for String {
+    fn from(value: TensorDataUnit) -> Self {
+        match value {
+            TensorDataUnit::ArbitraryUnit => ARBITRARY_UNIT.to_owned(),
+            TensorDataUnit::Si(unit) => unit.into(),
+        }
+    }
+}
+
+/// Categorical data, e.g. class labels, where only the listed values can occur.
+/// Strings are the names of values stored as their index in `values`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawNominalOrOrdinalDataDescr")]
 pub struct NominalOrOrdinalDataDescr {
     pub values: TVs,
     #[serde(rename = "type")]
-    #[serde(default = "_default_data_type")]
     pub data_type: DataType,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub unit: Option<TensorDataUnit>,
 }
 
+#[derive(Deserialize)]
+struct RawNominalOrOrdinalDataDescr {
+    values: TVs,
+    #[serde(rename = "type")]
+    #[serde(default = "_default_data_type")]
+    data_type: DataType,
+    #[serde(default)]
+    unit: Option<TensorDataUnit>,
+}
+
+impl TryFrom<RawNominalOrOrdinalDataDescr> for NominalOrOrdinalDataDescr {
+    type Error = TensorDataParsingError;
+    fn try_from(raw: RawNominalOrOrdinalDataDescr) -> Result<Self, Self::Error> {
+        let data_type = raw.data_type;
+        let is_float = matches!(data_type, DataType::Float32 | DataType::Float64);
+        let is_integer = !is_float && data_type != DataType::Bool;
+        let (lowest, highest) = data_type.value_range();
+        let (fits, kind) = match &raw.values {
+            TVs::Ints(values) => (is_integer && values.iter().all(|v| (lowest..=highest).contains(&(*v as f64))), "Integer"),
+            TVs::Floats(_) => (is_float, "Float"),
+            TVs::Bools(_) => (data_type == DataType::Bool, "Boolean"),
+            TVs::Strings(_) => (is_integer, "String"),
+        };
+        if !fits {
+            return Err(TensorDataParsingError::ValuesDontFit { data_type, kind });
+        }
+        Ok(Self {
+            values: raw.values,
+            data_type,
+            unit: raw.unit,
+        })
+    }
+}
+
 fn _default_data_type() -> DataType {
     DataType::Uint8
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Data measured on a scale. The physical value of a tensor entry `x` is `x * scale + offset`, in `unit`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "RawIntervalOrRatioDataDescr")]
 pub struct IntervalOrRatioDataDescr {
     #[serde(rename = "type")]
+    pub data_type: DataType,
+    pub range: DataRange,
+    pub unit: TensorDataUnit,
+    pub scale: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawIntervalOrRatioDataDescr {
+    #[serde(rename = "type")]
+    #[serde(default = "_default_interval_data_type")]
     data_type: DataType,
-    range: (Option<f32>, Option<f32>),
+    #[serde(default)]
+    range: DataRange,
+    #[serde(default)]
     unit: TensorDataUnit,
     #[serde(default = "_default_scale")]
-    scale: f32,
+    scale: f64,
     #[serde(default)]
-    offset: Option<f32>,
+    offset: Option<f64>,
+}
+
+impl TryFrom<RawIntervalOrRatioDataDescr> for IntervalOrRatioDataDescr {
+    type Error = TensorDataParsingError;
+    fn try_from(raw: RawIntervalOrRatioDataDescr) -> Result<Self, Self::Error> {
+        if raw.data_type == DataType::Bool {
+            return Err(TensorDataParsingError::BoolInterval);
+        }
+        raw.range.validate_for(raw.data_type)?;
+        Ok(Self {
+            data_type: raw.data_type,
+            range: raw.range,
+            unit: raw.unit,
+            scale: raw.scale,
+            offset: raw.offset,
+        })
+    }
 }
 
-fn _default_scale() -> f32 {
+fn _default_interval_data_type() -> DataType {
+    DataType::Float32
+}
+
+fn _default_scale() -> f64 {
     1.0
 }
+
+#[test]
+fn test_tensor_data_parsing() {
+    let parse = |raw: &str| serde_yaml::from_str::<TensorData>(raw);
+
+    let data = parse("{type: uint16, range: [0, 4095], unit: µm, scale: 0.5}").unwrap();
+    let TensorData::Whole(TensorDataDescr::IntervalOrRatio(descr)) = &data else {
+        panic!("expected interval data, found {data:?}");
+    };
+    assert!(matches!(&descr.unit, TensorDataUnit::Si(unit) if unit.to_string() == "µm"));
+    assert_eq!(parse(&serde_yaml::to_string(&data).unwrap()).unwrap(), data);
+
+    let defaults = parse("{}").unwrap();
+    assert_eq!(defaults.descrs()[0].data_type(), DataType::Float32);
+    assert!(serde_yaml::to_string(&defaults).unwrap().contains("arbitrary unit"));
+
+    let per_channel = parse("[{values: [background, nucleus]}, {type: float32}]").unwrap();
+    assert_eq!(per_channel.descrs()[0].data_type(), DataType::Uint8);
+    per_channel.check_channel_count(Some(2)).unwrap();
+    assert_eq!(
+        per_channel.check_channel_count(None),
+        Err(TensorDataParsingError::ChannelCountMismatch { channels: 1, descriptions: 2 })
+    );
+
+    assert!(parse("{values: [0, 300], type: uint8}").unwrap_err().to_string().contains("can't be stored as 'uint8'"));
+    assert!(parse("{values: [0.5, 1.5], type: int32}").is_err());
+    assert!(parse("{values: [true, false], type: bool}").is_ok());
+    assert!(parse("{type: bool}").unwrap_err().to_string().contains("list its values"));
+    assert!(parse("{type: uint8, range: [-1, null]}").is_err());
+    assert!(parse("{unit: micrometer}").is_err());
+    assert!(parse("[]").is_err());
+}
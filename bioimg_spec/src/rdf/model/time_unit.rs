@@ -4,6 +4,11 @@ use serde::{Serialize, Deserialize};
     Default, Serialize, Deserialize, Eq, PartialEq, Debug, Copy, Clone, strum::VariantArray, strum::VariantNames, strum::Display
 )]
 pub enum TimeUnit{
+    /// A unit that isn't physical, e.g. for simulated data. The `scale` of the axis is then in
+    /// multiples of it, and it can't be converted to seconds.
+    #[serde(rename = "arbitrary unit")]
+    #[strum(to_string = "arbitrary unit")]
+    ArbitraryUnit,
     #[serde(rename = "attosecond")]
     #[strum(to_string = "attosecond")]
     Attosecond,
@@ -75,3 +80,74 @@ pub enum TimeUnit{
     #[strum(to_string = "zettasecond")]
     Zettasecond,
 }
+
+impl TimeUnit {
+    /// How many seconds one of this unit is, or `None` for [Self::ArbitraryUnit]. Multiplied with
+    /// the `scale` of an axis, it gives the physical size of one step along it.
+    pub fn to_seconds(self) -> Option<f64> {
+        Some(match self {
+            Self::ArbitraryUnit => return None,
+            Self::Attosecond => 1e-18,
+            Self::Centisecond => 1e-2,
+            Self::Day => 86400.0,
+            Self::Decisecond => 1e-1,
+            Self::Exasecond => 1e18,
+            Self::Femtosecond => 1e-15,
+            Self::Gigasecond => 1e9,
+            Self::Hectosecond => 1e2,
+            Self::Hour => 3600.0,
+            Self::Kilosecond => 1e3,
+            Self::Megasecond => 1e6,
+            Self::Microsecond => 1e-6,
+            Self::Millisecond => 1e-3,
+            Self::Minute => 60.0,
+            Self::Nanosecond => 1e-9,
+            Self::Petasecond => 1e15,
+            Self::Picosecond => 1e-12,
+            Self::Second => 1.0,
+            Self::Terasecond => 1e12,
+            Self::Yoctosecond => 1e-24,
+            Self::Yottasecond => 1e24,
+            Self::Zeptosecond => 1e-21,
+            Self::Zettasecond => 1e21,
+        })
+    }
+
+    /// The abbreviation of the unit, like the ones written after a number
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::ArbitraryUnit => "a.u.",
+            Self::Attosecond => "as",
+            Self::Centisecond => "cs",
+            Self::Day => "d",
+            Self::Decisecond => "ds",
+            Self::Exasecond => "Es",
+            Self::Femtosecond => "fs",
+            Self::Gigasecond => "Gs",
+            Self::Hectosecond => "hs",
+            Self::Hour => "h",
+            Self::Kilosecond => "ks",
+            Self::Megasecond => "Ms",
+            Self::Microsecond => "µs",
+            Self::Millisecond => "ms",
+            Self::Minute => "min",
+            Self::Nanosecond => "ns",
+            Self::Petasecond => "Ps",
+            Self::Picosecond => "ps",
+            Self::Second => "s",
+            Self::Terasecond => "Ts",
+            Self::Yoctosecond => "ys",
+            Self::Yottasecond => "Ys",
+            Self::Zeptosecond => "zs",
+            Self::Zettasecond => "Zs",
+        }
+    }
+}
+
+#[test]
+fn test_time_unit_conversion() {
+    assert_eq!(TimeUnit::Hour.to_seconds(), Some(3600.0));
+    assert_eq!(TimeUnit::Millisecond.to_seconds(), Some(1e-3));
+    assert_eq!(TimeUnit::Microsecond.symbol(), "µs");
+    assert_eq!(serde_yaml::from_str::<TimeUnit>("arbitrary unit").unwrap().to_seconds(), None);
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdf::{dataset::DatasetRdf, LinkedResource};
+
+/// The data a model was trained on
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum TrainingData {
+    /// A dataset described in place, e.g. because it isn't in the zoo. Tried first, since an inline
+    /// dataset can have an `id` too.
+    Inline(Box<DatasetRdf>),
+    /// A dataset in the zoo
+    Linked(LinkedResource),
+}
+
+#[test]
+fn test_training_data_serde() {
+    let linked: TrainingData = serde_yaml::from_str("id: affable-shark-data\nversion_number: 1\n").unwrap();
+    let TrainingData::Linked(dataset) = &linked else {
+        panic!("expected a linked dataset, got {linked:?}");
+    };
+    assert_eq!((dataset.id.as_str(), dataset.version_number), ("affable-shark-data", Some(1)));
+
+    let raw = "type: dataset\nformat_version: 0.2.3\nid: nuclei\nname: nuclei\ndescription: Some nuclei\nsource: data.zip\n";
+    let inline: TrainingData = serde_yaml::from_str(raw).unwrap();
+    let TrainingData::Inline(dataset) = &inline else {
+        panic!("expected an inline dataset, got {inline:?}");
+    };
+    assert_eq!(dataset.base.name.to_string(), "nuclei");
+    let written = serde_yaml::to_string(&inline).unwrap();
+    assert_eq!(serde_yaml::from_str::<TrainingData>(&written).unwrap(), inline);
+
+    assert!(serde_yaml::from_str::<TrainingData>("name: no id and no type\n").is_err());
+}
@@ -0,0 +1,1033 @@
+//! Format 0.4 model descriptions, which most published models still use, and their upgrade to 0.5
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::rdf::{
+    author::Author2, badge::Badge, bounded_string::BoundedString, cite_entry::CiteEntry2, file_reference::FileDescr,
+    file_reference::FileReference, identifier::Identifier, maintainer::Maintainer, non_empty_list::NonEmptyList, sha256::Sha256,
+    validation::{spec_path, Severity, ValidationReport},
+    version::MinimumVersion, version_history::VersionHistory, LinkedResource, ResourceId, SpdxLicense, Version,
+};
+use crate::util::SingleOrMultiple;
+
+use super::{
+    axes::{AxisId, BatchAxis, ChannelAxis, IndexAxis, InputAxis, OutputAxis, SpaceInputAxis, SpaceOutputAxis},
+    axes::{TimeInputAxis, TimeOutputAxis},
+    data_range::{DataRange, DataRangeError},
+    data_type::DataType,
+    postprocessing::Postprocessing,
+    preprocessing::{BinarizeKwargs, ClipKwargs, FixedZeroMeanUnitVarianceKwargs, Preprocessing, ScaleLinearKwargs},
+    preprocessing::{ScaleMeanVarianceKwargs, ScaleRangeKwargs, ZeroMeanUnitVarianceKwargs},
+    run_mode::RunMode,
+    training_data::TrainingData,
+    tensor_data_descr::{TVs, TensorData, TensorDataDescr, TensorDataUnit},
+    weights::{ArchitectureDescr, FrameworkVersion, KerasHdf5Weights, OnnxWeights, PytorchStateDictWeights, WeightsEntry},
+    weights::{TensorflowJsWeights, TensorflowSavedModelBundleWeights, TorchscriptWeights, WeightsDescr, WeightsFormat},
+    AnyAxisSize, AxisScale, AxisSizeReference, InputTensorDescr2, ModelRdf, ModelType, OutputTensorDescr, ParameterizedAxisSize,
+};
+
+/// The format version upgraded models are written with
+pub const UPGRADED_FORMAT_VERSION: Version = Version {
+    major: 0,
+    minor: 5,
+    patch: 3,
+};
+
+/// Framework versions became mandatory in 0.5; these are used when a 0.4 model didn't state one
+pub const FALLBACK_PYTORCH_VERSION: &str = "1.10";
+pub const FALLBACK_TENSORFLOW_VERSION: &str = "1.15";
+pub const FALLBACK_OPSET_VERSION: u32 = 15;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ModelUpgradeError {
+    #[error("Tensor name '{0}' is not a valid 0.5 tensor id")]
+    BadTensorName(String),
+    #[error("Tensor '{tensor}' has axes '{axes}' but a shape with {shape_len} entries")]
+    ShapeLengthMismatch { tensor: String, axes: String, shape_len: usize },
+    #[error("Tensor '{tensor}' has an unknown axis '{letter}'")]
+    UnknownAxis { tensor: String, letter: char },
+    #[error("Channel axis of tensor '{0}' needs a fixed size")]
+    VariableChannelCount(String),
+    #[error("Axis '{letter}' of tensor '{tensor}' has a halo, but its size doesn't depend on an input")]
+    HaloOnFixedAxis { tensor: String, letter: char },
+    #[error("Axis '{letter}' of tensor '{tensor}' has size offset {offset}, which is not a non-negative integer")]
+    BadOffset { tensor: String, letter: char, offset: f64 },
+    #[error("Axis '{letter}' of tensor '{tensor}' has scale {scale}, which can't be expressed in 0.5")]
+    BadScale { tensor: String, letter: char, scale: f64 },
+    #[error("Tensor '{tensor}' refers to tensor '{reference}', which is not an input")]
+    MissingReference { tensor: String, reference: String },
+    #[error("Tensor '{0}' has no test tensor")]
+    MissingTestTensor(String),
+    #[error("Processing step '{step}' of tensor '{tensor}' has no 0.5 equivalent")]
+    UnsupportedProcessing { tensor: String, step: String },
+    #[error("Bad architecture '{0}', expected 'file.py:Callable' or 'module.Callable'")]
+    BadArchitecture(String),
+    #[error("Weights '{0}' can't be upgraded: {1}")]
+    BadWeights(WeightsFormat, String),
+    #[error("Data range of tensor '{tensor}' is not valid: {source}")]
+    BadDataRange { tensor: String, source: DataRangeError },
+    #[error("{0}")]
+    BadModel(String),
+}
+
+/// How big a 0.4 input tensor is along each of its axes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum InputShapeV04 {
+    Fixed(Vec<usize>),
+    Parameterized { min: Vec<usize>, step: Vec<usize> },
+}
+
+/// How big a 0.4 output tensor is along each of its axes: `reference * scale + 2 * offset` when
+/// it is implicit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum OutputShapeV04 {
+    Fixed(Vec<usize>),
+    Implicit {
+        reference_tensor: String,
+        scale: Vec<Option<f64>>,
+        offset: Vec<f64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "name", content = "kwargs", rename_all = "snake_case")]
+pub enum ProcessingV04 {
+    Binarize {
+        threshold: f64,
+    },
+    Clip {
+        min: f64,
+        max: f64,
+    },
+    ScaleLinear {
+        #[serde(default)]
+        axes: Option<String>,
+        #[serde(default = "_default_gain")]
+        gain: SingleOrMultiple<f64>,
+        #[serde(default = "_default_offset")]
+        offset: SingleOrMultiple<f64>,
+    },
+    ScaleRange {
+        mode: String,
+        #[serde(default)]
+        axes: Option<String>,
+        #[serde(default = "_default_eps")]
+        eps: f64,
+        #[serde(default = "_default_max_percentile")]
+        max_percentile: f64,
+        #[serde(default)]
+        min_percentile: f64,
+        #[serde(default)]
+        reference_tensor: Option<String>,
+    },
+    Sigmoid,
+    ZeroMeanUnitVariance {
+        mode: String,
+        #[serde(default)]
+        axes: Option<String>,
+        #[serde(default = "_default_eps")]
+        eps: f64,
+        #[serde(default)]
+        mean: Option<SingleOrMultiple<f64>>,
+        #[serde(default)]
+        std: Option<SingleOrMultiple<f64>>,
+    },
+    ScaleMeanVariance {
+        mode: String,
+        reference_tensor: String,
+        #[serde(default)]
+        axes: Option<String>,
+        #[serde(default = "_default_eps")]
+        eps: f64,
+    },
+}
+
+const fn _default_gain() -> SingleOrMultiple<f64> {
+    SingleOrMultiple::Single(1.0)
+}
+
+const fn _default_offset() -> SingleOrMultiple<f64> {
+    SingleOrMultiple::Single(0.0)
+}
+
+const fn _default_eps() -> f64 {
+    10E-6
+}
+
+const fn _default_max_percentile() -> f64 {
+    100f64
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InputTensorV04 {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub axes: String,
+    pub data_type: DataType,
+    #[serde(default)]
+    pub data_range: Option<DataRange>,
+    pub shape: InputShapeV04,
+    #[serde(default)]
+    pub preprocessing: Vec<ProcessingV04>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OutputTensorV04 {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub axes: String,
+    pub data_type: DataType,
+    #[serde(default)]
+    pub data_range: Option<DataRange>,
+    pub shape: OutputShapeV04,
+    #[serde(default)]
+    pub halo: Option<Vec<usize>>,
+    #[serde(default)]
+    pub postprocessing: Vec<ProcessingV04>,
+}
+
+/// The weights of one format. 0.4 used the same fields for every format, only some of which
+/// apply to each.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WeightsEntryV04 {
+    pub source: FileReference,
+    #[serde(default)]
+    pub sha256: Option<Sha256>,
+    #[serde(default)]
+    pub authors: Vec<Author2>,
+    #[serde(default)]
+    pub parent: Option<WeightsFormat>,
+    #[serde(default)]
+    pub architecture: Option<String>,
+    #[serde(default)]
+    pub architecture_sha256: Option<Sha256>,
+    #[serde(default)]
+    pub kwargs: serde_yaml::Mapping,
+    #[serde(default)]
+    pub pytorch_version: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub tensorflow_version: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub opset_version: Option<u32>,
+    #[serde(default)]
+    pub dependencies: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct AttachmentsV04 {
+    #[serde(default)]
+    pub files: Vec<FileReference>,
+}
+
+/// A model description of format 0.4
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ModelRdfV04 {
+    pub format_version: MinimumVersion<0, 4, 0>,
+    pub name: BoundedString<1, 1023>,
+    pub description: BoundedString<1, 1023>,
+    pub authors: NonEmptyList<Author2>,
+    #[serde(default)]
+    pub badges: Vec<Badge>,
+    pub cite: NonEmptyList<CiteEntry2>,
+    pub documentation: FileReference,
+    pub license: SpdxLicense,
+    pub inputs: NonEmptyList<InputTensorV04>,
+    pub outputs: NonEmptyList<OutputTensorV04>,
+    pub test_inputs: Vec<FileReference>,
+    pub test_outputs: Vec<FileReference>,
+    #[serde(default)]
+    pub sample_inputs: Vec<FileReference>,
+    #[serde(default)]
+    pub sample_outputs: Vec<FileReference>,
+    pub weights: BTreeMap<WeightsFormat, WeightsEntryV04>,
+    #[serde(default)]
+    pub attachments: Option<AttachmentsV04>,
+    #[serde(default)]
+    pub config: Option<serde_yaml::Mapping>,
+    #[serde(default)]
+    pub covers: Vec<FileReference>,
+    #[serde(default)]
+    pub git_repo: Option<Url>,
+    #[serde(default)]
+    pub icon: Option<BoundedString<1, 1023>>,
+    #[serde(default)]
+    pub id: Option<BoundedString<1, 1023>>,
+    #[serde(default)]
+    pub links: Vec<ResourceId>,
+    #[serde(default)]
+    pub maintainers: Vec<Maintainer>,
+    #[serde(default)]
+    pub parent: Option<LinkedResource>,
+    #[serde(default)]
+    pub packaged_by: Vec<Author2>,
+    #[serde(default)]
+    pub run_mode: Option<RunMode>,
+    #[serde(default)]
+    pub tags: Vec<BoundedString<1, 1023>>,
+    #[serde(default)]
+    pub training_data: Option<TrainingData>,
+    /// Often written without a timezone, which 0.5 requires; those are taken to be in UTC
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub version: Option<Version>,
+}
+
+fn upgrade_err(err: impl std::fmt::Display) -> ModelUpgradeError {
+    ModelUpgradeError::BadModel(err.to_string())
+}
+
+fn axis_id(letter: char) -> AxisId {
+    let id = match letter {
+        'b' => "batch",
+        'c' => "channel",
+        'i' => "index",
+        't' => "time",
+        other => return AxisId::try_from(other.to_string()).expect("single letters are valid axis ids"),
+    };
+    AxisId::try_from(id.to_owned()).expect("axis names are valid axis ids")
+}
+
+fn axis_ids(axes: &Option<String>) -> Option<Vec<AxisId>> {
+    axes.as_ref().map(|axes| axes.chars().map(axis_id).collect())
+}
+
+fn channel_names(count: usize) -> Vec<Identifier<String>> {
+    (0..count)
+        .map(|idx| Identifier::try_from(format!("channel{idx}")).expect("channel names are valid identifiers"))
+        .collect()
+}
+
+fn description(description: &Option<String>) -> Result<BoundedString<0, 128>, ModelUpgradeError> {
+    BoundedString::try_from(description.clone().unwrap_or_default()).map_err(upgrade_err)
+}
+
+/// The 0.5 input axes for the axes letters and shape of a 0.4 input tensor
+pub fn upgrade_input_axes(tensor: &str, letters: &str, shape: &InputShapeV04) -> Result<Vec<InputAxis>, ModelUpgradeError> {
+    let num_axes = letters.chars().count();
+    let sizes: Vec<(usize, usize)> = match shape {
+        InputShapeV04::Fixed(sizes) => sizes.iter().map(|size| (*size, 0)).collect(),
+        InputShapeV04::Parameterized { min, step } => min.iter().copied().zip(step.iter().copied()).collect(),
+    };
+    let shape_len = match shape {
+        InputShapeV04::Fixed(sizes) => sizes.len(),
+        InputShapeV04::Parameterized { min, step } => min.len().max(step.len()),
+    };
+    if sizes.len() != num_axes || shape_len != num_axes {
+        return Err(ModelUpgradeError::ShapeLengthMismatch {
+            tensor: tensor.to_owned(),
+            axes: letters.to_owned(),
+            shape_len,
+        });
+    }
+    letters
+        .chars()
+        .zip(sizes)
+        .map(|(letter, (min, step))| {
+            let bad_size = || upgrade_err(format!("axis '{letter}' of tensor '{tensor}' has size 0"));
+            let min = min.try_into().map_err(|_| bad_size())?;
+            let size = match step.try_into() {
+                Ok(step) => AnyAxisSize::Parameterized(ParameterizedAxisSize { min, step }),
+                Err(_) => AnyAxisSize::Fixed(min),
+            };
+            let id = axis_id(letter);
+            Ok(match letter {
+                'b' => InputAxis::Batch(BatchAxis {
+                    id,
+                    description: Default::default(),
+                    size: None,
+                }),
+                'c' => match size {
+                    AnyAxisSize::Fixed(count) => InputAxis::Channel(ChannelAxis {
+                        id,
+                        description: Default::default(),
+                        channel_names: channel_names(count.get()),
+                    }),
+                    _ => return Err(ModelUpgradeError::VariableChannelCount(tensor.to_owned())),
+                },
+                'i' => InputAxis::Index(IndexAxis {
+                    id,
+                    description: Default::default(),
+                    size,
+                }),
+                't' => InputAxis::Time(TimeInputAxis {
+                    id,
+                    description: Default::default(),
+                    unit: None,
+                    scale: AxisScale::default(),
+                    size,
+                }),
+                'x' | 'y' | 'z' => InputAxis::Space(SpaceInputAxis {
+                    id,
+                    description: Default::default(),
+                    unit: None,
+                    scale: AxisScale::default(),
+                    size,
+                }),
+                letter => {
+                    return Err(ModelUpgradeError::UnknownAxis {
+                        tensor: tensor.to_owned(),
+                        letter,
+                    })
+                }
+            })
+        })
+        .collect()
+}
+
+/// The 0.5 output axes of a 0.4 output tensor, whose shape may be relative to one of `inputs`
+fn upgrade_output_axes(output: &OutputTensorV04, inputs: &[InputTensorDescr2]) -> Result<Vec<OutputAxis>, ModelUpgradeError> {
+    let tensor = output.name.as_str();
+    let num_axes = output.axes.chars().count();
+    let halo = output.halo.clone().unwrap_or_else(|| vec![0; num_axes]);
+    let mismatch = |shape_len| ModelUpgradeError::ShapeLengthMismatch {
+        tensor: tensor.to_owned(),
+        axes: output.axes.clone(),
+        shape_len,
+    };
+    if halo.len() != num_axes {
+        return Err(mismatch(halo.len()));
+    }
+
+    // each axis is either fixed, or a reference with the output axis scale relative to the input
+    let sizes: Vec<Result<AnyAxisSize, (AxisSizeReference, f64)>> = match &output.shape {
+        OutputShapeV04::Fixed(sizes) if sizes.len() == num_axes => sizes
+            .iter()
+            .map(|size| (*size).try_into().map(AnyAxisSize::Fixed).map_err(upgrade_err).map(Ok))
+            .collect::<Result<_, _>>()?,
+        OutputShapeV04::Fixed(sizes) => return Err(mismatch(sizes.len())),
+        OutputShapeV04::Implicit { scale, offset, .. } if scale.len() != num_axes || offset.len() != num_axes => {
+            return Err(mismatch(scale.len().max(offset.len())))
+        }
+        OutputShapeV04::Implicit {
+            reference_tensor,
+            scale,
+            offset,
+        } => {
+            let reference = inputs.iter().find(|input| *input.id == *reference_tensor).ok_or_else(|| {
+                ModelUpgradeError::MissingReference {
+                    tensor: tensor.to_owned(),
+                    reference: reference_tensor.clone(),
+                }
+            })?;
+            output
+                .axes
+                .chars()
+                .zip(scale.iter().zip(offset))
+                .map(|(letter, (scale, offset))| {
+                    let double_offset = 2.0 * offset;
+                    if double_offset < 0.0 || double_offset.fract() != 0.0 {
+                        return Err(ModelUpgradeError::BadOffset {
+                            tensor: tensor.to_owned(),
+                            letter,
+                            offset: *offset,
+                        });
+                    }
+                    let scale = scale.unwrap_or(0.0);
+                    let reference_axis = reference.axes.iter().find(|axis| **axis.id() == *axis_id(letter));
+                    let reference_axis = match reference_axis {
+                        _ if scale == 0.0 => {
+                            let size = (double_offset as usize).try_into().map_err(upgrade_err)?;
+                            return Ok(Ok(AnyAxisSize::Fixed(size)));
+                        }
+                        Some(InputAxis::Channel(channel)) if scale == 1.0 => {
+                            let size = (channel.channel_names.len() + double_offset as usize)
+                                .try_into()
+                                .map_err(upgrade_err)?;
+                            return Ok(Ok(AnyAxisSize::Fixed(size)));
+                        }
+                        Some(reference_axis) if scale > 0.0 && scale.is_finite() => reference_axis,
+                        _ => {
+                            return Err(ModelUpgradeError::BadScale {
+                                tensor: tensor.to_owned(),
+                                letter,
+                                scale,
+                            })
+                        }
+                    };
+                    let reference = AxisSizeReference {
+                        tensor_id: reference.id.clone(),
+                        axis_id: reference_axis.id().clone(),
+                        offset: double_offset as usize,
+                    };
+                    Ok(Err((reference, scale)))
+                })
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    output
+        .axes
+        .chars()
+        .zip(sizes)
+        .zip(halo)
+        .map(|((letter, size), halo)| {
+            let id = axis_id(letter);
+            let (size, scale) = match size {
+                Ok(_) if halo > 0 => {
+                    return Err(ModelUpgradeError::HaloOnFixedAxis {
+                        tensor: tensor.to_owned(),
+                        letter,
+                    })
+                }
+                Ok(size) => (size, AxisScale::default()),
+                // 0.5 divides the reference size by the scale of the referring axis
+                Err((reference, scale)) => {
+                    let axis_scale = AxisScale::try_from((1.0 / scale) as f32).map_err(upgrade_err)?;
+                    (AnyAxisSize::Reference(reference), axis_scale)
+                }
+            };
+            Ok(match letter {
+                'b' => OutputAxis::Batch(BatchAxis {
+                    id,
+                    description: Default::default(),
+                    size: None,
+                }),
+                'c' => match size {
+                    AnyAxisSize::Fixed(count) => OutputAxis::Channel(ChannelAxis {
+                        id,
+                        description: Default::default(),
+                        channel_names: channel_names(count.get()),
+                    }),
+                    _ => return Err(ModelUpgradeError::VariableChannelCount(tensor.to_owned())),
+                },
+                'i' => OutputAxis::Index(IndexAxis {
+                    id,
+                    description: Default::default(),
+                    size,
+                }),
+                't' => OutputAxis::Time(TimeOutputAxis {
+                    base: TimeInputAxis {
+                        id,
+                        description: Default::default(),
+                        unit: None,
+                        scale,
+                        size,
+                    },
+                    halo,
+                }),
+                'x' | 'y' | 'z' => OutputAxis::Space(SpaceOutputAxis {
+                    base: SpaceInputAxis {
+                        id,
+                        description: Default::default(),
+                        unit: None,
+                        scale,
+                        size,
+                    },
+                    halo,
+                }),
+                letter => {
+                    return Err(ModelUpgradeError::UnknownAxis {
+                        tensor: tensor.to_owned(),
+                        letter,
+                    })
+                }
+            })
+        })
+        .collect()
+}
+
+fn tensor_id(name: &str) -> Result<super::tensor_id::TensorId, ModelUpgradeError> {
+    name.to_owned()
+        .try_into()
+        .map_err(|_| ModelUpgradeError::BadTensorName(name.to_owned()))
+}
+
+fn upgrade_processing(tensor: &str, step: &ProcessingV04) -> Result<Postprocessing, ModelUpgradeError> {
+    let unsupported = |step: &str| ModelUpgradeError::UnsupportedProcessing {
+        tensor: tensor.to_owned(),
+        step: step.to_owned(),
+    };
+    Ok(match step.clone() {
+        ProcessingV04::Binarize { threshold } => Postprocessing::Binarize(BinarizeKwargs {
+            threshold: SingleOrMultiple::Single(threshold),
+            axis: None,
+        }),
+        ProcessingV04::Clip { min, max } => Postprocessing::Clip(ClipKwargs { min, max }),
+        ProcessingV04::ScaleLinear { axes, gain, offset } => {
+            // 0.5 scales along a single axis; 0.4 listed the axes the parameters don't vary along
+            let per_channel = matches!(gain, SingleOrMultiple::Multiple(_)) || matches!(offset, SingleOrMultiple::Multiple(_));
+            let axis = per_channel.then(|| AxisId::try_from("channel".to_owned()).expect("valid axis id"));
+            if per_channel && axes.is_some_and(|axes| axes.contains('c')) {
+                return Err(unsupported("scale_linear"));
+            }
+            Postprocessing::ScaleLinear(ScaleLinearKwargs { axis, gain, offset })
+        }
+        ProcessingV04::ScaleRange {
+            mode,
+            axes,
+            eps,
+            max_percentile,
+            min_percentile,
+            reference_tensor,
+        } => {
+            if mode != "per_sample" {
+                return Err(unsupported(&format!("scale_range with mode '{mode}'")));
+            }
+            Postprocessing::ScaleRange(ScaleRangeKwargs {
+                axes: axis_ids(&axes),
+                eps,
+                max_percentile,
+                min_percentile,
+                reference_tensor: reference_tensor.as_deref().map(tensor_id).transpose()?,
+            })
+        }
+        ProcessingV04::Sigmoid => Postprocessing::Sigmoid,
+        ProcessingV04::ZeroMeanUnitVariance {
+            mode,
+            axes,
+            eps,
+            mean,
+            std,
+        } => match (mode.as_str(), mean, std) {
+            ("per_sample", _, _) => Postprocessing::ZeroMeanUnitVariance(ZeroMeanUnitVarianceKwargs {
+                axes: axis_ids(&axes),
+                eps,
+            }),
+            ("fixed", Some(mean), Some(std)) => {
+                let per_channel = matches!(mean, SingleOrMultiple::Multiple(_));
+                let axis = per_channel.then(|| AxisId::try_from("channel".to_owned()).expect("valid axis id"));
+                Postprocessing::FixedZeroMeanUnitVariance(FixedZeroMeanUnitVarianceKwargs { axis, mean, std })
+            }
+            (mode, _, _) => return Err(unsupported(&format!("zero_mean_unit_variance with mode '{mode}'"))),
+        },
+        ProcessingV04::ScaleMeanVariance {
+            mode,
+            reference_tensor,
+            axes,
+            eps,
+        } => {
+            if mode != "per_sample" {
+                return Err(unsupported(&format!("scale_mean_variance with mode '{mode}'")));
+            }
+            Postprocessing::ScaleMeanVariance(ScaleMeanVarianceKwargs {
+                reference_tensor: tensor_id(&reference_tensor)?,
+                axes: axis_ids(&axes),
+                eps,
+            })
+        }
+    })
+}
+
+/// 0.4 only had a data type and range, which become interval data in an arbitrary unit. Booleans
+/// can't be interval data, so they become the categorical values true and false.
+fn data_description(tensor: &str, data_type: &DataType, data_range: &Option<DataRange>) -> Result<TensorData, ModelUpgradeError> {
+    let bad_range = |source| ModelUpgradeError::BadDataRange {
+        tensor: tensor.to_owned(),
+        source,
+    };
+    let range = data_range.unwrap_or_default();
+    range.validate_for(*data_type).map_err(bad_range)?;
+    let descr = match data_type {
+        DataType::Bool => {
+            let values = TVs::Bools(vec![false, true].try_into().expect("list is not empty"));
+            TensorDataDescr::nominal_or_ordinal(values, DataType::Bool).map_err(upgrade_err)?
+        }
+        _ => TensorDataDescr::interval_or_ratio(*data_type, range, TensorDataUnit::ArbitraryUnit).map_err(upgrade_err)?,
+    };
+    Ok(TensorData::Whole(descr))
+}
+
+fn test_tensor(files: &[FileReference], idx: usize, tensor: &str) -> Result<FileDescr, ModelUpgradeError> {
+    let source = files
+        .get(idx)
+        .cloned()
+        .ok_or_else(|| ModelUpgradeError::MissingTestTensor(tensor.to_owned()))?;
+    Ok(FileDescr { source, sha256: None })
+}
+
+fn sample_tensor(files: &[FileReference], idx: usize) -> Option<FileDescr> {
+    files.get(idx).cloned().map(|source| FileDescr { source, sha256: None })
+}
+
+fn framework_version(raw: &Option<serde_yaml::Value>, fallback: &str) -> Result<FrameworkVersion, String> {
+    let version = match raw {
+        // versions like 1.10 are often written unquoted and read as numbers
+        Some(serde_yaml::Value::Number(number)) => number.to_string(),
+        Some(serde_yaml::Value::String(version)) => version.clone(),
+        Some(other) => return Err(format!("bad version {other:?}")),
+        None => fallback.to_owned(),
+    };
+    FrameworkVersion::try_from(version).map_err(|err| err.to_string())
+}
+
+fn upgrade_architecture(entry: &WeightsEntryV04) -> Result<ArchitectureDescr, ModelUpgradeError> {
+    let raw = entry.architecture.clone().unwrap_or_default();
+    let bad_architecture = || ModelUpgradeError::BadArchitecture(raw.clone());
+    let callable = |name: &str| Identifier::try_from(name.to_owned()).map_err(|_| bad_architecture());
+    if let Some((file, name)) = raw.rsplit_once(':') {
+        let source = serde_yaml::from_value(file.into()).map_err(|_| bad_architecture())?;
+        return Ok(ArchitectureDescr::FromFile {
+            source,
+            sha256: entry.architecture_sha256,
+            callable: callable(name)?,
+            kwargs: entry.kwargs.clone(),
+        });
+    }
+    let (module, name) = raw.rsplit_once('.').ok_or_else(bad_architecture)?;
+    Ok(ArchitectureDescr::FromLibrary {
+        import_from: BoundedString::try_from(module.to_owned()).map_err(|_| bad_architecture())?,
+        callable: callable(name)?,
+        kwargs: entry.kwargs.clone(),
+    })
+}
+
+fn upgrade_weights(weights: BTreeMap<WeightsFormat, WeightsEntryV04>) -> Result<WeightsDescr, ModelUpgradeError> {
+    let mut upgraded = WeightsDescr {
+        keras_hdf5: None,
+        onnx: None,
+        pytorch_state_dict: None,
+        tensorflow_js: None,
+        tensorflow_saved_model_bundle: None,
+        torchscript: None,
+    };
+    for (format, raw) in weights {
+        let bad_weights = |err: String| ModelUpgradeError::BadWeights(format, err);
+        let dependencies = raw
+            .dependencies
+            .as_deref()
+            // 0.4 prefixed the file with its package manager, as in 'conda:environment.yaml'
+            .map(|deps| serde_yaml::from_value(deps.split_once(':').map_or(deps, |(_, file)| file).into()))
+            .transpose()
+            .map_err(|err| bad_weights(err.to_string()))?;
+        let pytorch_version = framework_version(&raw.pytorch_version, FALLBACK_PYTORCH_VERSION);
+        let tensorflow_version = framework_version(&raw.tensorflow_version, FALLBACK_TENSORFLOW_VERSION);
+        let entry = WeightsEntry {
+            source: raw.source.clone(),
+            sha256: raw.sha256,
+            authors: vec![],
+            parent: raw.parent,
+            comment: None,
+        };
+        match format {
+            WeightsFormat::KerasHdf5 => {
+                let tensorflow_version = tensorflow_version.map_err(bad_weights)?;
+                upgraded.keras_hdf5 = Some(KerasHdf5Weights {
+                    entry,
+                    tensorflow_version,
+                });
+            }
+            WeightsFormat::Onnx => {
+                let opset_version = raw.opset_version.unwrap_or(FALLBACK_OPSET_VERSION);
+                upgraded.onnx = Some(OnnxWeights { entry, opset_version });
+            }
+            WeightsFormat::PytorchStateDict => {
+                upgraded.pytorch_state_dict = Some(PytorchStateDictWeights {
+                    architecture: upgrade_architecture(&raw)?,
+                    entry,
+                    pytorch_version: pytorch_version.map_err(bad_weights)?,
+                    dependencies,
+                });
+            }
+            WeightsFormat::TensorflowJs => {
+                let tensorflow_version = tensorflow_version.map_err(bad_weights)?;
+                upgraded.tensorflow_js = Some(TensorflowJsWeights {
+                    entry,
+                    tensorflow_version,
+                });
+            }
+            WeightsFormat::TensorflowSavedModelBundle => {
+                upgraded.tensorflow_saved_model_bundle = Some(TensorflowSavedModelBundleWeights {
+                    entry,
+                    tensorflow_version: tensorflow_version.map_err(bad_weights)?,
+                    dependencies,
+                });
+            }
+            WeightsFormat::Torchscript => {
+                let pytorch_version = pytorch_version.map_err(bad_weights)?;
+                upgraded.torchscript = Some(TorchscriptWeights { entry, pytorch_version });
+            }
+        }
+        // the authors of 0.4 weights entries are who converted them, as in 0.5
+        let entry = match format {
+            WeightsFormat::KerasHdf5 => upgraded.keras_hdf5.as_mut().map(|w| &mut w.entry),
+            WeightsFormat::Onnx => upgraded.onnx.as_mut().map(|w| &mut w.entry),
+            WeightsFormat::PytorchStateDict => upgraded.pytorch_state_dict.as_mut().map(|w| &mut w.entry),
+            WeightsFormat::TensorflowJs => upgraded.tensorflow_js.as_mut().map(|w| &mut w.entry),
+            WeightsFormat::TensorflowSavedModelBundle => upgraded.tensorflow_saved_model_bundle.as_mut().map(|w| &mut w.entry),
+            WeightsFormat::Torchscript => upgraded.torchscript.as_mut().map(|w| &mut w.entry),
+        };
+        if let Some(entry) = entry {
+            entry.authors = raw.authors;
+        }
+    }
+    // runs the same checks as when 0.5 weights are parsed
+    serde_yaml::to_value(&upgraded)
+        .and_then(serde_yaml::from_value)
+        .map_err(upgrade_err)
+}
+
+fn upgrade_timestamp(raw: &str) -> Result<chrono::DateTime<chrono::Utc>, ModelUpgradeError> {
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Ok(timestamp.with_timezone(&chrono::Utc));
+    }
+    let naive = chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f").map_err(upgrade_err)?;
+    Ok(naive.and_utc())
+}
+
+/// Fields of a 0.4 model that the upgrade reads; anything else in a description is left out
+const MODEL_FIELDS: [&str; 30] = [
+    "attachments", "authors", "cite", "config", "covers", "description", "documentation", "format_version", "git_repo", "icon",
+    "id", "inputs", "license", "links", "maintainers", "name", "outputs", "packaged_by", "parent", "run_mode", "sample_inputs",
+    "sample_outputs", "tags", "test_inputs", "test_outputs", "timestamp", "training_data", "type", "version", "weights",
+];
+const INPUT_FIELDS: [&str; 7] = ["axes", "data_range", "data_type", "description", "name", "preprocessing", "shape"];
+const OUTPUT_FIELDS: [&str; 8] = ["axes", "data_range", "data_type", "description", "halo", "name", "postprocessing", "shape"];
+
+fn unknown_fields<'a>(raw: Option<&'a serde_yaml::Value>, known: &'a [&str]) -> impl Iterator<Item = &'a str> {
+    let keys = raw.and_then(serde_yaml::Value::as_mapping).into_iter().flat_map(|mapping| mapping.keys());
+    keys.filter_map(serde_yaml::Value::as_str).filter(|key| !known.contains(key))
+}
+
+impl ModelRdfV04 {
+    /// What the upgrade to 0.5 has to assume or leave out, as warnings about the 0.4 fields involved.
+    /// `raw` is the description this was read from, to find the fields that aren't part of 0.4.
+    pub fn upgrade_notes(&self, raw: &serde_yaml::Value) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let left_out = "Not part of format 0.4 models, so it was left out";
+        for field in unknown_fields(Some(raw), &MODEL_FIELDS) {
+            report.push(Severity::Warning, spec_path![field], left_out);
+        }
+        for (field, known) in [("inputs", INPUT_FIELDS.as_slice()), ("outputs", OUTPUT_FIELDS.as_slice())] {
+            let tensors = raw.get(field).and_then(serde_yaml::Value::as_sequence).into_iter().flatten();
+            for (idx, tensor) in tensors.enumerate() {
+                for tensor_field in unknown_fields(Some(tensor), known) {
+                    report.push(Severity::Warning, spec_path![field, idx, tensor_field], left_out);
+                }
+            }
+        }
+        for attachment in unknown_fields(raw.get("attachments"), &["files"]) {
+            let message = "Only files are kept from the attachments";
+            report.push(Severity::Warning, spec_path!["attachments", attachment], message);
+        }
+
+        let inputs = self.inputs.iter().enumerate().map(|(idx, t)| ("inputs", idx, &t.axes, &t.data_range));
+        let outputs = self.outputs.iter().enumerate().map(|(idx, t)| ("outputs", idx, &t.axes, &t.data_range));
+        for (field, idx, axes, data_range) in inputs.chain(outputs) {
+            if axes.contains('c') {
+                let message = "Format 0.4 didn't name channels, so they were named channel0, channel1 and so on";
+                report.push(Severity::Warning, spec_path![field, idx, "axes"], message);
+            }
+            if data_range.is_none() {
+                let message = "No data_range was given, so the values were taken to be unbounded";
+                report.push(Severity::Warning, spec_path![field, idx], message);
+            }
+        }
+        for (field, files, count) in [
+            ("test_inputs", &self.test_inputs, self.inputs.iter().count()),
+            ("test_outputs", &self.test_outputs, self.outputs.iter().count()),
+            ("sample_inputs", &self.sample_inputs, self.inputs.iter().count()),
+            ("sample_outputs", &self.sample_outputs, self.outputs.iter().count()),
+        ] {
+            for idx in count..files.len() {
+                report.push(Severity::Warning, spec_path![field, idx], "There is no tensor for this file, so it was left out");
+            }
+        }
+
+        for (format, entry) in &self.weights {
+            let format_name = format.to_string();
+            let fallback = match format {
+                WeightsFormat::PytorchStateDict | WeightsFormat::Torchscript if entry.pytorch_version.is_none() => {
+                    Some(("pytorch_version", FALLBACK_PYTORCH_VERSION.to_owned()))
+                }
+                WeightsFormat::KerasHdf5 | WeightsFormat::TensorflowJs | WeightsFormat::TensorflowSavedModelBundle
+                    if entry.tensorflow_version.is_none() =>
+                {
+                    Some(("tensorflow_version", FALLBACK_TENSORFLOW_VERSION.to_owned()))
+                }
+                WeightsFormat::Onnx if entry.opset_version.is_none() => {
+                    Some(("opset_version", FALLBACK_OPSET_VERSION.to_string()))
+                }
+                _ => None,
+            };
+            if let Some((field, version)) = fallback {
+                let message = format!("Required in format 0.5 but not given, so {version} was assumed");
+                report.push(Severity::Warning, spec_path!["weights", format_name.as_str(), field], message);
+            }
+        }
+        let naive_timestamp = self.timestamp.as_deref().filter(|raw| chrono::DateTime::parse_from_rfc3339(raw).is_err());
+        if naive_timestamp.is_some() {
+            report.push(Severity::Warning, spec_path!["timestamp"], "Has no timezone, so it was taken to be in UTC");
+        }
+        report
+    }
+}
+
+impl TryFrom<ModelRdfV04> for ModelRdf {
+    type Error = ModelUpgradeError;
+    fn try_from(v04: ModelRdfV04) -> Result<Self, Self::Error> {
+        let inputs = v04
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(idx, input)| {
+                let tensor = input.name.as_str();
+                let preprocessing = input.preprocessing.iter().map(|step| {
+                    let step = upgrade_processing(tensor, step)?;
+                    Preprocessing::try_from(step).map_err(|_| ModelUpgradeError::UnsupportedProcessing {
+                        tensor: tensor.to_owned(),
+                        step: "scale_mean_variance as preprocessing".to_owned(),
+                    })
+                });
+                Ok(InputTensorDescr2 {
+                    id: tensor_id(tensor)?,
+                    description: description(&input.description)?,
+                    axes: upgrade_input_axes(tensor, &input.axes, &input.shape)?
+                        .try_into()
+                        .map_err(|_| ModelUpgradeError::ShapeLengthMismatch {
+                            tensor: tensor.to_owned(),
+                            axes: String::new(),
+                            shape_len: 0,
+                        })?,
+                    test_tensor: test_tensor(&v04.test_inputs, idx, tensor)?,
+                    sample_tensor: sample_tensor(&v04.sample_inputs, idx),
+                    optional: false,
+                    preprocessing: preprocessing.collect::<Result<_, _>>()?,
+                    data: Some(data_description(tensor, &input.data_type, &input.data_range)?),
+                })
+            })
+            .collect::<Result<Vec<_>, ModelUpgradeError>>()?;
+        let outputs = v04
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(idx, output)| {
+                let tensor = output.name.as_str();
+                let postprocessing = output.postprocessing.iter().map(|step| upgrade_processing(tensor, step));
+                let upgraded = OutputTensorDescr {
+                    id: tensor_id(tensor)?,
+                    description: description(&output.description)?,
+                    axes: upgrade_output_axes(output, &inputs)?.try_into().map_err(|_| {
+                        ModelUpgradeError::ShapeLengthMismatch {
+                            tensor: tensor.to_owned(),
+                            axes: String::new(),
+                            shape_len: 0,
+                        }
+                    })?,
+                    test_tensor: test_tensor(&v04.test_outputs, idx, tensor)?,
+                    sample_tensor: sample_tensor(&v04.sample_outputs, idx),
+                    postprocessing: postprocessing.collect::<Result<_, _>>()?,
+                    data: Some(data_description(tensor, &output.data_type, &output.data_range)?),
+                };
+                upgraded.check_halos(&inputs).map_err(upgrade_err)?;
+                Ok(upgraded)
+            })
+            .collect::<Result<Vec<_>, ModelUpgradeError>>()?;
+
+        let attachments = v04.attachments.unwrap_or_default().files;
+        let upgraded = Self {
+            format_version: UPGRADED_FORMAT_VERSION.try_into().expect("the upgraded format version is 0.5"),
+            description: v04.description,
+            name: v04.name,
+            attachments: attachments
+                .into_iter()
+                .map(|source| FileDescr { source, sha256: None })
+                .collect(),
+            authors: v04.authors,
+            badges: v04.badges,
+            cite: v04.cite,
+            config: v04.config,
+            covers: v04.covers,
+            documentation: v04.documentation,
+            git_repo: v04.git_repo,
+            icon: v04.icon,
+            id: v04.id,
+            license: v04.license,
+            links: v04.links,
+            maintainers: v04.maintainers,
+            parent: v04.parent,
+            tags: v04.tags,
+            rdf_type: ModelType::Model,
+            run_mode: v04.run_mode,
+            training_data: v04.training_data,
+            version: v04.version,
+            inputs: inputs.try_into().expect("inputs come from a non-empty list"),
+            outputs: outputs.try_into().expect("outputs come from a non-empty list"),
+            weights: upgrade_weights(v04.weights)?,
+            packaged_by: v04.packaged_by,
+            history: VersionHistory {
+                timestamp: v04.timestamp.as_deref().map(upgrade_timestamp).transpose()?,
+                ..Default::default()
+            },
+        };
+        // runs the checks that span tensors, like dangling size references
+        let value = serde_yaml::to_value(&upgraded).map_err(upgrade_err)?;
+        Self::try_from(value).map_err(upgrade_err)
+    }
+}
+
+#[test]
+fn test_model_upgrade_from_v04() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_v04_unet2d_nuclei_broad.yaml");
+    let v04: ModelRdfV04 = serde_yaml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let model = ModelRdf::try_from(v04).unwrap();
+    assert_eq!(*model.format_version, UPGRADED_FORMAT_VERSION);
+
+    let input = model.inputs.iter().next().unwrap();
+    assert!(matches!(input.axes.iter().nth(1), Some(InputAxis::Channel(channel)) if channel.channel_names.len() == 1));
+    let spatial_size = ParameterizedAxisSize {
+        min: 64.try_into().unwrap(),
+        step: 16.try_into().unwrap(),
+    };
+    assert!(matches!(input.axes.iter().nth(3), Some(InputAxis::Space(space))
+        if space.size == AnyAxisSize::Parameterized(spatial_size)));
+    assert!(matches!(
+        input.preprocessing.as_slice(),
+        [Preprocessing::ZeroMeanUnitVariance(_)]
+    ));
+
+    let output = model.outputs.iter().next().unwrap();
+    let Some(OutputAxis::Space(space)) = output.axes.iter().nth(2) else {
+        panic!("expected a space axis");
+    };
+    assert_eq!(space.halo, 16);
+    let AnyAxisSize::Reference(reference) = &space.base.size else {
+        panic!("expected a size reference");
+    };
+    assert_eq!(
+        (&*reference.tensor_id, &*reference.axis_id, reference.offset),
+        ("input0", "y", 0)
+    );
+
+    let Some(pytorch) = &model.weights.pytorch_state_dict else {
+        panic!("expected pytorch weights");
+    };
+    assert!(matches!(&pytorch.architecture, ArchitectureDescr::FromFile { callable, .. } if callable.to_string() == "UNet2d"));
+    assert_eq!(
+        model.weights.torchscript.as_ref().unwrap().entry.parent,
+        Some(WeightsFormat::PytorchStateDict)
+    );
+}
+
+#[test]
+fn test_model_upgrade_notes() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_v04_unet2d_nuclei_broad.yaml");
+    let mut raw: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    raw["download_url"] = "https://example.com/model.zip".into();
+    raw["inputs"][0]["units"] = "um".into();
+    raw["weights"]["torchscript"].as_mapping_mut().unwrap().remove("pytorch_version");
+    let v04: ModelRdfV04 = serde_yaml::from_value(raw.clone()).unwrap();
+
+    let report = v04.upgrade_notes(&raw);
+    let paths: Vec<_> = report.entries.iter().map(|entry| entry.path_string()).collect();
+    assert_eq!(
+        paths,
+        [
+            "download_url",
+            "inputs[0].units",
+            "inputs[0].axes",
+            "outputs[0].axes",
+            "weights.torchscript.pytorch_version",
+            "timestamp"
+        ]
+    );
+    assert!(report.is_valid());
+    assert!(report.entries[4].message.contains(FALLBACK_PYTORCH_VERSION));
+}
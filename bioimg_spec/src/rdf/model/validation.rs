@@ -0,0 +1,178 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+
+use super::{
+    axes::{AxisId, InputAxis, OutputAxis},
+    tensor_id::TensorId,
+    ModelRdf,
+};
+use crate::rdf::{
+    partner_config::PartnerConfigRegistry,
+    spec_error::PathSegment,
+    validation::{spec_path, Severity, ValidationReport},
+    FileReference, SpdxLicense,
+};
+
+impl ModelRdf {
+    /// Checks the whole model, collecting every problem instead of stopping at the first one.
+    /// Fields can be edited after parsing, so this also repeats the checks done when parsing.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        self.check_tensor_ids(&mut report);
+        self.check_processing_references(&mut report);
+        self.check_sizes(&mut report);
+        self.check_data(&mut report);
+        self.check_files(&mut report);
+        self.check_metadata(&mut report);
+        if let Some(config) = &self.config {
+            PartnerConfigRegistry::default().check(config, spec_path!["config"], &mut report);
+        }
+        report
+    }
+
+    /// Path to the size of an axis, or to the tensor if the axis isn't found
+    fn axis_size_path(&self, tensor_id: &TensorId, axis_id: &AxisId) -> Vec<PathSegment> {
+        let input = self.inputs.iter().position(|t| t.id == *tensor_id).map(|idx| {
+            let axis = self.inputs.iter().nth(idx).and_then(|t| t.axes.iter().position(|a| a.id() == axis_id));
+            ("inputs", idx, axis)
+        });
+        let output = || {
+            self.outputs.iter().position(|t| t.id == *tensor_id).map(|idx| {
+                let axis = self.outputs.iter().nth(idx).and_then(|t| t.axes.iter().position(|a| a.id() == axis_id));
+                ("outputs", idx, axis)
+            })
+        };
+        match input.or_else(output) {
+            Some((field, idx, Some(axis))) => spec_path![field, idx, "axes", axis, "size"],
+            Some((field, idx, None)) => spec_path![field, idx],
+            None => vec![],
+        }
+    }
+
+    fn check_tensor_ids(&self, report: &mut ValidationReport) {
+        let mut seen = HashSet::new();
+        let inputs = self.inputs.iter().enumerate().map(|(idx, t)| (spec_path!["inputs", idx, "id"], &t.id));
+        let outputs = self.outputs.iter().enumerate().map(|(idx, t)| (spec_path!["outputs", idx, "id"], &t.id));
+        for (path, id) in inputs.chain(outputs) {
+            if !seen.insert(id.to_string()) {
+                report.push(Severity::Error, path, format!("Tensor id '{id}' is used more than once"));
+            }
+        }
+    }
+
+    fn check_processing_references(&self, report: &mut ValidationReport) {
+        let ids: HashSet<String> =
+            self.inputs.iter().map(|t| t.id.to_string()).chain(self.outputs.iter().map(|t| t.id.to_string())).collect();
+        let mut check = |path: Vec<PathSegment>, reference: Option<&TensorId>| match reference {
+            Some(reference) if !ids.contains(&reference.to_string()) => {
+                report.push(Severity::Error, path, format!("Refers to tensor '{reference}', which doesn't exist"))
+            }
+            _ => (),
+        };
+        for (idx, input) in self.inputs.iter().enumerate() {
+            for (step_idx, step) in input.preprocessing.iter().enumerate() {
+                check(spec_path!["inputs", idx, "preprocessing", step_idx], step.reference_tensor());
+            }
+        }
+        for (idx, output) in self.outputs.iter().enumerate() {
+            for (step_idx, step) in output.postprocessing.iter().enumerate() {
+                check(spec_path!["outputs", idx, "postprocessing", step_idx], step.reference_tensor());
+            }
+        }
+    }
+
+    fn check_sizes(&self, report: &mut ValidationReport) {
+        for error in self.size_reference_errors() {
+            report.push(Severity::Error, self.axis_size_path(&error.tensor_id, &error.axis_id), error);
+        }
+        for error in self.scaled_size_errors() {
+            report.push(Severity::Error, self.axis_size_path(&error.tensor_id, &error.axis_id), error);
+        }
+        for (idx, output) in self.outputs.iter().enumerate() {
+            if let Err(err) = output.check_halos(self.inputs.borrow()) {
+                report.push(Severity::Error, spec_path!["outputs", idx, "axes"], err);
+            }
+        }
+    }
+
+    fn check_data(&self, report: &mut ValidationReport) {
+        for (idx, input) in self.inputs.iter().enumerate() {
+            let channels = input.axes.iter().find_map(InputAxis::channel_count);
+            if let Some(Err(err)) = input.data.as_ref().map(|data| data.check_channel_count(channels)) {
+                report.push(Severity::Error, spec_path!["inputs", idx, "data"], err);
+            }
+        }
+        for (idx, output) in self.outputs.iter().enumerate() {
+            let channels = output.axes.iter().find_map(OutputAxis::channel_count);
+            if let Some(Err(err)) = output.data.as_ref().map(|data| data.check_channel_count(channels)) {
+                report.push(Severity::Error, spec_path!["outputs", idx, "data"], err);
+            }
+        }
+    }
+
+    fn check_files(&self, report: &mut ValidationReport) {
+        if self.covers.is_empty() {
+            let message = "Model has no cover images, so it will be shown without a picture";
+            report.push(Severity::Warning, spec_path!["covers"], message);
+        }
+        for (format, entry) in self.weights.entries() {
+            // local files get their hash when packaged
+            if matches!(entry.source, FileReference::Url(_)) && entry.sha256.is_none() {
+                let path = vec![PathSegment::Field("weights".into()), PathSegment::Field(format.to_string())];
+                report.push(Severity::Warning, path, "Weights are downloaded without a sha256 to check them against");
+            }
+        }
+    }
+
+    fn check_metadata(&self, report: &mut ValidationReport) {
+        if !SpdxLicense::COMMON.contains(&self.license) {
+            let message = format!("{} is unusual for models, which may make users unsure whether they can use it", self.license);
+            report.push(Severity::Info, spec_path!["license"], message);
+        }
+        if self.git_repo.is_none() {
+            report.push(Severity::Info, spec_path!["git_repo"], "Model has no git_repo with the code it was trained with");
+        }
+    }
+}
+
+#[test]
+fn test_model_validation_report() {
+    use super::axis_size::AnyAxisSize;
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_affable_shark.yaml");
+    let mut model = ModelRdf::from_yaml(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(model.validate(), ValidationReport::default());
+
+    model.covers.clear();
+    let output = model.outputs.iter_mut().next().unwrap();
+    let Some(OutputAxis::Space(space)) = output.axes.iter_mut().nth(2) else {
+        panic!("Expected a space axis");
+    };
+    let AnyAxisSize::Reference(reference) = &mut space.base.size else {
+        panic!("Expected a size reference");
+    };
+    reference.axis_id = String::from("z").try_into().unwrap();
+
+    let report = model.validate();
+    let found: Vec<_> = report.entries.iter().map(|e| (e.severity, e.path_string())).collect();
+    assert_eq!(
+        found,
+        [
+            (Severity::Error, "outputs[0].axes[2].size".to_owned()),
+            (Severity::Error, "outputs[0].axes".to_owned()),
+            (Severity::Warning, "covers".to_owned()),
+        ]
+    );
+    assert!(!report.is_valid());
+    assert_eq!(report.warnings().count(), 1);
+
+    model.license = SpdxLicense::Glide;
+    let report = model.validate();
+    assert_eq!(report.infos().map(|e| e.path_string()).collect::<Vec<_>>(), ["license"]);
+    assert!(report.fails_at(Severity::Info));
+    assert_eq!(report.at_least(Severity::Warning).count(), report.entries.len() - 1);
+
+    model.outputs.iter_mut().next().unwrap().id = String::from("input0").try_into().unwrap();
+    let report = model.validate();
+    assert!(report.entries[0].to_string().starts_with("error: outputs[0].id: Tensor id 'input0'"));
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdf::{
+    author::Author2, bounded_string::BoundedString, file_reference::FileReference, identifier::Identifier, sha256::Sha256,
+};
+
+/// Versions of frameworks and converters, which are free-form ("1.13", "2.0.1+cu117") rather
+/// than the major.minor.patch of [crate::rdf::Version]
+pub type FrameworkVersion = BoundedString<1, 1023>;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, strum::Display, strum::VariantArray)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WeightsFormat {
+    KerasHdf5,
+    Onnx,
+    PytorchStateDict,
+    TensorflowJs,
+    TensorflowSavedModelBundle,
+    Torchscript,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum WeightsParsingError {
+    #[error("A model needs weights in at least one format")]
+    NoWeights,
+    #[error("Weights '{format}' can't be converted from themselves")]
+    OwnParent { format: WeightsFormat },
+    #[error("Weights '{format}' were converted from '{parent}', which the model doesn't have")]
+    MissingParent { format: WeightsFormat, parent: WeightsFormat },
+}
+
+/// Fields shared by the weights of every format
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct WeightsEntry {
+    pub source: FileReference,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Sha256>,
+    /// Who converted the weights to this format, if that is not who trained the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<Author2>,
+    /// The format these weights were converted from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<WeightsFormat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<BoundedString<1, 1023>>,
+}
+
+/// Where the python class or function that builds a pytorch model comes from
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum ArchitectureDescr {
+    FromFile {
+        source: FileReference,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<Sha256>,
+        callable: Identifier<String>,
+        #[serde(default, skip_serializing_if = "serde_yaml::Mapping::is_empty")]
+        kwargs: serde_yaml::Mapping,
+    },
+    FromLibrary {
+        import_from: BoundedString<1, 1023>,
+        callable: Identifier<String>,
+        #[serde(default, skip_serializing_if = "serde_yaml::Mapping::is_empty")]
+        kwargs: serde_yaml::Mapping,
+    },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct KerasHdf5Weights {
+    #[serde(flatten)]
+    pub entry: WeightsEntry,
+    pub tensorflow_version: FrameworkVersion,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct OnnxWeights {
+    #[serde(flatten)]
+    pub entry: WeightsEntry,
+    pub opset_version: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct PytorchStateDictWeights {
+    #[serde(flatten)]
+    pub entry: WeightsEntry,
+    pub architecture: ArchitectureDescr,
+    pub pytorch_version: FrameworkVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<FileReference>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct TensorflowJsWeights {
+    #[serde(flatten)]
+    pub entry: WeightsEntry,
+    pub tensorflow_version: FrameworkVersion,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct TensorflowSavedModelBundleWeights {
+    #[serde(flatten)]
+    pub entry: WeightsEntry,
+    pub tensorflow_version: FrameworkVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<FileReference>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct TorchscriptWeights {
+    #[serde(flatten)]
+    pub entry: WeightsEntry,
+    pub pytorch_version: FrameworkVersion,
+}
+
+/// The weights of a model in each of the formats it is available in
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(try_from = "RawWeightsDescr")]
+pub struct WeightsDescr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keras_hdf5: Option<KerasHdf5Weights>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub onnx: Option<OnnxWeights>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pytorch_state_dict: Option<PytorchStateDictWeights>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tensorflow_js: Option<TensorflowJsWeights>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tensorflow_saved_model_bundle: Option<TensorflowSavedModelBundleWeights>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torchscript: Option<TorchscriptWeights>,
+}
+
+#[derive(Deserialize)]
+struct RawWeightsDescr {
+    #[serde(default)]
+    keras_hdf5: Option<KerasHdf5Weights>,
+    #[serde(default)]
+    onnx: Option<OnnxWeights>,
+    #[serde(default)]
+    pytorch_state_dict: Option<PytorchStateDictWeights>,
+    #[serde(default)]
+    tensorflow_js: Option<TensorflowJsWeights>,
+    #[serde(default)]
+    tensorflow_saved_model_bundle: Option<TensorflowSavedModelBundleWeights>,
+    #[serde(default)]
+    torchscript: Option<TorchscriptWeights>,
+}
+
+impl WeightsDescr {
+    /// The formats the weights are available in, with the fields they all share
+    pub fn entries(&self) -> Vec<(WeightsFormat, &WeightsEntry)> {
+        [
+            self.keras_hdf5.as_ref().map(|w| (WeightsFormat::KerasHdf5, &w.entry)),
+            self.onnx.as_ref().map(|w| (WeightsFormat::Onnx, &w.entry)),
+            self.pytorch_state_dict.as_ref().map(|w| (WeightsFormat::PytorchStateDict, &w.entry)),
+            self.tensorflow_js.as_ref().map(|w| (WeightsFormat::TensorflowJs, &w.entry)),
+            self.tensorflow_saved_model_bundle.as_ref().map(|w| (WeightsFormat::TensorflowSavedModelBundle, &w.entry)),
+            self.torchscript.as_ref().map(|w| (WeightsFormat::Torchscript, &w.entry)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Checks that there are weights at all, and that every `parent` is one of the other formats.
+    /// This is done when parsing, and should be done again for weights put together by hand.
+    pub fn check(&self) -> Result<(), WeightsParsingError> {
+        let entries = self.entries();
+        if entries.is_empty() {
+            return Err(WeightsParsingError::NoWeights);
+        }
+        for (format, entry) in &entries {
+            let Some(parent) = entry.parent else {
+                continue;
+            };
+            if parent == *format {
+                return Err(WeightsParsingError::OwnParent { format: *format });
+            }
+            if !entries.iter().any(|(other, _)| *other == parent) {
+                return Err(WeightsParsingError::MissingParent { format: *format, parent });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<RawWeightsDescr> for WeightsDescr {
+    type Error = WeightsParsingError;
+    fn try_from(raw: RawWeightsDescr) -> Result<Self, Self::Error> {
+        let weights = Self {
+            keras_hdf5: raw.keras_hdf5,
+            onnx: raw.onnx,
+            pytorch_state_dict: raw.pytorch_state_dict,
+            tensorflow_js: raw.tensorflow_js,
+            tensorflow_saved_model_bundle: raw.tensorflow_saved_model_bundle,
+            torchscript: raw.torchscript,
+        };
+        weights.check()?;
+        Ok(weights)
+    }
+}
+
+#[test]
+fn test_weights_validation() {
+    let parse = |raw: &str| serde_yaml::from_str::<WeightsDescr>(raw).map_err(|err| err.to_string());
+
+    assert!(parse("{}").unwrap_err().contains("at least one format"));
+    let weights = parse(
+        "
+        onnx: {source: weights.onnx, opset_version: 15, parent: pytorch_state_dict}
+        pytorch_state_dict:
+          source: weights.pt
+          architecture: {import_from: torch_em.model, callable: UNet2d}
+          pytorch_version: '1.13'
+        ",
+    )
+    .unwrap();
+    assert!(matches!(&weights.pytorch_state_dict, Some(w) if matches!(w.architecture, ArchitectureDescr::FromLibrary { .. })));
+    assert_eq!(weights.entries().len(), 2);
+
+    assert!(parse("onnx: {source: weights.onnx, opset_version: 15, parent: torchscript}")
+        .unwrap_err()
+        .contains("which the model doesn't have"));
+    assert!(parse("torchscript: {source: weights.pt, pytorch_version: '1.13', parent: torchscript}")
+        .unwrap_err()
+        .contains("from themselves"));
+}
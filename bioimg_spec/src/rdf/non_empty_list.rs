@@ -2,7 +2,7 @@ use std::borrow::Borrow;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(transparent)]
 pub struct NonEmptyList<T>(Vec<T>);
 
@@ -17,6 +17,16 @@ impl<T> TryFrom<Vec<T>> for NonEmptyList<T> {
     }
 }
 
+impl<T> NonEmptyList<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.0.iter_mut()
+    }
+}
+
 impl<T> Borrow<[T]> for NonEmptyList<T> {
     fn borrow(&self) -> &[T] {
         return &self.0;
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use super::{file_reference::FileReference, Rdf};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum NotebookType {
+    #[default]
+    #[serde(rename = "notebook")]
+    Notebook,
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum NotebookRdfParsingError {
+    #[error("Notebooks must have a 'source' pointing at the .ipynb file")]
+    MissingSource,
+    #[error("Expected source to be an .ipynb file, found {0:?}")]
+    NotAnIpynbFile(FileReference),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(try_from = "RawNotebookRdf")]
+pub struct NotebookRdf {
+    #[serde(rename = "type")]
+    pub rdf_type: NotebookType,
+    #[serde(flatten)]
+    pub base: Rdf,
+}
+
+#[derive(Deserialize)]
+struct RawNotebookRdf {
+    #[serde(rename = "type")]
+    rdf_type: NotebookType,
+    #[serde(flatten)]
+    base: Rdf,
+}
+
+impl TryFrom<RawNotebookRdf> for NotebookRdf {
+    type Error = NotebookRdfParsingError;
+    fn try_from(raw: RawNotebookRdf) -> Result<Self, Self::Error> {
+        let Some(source) = &raw.base.source else {
+            return Err(NotebookRdfParsingError::MissingSource);
+        };
+        let path = match source {
+            FileReference::Url(url) => url.path(),
+            FileReference::Path(path) => path.to_str().unwrap_or_default(),
+        };
+        if !path.ends_with(".ipynb") {
+            return Err(NotebookRdfParsingError::NotAnIpynbFile(source.clone()));
+        }
+        Ok(Self {
+            rdf_type: raw.rdf_type,
+            base: raw.base,
+        })
+    }
+}
@@ -6,6 +6,8 @@ pub enum OrcidParsingError{
     BadChar(char),
     #[error("Bad ORCID checksum char: {0}")]
     BadChecksumChar(char),
+    #[error("ORCID {code} fails its check digit: expected {expected}, found {found}")]
+    ChecksumMismatch{code: String, expected: char, found: char},
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -66,6 +68,13 @@ impl Into<String> for Orcid{
     }
 }
 
+impl std::fmt::Display for Orcid{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let as_string: String = self.clone().into();
+        write!(f, "{as_string}")
+    }
+}
+
 impl TryFrom<String> for Orcid{
     type Error = OrcidParsingError;
     fn try_from(value: String) -> Result<Self, Self::Error>{
@@ -98,7 +107,10 @@ impl TryFrom<String> for Orcid{
         let found_checksum = four_parts[3][3].try_as_orcid_checksum()?;
 
         if expected_checksum != found_checksum{
-            return Err(OrcidParsingError::BadCode(value))
+            let as_char = |checksum: u64| if checksum == 10 { 'X' } else { char::from_digit(checksum as u32, 10).unwrap() };
+            return Err(OrcidParsingError::ChecksumMismatch{
+                code: value, expected: as_char(expected_checksum), found: as_char(found_checksum)
+            })
         }
         Ok(Self{value: orcid_value, checksum: found_checksum})
     }
@@ -115,5 +127,10 @@ fn test_orcid_parsing(){
     assert_eq!(good_raw_orcid, reproduced_orcid);
 
     let bad_raw_orcid: String = "0000-0001-7051-119X".into();
-    assert!(Orcid::try_from(bad_raw_orcid).is_err());
+    assert!(matches!(
+        Orcid::try_from(bad_raw_orcid),
+        Err(OrcidParsingError::ChecksumMismatch{expected: '7', found: 'X', ..})
+    ));
+    assert!(matches!(Orcid::try_from("0000-0001-7051".to_owned()), Err(OrcidParsingError::BadCode(_))));
+    assert!(matches!(Orcid::try_from("0000-000a-7051-1197".to_owned()), Err(OrcidParsingError::BadChar('a'))));
 }
\ No newline at end of file
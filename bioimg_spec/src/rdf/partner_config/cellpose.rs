@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use super::SerdePartnerConfig;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum CellposeConfigParsingError {
+    #[error("Diameter must not be negative, found {0}")]
+    NegativeDiameter(f64),
+}
+
+const fn _default_flow_threshold() -> f64 {
+    0.4
+}
+
+/// The `config.cellpose` block
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(try_from = "RawCellposeConfig")]
+pub struct CellposeConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cellpose_version: Option<String>,
+    /// Name of the builtin cellpose model this was trained from, e.g. "cyto" or "nuclei"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_type: Option<String>,
+    diameter: f64,
+    pub flow_threshold: f64,
+    pub cellprob_threshold: f64,
+}
+
+#[derive(Deserialize)]
+struct RawCellposeConfig {
+    #[serde(default)]
+    cellpose_version: Option<String>,
+    #[serde(default)]
+    model_type: Option<String>,
+    diameter: f64,
+    #[serde(default = "_default_flow_threshold")]
+    flow_threshold: f64,
+    #[serde(default)]
+    cellprob_threshold: f64,
+}
+
+impl TryFrom<RawCellposeConfig> for CellposeConfig {
+    type Error = CellposeConfigParsingError;
+    fn try_from(raw: RawCellposeConfig) -> Result<Self, Self::Error> {
+        let mut config = Self::new(raw.diameter)?;
+        config.cellpose_version = raw.cellpose_version;
+        config.model_type = raw.model_type;
+        config.flow_threshold = raw.flow_threshold;
+        config.cellprob_threshold = raw.cellprob_threshold;
+        Ok(config)
+    }
+}
+
+impl CellposeConfig {
+    /// A diameter of 0 asks cellpose to estimate it for each image
+    pub fn new(diameter: f64) -> Result<Self, CellposeConfigParsingError> {
+        if diameter < 0.0 || diameter.is_nan() {
+            return Err(CellposeConfigParsingError::NegativeDiameter(diameter));
+        }
+        Ok(Self {
+            cellpose_version: None,
+            model_type: None,
+            diameter,
+            flow_threshold: _default_flow_threshold(),
+            cellprob_threshold: 0.0,
+        })
+    }
+
+    pub fn diameter(&self) -> f64 {
+        self.diameter
+    }
+
+    fn builtin(model_type: &str, diameter: f64) -> Self {
+        Self {
+            model_type: Some(model_type.into()),
+            ..Self::new(diameter).unwrap()
+        }
+    }
+
+    pub fn cyto() -> Self {
+        Self::builtin("cyto", 30.0)
+    }
+
+    pub fn nuclei() -> Self {
+        Self::builtin("nuclei", 17.0)
+    }
+}
+
+pub const VALIDATOR: SerdePartnerConfig<CellposeConfig> = SerdePartnerConfig::new("cellpose");
+
+#[test]
+fn test_cellpose_config_serde() {
+    let parsed = VALIDATOR.parse(&serde_yaml::from_str("{model_type: nuclei, diameter: 17}").unwrap()).unwrap();
+    assert_eq!(parsed, CellposeConfig::nuclei());
+
+    assert!(VALIDATOR.parse(&serde_yaml::from_str("{diameter: -3}").unwrap()).is_err());
+}
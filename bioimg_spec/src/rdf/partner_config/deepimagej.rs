@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use super::SerdePartnerConfig;
+
+/// How deepimagej should pre/postprocess the tensors, as names of bundled macro or jar files
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct DeepImageJProcessing {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kwargs: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct DeepImageJPrediction {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preprocess: Vec<DeepImageJProcessing>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub postprocess: Vec<DeepImageJProcessing>,
+}
+
+/// The `config.deepimagej` block
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DeepImageJConfig {
+    #[serde(default)]
+    pub pyramidal_model: bool,
+    #[serde(default)]
+    pub allow_tiling: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_keys: Option<serde_yaml::Mapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_information: Option<serde_yaml::Mapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prediction: Option<DeepImageJPrediction>,
+    /// Fields deepimagej adds that aren't listed here, kept as they are
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+pub const VALIDATOR: SerdePartnerConfig<DeepImageJConfig> = SerdePartnerConfig::new("deepimagej");
+
+#[test]
+fn test_deepimagej_config_serde() {
+    let raw: serde_yaml::Value = serde_yaml::from_str(
+        "
+pyramidal_model: false
+allow_tiling: true
+prediction:
+  preprocess: [{spec: 'ij.IJ::runMacroFile', kwargs: preprocessing.ijm}]
+tensorflow_version: '1.15'
+",
+    )
+    .unwrap();
+    let parsed = VALIDATOR.parse(&raw).unwrap();
+    assert!(parsed.allow_tiling);
+    assert_eq!(parsed.prediction.as_ref().unwrap().preprocess[0].kwargs.as_deref(), Some("preprocessing.ijm"));
+    assert_eq!(parsed.extra.get("tensorflow_version"), Some(&"1.15".into()));
+    assert_eq!(serde_yaml::to_value(&parsed).unwrap(), raw);
+}
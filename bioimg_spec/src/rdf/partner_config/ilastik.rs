@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use super::SerdePartnerConfig;
+
+/// The `config.ilastik` block
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct IlastikConfig {
+    /// Oldest ilastik release known to run this resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_ilastik_version: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+pub const VALIDATOR: SerdePartnerConfig<IlastikConfig> = SerdePartnerConfig::new("ilastik");
+
+#[test]
+fn test_ilastik_config_serde() {
+    let raw: serde_yaml::Value = serde_yaml::from_str("{min_ilastik_version: 1.4.0, workflow: pixel_classification}").unwrap();
+    let parsed = VALIDATOR.parse(&raw).unwrap();
+    assert_eq!(parsed.min_ilastik_version.as_deref(), Some("1.4.0"));
+    assert_eq!(serde_yaml::to_value(&parsed).unwrap(), raw);
+}
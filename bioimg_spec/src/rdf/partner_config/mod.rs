@@ -0,0 +1,160 @@
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use super::{
+    spec_error::PathSegment,
+    validation::{Severity, ValidationReport},
+};
+
+pub mod cellpose;
+pub mod deepimagej;
+pub mod ilastik;
+pub mod stardist;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PartnerConfigError {
+    #[error("Bad config.{partner}: {reason}")]
+    Invalid { partner: String, reason: String },
+}
+
+/// A validator for the `config.<partner>` block that a partner tool (ilastik, deepimagej, ...) puts in an rdf.
+/// Implement this in a third-party crate and add it to a [PartnerConfigRegistry] to check more partners.
+pub trait PartnerConfig {
+    /// The key under `config` that this partner owns
+    fn partner_id(&self) -> &str;
+    fn validate(&self, section: &serde_yaml::Value) -> Result<(), PartnerConfigError>;
+}
+
+/// A [PartnerConfig] that validates a section by deserializing it into `T`
+pub struct SerdePartnerConfig<T> {
+    partner_id: &'static str,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> SerdePartnerConfig<T> {
+    pub const fn new(partner_id: &'static str) -> Self {
+        Self {
+            partner_id,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn parse(&self, section: &serde_yaml::Value) -> Result<T, PartnerConfigError> {
+        serde_yaml::from_value(section.clone()).map_err(|err| PartnerConfigError::Invalid {
+            partner: self.partner_id.into(),
+            reason: err.to_string(),
+        })
+    }
+}
+
+impl<T: DeserializeOwned> PartnerConfig for SerdePartnerConfig<T> {
+    fn partner_id(&self) -> &str {
+        self.partner_id
+    }
+    fn validate(&self, section: &serde_yaml::Value) -> Result<(), PartnerConfigError> {
+        self.parse(section).map(|_| ())
+    }
+}
+
+/// The set of partner validators to run over the `config` field of an rdf
+pub struct PartnerConfigRegistry {
+    validators: Vec<Box<dyn PartnerConfig + Send + Sync>>,
+}
+
+impl Default for PartnerConfigRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+impl PartnerConfigRegistry {
+    pub fn empty() -> Self {
+        Self { validators: vec![] }
+    }
+
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::empty();
+        registry.register(cellpose::VALIDATOR);
+        registry.register(deepimagej::VALIDATOR);
+        registry.register(ilastik::VALIDATOR);
+        registry.register(stardist::VALIDATOR);
+        registry
+    }
+
+    /// Adds a validator, replacing any previous one for the same partner
+    pub fn register(&mut self, validator: impl PartnerConfig + Send + Sync + 'static) {
+        self.validators.retain(|v| v.partner_id() != validator.partner_id());
+        self.validators.push(Box::new(validator));
+    }
+
+    pub fn partner_ids(&self) -> impl Iterator<Item = &str> {
+        self.validators.iter().map(|v| v.partner_id())
+    }
+
+    /// Validates every known partner block in `config`. Blocks of unknown partners are left alone.
+    pub fn validate(&self, config: &serde_yaml::Mapping) -> Vec<PartnerConfigError> {
+        self.validators
+            .iter()
+            .filter_map(|validator| {
+                let section = config.get(validator.partner_id())?;
+                validator.validate(section).err()
+            })
+            .collect()
+    }
+
+    /// Adds an error to `report` for every known partner block in `config` that is invalid, at `path` (where
+    /// `config` is) followed by the partner id
+    pub fn check(&self, config: &serde_yaml::Mapping, path: Vec<PathSegment>, report: &mut ValidationReport) {
+        for validator in &self.validators {
+            let Some(section) = config.get(validator.partner_id()) else {
+                continue;
+            };
+            if let Err(err) = validator.validate(section) {
+                let mut path = path.clone();
+                path.push(PathSegment::Field(validator.partner_id().into()));
+                report.push(Severity::Error, path, err);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_partner_config_registry() {
+    let config: serde_yaml::Mapping = serde_yaml::from_str(
+        "
+deepimagej:
+  pyramidal_model: false
+  allow_tiling: true
+stardist:
+  stardist_version: 0.8.3
+  thresholds: {prob: 0.5, nms: 0.4}
+some_unknown_partner: [1, 2, 3]
+",
+    )
+    .unwrap();
+    let registry = PartnerConfigRegistry::default();
+    assert_eq!(registry.validate(&config), vec![]);
+
+    let bad_config: serde_yaml::Mapping = serde_yaml::from_str("stardist: {thresholds: {prob: high}}").unwrap();
+    let errors = registry.validate(&bad_config);
+    assert!(matches!(errors.as_slice(), [PartnerConfigError::Invalid { partner, .. }] if partner == "stardist"));
+
+    struct AlwaysInvalid;
+    impl PartnerConfig for AlwaysInvalid {
+        fn partner_id(&self) -> &str {
+            "ilastik"
+        }
+        fn validate(&self, _section: &serde_yaml::Value) -> Result<(), PartnerConfigError> {
+            Err(PartnerConfigError::Invalid {
+                partner: "ilastik".into(),
+                reason: "nope".into(),
+            })
+        }
+    }
+    let mut registry = PartnerConfigRegistry::empty();
+    registry.register(AlwaysInvalid);
+    let ilastik_config: serde_yaml::Mapping = serde_yaml::from_str("ilastik: {}").unwrap();
+    assert_eq!(registry.validate(&ilastik_config).len(), 1);
+    assert_eq!(registry.partner_ids().collect::<Vec<_>>(), vec!["ilastik"]);
+}
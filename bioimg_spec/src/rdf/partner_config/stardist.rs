@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use super::SerdePartnerConfig;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum StarDistThresholdsParsingError {
+    #[error("Threshold '{name}' must be in [0, 1], found {value}")]
+    OutOfRange { name: &'static str, value: f64 },
+}
+
+/// Probability and non-maximum suppression thresholds used when turning stardist predictions into instances
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+#[serde(try_from = "RawStarDistThresholds")]
+pub struct StarDistThresholds {
+    prob: f64,
+    nms: f64,
+}
+
+#[derive(Deserialize)]
+struct RawStarDistThresholds {
+    prob: f64,
+    nms: f64,
+}
+
+impl TryFrom<RawStarDistThresholds> for StarDistThresholds {
+    type Error = StarDistThresholdsParsingError;
+    fn try_from(raw: RawStarDistThresholds) -> Result<Self, Self::Error> {
+        Self::new(raw.prob, raw.nms)
+    }
+}
+
+impl StarDistThresholds {
+    pub fn new(prob: f64, nms: f64) -> Result<Self, StarDistThresholdsParsingError> {
+        for (name, value) in [("prob", prob), ("nms", nms)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(StarDistThresholdsParsingError::OutOfRange { name, value });
+            }
+        }
+        Ok(Self { prob, nms })
+    }
+    pub fn prob(&self) -> f64 {
+        self.prob
+    }
+    pub fn nms(&self) -> f64 {
+        self.nms
+    }
+}
+
+/// The `config.stardist` block
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct StarDistConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stardist_version: Option<String>,
+    pub thresholds: StarDistThresholds,
+    /// The stardist training config (n_rays, grid, ...), kept as is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_yaml::Mapping>,
+}
+
+impl StarDistConfig {
+    fn pretrained_2d(prob: f64, n_channel_in: u64) -> Self {
+        let mut config = serde_yaml::Mapping::new();
+        config.insert("n_dim".into(), 2.into());
+        config.insert("n_rays".into(), 32.into());
+        config.insert("grid".into(), serde_yaml::Value::Sequence(vec![2.into(), 2.into()]));
+        config.insert("n_channel_in".into(), n_channel_in.into());
+        Self {
+            stardist_version: None,
+            thresholds: StarDistThresholds { prob, nms: 0.3 },
+            config: Some(config),
+        }
+    }
+
+    /// Same settings as the `2D_versatile_fluo` pretrained model, for single channel fluorescence images
+    pub fn versatile_fluo_2d() -> Self {
+        Self::pretrained_2d(0.479071, 1)
+    }
+
+    /// Same settings as the `2D_versatile_he` pretrained model, for RGB H&E stained images
+    pub fn versatile_he_2d() -> Self {
+        Self::pretrained_2d(0.692478, 3)
+    }
+}
+
+pub const VALIDATOR: SerdePartnerConfig<StarDistConfig> = SerdePartnerConfig::new("stardist");
+
+#[test]
+fn test_stardist_config_serde() {
+    let preset = StarDistConfig::versatile_fluo_2d();
+    let raw = serde_yaml::to_value(&preset).unwrap();
+    assert_eq!(VALIDATOR.parse(&raw).unwrap(), preset);
+
+    let bad: serde_yaml::Value = serde_yaml::from_str("thresholds: {prob: 0.5, nms: 1.5}").unwrap();
+    assert!(VALIDATOR.parse(&bad).is_err());
+}
@@ -0,0 +1,160 @@
+//! Spotting authors and maintainers that are listed more than once
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use super::{author::Author2, maintainer::Maintainer, orcid::Orcid};
+
+/// The fields that identify someone listed as an author or maintainer
+pub trait Person {
+    fn name(&self) -> Option<&str>;
+    fn orcid(&self) -> Option<&Orcid>;
+    fn github_user(&self) -> Option<&str>;
+}
+
+impl Person for Author2 {
+    fn name(&self) -> Option<&str> {
+        Some(self.name.as_str())
+    }
+    fn orcid(&self) -> Option<&Orcid> {
+        self.orcid.as_ref()
+    }
+    fn github_user(&self) -> Option<&str> {
+        self.github_user.as_ref().map(|user| user.as_str())
+    }
+}
+
+impl Person for Maintainer {
+    fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| name.as_str())
+    }
+    fn orcid(&self) -> Option<&Orcid> {
+        self.orcid.as_ref()
+    }
+    fn github_user(&self) -> Option<&str> {
+        Some(self.github_user.as_str())
+    }
+}
+
+/// Lowercase words of `name` without accents or punctuation, sorted so that "Doe, Jane" and
+/// "Jane Doe" come out the same
+fn name_key(name: &str) -> String {
+    let plain: String = name
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase();
+    let mut words: Vec<&str> = plain.split_whitespace().collect();
+    words.sort_unstable();
+    words.join(" ")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether `a` and `b` are likely the same person: same ORCID or GitHub user, or names that are
+/// equal up to accents, case, word order and the odd typo. Different ORCIDs always mean
+/// different people.
+pub fn is_probably_same_person(a: &impl Person, b: &impl Person) -> bool {
+    if let (Some(a_orcid), Some(b_orcid)) = (a.orcid(), b.orcid()) {
+        return a_orcid == b_orcid;
+    }
+    if let (Some(a_user), Some(b_user)) = (a.github_user(), b.github_user()) {
+        if a_user.eq_ignore_ascii_case(b_user) {
+            return true;
+        }
+    }
+    let (Some(a_name), Some(b_name)) = (a.name(), b.name()) else {
+        return false;
+    };
+    let (a_key, b_key) = (name_key(a_name), name_key(b_name));
+    // a single typo is only tolerated in names long enough for it not to be a different name
+    let tolerance = if a_key.chars().count().min(b_key.chars().count()) >= 8 {
+        1
+    } else {
+        0
+    };
+    !a_key.is_empty() && edit_distance(&a_key, &b_key) <= tolerance
+}
+
+/// Groups of indices into `people` that seem to be the same person, each sorted and with at
+/// least two entries
+pub fn find_duplicates<P: Person>(people: &[P]) -> Vec<Vec<usize>> {
+    let mut group_of: Vec<usize> = (0..people.len()).collect();
+    for a in 0..people.len() {
+        for b in (a + 1)..people.len() {
+            if group_of[b] == b && is_probably_same_person(&people[a], &people[b]) {
+                group_of[b] = group_of[a];
+            }
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = (0..people.len())
+        .map(|leader| (0..people.len()).filter(|idx| group_of[*idx] == leader).collect::<Vec<_>>())
+        .filter(|group| group.len() > 1)
+        .collect();
+    groups.sort();
+    groups
+}
+
+impl Author2 {
+    /// Combines two entries for the same person, preferring the fields of `self`
+    pub fn merge(self, other: Author2) -> Author2 {
+        Author2 {
+            name: self.name,
+            affiliation: self.affiliation.or(other.affiliation),
+            email: self.email.or(other.email),
+            github_user: self.github_user.or(other.github_user),
+            orcid: self.orcid.or(other.orcid),
+        }
+    }
+}
+
+impl Maintainer {
+    /// Combines two entries for the same person, preferring the fields of `self`
+    pub fn merge(self, other: Maintainer) -> Maintainer {
+        Maintainer {
+            affiliation: self.affiliation.or(other.affiliation),
+            email: self.email.or(other.email),
+            orcid: self.orcid.or(other.orcid),
+            name: self.name.or(other.name),
+            github_user: self.github_user,
+        }
+    }
+}
+
+#[test]
+fn test_find_duplicate_authors() {
+    let author = |name: &str, orcid: Option<&str>| Author2 {
+        name: name.try_into().unwrap(),
+        affiliation: None,
+        email: None,
+        github_user: None,
+        orcid: orcid.map(|orcid| Orcid::try_from(orcid.to_owned()).unwrap()),
+    };
+    let authors = vec![
+        author("José Müller", None),
+        author("Jane Doe", None),
+        author("muller, jose", None),
+        author("Jane Doe", Some("0000-0002-1825-0097")),
+        author("J. Smith", Some("0000-0002-1825-0097")),
+        author("Bob", None),
+        author("Rob", None),
+        author("Christopher Jones", None),
+        author("Christopher Jomes", None),
+    ];
+    assert_eq!(find_duplicates(&authors), vec![vec![0, 2], vec![1, 3, 4], vec![7, 8]]);
+
+    let merged = author("Jane Doe", None).merge(author("Jane Doe", Some("0000-0002-1825-0097")));
+    assert!(merged.orcid.is_some());
+}
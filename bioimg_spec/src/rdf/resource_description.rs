@@ -1,3 +1,298 @@
-pub struct ResourceDEscription{
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-}
\ No newline at end of file
+use super::{
+    application::ApplicationRdf, bounded_string::BoundedString, canonical_yaml, collection::CollectionRdf, dataset::DatasetRdf,
+    model::ModelRdf, notebook::NotebookRdf, partner_config::PartnerConfigRegistry, spec_error,
+    validation::{spec_path, ValidationReport},
+    Rdf, SpecError, Version,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ResourceRdfParsingError {
+    #[error("Resource description has no 'type' field")]
+    MissingType,
+    #[error("Unsupported resource type: '{0}'")]
+    UnsupportedType(String),
+    #[error("{0}")]
+    BadYaml(#[from] SpecError),
+}
+
+/// Any of the resource descriptions, picked by their `type` field
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+#[serde(try_from = "serde_yaml::Value")]
+pub enum AnyResourceRdf {
+    Model(Box<ModelRdf>),
+    Dataset(DatasetRdf),
+    Notebook(NotebookRdf),
+    Application(ApplicationRdf),
+    Collection(CollectionRdf),
+}
+
+impl AnyResourceRdf {
+    /// The value of the `type` field
+    pub fn rdf_type(&self) -> &'static str {
+        match self {
+            Self::Model(_) => "model",
+            Self::Dataset(_) => "dataset",
+            Self::Notebook(_) => "notebook",
+            Self::Application(_) => "application",
+            Self::Collection(_) => "collection",
+        }
+    }
+
+    /// The general fields, for every type but models, which have their own stricter version of them
+    pub fn base(&self) -> Option<&Rdf> {
+        match self {
+            Self::Model(_) => None,
+            Self::Dataset(rdf) => Some(&rdf.base),
+            Self::Notebook(rdf) => Some(&rdf.base),
+            Self::Application(rdf) => Some(&rdf.base),
+            Self::Collection(rdf) => Some(&rdf.base),
+        }
+    }
+
+    pub fn name(&self) -> &BoundedString<1, 1023> {
+        match self {
+            Self::Model(model) => &model.name,
+            Self::Dataset(rdf) => &rdf.base.name,
+            Self::Notebook(rdf) => &rdf.base.name,
+            Self::Application(rdf) => &rdf.base.name,
+            Self::Collection(rdf) => &rdf.base.name,
+        }
+    }
+
+    pub fn description(&self) -> &BoundedString<1, 1023> {
+        match self {
+            Self::Model(model) => &model.description,
+            Self::Dataset(rdf) => &rdf.base.description,
+            Self::Notebook(rdf) => &rdf.base.description,
+            Self::Application(rdf) => &rdf.base.description,
+            Self::Collection(rdf) => &rdf.base.description,
+        }
+    }
+
+    pub fn format_version(&self) -> &Version {
+        match self {
+            Self::Model(model) => &model.format_version,
+            Self::Dataset(rdf) => &rdf.base.format_version,
+            Self::Notebook(rdf) => &rdf.base.format_version,
+            Self::Application(rdf) => &rdf.base.format_version,
+            Self::Collection(rdf) => &rdf.base.format_version,
+        }
+    }
+
+    /// Checks the whole description, for the types that have checks beyond the ones done when parsing
+    pub fn validate(&self) -> ValidationReport {
+        match self {
+            Self::Model(model) => model.validate(),
+            Self::Collection(collection) => collection.validate(),
+            Self::Dataset(_) | Self::Notebook(_) | Self::Application(_) => {
+                let mut report = ValidationReport::default();
+                if let Some(config) = self.base().and_then(|base| base.config.as_ref()) {
+                    PartnerConfigRegistry::default().check(config, spec_path!["config"], &mut report);
+                }
+                report
+            }
+        }
+    }
+
+    pub fn from_yaml(raw: &str) -> Result<Self, ResourceRdfParsingError> {
+        Self::try_from(spec_error::from_yaml_str::<serde_yaml::Value>(raw)?)
+    }
+
+    /// Writes the description in the canonical formatting of [canonical_yaml], so exports by
+    /// different versions of this crate only differ where the content does
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        Ok(canonical_yaml::to_canonical_string(&serde_yaml::to_value(self)?))
+    }
+}
+
+impl TryFrom<serde_yaml::Value> for AnyResourceRdf {
+    type Error = ResourceRdfParsingError;
+    fn try_from(value: serde_yaml::Value) -> Result<Self, Self::Error> {
+        let Some(rdf_type) = value.get("type").and_then(|t| t.as_str()) else {
+            return Err(ResourceRdfParsingError::MissingType);
+        };
+        Ok(match rdf_type {
+            "model" => Self::Model(spec_error::from_value(value)?),
+            "dataset" => Self::Dataset(spec_error::from_value(value)?),
+            "notebook" => Self::Notebook(spec_error::from_value(value)?),
+            "application" => Self::Application(spec_error::from_value(value)?),
+            "collection" => Self::Collection(spec_error::from_value(value)?),
+            other => return Err(ResourceRdfParsingError::UnsupportedType(other.into())),
+        })
+    }
+}
+
+/// A resource of a type that isn't modeled here yet. Only the general fields are validated, the rest is kept as is.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(try_from = "RawUnknownResourceRdf")]
+pub struct UnknownResourceRdf {
+    #[serde(rename = "type")]
+    pub rdf_type: String,
+    #[serde(flatten)]
+    pub base: Rdf,
+    /// Every field that isn't one of the general ones
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+#[derive(Deserialize)]
+struct RawUnknownResourceRdf {
+    #[serde(rename = "type")]
+    rdf_type: String,
+    #[serde(flatten)]
+    base: Rdf,
+    #[serde(flatten)]
+    extra: serde_yaml::Mapping,
+}
+
+impl TryFrom<RawUnknownResourceRdf> for UnknownResourceRdf {
+    type Error = serde_yaml::Error;
+    fn try_from(raw: RawUnknownResourceRdf) -> Result<Self, Self::Error> {
+        // flattening Rdf doesn't consume its keys, so they have to be taken out of `extra` here
+        let mut extra = raw.extra;
+        if let serde_yaml::Value::Mapping(general_fields) = serde_yaml::to_value(&raw.base)? {
+            general_fields.keys().for_each(|key| {
+                extra.remove(key);
+            });
+        }
+        extra.remove("type");
+        Ok(Self {
+            rdf_type: raw.rdf_type,
+            base: raw.base,
+            extra,
+        })
+    }
+}
+
+/// A description together with the top level fields of its document that it doesn't model, e.g.
+/// ones added by other tools, so that writing it back out doesn't drop them. Unknown fields nested
+/// deeper are not kept. A description that was upgraded from an older format keeps none, since the
+/// fields the old format had under other names would come back.
+#[derive(Debug, PartialEq)]
+pub struct WithExtras<T> {
+    pub rdf: T,
+    pub extra: serde_yaml::Mapping,
+}
+
+impl<T: Serialize> WithExtras<T> {
+    /// Writes the description and its extra fields like [AnyResourceRdf::to_yaml] does
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        Ok(canonical_yaml::to_canonical_string(&serde_yaml::to_value(self)?))
+    }
+}
+
+impl<'de, T: DeserializeOwned + Serialize> Deserialize<'de> for WithExtras<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let document = serde_yaml::Value::deserialize(deserializer)?;
+        let rdf: T = spec_error::from_value(document.clone()).map_err(D::Error::custom)?;
+        let serde_yaml::Value::Mapping(known) = serde_yaml::to_value(&rdf).map_err(D::Error::custom)? else {
+            return Ok(Self {
+                rdf,
+                extra: Default::default(),
+            });
+        };
+        let version = |fields: &serde_yaml::Mapping| fields.get("format_version").cloned();
+        let mut extra = document.as_mapping().cloned().unwrap_or_default();
+        if version(&extra) != version(&known) {
+            extra.clear();
+        }
+        known.keys().for_each(|key| {
+            extra.remove(key);
+        });
+        Ok(Self { rdf, extra })
+    }
+}
+
+impl<T: Serialize> Serialize for WithExtras<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+        let mut value = serde_yaml::to_value(&self.rdf).map_err(S::Error::custom)?;
+        if let serde_yaml::Value::Mapping(fields) = &mut value {
+            for (key, extra_value) in &self.extra {
+                if !fields.contains_key(key) {
+                    fields.insert(key.clone(), extra_value.clone());
+                }
+            }
+        }
+        value.serialize(serializer)
+    }
+}
+
+#[test]
+fn test_resource_type_detection() {
+    let raw_notebook = "
+type: notebook
+format_version: 0.2.3
+name: my notebook
+description: trains a model
+source: notebooks/train.ipynb
+";
+    let notebook = AnyResourceRdf::from_yaml(raw_notebook).unwrap();
+    assert!(matches!(notebook, AnyResourceRdf::Notebook(_)));
+    assert_eq!((notebook.rdf_type(), notebook.name().as_str()), ("notebook", "my notebook"));
+    assert_eq!(notebook.format_version().to_string(), "0.2.3");
+    assert!(AnyResourceRdf::from_yaml(&raw_notebook.replace(".ipynb", ".py")).is_err());
+
+    let raw_application = raw_notebook.replace("notebook", "application");
+    let application = AnyResourceRdf::from_yaml(&raw_application).unwrap();
+    assert!(matches!(application, AnyResourceRdf::Application(_)));
+    assert_eq!(AnyResourceRdf::from_yaml(&application.to_yaml().unwrap()).unwrap(), application);
+
+    assert!(matches!(
+        AnyResourceRdf::from_yaml(&raw_notebook.replace("type: notebook", "type: blas")),
+        Err(ResourceRdfParsingError::UnsupportedType(_))
+    ));
+    assert!(matches!(
+        AnyResourceRdf::from_yaml(&raw_notebook.replace("type: notebook", "")),
+        Err(ResourceRdfParsingError::MissingType)
+    ));
+}
+
+#[test]
+fn test_unknown_resource() {
+    let raw = "
+type: workflow
+format_version: 0.2.3
+name: my workflow
+description: runs things
+steps: [a, b]
+";
+    let value: serde_yaml::Value = serde_yaml::from_str(raw).unwrap();
+    assert!(matches!(
+        AnyResourceRdf::try_from(value.clone()),
+        Err(ResourceRdfParsingError::UnsupportedType(_))
+    ));
+    let unknown: UnknownResourceRdf = serde_yaml::from_value(value).unwrap();
+    assert_eq!(unknown.rdf_type, "workflow");
+    assert_eq!(unknown.base.name.as_str(), "my workflow");
+    assert!(unknown.extra.contains_key("steps"));
+    assert!(!unknown.extra.contains_key("name"));
+
+    assert!(serde_yaml::from_str::<UnknownResourceRdf>(&raw.replace("name: my workflow", "")).is_err());
+}
+
+#[test]
+fn test_extra_fields_roundtrip() {
+    let raw = "
+type: notebook
+format_version: 0.2.3
+name: my notebook
+description: trains a model
+source: notebooks/train.ipynb
+x-other-tool: {reviewed: true}
+";
+    let notebook: WithExtras<AnyResourceRdf> = serde_yaml::from_str(raw).unwrap();
+    assert_eq!(notebook.extra.keys().collect::<Vec<_>>(), ["x-other-tool"]);
+    let written = notebook.to_yaml().unwrap();
+    assert!(written.contains("reviewed: true"));
+    assert_eq!(serde_yaml::from_str::<WithExtras<AnyResourceRdf>>(&written).unwrap(), notebook);
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_v04_unet2d_nuclei_broad.yaml");
+    let raw_v04 = std::fs::read_to_string(path).unwrap() + "\nx-other-tool: {reviewed: true}\n";
+    let upgraded: WithExtras<AnyResourceRdf> = serde_yaml::from_str(&raw_v04).unwrap();
+    assert!(upgraded.extra.is_empty());
+}
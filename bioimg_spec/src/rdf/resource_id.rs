@@ -0,0 +1,94 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::sha256::Sha256;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ResourceIdParsingError {
+    #[error("Resource ids can't be empty")]
+    Empty,
+    #[error("Resource id '{id}' has character '{character}', but only letters, digits and '_', '-', '.', '/' are allowed")]
+    BadCharacter { id: String, character: char },
+    #[error("Resource id '{0}' has an empty part between slashes")]
+    EmptyPart(String),
+}
+
+/// The id of a resource in the zoo, like `affable-shark`, `ilastik/ilastik` or `10.5281/zenodo.5764892`
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct ResourceId(String);
+
+impl ResourceId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for ResourceId {
+    type Error = ResourceIdParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let id = value.trim();
+        if id.is_empty() {
+            return Err(ResourceIdParsingError::Empty);
+        }
+        let allowed = |c: char| c.is_ascii_alphanumeric() || "_-./".contains(c);
+        if let Some(character) = id.chars().find(|c| !allowed(*c)) {
+            return Err(ResourceIdParsingError::BadCharacter { id: id.to_owned(), character });
+        }
+        if id.split('/').any(str::is_empty) {
+            return Err(ResourceIdParsingError::EmptyPart(id.to_owned()));
+        }
+        Ok(Self(id.to_owned()))
+    }
+}
+
+impl FromStr for ResourceId {
+    type Err = ResourceIdParsingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_owned())
+    }
+}
+
+impl Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<ResourceId> for String {
+    fn from(value: ResourceId) -> Self {
+        value.0
+    }
+}
+
+/// Another resource this one is derived from, e.g. the model a model was fine-tuned from. The hash
+/// or version pin down which upload of it is meant; without either, it is the latest one.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct LinkedResource {
+    pub id: ResourceId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<Sha256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_number: Option<u32>,
+}
+
+#[test]
+fn test_resource_id_parsing() {
+    for good in ["affable-shark", "ilastik/ilastik", "10.5281/zenodo.5764892", " padded_id "] {
+        assert!(ResourceId::from_str(good).is_ok(), "{good} should be a valid id");
+    }
+    assert_eq!(ResourceId::from_str(" padded_id ").unwrap().as_str(), "padded_id");
+    assert_eq!(ResourceId::from_str(""), Err(ResourceIdParsingError::Empty));
+    assert!(matches!(
+        ResourceId::from_str("affable shark"),
+        Err(ResourceIdParsingError::BadCharacter { character: ' ', .. })
+    ));
+    assert!(matches!(ResourceId::from_str("ilastik//ilastik"), Err(ResourceIdParsingError::EmptyPart(_))));
+    assert!(matches!(ResourceId::from_str("/ilastik"), Err(ResourceIdParsingError::EmptyPart(_))));
+
+    let parent: LinkedResource = serde_yaml::from_str("{id: affable-shark, version_number: 2}").unwrap();
+    assert_eq!((parent.id.as_str(), parent.sha256, parent.version_number), ("affable-shark", None, Some(2)));
+    assert!(serde_yaml::from_str::<LinkedResource>("{id: 'affable shark'}").is_err());
+}
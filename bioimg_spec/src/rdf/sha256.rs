@@ -0,0 +1,115 @@
+use std::{fmt::Display, io::Read, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+use crate::util::progress::{OnProgress, Progress, ProgressReader};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Sha256ParsingError {
+    #[error("Expected 64 hex characters, found '{0}'")]
+    BadHexString(String),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct Sha256([u8; 32]);
+
+impl Sha256 {
+    pub fn digest(data: impl AsRef<[u8]>) -> Self {
+        Self(sha2::Sha256::digest(data).into())
+    }
+
+    /// Hashes everything `reader` yields, without holding it all in memory
+    pub fn compute(mut reader: impl Read) -> std::io::Result<Self> {
+        let mut hasher = sha2::Sha256::new();
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            hasher.update(&buffer[..read]);
+        }
+        Ok(Self(hasher.finalize().into()))
+    }
+
+    /// Hashes the file at `path` a chunk at a time, so that large weights can be hashed too
+    pub fn of_file(path: &Path) -> std::io::Result<Self> {
+        Self::compute(std::fs::File::open(path)?)
+    }
+
+    /// Like [Self::of_file], reporting how much of the file was hashed
+    pub fn of_file_with_progress(path: &Path, on_progress: OnProgress) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let total = file.metadata()?.len();
+        Self::compute(ProgressReader {
+            inner: file,
+            progress: Progress { done: 0, total },
+            on_progress,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for Sha256 {
+    fn from(value: [u8; 32]) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for Sha256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl TryFrom<String> for Sha256 {
+    type Error = Sha256ParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        // from_str_radix alone would also take a leading '+'
+        if value.len() != 64 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(Sha256ParsingError::BadHexString(value));
+        }
+        let mut out = [0u8; 32];
+        for (idx, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[idx * 2..idx * 2 + 2], 16)
+                .map_err(|_| Sha256ParsingError::BadHexString(value.clone()))?;
+        }
+        Ok(Self(out))
+    }
+}
+
+impl FromStr for Sha256 {
+    type Err = Sha256ParsingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_owned())
+    }
+}
+
+impl From<Sha256> for String {
+    fn from(value: Sha256) -> Self {
+        value.to_string()
+    }
+}
+
+#[test]
+fn test_sha256() {
+    let hash = Sha256::digest(b"abc");
+    let hex = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    assert_eq!(hash.to_string(), hex);
+    assert_eq!(Sha256::try_from(hex.to_uppercase()), Ok(hash));
+    assert!(Sha256::try_from("abc".to_owned()).is_err());
+    assert!(Sha256::from_str(&format!("+{}", &hex[1..])).is_err());
+    assert_eq!(Sha256::compute(&b"abc"[..]).unwrap(), hash);
+    assert_eq!(serde_yaml::to_string(&Sha256::from_str(&hex.to_uppercase()).unwrap()).unwrap().trim(), hex);
+
+    let long = vec![7u8; 200 * 1024];
+    assert_eq!(Sha256::compute(long.as_slice()).unwrap(), Sha256::digest(&long));
+}
@@ -1,14 +1,126 @@
+use std::{fmt::Display, str::FromStr};
+
+use strum::VariantArray;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct SiUnit {
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SiUnitParsingError {
+    #[error("Expected a unit like 'µm' or 'm·s^-1', found nothing")]
+    Empty,
+    #[error("'{0}' is not a prefixed SI unit")]
+    UnknownFactor(String),
+    #[error("Bad exponent in '{0}', expected a non-zero integer")]
+    BadExponent(String),
+    #[error("Unit '{0}' has more than one '/'")]
+    ManyDivisions(String),
+}
+
+/// A unit, prefix and exponent like the `mm^2` in `mm^2·s^-1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiUnitFactor {
     pub multiplier: Option<SiMultiplier>,
     pub measure: SiMesaure,
     pub exponent: i32,
 }
 
+/// A product of SI units, written like `µm`, `m·s^-1` or `kg/m^3`. Factors can be separated by '·' or '⋅';
+/// everything after a '/' is divided by.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct SiUnit(Vec<SiUnitFactor>);
+
+impl SiUnit {
+    pub fn factors(&self) -> &[SiUnitFactor] {
+        &self.0
+    }
+}
+
+impl FromStr for SiUnitFactor {
+    type Err = SiUnitParsingError;
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let (symbol, exponent) = match raw.split_once('^') {
+            Some((symbol, exponent)) => match exponent.parse::<i32>() {
+                Ok(exponent) if exponent != 0 => (symbol, exponent),
+                _ => return Err(SiUnitParsingError::BadExponent(raw.to_owned())),
+            },
+            None => (raw, 1),
+        };
+        let measure_named = |name: &str| SiMesaure::VARIANTS.iter().find(|measure| measure.to_string() == name).cloned();
+        // a bare measure wins, so that e.g. 'T' is tesla and not a stray tera prefix
+        if let Some(measure) = measure_named(symbol) {
+            return Ok(Self { multiplier: None, measure, exponent });
+        }
+        SiMultiplier::VARIANTS
+            .iter()
+            .find_map(|multiplier| {
+                let measure = measure_named(symbol.strip_prefix(multiplier.to_string().as_str())?)?;
+                Some(Self { multiplier: Some(multiplier.clone()), measure, exponent })
+            })
+            .ok_or_else(|| SiUnitParsingError::UnknownFactor(raw.to_owned()))
+    }
+}
+
+impl Display for SiUnitFactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(multiplier) = &self.multiplier {
+            write!(f, "{multiplier}")?;
+        }
+        write!(f, "{}", self.measure)?;
+        if self.exponent != 1 {
+            write!(f, "^{}", self.exponent)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for SiUnit {
+    type Error = SiUnitParsingError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let raw = value.trim();
+        if raw.is_empty() {
+            return Err(SiUnitParsingError::Empty);
+        }
+        let (numerator, denominator) = match raw.split_once('/') {
+            Some((_, denominator)) if denominator.contains('/') => return Err(SiUnitParsingError::ManyDivisions(value)),
+            Some((numerator, denominator)) => (numerator, Some(denominator)),
+            None => (raw, None),
+        };
+        let parse_factors = |raw: &str| -> Result<Vec<SiUnitFactor>, SiUnitParsingError> {
+            raw.split(['·', '⋅']).map(|factor| factor.trim().parse()).collect()
+        };
+        let mut factors = parse_factors(numerator)?;
+        for mut factor in denominator.map(parse_factors).transpose()?.unwrap_or_default() {
+            factor.exponent = -factor.exponent;
+            factors.push(factor);
+        }
+        Ok(Self(factors))
+    }
+}
+
+impl FromStr for SiUnit {
+    type Err = SiUnitParsingError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_owned())
+    }
+}
+
+impl Display for SiUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let factors: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", factors.join("·"))
+    }
+}
+
+impl From<SiUnit> for String {
+    fn from(value: SiUnit) -> Self {
+        value.to_string()
+    }
+}
+
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, strum::VariantArray, strum::Display)]
 pub enum SiMultiplier {
     Q,
     R,
@@ -37,7 +149,7 @@ pub enum SiMultiplier {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, strum::VariantArray, strum::Display)]
 pub enum SiMesaure {
     m,
     g,
@@ -68,3 +180,23 @@ pub enum SiMesaure {
     l,
     L,
 }
+
+#[test]
+fn test_si_unit_parsing() {
+    let unit = |raw: &str| SiUnit::from_str(raw).map(|unit| unit.to_string());
+
+    assert_eq!(unit("µm").as_deref(), Ok("µm"));
+    assert_eq!(unit("T").as_deref(), Ok("T"));
+    assert_eq!(unit("mm").as_deref(), Ok("mm"));
+    assert_eq!(unit("dam").as_deref(), Ok("dam"));
+    assert_eq!(unit("kg/m^3").as_deref(), Ok("kg·m^-3"));
+    assert_eq!(unit("m⋅s^-1").as_deref(), Ok("m·s^-1"));
+
+    let factors = SiUnit::from_str("cd").unwrap().factors().to_vec();
+    assert_eq!(factors, [SiUnitFactor { multiplier: None, measure: SiMesaure::cd, exponent: 1 }]);
+
+    assert_eq!(unit("micrometer"), Err(SiUnitParsingError::UnknownFactor("micrometer".into())));
+    assert_eq!(unit("m^0"), Err(SiUnitParsingError::BadExponent("m^0".into())));
+    assert_eq!(unit(" "), Err(SiUnitParsingError::Empty));
+    assert!(matches!(unit("m/s/s"), Err(SiUnitParsingError::ManyDivisions(_))));
+}
@@ -22,3 +22,9 @@ impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> TryFrom<String> for Slash
         Ok(Self(bs))
     }
 }
+
+impl<const MIN_CHARS: usize, const EXTRA_CHARS: usize> SlashlessString<MIN_CHARS, EXTRA_CHARS> {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
@@ -0,0 +1,344 @@
+//! Deserialization that remembers where in the document it failed.
+//!
+//! serde_yaml only reports the path to an error when it deserializes from text, and most
+//! descriptions here are first read into a [serde_yaml::Value] and converted from that. Going
+//! through [from_value] instead keeps track of the path at every mapping and sequence, including
+//! across the `TryFrom<serde_yaml::Value>` conversions that also use it, so an error can say
+//! `inputs[1].axes[2].size: ...` instead of just what was wrong.
+
+use std::cell::RefCell;
+use std::fmt::Display;
+
+use serde::de::{DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::Deserializer;
+use serde_yaml::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// A deserialization error and the path to the value that caused it, empty if that is the whole document
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{}", self.display())]
+pub struct SpecError {
+    pub path: Vec<PathSegment>,
+    pub message: String,
+}
+
+impl SpecError {
+    /// The path as it would be written in code, e.g. `inputs[1].axes[2].size`
+    pub fn path_string(&self) -> String {
+        path_string(&self.path)
+    }
+
+    fn display(&self) -> String {
+        if self.path.is_empty() {
+            self.message.clone()
+        } else {
+            format!("{}: {}", self.path_string(), self.message)
+        }
+    }
+}
+
+/// `path` as it would be written in code, e.g. `inputs[1].axes[2].size`
+pub fn path_string(path: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Field(field) if out.is_empty() => out.push_str(field),
+            PathSegment::Field(field) => {
+                out.push('.');
+                out.push_str(field);
+            }
+            PathSegment::Index(idx) => out.push_str(&format!("[{idx}]")),
+        }
+    }
+    out
+}
+
+thread_local! {
+    /// Path to the value being deserialized
+    static CURRENT_PATH: RefCell<Vec<PathSegment>> = const { RefCell::new(Vec::new()) };
+    /// The deepest error seen so far, which is what the outermost call reports
+    static DEEPEST_ERROR: RefCell<Option<SpecError>> = const { RefCell::new(None) };
+    /// How many calls to [from_value] are running, so only the outermost one resets the state above
+    static DEPTH: RefCell<usize> = const { RefCell::new(0) };
+}
+
+fn current_path() -> Vec<PathSegment> {
+    CURRENT_PATH.with(|path| path.borrow().clone())
+}
+
+/// Remembers `error` as having happened at the current path, unless a deeper error under it was already seen
+fn record_error(error: &impl Display) {
+    let path = current_path();
+    DEEPEST_ERROR.with(|deepest| {
+        let mut deepest = deepest.borrow_mut();
+        let is_deeper = deepest.as_ref().is_some_and(|deepest| deepest.path.starts_with(&path));
+        if !is_deeper {
+            *deepest = Some(SpecError {
+                path,
+                message: error.to_string(),
+            });
+        }
+    });
+}
+
+/// Forgets errors under the current path, e.g. those of an untagged enum variant that didn't match
+/// before one that did
+fn clear_errors_under_current() {
+    let path = current_path();
+    DEEPEST_ERROR.with(|deepest| {
+        let mut deepest = deepest.borrow_mut();
+        if deepest.as_ref().is_some_and(|deepest| deepest.path.starts_with(&path)) {
+            *deepest = None;
+        }
+    });
+}
+
+fn at_segment<T>(segment: PathSegment, f: impl FnOnce() -> Result<T, serde_yaml::Error>) -> Result<T, serde_yaml::Error> {
+    CURRENT_PATH.with(|path| path.borrow_mut().push(segment));
+    let result = f();
+    match &result {
+        Ok(_) => clear_errors_under_current(),
+        Err(err) => record_error(err),
+    }
+    CURRENT_PATH.with(|path| path.borrow_mut().pop());
+    result
+}
+
+/// Deserializes `T` from `value`, reporting the path to the first error
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, SpecError> {
+    let is_outermost = DEPTH.with(|depth| {
+        let mut depth = depth.borrow_mut();
+        *depth += 1;
+        *depth == 1
+    });
+    if is_outermost {
+        CURRENT_PATH.with(|path| path.borrow_mut().clear());
+        DEEPEST_ERROR.with(|deepest| deepest.borrow_mut().take());
+    }
+    let result = T::deserialize(TrackedValue(value));
+    DEPTH.with(|depth| *depth.borrow_mut() -= 1);
+    result.map_err(|err| {
+        let deepest = if is_outermost {
+            DEEPEST_ERROR.with(|deepest| deepest.borrow_mut().take())
+        } else {
+            DEEPEST_ERROR.with(|deepest| deepest.borrow().clone())
+        };
+        let deepest = deepest.filter(|deepest| deepest.path.starts_with(&current_path()));
+        deepest.unwrap_or_else(|| SpecError {
+            path: current_path(),
+            message: err.to_string(),
+        })
+    })
+}
+
+/// Like [from_value], for the text of a yaml document
+pub fn from_yaml_str<T: DeserializeOwned>(raw: &str) -> Result<T, SpecError> {
+    let value: Value = serde_yaml::from_str(raw).map_err(|err| SpecError {
+        path: vec![],
+        message: err.to_string(),
+    })?;
+    from_value(value)
+}
+
+/// A [Value] that tracks the path into its mappings and sequences while being deserialized.
+/// Everything else is left to the deserializer of [Value] itself.
+struct TrackedValue(Value);
+
+macro_rules! delegate {
+    ($($method:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.0.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for TrackedValue {
+    type Error = serde_yaml::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Mapping(mapping) => visitor.visit_map(TrackedMap::new(mapping)),
+            Value::Sequence(sequence) => visitor.visit_seq(TrackedSeq::new(sequence)),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Mapping(mapping) => visitor.visit_map(TrackedMap::new(mapping)),
+            other => other.deserialize_map(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Mapping(mapping) => visitor.visit_map(TrackedMap::new(mapping)),
+            other => other.deserialize_struct(name, fields, visitor),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Sequence(sequence) => visitor.visit_seq(TrackedSeq::new(sequence)),
+            other => other.deserialize_seq(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(TrackedValue(other)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    delegate!(
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_unit,
+        deserialize_identifier,
+        deserialize_ignored_any,
+    );
+}
+
+struct TrackedMap {
+    entries: serde_yaml::mapping::IntoIter,
+    value: Option<(PathSegment, Value)>,
+}
+
+impl TrackedMap {
+    fn new(mapping: serde_yaml::Mapping) -> Self {
+        Self {
+            entries: mapping.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for TrackedMap {
+    type Error = serde_yaml::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        let segment = match &key {
+            Value::String(key) => PathSegment::Field(key.clone()),
+            other => PathSegment::Field(serde_yaml::to_string(other).unwrap_or_default().trim().to_owned()),
+        };
+        self.value = Some((segment, value));
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let (segment, value) = self.value.take().expect("next_value_seed is only called after next_key_seed");
+        at_segment(segment, || seed.deserialize(TrackedValue(value)))
+    }
+}
+
+struct TrackedSeq {
+    items: std::iter::Enumerate<std::vec::IntoIter<Value>>,
+}
+
+impl TrackedSeq {
+    fn new(sequence: serde_yaml::Sequence) -> Self {
+        Self {
+            items: sequence.into_iter().enumerate(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for TrackedSeq {
+    type Error = serde_yaml::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        let Some((idx, item)) = self.items.next() else {
+            return Ok(None);
+        };
+        at_segment(PathSegment::Index(idx), || seed.deserialize(TrackedValue(item))).map(Some)
+    }
+}
+
+#[test]
+fn test_error_paths() {
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Axis {
+        size: usize,
+    }
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Tensor {
+        axes: Vec<Axis>,
+    }
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Doc {
+        inputs: Vec<Tensor>,
+    }
+
+    let err = from_yaml_str::<Doc>("inputs: [{axes: [{size: 1}]}, {axes: [{size: 1}, {size: 2}, {size: x}]}]").unwrap_err();
+    assert_eq!(err.path_string(), "inputs[1].axes[2].size");
+    assert!(err.to_string().starts_with("inputs[1].axes[2].size: invalid type"));
+
+    let err = from_yaml_str::<Doc>("inputs: [{axes: [{}]}]").unwrap_err();
+    assert_eq!(err.to_string(), "inputs[0].axes[0]: missing field `size`");
+    assert!(from_yaml_str::<Doc>("inputs: []").is_ok());
+}
@@ -0,0 +1,125 @@
+//! Problems found by checking a whole description at once, as opposed to the first error that
+//! stops it from being parsed.
+
+use std::fmt::Display;
+
+use super::spec_error::{path_string, PathSegment};
+
+/// How much an entry of a report matters, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display, strum::EnumString, strum::VariantArray)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// Something worth knowing about that doesn't need to change, e.g. an uncommon license
+    Info,
+    /// Allowed by the spec, but likely a mistake or a problem for users of the resource
+    Warning,
+    /// Not allowed by the spec
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationEntry {
+    pub path: Vec<PathSegment>,
+    pub message: String,
+    pub severity: Severity,
+}
+
+impl ValidationEntry {
+    /// The path as it would be written in code, e.g. `inputs[1].axes[2].size`
+    pub fn path_string(&self) -> String {
+        path_string(&self.path)
+    }
+}
+
+impl Display for ValidationEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, "{}: {}", self.severity, self.message),
+            false => write!(f, "{}: {}: {}", self.severity, self.path_string(), self.message),
+        }
+    }
+}
+
+/// Every problem found in a description, in the order of the fields they are about
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub entries: Vec<ValidationEntry>,
+}
+
+impl ValidationReport {
+    pub fn push(&mut self, severity: Severity, path: Vec<PathSegment>, message: impl Display) {
+        self.entries.push(ValidationEntry {
+            path,
+            message: message.to_string(),
+            severity,
+        });
+    }
+
+    /// Whether nothing the spec forbids was found. There may still be warnings.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationEntry> {
+        self.entries.iter().filter(|entry| entry.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationEntry> {
+        self.entries.iter().filter(|entry| entry.severity == Severity::Warning)
+    }
+
+    pub fn infos(&self) -> impl Iterator<Item = &ValidationEntry> {
+        self.entries.iter().filter(|entry| entry.severity == Severity::Info)
+    }
+
+    /// The entries that are at least as severe as `threshold`
+    pub fn at_least(&self, threshold: Severity) -> impl Iterator<Item = &ValidationEntry> {
+        self.entries.iter().filter(move |entry| entry.severity >= threshold)
+    }
+
+    /// Whether anything at least as severe as `threshold` was found. Failing on errors is the same as not being
+    /// [valid](Self::is_valid); stricter thresholds also fail on warnings or infos.
+    pub fn fails_at(&self, threshold: Severity) -> bool {
+        self.at_least(threshold).next().is_some()
+    }
+
+    /// The report as `{"valid": bool, "entries": [{"severity", "path", "message"}]}`, for other tools to read
+    pub fn to_json(&self) -> serde_json::Value {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "severity": entry.severity.to_string(),
+                    "path": entry.path_string(),
+                    "message": entry.message,
+                })
+            })
+            .collect();
+        serde_json::json!({ "valid": self.is_valid(), "entries": entries })
+    }
+}
+
+/// Builds a path out of field names and indices, e.g. `spec_path!["inputs", 1, "axes"]`
+macro_rules! spec_path {
+    ($($segment:expr),* $(,)?) => {
+        vec![$($crate::rdf::validation::IntoPathSegment::into_segment($segment)),*]
+    };
+}
+pub(crate) use spec_path;
+
+pub(crate) trait IntoPathSegment {
+    fn into_segment(self) -> PathSegment;
+}
+
+impl IntoPathSegment for &str {
+    fn into_segment(self) -> PathSegment {
+        PathSegment::Field(self.to_owned())
+    }
+}
+
+impl IntoPathSegment for usize {
+    fn into_segment(self) -> PathSegment {
+        PathSegment::Index(self)
+    }
+}
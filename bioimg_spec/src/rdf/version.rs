@@ -11,6 +11,10 @@ pub enum VersionParsingError {
     ParseIntError(ParseIntError),
     #[error("Expected version '{expected}', found '{found}'")]
     UnexpectedVersion { expected: Version, found: Version },
+    #[error("Version '{found}' is older than '{minimum}', the oldest supported one")]
+    TooOld { minimum: Version, found: Version },
+    #[error("Only versions {major}.{minor}.x are supported, found '{found}'")]
+    IncompatibleVersion { major: usize, minor: usize, found: Version },
 }
 impl From<ParseIntError> for VersionParsingError {
     fn from(value: ParseIntError) -> Self {
@@ -18,7 +22,8 @@ impl From<ParseIntError> for VersionParsingError {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
+/// Ordered by major, then minor, then patch
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, Clone)]
 #[serde(try_from = "String")]
 #[serde(into = "String")]
 pub struct Version {
@@ -56,6 +61,25 @@ impl TryFrom<String> for Version {
     }
 }
 
+/// Which component of a [Version] to increase when publishing a new version of a resource
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    #[default]
+    Patch,
+}
+
+impl Version {
+    pub fn bumped(&self, bump: VersionBump) -> Version {
+        match bump {
+            VersionBump::Major => Version { major: self.major + 1, minor: 0, patch: 0 },
+            VersionBump::Minor => Version { major: self.major, minor: self.minor + 1, patch: 0 },
+            VersionBump::Patch => Version { major: self.major, minor: self.minor, patch: self.patch + 1 },
+        }
+    }
+}
+
 impl Into<String> for Version {
     fn into(self) -> String {
         format!("{}.{}.{}", self.major, self.minor, self.patch)
@@ -86,6 +110,15 @@ fn test_version_parsing() {
             "bla".parse::<u32>().expect_err("should fail parsing")
         ))
     );
+
+    let version = Version::try_from("1.2.3").unwrap();
+    assert_eq!(version.bumped(VersionBump::Major), Version::try_from("2.0.0").unwrap());
+    assert_eq!(version.bumped(VersionBump::Minor), Version::try_from("1.3.0").unwrap());
+    assert_eq!(version.bumped(VersionBump::Patch), Version::try_from("1.2.4").unwrap());
+
+    let mut versions: Vec<Version> = ["0.10.0", "0.4.10", "0.5.0", "0.4.9"].into_iter().map(|v| v.try_into().unwrap()).collect();
+    versions.sort();
+    assert_eq!(versions.into_iter().map(|v| v.to_string()).collect::<Vec<_>>(), ["0.4.9", "0.4.10", "0.5.0", "0.10.0"]);
 }
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
@@ -117,3 +150,62 @@ impl<const MAJOR: usize, const MINOR: usize, const PATCH: usize> TryFrom<Version
         }
     }
 }
+
+/// A version in the `MAJOR.MINOR` series that is at least `MAJOR.MINOR.PATCH`. Format versions
+/// only add fields in patch releases, so this is what a reader of some format can accept.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, Clone)]
+#[serde(try_from = "Version")]
+#[serde(into = "Version")]
+pub struct MinimumVersion<const MAJOR: usize, const MINOR: usize, const PATCH: usize>(Version);
+
+impl<const MAJOR: usize, const MINOR: usize, const PATCH: usize> MinimumVersion<MAJOR, MINOR, PATCH> {
+    pub fn minimum() -> Version {
+        Version { major: MAJOR, minor: MINOR, patch: PATCH }
+    }
+}
+
+impl<const MAJOR: usize, const MINOR: usize, const PATCH: usize> std::ops::Deref for MinimumVersion<MAJOR, MINOR, PATCH> {
+    type Target = Version;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const MAJOR: usize, const MINOR: usize, const PATCH: usize> Display for MinimumVersion<MAJOR, MINOR, PATCH> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const MAJOR: usize, const MINOR: usize, const PATCH: usize> From<MinimumVersion<MAJOR, MINOR, PATCH>> for Version {
+    fn from(value: MinimumVersion<MAJOR, MINOR, PATCH>) -> Self {
+        value.0
+    }
+}
+
+impl<const MAJOR: usize, const MINOR: usize, const PATCH: usize> TryFrom<Version> for MinimumVersion<MAJOR, MINOR, PATCH> {
+    type Error = VersionParsingError;
+
+    fn try_from(value: Version) -> Result<Self, Self::Error> {
+        if (value.major, value.minor) != (MAJOR, MINOR) {
+            return Err(VersionParsingError::IncompatibleVersion { major: MAJOR, minor: MINOR, found: value });
+        }
+        if value < Self::minimum() {
+            return Err(VersionParsingError::TooOld { minimum: Self::minimum(), found: value });
+        }
+        Ok(Self(value))
+    }
+}
+
+#[test]
+fn test_minimum_version() {
+    type Format = MinimumVersion<0, 5, 2>;
+    let parse = |raw: &str| Format::try_from(Version::try_from(raw).unwrap());
+
+    assert_eq!(*parse("0.5.2").unwrap(), Version::try_from("0.5.2").unwrap());
+    assert!(parse("0.5.17").is_ok());
+    assert!(matches!(parse("0.5.1"), Err(VersionParsingError::TooOld { .. })));
+    assert!(matches!(parse("0.4.9"), Err(VersionParsingError::IncompatibleVersion { .. })));
+    assert!(matches!(parse("1.5.3"), Err(VersionParsingError::IncompatibleVersion { .. })));
+    assert_eq!(serde_yaml::to_string(&parse("0.5.3").unwrap()).unwrap().trim(), "0.5.3");
+}
@@ -0,0 +1,116 @@
+use std::num::NonZeroU64;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{version::VersionBump, Rdf, Version};
+
+/// A version of a resource that has been superseded by a newer one
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct PreviousVersion {
+    pub version: Version,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_number: Option<NonZeroU64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// The bookkeeping the zoo keeps alongside `version` to tell successive uploads of a resource apart
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct VersionHistory {
+    /// Counts uploads of this resource, starting at 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_number: Option<NonZeroU64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_versions: Vec<PreviousVersion>,
+}
+
+impl VersionHistory {
+    /// Records `current` as a previous version and returns the version that should replace it
+    pub fn new_version(
+        &mut self,
+        current: Option<&Version>,
+        id: Option<String>,
+        doi: Option<String>,
+        bump: VersionBump,
+        now: DateTime<Utc>,
+    ) -> Version {
+        let next_version = match current {
+            Some(current) => {
+                self.previous_versions.push(PreviousVersion {
+                    version: current.clone(),
+                    version_number: self.version_number,
+                    id,
+                    doi,
+                    timestamp: self.timestamp,
+                });
+                current.bumped(bump)
+            }
+            None => Version { major: 0, minor: 1, patch: 0 },
+        };
+        self.version_number = Some(match self.version_number {
+            Some(number) => number.saturating_add(1),
+            None => NonZeroU64::MIN.saturating_add(self.previous_versions.len() as u64),
+        });
+        self.timestamp = Some(now);
+        next_version
+    }
+}
+
+impl Rdf {
+    fn doi(&self) -> Option<&str> {
+        self.config.as_ref()?.get("bioimageio")?.get("doi")?.as_str()
+    }
+
+    /// Turns this rdf into the next version of the same resource. The DOI in `config.bioimageio` belongs
+    /// to the version being replaced, so it is moved into the history.
+    pub fn start_new_version(&mut self, bump: VersionBump) {
+        let doi = self.doi().map(String::from);
+        if let Some(bioimageio) = self
+            .config
+            .as_mut()
+            .and_then(|config| config.get_mut("bioimageio"))
+            .and_then(|bioimageio| bioimageio.as_mapping_mut())
+        {
+            bioimageio.remove("doi");
+        }
+        let id = self.id.as_ref().map(|id| id.to_string());
+        self.version = Some(self.history.new_version(self.version.as_ref(), id, doi, bump, Utc::now()));
+    }
+}
+
+#[test]
+fn test_new_version() {
+    let raw = "
+format_version: 0.2.3
+description: some model
+name: my model
+id: my-model
+version: 1.2.3
+version_number: 2
+config:
+  bioimageio:
+    doi: 10.5281/zenodo.123
+";
+    let mut rdf: Rdf = serde_yaml::from_str(raw).unwrap();
+    rdf.start_new_version(VersionBump::Minor);
+    assert_eq!(rdf.version, Some(Version::try_from("1.3.0").unwrap()));
+    assert_eq!(rdf.history.version_number, NonZeroU64::new(3));
+    assert!(rdf.history.timestamp.is_some());
+    assert_eq!(rdf.doi(), None);
+
+    let previous = &rdf.history.previous_versions[0];
+    assert_eq!(previous.version, Version::try_from("1.2.3").unwrap());
+    assert_eq!(previous.version_number, NonZeroU64::new(2));
+    assert_eq!(previous.id.as_deref(), Some("my-model"));
+    assert_eq!(previous.doi.as_deref(), Some("10.5281/zenodo.123"));
+
+    let reparsed: Rdf = serde_yaml::from_str(&serde_yaml::to_string(&rdf).unwrap()).unwrap();
+    assert_eq!(reparsed, rdf);
+}
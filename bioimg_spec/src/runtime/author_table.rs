@@ -0,0 +1,220 @@
+//! Reading authors from a spreadsheet saved as CSV or TSV, one author per row. The columns are told apart by a
+//! header row if there is one, and are otherwise taken to be name, affiliation, email, ORCID and GitHub user.
+
+use crate::rdf::{author::Author2, bounded_string::BoundedString, orcid::Orcid};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AuthorTableError {
+    #[error("Line {line}: a quoted cell is never closed")]
+    UnclosedQuote { line: usize },
+    #[error("The header has no name column")]
+    NoNameColumn,
+    #[error("No authors found")]
+    NoRows,
+}
+
+/// Why one row couldn't become an author. The other rows are read all the same.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Line {line}: bad {column}: {message}")]
+pub struct AuthorRowError {
+    pub line: usize,
+    pub column: &'static str,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Column {
+    Name,
+    Affiliation,
+    Email,
+    Orcid,
+    Github,
+}
+
+impl Column {
+    const IN_ORDER: [Self; 5] = [Self::Name, Self::Affiliation, Self::Email, Self::Orcid, Self::Github];
+
+    /// The column a header cell names, ignoring case, spaces and punctuation
+    fn from_header(header: &str) -> Option<Self> {
+        let normalized: String = header.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+        Some(match normalized.as_str() {
+            "name" | "fullname" | "author" => Self::Name,
+            "affiliation" | "institution" | "institute" => Self::Affiliation,
+            "email" | "mail" | "emailaddress" => Self::Email,
+            "orcid" | "orcidid" => Self::Orcid,
+            "github" | "githubuser" | "githubusername" => Self::Github,
+            _ => return None,
+        })
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Affiliation => "affiliation",
+            Self::Email => "email",
+            Self::Orcid => "ORCID",
+            Self::Github => "GitHub user",
+        }
+    }
+}
+
+/// Tabs, semicolons or commas, whichever the first line has most of
+fn detect_delimiter(raw: &str) -> char {
+    let first_line = raw.lines().next().unwrap_or_default();
+    ['\t', ';', ',']
+        .into_iter()
+        .rev()
+        .max_by_key(|delimiter| first_line.matches(*delimiter).count())
+        .unwrap_or(',')
+}
+
+/// The cells of each row, next to the line the row starts on. Cells can be quoted with `"`, which lets them hold
+/// delimiters and line breaks, and `""` within quotes is a quote.
+fn records(raw: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, AuthorTableError> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut in_quotes = false;
+    let mut quote_line = 1;
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                in_quotes = true;
+                quote_line = line;
+            }
+            _ if in_quotes => cell.push(c),
+            '\r' => (),
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push((row_line, std::mem::take(&mut row)));
+                row_line = line;
+            }
+            _ if c == delimiter => row.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(AuthorTableError::UnclosedQuote { line: quote_line });
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push((row_line, row));
+    }
+    Ok(rows)
+}
+
+fn bounded(cell: &str, column: Column, line: usize) -> Result<BoundedString<1, 1023>, AuthorRowError> {
+    BoundedString::try_from(cell).map_err(|err| AuthorRowError {
+        line,
+        column: column.label(),
+        message: err.to_string(),
+    })
+}
+
+fn author_from_row(columns: &[Option<Column>], cells: &[String], line: usize) -> Result<Author2, AuthorRowError> {
+    let cell = |wanted: Column| {
+        let index = columns.iter().position(|column| *column == Some(wanted))?;
+        cells.get(index).map(|cell| cell.trim()).filter(|cell| !cell.is_empty())
+    };
+    let optional = |column: Column, value: Option<&str>| value.map(|value| bounded(value, column, line)).transpose();
+
+    let Some(name) = cell(Column::Name) else {
+        return Err(AuthorRowError {
+            line,
+            column: Column::Name.label(),
+            message: "missing".to_owned(),
+        });
+    };
+    let orcid = cell(Column::Orcid).map(|orcid| {
+        let code = ["https://orcid.org/", "http://orcid.org/", "orcid.org/"]
+            .iter()
+            .find_map(|prefix| orcid.strip_prefix(prefix))
+            .unwrap_or(orcid);
+        Orcid::try_from(code.to_uppercase()).map_err(|err| AuthorRowError {
+            line,
+            column: Column::Orcid.label(),
+            message: err.to_string(),
+        })
+    });
+    let github_user = cell(Column::Github).map(|user| {
+        let user = user.strip_prefix("https://github.com/").unwrap_or(user);
+        user.trim_start_matches('@').trim_end_matches('/')
+    });
+    Ok(Author2 {
+        name: bounded(name, Column::Name, line)?,
+        affiliation: optional(Column::Affiliation, cell(Column::Affiliation))?,
+        email: optional(Column::Email, cell(Column::Email))?,
+        github_user: optional(Column::Github, github_user)?,
+        orcid: orcid.transpose()?,
+    })
+}
+
+/// Reads one author per row of `raw`, skipping empty rows. Rows that don't make a valid author are returned as
+/// errors in their place, so that the valid ones can still be used.
+pub fn read_author_table(raw: &str) -> Result<Vec<Result<Author2, AuthorRowError>>, AuthorTableError> {
+    let raw = raw.trim_start_matches('\u{feff}');
+    let mut rows = records(raw, detect_delimiter(raw))?
+        .into_iter()
+        .filter(|(_, cells)| cells.iter().any(|cell| !cell.trim().is_empty()))
+        .peekable();
+
+    let header: Option<Vec<Option<Column>>> = rows
+        .peek()
+        .map(|(_, cells)| cells.iter().map(|cell| Column::from_header(cell)).collect())
+        .filter(|columns: &Vec<Option<Column>>| columns.iter().any(Option::is_some));
+    let columns = match header {
+        Some(columns) => {
+            rows.next();
+            if !columns.contains(&Some(Column::Name)) {
+                return Err(AuthorTableError::NoNameColumn);
+            }
+            columns
+        }
+        None => Column::IN_ORDER.into_iter().map(Some).collect(),
+    };
+
+    let authors: Vec<_> = rows.map(|(line, cells)| author_from_row(&columns, &cells, line)).collect();
+    if authors.is_empty() {
+        return Err(AuthorTableError::NoRows);
+    }
+    Ok(authors)
+}
+
+#[test]
+fn test_read_author_table() {
+    let raw = "\u{feff}Name,E-Mail,ORCID,Affiliation,Notes\r\n\
+        \"Doe, Jane\",jane@example.org,https://orcid.org/0000-0002-8205-121x,\"EMBL \"\"Heidelberg\"\"\",lead\r\n\
+        \r\n\
+        ,nobody@example.org,,,\r\n\
+        John Roe,,0000-0001-7051-119X,,\r\n";
+    let authors = read_author_table(raw).unwrap();
+    assert_eq!(authors.len(), 3);
+    let jane = authors[0].as_ref().unwrap();
+    assert_eq!(jane.name.to_string(), "Doe, Jane");
+    assert_eq!(jane.affiliation.as_ref().unwrap().to_string(), "EMBL \"Heidelberg\"");
+    assert_eq!(jane.orcid.clone().map(Into::<String>::into).as_deref(), Some("0000-0002-8205-121X"));
+    assert!(matches!(&authors[1], Err(AuthorRowError { line: 4, column: "name", .. })));
+    assert!(matches!(&authors[2], Err(AuthorRowError { line: 5, column: "ORCID", .. })));
+
+    let headerless = read_author_table("Jane Doe\tEMBL\t\t\t@janedoe\nJohn Roe").unwrap();
+    let jane = headerless[0].as_ref().unwrap();
+    assert_eq!(jane.github_user.as_ref().unwrap().to_string(), "janedoe");
+    assert!(jane.email.is_none());
+    assert_eq!(headerless[1].as_ref().unwrap().name.to_string(), "John Roe");
+
+    assert_eq!(read_author_table("email\njane@example.org"), Err(AuthorTableError::NoNameColumn));
+    assert_eq!(read_author_table("name;affiliation\n"), Err(AuthorTableError::NoRows));
+    assert_eq!(read_author_table("\"Jane"), Err(AuthorTableError::UnclosedQuote { line: 1 }));
+}
@@ -0,0 +1,415 @@
+//! Reading references from BibTeX, where most authors already keep them. Only what a citation needs is read:
+//! the authors, year, title and journal become its text, next to its DOI and url.
+
+use std::collections::HashMap;
+
+use url::Url;
+
+use super::citation::{cited_name, citation_text};
+use crate::rdf::{bounded_string::BoundedString, cite_entry::CiteEntry2, Doi};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BibtexError {
+    #[error("Line {line}: {message}")]
+    BadSyntax { line: usize, message: String },
+    #[error("No references found")]
+    NoEntries,
+    #[error("Bad {field} in '{key}': {message}")]
+    BadField { key: String, field: &'static str, message: String },
+}
+
+/// The fields of an entry by lowercase name, as they are written
+type Fields = HashMap<String, String>;
+
+/// Reads the `@...{...}` entries of a `.bib` file
+struct BibParser<'r> {
+    raw: &'r str,
+    pos: usize,
+    /// Abbreviations defined with `@string`
+    strings: HashMap<String, String>,
+}
+
+impl<'r> BibParser<'r> {
+    fn error(&self, message: impl Into<String>) -> BibtexError {
+        BibtexError::BadSyntax {
+            line: self.raw[..self.pos].matches('\n').count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.raw[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), BibtexError> {
+        self.skip_whitespace();
+        match self.bump() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.error(format!("expected '{expected}', found '{c}'"))),
+            None => Err(self.error(format!("expected '{expected}', found the end"))),
+        }
+    }
+
+    /// A name, key or bare value: everything up to a character with a meaning of its own
+    fn word(&mut self) -> &'r str {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !c.is_whitespace() && !"{}()\",=#@".contains(c)) {
+            self.bump();
+        }
+        &self.raw[start..self.pos]
+    }
+
+    /// What is between the braces starting here, inner braces included
+    fn braced(&mut self) -> Result<&'r str, BibtexError> {
+        self.expect('{')?;
+        let start = self.pos;
+        let mut depth = 0;
+        loop {
+            match self.bump() {
+                Some('{') => depth += 1,
+                Some('}') if depth == 0 => return Ok(&self.raw[start..self.pos - 1]),
+                Some('}') => depth -= 1,
+                Some(_) => (),
+                None => return Err(self.error("a '{' is never closed")),
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<&'r str, BibtexError> {
+        self.expect('"')?;
+        let start = self.pos;
+        let mut depth = 0;
+        loop {
+            match self.bump() {
+                Some('{') => depth += 1,
+                Some('}') => depth -= 1,
+                Some('"') if depth == 0 => return Ok(&self.raw[start..self.pos - 1]),
+                Some(_) => (),
+                None => return Err(self.error("a '\"' is never closed")),
+            }
+        }
+    }
+
+    /// A value made of braced, quoted and bare parts joined with `#`, bare ones standing for abbreviations
+    fn value(&mut self) -> Result<String, BibtexError> {
+        let mut value = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('{') => value.push_str(self.braced()?),
+                Some('"') => value.push_str(self.quoted()?),
+                _ => {
+                    let word = self.word();
+                    if word.is_empty() {
+                        return Err(self.error("expected a value"));
+                    }
+                    value.push_str(self.strings.get(&word.to_lowercase()).map_or(word, String::as_str));
+                }
+            }
+            self.skip_whitespace();
+            if self.peek() != Some('#') {
+                return Ok(value);
+            }
+            self.bump();
+        }
+    }
+
+    /// The `name = value` pairs of an entry, up to and including `closing`
+    fn fields(&mut self, closing: char) -> Result<Fields, BibtexError> {
+        let mut fields = HashMap::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(closing) {
+                self.bump();
+                return Ok(fields);
+            }
+            let name = self.word().to_lowercase();
+            if name.is_empty() {
+                return Err(self.error("expected the name of a field"));
+            }
+            self.expect('=')?;
+            let value = self.value()?;
+            fields.insert(name, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(c) if c == closing => (),
+                _ => return Err(self.error(format!("expected ',' or '{closing}' after a field"))),
+            }
+        }
+    }
+
+    /// The next reference, with its key, skipping comments and taking note of abbreviations
+    fn next_entry(&mut self) -> Result<Option<(String, Fields)>, BibtexError> {
+        loop {
+            // anything outside of entries is a comment
+            let Some(at) = self.raw[self.pos..].find('@') else {
+                return Ok(None);
+            };
+            self.pos += at + 1;
+            let kind = self.word().to_lowercase();
+            self.skip_whitespace();
+            let closing = match self.peek() {
+                Some('{') => '}',
+                Some('(') => ')',
+                // like the @ of an email address in a comment
+                _ => continue,
+            };
+            match kind.as_str() {
+                "comment" | "preamble" if closing == '}' => {
+                    self.braced()?;
+                }
+                "comment" | "preamble" => {
+                    let end = self.raw[self.pos..].find(')').ok_or_else(|| self.error("a '(' is never closed"))?;
+                    self.pos += end + 1;
+                }
+                "string" => {
+                    self.bump();
+                    let strings = self.fields(closing)?;
+                    self.strings.extend(strings);
+                }
+                _ => {
+                    self.bump();
+                    let key = self.word().to_owned();
+                    self.expect(',')?;
+                    return Ok(Some((key, self.fields(closing)?)));
+                }
+            }
+        }
+    }
+}
+
+/// The letter `accent` puts on `letter`, e.g. `\"o` for `ö`
+fn accented(accent: char, letter: char) -> Option<char> {
+    const ACCENTED: [(char, &str, &str); 6] = [
+        ('"', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+        ('\'', "aeiouycnAEIOUY", "áéíóúýćńÁÉÍÓÚÝ"),
+        ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+        ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+        ('~', "anoANO", "ãñõÃÑÕ"),
+        ('c', "csCS", "çşÇŞ"),
+    ];
+    let (_, letters, accented) = ACCENTED.iter().find(|(a, ..)| *a == accent)?;
+    letters.chars().position(|l| l == letter).and_then(|index| accented.chars().nth(index))
+}
+
+/// The text a value stands for, without the braces and LaTeX commands in it
+fn clean(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => (),
+            '~' => text.push(' '),
+            '\\' => {
+                let Some(next) = chars.next() else {
+                    break;
+                };
+                if next.is_ascii_alphabetic() {
+                    let mut command = next.to_string();
+                    while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+                        command.push(letter);
+                    }
+                    match command.as_str() {
+                        "ss" => text.push('ß'),
+                        "o" => text.push('ø'),
+                        "O" => text.push('Ø'),
+                        "aa" => text.push('å'),
+                        "AA" => text.push('Å'),
+                        "c" => {
+                            while chars.next_if(|c| *c == '{' || c.is_whitespace()).is_some() {}
+                            if let Some(letter) = chars.next() {
+                                text.push(accented('c', letter).unwrap_or(letter));
+                            }
+                        }
+                        // commands like \textit only style their argument, which is kept
+                        _ => (),
+                    }
+                } else if "\"'`^~".contains(next) {
+                    while chars.next_if_eq(&'{').is_some() {}
+                    if let Some(letter) = chars.next() {
+                        text.push(accented(next, letter).unwrap_or(letter));
+                    }
+                } else {
+                    // escaped characters like \& and \%
+                    text.push(next);
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ").replace("--", "–")
+}
+
+/// Splits `value` at `separator` wherever it is outside of braces
+fn split_outside_braces<'v>(value: &'v str, separator: &str) -> Vec<&'v str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    // keeps the byte offsets of `value`, unlike to_lowercase
+    let lowercase = value.to_ascii_lowercase();
+    for (idx, c) in value.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ if depth == 0 && idx >= start && lowercase[idx..].starts_with(separator) => {
+                parts.push(&value[start..idx]);
+                start = idx + separator.len();
+            }
+            _ => (),
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Whether all of `name` is inside a single pair of braces
+fn wholly_braced(name: &str) -> bool {
+    if !name.starts_with('{') {
+        return false;
+    }
+    let mut depth = 0;
+    for (idx, c) in name.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => (),
+        }
+        if depth == 0 {
+            return idx + 1 == name.len();
+        }
+    }
+    false
+}
+
+/// The authors as they are cited, and whether the list ends in "and others"
+fn cited_authors(authors: &str) -> (Vec<String>, bool) {
+    let authors = authors.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut cited = vec![];
+    let mut others = false;
+    for name in split_outside_braces(&authors, " and ").into_iter().map(str::trim).filter(|name| !name.is_empty()) {
+        if name == "others" {
+            others = true;
+            continue;
+        }
+        // names that are all in braces, like those of consortia, are not split up
+        if wholly_braced(name) {
+            cited.push(clean(name));
+            continue;
+        }
+        let parts = split_outside_braces(name, ",");
+        let (family, given) = match parts.as_slice() {
+            // "von Last, First" or "von Last, Jr, First"
+            [family, .., given] => (clean(family), clean(given)),
+            // "First von Last", where the family name starts at the first lowercase word
+            _ => {
+                let words: Vec<&str> = split_outside_braces(name, " ").into_iter().filter(|w| !w.is_empty()).collect();
+                let last = words.len() - 1;
+                let start = words[..last].iter().position(|word| word.starts_with(char::is_lowercase)).unwrap_or(last);
+                (clean(&words[start..].join(" ")), clean(&words[..start].join(" ")))
+            }
+        };
+        cited.extend(cited_name(&family, &given));
+    }
+    (cited, others)
+}
+
+fn cite_entry(key: String, fields: Fields) -> Result<CiteEntry2, BibtexError> {
+    let field = |name: &str| fields.get(name).map(|value| clean(value)).filter(|value| !value.is_empty());
+    let bad_field = |field: &'static str, message: String| BibtexError::BadField { key: key.clone(), field, message };
+
+    let (authors, others) = fields.get("author").map(|authors| cited_authors(authors)).unwrap_or_default();
+    let year = field("year").or_else(|| field("date").map(|date| date.chars().take(4).collect()));
+    let parts = [field("title"), field("journal").or_else(|| field("booktitle"))];
+    let mut text = citation_text(authors, others, year, parts.into_iter().flatten());
+    if text.is_empty() {
+        text = key.clone();
+    }
+    let doi = match field("doi") {
+        Some(doi) => Some(Doi::try_from(doi).map_err(|err| bad_field("doi", err.to_string()))?),
+        None => None,
+    };
+    let url = match field("url") {
+        Some(url) => Some(Url::parse(&url).map_err(|err| bad_field("url", err.to_string()))?),
+        None => None,
+    };
+    Ok(CiteEntry2 {
+        text: BoundedString::try_from(text).map_err(|err| bad_field("text", err.to_string()))?,
+        doi,
+        url,
+    })
+}
+
+/// Reads every reference in `raw`, the contents of a `.bib` file or a single entry pasted from one
+pub fn read_bibtex(raw: &str) -> Result<Vec<CiteEntry2>, BibtexError> {
+    let mut parser = BibParser {
+        raw,
+        pos: 0,
+        strings: HashMap::new(),
+    };
+    let mut entries = vec![];
+    while let Some((key, fields)) = parser.next_entry()? {
+        entries.push(cite_entry(key, fields)?);
+    }
+    if entries.is_empty() {
+        return Err(BibtexError::NoEntries);
+    }
+    Ok(entries)
+}
+
+#[test]
+fn test_reading_bibtex() {
+    let raw = r#"
+        Exported from a reference manager
+        @string{ lncs = "Lecture Notes in Computer Science" }
+        @InProceedings{ronneberger2015,
+          author    = {Ronneberger, Olaf and Fischer, Philipp and Brox, Thomas},
+          title     = {{U-Net}: Convolutional Networks for Biomedical Image Segmentation},
+          booktitle = lncs,
+          year      = 2015,
+          doi       = {10.1007/978-3-319-24574-4\_28},
+        }
+        @comment{ not a reference }
+        @article(schmidt2018,
+          author = "Uwe Schmidt and Martin Weigert and others",
+          title = "Cell Detection with Star-convex Polygons",
+          journal = {MICCAI},
+          date = {2018-09-13},
+          url = {https://arxiv.org/abs/1806.03535}
+        )
+        @misc{consortium, author = {{The J{\"o}rg Consortium} and Ludwig van Beethoven}, title = "Stra{\ss}e \& S{\'e}ance"}
+    "#;
+    let entries = read_bibtex(raw).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries[0].text.to_string(),
+        "Ronneberger, O., Fischer, P., Brox, T. (2015). U-Net: Convolutional Networks for Biomedical Image \
+         Segmentation. Lecture Notes in Computer Science."
+    );
+    assert_eq!(entries[0].doi.as_ref().map(ToString::to_string).as_deref(), Some("10.1007/978-3-319-24574-4_28"));
+    assert_eq!(entries[0].url, None);
+    assert_eq!(entries[1].text.to_string(), "Schmidt, U. et al. (2018). Cell Detection with Star-convex Polygons. MICCAI.");
+    assert_eq!(entries[1].url.as_ref().map(Url::as_str), Some("https://arxiv.org/abs/1806.03535"));
+    assert_eq!(entries[2].text.to_string(), "The Jörg Consortium, van Beethoven, L. Straße & Séance.");
+
+    assert_eq!(read_bibtex("no entries here"), Err(BibtexError::NoEntries));
+    assert!(matches!(read_bibtex("@article{key,\n title = {open"), Err(BibtexError::BadSyntax { line: 2, .. })));
+    assert!(matches!(
+        read_bibtex("@article{key, doi = {not a doi}}"),
+        Err(BibtexError::BadField { field: "doi", .. })
+    ));
+}
@@ -0,0 +1,235 @@
+//! Looking references up by their DOI, to fill in how they are cited. The DOI resolver answers for
+//! both Crossref and DataCite DOIs when asked for CSL json, so a single request covers either.
+
+use serde::Deserialize;
+use url::Url;
+
+use crate::rdf::Doi;
+
+/// The DOI resolver, which redirects to the registration agency of each DOI
+pub const DOI_RESOLVER_URL: &str = "https://doi.org";
+
+/// Authors beyond this many are cited as "et al."
+const MAX_CITED_AUTHORS: usize = 3;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CitationLookupError {
+    #[error("Could not download {url}: {message}")]
+    HttpError { url: String, message: String },
+    #[error("{url} is not a citation record: {message}")]
+    BadRecord { url: String, message: String },
+}
+
+/// Some registries give titles as a string and others as a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn first(self) -> Option<String> {
+        match self {
+            Self::One(text) => Some(text),
+            Self::Many(texts) => texts.into_iter().next(),
+        }
+        .map(|text| text.trim().to_owned())
+        .filter(|text| !text.is_empty())
+    }
+}
+
+#[derive(Deserialize)]
+struct CslName {
+    #[serde(default)]
+    family: Option<String>,
+    #[serde(default)]
+    given: Option<String>,
+    /// Organizations and names that couldn't be split up
+    #[serde(default)]
+    literal: Option<String>,
+}
+
+impl CslName {
+    fn cited(&self) -> Option<String> {
+        match self.family.as_deref() {
+            Some(family) => cited_name(family, self.given.as_deref().unwrap_or_default()),
+            None => self.literal.as_deref().map(str::trim).filter(|literal| !literal.is_empty()).map(str::to_owned),
+        }
+    }
+}
+
+/// Like "Ronneberger, O.", or `None` without a family name
+pub(crate) fn cited_name(family: &str, given: &str) -> Option<String> {
+    let family = family.trim();
+    if family.is_empty() {
+        return None;
+    }
+    let initials: Vec<String> = given
+        .split_whitespace()
+        .filter_map(|name| name.chars().next())
+        .map(|initial| format!("{initial}."))
+        .collect();
+    if initials.is_empty() {
+        return Some(family.to_owned());
+    }
+    Some(format!("{family}, {}", initials.join(" ")))
+}
+
+/// A line in the style of "Authors (year). Title. Journal.", citing only the first author if there are
+/// many of them or `et_al` is set
+pub(crate) fn citation_text(
+    mut authors: Vec<String>,
+    et_al: bool,
+    year: Option<String>,
+    parts: impl IntoIterator<Item = String>,
+) -> String {
+    if !authors.is_empty() && (et_al || authors.len() > MAX_CITED_AUTHORS) {
+        authors.truncate(1);
+        authors[0].push_str(" et al.");
+    }
+    let mut text = authors.join(", ");
+    if let Some(year) = year {
+        text = format!("{text} ({year})").trim().to_owned();
+    }
+    for part in parts {
+        if !text.is_empty() {
+            text.push_str(if text.ends_with('.') { " " } else { ". " });
+        }
+        text.push_str(&part);
+    }
+    if !text.is_empty() && !text.ends_with('.') {
+        text.push('.');
+    }
+    text
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CslDate {
+    #[serde(default)]
+    date_parts: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CslRecord {
+    #[serde(default)]
+    title: Option<OneOrMany>,
+    #[serde(default)]
+    author: Vec<CslName>,
+    #[serde(default)]
+    issued: Option<CslDate>,
+    #[serde(default)]
+    container_title: Option<OneOrMany>,
+    #[serde(rename = "URL", default)]
+    url: Option<String>,
+}
+
+/// How a reference is cited: a line of text in the style of "Authors (year). Title. Journal." and where to read it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+    pub text: String,
+    pub url: Url,
+}
+
+impl Citation {
+    /// Reads a record in CSL json, as served by the DOI resolver. The url of `doi` is used if the record has none.
+    pub fn from_csl_json(doi: &Doi, raw: &str) -> Result<Self, serde_json::Error> {
+        let record: CslRecord = serde_json::from_str(raw)?;
+        let authors: Vec<String> = record.author.iter().filter_map(CslName::cited).collect();
+        let year = record
+            .issued
+            .and_then(|issued| issued.date_parts.into_iter().next())
+            .and_then(|parts| parts.into_iter().next())
+            .and_then(|year| match year {
+                serde_json::Value::Number(year) => Some(year.to_string()),
+                serde_json::Value::String(year) => Some(year),
+                _ => None,
+            });
+
+        let parts = [record.title, record.container_title].into_iter().flatten().filter_map(OneOrMany::first);
+        let mut text = citation_text(authors, false, year, parts);
+        if text.is_empty() {
+            text = doi.to_string();
+        }
+
+        let url = record
+            .url
+            .and_then(|url| Url::parse(&url).ok())
+            .unwrap_or_else(|| Url::parse(&doi.url()).expect("DOI urls are valid"));
+        Ok(Self { text, url })
+    }
+}
+
+/// Looks DOIs up through a DOI resolver
+#[derive(Clone)]
+pub struct CitationClient {
+    resolver_url: String,
+    agent: ureq::Agent,
+}
+
+impl CitationClient {
+    pub fn new(resolver_url: impl Into<String>) -> Self {
+        Self {
+            resolver_url: resolver_url.into(),
+            agent: super::network::agent_builder().build(),
+        }
+    }
+
+    /// A client for the resolver at doi.org
+    pub fn doi_org() -> Self {
+        Self::new(DOI_RESOLVER_URL)
+    }
+
+    pub fn lookup(&self, doi: &Doi) -> Result<Citation, CitationLookupError> {
+        let url = format!("{}/{}", self.resolver_url.trim_end_matches('/'), doi);
+        let raw = self
+            .agent
+            .get(&url)
+            .set("Accept", "application/vnd.citationstyles.csl+json")
+            .call()
+            .map_err(|err| CitationLookupError::HttpError { url: url.clone(), message: err.to_string() })?
+            .into_string()
+            .map_err(|err| CitationLookupError::HttpError { url: url.clone(), message: err.to_string() })?;
+        Citation::from_csl_json(doi, &raw).map_err(|err| CitationLookupError::BadRecord { url, message: err.to_string() })
+    }
+}
+
+#[test]
+fn test_reading_csl_record() {
+    use std::str::FromStr;
+
+    let doi = Doi::from_str("10.1007/978-3-319-24574-4_28").unwrap();
+    let raw = r#"{
+        "type": "chapter",
+        "title": "U-Net: Convolutional Networks for Biomedical Image Segmentation",
+        "container-title": "Lecture Notes in Computer Science",
+        "author": [
+            {"family": "Ronneberger", "given": "Olaf"},
+            {"family": "Fischer", "given": "Philipp"},
+            {"family": "Brox", "given": "Thomas"}
+        ],
+        "issued": {"date-parts": [[2015]]},
+        "URL": "http://dx.doi.org/10.1007/978-3-319-24574-4_28"
+    }"#;
+    let citation = Citation::from_csl_json(&doi, raw).unwrap();
+    assert_eq!(
+        citation.text,
+        "Ronneberger, O., Fischer, P., Brox, T. (2015). U-Net: Convolutional Networks for Biomedical Image Segmentation. \
+         Lecture Notes in Computer Science."
+    );
+    assert_eq!(citation.url.as_str(), "http://dx.doi.org/10.1007/978-3-319-24574-4_28");
+
+    // DataCite records, e.g. of zenodo uploads, have organizations as authors and titles in lists
+    let raw = r#"{
+        "title": ["Some dataset"],
+        "author": [{"literal": "A Lab"}, {"family": "B"}, {"family": "C"}, {"family": "D"}],
+        "issued": {"date-parts": [["2021", 3]]}
+    }"#;
+    let citation = Citation::from_csl_json(&doi, raw).unwrap();
+    assert_eq!(citation.text, "A Lab et al. (2021). Some dataset.");
+    assert_eq!(citation.url.as_str(), doi.url());
+
+    assert_eq!(Citation::from_csl_json(&doi, "{}").unwrap().text, doi.to_string());
+}
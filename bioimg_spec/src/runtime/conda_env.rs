@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use crate::rdf::{FileDescr, FileReference, Sha256};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CondaEnvParsingError {
+    #[error("Environment file is not valid: {0}")]
+    BadYaml(String),
+    #[error("Environment has no dependencies")]
+    NoDependencies,
+    #[error("Channel name can't be empty")]
+    EmptyChannel,
+    #[error("Dependency '{0}' is not a valid package spec")]
+    BadDependency(String),
+    #[error("Dependency '{0}' is neither a package spec nor a pip section with a list of packages")]
+    BadEntry(String),
+    #[error("Environment has more than one pip section")]
+    ManyPipSections,
+    #[error("Environment installs pip packages, but doesn't depend on 'pip' itself")]
+    PipNotInstalled,
+}
+
+/// A conda environment file, as given for the `dependencies` of weights entries. Conda packages
+/// are specs like `python=3.10` or `conda-forge::numpy >=1.24`; pip packages are kept as they are,
+/// since pip also takes urls and options there.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawCondaEnv")]
+#[serde(into = "RawCondaEnv")]
+pub struct CondaEnv {
+    pub name: Option<String>,
+    pub channels: Vec<String>,
+    pub dependencies: Vec<String>,
+    pub pip: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawCondaEnv {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    channels: Vec<String>,
+    #[serde(default)]
+    dependencies: Vec<RawDependency>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawDependency {
+    Conda(String),
+    Pip { pip: Vec<String> },
+    /// Anything else, kept so that it can be pointed out instead of failing on the whole file
+    Other(serde_yaml::Value),
+}
+
+impl TryFrom<RawCondaEnv> for CondaEnv {
+    type Error = CondaEnvParsingError;
+    fn try_from(raw: RawCondaEnv) -> Result<Self, Self::Error> {
+        if raw.channels.iter().any(|channel| channel.trim().is_empty()) {
+            return Err(CondaEnvParsingError::EmptyChannel);
+        }
+        let mut dependencies = vec![];
+        let mut pip = None;
+        for dependency in raw.dependencies {
+            match dependency {
+                RawDependency::Conda(spec) => dependencies.push(check_package_spec(spec)?),
+                RawDependency::Pip { .. } if pip.is_some() => return Err(CondaEnvParsingError::ManyPipSections),
+                RawDependency::Pip { pip: packages } => pip = Some(packages),
+                RawDependency::Other(entry) => {
+                    let entry = serde_yaml::to_string(&entry).unwrap_or_default();
+                    return Err(CondaEnvParsingError::BadEntry(entry.trim_end().to_owned()));
+                }
+            }
+        }
+        let pip = pip.unwrap_or_default();
+        if dependencies.is_empty() && pip.is_empty() {
+            return Err(CondaEnvParsingError::NoDependencies);
+        }
+        if !pip.is_empty() && !dependencies.iter().any(|spec| package_name(spec) == "pip") {
+            return Err(CondaEnvParsingError::PipNotInstalled);
+        }
+        Ok(Self {
+            name: raw.name,
+            channels: raw.channels,
+            dependencies,
+            pip,
+        })
+    }
+}
+
+impl From<CondaEnv> for RawCondaEnv {
+    fn from(env: CondaEnv) -> Self {
+        let mut dependencies: Vec<_> = env.dependencies.into_iter().map(RawDependency::Conda).collect();
+        if !env.pip.is_empty() {
+            dependencies.push(RawDependency::Pip { pip: env.pip });
+        }
+        Self {
+            name: env.name,
+            channels: env.channels,
+            dependencies,
+        }
+    }
+}
+
+/// The package name of a conda spec, without the channel and version, e.g. `numpy` for `conda-forge::numpy>=1.24`
+fn package_name(spec: &str) -> &str {
+    let spec = spec.rsplit_once("::").map_or(spec, |(_, spec)| spec);
+    let end = spec.find(|c: char| "=<>!~ [".contains(c)).unwrap_or(spec.len());
+    &spec[..end]
+}
+
+fn check_package_spec(spec: String) -> Result<String, CondaEnvParsingError> {
+    let spec = spec.trim().to_owned();
+    let name = package_name(&spec);
+    let is_valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    if !is_valid_name {
+        return Err(CondaEnvParsingError::BadDependency(spec));
+    }
+    Ok(spec)
+}
+
+impl CondaEnv {
+    /// Name of the environment file inside of a package
+    pub const FILE_NAME: &'static str = "environment.yaml";
+
+    pub fn from_yaml(raw: &str) -> Result<Self, CondaEnvParsingError> {
+        let raw: RawCondaEnv = serde_yaml::from_str(raw).map_err(|err| CondaEnvParsingError::BadYaml(err.to_string()))?;
+        Self::try_from(raw)
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("environments are plain strings and lists")
+    }
+
+    /// How weights entries refer to this environment once [Self::to_yaml] is written to [Self::FILE_NAME]
+    pub fn file_descr(&self) -> FileDescr {
+        FileDescr {
+            source: FileReference::Path(Self::FILE_NAME.into()),
+            sha256: Some(Sha256::digest(self.to_yaml())),
+        }
+    }
+}
+
+#[test]
+fn test_conda_env_parsing() {
+    let raw_env = "
+name: unet
+channels: [pytorch, conda-forge]
+dependencies:
+  - python=3.10
+  - conda-forge::numpy >=1.24
+  - pytorch
+  - pip
+  - pip:
+      - torch-em
+      - git+https://github.com/constantinpape/elf.git
+";
+    let env = CondaEnv::from_yaml(raw_env).unwrap();
+    assert_eq!(env.dependencies.len(), 4);
+    assert_eq!(env.pip.len(), 2);
+    assert_eq!(CondaEnv::from_yaml(&env.to_yaml()).unwrap(), env);
+    assert_eq!(env.file_descr().sha256, Some(Sha256::digest(env.to_yaml())));
+
+    assert_eq!(
+        CondaEnv::from_yaml(&raw_env.replace("  - pip\n", "")),
+        Err(CondaEnvParsingError::PipNotInstalled)
+    );
+    assert_eq!(
+        CondaEnv::from_yaml(&raw_env.replace("pytorch\n", "py/torch\n")),
+        Err(CondaEnvParsingError::BadDependency("py/torch".into()))
+    );
+    assert_eq!(CondaEnv::from_yaml("name: empty"), Err(CondaEnvParsingError::NoDependencies));
+    assert!(CondaEnv::from_yaml("dependencies: [{pip: [a]}, pip, {pip: [b]}]").is_err());
+    assert_eq!(
+        CondaEnv::from_yaml("dependencies: [pip, {pip: torch-em}]"),
+        Err(CondaEnvParsingError::BadEntry("pip: torch-em".into()))
+    );
+}
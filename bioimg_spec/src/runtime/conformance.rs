@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+
+use crate::rdf::resource_description::AnyResourceRdf;
+use crate::rdf::ValidationReport;
+use crate::runtime::model_package::validate_yaml;
+
+/// Marks a corpus file as broken on purpose. The rest of the line must show up in the parsing error, and in an
+/// error of the validation report.
+const EXPECT_ERROR_MARKER: &str = "# expect-error:";
+/// Lists an entry the validation report must have, as the start of how it is displayed, e.g.
+/// `# expect-entry: warning: covers: Model has no cover`. The report must have no other entries. Invalid files
+/// only need these where validating reports the problem differently from parsing.
+const EXPECT_ENTRY_MARKER: &str = "# expect-entry:";
+
+#[derive(thiserror::Error, Debug)]
+pub enum CorpusError {
+    #[error("Could not read corpus at {path}: {source}")]
+    IoError { path: PathBuf, source: std::io::Error },
+    #[error("{0} has no yaml files")]
+    Empty(PathBuf),
+}
+
+/// What a corpus file is supposed to do when parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// Parses, and parses to the same thing again after being written back out. Validating it reports exactly
+    /// the `entries`, each of which is the start of a displayed entry.
+    Valid { entries: Vec<String> },
+    /// Fails to parse with an error mentioning `message`. Validating it reports exactly the `entries`, or an
+    /// error mentioning `message` too if there are none.
+    Invalid { message: String, entries: Vec<String> },
+    /// Is under an `invalid` directory without saying how it should fail, so any error would do, including one
+    /// about the resource type not being supported at all
+    Unmarked,
+}
+
+impl Expectation {
+    /// Files under an `invalid` directory are expected to fail with the message in their
+    /// `# expect-error:` line, which they must have; any other file is expected to be valid.
+    /// Either has the report entries of its `# expect-entry:` lines.
+    pub fn of_file(path: &Path, text: &str) -> Self {
+        let marked = |marker: &str| -> Vec<String> {
+            let lines = text.lines().filter_map(|line| line.trim().strip_prefix(marker));
+            lines.map(|rest| rest.trim().to_owned()).collect()
+        };
+        let in_invalid_dir = path.parent().is_some_and(|parent| parent.ends_with("invalid"));
+        match marked(EXPECT_ERROR_MARKER).into_iter().next() {
+            Some(message) => Self::Invalid {
+                message,
+                entries: marked(EXPECT_ENTRY_MARKER),
+            },
+            None if in_invalid_dir => Self::Unmarked,
+            None => Self::Valid {
+                entries: marked(EXPECT_ENTRY_MARKER),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseFailure {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Outcome of running every file of a corpus
+#[derive(Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub passed: Vec<PathBuf>,
+    pub failures: Vec<CaseFailure>,
+}
+
+impl CorpusReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl std::fmt::Display for CorpusReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} passed, {} failed", self.passed.len(), self.failures.len())?;
+        for failure in &self.failures {
+            writeln!(f, "  {}: {}", failure.path.display(), failure.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the entries of `report` to the `expected` starts of their displayed form, one entry per line
+fn check_report(report: &ValidationReport, expected: &[String]) -> Result<(), String> {
+    let entries: Vec<String> = report.entries.iter().map(ToString::to_string).collect();
+    if let Some(missing) = expected.iter().find(|expected| !entries.iter().any(|entry| entry.starts_with(expected.as_str()))) {
+        return Err(format!("expected the report to have '{missing}', got: [{}]", entries.join("; ")));
+    }
+    match entries.iter().find(|entry| !expected.iter().any(|expected| entry.starts_with(expected.as_str()))) {
+        Some(unexpected) => Err(format!("reported '{unexpected}' without an '{EXPECT_ENTRY_MARKER}' line for it")),
+        None => Ok(()),
+    }
+}
+
+/// Checks a single resource description against what it is expected to do, returning why it
+/// didn't behave, if it didn't
+pub fn check_case(text: &str, expectation: &Expectation) -> Result<(), String> {
+    let parsed = AnyResourceRdf::from_yaml(text);
+    match (expectation, parsed) {
+        (Expectation::Valid { .. }, Err(err)) => Err(format!("expected to be valid, but failed with: {err}")),
+        (Expectation::Valid { entries }, Ok(rdf)) => {
+            let written = rdf.to_yaml().map_err(|err| format!("could not be written back out: {err}"))?;
+            match AnyResourceRdf::from_yaml(&written) {
+                Ok(reparsed) if reparsed == rdf => check_report(&validate_yaml(text), entries),
+                Ok(_) => Err("changed after being written back out and parsed again".into()),
+                Err(err) => Err(format!("could not be parsed again after being written back out: {err}")),
+            }
+        }
+        (Expectation::Unmarked, _) => Err(format!("broken on purpose, but has no '{EXPECT_ERROR_MARKER}' line saying how")),
+        (Expectation::Invalid { .. }, Ok(_)) => Err("expected to be invalid, but parsed fine".into()),
+        (Expectation::Invalid { message, entries }, Err(err)) => {
+            let error = err.to_string();
+            if !error.contains(message.as_str()) {
+                return Err(format!("expected an error mentioning '{message}', got: {error}"));
+            }
+            let report = validate_yaml(text);
+            if !entries.is_empty() {
+                return check_report(&report, entries);
+            }
+            if report.errors().any(|entry| entry.message.contains(message.as_str())) {
+                return Ok(());
+            }
+            let entries: Vec<String> = report.entries.iter().map(ToString::to_string).collect();
+            Err(format!("expected the report to have an error mentioning '{message}', got: [{}]", entries.join("; ")))
+        }
+    }
+}
+
+fn collect_yaml_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), CorpusError> {
+    let io_error = |source| CorpusError::IoError {
+        path: dir.to_owned(),
+        source,
+    };
+    for entry in std::fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.is_dir() {
+            collect_yaml_files(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parses every yaml file under `dir`, checking each one against its [Expectation]
+pub fn run_corpus(dir: &Path) -> Result<CorpusReport, CorpusError> {
+    let mut paths = vec![];
+    collect_yaml_files(dir, &mut paths)?;
+    if paths.is_empty() {
+        return Err(CorpusError::Empty(dir.to_owned()));
+    }
+    paths.sort();
+
+    let mut report = CorpusReport::default();
+    for path in paths {
+        let text = std::fs::read_to_string(&path).map_err(|source| CorpusError::IoError {
+            path: path.clone(),
+            source,
+        })?;
+        match check_case(&text, &Expectation::of_file(&path, &text)) {
+            Ok(()) => report.passed.push(path),
+            Err(reason) => report.failures.push(CaseFailure { path, reason }),
+        }
+    }
+    Ok(report)
+}
+
+#[test]
+fn test_conformance_corpus() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    let report = run_corpus(&corpus).unwrap();
+    assert!(report.is_success(), "{report}");
+    assert!(report.passed.iter().any(|path| path.starts_with(corpus.join("invalid"))));
+}
+
+#[test]
+fn test_unmarked_invalid_case() {
+    let text = "type: model\nname: Anything\n";
+    let expectation = Expectation::of_file(Path::new("corpus/invalid/model.yaml"), text);
+    assert_eq!(expectation, Expectation::Unmarked);
+    assert!(check_case(text, &expectation).is_err());
+}
+
+#[test]
+fn test_report_entries_must_be_expected() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_without_cover_or_repo.yaml");
+    let text = std::fs::read_to_string(&path).unwrap();
+    let unmarked: String = text
+        .lines()
+        .filter(|line| !line.starts_with(EXPECT_ENTRY_MARKER))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    let reason = check_case(&unmarked, &Expectation::of_file(&path, &unmarked)).unwrap_err();
+    assert!(reason.contains("warning: covers: Model has no cover images"), "{reason}");
+
+    let misspelled = text.replace("info: git_repo", "info: license");
+    let reason = check_case(&misspelled, &Expectation::of_file(&path, &misspelled)).unwrap_err();
+    assert!(reason.contains("expected the report to have 'info: license"), "{reason}");
+}
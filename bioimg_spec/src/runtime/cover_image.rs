@@ -1,23 +1,126 @@
 use std::ops::Deref;
 
-pub struct CoverImage(image::DynamicImage);
+pub struct CoverImage {
+    image: image::DynamicImage,
+    encoded: Vec<u8>,
+}
 
 impl CoverImage {
     pub const ALLOWED_WIDTH_TO_HEIGHT_RATIOS: [f32; 2] = [1.0, 2.0];
     pub const MAX_SIZE_IN_BYTES: usize = 500 * 1024;
+    /// How far, relative to the allowed ratio, an image's ratio may be off and still be accepted
+    pub const RATIO_TOLERANCE: f32 = 0.01;
+    /// Qualities of the jpegs [Self::fit] falls back to, in order, when a png would be too big.
+    /// The image is only scaled down once even the lowest is too big.
+    pub const FIT_JPEG_QUALITIES: [u8; 4] = [85, 70, 55, 40];
 
     fn is_valid_ratio(ratio: f32) -> bool {
         return Self::ALLOWED_WIDTH_TO_HEIGHT_RATIOS
             .into_iter()
-            .find(|v| *v == ratio)
-            .is_some();
+            .any(|allowed| ((ratio - allowed) / allowed).abs() <= Self::RATIO_TOLERANCE);
+    }
+
+    /// The allowed ratio closest to that of a `width` x `height` image, and the biggest crop of
+    /// the image that has it
+    pub fn nearest_allowed_crop(width: u32, height: u32) -> CoverCrop {
+        let ratio = width as f32 / height as f32;
+        let nearest = Self::ALLOWED_WIDTH_TO_HEIGHT_RATIOS
+            .into_iter()
+            .min_by(|a, b| (ratio - a).abs().total_cmp(&(ratio - b).abs()))
+            .expect("there are allowed ratios");
+        CoverCrop::largest(nearest, width, height)
+    }
+
+    /// Makes a valid cover out of any image, by cropping its center to the nearest allowed ratio and,
+    /// if it is still too big once encoded, re-encoding it as jpeg and scaling it down until it fits
+    pub fn fit(img: image::DynamicImage) -> Result<Self, CoverImageParsingError> {
+        let crop = Self::nearest_allowed_crop(img.width(), img.height());
+        let (x, y) = ((img.width() - crop.width) / 2, (img.height() - crop.height) / 2);
+        Self::fit_region(&img, x, y, crop)
+    }
+
+    /// Like [Self::fit], with the part of the image to use picked by hand: `crop`, with its top left corner at `x`, `y`.
+    /// The region is clamped to the image.
+    pub fn fit_region(img: &image::DynamicImage, x: u32, y: u32, crop: CoverCrop) -> Result<Self, CoverImageParsingError> {
+        let width = crop.width.clamp(1, img.width());
+        let height = crop.height.clamp(1, img.height());
+        let mut image = img.crop_imm(x.min(img.width() - width), y.min(img.height() - height), width, height);
+        loop {
+            let png = encode(&image, image::ImageOutputFormat::Png)?;
+            if png.len() <= Self::MAX_SIZE_IN_BYTES {
+                return Ok(Self { image, encoded: png });
+            }
+            let rgb: image::DynamicImage = image.to_rgb8().into();
+            let mut smallest = None;
+            for quality in Self::FIT_JPEG_QUALITIES {
+                let jpeg = encode(&rgb, image::ImageOutputFormat::Jpeg(quality))?;
+                if jpeg.len() <= Self::MAX_SIZE_IN_BYTES {
+                    return Ok(Self { image, encoded: jpeg });
+                }
+                smallest = Some(jpeg.len());
+            }
+            let size = smallest.expect("there are jpeg qualities to try");
+            if image.width() <= 16 {
+                return Err(CoverImageParsingError::TooBig { size });
+            }
+            // the encoded size goes roughly with the number of pixels
+            let factor = (Self::MAX_SIZE_IN_BYTES as f32 / size as f32).sqrt().min(0.9);
+            let width = ((image.width() as f32 * factor).round() as u32).max(16);
+            let height = ((width as f32 / crop.ratio).round() as u32).max(1);
+            image = image.resize_exact(width, height, image::imageops::FilterType::Triangle);
+        }
+    }
+
+    /// The image as it should be written to the package
+    pub fn encoded(&self) -> &[u8] {
+        &self.encoded
+    }
+}
+
+fn encode(image: &image::DynamicImage, format: image::ImageOutputFormat) -> Result<Vec<u8>, image::ImageError> {
+    let mut out = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut out, format)?;
+    Ok(out.into_inner())
+}
+
+/// A crop that would give a cover image an allowed aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoverCrop {
+    pub ratio: f32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CoverCrop {
+    /// The biggest crop of a `width` x `height` image with `ratio`
+    pub fn largest(ratio: f32, width: u32, height: u32) -> Self {
+        let (crop_width, crop_height) = if width as f32 / height as f32 > ratio {
+            ((height as f32 * ratio).round() as u32, height)
+        } else {
+            (width, (width as f32 / ratio).round() as u32)
+        };
+        Self {
+            ratio,
+            width: crop_width.clamp(1, width),
+            height: crop_height.clamp(1, height),
+        }
+    }
+
+    /// The same crop shrunk by `factor`, keeping its ratio
+    pub fn scaled(self, factor: f32) -> Self {
+        let width = ((self.width as f32 * factor).round() as u32).max(1);
+        Self {
+            width,
+            height: ((width as f32 / self.ratio).round() as u32).max(1),
+            ..self
+        }
     }
 }
 
 impl Deref for CoverImage {
     type Target = image::DynamicImage;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.image
     }
 }
 
@@ -25,8 +128,11 @@ impl Deref for CoverImage {
 pub enum CoverImageParsingError {
     #[error("Image is too big ({size} bytes), must be up to 500KB")]
     TooBig { size: usize },
-    #[error("Bad aspect ratio (width / height): {ratio}, expected 2:1 or 1:1")]
-    BadAspectRatio { ratio: f32 },
+    #[error(
+        "Bad aspect ratio (width / height): {ratio}, expected 2:1 or 1:1; cropping to {}x{} would give {}:1",
+        nearest.width, nearest.height, nearest.ratio
+    )]
+    BadAspectRatio { ratio: f32, nearest: CoverCrop },
     #[error("{0}")]
     BadImageData(#[from] image::ImageError),
 }
@@ -42,8 +148,69 @@ impl TryFrom<&'_ [u8]> for CoverImage {
         let img = image::io::Reader::new(cursor).with_guessed_format().unwrap().decode()?;
         let ratio = (img.width() as f32) / (img.height() as f32);
         if !Self::is_valid_ratio(ratio) {
-            return Err(CoverImageParsingError::BadAspectRatio { ratio });
+            let nearest = Self::nearest_allowed_crop(img.width(), img.height());
+            return Err(CoverImageParsingError::BadAspectRatio { ratio, nearest });
         }
-        return Ok(Self(img));
+        return Ok(Self {
+            image: img,
+            encoded: value.to_vec(),
+        });
+    }
+}
+
+#[test]
+fn test_cover_image_ratio_tolerance() {
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(width, height)
+            .write_to(&mut out, image::ImageOutputFormat::Png)
+            .unwrap();
+        out.into_inner()
     }
+
+    assert!(CoverImage::try_from(png(1023, 512).as_slice()).is_ok());
+    assert!(CoverImage::try_from(png(300, 301).as_slice()).is_ok());
+
+    match CoverImage::try_from(png(600, 400).as_slice()) {
+        Err(CoverImageParsingError::BadAspectRatio { nearest, .. }) => assert_eq!(
+            nearest,
+            CoverCrop {
+                ratio: 1.0,
+                width: 400,
+                height: 400
+            }
+        ),
+        _ => panic!("Expected a bad aspect ratio"),
+    }
+    assert_eq!(
+        CoverImage::nearest_allowed_crop(500, 200),
+        CoverCrop {
+            ratio: 2.0,
+            width: 400,
+            height: 200
+        }
+    );
+}
+
+#[test]
+fn test_cover_image_fit() {
+    // noise, so that even the jpeg has to be scaled down a few times
+    let mut state = 12345u32;
+    let noise = image::RgbImage::from_fn(1300, 600, |_, _| {
+        state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        image::Rgb([(state >> 24) as u8, (state >> 16) as u8, (state >> 8) as u8])
+    });
+    let cover = CoverImage::fit(noise.into()).unwrap();
+    assert!(cover.encoded().len() <= CoverImage::MAX_SIZE_IN_BYTES);
+    assert!(cover.width() < 1200);
+    assert!(CoverImage::try_from(cover.encoded()).is_ok());
+
+    let small = CoverImage::fit(image::DynamicImage::new_rgb8(600, 400)).unwrap();
+    assert_eq!((small.width(), small.height()), (400, 400));
+
+    let picked = CoverCrop::largest(2.0, 600, 400).scaled(0.5);
+    assert_eq!((picked.width, picked.height), (300, 150));
+    let region = CoverImage::fit_region(&image::DynamicImage::new_rgb8(600, 400), 500, 0, picked).unwrap();
+    assert_eq!((region.width(), region.height()), (300, 150));
+    assert!(CoverImage::try_from(region.encoded()).is_ok());
 }
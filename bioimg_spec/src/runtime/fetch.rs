@@ -0,0 +1,380 @@
+//! Downloading the files that descriptions refer to by url, keeping them in a local cache so that
+//! they are only downloaded once.
+
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use sha2::Digest;
+use url::Url;
+
+use crate::rdf::{FileDescr, FileReference, Sha256};
+
+/// Tells apart the downloads of one process, which may be running at the same time
+static DOWNLOAD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// How many times a download that breaks off is tried before giving up
+const MAX_DOWNLOAD_ATTEMPTS: usize = 3;
+
+#[derive(thiserror::Error, Debug)]
+pub enum FetchError {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Could not download {url}: {message}")]
+    HttpError { url: String, message: String },
+    #[error("{url} has hash {found}, but {expected} was expected")]
+    ChangedFile { url: String, expected: Sha256, found: Sha256 },
+    #[error("No cache directory was found for this user")]
+    NoCacheDir,
+}
+
+/// Why a download didn't finish
+enum Interruption {
+    /// The connection broke off, with what came before kept in the partial file
+    Broken(std::io::Error),
+    Failed(FetchError),
+}
+
+fn hash_into(reader: &mut impl std::io::Read, hasher: &mut sha2::Sha256) -> std::io::Result<()> {
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(read) => hasher.update(&buf[..read]),
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Downloads files into `cache_dir`. Files with a known sha256 are kept by their hash, so they are
+/// never downloaded twice; the others are kept by their url, and only downloaded again once they
+/// are removed from the cache.
+#[derive(Clone)]
+pub struct ResourceFetcher {
+    cache_dir: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl ResourceFetcher {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            agent: super::network::agent_builder().build(),
+        }
+    }
+
+    /// A fetcher that caches into the cache directory of the user, e.g. `~/.cache/bioimg`
+    pub fn with_user_cache() -> Result<Self, FetchError> {
+        let dirs = directories_next::ProjectDirs::from("", "", "bioimg").ok_or(FetchError::NoCacheDir)?;
+        Ok(Self::new(dirs.cache_dir()))
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Where the file at `url` is kept once downloaded. The file name is kept for its extension.
+    pub fn cache_path(&self, url: &Url, sha256: Option<&Sha256>) -> PathBuf {
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("file");
+        match sha256 {
+            Some(sha256) => self.cache_dir.join("sha256").join(sha256.to_string()).join(file_name),
+            None => self.cache_dir.join("url").join(Sha256::digest(url.as_str()).to_string()).join(file_name),
+        }
+    }
+
+    /// Downloads the file at `url` unless it is cached already, and returns the path to the cached
+    /// file. If `sha256` is given, the download must match it.
+    ///
+    /// Downloads that break off are picked up where they stopped, a few times over. For files with a known
+    /// hash, what was downloaded is also kept when giving up, for the next fetch to resume; a resumed download
+    /// that doesn't match the hash is downloaded again from the start.
+    pub fn fetch(&self, url: &Url, sha256: Option<&Sha256>) -> Result<PathBuf, FetchError> {
+        let path = self.cache_path(url, sha256);
+        if path.is_file() {
+            return Ok(path);
+        }
+        let dir = path.parent().expect("cache paths are inside the cache dir");
+        std::fs::create_dir_all(dir)?;
+
+        // downloaded next to where it goes, so that an interrupted download is never mistaken for the file
+        let download = DOWNLOAD_COUNT.fetch_add(1, Ordering::Relaxed);
+        let partial_path = dir.join(format!(".partial-{}-{download}", std::process::id()));
+        // the dir is only ever for a file with this hash, so what was left in it can't be of another file.
+        // Renaming it claims it, so that two fetches never append to the same file.
+        let resumable_path = dir.join(".partial");
+        let mut resume = sha256.is_some() && std::fs::rename(&resumable_path, &partial_path).is_ok();
+        let mut attempt = 1;
+        let found = loop {
+            match self.download(url, &partial_path, resume) {
+                Ok(found) if resume && sha256.is_some_and(|expected| *expected != found) => resume = false,
+                Ok(found) => break found,
+                Err(Interruption::Broken(_)) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                    resume = true;
+                    attempt += 1;
+                }
+                Err(Interruption::Broken(err)) => {
+                    match sha256 {
+                        Some(_) => std::fs::rename(&partial_path, &resumable_path).ok(),
+                        None => std::fs::remove_file(&partial_path).ok(),
+                    };
+                    return Err(err.into());
+                }
+                Err(Interruption::Failed(err)) => {
+                    std::fs::remove_file(&partial_path).ok();
+                    return Err(err);
+                }
+            }
+        };
+        match sha256 {
+            Some(expected) if *expected != found => {
+                std::fs::remove_file(&partial_path).ok();
+                Err(FetchError::ChangedFile {
+                    url: url.to_string(),
+                    expected: *expected,
+                    found,
+                })
+            }
+            _ => {
+                std::fs::rename(&partial_path, &path)?;
+                Ok(path)
+            }
+        }
+    }
+
+    /// Downloads `url` into `partial_path`, asking for only what comes after the bytes already in it if `resume`
+    /// is set, and returns the hash of the whole file
+    fn download(&self, url: &Url, partial_path: &Path, resume: bool) -> Result<Sha256, Interruption> {
+        let http_error = |message: String| Interruption::Failed(FetchError::HttpError { url: url.to_string(), message });
+        let failed = |err: std::io::Error| Interruption::Failed(err.into());
+        let offset = match resume {
+            true => std::fs::metadata(partial_path).map(|metadata| metadata.len()).unwrap_or(0),
+            false => 0,
+        };
+        let mut request = self.agent.get(url.as_str());
+        if offset > 0 {
+            request = request.set("Range", &format!("bytes={offset}-"));
+        }
+        let mut hasher = sha2::Sha256::new();
+        let response = match request.call() {
+            Ok(response) => response,
+            // there is nothing after the bytes there are already; whether they are the file is up to the hash
+            Err(ureq::Error::Status(416, _)) if offset > 0 => {
+                let mut downloaded = std::fs::File::open(partial_path).map_err(failed)?;
+                hash_into(&mut downloaded, &mut hasher).map_err(failed)?;
+                return Ok(Sha256::from(<[u8; 32]>::from(hasher.finalize())));
+            }
+            Err(err) => return Err(http_error(err.to_string())),
+        };
+        // servers that don't do ranges send the whole file instead
+        let appending = offset > 0 && response.status() == 206;
+        let mut partial = match appending {
+            true => {
+                let mut downloaded = std::fs::File::open(partial_path).map_err(failed)?;
+                hash_into(&mut downloaded, &mut hasher).map_err(failed)?;
+                std::fs::OpenOptions::new().append(true).open(partial_path)
+            }
+            false => std::fs::File::create(partial_path),
+        }
+        .map_err(failed)?;
+        let mut reader = response.into_reader();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let read = match std::io::Read::read(&mut reader, &mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(Interruption::Broken(err)),
+            };
+            hasher.update(&buf[..read]);
+            partial.write_all(&buf[..read]).map_err(failed)?;
+        }
+        Ok(Sha256::from(<[u8; 32]>::from(hasher.finalize())))
+    }
+
+    /// Like [Self::fetch], on a background thread
+    pub fn fetch_async(&self, url: &Url, sha256: Option<&Sha256>) -> PendingFetch {
+        let pending = PendingFetch::default();
+        let shared = Arc::clone(&pending.shared);
+        let (fetcher, url, sha256) = (self.clone(), url.clone(), sha256.copied());
+        std::thread::spawn(move || {
+            let result = fetcher.fetch(&url, sha256.as_ref());
+            let mut shared = shared.lock().unwrap();
+            shared.result = Some(result);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        });
+        pending
+    }
+
+    /// A local path to the file `reference` refers to, downloading it if it is a url. Paths are
+    /// relative to `base_dir`, usually the directory the description is in.
+    pub fn fetch_reference(
+        &self,
+        reference: &FileReference,
+        sha256: Option<&Sha256>,
+        base_dir: &Path,
+    ) -> Result<PathBuf, FetchError> {
+        match reference {
+            FileReference::Url(url) => self.fetch(url, sha256),
+            FileReference::Path(path) => Ok(base_dir.join(path)),
+        }
+    }
+
+    /// Like [Self::fetch_reference], checking the download against the hash in `descr`
+    pub fn fetch_descr(&self, descr: &FileDescr, base_dir: &Path) -> Result<PathBuf, FetchError> {
+        self.fetch_reference(&descr.source, descr.sha256.as_ref(), base_dir)
+    }
+}
+
+#[derive(Default)]
+struct FetchState {
+    result: Option<Result<PathBuf, FetchError>>,
+    waker: Option<Waker>,
+}
+
+/// A download running in the background. It can be awaited, or checked on with
+/// [PendingFetch::try_take], e.g. once per frame.
+#[derive(Default)]
+pub struct PendingFetch {
+    shared: Arc<Mutex<FetchState>>,
+}
+
+impl PendingFetch {
+    /// The result of the download if it is done. It is only given out once.
+    pub fn try_take(&self) -> Option<Result<PathBuf, FetchError>> {
+        self.shared.lock().unwrap().result.take()
+    }
+}
+
+impl Future for PendingFetch {
+    type Output = Result<PathBuf, FetchError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fetching_into_cache() {
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    // serves `body` to every request, counting them
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(0));
+    let counter = Arc::clone(&requests);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let request = std::io::BufReader::new(&stream).lines().map(Result::unwrap);
+            request.take_while(|line| !line.is_empty()).for_each(drop);
+            *counter.lock().unwrap() += 1;
+            let body = "weights";
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len());
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    let cache_dir = std::env::temp_dir().join(format!("bioimg_fetch_test_{}", std::process::id()));
+    let fetcher = ResourceFetcher::new(&cache_dir);
+    let url = Url::parse(&format!("http://127.0.0.1:{port}/models/weights.pt")).unwrap();
+    let expected = Sha256::digest("weights");
+
+    let path = fetcher.fetch(&url, Some(&expected)).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "weights");
+    assert_eq!(path.file_name().unwrap(), "weights.pt");
+    assert_eq!(fetcher.fetch(&url, Some(&expected)).unwrap(), path);
+    assert_eq!(*requests.lock().unwrap(), 1);
+
+    let wrong = Sha256::digest("other weights");
+    assert!(matches!(fetcher.fetch(&url, Some(&wrong)), Err(FetchError::ChangedFile { .. })));
+    assert!(!fetcher.cache_path(&url, Some(&wrong)).exists());
+
+    let pending = fetcher.fetch_async(&url, None);
+    let fetched = loop {
+        if let Some(result) = pending.try_take() {
+            break result.unwrap();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    };
+    assert_eq!(fetched, fetcher.cache_path(&url, None));
+    assert_eq!(*requests.lock().unwrap(), 3);
+
+    let local = FileReference::Path("cover.png".into());
+    assert_eq!(fetcher.fetch_reference(&local, None, Path::new("model")).unwrap(), Path::new("model/cover.png"));
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn test_resuming_downloads() {
+    use std::io::{BufRead, Read};
+    use std::net::TcpListener;
+
+    // breaks off the first response halfway, and serves the rest of `body` to requests for a range
+    let body: Vec<u8> = (0..100_000u32).map(|index| (index % 251) as u8).collect();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let ranges = Arc::new(Mutex::new(Vec::<Option<usize>>::new()));
+    let (served, seen) = (body.clone(), Arc::clone(&ranges));
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let request = std::io::BufReader::new(&stream).lines().map(Result::unwrap);
+            let range = request
+                .take_while(|line| !line.is_empty())
+                .find_map(|line| line.to_lowercase().strip_prefix("range: bytes=")?.strip_suffix('-')?.parse().ok());
+            let first = seen.lock().unwrap().is_empty();
+            seen.lock().unwrap().push(range);
+            let start = range.unwrap_or(0);
+            let (status, rest) = match range {
+                Some(_) => ("206 Partial Content", &served[start..]),
+                None => ("200 OK", &served[..]),
+            };
+            let head = format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", rest.len());
+            stream.write_all(head.as_bytes()).unwrap();
+            let sent = if first { &rest[..rest.len() / 2] } else { rest };
+            stream.write_all(sent).unwrap();
+        }
+    });
+
+    let cache_dir = std::env::temp_dir().join(format!("bioimg_resume_test_{}", std::process::id()));
+    let fetcher = ResourceFetcher::new(&cache_dir);
+    let url = Url::parse(&format!("http://127.0.0.1:{port}/weights.pt")).unwrap();
+    let mut expected = sha2::Sha256::new();
+    hash_into(&mut &body[..], &mut expected).unwrap();
+    let expected = Sha256::from(<[u8; 32]>::from(expected.finalize()));
+
+    let path = fetcher.fetch(&url, Some(&expected)).unwrap();
+    let mut fetched = vec![];
+    std::fs::File::open(&path).unwrap().read_to_end(&mut fetched).unwrap();
+    assert_eq!(fetched, body);
+    assert_eq!(*ranges.lock().unwrap(), [None, Some(body.len() / 2)]);
+    std::fs::remove_file(&path).unwrap();
+
+    // what was left behind belongs to another version of the file, so it is downloaded again in full
+    let dir = path.parent().unwrap();
+    std::fs::write(dir.join(".partial"), vec![7u8; 1000]).unwrap();
+    assert_eq!(fetcher.fetch(&url, Some(&expected)).unwrap(), path);
+    assert_eq!(std::fs::read(&path).unwrap(), body);
+    assert_eq!(ranges.lock().unwrap()[2..], [Some(1000), None]);
+    assert!(!dir.join(".partial").exists());
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
@@ -0,0 +1,125 @@
+//! Looking up GitHub accounts, to catch misspelled user names before they end up in a published description
+
+use std::io::Read;
+
+use serde::Deserialize;
+
+use crate::rdf::GithubUser;
+
+/// The public API of GitHub, which answers a limited number of requests without credentials
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+/// Avatars are downloaded at this size in pixels, which is plenty for a preview next to the user name
+const AVATAR_SIZE: u32 = 64;
+/// Avatars bigger than this are not downloaded
+const MAX_AVATAR_BYTES: u64 = 1024 * 1024;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GithubLookupError {
+    #[error("Could not download {url}: {message}")]
+    HttpError { url: String, message: String },
+    #[error("{url} is not a GitHub account: {message}")]
+    BadAccount { url: String, message: String },
+}
+
+/// What GitHub makes public about an account
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GithubAccount {
+    /// The user name the way it is spelled on GitHub, which can differ in case from the one looked up
+    pub login: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    /// Whether this is a `User` or an `Organization`
+    #[serde(rename = "type", default)]
+    pub kind: String,
+}
+
+impl GithubAccount {
+    /// Reads the json of an account, as served at `{GITHUB_API_URL}/users/{user}`
+    pub fn from_json(raw: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(raw)
+    }
+
+    pub fn is_organization(&self) -> bool {
+        self.kind == "Organization"
+    }
+}
+
+/// Reads public accounts from the GitHub API
+#[derive(Clone)]
+pub struct GithubClient {
+    api_url: String,
+    agent: ureq::Agent,
+}
+
+impl GithubClient {
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            agent: super::network::agent_builder().build(),
+        }
+    }
+
+    /// A client for github.com
+    pub fn public() -> Self {
+        Self::new(GITHUB_API_URL)
+    }
+
+    /// The account of `user`, or `None` if there is no such account
+    pub fn lookup(&self, user: &GithubUser) -> Result<Option<GithubAccount>, GithubLookupError> {
+        let url = format!("{}/users/{}", self.api_url.trim_end_matches('/'), user.as_str());
+        let response = match self.agent.get(&url).set("Accept", "application/vnd.github+json").call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(err) => return Err(GithubLookupError::HttpError { url, message: err.to_string() }),
+        };
+        let raw = response
+            .into_string()
+            .map_err(|err| GithubLookupError::HttpError { url: url.clone(), message: err.to_string() })?;
+        GithubAccount::from_json(&raw)
+            .map(Some)
+            .map_err(|err| GithubLookupError::BadAccount { url, message: err.to_string() })
+    }
+
+    /// The encoded image of the avatar of `account`, if it has one
+    pub fn avatar(&self, account: &GithubAccount) -> Result<Option<Vec<u8>>, GithubLookupError> {
+        let Some(avatar_url) = &account.avatar_url else {
+            return Ok(None);
+        };
+        let separator = if avatar_url.contains('?') { '&' } else { '?' };
+        let url = format!("{avatar_url}{separator}s={AVATAR_SIZE}");
+        let http_error = |message: String| GithubLookupError::HttpError { url: url.clone(), message };
+        let response = self.agent.get(&url).call().map_err(|err| http_error(err.to_string()))?;
+        let mut bytes = vec![];
+        response
+            .into_reader()
+            .take(MAX_AVATAR_BYTES)
+            .read_to_end(&mut bytes)
+            .map_err(|err| http_error(err.to_string()))?;
+        Ok(Some(bytes))
+    }
+}
+
+#[test]
+fn test_reading_github_account() {
+    let raw = r#"{
+        "login": "Octocat",
+        "id": 583231,
+        "avatar_url": "https://avatars.githubusercontent.com/u/583231?v=4",
+        "type": "User",
+        "name": "The Octocat",
+        "company": "@github"
+    }"#;
+    let account = GithubAccount::from_json(raw).unwrap();
+    assert_eq!(account.login, "Octocat");
+    assert_eq!(account.name.as_deref(), Some("The Octocat"));
+    assert!(!account.is_organization());
+
+    let organization = r#"{"login": "bioimage-io", "type": "Organization", "name": null}"#;
+    let account = GithubAccount::from_json(organization).unwrap();
+    assert!(account.is_organization());
+    assert_eq!(account.avatar_url, None);
+
+    assert!(GithubAccount::from_json(r#"{"message": "Not Found"}"#).is_err());
+}
@@ -5,10 +5,13 @@ use image::DynamicImage;
 pub enum IconParsingError {
     #[error("Image is not square")]
     ImageNotSquare(DynamicImage),
-    #[error("0")]
+    #[error("{0}")]
     RdfError(#[from] rdf::IconParsingError),
+    #[error("{0}")]
+    BadImageData(String),
 }
 
+#[derive(Clone)]
 pub struct IconImage(DynamicImage);
 
 impl TryFrom<DynamicImage> for IconImage {
@@ -30,6 +33,7 @@ impl TryFrom<DynamicImage> for Icon {
     }
 }
 
+#[derive(Clone)]
 pub enum Icon {
     Image(IconImage),
     Text(rdf::icon::EmojiIcon),
@@ -0,0 +1,240 @@
+//! Converting between images and tensors: which dimension of the tensor takes the rows, columns and channels of the
+//! image is spelled out by an [ImageAxis] per dimension, so that nothing depends on the order the axes come in.
+
+use image::DynamicImage;
+use ndarray::{ArrayD, Dimension, IxDyn};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ImageConversionError {
+    #[error("Images need exactly one y axis, one x axis and at most one channel axis")]
+    BadAxisMapping,
+    #[error("The axes map {mapping} dimensions, but the data has {dimensions}")]
+    WrongDimensions { mapping: usize, dimensions: usize },
+    #[error("Dimension {index} has size {size}, but only dimensions of size 1 can be left out of an image")]
+    NotSingleton { index: usize, size: usize },
+    #[error("Images have 1 to 4 channels, not {0}")]
+    BadChannelCount(usize),
+    #[error("The image has no alpha channel")]
+    MissingAlpha,
+}
+
+/// What a dimension of a tensor takes from an image
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ImageAxis {
+    Y,
+    X,
+    Channel,
+    /// A dimension of size 1, e.g. for the batch
+    #[default]
+    Singleton,
+}
+
+/// The red, green, blue and alpha values of every pixel, with the values the image stores rather than ones scaled
+/// to 0..1. Gray images have their values in red, green and blue.
+struct RgbaSamples {
+    width: usize,
+    height: usize,
+    samples: Vec<f32>,
+    has_alpha: bool,
+    /// Whether all of the channels hold the same values
+    is_gray: bool,
+}
+
+impl RgbaSamples {
+    fn new(image: &DynamicImage) -> Self {
+        let color = image.color();
+        let samples = match color.bytes_per_pixel() / color.channel_count() {
+            2 => image.to_rgba16().into_raw().into_iter().map(f32::from).collect(),
+            4 => image.to_rgba32f().into_raw(),
+            _ => image.to_rgba8().into_raw().into_iter().map(f32::from).collect(),
+        };
+        Self {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            samples,
+            has_alpha: color.has_alpha(),
+            is_gray: !color.has_color(),
+        }
+    }
+
+    fn get(&self, x: usize, y: usize, component: usize) -> f32 {
+        self.samples[(y * self.width + x) * 4 + component]
+    }
+}
+
+/// The red, green, blue or alpha component a channel called `name` holds, if its name says so
+fn named_component(name: &str) -> Option<usize> {
+    match name.to_ascii_lowercase().as_str() {
+        "r" | "red" | "gray" | "grey" | "luma" | "intensity" => Some(0),
+        "g" | "green" => Some(1),
+        "b" | "blue" => Some(2),
+        "a" | "alpha" => Some(3),
+        _ => None,
+    }
+}
+
+/// The components that go to the channels of a tensor, in order. Channels named after colors take those, and
+/// otherwise 1 channel is gray, 2 gray and alpha, 3 rgb and 4 rgba.
+fn channel_components(channel_names: &[&str], channels: usize) -> Result<Vec<usize>, ImageConversionError> {
+    let named: Option<Vec<usize>> = channel_names.iter().map(|name| named_component(name)).collect();
+    match (named, channels) {
+        (Some(components), _) if !components.is_empty() => Ok(components),
+        (_, 1) => Ok(vec![0]),
+        (_, 2) => Ok(vec![0, 3]),
+        (_, 3) => Ok(vec![0, 1, 2]),
+        (_, 4) => Ok(vec![0, 1, 2, 3]),
+        (_, channels) => Err(ImageConversionError::BadChannelCount(channels)),
+    }
+}
+
+fn check_mapping(mapping: &[ImageAxis]) -> Result<(), ImageConversionError> {
+    let count = |axis: ImageAxis| mapping.iter().filter(|mapped| **mapped == axis).count();
+    if count(ImageAxis::Y) != 1 || count(ImageAxis::X) != 1 || count(ImageAxis::Channel) > 1 {
+        return Err(ImageConversionError::BadAxisMapping);
+    }
+    Ok(())
+}
+
+/// The pixels of `image` laid out along dimensions that take what `mapping` says from the image.
+///
+/// The channel dimension holds the channels named in `channel_names` if they are all named after colors, like
+/// `["b", "g", "r"]`; otherwise it holds all channels of the image, or as many as there are names. Without a channel
+/// dimension, the color channels are averaged into one.
+pub fn image_to_array(
+    image: &DynamicImage,
+    mapping: &[ImageAxis],
+    channel_names: &[&str],
+) -> Result<ArrayD<f32>, ImageConversionError> {
+    check_mapping(mapping)?;
+    let rgba = RgbaSamples::new(image);
+    let stored_channels = image.color().channel_count() as usize;
+    let channels = if channel_names.is_empty() { stored_channels } else { channel_names.len() };
+    let components = channel_components(channel_names, channels)?;
+    if components.contains(&3) && !rgba.has_alpha {
+        return Err(ImageConversionError::MissingAlpha);
+    }
+    let color_components = if rgba.is_gray { 1 } else { 3 };
+
+    let shape: Vec<usize> = mapping
+        .iter()
+        .map(|axis| match axis {
+            ImageAxis::Y => rgba.height,
+            ImageAxis::X => rgba.width,
+            ImageAxis::Channel => components.len(),
+            ImageAxis::Singleton => 1,
+        })
+        .collect();
+    Ok(ArrayD::from_shape_fn(IxDyn(&shape), |index| {
+        let (mut x, mut y, mut channel) = (0, 0, None);
+        for (&position, axis) in index.slice().iter().zip(mapping) {
+            match axis {
+                ImageAxis::Y => y = position,
+                ImageAxis::X => x = position,
+                ImageAxis::Channel => channel = Some(position),
+                ImageAxis::Singleton => (),
+            }
+        }
+        match channel {
+            Some(channel) => rgba.get(x, y, components[channel]),
+            None => (0..color_components).map(|component| rgba.get(x, y, component)).sum::<f32>() / color_components as f32,
+        }
+    }))
+}
+
+/// The image whose pixels `array` holds along the dimensions `mapping` describes, e.g. to look at a tensor a model
+/// produced. The values are kept as they are, so they may need to be scaled before the image can be saved or shown.
+///
+/// Channels are picked like in [image_to_array], and any dimension that isn't y, x or the channels has to be of
+/// size 1.
+pub fn array_to_image(
+    array: &ArrayD<f32>,
+    mapping: &[ImageAxis],
+    channel_names: &[&str],
+) -> Result<DynamicImage, ImageConversionError> {
+    check_mapping(mapping)?;
+    if mapping.len() != array.ndim() {
+        return Err(ImageConversionError::WrongDimensions { mapping: mapping.len(), dimensions: array.ndim() });
+    }
+    let size_of = |wanted: ImageAxis| {
+        mapping.iter().zip(array.shape()).find(|(axis, _)| **axis == wanted).map(|(_, size)| *size)
+    };
+    for (index, (axis, size)) in mapping.iter().zip(array.shape()).enumerate() {
+        if *axis == ImageAxis::Singleton && *size != 1 {
+            return Err(ImageConversionError::NotSingleton { index, size: *size });
+        }
+    }
+    let (height, width) = (size_of(ImageAxis::Y).unwrap_or(1), size_of(ImageAxis::X).unwrap_or(1));
+    let channels = size_of(ImageAxis::Channel).unwrap_or(1);
+    let components = channel_components(channel_names, channels)?;
+    if components.len() != channels {
+        return Err(ImageConversionError::BadChannelCount(channels));
+    }
+    let has_alpha = components.contains(&3);
+    let is_gray = components.iter().all(|component| *component == 0);
+
+    let mut position = vec![0; mapping.len()];
+    let mut samples = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let mut pixel = [0.0, 0.0, 0.0, 1.0];
+            for (channel, component) in components.iter().enumerate() {
+                for (coordinate, axis) in position.iter_mut().zip(mapping) {
+                    *coordinate = match axis {
+                        ImageAxis::Y => y,
+                        ImageAxis::X => x,
+                        ImageAxis::Channel => channel,
+                        ImageAxis::Singleton => 0,
+                    };
+                }
+                pixel[*component] = array[IxDyn(&position)];
+            }
+            if is_gray {
+                pixel[1] = pixel[0];
+                pixel[2] = pixel[0];
+            }
+            samples.extend_from_slice(if has_alpha { &pixel[..] } else { &pixel[..3] });
+        }
+    }
+    let (width, height) = (width as u32, height as u32);
+    let image = if has_alpha {
+        image::Rgba32FImage::from_raw(width, height, samples).map(DynamicImage::ImageRgba32F)
+    } else {
+        image::Rgb32FImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb32F)
+    };
+    Ok(image.expect("one sample per pixel and channel"))
+}
+
+#[test]
+fn test_image_tensor_conversion() {
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    let mut image = RgbImage::new(3, 2);
+    image.put_pixel(2, 1, Rgb([10, 20, 30]));
+    let image = DynamicImage::ImageRgb8(image);
+    let mapping = [ImageAxis::Singleton, ImageAxis::Channel, ImageAxis::Y, ImageAxis::X];
+
+    let array = image_to_array(&image, &mapping, &[]).unwrap();
+    assert_eq!(array.shape(), [1, 3, 2, 3]);
+    assert_eq!(array[[0, 0, 1, 2]], 10.0);
+    let bgr = image_to_array(&image, &mapping, &["b", "g", "r"]).unwrap();
+    assert_eq!(bgr[[0, 0, 1, 2]], 30.0);
+    let gray = image_to_array(&image, &[ImageAxis::X, ImageAxis::Y], &[]).unwrap();
+    assert_eq!(gray[[2, 1]], 20.0);
+
+    let roundtrip = array_to_image(&bgr, &mapping, &["b", "g", "r"]).unwrap();
+    assert_eq!(roundtrip.to_rgb32f().get_pixel(2, 1).0, [10.0, 20.0, 30.0]);
+    assert_eq!(array_to_image(&gray, &[ImageAxis::X, ImageAxis::Y], &[]).unwrap().to_rgb32f().get_pixel(2, 1).0, [20.0; 3]);
+
+    assert_eq!(image_to_array(&image, &mapping, &["r", "g", "b", "a"]), Err(ImageConversionError::MissingAlpha));
+    assert_eq!(image_to_array(&image, &[ImageAxis::Y, ImageAxis::Y], &[]), Err(ImageConversionError::BadAxisMapping));
+    let rgba = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([1, 2, 3, 4])));
+    assert_eq!(image_to_array(&rgba, &[ImageAxis::Channel, ImageAxis::Y, ImageAxis::X], &[]).unwrap().len(), 4);
+    assert_eq!(
+        image_to_array(&image, &mapping, &["channel0", "channel1", "channel2", "channel3", "channel4"]),
+        Err(ImageConversionError::BadChannelCount(5))
+    );
+    assert_eq!(
+        array_to_image(&ArrayD::zeros(IxDyn(&[2, 3, 2, 3])), &mapping, &[]),
+        Err(ImageConversionError::NotSingleton { index: 0, size: 2 })
+    );
+}
@@ -0,0 +1,63 @@
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+
+use super::{InferenceBackend, ModelPackage};
+use crate::rdf::model::weights::WeightsFormat;
+
+/// Where a backend runs the model. GPUs are to be added along with a runtime that can use them.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ExecutionProvider {
+    #[default]
+    Cpu,
+}
+
+/// Settings shared by all inference backends
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub struct BackendConfig {
+    pub num_threads: NonZeroUsize,
+    #[serde(default)]
+    pub execution_provider: ExecutionProvider,
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        Self {
+            num_threads: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+            execution_provider: ExecutionProvider::default(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BackendError {
+    #[error("No inference runtime is built in for the weights of this model ({formats})")]
+    NoRuntime { formats: String },
+}
+
+/// The weights formats [load_backend] can run. There are none yet: ONNX and TorchScript weights are to be run with
+/// tract or ort, behind features of their own, which aren't there yet.
+const RUNTIMES: [WeightsFormat; 0] = [];
+
+/// Whether [load_backend] can run weights in `format`
+pub fn has_runtime(format: WeightsFormat) -> bool {
+    RUNTIMES.contains(&format)
+}
+
+/// A backend that runs the first weights of `package` that a built in runtime can run
+pub fn load_backend(package: &ModelPackage, _config: BackendConfig) -> Result<Box<dyn InferenceBackend + Send>, BackendError> {
+    let entries = package.rdf.weights.entries();
+    Err(BackendError::NoRuntime {
+        formats: entries.iter().map(|(format, _)| format.to_string()).collect::<Vec<_>>().join(", "),
+    })
+}
+
+#[test]
+fn test_load_backend() {
+    let package = super::model_package::shark_package();
+    assert!(!has_runtime(WeightsFormat::Onnx) && !has_runtime(WeightsFormat::Torchscript));
+    assert_eq!(
+        load_backend(&package, BackendConfig::default()).err(),
+        Some(BackendError::NoRuntime { formats: "onnx, pytorch_state_dict, torchscript".into() })
+    );
+}
@@ -0,0 +1,114 @@
+//! Checking that the urls in a description still lead somewhere, so that dead links are noticed before
+//! the description is published.
+
+use std::time::Duration;
+
+use url::Url;
+
+/// How long a server gets to answer before the link counts as timed out
+pub const DEFAULT_LINK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What came of asking for a url
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reachability {
+    Reachable,
+    /// The server answered, but with an error status like 404
+    Status(u16),
+    TimedOut,
+    /// There was no answer at all, e.g. because the host doesn't exist
+    Unreachable(String),
+}
+
+impl Reachability {
+    pub fn is_reachable(&self) -> bool {
+        matches!(self, Self::Reachable)
+    }
+}
+
+/// Asks servers whether a url exists, without downloading what it leads to
+#[derive(Clone)]
+pub struct LinkChecker {
+    agent: ureq::Agent,
+}
+
+impl Default for LinkChecker {
+    fn default() -> Self {
+        Self::new(DEFAULT_LINK_TIMEOUT)
+    }
+}
+
+impl LinkChecker {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            agent: super::network::agent_builder().timeout(timeout).build(),
+        }
+    }
+
+    /// Sends a HEAD request to `url`. Some servers don't answer HEAD requests, so those get a GET whose
+    /// body is never read.
+    pub fn check(&self, url: &Url) -> Reachability {
+        match self.agent.head(url.as_str()).call() {
+            Err(ureq::Error::Status(405 | 501, _)) => reachability(self.agent.get(url.as_str()).call()),
+            response => reachability(response),
+        }
+    }
+}
+
+fn reachability(response: Result<ureq::Response, ureq::Error>) -> Reachability {
+    match response {
+        Ok(_) => Reachability::Reachable,
+        Err(ureq::Error::Status(status, _)) => Reachability::Status(status),
+        Err(ureq::Error::Transport(transport)) => {
+            let timed_out = std::error::Error::source(&transport)
+                .and_then(|source| source.downcast_ref::<std::io::Error>())
+                .is_some_and(|err| matches!(err.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock));
+            if timed_out {
+                Reachability::TimedOut
+            } else {
+                Reachability::Unreachable(transport.to_string())
+            }
+        }
+    }
+}
+
+#[test]
+fn test_checking_links() {
+    use std::io::{BufRead, Write};
+    use std::net::TcpListener;
+
+    // answers HEAD requests by path, and refuses them on /get-only
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut lines = std::io::BufReader::new(&stream).lines().map(Result::unwrap);
+            let request = lines.next().unwrap();
+            lines.take_while(|line| !line.is_empty()).for_each(drop);
+            let status = match request.split_whitespace().collect::<Vec<_>>()[..] {
+                ["HEAD", "/docs", ..] => "200 OK",
+                ["HEAD", "/get-only", ..] => "405 Method Not Allowed",
+                ["GET", "/get-only", ..] => "200 OK",
+                ["HEAD", "/slow", ..] => {
+                    std::thread::sleep(Duration::from_millis(500));
+                    "200 OK"
+                }
+                _ => "404 Not Found",
+            };
+            let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            stream.write_all(response.as_bytes()).ok();
+        }
+    });
+
+    let checker = LinkChecker::new(Duration::from_millis(200));
+    let url = |path: &str| Url::parse(&format!("http://127.0.0.1:{port}{path}")).unwrap();
+    assert_eq!(checker.check(&url("/docs")), Reachability::Reachable);
+    assert_eq!(checker.check(&url("/get-only")), Reachability::Reachable);
+    assert_eq!(checker.check(&url("/missing")), Reachability::Status(404));
+    assert_eq!(checker.check(&url("/slow")), Reachability::TimedOut);
+
+    // nothing listens on the port of a dropped listener
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let unreachable = checker.check(&Url::parse(&format!("http://127.0.0.1:{closed}/")).unwrap());
+    assert!(matches!(unreachable, Reachability::Unreachable(_)));
+}
@@ -0,0 +1,163 @@
+//! Reading `.npy` files without loading them: the file is mapped into memory, and only the values that are asked
+//! for are read, so that e.g. a single slice of a tensor of several GB can be looked at.
+
+use std::path::Path;
+
+use memmap2::Mmap;
+use ndarray::{ArrayD, IxDyn};
+
+use super::test_tensor::{NpyHeader, NpyHeaderError, NpyLayout};
+use crate::rdf::model::data_type::DataType;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum NpySliceError {
+    #[error("Expected an index or a kept axis for each of the {expected} axes, got {found}")]
+    WrongDimensions { expected: usize, found: usize },
+    #[error("Index {index} is out of bounds for axis {axis}, which has size {size}")]
+    OutOfBounds { axis: usize, index: usize, size: usize },
+}
+
+/// An `.npy` file mapped into memory
+pub struct MappedNpy {
+    header: NpyHeader,
+    layout: NpyLayout,
+    /// How many values apart neighbours along each axis are
+    strides: Vec<usize>,
+    map: Mmap,
+}
+
+impl MappedNpy {
+    /// Maps the file at `path`, reading only its header, and checks that it is long enough for the values the header
+    /// announces
+    pub fn open(path: &Path) -> Result<Self, NpyHeaderError> {
+        let file = std::fs::File::open(path)?;
+        // Safety: the map is only ever read. A file that is changed while it is mapped gives wrong values, like
+        // it would if it was changed while being read.
+        let map = unsafe { Mmap::map(&file)? };
+        let (header, layout) = NpyHeader::read_with_layout(&map[..])?;
+        let data_len: usize = header.shape.iter().product::<usize>() * header.value_size();
+        if map.len() < layout.data_offset + data_len {
+            return Err(NpyHeaderError::BadHeader(format!(
+                "the file has {} bytes, but its shape needs {}",
+                map.len(),
+                layout.data_offset + data_len
+            )));
+        }
+        let mut strides = vec![1; header.shape.len()];
+        let axes: Vec<usize> = match layout.fortran_order {
+            false => (0..header.shape.len()).rev().collect(),
+            true => (0..header.shape.len()).collect(),
+        };
+        let mut stride = 1;
+        for axis in axes {
+            strides[axis] = stride;
+            stride *= header.shape[axis];
+        }
+        Ok(Self { header, layout, strides, map })
+    }
+
+    pub fn header(&self) -> &NpyHeader {
+        &self.header
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.header.shape
+    }
+
+    /// The value at position `offset` of the data, as f32
+    fn value_at(&self, offset: usize) -> f32 {
+        let size = self.header.value_size();
+        let start = self.layout.data_offset + offset * size;
+        let bytes = &self.map[start..start + size];
+        macro_rules! read_as {
+            ($element:ty) => {{
+                let bytes = bytes.try_into().expect("value_size bytes");
+                let value = if self.layout.big_endian {
+                    <$element>::from_be_bytes(bytes)
+                } else {
+                    <$element>::from_le_bytes(bytes)
+                };
+                value as f32
+            }};
+        }
+        match self.header.data_type {
+            DataType::Bool => f32::from(bytes[0] != 0),
+            DataType::Uint8 => read_as!(u8),
+            DataType::Int8 => read_as!(i8),
+            DataType::Uint16 => read_as!(u16),
+            DataType::Int16 => read_as!(i16),
+            DataType::Uint32 => read_as!(u32),
+            DataType::Int32 => read_as!(i32),
+            DataType::Float32 => read_as!(f32),
+            DataType::Uint64 => read_as!(u64),
+            DataType::Int64 => read_as!(i64),
+            DataType::Float64 => read_as!(f64),
+        }
+    }
+
+    /// Reads the values along the axes whose entry in `indices` is `None`, at the given index along every other
+    /// axis. The axes that are kept stay in their order.
+    pub fn read_slice(&self, indices: &[Option<usize>]) -> Result<ArrayD<f32>, NpySliceError> {
+        let shape = &self.header.shape;
+        if indices.len() != shape.len() {
+            return Err(NpySliceError::WrongDimensions { expected: shape.len(), found: indices.len() });
+        }
+        let mut base = 0;
+        let mut kept = vec![];
+        for (axis, index) in indices.iter().enumerate() {
+            match index {
+                Some(index) if *index >= shape[axis] => {
+                    return Err(NpySliceError::OutOfBounds { axis, index: *index, size: shape[axis] });
+                }
+                Some(index) => base += index * self.strides[axis],
+                None => kept.push(axis),
+            }
+        }
+        let kept_shape: Vec<usize> = kept.iter().map(|axis| shape[*axis]).collect();
+        Ok(ArrayD::from_shape_fn(IxDyn(&kept_shape), |index| {
+            let offset: usize = kept.iter().enumerate().map(|(position, axis)| index[position] * self.strides[*axis]).sum();
+            self.value_at(base + offset)
+        }))
+    }
+
+    /// Reads all values, which for big files takes as much memory as loading them would
+    pub fn read_all(&self) -> ArrayD<f32> {
+        self.read_slice(&vec![None; self.header.shape.len()]).expect("every axis is kept")
+    }
+}
+
+#[test]
+fn test_mapped_npy() {
+    use ndarray::ShapeBuilder;
+    use ndarray_npy::WriteNpyExt;
+
+    let dir = std::env::temp_dir().join(format!("bioimg_mapped_npy_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let write = |name: &str, array: ArrayD<u16>| {
+        let path = dir.join(name);
+        array.write_npy(std::fs::File::create(&path).unwrap()).unwrap();
+        path
+    };
+    let value = |index: IxDyn| (index[0] * 100 + index[1] * 10 + index[2]) as u16;
+    let array = ArrayD::from_shape_fn(IxDyn(&[2, 3, 4]), value);
+    let c_order = MappedNpy::open(&write("c.npy", array.clone())).unwrap();
+    let fortran_order = MappedNpy::open(&write("f.npy", ArrayD::from_shape_fn(IxDyn(&[2, 3, 4]).f(), value))).unwrap();
+    assert!(fortran_order.layout.fortran_order);
+    assert_eq!(c_order.header().data_type, DataType::Uint16);
+
+    for npy in [&c_order, &fortran_order] {
+        assert_eq!(npy.read_all(), array.mapv(f32::from));
+        let slice = npy.read_slice(&[Some(1), None, Some(2)]).unwrap();
+        assert_eq!(slice.as_slice().unwrap(), [102.0, 112.0, 122.0]);
+    }
+    assert_eq!(
+        c_order.read_slice(&[Some(2), None, None]),
+        Err(NpySliceError::OutOfBounds { axis: 0, index: 2, size: 2 })
+    );
+    assert_eq!(c_order.read_slice(&[None]), Err(NpySliceError::WrongDimensions { expected: 3, found: 1 }));
+
+    let full = std::fs::read(dir.join("c.npy")).unwrap();
+    std::fs::write(dir.join("short.npy"), &full[..full.len() - 2]).unwrap();
+    assert!(matches!(MappedNpy::open(&dir.join("short.npy")), Err(NpyHeaderError::BadHeader(_))));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,7 +1,55 @@
+pub mod author_table;
+pub mod bibtex;
 pub mod channel_names;
+pub mod citation;
+pub mod conda_env;
+pub mod conformance;
 pub mod cover_image;
+pub mod fetch;
+pub mod github;
 pub mod icon;
+pub mod image_tensor;
+pub mod inference;
+pub mod link_check;
+pub mod mapped_npy;
 pub mod model;
+pub mod model_card;
+pub mod model_package;
+pub mod network;
+pub mod orcid;
+pub mod package_reader;
+pub mod processing;
+pub mod provenance;
+pub mod self_test;
+pub mod tensor;
+pub mod test_tensor;
+pub mod tiling;
+#[cfg(feature = "upload")]
+pub mod upload;
+pub mod watch;
+pub mod zoo;
 
-pub use cover_image::{CoverImage, CoverImageParsingError};
+pub use author_table::{read_author_table, AuthorRowError, AuthorTableError};
+pub use bibtex::{read_bibtex, BibtexError};
+pub use citation::{Citation, CitationClient, CitationLookupError};
+pub use conda_env::{CondaEnv, CondaEnvParsingError};
+pub use cover_image::{CoverCrop, CoverImage, CoverImageParsingError};
+pub use fetch::{FetchError, PendingFetch, ResourceFetcher};
+pub use github::{GithubAccount, GithubClient, GithubLookupError};
 pub use icon::Icon;
+pub use image_tensor::{array_to_image, image_to_array, ImageAxis, ImageConversionError};
+pub use link_check::{LinkChecker, Reachability};
+pub use mapped_npy::{MappedNpy, NpySliceError};
+pub use model_card::{model_card, CardFormat};
+pub use model_package::{validate_path, validate_yaml};
+pub use model_package::{
+    FileCompression, ModelPackage, ModelPackagingError, ModelUnpackingError, PackageFile, PackageManifest,
+};
+pub use network::{NetworkConfig, NetworkConfigError};
+pub use orcid::{OrcidClient, OrcidLookupError, OrcidPerson};
+pub use package_reader::{LoadedRdf, RdfEncoding, RdfLoadingError, RdfSource};
+pub use self_test::{InferenceBackend, SelfTestError, SelfTestReport, Tolerance};
+pub use tensor::{Tensor, TensorAxis, TensorError};
+pub use test_tensor::{NpyHeader, NpyHeaderError, TestTensorError};
+pub use watch::{ValidationWatcher, WatchUpdate};
+pub use zoo::{TagUsage, ZooClient, ZooEntry, ZooError, ZooIndex, ZooQuery};
@@ -0,0 +1,378 @@
+//! Model cards: a page about a model, for a README or for reviewing it, put together from its description.
+//!
+//! The card is first laid out as [Block]s, which are then written out as markdown or HTML, so that both formats
+//! always hold the same things.
+
+use crate::rdf::{
+    author::Author2,
+    cite_entry::CiteEntry2,
+    file_reference::FileReference,
+    model::{
+        axes::{InputAxis, OutputAxis},
+        data_type::DataType,
+        tensor_data_descr::TensorData,
+        AnyAxisSize, ModelRdf, TrainingData,
+    },
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CardFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl CardFormat {
+    /// The usual extension of files in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// A piece of text within a paragraph, list item or table cell
+enum Inline {
+    Text(String),
+    Code(String),
+    Link { text: String, url: String },
+}
+
+enum Block {
+    Heading(u8, String),
+    Paragraph(Vec<Inline>),
+    /// Images with their alt text
+    Images(Vec<(String, String)>),
+    List(Vec<Vec<Inline>>),
+    Table { header: Vec<String>, rows: Vec<Vec<Vec<Inline>>> },
+}
+
+/// The card of the model described by `rdf`. Covers and other files are referred to as the description does, so
+/// relative paths work for a card that is kept next to the description.
+pub fn model_card(rdf: &ModelRdf, format: CardFormat) -> String {
+    let blocks = card_blocks(rdf);
+    match format {
+        CardFormat::Markdown => to_markdown(&blocks),
+        CardFormat::Html => to_html(&rdf.name.to_string(), &blocks),
+    }
+}
+
+fn text(text: impl Into<String>) -> Inline {
+    Inline::Text(text.into())
+}
+
+fn link(text: impl Into<String>, url: impl Into<String>) -> Inline {
+    Inline::Link { text: text.into(), url: url.into() }
+}
+
+fn reference(reference: &FileReference) -> Inline {
+    let target = String::from(reference.clone());
+    link(target.clone(), target)
+}
+
+fn card_blocks(rdf: &ModelRdf) -> Vec<Block> {
+    let mut blocks = vec![];
+    let title = match &rdf.version {
+        Some(version) => format!("{} {version}", rdf.name),
+        None => rdf.name.to_string(),
+    };
+    blocks.push(Block::Heading(1, title));
+    blocks.push(Block::Paragraph(vec![text(rdf.description.to_string())]));
+    if !rdf.covers.is_empty() {
+        let covers = rdf.covers.iter().map(|cover| (String::from(cover.clone()), format!("Cover of {}", rdf.name)));
+        blocks.push(Block::Images(covers.collect()));
+    }
+
+    let mut facts = vec![vec![text("License: "), Inline::Code(rdf.license.to_string())]];
+    if let Some(id) = &rdf.id {
+        facts.push(vec![text("Id: "), Inline::Code(id.to_string())]);
+    }
+    facts.push(vec![text("Documentation: "), reference(&rdf.documentation)]);
+    if let Some(git_repo) = &rdf.git_repo {
+        facts.push(vec![text("Source code: "), link(git_repo.as_str(), git_repo.as_str())]);
+    }
+    if !rdf.tags.is_empty() {
+        let mut tags = vec![text("Tags: ")];
+        for (index, tag) in rdf.tags.iter().enumerate() {
+            if index > 0 {
+                tags.push(text(", "));
+            }
+            tags.push(Inline::Code(tag.to_string()));
+        }
+        facts.push(tags);
+    }
+    if let Some(parent) = &rdf.parent {
+        facts.push(vec![text("Derived from: "), Inline::Code(parent.id.to_string())]);
+    }
+    match &rdf.training_data {
+        Some(TrainingData::Linked(dataset)) => facts.push(vec![text("Trained on: "), Inline::Code(dataset.id.to_string())]),
+        Some(TrainingData::Inline(dataset)) => facts.push(vec![text(format!("Trained on: {}", dataset.base.name))]),
+        None => (),
+    }
+    blocks.push(Block::List(facts));
+
+    blocks.push(Block::Heading(2, "Authors".to_owned()));
+    blocks.push(Block::List(rdf.authors.iter().map(person).collect()));
+    if !rdf.packaged_by.is_empty() {
+        blocks.push(Block::Heading(2, "Packaged by".to_owned()));
+        blocks.push(Block::List(rdf.packaged_by.iter().map(person).collect()));
+    }
+    if !rdf.maintainers.is_empty() {
+        blocks.push(Block::Heading(2, "Maintainers".to_owned()));
+        let maintainers = rdf.maintainers.iter().map(|maintainer| {
+            let user = maintainer.github_user.to_string();
+            let mut item = vec![link(user.clone(), format!("https://github.com/{user}"))];
+            if let Some(name) = &maintainer.name {
+                item.push(text(format!(" ({})", name.as_str())));
+            }
+            item
+        });
+        blocks.push(Block::List(maintainers.collect()));
+    }
+
+    blocks.push(Block::Heading(2, "Citations".to_owned()));
+    blocks.push(Block::List(rdf.cite.iter().map(citation).collect()));
+
+    let tensor_header = ["Id", "Description", "Axes", "Data type"].map(String::from).to_vec();
+    blocks.push(Block::Heading(2, "Inputs".to_owned()));
+    let inputs = rdf.inputs.iter().map(|input| {
+        let axes: Vec<String> = input.axes.iter().map(input_axis).collect();
+        tensor_row(&input.id.to_string(), &input.description.to_string(), &axes, input.data.as_ref())
+    });
+    blocks.push(Block::Table { header: tensor_header.clone(), rows: inputs.collect() });
+    blocks.push(Block::Heading(2, "Outputs".to_owned()));
+    let outputs = rdf.outputs.iter().map(|output| {
+        let axes: Vec<String> = output.axes.iter().map(output_axis).collect();
+        tensor_row(&output.id.to_string(), &output.description.to_string(), &axes, output.data.as_ref())
+    });
+    blocks.push(Block::Table { header: tensor_header, rows: outputs.collect() });
+
+    blocks.push(Block::Heading(2, "Weights".to_owned()));
+    let weights = rdf.weights.entries().into_iter().map(|(format, entry)| {
+        vec![
+            vec![Inline::Code(format.to_string())],
+            vec![reference(&entry.source)],
+            entry.parent.map(|parent| vec![Inline::Code(parent.to_string())]).unwrap_or_default(),
+        ]
+    });
+    blocks.push(Block::Table {
+        header: ["Format", "File", "Converted from"].map(String::from).to_vec(),
+        rows: weights.collect(),
+    });
+    blocks
+}
+
+fn person(author: &Author2) -> Vec<Inline> {
+    let mut item = vec![text(author.name.to_string())];
+    if let Some(orcid) = &author.orcid {
+        item.push(text(" ("));
+        item.push(link(format!("ORCID {orcid}"), format!("https://orcid.org/{orcid}")));
+        item.push(text(")"));
+    }
+    if let Some(affiliation) = &author.affiliation {
+        item.push(text(format!(", {affiliation}")));
+    }
+    if let Some(user) = &author.github_user {
+        item.push(text(", "));
+        item.push(link(format!("@{user}"), format!("https://github.com/{user}")));
+    }
+    item
+}
+
+fn citation(entry: &CiteEntry2) -> Vec<Inline> {
+    let mut item = vec![text(entry.text.to_string())];
+    if let Some(doi) = &entry.doi {
+        item.push(text(" "));
+        item.push(link(format!("doi:{doi}"), doi.url()));
+    }
+    if let Some(url) = &entry.url {
+        item.push(text(" "));
+        item.push(link(url.as_str(), url.as_str()));
+    }
+    item
+}
+
+fn tensor_row(id: &str, description: &str, axes: &[String], data: Option<&TensorData>) -> Vec<Vec<Inline>> {
+    let data_types = match data {
+        // tensors that don't say are float32
+        None => vec![DataType::Float32],
+        Some(data) => {
+            let mut data_types: Vec<DataType> = data.descrs().iter().map(|descr| descr.data_type()).collect();
+            data_types.dedup();
+            data_types
+        }
+    };
+    let data_types: Vec<String> = data_types.iter().map(ToString::to_string).collect();
+    vec![
+        vec![Inline::Code(id.to_owned())],
+        vec![text(description)],
+        vec![text(axes.join(", "))],
+        vec![text(data_types.join(", "))],
+    ]
+}
+
+/// What sizes an axis can have, e.g. `64`, `64+16n` or `=raw.x`, for axes that have one
+fn axis_size(size: Option<&AnyAxisSize>) -> Option<String> {
+    Some(match size? {
+        AnyAxisSize::Fixed(size) => size.to_string(),
+        AnyAxisSize::Parameterized(size) => format!("{}+{}n", size.min, size.step),
+        AnyAxisSize::Reference(reference) if reference.offset > 0 => {
+            format!("={}.{}+{}", reference.tensor_id, reference.axis_id, reference.offset)
+        }
+        AnyAxisSize::Reference(reference) => format!("={}.{}", reference.tensor_id, reference.axis_id),
+    })
+}
+
+fn input_axis(axis: &InputAxis) -> String {
+    let size = match axis {
+        InputAxis::Channel(channel) => Some(channel.size().to_string()),
+        _ => axis_size(axis.size()),
+    };
+    match size {
+        Some(size) => format!("{} ({size})", axis.id()),
+        None => axis.id().to_string(),
+    }
+}
+
+fn output_axis(axis: &OutputAxis) -> String {
+    let size = match axis {
+        OutputAxis::Channel(channel) => Some(channel.size().to_string()),
+        _ => axis_size(axis.size()),
+    };
+    match size {
+        Some(size) => format!("{} ({size})", axis.id()),
+        None => axis.id().to_string(),
+    }
+}
+
+/// Escapes the characters markdown would otherwise read as formatting
+fn escape_markdown(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(if c == '\n' { ' ' } else { c });
+    }
+    escaped
+}
+
+fn inline_markdown(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape_markdown(text),
+            Inline::Code(code) => format!("`{}`", code.replace('`', "'")),
+            Inline::Link { text, url } => format!("[{}](<{}>)", escape_markdown(text), url.replace('>', "%3E")),
+        })
+        .collect()
+}
+
+fn to_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, title) => out += &format!("{} {}\n", "#".repeat(*level as usize), escape_markdown(title)),
+            Block::Paragraph(inlines) => out += &format!("{}\n", inline_markdown(inlines)),
+            Block::Images(images) => {
+                for (url, alt) in images {
+                    out += &format!("![{}](<{}>)\n", escape_markdown(alt), url.replace('>', "%3E"));
+                }
+            }
+            Block::List(items) => {
+                for item in items {
+                    out += &format!("- {}\n", inline_markdown(item));
+                }
+            }
+            Block::Table { header, rows } => {
+                let header: Vec<String> = header.iter().map(|cell| escape_markdown(cell)).collect();
+                out += &format!("| {} |\n", header.join(" | "));
+                out += &format!("|{}\n", " --- |".repeat(header.len()));
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|cell| inline_markdown(cell)).collect();
+                    out += &format!("| {} |\n", cells.join(" | "));
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn inline_html(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape_html(text),
+            Inline::Code(code) => format!("<code>{}</code>", escape_html(code)),
+            Inline::Link { text, url } => format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text)),
+        })
+        .collect()
+}
+
+fn to_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n",
+        escape_html(title)
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, title) => out += &format!("<h{level}>{}</h{level}>\n", escape_html(title)),
+            Block::Paragraph(inlines) => out += &format!("<p>{}</p>\n", inline_html(inlines)),
+            Block::Images(images) => {
+                out += "<p>\n";
+                for (url, alt) in images {
+                    out += &format!("<img src=\"{}\" alt=\"{}\">\n", escape_html(url), escape_html(alt));
+                }
+                out += "</p>\n";
+            }
+            Block::List(items) => {
+                out += "<ul>\n";
+                for item in items {
+                    out += &format!("<li>{}</li>\n", inline_html(item));
+                }
+                out += "</ul>\n";
+            }
+            Block::Table { header, rows } => {
+                out += "<table>\n<tr>";
+                for cell in header {
+                    out += &format!("<th>{}</th>", escape_html(cell));
+                }
+                out += "</tr>\n";
+                for row in rows {
+                    out += "<tr>";
+                    for cell in row {
+                        out += &format!("<td>{}</td>", inline_html(cell));
+                    }
+                    out += "</tr>\n";
+                }
+                out += "</table>\n";
+            }
+        }
+    }
+    out += "</body>\n</html>\n";
+    out
+}
+
+#[test]
+fn test_model_card() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_affable_shark.yaml");
+    let mut model = ModelRdf::from_yaml(&std::fs::read_to_string(path).unwrap()).unwrap();
+    model.name = "Shark <v2> | *nuclei*".to_owned().try_into().unwrap();
+    let markdown = model_card(&model, CardFormat::Markdown);
+    assert!(markdown.starts_with("# Shark \\<v2\\> \\| \\*nuclei\\*"));
+    assert!(markdown.contains("## Inputs\n\n| Id | Description | Axes | Data type |\n| --- | --- | --- | --- |\n"));
+    assert!(markdown.contains("| `pytorch_state_dict` |"));
+
+    let html = model_card(&model, CardFormat::Html);
+    assert!(html.contains("<title>Shark &lt;v2&gt; | *nuclei*</title>"));
+    assert!(html.contains("<h2>Weights</h2>\n<table>\n<tr><th>Format</th>"));
+    assert_eq!(html.matches("<ul>").count(), html.matches("</ul>").count());
+}
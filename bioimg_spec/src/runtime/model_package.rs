@@ -0,0 +1,911 @@
+//! Writing a model description and the files it refers to into a zip package, and reading one back.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{Read, Seek, Write},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rayon::prelude::*;
+use sha2::Digest;
+
+use super::{
+    icon::{Icon, IconParsingError},
+    mapped_npy::MappedNpy,
+    package_reader::{read_rdf_text, PackageReadingError, RDF_FILE_NAMES},
+    test_tensor::{NpyHeader, TestTensorError},
+    CoverImage, CoverImageParsingError,
+};
+use crate::rdf::{
+    model::{tensor_id::TensorId, weights::ArchitectureDescr, ModelRdf},
+    resource_description::{AnyResourceRdf, ResourceRdfParsingError},
+    validation::{spec_path, Severity, ValidationReport},
+    FileDescr, FileReference, Sha256, SpecError,
+};
+use crate::util::progress::{ignore_progress, OnProgress, SharedProgress, SharedProgressReader};
+
+#[derive(thiserror::Error, Debug)]
+pub enum ModelPackagingError {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("{0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("Could not write rdf.yaml: {0}")]
+    BadYaml(#[from] serde_yaml::Error),
+    #[error("The model refers to '{}', but no file was given for it", path.to_string_lossy())]
+    MissingFile { path: PathBuf },
+    #[error("'{}' is not referred to by the model", path.to_string_lossy())]
+    UnusedFile { path: PathBuf },
+    #[error("'{}' can't be packaged, as it is where the model description goes", path.to_string_lossy())]
+    ReservedName { path: PathBuf },
+    #[error("'{}' has hash {found}, but the model expects {expected}", path.to_string_lossy())]
+    ChangedFile { path: PathBuf, expected: Sha256, found: Sha256 },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ModelUnpackingError {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("{0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("{0}")]
+    BadPackage(#[from] PackageReadingError),
+    #[error("Bad model description: {0}")]
+    BadRdf(#[from] SpecError),
+    #[error("The model refers to '{}', which is not in the package", path.to_string_lossy())]
+    MissingFile { path: PathBuf },
+    #[error("'{}' has hash {found}, but the model expects {expected}", path.to_string_lossy())]
+    ChangedFile { path: PathBuf, expected: Sha256, found: Sha256 },
+    #[error("'{}' points outside of the package", path.to_string_lossy())]
+    OutsideOfPackage { path: PathBuf },
+}
+
+/// Where the contents of a packaged file come from
+pub enum PackageFile {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl PackageFile {
+    /// How many bytes the file has, as far as can be told before reading it
+    fn size(&self) -> u64 {
+        match self {
+            Self::Path(path) => std::fs::metadata(path).map_or(0, |metadata| metadata.len()),
+            Self::Bytes(bytes) => bytes.len() as u64,
+        }
+    }
+}
+
+/// Extensions of files that are compressed already, like images and the zip archives PyTorch saves, which are
+/// stored as they are by default since deflating them again takes long and gains next to nothing
+pub const STORED_EXTENSIONS: &[&str] = &["gz", "jpeg", "jpg", "npz", "png", "pt", "pth", "torchscript", "webp", "zip"];
+
+/// How a file is put into the package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileCompression {
+    Store,
+    Deflate,
+}
+
+impl FileCompression {
+    fn method(self) -> zip::CompressionMethod {
+        match self {
+            Self::Store => zip::CompressionMethod::Stored,
+            Self::Deflate => zip::CompressionMethod::Deflated,
+        }
+    }
+}
+
+/// Size of the chunks files are copied into the package in, which is as much of a file as is held in memory at once
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// A file that was written into the package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: Sha256,
+}
+
+/// Everything written into a package, in the order it was written. The rdf itself comes last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageManifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// A model description together with the local files it refers to, keyed by the relative path
+/// they are referred to by. Urls are left as they are.
+pub struct ModelPackage {
+    pub rdf: ModelRdf,
+    files: BTreeMap<PathBuf, PackageFile>,
+    compression_level: Option<i32>,
+    /// How files with these lowercase extensions are put into the package, instead of the default
+    compression_by_extension: BTreeMap<String, FileCompression>,
+}
+
+/// A local file referred to by the model, and the hash that goes with it if the field has one
+struct FileSlot<'a> {
+    path: &'a Path,
+    sha256: Option<&'a mut Option<Sha256>>,
+}
+
+impl<'a> FileSlot<'a> {
+    fn new(source: &'a FileReference, sha256: Option<&'a mut Option<Sha256>>) -> Option<Self> {
+        match source {
+            FileReference::Path(path) => Some(Self { path, sha256 }),
+            FileReference::Url(_) => None,
+        }
+    }
+
+    fn of_descr(descr: &'a mut FileDescr) -> Option<Self> {
+        let FileDescr { source, sha256 } = descr;
+        Self::new(source, Some(sha256))
+    }
+}
+
+/// The local files `rdf` refers to, its icon included, relative to the description
+pub(crate) fn local_paths(rdf: &mut ModelRdf) -> Vec<PathBuf> {
+    let icon = rdf.icon.as_ref().map(|icon| PathBuf::from(icon.as_str()));
+    let mut paths: Vec<PathBuf> = file_slots(rdf).into_iter().map(|slot| slot.path.to_owned()).collect();
+    paths.extend(icon);
+    paths
+}
+
+/// The local files a description of any type refers to, relative to it, like [local_paths] does for models
+pub(crate) fn resource_local_paths(rdf: &mut AnyResourceRdf) -> Vec<PathBuf> {
+    let (base, entries) = match rdf {
+        AnyResourceRdf::Model(model) => return local_paths(model),
+        AnyResourceRdf::Dataset(rdf) => (&rdf.base, &[][..]),
+        AnyResourceRdf::Notebook(rdf) => (&rdf.base, &[][..]),
+        AnyResourceRdf::Application(rdf) => (&rdf.base, &[][..]),
+        AnyResourceRdf::Collection(rdf) => (&rdf.base, rdf.entries()),
+    };
+    let attachments = base.attachments.iter().flat_map(|attachments| attachments.files.iter().map(|file| &file.source));
+    let references = attachments
+        .chain(base.covers.iter().flatten())
+        .chain(&base.documentation)
+        .chain(&base.download_url)
+        .chain(&base.source)
+        .chain(entries.iter().filter_map(|entry| entry.rdf_source.as_ref()));
+    let mut paths: Vec<PathBuf> = references
+        .filter_map(|reference| match reference {
+            FileReference::Path(path) => Some(path.clone()),
+            FileReference::Url(_) => None,
+        })
+        .collect();
+    paths.extend(base.icon.as_ref().map(|icon| PathBuf::from(icon.as_str())));
+    paths
+}
+
+fn file_slots(rdf: &mut ModelRdf) -> Vec<FileSlot<'_>> {
+    let mut slots = vec![];
+    slots.extend(rdf.attachments.iter_mut().filter_map(FileSlot::of_descr));
+    slots.extend(rdf.covers.iter().filter_map(|cover| FileSlot::new(cover, None)));
+    slots.extend(FileSlot::new(&rdf.documentation, None));
+    for input in rdf.inputs.iter_mut() {
+        slots.extend(FileSlot::of_descr(&mut input.test_tensor));
+        slots.extend(input.sample_tensor.as_mut().and_then(FileSlot::of_descr));
+    }
+    for output in rdf.outputs.iter_mut() {
+        slots.extend(FileSlot::of_descr(&mut output.test_tensor));
+        slots.extend(output.sample_tensor.as_mut().and_then(FileSlot::of_descr));
+    }
+
+    let weights = &mut rdf.weights;
+    let mut entries = vec![];
+    entries.extend(weights.keras_hdf5.as_mut().map(|w| &mut w.entry));
+    entries.extend(weights.onnx.as_mut().map(|w| &mut w.entry));
+    entries.extend(weights.tensorflow_js.as_mut().map(|w| &mut w.entry));
+    entries.extend(weights.torchscript.as_mut().map(|w| &mut w.entry));
+    if let Some(pytorch) = &mut weights.pytorch_state_dict {
+        entries.push(&mut pytorch.entry);
+        if let ArchitectureDescr::FromFile { source, sha256, .. } = &mut pytorch.architecture {
+            slots.extend(FileSlot::new(source, Some(sha256)));
+        }
+        slots.extend(pytorch.dependencies.as_ref().and_then(|deps| FileSlot::new(deps, None)));
+    }
+    if let Some(tensorflow) = &mut weights.tensorflow_saved_model_bundle {
+        entries.push(&mut tensorflow.entry);
+        slots.extend(tensorflow.dependencies.as_ref().and_then(|deps| FileSlot::new(deps, None)));
+    }
+    slots.extend(entries.into_iter().filter_map(|entry| FileSlot::new(&entry.source, Some(&mut entry.sha256))));
+    slots
+}
+
+/// Hashes everything written through it
+struct HashingWriter<W> {
+    inner: W,
+    hasher: sha2::Sha256,
+    size: u64,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn compression_of(by_extension: &BTreeMap<String, FileCompression>, package_path: &Path) -> FileCompression {
+    let Some(extension) = package_path.extension().map(|extension| extension.to_string_lossy().to_lowercase()) else {
+        return FileCompression::Deflate;
+    };
+    match by_extension.get(&extension) {
+        Some(compression) => *compression,
+        None if STORED_EXTENSIONS.contains(&extension.as_str()) => FileCompression::Store,
+        None => FileCompression::Deflate,
+    }
+}
+
+/// A file compressed into a zip of its own, to be copied into the package as it is
+struct CompressedFile {
+    entry: ManifestEntry,
+    zip_path: PathBuf,
+}
+
+/// Compresses `file` into a new zip at `zip_path`, under the name it has in the package, hashing it on the way
+fn compress_file(
+    package_path: &Path,
+    file: &PackageFile,
+    options: zip::write::FileOptions,
+    zip_path: PathBuf,
+    progress: &SharedProgress,
+) -> Result<CompressedFile, ModelPackagingError> {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path)?);
+    zip.start_file(zip_name(package_path), options)?;
+    let mut hashing = HashingWriter {
+        inner: &mut zip,
+        hasher: sha2::Sha256::new(),
+        size: 0,
+    };
+    match file {
+        PackageFile::Path(path) => {
+            let mut reader = SharedProgressReader {
+                inner: std::fs::File::open(path)?,
+                progress,
+            };
+            let mut chunk = vec![0; COPY_CHUNK_SIZE];
+            loop {
+                match reader.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(read) => hashing.write_all(&chunk[..read])?,
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        }
+        PackageFile::Bytes(bytes) => {
+            for chunk in bytes.chunks(COPY_CHUNK_SIZE) {
+                hashing.write_all(chunk)?;
+                progress.add(chunk.len() as u64)?;
+            }
+        }
+    }
+    let entry = ManifestEntry {
+        path: package_path.to_owned(),
+        size: hashing.size,
+        sha256: Sha256::from(<[u8; 32]>::from(hashing.hasher.finalize())),
+    };
+    zip.finish()?;
+    Ok(CompressedFile { entry, zip_path })
+}
+
+/// A new directory for temporary files, removed with everything in it once dropped
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn create() -> std::io::Result<Self> {
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+        let name = format!("bioimg_packaging_{}_{}", std::process::id(), CREATED.fetch_add(1, Ordering::Relaxed));
+        let path = std::env::temp_dir().join(name);
+        std::fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Name of the entry for `path` in the zip, which always uses `/`
+fn zip_name(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl ModelPackage {
+    pub fn new(rdf: ModelRdf) -> Self {
+        Self {
+            rdf,
+            files: BTreeMap::new(),
+            compression_level: None,
+            compression_by_extension: BTreeMap::new(),
+        }
+    }
+
+    /// Takes the files a description refers to from the directory it is in, without checking their
+    /// hashes until the package is written. Files that don't exist are left out.
+    pub fn from_description_file(path: &Path) -> Result<Self, ModelUnpackingError> {
+        let mut package = Self::new(ModelRdf::from_yaml(&std::fs::read_to_string(path)?)?);
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for package_path in local_paths(&mut package.rdf) {
+            let file_path = dir.join(&package_path);
+            if file_path.is_file() {
+                package.add_file(package_path, PackageFile::Path(file_path));
+            }
+        }
+        Ok(package)
+    }
+
+    /// Deflate level from 0 (fastest) to 9 (smallest) for the written files, or the zip default if `None`
+    pub fn set_compression_level(&mut self, level: Option<i32>) {
+        self.compression_level = level;
+    }
+
+    /// Picks how files ending in `.{extension}` are put into the package, e.g. to store weights that don't compress
+    pub fn set_compression_for(&mut self, extension: &str, compression: FileCompression) {
+        self.compression_by_extension.insert(extension.to_lowercase(), compression);
+    }
+
+    /// How the file at `package_path` is put into the package: as set with [Self::set_compression_for], or else
+    /// stored if its extension is one of the [STORED_EXTENSIONS] and deflated otherwise
+    pub fn compression_of(&self, package_path: &Path) -> FileCompression {
+        compression_of(&self.compression_by_extension, package_path)
+    }
+
+    /// Forgets the hashes in the rdf, so that writing fills them in from the files instead of checking them
+    pub fn clear_hashes(&mut self) {
+        for slot in file_slots(&mut self.rdf) {
+            if let Some(sha256) = slot.sha256 {
+                *sha256 = None;
+            }
+        }
+    }
+
+    /// Gives the contents of the file the model refers to as `package_path`
+    pub fn add_file(&mut self, package_path: impl Into<PathBuf>, file: PackageFile) {
+        self.files.insert(package_path.into(), file);
+    }
+
+    /// Fills in the hash of every given file the rdf has a hash field for, as writing the package
+    /// would, without writing it. A hash the rdf already had must match the file.
+    pub fn fill_hashes(&mut self) -> Result<(), ModelPackagingError> {
+        let mut slots = file_slots(&mut self.rdf);
+        slots.retain(|slot| slot.sha256.is_some() && self.files.contains_key(slot.path));
+        let mut paths: Vec<&Path> = slots.iter().map(|slot| slot.path).collect();
+        paths.sort();
+        paths.dedup();
+        let files = &self.files;
+        let computed: BTreeMap<&Path, Sha256> = paths
+            .into_par_iter()
+            .map(|path| {
+                let found = match &files[path] {
+                    PackageFile::Path(path) => Sha256::of_file(path)?,
+                    PackageFile::Bytes(bytes) => Sha256::digest(bytes),
+                };
+                Ok((path, found))
+            })
+            .collect::<std::io::Result<_>>()?;
+        for slot in slots {
+            let (Some(sha256), Some(found)) = (slot.sha256, computed.get(slot.path).copied()) else {
+                continue;
+            };
+            match sha256 {
+                Some(expected) if *expected != found => {
+                    return Err(ModelPackagingError::ChangedFile {
+                        path: slot.path.to_owned(),
+                        expected: *expected,
+                        found,
+                    })
+                }
+                _ => *sha256 = Some(found),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes every local file the model refers to, then the rdf with the hashes of those files
+    /// filled in. A hash the rdf already had must match the file.
+    pub fn write(&mut self, writer: impl Write + Seek) -> Result<PackageManifest, ModelPackagingError> {
+        self.write_with_progress(writer, &mut ignore_progress)
+    }
+
+    /// Like [Self::write], reporting how many of the bytes of the files were written. The files are hashed and
+    /// compressed on several threads at once, a chunk at a time so that even big weights aren't held in memory.
+    pub fn write_with_progress(
+        &mut self,
+        writer: impl Write + Seek,
+        on_progress: OnProgress,
+    ) -> Result<PackageManifest, ModelPackagingError> {
+        let total = self.files.values().map(PackageFile::size).sum();
+        let icon = self.rdf.icon.as_ref().map(|icon| PathBuf::from(icon.as_str()));
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .compression_level(self.compression_level)
+            // files bigger than 4 GB need zip64, and whether a file is that big is only known once it is written
+            .large_file(true);
+
+        let mut slots = file_slots(&mut self.rdf);
+        // the icon is a file if it is one of the given ones; otherwise it is an emoji
+        let icon_slot = icon.as_deref().filter(|icon| self.files.contains_key(*icon)).map(|path| FileSlot { path, sha256: None });
+        slots.extend(icon_slot);
+
+        // every file once, in the order the model first refers to it
+        let mut paths: Vec<&Path> = vec![];
+        for slot in &slots {
+            if RDF_FILE_NAMES.iter().any(|name| slot.path == Path::new(name)) {
+                return Err(ModelPackagingError::ReservedName { path: slot.path.to_owned() });
+            }
+            if !self.files.contains_key(slot.path) {
+                return Err(ModelPackagingError::MissingFile { path: slot.path.to_owned() });
+            }
+            if !paths.contains(&slot.path) {
+                paths.push(slot.path);
+            }
+        }
+        if let Some(path) = self.files.keys().find(|path| !paths.contains(&path.as_path())) {
+            return Err(ModelPackagingError::UnusedFile { path: path.clone() });
+        }
+
+        let scratch = ScratchDir::create()?;
+        let files = &self.files;
+        let file_options: Vec<_> = paths
+            .iter()
+            .map(|path| options.compression_method(compression_of(&self.compression_by_extension, path).method()))
+            .collect();
+        let compressed = SharedProgress::run(total, on_progress, |progress| {
+            paths
+                .par_iter()
+                .zip(&file_options)
+                .enumerate()
+                .map(|(index, (path, options))| {
+                    let zip_path = scratch.0.join(format!("{index}.zip"));
+                    compress_file(path, &files[*path], *options, zip_path, progress)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        for slot in slots {
+            let Some(sha256) = slot.sha256 else {
+                continue;
+            };
+            let entry = &compressed[paths.iter().position(|path| *path == slot.path).expect("every path is compressed")].entry;
+            match sha256 {
+                Some(expected) if *expected != entry.sha256 => {
+                    return Err(ModelPackagingError::ChangedFile {
+                        path: entry.path.clone(),
+                        expected: *expected,
+                        found: entry.sha256,
+                    })
+                }
+                _ => *sha256 = Some(entry.sha256),
+            }
+        }
+
+        let mut zip = zip::ZipWriter::new(writer);
+        let mut entries = vec![];
+        for file in compressed {
+            let mut archive = zip::ZipArchive::new(std::fs::File::open(&file.zip_path)?)?;
+            zip.raw_copy_file(archive.by_index(0)?)?;
+            entries.push(file.entry);
+        }
+
+        let rdf_yaml = self.rdf.to_yaml()?;
+        zip.start_file(RDF_FILE_NAMES[0], options.large_file(false))?;
+        zip.write_all(rdf_yaml.as_bytes())?;
+        entries.push(ManifestEntry {
+            path: PathBuf::from(RDF_FILE_NAMES[0]),
+            size: rdf_yaml.len() as u64,
+            sha256: Sha256::digest(&rdf_yaml),
+        });
+        zip.finish()?;
+        Ok(PackageManifest { entries })
+    }
+
+    /// Like [Self::write], into a new zip file at `path`
+    pub fn write_to_path(&mut self, path: &Path) -> Result<PackageManifest, ModelPackagingError> {
+        self.write(std::fs::File::create(path)?)
+    }
+
+    /// Like [Self::write_with_progress], into a new zip file at `path`
+    pub fn write_to_path_with_progress(
+        &mut self,
+        path: &Path,
+        on_progress: OnProgress,
+    ) -> Result<PackageManifest, ModelPackagingError> {
+        self.write_with_progress(std::fs::File::create(path)?, on_progress)
+    }
+
+    /// Reads a packaged model, which can be a zip file or a directory, along with every local file
+    /// it refers to. Files in a directory are only read when needed, except to check their hashes.
+    pub fn open(path: &Path) -> Result<Self, ModelUnpackingError> {
+        let mut rdf = ModelRdf::from_yaml(&read_rdf_text(path)?)?;
+        let mut archive = match path.is_dir() {
+            true => None,
+            false => Some(zip::ZipArchive::new(std::fs::File::open(path)?)?),
+        };
+        let icon = rdf.icon.as_ref().map(|icon| PathBuf::from(icon.as_str()));
+
+        let mut slots = file_slots(&mut rdf);
+        let mut icon_is_file = |icon: &Path| match &mut archive {
+            Some(archive) => archive.by_name(&zip_name(icon)).is_ok(),
+            None => path.join(icon).is_file(),
+        };
+        if let Some(icon) = icon.as_deref().filter(|icon| icon_is_file(icon)) {
+            slots.push(FileSlot { path: icon, sha256: None });
+        }
+        let is_zip = archive.is_some();
+        drop(archive);
+        // every file once, with the hash the model expects for it if it has one
+        let mut wanted: Vec<(&Path, Option<Sha256>)> = vec![];
+        for slot in &slots {
+            if !wanted.iter().any(|(path, _)| *path == slot.path) {
+                wanted.push((slot.path, slot.sha256.as_deref().copied().flatten()));
+            }
+        }
+        let files = wanted
+            .into_par_iter()
+            .map(|(package_path, expected)| {
+                let missing = || ModelUnpackingError::MissingFile { path: package_path.to_owned() };
+                let file = if is_zip {
+                    // each file is decoded from an archive of its own, so that they can be decoded at the same time
+                    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+                    let mut entry = archive.by_name(&zip_name(package_path)).map_err(|err| match err {
+                        zip::result::ZipError::FileNotFound => missing(),
+                        err => err.into(),
+                    })?;
+                    let mut bytes = Vec::with_capacity(entry.size() as usize);
+                    entry.read_to_end(&mut bytes)?;
+                    PackageFile::Bytes(bytes)
+                } else {
+                    let file_path = path.join(package_path);
+                    if !file_path.is_file() {
+                        return Err(missing());
+                    }
+                    PackageFile::Path(file_path)
+                };
+                if let Some(expected) = expected {
+                    let found = match &file {
+                        PackageFile::Bytes(bytes) => Sha256::digest(bytes),
+                        PackageFile::Path(path) => Sha256::of_file(path)?,
+                    };
+                    if found != expected {
+                        return Err(ModelUnpackingError::ChangedFile {
+                            path: package_path.to_owned(),
+                            expected,
+                            found,
+                        });
+                    }
+                }
+                Ok((package_path.to_owned(), file))
+            })
+            .collect::<Result<_, _>>()?;
+        drop(slots);
+        Ok(Self {
+            rdf,
+            files,
+            compression_level: None,
+            compression_by_extension: BTreeMap::new(),
+        })
+    }
+
+    /// The contents of the file the model refers to as `package_path`, if it was given
+    pub fn file_bytes(&self, package_path: &Path) -> Option<std::io::Result<Cow<'_, [u8]>>> {
+        Some(match self.files.get(package_path)? {
+            PackageFile::Bytes(bytes) => Ok(Cow::Borrowed(bytes.as_slice())),
+            PackageFile::Path(path) => std::fs::read(path).map(Cow::Owned),
+        })
+    }
+
+    /// Writes the files of the package that aren't on disk yet into `dir`, under the paths the model
+    /// refers to them by, and returns where each file of the package can be found
+    pub fn unpack_to(&self, dir: &Path) -> Result<BTreeMap<PathBuf, PathBuf>, ModelUnpackingError> {
+        let mut local_paths = BTreeMap::new();
+        for (package_path, file) in &self.files {
+            let local_path = match file {
+                PackageFile::Path(path) => path.clone(),
+                PackageFile::Bytes(bytes) => {
+                    if !package_path.components().all(|component| matches!(component, Component::Normal(_))) {
+                        return Err(ModelUnpackingError::OutsideOfPackage { path: package_path.clone() });
+                    }
+                    let local_path = dir.join(package_path);
+                    if let Some(parent) = local_path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&local_path, bytes)?;
+                    local_path
+                }
+            };
+            local_paths.insert(package_path.clone(), local_path);
+        }
+        Ok(local_paths)
+    }
+
+    /// The cover images that are part of the package, in the order the model lists them
+    pub fn cover_images(&self) -> Vec<Result<CoverImage, CoverImageParsingError>> {
+        self.rdf
+            .covers
+            .iter()
+            .filter_map(|cover| match cover {
+                FileReference::Path(path) => self.file_bytes(path),
+                FileReference::Url(_) => None,
+            })
+            .map(|bytes| CoverImage::try_from(bytes.map_err(image::ImageError::IoError)?.as_ref()))
+            .collect()
+    }
+
+    /// The icon of the model, which is either an image in the package or an emoji
+    pub fn icon(&self) -> Option<Result<Icon, IconParsingError>> {
+        let icon = self.rdf.icon.as_ref()?;
+        Some(match self.file_bytes(Path::new(icon.as_str())) {
+            Some(bytes) => bytes
+                .map_err(image::ImageError::IoError)
+                .and_then(|bytes| image::load_from_memory(&bytes))
+                .map_err(|err| IconParsingError::BadImageData(err.to_string()))
+                .and_then(Icon::try_from),
+            None => Icon::try_from(icon.to_string()),
+        })
+    }
+
+    /// Checks the test tensor of every input and output against the tensor it is for
+    pub fn check_test_tensors(&self) -> Vec<(TensorId, TestTensorError)> {
+        let header = |descr: &FileDescr| -> Option<Result<NpyHeader, TestTensorError>> {
+            let FileReference::Path(path) = &descr.source else {
+                return None;
+            };
+            // files on disk are mapped rather than read, since test tensors can be big
+            let header = match self.files.get(Path::new(path))? {
+                PackageFile::Bytes(bytes) => NpyHeader::read(bytes.as_slice()),
+                PackageFile::Path(local_path) => MappedNpy::open(local_path).map(|npy| npy.header().clone()),
+            };
+            Some(header.map_err(Into::into))
+        };
+        let inputs = self.rdf.inputs.iter().filter_map(|input| {
+            let result = header(&input.test_tensor)?.and_then(|header| input.check_array(&header));
+            result.err().map(|err| (input.id.clone(), err))
+        });
+        let outputs = self.rdf.outputs.iter().filter_map(|output| {
+            let result = header(&output.test_tensor)?.and_then(|header| output.check_array(&header));
+            result.err().map(|err| (output.id.clone(), err))
+        });
+        inputs.chain(outputs).collect()
+    }
+
+    /// Like [ModelRdf::validate], also checking the files that came with the model
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = self.rdf.validate();
+        for (tensor_id, err) in self.check_test_tensors() {
+            let input = self.rdf.inputs.iter().position(|t| t.id == tensor_id).map(|idx| ("inputs", idx));
+            let output = || self.rdf.outputs.iter().position(|t| t.id == tensor_id).map(|idx| ("outputs", idx));
+            let path = match input.or_else(output) {
+                Some((field, idx)) => spec_path![field, idx, "test_tensor"],
+                None => vec![],
+            };
+            report.push(Severity::Error, path, err);
+        }
+        for err in self.cover_images().into_iter().filter_map(Result::err) {
+            report.push(Severity::Error, spec_path!["covers"], err);
+        }
+        if let Some(Err(err)) = self.icon() {
+            report.push(Severity::Error, spec_path!["icon"], err);
+        }
+        report
+    }
+}
+
+/// Validates whatever is at `path`. A description on its own is only checked against the spec; packages,
+/// be they zips or directories, also have their files checked.
+pub fn validate_path(path: &Path) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let is_description = matches!(path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml"));
+    if !is_description {
+        return match ModelPackage::open(path) {
+            Ok(package) => package.validate(),
+            Err(err) => {
+                report.push(Severity::Error, vec![], err);
+                report
+            }
+        };
+    }
+    match std::fs::read_to_string(path) {
+        Ok(raw) => validate_yaml(&raw),
+        Err(err) => {
+            report.push(Severity::Error, vec![], err);
+            report
+        }
+    }
+}
+
+/// Validates the text of a description against the spec. Descriptions without a `type` are taken to be models.
+pub fn validate_yaml(raw: &str) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let rdf_type = serde_yaml::from_str::<serde_yaml::Value>(raw)
+        .ok()
+        .and_then(|value| value.get("type")?.as_str().map(str::to_owned));
+    if rdf_type.as_deref().is_some_and(|rdf_type| rdf_type != "model") {
+        match AnyResourceRdf::from_yaml(raw) {
+            Ok(rdf) => return rdf.validate(),
+            Err(ResourceRdfParsingError::BadYaml(err)) => report.push(Severity::Error, err.path, err.message),
+            Err(err) => report.push(Severity::Error, spec_path!["type"], err),
+        }
+        return report;
+    }
+    match ModelRdf::from_yaml(raw) {
+        Ok(model) => model.validate(),
+        Err(err) => {
+            report.push(Severity::Error, err.path, err.message);
+            report
+        }
+    }
+}
+
+#[cfg(test)]
+const SHARK_FILES: [&str; 10] = [
+    "training_config.yaml",
+    "cover.png",
+    "README.md",
+    "test_input_0.npy",
+    "sample_input_0.tif",
+    "test_output_0.npy",
+    "weights.pt",
+    "unet.py",
+    "weights-torchscript.pt",
+    "weights.onnx",
+];
+
+/// The packaged corpus model, with placeholder contents and without the hashes of the real weights
+#[cfg(test)]
+pub(super) fn shark_package() -> ModelPackage {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_affable_shark.yaml");
+    let model = ModelRdf::from_yaml(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let mut package = ModelPackage::new(model);
+    for name in SHARK_FILES {
+        package.add_file(name, PackageFile::Bytes(name.as_bytes().to_vec()));
+    }
+    for entry in [
+        &mut package.rdf.weights.onnx.as_mut().unwrap().entry,
+        &mut package.rdf.weights.pytorch_state_dict.as_mut().unwrap().entry,
+        &mut package.rdf.weights.torchscript.as_mut().unwrap().entry,
+    ] {
+        entry.sha256 = None;
+    }
+    package
+}
+
+#[test]
+fn test_writing_model_package() {
+    use crate::util::progress::Progress;
+
+    let mut package = shark_package();
+    package.rdf.weights.onnx.as_mut().unwrap().entry.sha256 = Some(Sha256::digest("the real weights"));
+    let mut out = std::io::Cursor::new(Vec::new());
+    assert!(matches!(package.write(&mut out), Err(ModelPackagingError::ChangedFile { .. })));
+    package.rdf.weights.onnx.as_mut().unwrap().entry.sha256 = None;
+
+    let mut filled = shark_package();
+    filled.fill_hashes().unwrap();
+    let onnx = &filled.rdf.weights.onnx.as_ref().unwrap().entry;
+    assert_eq!(onnx.sha256, Some(Sha256::digest("weights.onnx")));
+    filled.rdf.weights.onnx.as_mut().unwrap().entry.sha256 = Some(Sha256::digest("the real weights"));
+    assert!(matches!(filled.fill_hashes(), Err(ModelPackagingError::ChangedFile { .. })));
+
+    package.add_file("notes.txt", PackageFile::Bytes(vec![]));
+    let mut out = std::io::Cursor::new(Vec::new());
+    assert!(matches!(package.write(&mut out), Err(ModelPackagingError::UnusedFile { .. })));
+    package.files.remove(Path::new("notes.txt"));
+
+    let total: u64 = SHARK_FILES.iter().map(|name| name.len() as u64).sum();
+    let mut last = None;
+    let mut on_progress = |progress: Progress| {
+        last = Some(progress);
+        match progress.done < total {
+            true => std::ops::ControlFlow::Continue(()),
+            false => std::ops::ControlFlow::Break(()),
+        }
+    };
+    let written = package.write_with_progress(std::io::Cursor::new(Vec::new()), &mut on_progress);
+    assert!(matches!(written, Err(ModelPackagingError::IoError(err)) if crate::util::progress::is_cancellation(&err)));
+    assert_eq!(last, Some(Progress { done: total, total }));
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    let manifest = package.write(&mut out).unwrap();
+    assert_eq!(manifest.entries.len(), SHARK_FILES.len() + 1);
+    assert_eq!(manifest.entries.last().unwrap().path, Path::new("rdf.yaml"));
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(out.into_inner())).unwrap();
+    let mut rdf_yaml = String::new();
+    archive.by_name("rdf.yaml").unwrap().read_to_string(&mut rdf_yaml).unwrap();
+    let written = ModelRdf::from_yaml(&rdf_yaml).unwrap();
+    let onnx = &written.weights.onnx.as_ref().unwrap().entry;
+    assert_eq!(onnx.sha256, Some(Sha256::digest("weights.onnx")));
+    assert_eq!(written.inputs.iter().next().unwrap().test_tensor.sha256, Some(Sha256::digest("test_input_0.npy")));
+    assert_eq!(archive.by_name("unet.py").unwrap().compression(), zip::CompressionMethod::Deflated);
+    assert_eq!(archive.by_name("weights.pt").unwrap().compression(), zip::CompressionMethod::Stored);
+
+    package.set_compression_for("PY", FileCompression::Store);
+    package.set_compression_for("pt", FileCompression::Deflate);
+    let mut out = std::io::Cursor::new(Vec::new());
+    package.write(&mut out).unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(out.into_inner())).unwrap();
+    assert_eq!(archive.by_name("unet.py").unwrap().compression(), zip::CompressionMethod::Stored);
+    assert_eq!(archive.by_name("weights.pt").unwrap().compression(), zip::CompressionMethod::Deflated);
+    let mut unet = String::new();
+    archive.by_name("unet.py").unwrap().read_to_string(&mut unet).unwrap();
+    assert_eq!(unet, "unet.py");
+}
+
+#[test]
+fn test_opening_model_package() {
+    let dir = std::env::temp_dir().join(format!("bioimg_model_package_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut cover = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::new_rgb8(64, 32).write_to(&mut cover, image::ImageOutputFormat::Png).unwrap();
+    let mut package = shark_package();
+    package.add_file("cover.png", PackageFile::Bytes(cover.into_inner()));
+    package.rdf.icon = Some("🦈".try_into().unwrap());
+    let zip_path = dir.join("shark.zip");
+    package.write_to_path(&zip_path).unwrap();
+
+    let opened = ModelPackage::open(&zip_path).unwrap();
+    assert_eq!(opened.rdf, package.rdf);
+    assert_eq!(opened.file_bytes(Path::new("unet.py")).unwrap().unwrap().as_ref(), b"unet.py");
+    assert!(opened.file_bytes(Path::new("elsewhere.py")).is_none());
+    assert!(matches!(opened.cover_images().as_slice(), [Ok(cover)] if cover.width() == 64));
+    assert!(matches!(opened.icon(), Some(Ok(Icon::Text(_)))));
+    // the placeholder test tensors aren't npy files
+    assert_eq!(opened.check_test_tensors().len(), 2);
+    let report = opened.validate();
+    let errors: Vec<_> = report.errors().map(|entry| entry.path_string()).collect();
+    assert_eq!(errors, ["inputs[0].test_tensor", "outputs[0].test_tensor"]);
+
+    // the same files, unpacked into a directory
+    let unpacked = dir.join("unpacked");
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+    archive.extract(&unpacked).unwrap();
+    assert_eq!(ModelPackage::open(&unpacked).unwrap().rdf, package.rdf);
+    std::fs::write(unpacked.join("test_input_0.npy"), "changed").unwrap();
+    assert!(matches!(ModelPackage::open(&unpacked), Err(ModelUnpackingError::ChangedFile { .. })));
+
+    let mut from_description = ModelPackage::from_description_file(&unpacked.join("rdf.yaml")).unwrap();
+    let mut out = std::io::Cursor::new(Vec::new());
+    assert!(matches!(from_description.write(&mut out), Err(ModelPackagingError::ChangedFile { .. })));
+    from_description.clear_hashes();
+    from_description.set_compression_level(Some(9));
+    let manifest = from_description.write(&mut out).unwrap();
+    let test_input = manifest.entries.iter().find(|entry| entry.path == Path::new("test_input_0.npy")).unwrap();
+    assert_eq!(test_input.sha256, Sha256::digest("changed"));
+
+    std::fs::remove_file(unpacked.join("README.md")).unwrap();
+    assert!(matches!(ModelPackage::open(&unpacked), Err(ModelUnpackingError::MissingFile { .. })));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unpacking_model_package() {
+    let dir = std::env::temp_dir().join(format!("bioimg_model_unpacking_test_{}", std::process::id()));
+    let mut package = shark_package();
+    package.add_file("docs/figure.png", PackageFile::Bytes(b"figure".to_vec()));
+    package.add_file("on_disk.txt", PackageFile::Path(PathBuf::from("/somewhere/on_disk.txt")));
+    let local_paths = package.unpack_to(&dir).unwrap();
+    assert_eq!(local_paths.len(), SHARK_FILES.len() + 2);
+    assert_eq!(std::fs::read(&local_paths[Path::new("unet.py")]).unwrap(), b"unet.py");
+    assert_eq!(local_paths[Path::new("docs/figure.png")], dir.join("docs/figure.png"));
+    assert_eq!(local_paths[Path::new("on_disk.txt")], Path::new("/somewhere/on_disk.txt"));
+
+    package.add_file("../escaped.txt", PackageFile::Bytes(vec![]));
+    assert!(matches!(package.unpack_to(&dir), Err(ModelUnpackingError::OutsideOfPackage { .. })));
+    assert!(!dir.join("../escaped.txt").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,201 @@
+//! Looking researchers up in the public ORCID registry, to fill in their details from their ORCID iD
+
+use serde::Deserialize;
+
+use crate::rdf::orcid::Orcid;
+
+/// The public API of the ORCID registry, which needs no credentials to read
+pub const ORCID_PUBLIC_API_URL: &str = "https://pub.orcid.org/v3.0";
+
+#[derive(thiserror::Error, Debug)]
+pub enum OrcidLookupError {
+    #[error("Could not download {url}: {message}")]
+    HttpError { url: String, message: String },
+    #[error("{url} is not an ORCID record: {message}")]
+    BadRecord { url: String, message: String },
+}
+
+#[derive(Deserialize, Default)]
+struct Value {
+    value: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct RecordName {
+    #[serde(default)]
+    given_names: Option<Value>,
+    #[serde(default)]
+    family_name: Option<Value>,
+    #[serde(default)]
+    credit_name: Option<Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct RecordPerson {
+    #[serde(default)]
+    name: Option<RecordName>,
+}
+
+#[derive(Deserialize)]
+struct Organization {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct EmploymentSummary {
+    organization: Organization,
+    #[serde(default)]
+    end_date: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct EmploymentSummaries {
+    employment_summary: EmploymentSummary,
+}
+
+#[derive(Deserialize)]
+struct AffiliationGroup {
+    #[serde(default)]
+    summaries: Vec<EmploymentSummaries>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+struct Employments {
+    #[serde(default)]
+    affiliation_group: Vec<AffiliationGroup>,
+}
+
+#[derive(Deserialize, Default)]
+struct ActivitiesSummary {
+    #[serde(default)]
+    employments: Option<Employments>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Record {
+    #[serde(default)]
+    person: Option<RecordPerson>,
+    #[serde(default)]
+    activities_summary: Option<ActivitiesSummary>,
+}
+
+/// What the registry makes public about a researcher. Researchers choose what is public, so any of it can be missing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrcidPerson {
+    /// The name they want to be credited with, or else their given and family names
+    pub name: Option<String>,
+    /// The organization of their current employment, or of their latest one if none is current
+    pub affiliation: Option<String>,
+}
+
+impl OrcidPerson {
+    /// Reads the json of a record, as served at `{ORCID_PUBLIC_API_URL}/{orcid}/record`
+    pub fn from_record_json(raw: &str) -> Result<Self, serde_json::Error> {
+        let record: Record = serde_json::from_str(raw)?;
+        let name = record.person.and_then(|person| person.name).unwrap_or_default();
+        let full_name = [name.given_names, name.family_name]
+            .into_iter()
+            .flatten()
+            .map(|part| part.value.trim().to_owned())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let name = name
+            .credit_name
+            .map(|credit_name| credit_name.value.trim().to_owned())
+            .filter(|credit_name| !credit_name.is_empty())
+            .or_else(|| Some(full_name).filter(|full_name| !full_name.is_empty()));
+
+        // the registry lists employments latest first
+        let employments: Vec<EmploymentSummary> = record
+            .activities_summary
+            .and_then(|activities| activities.employments)
+            .unwrap_or_default()
+            .affiliation_group
+            .into_iter()
+            .flat_map(|group| group.summaries)
+            .map(|summaries| summaries.employment_summary)
+            .collect();
+        let current = employments.iter().find(|employment| employment.end_date.is_none());
+        let affiliation = current.or(employments.first()).map(|employment| employment.organization.name.clone());
+        Ok(Self { name, affiliation })
+    }
+}
+
+/// Reads public records from an ORCID registry
+#[derive(Clone)]
+pub struct OrcidClient {
+    api_url: String,
+    agent: ureq::Agent,
+}
+
+impl OrcidClient {
+    pub fn new(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            agent: super::network::agent_builder().build(),
+        }
+    }
+
+    /// A client for the public registry at orcid.org
+    pub fn public() -> Self {
+        Self::new(ORCID_PUBLIC_API_URL)
+    }
+
+    pub fn lookup(&self, orcid: &Orcid) -> Result<OrcidPerson, OrcidLookupError> {
+        let url = format!("{}/{}/record", self.api_url.trim_end_matches('/'), Into::<String>::into(orcid.clone()));
+        let raw = self
+            .agent
+            .get(&url)
+            .set("Accept", "application/json")
+            .call()
+            .map_err(|err| OrcidLookupError::HttpError { url: url.clone(), message: err.to_string() })?
+            .into_string()
+            .map_err(|err| OrcidLookupError::HttpError { url: url.clone(), message: err.to_string() })?;
+        OrcidPerson::from_record_json(&raw).map_err(|err| OrcidLookupError::BadRecord { url, message: err.to_string() })
+    }
+}
+
+#[test]
+fn test_reading_orcid_record() {
+    let raw = r#"{
+        "orcid-identifier": {"path": "0000-0002-1825-0097"},
+        "person": {
+            "name": {
+                "given-names": {"value": "Josiah"},
+                "family-name": {"value": "Carberry"},
+                "credit-name": null
+            }
+        },
+        "activities-summary": {
+            "employments": {
+                "affiliation-group": [
+                    {"summaries": [{"employment-summary": {
+                        "organization": {"name": "Wesleyan University"},
+                        "end-date": {"year": {"value": "2020"}}
+                    }}]},
+                    {"summaries": [{"employment-summary": {
+                        "organization": {"name": "Brown University"},
+                        "end-date": null
+                    }}]}
+                ]
+            }
+        }
+    }"#;
+    let person = OrcidPerson::from_record_json(raw).unwrap();
+    assert_eq!(person.name.as_deref(), Some("Josiah Carberry"));
+    assert_eq!(person.affiliation.as_deref(), Some("Brown University"));
+
+    let credited = r#"{"person": {"name": {"given-names": {"value": "J."}, "credit-name": {"value": "J. L. Carberry"}}}}"#;
+    let person = OrcidPerson::from_record_json(credited).unwrap();
+    assert_eq!(person.name.as_deref(), Some("J. L. Carberry"));
+    assert_eq!(person.affiliation, None);
+
+    let private = r#"{"person": {"name": null}, "activities-summary": {"employments": {"affiliation-group": []}}}"#;
+    assert_eq!(OrcidPerson::from_record_json(private).unwrap(), OrcidPerson::default());
+}
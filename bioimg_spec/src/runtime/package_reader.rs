@@ -0,0 +1,256 @@
+use std::{
+    io::{BufReader, Cursor, Read, Seek},
+    path::{Path, PathBuf},
+};
+
+use crate::rdf::{
+    resource_description::{AnyResourceRdf, ResourceRdfParsingError},
+    spec_error, Version,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum PackageReadingError {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("{0}")]
+    ZipError(#[from] zip::result::ZipError),
+    #[error("Package has no rdf.yaml")]
+    MissingRdf,
+    #[error("Bad rdf.yaml: {0}")]
+    BadYaml(#[from] serde_yaml::Error),
+    #[error("Field '{field}' is missing or not a string")]
+    MissingField { field: &'static str },
+}
+
+pub const RDF_FILE_NAMES: [&str; 2] = ["rdf.yaml", "bioimageio.yaml"];
+
+/// Reads the raw description out of a packaged resource, which can be a zip file, a directory or
+/// the yaml file itself
+pub fn read_rdf_text(path: &Path) -> Result<String, PackageReadingError> {
+    if path.is_dir() {
+        let rdf_path = RDF_FILE_NAMES
+            .iter()
+            .map(|name| path.join(name))
+            .find(|p| p.exists())
+            .ok_or(PackageReadingError::MissingRdf)?;
+        return Ok(std::fs::read_to_string(rdf_path)?);
+    }
+    if path.extension().map(|ext| ext == "zip").unwrap_or(false) {
+        return read_zipped_rdf_text(std::fs::File::open(path)?);
+    }
+    Ok(std::fs::read_to_string(path)?)
+}
+
+fn read_zipped_rdf_text(zip: impl Read + Seek) -> Result<String, PackageReadingError> {
+    let mut archive = zip::ZipArchive::new(zip)?;
+    for name in RDF_FILE_NAMES {
+        let mut entry = match archive.by_name(name) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let mut out = String::new();
+        entry.read_to_string(&mut out)?;
+        return Ok(out);
+    }
+    Err(PackageReadingError::MissingRdf)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RdfLoadingError {
+    #[error("{0}")]
+    ReadingError(#[from] PackageReadingError),
+    #[error("Description is neither a zip file nor text")]
+    NotText,
+    #[error("Bad json: {0}")]
+    BadJson(#[from] serde_json::Error),
+    #[error("{0}")]
+    ParsingError(#[from] ResourceRdfParsingError),
+}
+
+/// How a loaded description was stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RdfEncoding {
+    Yaml,
+    Json,
+    /// A package with an `rdf.yaml` in it
+    Zip,
+}
+
+/// Where to load a description from: a yaml or json file, a zip, a directory with an `rdf.yaml`, or the
+/// contents of any of the files
+#[derive(Debug, Clone, Copy)]
+pub enum RdfSource<'a> {
+    Path(&'a Path),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> From<&'a Path> for RdfSource<'a> {
+    fn from(path: &'a Path) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl<'a> From<&'a PathBuf> for RdfSource<'a> {
+    fn from(path: &'a PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl<'a> From<&'a [u8]> for RdfSource<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+/// A description read by [AnyResourceRdf::load]
+#[derive(Debug, PartialEq)]
+pub struct LoadedRdf {
+    pub rdf: AnyResourceRdf,
+    pub encoding: RdfEncoding,
+    /// The format version as it was written, which is older than the one of [Self::rdf] if it had to be upgraded
+    pub format_version: Option<Version>,
+}
+
+impl LoadedRdf {
+    pub fn was_upgraded(&self) -> bool {
+        self.format_version.as_ref().is_some_and(|version| version != self.rdf.format_version())
+    }
+}
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+impl AnyResourceRdf {
+    /// Reads a description of any type and format version, telling zips, json and yaml apart by their contents
+    /// rather than by the file extension. Older model descriptions are upgraded to the current format.
+    pub fn load<'a>(source: impl Into<RdfSource<'a>>) -> Result<LoadedRdf, RdfLoadingError> {
+        let (text, zipped) = match source.into() {
+            RdfSource::Path(path) if path.is_dir() => (read_rdf_text(path)?, false),
+            // packages can hold gigabytes of weights, so only the description is read out of them
+            RdfSource::Path(path) => {
+                let mut file = std::fs::File::open(path).map_err(PackageReadingError::from)?;
+                let mut magic = vec![];
+                (&mut file).take(ZIP_MAGIC.len() as u64).read_to_end(&mut magic).map_err(PackageReadingError::from)?;
+                file.rewind().map_err(PackageReadingError::from)?;
+                if magic == ZIP_MAGIC {
+                    (read_zipped_rdf_text(BufReader::new(file))?, true)
+                } else {
+                    let mut bytes = vec![];
+                    file.read_to_end(&mut bytes).map_err(PackageReadingError::from)?;
+                    (String::from_utf8(bytes).map_err(|_| RdfLoadingError::NotText)?, false)
+                }
+            }
+            RdfSource::Bytes(bytes) if bytes.starts_with(ZIP_MAGIC) => (read_zipped_rdf_text(Cursor::new(bytes))?, true),
+            RdfSource::Bytes(bytes) => (String::from_utf8(bytes.to_vec()).map_err(|_| RdfLoadingError::NotText)?, false),
+        };
+        // editors on windows like to start files with a byte order mark, which neither parser skips
+        let text = text.trim_start_matches('\u{feff}');
+        let is_json = text.trim_start().starts_with('{');
+        let value: serde_yaml::Value = match is_json {
+            true => serde_json::from_str(text)?,
+            false => spec_error::from_yaml_str(text).map_err(ResourceRdfParsingError::from)?,
+        };
+        let encoding = match (zipped, is_json) {
+            (true, _) => RdfEncoding::Zip,
+            (false, true) => RdfEncoding::Json,
+            (false, false) => RdfEncoding::Yaml,
+        };
+        let format_version = value.get("format_version").and_then(|v| Version::try_from(v.as_str()?).ok());
+        Ok(LoadedRdf {
+            rdf: AnyResourceRdf::try_from(value)?,
+            encoding,
+            format_version,
+        })
+    }
+}
+
+/// The handful of fields needed to list a resource without fully parsing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageSummary {
+    pub path: PathBuf,
+    pub rdf_type: String,
+    pub name: String,
+    pub description: String,
+    pub id: Option<String>,
+    pub version: Option<String>,
+}
+
+impl PackageSummary {
+    pub fn read(path: &Path) -> Result<Self, PackageReadingError> {
+        let value: serde_yaml::Value = serde_yaml::from_str(&read_rdf_text(path)?)?;
+        let get_str = |field: &'static str| value.get(field).and_then(|v| v.as_str()).map(String::from);
+        let version = value.get("version").and_then(|v| match v {
+            serde_yaml::Value::String(s) => Some(s.clone()),
+            serde_yaml::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        });
+        Ok(Self {
+            path: path.to_owned(),
+            rdf_type: get_str("type").ok_or(PackageReadingError::MissingField { field: "type" })?,
+            name: get_str("name").ok_or(PackageReadingError::MissingField { field: "name" })?,
+            description: get_str("description").ok_or(PackageReadingError::MissingField { field: "description" })?,
+            id: get_str("id"),
+            version,
+        })
+    }
+}
+
+#[test]
+fn test_reading_package_summary() {
+    use std::io::Write;
+
+    let dir = std::env::temp_dir().join(format!("bioimg_package_reader_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let zip_path = dir.join("model.zip");
+    {
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        writer.start_file("rdf.yaml", zip::write::FileOptions::default()).unwrap();
+        writer
+            .write_all(b"type: model\nname: my model\ndescription: segments things\nid: some-id\nversion: 1.2.3\n")
+            .unwrap();
+        writer.finish().unwrap();
+    }
+    let summary = PackageSummary::read(&zip_path).unwrap();
+    assert_eq!(summary.name, "my model");
+    assert_eq!(summary.id.as_deref(), Some("some-id"));
+    assert_eq!(summary.version.as_deref(), Some("1.2.3"));
+
+    assert!(matches!(PackageSummary::read(&dir), Err(PackageReadingError::MissingRdf)));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_loading_any_encoding() {
+    use std::io::Write;
+
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid");
+    let dataset = AnyResourceRdf::load(&corpus.join("dataset_nucmm.yaml")).unwrap();
+    assert_eq!((dataset.rdf.rdf_type(), dataset.encoding), ("dataset", RdfEncoding::Yaml));
+    assert!(!dataset.was_upgraded());
+
+    let json = serde_json::to_vec(&dataset.rdf).unwrap();
+    let from_json = AnyResourceRdf::load(json.as_slice()).unwrap();
+    assert_eq!((&from_json.rdf, from_json.encoding), (&dataset.rdf, RdfEncoding::Json));
+
+    let mut zipped = Cursor::new(vec![]);
+    {
+        let mut writer = zip::ZipWriter::new(&mut zipped);
+        writer.start_file("rdf.yaml", zip::write::FileOptions::default()).unwrap();
+        writer.write_all(dataset.rdf.to_yaml().unwrap().as_bytes()).unwrap();
+        writer.finish().unwrap();
+    }
+    let from_zip = AnyResourceRdf::load(zipped.get_ref().as_slice()).unwrap();
+    assert_eq!((&from_zip.rdf, from_zip.encoding), (&dataset.rdf, RdfEncoding::Zip));
+    let zip_path = std::env::temp_dir().join(format!("bioimg_load_{}.zip", std::process::id()));
+    std::fs::write(&zip_path, zipped.get_ref()).unwrap();
+    let from_zip_file = AnyResourceRdf::load(&zip_path).unwrap();
+    assert_eq!((&from_zip_file.rdf, from_zip_file.encoding), (&dataset.rdf, RdfEncoding::Zip));
+    std::fs::remove_file(zip_path).unwrap();
+
+    let v04 = AnyResourceRdf::load(&corpus.join("model_v04_unet2d_nuclei_broad.yaml")).unwrap();
+    assert!(matches!(v04.rdf, AnyResourceRdf::Model(_)));
+    assert!(v04.was_upgraded());
+
+    assert!(matches!(AnyResourceRdf::load(&[0xff, 0xfe, 0x00][..]), Err(RdfLoadingError::NotText)));
+    assert!(matches!(AnyResourceRdf::load(&b"{\"type\": "[..]), Err(RdfLoadingError::BadJson(_))));
+}
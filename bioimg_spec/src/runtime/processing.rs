@@ -0,0 +1,250 @@
+//! Applying the pre- and postprocessing steps of a model description to tensors, as a runtime
+//! would before and after running the network.
+
+use std::collections::HashMap;
+
+use ndarray::{ArrayD, Axis, IxDyn};
+
+use crate::rdf::model::{
+    axes::AxisId,
+    data_type::DataType,
+    postprocessing::Postprocessing,
+    preprocessing::{BinarizeKwargs, ClipKwargs, FixedZeroMeanUnitVarianceKwargs, ScaleLinearKwargs},
+    preprocessing::{ScaleMeanVarianceKwargs, ScaleRangeKwargs, ZeroMeanUnitVarianceKwargs},
+    tensor_id::TensorId,
+};
+use crate::util::SingleOrMultiple;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ProcessingError {
+    #[error("Tensor has no axis '{0}'")]
+    UnknownAxis(AxisId),
+    #[error("Axis '{axis}' has size {size}, but {values} values were given for it")]
+    WrongValueCount { axis: AxisId, size: usize, values: usize },
+    #[error("Reference tensor '{0}' is not available")]
+    MissingReference(TensorId),
+    #[error("Axis '{axis}' has size {size}, but size {reference_size} in the reference tensor")]
+    ReferenceSizeMismatch { axis: AxisId, size: usize, reference_size: usize },
+}
+
+/// The values of a tensor, with the ids of its axes in the order of its dimensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedTensor {
+    pub axes: Vec<AxisId>,
+    pub data: ArrayD<f32>,
+}
+
+impl NamedTensor {
+    fn axis_index(&self, id: &AxisId) -> Result<usize, ProcessingError> {
+        self.axes
+            .iter()
+            .position(|axis| axis == id)
+            .ok_or_else(|| ProcessingError::UnknownAxis(id.clone()))
+    }
+
+    /// Applies `f` to every value with the parameters for its entry along `axis`. Parameters with
+    /// a single value apply to every entry.
+    fn map_per_axis<const N: usize>(
+        &mut self,
+        axis: &Option<AxisId>,
+        params: [&SingleOrMultiple<f64>; N],
+        f: impl Fn(f32, [f32; N]) -> f32,
+    ) -> Result<(), ProcessingError> {
+        let Some(axis) = axis.as_ref().filter(|_| params.iter().any(|p| matches!(p, SingleOrMultiple::Multiple(_)))) else {
+            let values = params.map(|p| p.as_slice()[0] as f32);
+            self.data.mapv_inplace(|x| f(x, values));
+            return Ok(());
+        };
+        let axis_idx = self.axis_index(axis)?;
+        let size = self.data.shape()[axis_idx];
+        if let Some(values) = params.iter().map(|p| p.as_slice().len()).find(|len| *len != 1 && *len != size) {
+            return Err(ProcessingError::WrongValueCount { axis: axis.clone(), size, values });
+        }
+        for (entry, mut lane) in self.data.axis_iter_mut(Axis(axis_idx)).enumerate() {
+            let values = params.map(|p| *p.as_slice().get(entry).unwrap_or(&p.as_slice()[0]) as f32);
+            lane.mapv_inplace(|x| f(x, values));
+        }
+        Ok(())
+    }
+}
+
+/// Which statistics group each value of a tensor falls into: values that only differ along the
+/// axes the statistics are computed over share a group
+struct Groups {
+    /// Dimension and size of each axis that is kept apart
+    kept: Vec<(usize, usize)>,
+    count: usize,
+}
+
+impl Groups {
+    /// Groups for computing statistics over `over`, or over the whole tensor if it is `None`.
+    /// `like` lays out the groups the same way as in another tensor, so they can be matched up.
+    fn new(tensor: &NamedTensor, over: &Option<Vec<AxisId>>, like: Option<&NamedTensor>) -> Result<Self, ProcessingError> {
+        let layout = like.unwrap_or(tensor);
+        let kept_ids = layout.axes.iter().filter(|id| over.as_ref().is_some_and(|over| !over.contains(id)));
+        let mut kept = vec![];
+        for id in kept_ids {
+            let dim = tensor.axis_index(id)?;
+            let size = tensor.data.shape()[dim];
+            let reference_size = layout.data.shape()[layout.axis_index(id)?];
+            if size != reference_size {
+                return Err(ProcessingError::ReferenceSizeMismatch { axis: id.clone(), size, reference_size });
+            }
+            kept.push((dim, size));
+        }
+        let count = kept.iter().map(|(_, size)| size).product();
+        Ok(Self { kept, count })
+    }
+
+    fn of(&self, index: &IxDyn) -> usize {
+        self.kept.iter().fold(0, |group, (dim, size)| group * size + index[*dim])
+    }
+
+    fn values(&self, tensor: &NamedTensor) -> Vec<Vec<f32>> {
+        let mut values = vec![vec![]; self.count];
+        for (index, value) in tensor.data.indexed_iter() {
+            values[self.of(&index)].push(*value);
+        }
+        values
+    }
+
+    /// Mean and standard deviation of each group
+    fn mean_std(&self, tensor: &NamedTensor) -> Vec<(f32, f32)> {
+        let stats = |values: Vec<f32>| {
+            let count = values.len().max(1) as f64;
+            let mean = values.iter().map(|v| *v as f64).sum::<f64>() / count;
+            let variance = values.iter().map(|v| (*v as f64 - mean).powi(2)).sum::<f64>() / count;
+            (mean as f32, variance.sqrt() as f32)
+        };
+        self.values(tensor).into_iter().map(stats).collect()
+    }
+
+    /// The `low` and `high` percentiles of each group, interpolated as numpy does by default
+    fn percentiles(&self, tensor: &NamedTensor, low: f64, high: f64) -> Vec<(f32, f32)> {
+        let percentile = |sorted: &[f32], percent: f64| {
+            let Some(last) = sorted.len().checked_sub(1) else {
+                return 0.0;
+            };
+            let position = percent / 100.0 * last as f64;
+            let (below, above) = (position.floor() as usize, position.ceil() as usize);
+            let fraction = (position - below as f64) as f32;
+            sorted[below] + (sorted[above] - sorted[below]) * fraction
+        };
+        let mut groups = self.values(tensor);
+        groups.iter_mut().for_each(|values| values.sort_by(f32::total_cmp));
+        groups.iter().map(|sorted| (percentile(sorted, low), percentile(sorted, high))).collect()
+    }
+
+    fn map(&self, tensor: &mut NamedTensor, f: impl Fn(f32, usize) -> f32) {
+        for (index, value) in tensor.data.indexed_iter_mut() {
+            *value = f(*value, self.of(&index));
+        }
+    }
+}
+
+/// What a value of `data_type` would be once `value` is converted to it, e.g. truncated and clamped for integers
+fn cast(value: f32, data_type: DataType) -> f32 {
+    let (min, max) = match data_type {
+        DataType::Float32 | DataType::Float64 => return value,
+        DataType::Bool => return if value != 0.0 { 1.0 } else { 0.0 },
+        DataType::Uint8 => (u8::MIN as f32, u8::MAX as f32),
+        DataType::Uint16 => (u16::MIN as f32, u16::MAX as f32),
+        DataType::Uint32 => (u32::MIN as f32, u32::MAX as f32),
+        DataType::Uint64 => (u64::MIN as f32, u64::MAX as f32),
+        DataType::Int8 => (i8::MIN as f32, i8::MAX as f32),
+        DataType::Int16 => (i16::MIN as f32, i16::MAX as f32),
+        DataType::Int32 => (i32::MIN as f32, i32::MAX as f32),
+        DataType::Int64 => (i64::MIN as f32, i64::MAX as f32),
+    };
+    value.trunc().clamp(min, max)
+}
+
+/// Applies one processing step to `tensor`. Steps that refer to another tensor look it up in
+/// `references` by its id.
+pub fn apply(
+    step: &Postprocessing,
+    tensor: &mut NamedTensor,
+    references: &HashMap<String, NamedTensor>,
+) -> Result<(), ProcessingError> {
+    let reference = |id: &TensorId| references.get(&id.to_string()).ok_or_else(|| ProcessingError::MissingReference(id.clone()));
+    match step {
+        Postprocessing::Binarize(BinarizeKwargs { threshold, axis }) => {
+            tensor.map_per_axis(axis, [threshold], |x, [threshold]| if x > threshold { 1.0 } else { 0.0 })?
+        }
+        Postprocessing::Clip(ClipKwargs { min, max }) => tensor.data.mapv_inplace(|x| x.clamp(*min as f32, *max as f32)),
+        Postprocessing::EnsureDtype(kwargs) => tensor.data.mapv_inplace(|x| cast(x, kwargs.dtype)),
+        Postprocessing::ScaleLinear(ScaleLinearKwargs { axis, gain, offset }) => {
+            tensor.map_per_axis(axis, [gain, offset], |x, [gain, offset]| x * gain + offset)?
+        }
+        Postprocessing::Sigmoid => tensor.data.mapv_inplace(|x| 1.0 / (1.0 + (-x).exp())),
+        Postprocessing::FixedZeroMeanUnitVariance(FixedZeroMeanUnitVarianceKwargs { axis, mean, std }) => {
+            tensor.map_per_axis(axis, [mean, std], |x, [mean, std]| (x - mean) / std)?
+        }
+        Postprocessing::ZeroMeanUnitVariance(ZeroMeanUnitVarianceKwargs { axes, eps }) => {
+            let groups = Groups::new(tensor, axes, None)?;
+            let stats = groups.mean_std(tensor);
+            groups.map(tensor, |x, group| (x - stats[group].0) / (stats[group].1 + *eps as f32));
+        }
+        Postprocessing::ScaleRange(kwargs) => {
+            let ScaleRangeKwargs { axes, eps, max_percentile, min_percentile, reference_tensor } = kwargs;
+            let groups = Groups::new(tensor, axes, None)?;
+            let ranges = match reference_tensor {
+                Some(id) => {
+                    let source = reference(id)?;
+                    Groups::new(source, axes, Some(tensor))?.percentiles(source, *min_percentile, *max_percentile)
+                }
+                None => groups.percentiles(tensor, *min_percentile, *max_percentile),
+            };
+            groups.map(tensor, |x, group| {
+                let (low, high) = ranges[group];
+                (x - low) / (high - low + *eps as f32)
+            });
+        }
+        Postprocessing::ScaleMeanVariance(ScaleMeanVarianceKwargs { reference_tensor, axes, eps }) => {
+            let source = reference(reference_tensor)?;
+            let groups = Groups::new(tensor, axes, None)?;
+            let stats = groups.mean_std(tensor);
+            let source_stats = Groups::new(source, axes, Some(tensor))?.mean_std(source);
+            let eps = *eps as f32;
+            groups.map(tensor, |x, group| {
+                let ((mean, std), (source_mean, source_std)) = (stats[group], source_stats[group]);
+                (x - mean) / (std + eps) * (source_std + eps) + source_mean
+            });
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_processing_steps() {
+    let axis = |id: &str| AxisId::try_from(id.to_owned()).unwrap();
+    let tensor = NamedTensor {
+        axes: vec![axis("channel"), axis("x")],
+        data: ndarray::arr2(&[[0.0, 2.0, 4.0], [10.0, 10.0, 10.0]]).into_dyn(),
+    };
+    let processed = |yaml: &str| {
+        let step: Postprocessing = serde_yaml::from_str(yaml).unwrap();
+        let mut tensor = tensor.clone();
+        apply(&step, &mut tensor, &HashMap::new()).map(|_| tensor.data.into_raw_vec())
+    };
+
+    assert_eq!(processed("{id: binarize, kwargs: {threshold: 3}}").unwrap(), [0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(
+        processed("{id: scale_linear, kwargs: {axis: channel, gain: [1, 2], offset: 1}}").unwrap(),
+        [1.0, 3.0, 5.0, 21.0, 21.0, 21.0]
+    );
+    assert!(matches!(
+        processed("{id: scale_linear, kwargs: {axis: x, gain: [1, 2], offset: 0}}"),
+        Err(ProcessingError::WrongValueCount { size: 3, values: 2, .. })
+    ));
+    let normalized = processed("{id: zero_mean_unit_variance, kwargs: {axes: [x], eps: 1e-6}}").unwrap();
+    let expected = [-1.2247, 0.0, 1.2247, 0.0, 0.0, 0.0];
+    assert!(normalized.iter().zip(expected).all(|(found, expected)| (found - expected).abs() < 1e-3));
+    let scaled = processed("{id: scale_range, kwargs: {axes: [x], min_percentile: 0, max_percentile: 50, eps: 1e-6}}").unwrap();
+    assert!((scaled[2] - 2.0).abs() < 1e-3);
+    assert_eq!(processed("{id: ensure_dtype, kwargs: {dtype: uint8}}").unwrap(), [0.0, 2.0, 4.0, 10.0, 10.0, 10.0]);
+    assert!(matches!(
+        processed("{id: scale_mean_variance, kwargs: {reference_tensor: input0}}"),
+        Err(ProcessingError::MissingReference(_))
+    ));
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use crate::rdf::{file_reference::FileReference, sha256::Sha256};
+
+use super::package_reader::{read_rdf_text, PackageReadingError};
+
+/// Where an imported resource came from, so that re-exports can point back to it via `rdf_source`
+/// and changes can be compared against what was originally loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RdfProvenance {
+    pub rdf_source: FileReference,
+    pub sha256: Sha256,
+    pub original_text: String,
+}
+
+impl RdfProvenance {
+    pub fn new(rdf_source: FileReference, original_text: String) -> Self {
+        Self {
+            rdf_source,
+            sha256: Sha256::digest(&original_text),
+            original_text,
+        }
+    }
+
+    /// Reads the rdf of a local package (zip, directory or yaml file)
+    pub fn import(path: &Path) -> Result<Self, PackageReadingError> {
+        let text = read_rdf_text(path)?;
+        Ok(Self::new(FileReference::Path(path.to_owned()), text))
+    }
+
+    /// What exports should point back to with `rdf_source`: only resources downloaded from a url have it, since
+    /// the path of a local file means nothing anywhere else
+    pub fn published_source(&self) -> Option<&FileReference> {
+        matches!(self.rdf_source, FileReference::Url(_)).then_some(&self.rdf_source)
+    }
+
+    pub fn original_yaml(&self) -> Result<serde_yaml::Value, serde_yaml::Error> {
+        serde_yaml::from_str(&self.original_text)
+    }
+
+    pub fn is_modified(&self, current_text: &str) -> bool {
+        Sha256::digest(current_text) != self.sha256
+    }
+}
+
+#[test]
+fn test_rdf_provenance() {
+    let dir = std::env::temp_dir().join(format!("bioimg_provenance_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let text = "type: model\nname: my model\n";
+    std::fs::write(dir.join("rdf.yaml"), text).unwrap();
+
+    let provenance = RdfProvenance::import(&dir).unwrap();
+    assert_eq!(provenance.rdf_source, FileReference::Path(dir.clone()));
+    assert_eq!(provenance.published_source(), None);
+    assert_eq!(provenance.sha256, Sha256::digest(text));
+    assert!(!provenance.is_modified(text));
+    assert!(provenance.is_modified("type: model\nname: my other model\n"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let url = FileReference::Url("https://example.com/rdf.yaml".parse().unwrap());
+    assert_eq!(RdfProvenance::new(url.clone(), text.into()).published_source(), Some(&url));
+}
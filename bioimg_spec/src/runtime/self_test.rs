@@ -0,0 +1,237 @@
+//! Checking that a packaged model still produces its test outputs: the test inputs are
+//! preprocessed, run through the network and postprocessed, then compared to the test outputs.
+//!
+//! Running the network is left to an [InferenceBackend], so that this doesn't depend on any
+//! particular inference library.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use ndarray::ArrayD;
+
+use super::{
+    processing::{self, NamedTensor, ProcessingError},
+    tensor::{Tensor, TensorAxis},
+    ModelPackage,
+};
+use crate::rdf::{
+    model::{postprocessing::Postprocessing, tensor_id::TensorId},
+    FileDescr, FileReference,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SelfTestError {
+    #[error("Test tensor of '{0}' is not part of the package")]
+    MissingTestTensor(TensorId),
+    #[error("Could not read test tensor of '{tensor_id}': {message}")]
+    BadTestTensor { tensor_id: TensorId, message: String },
+    #[error("Could not process '{tensor_id}': {source}")]
+    Processing { tensor_id: TensorId, source: ProcessingError },
+    #[error("Running the model failed: {0}")]
+    Backend(String),
+    #[error("The model has {expected} outputs, but running it gave {found}")]
+    OutputCount { expected: usize, found: usize },
+}
+
+/// Runs the network of a model on one sample
+pub trait InferenceBackend {
+    /// `inputs` are already preprocessed, in the order the model lists them. The outputs are
+    /// expected in the order the model lists them too, before postprocessing.
+    fn run(&mut self, inputs: Vec<ArrayD<f32>>) -> Result<Vec<ArrayD<f32>>, String>;
+}
+
+/// How far an output may be from the test output, as in numpy's `allclose`:
+/// `|found - expected| <= absolute + relative * |expected|`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub relative: f32,
+    pub absolute: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            relative: 1e-3,
+            absolute: 1e-4,
+        }
+    }
+}
+
+/// How one output compares to its test output
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputComparison {
+    pub tensor_id: TensorId,
+    pub expected_shape: Vec<usize>,
+    pub found_shape: Vec<usize>,
+    /// Values that are not within the tolerance, or all of them if the shapes differ
+    pub mismatched: usize,
+    pub max_difference: f32,
+    /// What running the model gave, after postprocessing
+    pub found: ArrayD<f32>,
+    pub expected: ArrayD<f32>,
+}
+
+impl OutputComparison {
+    pub fn passed(&self) -> bool {
+        self.expected_shape == self.found_shape && self.mismatched == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestReport {
+    pub outputs: Vec<OutputComparison>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.outputs.iter().all(OutputComparison::passed)
+    }
+}
+
+fn compare(tensor_id: TensorId, found: ArrayD<f32>, expected: ArrayD<f32>, tolerance: Tolerance) -> OutputComparison {
+    let (expected_shape, found_shape) = (expected.shape().to_vec(), found.shape().to_vec());
+    if expected_shape != found_shape {
+        return OutputComparison {
+            tensor_id,
+            mismatched: expected.len(),
+            max_difference: f32::INFINITY,
+            expected_shape,
+            found_shape,
+            found,
+            expected,
+        };
+    }
+    let mut mismatched = 0;
+    let mut max_difference = 0f32;
+    for (found, expected) in found.iter().zip(expected.iter()) {
+        let difference = (found - expected).abs();
+        // NaN differences count as mismatches, unless both values are NaN
+        let allowed = tolerance.absolute + tolerance.relative * expected.abs();
+        if !(difference <= allowed || found.is_nan() && expected.is_nan()) {
+            mismatched += 1;
+        }
+        max_difference = max_difference.max(difference);
+    }
+    OutputComparison {
+        tensor_id,
+        expected_shape,
+        found_shape,
+        mismatched,
+        max_difference,
+        found,
+        expected,
+    }
+}
+
+impl ModelPackage {
+    fn read_test_tensor<A: TensorAxis>(
+        &self,
+        tensor_id: &TensorId,
+        descr: &FileDescr,
+        axes: Vec<A>,
+    ) -> Result<Tensor<A>, SelfTestError> {
+        let bad_tensor = |message: String| SelfTestError::BadTestTensor { tensor_id: tensor_id.clone(), message };
+        let FileReference::Path(path) = &descr.source else {
+            return Err(SelfTestError::MissingTestTensor(tensor_id.clone()));
+        };
+        let bytes = self
+            .file_bytes(Path::new(path))
+            .ok_or_else(|| SelfTestError::MissingTestTensor(tensor_id.clone()))?
+            .map_err(|err| bad_tensor(err.to_string()))?;
+        Tensor::from_npy(axes, &bytes).map_err(|err| bad_tensor(err.to_string()))
+    }
+
+    /// Runs the test inputs through `backend` with the pre- and postprocessing of the model, and
+    /// compares the results to the test outputs
+    pub fn self_test(&self, backend: &mut dyn InferenceBackend, tolerance: Tolerance) -> Result<SelfTestReport, SelfTestError> {
+        let processing_error = |tensor_id: &TensorId| {
+            let tensor_id = tensor_id.clone();
+            move |source| SelfTestError::Processing { tensor_id, source }
+        };
+        // processing that refers to another tensor uses it as it was read
+        let mut raw_inputs = HashMap::new();
+        for input in self.rdf.inputs.iter() {
+            let tensor = self.read_test_tensor(&input.id, &input.test_tensor, input.axes.iter().cloned().collect())?;
+            raw_inputs.insert(input.id.to_string(), NamedTensor::from(tensor));
+        }
+
+        let mut preprocessed = vec![];
+        for input in self.rdf.inputs.iter() {
+            let mut tensor = raw_inputs[&input.id.to_string()].clone();
+            for step in &input.preprocessing {
+                processing::apply(&Postprocessing::from(step.clone()), &mut tensor, &raw_inputs)
+                    .map_err(processing_error(&input.id))?;
+            }
+            preprocessed.push(tensor.data);
+        }
+
+        let outputs = backend.run(preprocessed).map_err(SelfTestError::Backend)?;
+        let expected = self.rdf.outputs.iter().count();
+        if outputs.len() != expected {
+            return Err(SelfTestError::OutputCount { expected, found: outputs.len() });
+        }
+
+        let mut comparisons = vec![];
+        for (output, data) in self.rdf.outputs.iter().zip(outputs) {
+            let mut tensor = NamedTensor {
+                axes: output.axes.iter().map(|axis| axis.id().clone()).collect(),
+                data,
+            };
+            for step in &output.postprocessing {
+                processing::apply(step, &mut tensor, &raw_inputs).map_err(processing_error(&output.id))?;
+            }
+            let expected = self
+                .read_test_tensor(&output.id, &output.test_tensor, output.axes.iter().cloned().collect())?
+                .into_data();
+            comparisons.push(compare(output.id.clone(), tensor.data, expected, tolerance));
+        }
+        Ok(SelfTestReport { outputs: comparisons })
+    }
+}
+
+#[test]
+fn test_model_self_test() {
+    use super::PackageFile;
+    use ndarray_npy::WriteNpyExt;
+
+    /// Stands in for the shark model: the foreground is the normalized input, the boundaries its negation
+    struct Mirror;
+    impl InferenceBackend for Mirror {
+        fn run(&mut self, inputs: Vec<ArrayD<f32>>) -> Result<Vec<ArrayD<f32>>, String> {
+            let input = inputs[0].view().into_dimensionality::<ndarray::Ix4>().unwrap();
+            let output = ndarray::concatenate![ndarray::Axis(1), input, input.mapv(|v| -v)];
+            Ok(vec![output.into_dyn()])
+        }
+    }
+    let npy = |array: ArrayD<f32>| {
+        let mut bytes = vec![];
+        array.write_npy(&mut bytes).unwrap();
+        PackageFile::Bytes(bytes)
+    };
+
+    let input = ArrayD::from_shape_fn(vec![1, 1, 64, 64], |idx| (idx[2] * 64 + idx[3]) as f32);
+    let normalized = {
+        let mean = input.mean().unwrap();
+        let std = input.std(0.0);
+        input.mapv(|v| (v - mean) / (std + 1e-6))
+    };
+    let mut expected = ndarray::concatenate![ndarray::Axis(1), normalized.view(), normalized.mapv(|v| -v).view()];
+
+    let mut package = super::model_package::shark_package();
+    package.add_file("test_input_0.npy", npy(input));
+    package.add_file("test_output_0.npy", npy(expected.clone()));
+    let report = package.self_test(&mut Mirror, Tolerance::default()).unwrap();
+    assert!(report.passed(), "{report:?}");
+
+    expected[[0, 1, 3, 3]] += 1.0;
+    package.add_file("test_output_0.npy", npy(expected));
+    let report = package.self_test(&mut Mirror, Tolerance::default()).unwrap();
+    assert_eq!(report.outputs[0].mismatched, 1);
+    assert!((report.outputs[0].max_difference - 1.0).abs() < 1e-3);
+
+    package.add_file("test_input_0.npy", PackageFile::Bytes(b"not npy".to_vec()));
+    assert!(matches!(
+        package.self_test(&mut Mirror, Tolerance::default()),
+        Err(SelfTestError::BadTestTensor { .. })
+    ));
+}
@@ -0,0 +1,278 @@
+//! Tensors the way a runtime handles them: the values together with the axes the description declares for them,
+//! so that axes can be looked up by id rather than by position.
+
+use std::borrow::Borrow;
+
+use image::DynamicImage;
+use ndarray::{ArrayD, IxDyn};
+use ndarray_npy::ReadNpyExt;
+
+use super::{
+    image_tensor::{array_to_image, image_to_array, ImageAxis, ImageConversionError},
+    processing::NamedTensor,
+    test_tensor::NpyHeader,
+};
+use crate::rdf::{
+    model::{
+        axes::{AxisId, InputAxis, OutputAxis},
+        data_type::DataType,
+    },
+    Identifier,
+};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum TensorError {
+    #[error("Tensor has {axes} axes, but the data has {dimensions} dimensions")]
+    WrongDimensions { axes: usize, dimensions: usize },
+    #[error("Tensor has no axis '{0}'")]
+    UnknownAxis(AxisId),
+    #[error("Expected an order of all {expected} axes, got {found}")]
+    BadAxisOrder { expected: usize, found: usize },
+    #[error("Could not read npy data: {0}")]
+    BadNpy(String),
+    #[error(transparent)]
+    Image(#[from] ImageConversionError),
+}
+
+/// What a [Tensor] needs to know about its axes, which input and output tensors describe a little differently
+pub trait TensorAxis: Clone {
+    fn axis_id(&self) -> &AxisId;
+    /// The names of the channels, if this is the channel axis
+    fn channel_names(&self) -> Option<&[Identifier<String>]>;
+    fn is_batch(&self) -> bool;
+    fn is_space(&self) -> bool;
+}
+
+impl TensorAxis for InputAxis {
+    fn axis_id(&self) -> &AxisId {
+        self.id()
+    }
+    fn channel_names(&self) -> Option<&[Identifier<String>]> {
+        match self {
+            Self::Channel(axis) => Some(&axis.channel_names),
+            _ => None,
+        }
+    }
+    fn is_batch(&self) -> bool {
+        matches!(self, Self::Batch(_))
+    }
+    fn is_space(&self) -> bool {
+        matches!(self, Self::Space(_))
+    }
+}
+
+impl TensorAxis for OutputAxis {
+    fn axis_id(&self) -> &AxisId {
+        self.id()
+    }
+    fn channel_names(&self) -> Option<&[Identifier<String>]> {
+        match self {
+            Self::Channel(axis) => Some(&axis.channel_names),
+            _ => None,
+        }
+    }
+    fn is_batch(&self) -> bool {
+        matches!(self, Self::Batch(_))
+    }
+    fn is_space(&self) -> bool {
+        matches!(self, Self::Space(_))
+    }
+}
+
+/// The values of a tensor, held as f32 whatever their [DataType], with the description of each of its dimensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor<A> {
+    axes: Vec<A>,
+    data: ArrayD<f32>,
+    /// The type the values came as or were cast to
+    data_type: DataType,
+}
+
+impl<A: TensorAxis> Tensor<A> {
+    pub fn new(axes: Vec<A>, data: ArrayD<f32>, data_type: DataType) -> Result<Self, TensorError> {
+        if axes.len() != data.ndim() {
+            return Err(TensorError::WrongDimensions { axes: axes.len(), dimensions: data.ndim() });
+        }
+        Ok(Self { axes, data, data_type })
+    }
+
+    /// Reads npy data of any of the types a tensor can have
+    pub fn from_npy(axes: Vec<A>, bytes: &[u8]) -> Result<Self, TensorError> {
+        let header = NpyHeader::read(bytes).map_err(|err| TensorError::BadNpy(err.to_string()))?;
+        let data = read_f32_npy(bytes, header.data_type).map_err(TensorError::BadNpy)?;
+        Self::new(axes, data, header.data_type)
+    }
+
+    /// Fills the channel, y and x axes from the pixels of `image`, with the values it stores rather than ones scaled
+    /// to 0..1. 16 bit images become uint16 tensors, float images float32 ones and the rest uint8 ones. Channels named
+    /// after colors, like `b`, `g` and `r`, get those; any other axis gets a size of 1.
+    pub fn from_image(axes: Vec<A>, image: &DynamicImage) -> Result<Self, TensorError> {
+        let mapping: Vec<ImageAxis> = axes.iter().map(image_axis).collect();
+        let data = image_to_array(image, &mapping, &channel_names(&axes))?;
+        let color = image.color();
+        let data_type = match color.bytes_per_pixel() / color.channel_count() {
+            2 => DataType::Uint16,
+            4 => DataType::Float32,
+            _ => DataType::Uint8,
+        };
+        Self::new(axes, data, data_type)
+    }
+
+    /// The image the channel, y and x axes hold, which needs every other axis to have a size of 1. The values are kept
+    /// as they are, so they may need to be scaled before the image can be saved or shown.
+    pub fn to_image(&self) -> Result<DynamicImage, TensorError> {
+        let mapping: Vec<ImageAxis> = self.axes.iter().map(image_axis).collect();
+        Ok(array_to_image(&self.data, &mapping, &channel_names(&self.axes))?)
+    }
+
+    pub fn axes(&self) -> &[A] {
+        &self.axes
+    }
+
+    pub fn data(&self) -> &ArrayD<f32> {
+        &self.data
+    }
+
+    pub fn into_data(self) -> ArrayD<f32> {
+        self.data
+    }
+
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    /// The dimension of the axis with id `id`
+    pub fn axis_index(&self, id: &AxisId) -> Option<usize> {
+        self.axes.iter().position(|axis| axis.axis_id() == id)
+    }
+
+    pub fn axis(&self, id: &AxisId) -> Option<&A> {
+        self.axis_index(id).map(|index| &self.axes[index])
+    }
+
+    /// The size of the data along the axis with id `id`
+    pub fn size(&self, id: &AxisId) -> Option<usize> {
+        self.axis_index(id).map(|index| self.data.shape()[index])
+    }
+
+    /// The tensor with its axes and data in the order of `order`, which has to name every axis once
+    pub fn permuted(&self, order: &[AxisId]) -> Result<Self, TensorError> {
+        if order.len() != self.axes.len() {
+            return Err(TensorError::BadAxisOrder { expected: self.axes.len(), found: order.len() });
+        }
+        let indices = order
+            .iter()
+            .map(|id| self.axis_index(id).ok_or_else(|| TensorError::UnknownAxis(id.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        if (0..indices.len()).any(|index| !indices.contains(&index)) {
+            return Err(TensorError::BadAxisOrder { expected: self.axes.len(), found: order.len() });
+        }
+        let data = self.data.view().permuted_axes(IxDyn(&indices)).as_standard_layout().into_owned();
+        Ok(Self {
+            axes: indices.iter().map(|&index| self.axes[index].clone()).collect(),
+            data,
+            data_type: self.data_type,
+        })
+    }
+
+    /// The values as `data_type` would store them: clamped to its range, rounded for integer types, and 0 or 1 for
+    /// bools
+    pub fn cast(&self, data_type: DataType) -> Self {
+        let (min, max) = data_type.value_range();
+        let data = match data_type {
+            DataType::Float32 | DataType::Float64 => self.data.clone(),
+            DataType::Bool => self.data.mapv(|value| if value != 0.0 { 1.0 } else { 0.0 }),
+            _ => self.data.mapv(|value| (value as f64).round().clamp(min, max) as f32),
+        };
+        Self {
+            axes: self.axes.clone(),
+            data,
+            data_type,
+        }
+    }
+}
+
+impl<A: TensorAxis> From<Tensor<A>> for NamedTensor {
+    fn from(tensor: Tensor<A>) -> Self {
+        Self {
+            axes: tensor.axes.iter().map(|axis| axis.axis_id().clone()).collect(),
+            data: tensor.data,
+        }
+    }
+}
+
+/// The channel axis, the space axes `y` and `x`, and everything else as a singleton
+fn image_axis(axis: &impl TensorAxis) -> ImageAxis {
+    match &**axis.axis_id() {
+        _ if axis.channel_names().is_some() => ImageAxis::Channel,
+        "y" if axis.is_space() => ImageAxis::Y,
+        "x" if axis.is_space() => ImageAxis::X,
+        _ => ImageAxis::Singleton,
+    }
+}
+
+fn channel_names<A: TensorAxis>(axes: &[A]) -> Vec<&str> {
+    let names = axes.iter().find_map(TensorAxis::channel_names).unwrap_or_default();
+    names.iter().map(|name| name.borrow()).collect()
+}
+
+/// Reads npy data holding values of `data_type` as f32
+fn read_f32_npy(bytes: &[u8], data_type: DataType) -> Result<ArrayD<f32>, String> {
+    macro_rules! read_as {
+        ($element:ty) => {
+            ArrayD::<$element>::read_npy(bytes).map(|array| array.mapv(|v| v as f32))
+        };
+    }
+    let array = match data_type {
+        DataType::Bool => ArrayD::<bool>::read_npy(bytes).map(|array| array.mapv(|v| if v { 1.0 } else { 0.0 })),
+        DataType::Float32 => ArrayD::<f32>::read_npy(bytes),
+        DataType::Float64 => read_as!(f64),
+        DataType::Uint8 => read_as!(u8),
+        DataType::Uint16 => read_as!(u16),
+        DataType::Uint32 => read_as!(u32),
+        DataType::Uint64 => read_as!(u64),
+        DataType::Int8 => read_as!(i8),
+        DataType::Int16 => read_as!(i16),
+        DataType::Int32 => read_as!(i32),
+        DataType::Int64 => read_as!(i64),
+    };
+    array.map_err(|err| err.to_string())
+}
+
+#[test]
+fn test_tensor_axes() {
+    use ndarray_npy::WriteNpyExt;
+
+    let axes: Vec<InputAxis> = serde_json::from_value(serde_json::json!([
+        {"type": "batch"},
+        {"type": "channel", "channel_names": ["r", "g", "b"]},
+        {"type": "space", "id": "y", "size": 2},
+        {"type": "space", "id": "x", "size": 3},
+    ]))
+    .unwrap();
+    let id = |id: &str| AxisId::try_from(id.to_owned()).unwrap();
+
+    let mut image = image::RgbImage::new(3, 2);
+    image.put_pixel(2, 1, image::Rgb([10, 20, 30]));
+    let tensor = Tensor::from_image(axes.clone(), &DynamicImage::ImageRgb8(image)).unwrap();
+    assert_eq!((tensor.data().shape(), tensor.data_type()), (&[1, 3, 2, 3][..], DataType::Uint8));
+    assert_eq!(tensor.data()[[0, 2, 1, 2]], 30.0);
+    assert_eq!(tensor.size(&id("x")), Some(3));
+    assert_eq!(tensor.to_image().unwrap().to_rgb32f().get_pixel(2, 1).0, [10.0, 20.0, 30.0]);
+
+    let order = [id("x"), id("y"), id("channel"), id("batch")];
+    let permuted = tensor.permuted(&order).unwrap();
+    assert_eq!(permuted.data().shape(), [3, 2, 3, 1]);
+    assert_eq!(permuted.data()[[2, 1, 2, 0]], 30.0);
+    assert_eq!(permuted.axes()[0].id(), &id("x"));
+    assert!(matches!(tensor.permuted(&order[..3]), Err(TensorError::BadAxisOrder { .. })));
+    assert!(matches!(tensor.permuted(&[id("x"), id("x"), id("y"), id("batch")]), Err(TensorError::BadAxisOrder { .. })));
+
+    let mut npy = vec![];
+    ndarray::arr1(&[-3i16, 7, 300]).into_dyn().write_npy(&mut npy).unwrap();
+    let line = Tensor::from_npy(axes[3..].to_vec(), &npy).unwrap();
+    assert_eq!(line.data_type(), DataType::Int16);
+    assert_eq!(line.cast(DataType::Uint8).data().as_slice().unwrap(), [0.0, 7.0, 255.0]);
+    assert_eq!(line.cast(DataType::Bool).data().as_slice().unwrap(), [1.0, 1.0, 1.0]);
+    assert!(matches!(Tensor::from_npy(axes, &npy), Err(TensorError::WrongDimensions { axes: 4, dimensions: 1 })));
+}
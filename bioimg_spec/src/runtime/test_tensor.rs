@@ -0,0 +1,413 @@
+//! Checks that the `.npy` files given as `test_tensor` or `sample_tensor` fit the tensor they are for.
+//! Only the header of the file is read, so this is cheap even for large tensors.
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::rdf::model::{
+    axes::{AxisId, InputAxis, OutputAxis},
+    axis_size::AnyAxisSize,
+    data_type::DataType,
+    input_tensor::InputTensorDescr2,
+    output_tensor::OutputTensorDescr,
+    tensor_data_descr::TensorData,
+};
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+#[derive(thiserror::Error, Debug)]
+pub enum NpyHeaderError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not an npy file")]
+    NotNpy,
+    #[error("Bad npy header: {0}")]
+    BadHeader(String),
+    #[error("Unsupported npy data type '{0}'")]
+    UnsupportedDataType(String),
+}
+
+/// What an `.npy` file says about the array it holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpyHeader {
+    pub data_type: DataType,
+    pub shape: Vec<usize>,
+}
+
+impl NpyHeader {
+    pub fn open(path: &Path) -> Result<Self, NpyHeaderError> {
+        Self::read(std::fs::File::open(path)?)
+    }
+
+    /// Reads the header at the start of `reader`, leaving it at the start of the data
+    pub fn read(reader: impl Read) -> Result<Self, NpyHeaderError> {
+        Self::read_with_layout(reader).map(|(header, _)| header)
+    }
+
+    /// Like [Self::read], also returning how the data after the header is laid out
+    pub(crate) fn read_with_layout(mut reader: impl Read) -> Result<(Self, NpyLayout), NpyHeaderError> {
+        let mut preamble = [0u8; 8];
+        reader.read_exact(&mut preamble).map_err(|_| NpyHeaderError::NotNpy)?;
+        if &preamble[..6] != NPY_MAGIC {
+            return Err(NpyHeaderError::NotNpy);
+        }
+        let (header_len, len_bytes) = match preamble[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                (u16::from_le_bytes(len) as usize, 2)
+            }
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                (u32::from_le_bytes(len) as usize, 4)
+            }
+            version => return Err(NpyHeaderError::BadHeader(format!("unknown version {version}"))),
+        };
+        let mut header = vec![0u8; header_len];
+        reader.read_exact(&mut header)?;
+        let header = String::from_utf8_lossy(&header);
+        let (parsed, mut layout) = Self::parse_header(&header)?;
+        layout.data_offset = preamble.len() + len_bytes + header_len;
+        Ok((parsed, layout))
+    }
+
+    /// Parses the python dict of the header, e.g. `{'descr': '<f4', 'fortran_order': False, 'shape': (1, 64), }`
+    fn parse_header(header: &str) -> Result<(Self, NpyLayout), NpyHeaderError> {
+        let bad_header = |what: &str| NpyHeaderError::BadHeader(format!("{what} in {}", header.trim()));
+        let value_of = |key: &str| {
+            let key_start = header.find(&format!("'{key}'")).ok_or_else(|| bad_header(&format!("no '{key}'")))?;
+            let after_key = &header[key_start + key.len() + 2..];
+            after_key.trim_start().strip_prefix(':').map(str::trim_start).ok_or_else(|| bad_header("no ':'"))
+        };
+
+        let descr = value_of("descr")?;
+        let descr = descr
+            .strip_prefix('\'')
+            .and_then(|descr| descr.split('\'').next())
+            .ok_or_else(|| bad_header("descr is not a string"))?;
+        // the first character is the byte order, which doesn't change what the values are
+        let data_type = match descr.get(1..) {
+            Some("b1") => DataType::Bool,
+            Some("u1") => DataType::Uint8,
+            Some("u2") => DataType::Uint16,
+            Some("u4") => DataType::Uint32,
+            Some("u8") => DataType::Uint64,
+            Some("i1") => DataType::Int8,
+            Some("i2") => DataType::Int16,
+            Some("i4") => DataType::Int32,
+            Some("i8") => DataType::Int64,
+            Some("f4") => DataType::Float32,
+            Some("f8") => DataType::Float64,
+            _ => return Err(NpyHeaderError::UnsupportedDataType(descr.to_owned())),
+        };
+
+        let shape = value_of("shape")?;
+        let shape = shape
+            .strip_prefix('(')
+            .and_then(|shape| shape.split(')').next())
+            .ok_or_else(|| bad_header("shape is not a tuple"))?;
+        let shape = shape
+            .split(',')
+            .map(str::trim)
+            .filter(|size| !size.is_empty())
+            .map(|size| size.parse::<usize>().map_err(|_| bad_header("shape is not a tuple of sizes")))
+            .collect::<Result<_, _>>()?;
+
+        let fortran_order = value_of("fortran_order").is_ok_and(|value| value.starts_with("True"));
+        let layout = NpyLayout {
+            data_offset: 0,
+            fortran_order,
+            big_endian: descr.starts_with('>'),
+        };
+        Ok((Self { data_type, shape }, layout))
+    }
+
+    /// How many bytes each value takes
+    pub fn value_size(&self) -> usize {
+        match self.data_type {
+            DataType::Bool | DataType::Uint8 | DataType::Int8 => 1,
+            DataType::Uint16 | DataType::Int16 => 2,
+            DataType::Uint32 | DataType::Int32 | DataType::Float32 => 4,
+            DataType::Uint64 | DataType::Int64 | DataType::Float64 => 8,
+        }
+    }
+}
+
+/// Where the values of an `.npy` file start and the order they come in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NpyLayout {
+    pub data_offset: usize,
+    /// Whether the first axis changes fastest, rather than the last one
+    pub fortran_order: bool,
+    pub big_endian: bool,
+}
+
+/// Axes with up to this many entries are taken for the channels when guessing axes from a shape
+const MAX_GUESSED_CHANNELS: usize = 8;
+
+/// What an axis of an array is taken for by [NpyHeader::guess_axes]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AxisGuess {
+    Batch,
+    Channel,
+    Time,
+    Index,
+    Space(&'static str),
+}
+
+/// Guesses the axes of an array of `shape` in the usual C ordered layouts: a leading batch of 1 when there are
+/// four axes or more, a small axis before or after the rest for the channels, and up to three space axes last,
+/// with time before them if there are more axes left
+fn guess_axis_kinds(shape: &[usize]) -> Vec<AxisGuess> {
+    let mut rest = shape;
+    let mut leading = vec![];
+    let mut trailing = vec![];
+    if rest.len() >= 4 && rest[0] == 1 {
+        leading.push(AxisGuess::Batch);
+        rest = &rest[1..];
+    }
+    if let (true, Some(first), Some(last)) = (rest.len() >= 3, rest.first(), rest.last()) {
+        if *first <= MAX_GUESSED_CHANNELS {
+            leading.push(AxisGuess::Channel);
+            rest = &rest[1..];
+        } else if *last <= MAX_GUESSED_CHANNELS {
+            trailing.push(AxisGuess::Channel);
+            rest = &rest[..rest.len() - 1];
+        }
+    }
+    let spaces = rest.len().min(3);
+    let extra = rest.len() - spaces;
+    leading.extend((0..extra).map(|idx| if idx == 0 { AxisGuess::Time } else { AxisGuess::Index }));
+    leading.extend(["z", "y", "x"][3 - spaces..].iter().map(|id| AxisGuess::Space(id)));
+    leading.extend(trailing);
+    leading
+}
+
+impl NpyHeader {
+    /// Proposes axes for a tensor that this array was given for, with the sizes of the array as fixed sizes.
+    /// Works for both [InputAxis] and [OutputAxis].
+    pub fn guess_axes<A: serde::de::DeserializeOwned>(&self) -> Vec<A> {
+        let mut index_axes = 0;
+        guess_axis_kinds(&self.shape)
+            .into_iter()
+            .zip(&self.shape)
+            .filter_map(|(guess, size)| {
+                let raw = match guess {
+                    AxisGuess::Batch => "{type: batch}".to_owned(),
+                    AxisGuess::Channel => {
+                        let names: Vec<String> = (0..*size).map(|idx| format!("channel{idx}")).collect();
+                        format!("{{type: channel, channel_names: [{}]}}", names.join(", "))
+                    }
+                    AxisGuess::Time => format!("{{type: time, size: {size}}}"),
+                    AxisGuess::Index => {
+                        index_axes += 1;
+                        let id = if index_axes == 1 { "index".to_owned() } else { format!("index{index_axes}") };
+                        format!("{{type: index, id: {id}, size: {size}}}")
+                    }
+                    AxisGuess::Space(id) => format!("{{type: space, id: {id}, size: {size}}}"),
+                };
+                serde_yaml::from_str(&raw).ok()
+            })
+            .collect()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TestTensorError {
+    #[error("{0}")]
+    BadFile(#[from] NpyHeaderError),
+    #[error("Tensor has {axes} axes, but the array has {dimensions} dimensions")]
+    WrongDimensions { axes: usize, dimensions: usize },
+    #[error("Axis '{axis_id}' has size {size} in the array, expected {expected}")]
+    WrongSize { axis_id: AxisId, size: usize, expected: String },
+    #[error("Array holds {found} values, but the tensor data is {expected}")]
+    WrongDataType { expected: DataType, found: DataType },
+}
+
+/// What the size of an axis in an array must be
+enum SizeRequirement<'a> {
+    Any,
+    Exactly(usize),
+    Declared(&'a AnyAxisSize),
+}
+
+impl SizeRequirement<'_> {
+    /// A description of the allowed sizes if `size` isn't one of them
+    fn check(&self, size: usize) -> Result<(), String> {
+        match self {
+            Self::Any | Self::Declared(AnyAxisSize::Reference(_)) => Ok(()),
+            Self::Exactly(expected) if size == *expected => Ok(()),
+            Self::Exactly(expected) => Err(expected.to_string()),
+            Self::Declared(AnyAxisSize::Fixed(expected)) if size == expected.get() => Ok(()),
+            Self::Declared(AnyAxisSize::Fixed(expected)) => Err(expected.to_string()),
+            Self::Declared(AnyAxisSize::Parameterized(param)) => {
+                if param.is_valid(size) {
+                    Ok(())
+                } else {
+                    Err(format!("{} + n * {}", param.min, param.step))
+                }
+            }
+        }
+    }
+}
+
+impl InputAxis {
+    fn size_requirement(&self) -> SizeRequirement<'_> {
+        match self {
+            Self::Batch(axis) => axis.size.as_ref().map_or(SizeRequirement::Any, |_| SizeRequirement::Exactly(1)),
+            Self::Channel(axis) => SizeRequirement::Exactly(axis.size()),
+            Self::Index(axis) => SizeRequirement::Declared(&axis.size),
+            Self::Time(axis) => SizeRequirement::Declared(&axis.size),
+            Self::Space(axis) => SizeRequirement::Declared(&axis.size),
+        }
+    }
+}
+
+impl OutputAxis {
+    fn size_requirement(&self) -> SizeRequirement<'_> {
+        match self {
+            Self::Batch(axis) => axis.size.as_ref().map_or(SizeRequirement::Any, |_| SizeRequirement::Exactly(1)),
+            Self::Channel(axis) => SizeRequirement::Exactly(axis.size()),
+            Self::Index(axis) => SizeRequirement::Declared(&axis.size),
+            Self::Time(axis) => SizeRequirement::Declared(&axis.base.size),
+            Self::Space(axis) => SizeRequirement::Declared(&axis.base.size),
+        }
+    }
+}
+
+/// Sizes that refer to other axes aren't checked, since they depend on the rest of the model
+fn check_array<'a>(
+    header: &NpyHeader,
+    axes: impl ExactSizeIterator<Item = (&'a AxisId, SizeRequirement<'a>)>,
+    data: Option<&TensorData>,
+) -> Result<(), TestTensorError> {
+    if axes.len() != header.shape.len() {
+        return Err(TestTensorError::WrongDimensions {
+            axes: axes.len(),
+            dimensions: header.shape.len(),
+        });
+    }
+    for ((axis_id, requirement), size) in axes.zip(header.shape.iter().copied()) {
+        requirement.check(size).map_err(|expected| TestTensorError::WrongSize {
+            axis_id: axis_id.clone(),
+            size,
+            expected,
+        })?;
+    }
+    // tensors without a data description hold float32
+    let expected_types = match data {
+        Some(data) => data.descrs().into_iter().map(|descr| descr.data_type()).collect(),
+        None => vec![DataType::default()],
+    };
+    if let Some(expected) = expected_types.into_iter().find(|expected| *expected != header.data_type) {
+        return Err(TestTensorError::WrongDataType {
+            expected,
+            found: header.data_type,
+        });
+    }
+    Ok(())
+}
+
+impl InputTensorDescr2 {
+    /// Checks that an array with `header`, e.g. that of the `test_tensor`, could be given for this tensor
+    pub fn check_array(&self, header: &NpyHeader) -> Result<(), TestTensorError> {
+        let axes: Vec<_> = self.axes.iter().map(|axis| (axis.id(), axis.size_requirement())).collect();
+        check_array(header, axes.into_iter(), self.data.as_ref())
+    }
+}
+
+impl OutputTensorDescr {
+    /// Checks that an array with `header`, e.g. that of the `test_tensor`, could come out for this tensor
+    pub fn check_array(&self, header: &NpyHeader) -> Result<(), TestTensorError> {
+        let axes: Vec<_> = self.axes.iter().map(|axis| (axis.id(), axis.size_requirement())).collect();
+        check_array(header, axes.into_iter(), self.data.as_ref())
+    }
+}
+
+#[test]
+fn test_test_tensor_checks() {
+    fn npy(descr: &str, shape: &str) -> Vec<u8> {
+        let header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}\n");
+        let mut out = NPY_MAGIC.to_vec();
+        out.extend([1, 0]);
+        out.extend((header.len() as u16).to_le_bytes());
+        out.extend(header.as_bytes());
+        out
+    }
+    let header = |descr: &str, shape: &str| NpyHeader::read(npy(descr, shape).as_slice()).unwrap();
+
+    assert_eq!(
+        header("<f4", "(1, 3, 64)"),
+        NpyHeader {
+            data_type: DataType::Float32,
+            shape: vec![1, 3, 64]
+        }
+    );
+    assert_eq!(header("|u1", "(5,)").shape, vec![5]);
+    assert!(matches!(NpyHeader::read(&b"PK\x03\x04zip!"[..]), Err(NpyHeaderError::NotNpy)));
+    assert!(matches!(
+        NpyHeader::read(npy("<c8", "(1,)").as_slice()),
+        Err(NpyHeaderError::UnsupportedDataType(_))
+    ));
+
+    let input: InputTensorDescr2 = serde_yaml::from_str(
+        "
+        id: raw
+        axes:
+          - {type: batch}
+          - {type: channel, channel_names: [r, g, b]}
+          - {type: space, id: x, size: {min: 16, step: 8}}
+        test_tensor: {source: raw.npy}
+        ",
+    )
+    .unwrap();
+    input.check_array(&header("<f4", "(2, 3, 32)")).unwrap();
+    assert!(matches!(
+        input.check_array(&header("<f4", "(2, 3, 36)")),
+        Err(TestTensorError::WrongSize { size: 36, .. })
+    ));
+    assert!(matches!(
+        input.check_array(&header("<f4", "(1, 2, 32)")),
+        Err(TestTensorError::WrongSize { size: 2, .. })
+    ));
+    assert!(matches!(
+        input.check_array(&header("<f4", "(3, 32)")),
+        Err(TestTensorError::WrongDimensions { axes: 3, dimensions: 2 })
+    ));
+    assert!(matches!(
+        input.check_array(&header("|u1", "(1, 3, 16)")),
+        Err(TestTensorError::WrongDataType {
+            expected: DataType::Float32,
+            found: DataType::Uint8
+        })
+    ));
+}
+
+#[test]
+fn test_guessing_axes() {
+    use AxisGuess::*;
+
+    assert_eq!(guess_axis_kinds(&[256, 256]), vec![Space("y"), Space("x")]);
+    assert_eq!(guess_axis_kinds(&[3, 256, 256]), vec![Channel, Space("y"), Space("x")]);
+    assert_eq!(guess_axis_kinds(&[256, 256, 3]), vec![Space("y"), Space("x"), Channel]);
+    assert_eq!(guess_axis_kinds(&[32, 256, 256]), vec![Space("z"), Space("y"), Space("x")]);
+    assert_eq!(guess_axis_kinds(&[1, 1, 256, 256]), vec![Batch, Channel, Space("y"), Space("x")]);
+    assert_eq!(
+        guess_axis_kinds(&[1, 2, 10, 32, 256, 256]),
+        vec![Batch, Channel, Time, Space("z"), Space("y"), Space("x")]
+    );
+
+    let header = NpyHeader {
+        data_type: DataType::Float32,
+        shape: vec![1, 2, 64, 64],
+    };
+    let inputs: Vec<InputAxis> = header.guess_axes();
+    assert_eq!(inputs.len(), 4);
+    assert!(matches!(inputs[0], InputAxis::Batch(_)));
+    assert_eq!(inputs[1].channel_count(), Some(2));
+    let outputs: Vec<OutputAxis> = header.guess_axes();
+    assert!(matches!(&outputs[3], OutputAxis::Space(axis) if axis.base.id.to_string() == "x"));
+    let descr = serde_yaml::to_string(&inputs).unwrap();
+    assert!(descr.contains("size: 64"), "{descr}");
+}
@@ -0,0 +1,463 @@
+use std::borrow::Borrow;
+use std::ops::Range;
+
+use ndarray::ArrayD;
+
+use super::InferenceBackend;
+use crate::rdf::model::axes::{InputAxis, OutputAxis};
+use crate::rdf::model::axis_size::AnyAxisSize;
+use crate::rdf::model::{ModelRdf, ParameterizedAxisSize};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum TilingError {
+    #[error("Halo of {halo} leaves no valid region in a tile of size {tile_size}")]
+    HaloTooLarge { halo: usize, tile_size: usize },
+    #[error("Expected {expected} axes, found {found}")]
+    WrongNumberOfAxes { expected: usize, found: usize },
+    #[error("Tile data has {found} elements, expected {expected}")]
+    BadTileData { expected: usize, found: usize },
+    #[error("Can't run this model tile by tile: {0}")]
+    NotTileable(String),
+}
+
+/// How a single axis of a tensor is split into tiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AxisTiling {
+    /// The axis is never split, e.g. batch or channel axes
+    Whole { extent: usize },
+    /// The axis is split into `min + k * step` sized tiles overlapping by `halo` on each side
+    Tiled {
+        extent: usize,
+        tile_size: usize,
+        halo: usize,
+    },
+}
+
+impl AxisTiling {
+    /// Picks the smallest valid tile size (`min + k * step`) that is at least `target` and that
+    /// still leaves a non-empty region once `halo` is trimmed from both ends.
+    pub fn tiled(extent: usize, size: &ParameterizedAxisSize, halo: usize, target: usize) -> Self {
+        let tile_size = size.smallest_valid_at_least(target.max(2 * halo + 1));
+        Self::Tiled { extent, tile_size, halo }
+    }
+
+    /// Tiling for an axis whose input size is fixed, which fails if the halo doesn't fit in the tile
+    pub fn fixed(extent: usize, tile_size: usize, halo: usize) -> Result<Self, TilingError> {
+        if 2 * halo >= tile_size {
+            return Err(TilingError::HaloTooLarge { halo, tile_size });
+        }
+        Ok(Self::Tiled { extent, tile_size, halo })
+    }
+
+    pub fn extent(&self) -> usize {
+        match self {
+            Self::Whole { extent } => *extent,
+            Self::Tiled { extent, .. } => *extent,
+        }
+    }
+
+    pub fn tiles(&self) -> Vec<Tile1d> {
+        match *self {
+            Self::Whole { extent } => vec![Tile1d {
+                input: 0..extent as isize,
+                valid: 0..extent,
+                crop: 0..extent,
+            }],
+            Self::Tiled { extent, tile_size, halo } => {
+                let stride = tile_size - 2 * halo;
+                (0..extent.div_ceil(stride))
+                    .map(|idx| {
+                        let valid_start = idx * stride;
+                        let valid_end = (valid_start + stride).min(extent);
+                        let input_start = valid_start as isize - halo as isize;
+                        Tile1d {
+                            input: input_start..input_start + tile_size as isize,
+                            valid: valid_start..valid_end,
+                            crop: halo..halo + (valid_end - valid_start),
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A tile along a single axis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tile1d {
+    /// Region of the full tensor fed to the model. Can go out of bounds, in which case it must be padded
+    pub input: Range<isize>,
+    /// Region of the full output that this tile is responsible for
+    pub valid: Range<usize>,
+    /// Part of the tile's output that is kept after trimming the halo
+    pub crop: Range<usize>,
+}
+
+impl Tile1d {
+    pub fn input_len(&self) -> usize {
+        (self.input.end - self.input.start) as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tile(pub Vec<Tile1d>);
+
+impl Tile {
+    pub fn shape(&self) -> Vec<usize> {
+        self.0.iter().map(|t| t.input_len()).collect()
+    }
+}
+
+/// The full set of tiles covering a tensor, in C order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TilingPlan {
+    axes: Vec<AxisTiling>,
+}
+
+impl TilingPlan {
+    pub fn new(axes: Vec<AxisTiling>) -> Self {
+        Self { axes }
+    }
+
+    pub fn shape(&self) -> Vec<usize> {
+        self.axes.iter().map(|a| a.extent()).collect()
+    }
+
+    pub fn tiles(&self) -> Vec<Tile> {
+        self.axes.iter().fold(vec![Tile(vec![])], |acc, axis| {
+            let axis_tiles = axis.tiles();
+            acc.into_iter()
+                .flat_map(|tile| {
+                    axis_tiles.iter().map(move |t| {
+                        let mut tiles = tile.0.clone();
+                        tiles.push(t.clone());
+                        Tile(tiles)
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Copies the region of `data` (C order, shaped like the plan) that `tile` reads, padding out-of-bounds
+    /// positions by reflecting them back into the tensor
+    pub fn extract<T: Copy>(&self, data: &[T], tile: &Tile) -> Result<Vec<T>, TilingError> {
+        let shape = self.shape();
+        self.check_tile(tile)?;
+        let expected = shape.iter().product();
+        if data.len() != expected {
+            return Err(TilingError::BadTileData { expected, found: data.len() });
+        }
+        let strides = c_strides(&shape);
+        let tile_shape = tile.shape();
+        Ok(for_each_index(&tile_shape)
+            .map(|tile_idx| {
+                let offset: usize = tile_idx
+                    .iter()
+                    .zip(&tile.0)
+                    .zip(shape.iter().zip(&strides))
+                    .map(|((i, t), (extent, stride))| reflect(t.input.start + *i as isize, *extent) * stride)
+                    .sum();
+                data[offset]
+            })
+            .collect())
+    }
+
+    /// Writes the halo-trimmed output of every tile into a buffer shaped like the plan
+    pub fn stitch<T: Copy + Default>(&self, tile_outputs: impl IntoIterator<Item = (Tile, Vec<T>)>) -> Result<Vec<T>, TilingError> {
+        let shape = self.shape();
+        let strides = c_strides(&shape);
+        let mut out = vec![T::default(); shape.iter().product()];
+        for (tile, tile_data) in tile_outputs {
+            self.check_tile(&tile)?;
+            let tile_shape = tile.shape();
+            let expected = tile_shape.iter().product();
+            if tile_data.len() != expected {
+                return Err(TilingError::BadTileData {
+                    expected,
+                    found: tile_data.len(),
+                });
+            }
+            let tile_strides = c_strides(&tile_shape);
+            let crop_shape: Vec<usize> = tile.0.iter().map(|t| t.crop.len()).collect();
+            for crop_idx in for_each_index(&crop_shape) {
+                let mut src = 0;
+                let mut dst = 0;
+                for (axis, i) in crop_idx.iter().enumerate() {
+                    src += (tile.0[axis].crop.start + i) * tile_strides[axis];
+                    dst += (tile.0[axis].valid.start + i) * strides[axis];
+                }
+                out[dst] = tile_data[src];
+            }
+        }
+        Ok(out)
+    }
+
+    fn check_tile(&self, tile: &Tile) -> Result<(), TilingError> {
+        if tile.0.len() != self.axes.len() {
+            return Err(TilingError::WrongNumberOfAxes {
+                expected: self.axes.len(),
+                found: tile.0.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// How an output of a [TiledBackend] is tiled, and the outputs of the tiles that were run
+type TiledOutput = (Vec<AxisTiling>, Vec<(Tile, Vec<f32>)>);
+
+/// Runs a backend tile by tile, on tiles whose size the model allows along its parameterized space and time axes,
+/// overlapping by the halo of the outputs. This takes models with one input, whose outputs have the same axes in
+/// the same order, sized after the input along the axes that are tiled.
+pub struct TiledBackend {
+    inner: Box<dyn InferenceBackend + Send>,
+    /// The allowed sizes and the halo of every axis of the input that is tiled
+    axes: Vec<Option<(ParameterizedAxisSize, usize)>>,
+    tile_size: usize,
+}
+
+impl TiledBackend {
+    /// Runs `inner` on tiles of at least `tile_size` along every axis that is tiled
+    pub fn new(model: &ModelRdf, inner: Box<dyn InferenceBackend + Send>, tile_size: usize) -> Result<Self, TilingError> {
+        let [input] = Borrow::<[_]>::borrow(&model.inputs) else {
+            return Err(TilingError::NotTileable("it doesn't take exactly one input".to_owned()));
+        };
+        let axes = input
+            .axes
+            .iter()
+            .enumerate()
+            .map(|(index, axis)| {
+                let (axis_id, size, scale) = match axis {
+                    InputAxis::Space(axis) => (&axis.id, &axis.size, axis.scale),
+                    InputAxis::Time(axis) => (&axis.id, &axis.size, axis.scale),
+                    _ => return Ok(None),
+                };
+                let AnyAxisSize::Parameterized(size) = size else {
+                    return Ok(None);
+                };
+                let mut halo = 0;
+                for output in model.outputs.iter() {
+                    let not_tileable = |reason: &str| {
+                        TilingError::NotTileable(format!("axis {index} of output '{}' {reason}", output.id))
+                    };
+                    let (output_size, output_scale, output_halo) = match output.axes.iter().nth(index) {
+                        Some(OutputAxis::Space(axis)) => (&axis.base.size, axis.base.scale, axis.halo),
+                        Some(OutputAxis::Time(axis)) => (&axis.base.size, axis.base.scale, axis.halo),
+                        _ => return Err(not_tileable(&format!("is not a space or time axis like '{axis_id}'"))),
+                    };
+                    // tiles are stitched back together at the positions they had in the input
+                    if output_scale.get() != scale.get() {
+                        return Err(not_tileable(&format!("is not at the same scale as '{axis_id}'")));
+                    }
+                    let follows_input = match output_size {
+                        AnyAxisSize::Reference(reference) => {
+                            reference.tensor_id == input.id && &reference.axis_id == axis_id && reference.offset == 0
+                        }
+                        _ => false,
+                    };
+                    if !follows_input {
+                        return Err(not_tileable(&format!("is not as large as '{axis_id}'")));
+                    }
+                    halo = halo.max(output_halo);
+                }
+                Ok(Some((size.clone(), halo)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { inner, axes, tile_size })
+    }
+
+    fn run_tiled(&mut self, input: ArrayD<f32>) -> Result<Vec<ArrayD<f32>>, String> {
+        if input.ndim() != self.axes.len() {
+            return Err(TilingError::WrongNumberOfAxes {
+                expected: self.axes.len(),
+                found: input.ndim(),
+            }
+            .to_string());
+        }
+        let input_axes = self
+            .axes
+            .iter()
+            .zip(input.shape())
+            .map(|(axis, extent)| match axis {
+                Some((size, halo)) => AxisTiling::tiled(*extent, size, *halo, self.tile_size),
+                None => AxisTiling::Whole { extent: *extent },
+            })
+            .collect::<Vec<_>>();
+        let plan = TilingPlan::new(input_axes.clone());
+        let input = input.as_standard_layout();
+        let data = input.as_slice().expect("standard layout");
+
+        let mut outputs: Vec<TiledOutput> = vec![];
+        for tile in plan.tiles() {
+            let tile_input = ArrayD::from_shape_vec(tile.shape(), plan.extract(data, &tile).map_err(|err| err.to_string())?);
+            let tile_outputs = self.inner.run(vec![tile_input.expect("one value per position")])?;
+            if outputs.is_empty() {
+                outputs = tile_outputs.iter().map(|_| (vec![], vec![])).collect();
+            }
+            if tile_outputs.len() != outputs.len() {
+                return Err(format!("expected {} outputs, found {}", outputs.len(), tile_outputs.len()));
+            }
+            for (output, (axes, tiles)) in tile_outputs.into_iter().zip(&mut outputs) {
+                if output.ndim() != input_axes.len() {
+                    return Err(format!("an output has {} axes instead of {}", output.ndim(), input_axes.len()));
+                }
+                // the axes that aren't tiled are as large as the model makes them
+                let whole = |extent: usize| Tile1d {
+                    input: 0..extent as isize,
+                    valid: 0..extent,
+                    crop: 0..extent,
+                };
+                let output_tile = input_axes.iter().zip(&tile.0).zip(output.shape());
+                let output_tile = output_tile.map(|((axis, tile), extent)| match axis {
+                    AxisTiling::Tiled { .. } => tile.clone(),
+                    AxisTiling::Whole { .. } => whole(*extent),
+                });
+                let output_tile = Tile(output_tile.collect());
+                if axes.is_empty() {
+                    *axes = input_axes.iter().zip(output.shape()).map(|(axis, extent)| match axis {
+                        AxisTiling::Tiled { .. } => axis.clone(),
+                        AxisTiling::Whole { .. } => AxisTiling::Whole { extent: *extent },
+                    })
+                    .collect();
+                }
+                let output = output.as_standard_layout().iter().copied().collect();
+                tiles.push((output_tile, output));
+            }
+        }
+        outputs
+            .into_iter()
+            .map(|(axes, tiles)| {
+                let output_plan = TilingPlan::new(axes);
+                let data = output_plan.stitch(tiles).map_err(|err| err.to_string())?;
+                Ok(ArrayD::from_shape_vec(output_plan.shape(), data).expect("one value per position"))
+            })
+            .collect()
+    }
+}
+
+impl InferenceBackend for TiledBackend {
+    fn run(&mut self, inputs: Vec<ArrayD<f32>>) -> Result<Vec<ArrayD<f32>>, String> {
+        let Ok([input]) = <[_; 1]>::try_from(inputs) else {
+            return Err("a model that is run tile by tile takes one input".to_owned());
+        };
+        self.run_tiled(input)
+    }
+}
+
+fn c_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for axis in (0..shape.len().saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * shape[axis + 1];
+    }
+    strides
+}
+
+fn for_each_index(shape: &[usize]) -> impl Iterator<Item = Vec<usize>> + '_ {
+    let total: usize = shape.iter().product();
+    (0..total).map(move |mut flat| {
+        let mut idx = vec![0; shape.len()];
+        for axis in (0..shape.len()).rev() {
+            idx[axis] = flat % shape[axis];
+            flat /= shape[axis];
+        }
+        idx
+    })
+}
+
+fn reflect(position: isize, extent: usize) -> usize {
+    let extent = extent as isize;
+    if extent == 1 {
+        return 0;
+    }
+    let period = 2 * (extent - 1);
+    let pos = position.rem_euclid(period);
+    (if pos < extent { pos } else { period - pos }) as usize
+}
+
+#[test]
+fn test_tiling_roundtrip() {
+    use std::num::NonZeroUsize;
+
+    let size = ParameterizedAxisSize {
+        min: NonZeroUsize::new(16).unwrap(),
+        step: NonZeroUsize::new(8).unwrap(),
+    };
+    assert_eq!(
+        AxisTiling::tiled(100, &size, 20, 0),
+        AxisTiling::Tiled {
+            extent: 100,
+            tile_size: 48,
+            halo: 20
+        }
+    );
+    assert_eq!(
+        AxisTiling::fixed(100, 16, 8),
+        Err(TilingError::HaloTooLarge { halo: 8, tile_size: 16 })
+    );
+
+    let plan = TilingPlan::new(vec![
+        AxisTiling::Whole { extent: 2 },
+        AxisTiling::tiled(13, &size, 4, 16),
+        AxisTiling::fixed(7, 5, 2).unwrap(),
+    ]);
+    let data: Vec<u32> = (0..2 * 13 * 7).collect();
+    let outputs = plan
+        .tiles()
+        .into_iter()
+        .map(|tile| {
+            let tile_data = plan.extract(&data, &tile).unwrap();
+            (tile, tile_data)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(plan.stitch(outputs).unwrap(), data);
+}
+
+#[test]
+fn test_tiled_backend() {
+    use std::sync::{Arc, Mutex};
+
+    /// Stands in for the shark model like in the self test, remembering the shapes it was given
+    struct Mirror(Arc<Mutex<Vec<Vec<usize>>>>);
+    impl InferenceBackend for Mirror {
+        fn run(&mut self, inputs: Vec<ArrayD<f32>>) -> Result<Vec<ArrayD<f32>>, String> {
+            self.0.lock().unwrap().push(inputs[0].shape().to_vec());
+            let output = ndarray::concatenate![ndarray::Axis(1), inputs[0].view(), inputs[0].mapv(|v| -v).view()];
+            Ok(vec![output])
+        }
+    }
+    let mut package = super::model_package::shark_package();
+    let shapes = Arc::new(Mutex::new(vec![]));
+    let mut backend = TiledBackend::new(&package.rdf, Box::new(Mirror(shapes.clone())), 60).unwrap();
+
+    let input = ArrayD::from_shape_fn(vec![1, 1, 100, 70], |idx| (idx[2] * 70 + idx[3]) as f32);
+    let outputs = backend.run(vec![input.clone()]).unwrap();
+    let expected = ndarray::concatenate![ndarray::Axis(1), input.view(), input.mapv(|v| -v).view()];
+    assert_eq!(outputs, vec![expected]);
+    // tiles of the smallest size the model allows, of which the halo of 16 is cut off on either side
+    let shapes = shapes.lock().unwrap();
+    assert_eq!(shapes.len(), 4 * 3);
+    assert!(shapes.iter().all(|shape| shape == &[1, 1, 64, 64]));
+
+    drop(shapes);
+
+    let not_tileable = |package: &super::model_package::ModelPackage| {
+        match TiledBackend::new(&package.rdf, Box::new(Mirror(Default::default())), 60) {
+            Err(TilingError::NotTileable(reason)) => reason,
+            Err(err) => panic!("tiling failed with {err}"),
+            Ok(_) => panic!("tiling worked"),
+        }
+    };
+    // an output upsampled 2x along y would be stitched at the wrong positions
+    let output = package.rdf.outputs.iter_mut().next().unwrap();
+    if let Some(OutputAxis::Space(axis)) = output.axes.iter_mut().nth(2) {
+        axis.base.scale = crate::rdf::model::axes::AxisScale::try_from(0.5).unwrap();
+    }
+    assert!(not_tileable(&package).contains("same scale"));
+
+    let output = package.rdf.outputs.iter_mut().next().unwrap();
+    if let Some(OutputAxis::Space(axis)) = output.axes.iter_mut().nth(2) {
+        axis.base.scale = Default::default();
+        axis.base.size = AnyAxisSize::Fixed(std::num::NonZeroUsize::new(64).unwrap());
+    }
+    assert!(not_tileable(&package).contains("as large as"));
+}
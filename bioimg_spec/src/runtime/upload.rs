@@ -0,0 +1,481 @@
+//! Publishing packaged models, either to the bioimage.io upload endpoint or as a new Zenodo
+//! deposition.
+
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Duration;
+
+use url::Url;
+
+#[derive(thiserror::Error, Debug)]
+pub enum UploadError {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Could not reach {url}: {message}")]
+    HttpError { url: String, message: String },
+    #[error("{url} refused the upload with status {status}: {message}")]
+    Rejected { url: String, status: u16, message: String },
+    #[error("Unexpected response from {url}: {message}")]
+    BadResponse { url: String, message: String },
+    #[error("Upload was cancelled")]
+    Cancelled,
+}
+
+/// Where a packaged model is published
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UploadTarget {
+    /// An endpoint that takes the zip in chunks, each a `PUT` with a `Content-Range`. It answers
+    /// `308` with the `Range` it has so far until the last chunk, so interrupted uploads resume
+    /// where they stopped instead of starting over.
+    BioimageIo { endpoint: Url },
+    /// A new deposition on a Zenodo instance, given the root of its REST api
+    Zenodo { api: Url },
+}
+
+impl UploadTarget {
+    pub fn zenodo() -> Self {
+        Self::Zenodo {
+            api: Url::parse("https://zenodo.org/api/").unwrap(),
+        }
+    }
+
+    /// The Zenodo instance meant for trying out uploads, whose depositions are thrown away
+    pub fn zenodo_sandbox() -> Self {
+        Self::Zenodo {
+            api: Url::parse("https://sandbox.zenodo.org/api/").unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UploadProgress {
+    pub sent: u64,
+    pub total: u64,
+}
+
+impl UploadProgress {
+    pub fn fraction(&self) -> f32 {
+        match self.total {
+            0 => 1.0,
+            total => self.sent as f32 / total as f32,
+        }
+    }
+}
+
+/// How often a request that failed for reasons that may go away, like a dropped connection or a
+/// busy server, is sent again. The delay doubles after every failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub first_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            first_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay(&self, failures: u32) -> Duration {
+        self.first_delay * 2u32.saturating_pow(failures.saturating_sub(1))
+    }
+}
+
+/// A finished upload
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Uploaded {
+    /// Where the upload can be looked at, if the server said so
+    pub location: Option<String>,
+}
+
+/// Whether sending a failed request again might work
+fn is_transient(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::Status(status, _) => matches!(status, 408 | 429 | 500..=599),
+        ureq::Error::Transport(transport) => matches!(
+            transport.kind(),
+            ureq::ErrorKind::Io | ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::Dns
+        ),
+    }
+}
+
+fn request_error(url: &str, err: ureq::Error) -> UploadError {
+    match err {
+        ureq::Error::Status(status, response) => UploadError::Rejected {
+            url: url.to_owned(),
+            status,
+            message: response.into_string().unwrap_or_default(),
+        },
+        ureq::Error::Transport(transport) => UploadError::HttpError {
+            url: url.to_owned(),
+            message: transport.to_string(),
+        },
+    }
+}
+
+/// The offset after the last byte in a `Range: bytes=0-<last>` header
+fn received_until(response: &ureq::Response) -> Option<u64> {
+    let (_, last) = response.header("Range")?.strip_prefix("bytes=")?.split_once('-')?;
+    Some(last.trim().parse::<u64>().ok()? + 1)
+}
+
+/// Reports how much of the file has been read so far, stopping once told to
+struct ProgressReader<'a, R> {
+    inner: R,
+    progress: UploadProgress,
+    on_progress: &'a mut dyn FnMut(UploadProgress) -> ControlFlow<()>,
+    cancelled: &'a Cell<bool>,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.progress.sent += read as u64;
+        if (self.on_progress)(self.progress).is_break() {
+            self.cancelled.set(true);
+            return Err(std::io::Error::other("upload was cancelled"));
+        }
+        Ok(read)
+    }
+}
+
+pub struct Uploader {
+    agent: ureq::Agent,
+    token: String,
+    retry: RetryPolicy,
+    chunk_size: usize,
+}
+
+impl Uploader {
+    /// Uploads with the api `token` of the account the model is published under
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            agent: super::network::agent_builder().build(),
+            token: token.into(),
+            retry: RetryPolicy::default(),
+            chunk_size: 8 * 1024 * 1024,
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// How much is sent at once to endpoints that take the upload in chunks
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    /// Uploads the zip at `zip_path`. `on_progress` is called as the file is sent, and stops the
+    /// upload when it returns [ControlFlow::Break].
+    pub fn upload(
+        &self,
+        target: &UploadTarget,
+        zip_path: &Path,
+        on_progress: &mut dyn FnMut(UploadProgress) -> ControlFlow<()>,
+    ) -> Result<Uploaded, UploadError> {
+        match target {
+            UploadTarget::BioimageIo { endpoint } => self.upload_in_chunks(endpoint, zip_path, on_progress),
+            UploadTarget::Zenodo { api } => self.upload_to_zenodo(api, zip_path, on_progress),
+        }
+    }
+
+    /// Sends `request` until it works, it fails for good or the retries run out
+    fn retrying(
+        &self,
+        url: &str,
+        cancelled: &Cell<bool>,
+        mut request: impl FnMut() -> Result<ureq::Response, Box<ureq::Error>>,
+    ) -> Result<ureq::Response, UploadError> {
+        let mut failures = 0;
+        loop {
+            match request() {
+                Ok(response) => return Ok(response),
+                Err(_) if cancelled.get() => return Err(UploadError::Cancelled),
+                Err(err) if is_transient(&err) && failures + 1 < self.retry.attempts => {
+                    failures += 1;
+                    std::thread::sleep(self.retry.delay(failures));
+                }
+                Err(err) => return Err(request_error(url, *err)),
+            }
+        }
+    }
+
+    fn upload_in_chunks(
+        &self,
+        endpoint: &Url,
+        zip_path: &Path,
+        on_progress: &mut dyn FnMut(UploadProgress) -> ControlFlow<()>,
+    ) -> Result<Uploaded, UploadError> {
+        let mut file = File::open(zip_path)?;
+        let total = file.metadata()?.len();
+        let mut buf = vec![0u8; self.chunk_size];
+        let mut offset = 0;
+        let mut failures = 0;
+        // after a failed chunk, the server is asked how much it got before sending more
+        let mut resuming = false;
+        loop {
+            let len = (total - offset).min(self.chunk_size as u64) as usize;
+            let request = self.agent.put(endpoint.as_str()).set("Authorization", &self.authorization());
+            let result = if resuming || total == 0 {
+                request.set("Content-Range", &format!("bytes */{total}")).send_bytes(&[])
+            } else {
+                file.seek(SeekFrom::Start(offset))?;
+                file.read_exact(&mut buf[..len])?;
+                let range = format!("bytes {offset}-{}/{total}", offset + len as u64 - 1);
+                request.set("Content-Range", &range).send_bytes(&buf[..len])
+            };
+            let response = match result {
+                Ok(response) => response,
+                Err(err) if is_transient(&err) && failures + 1 < self.retry.attempts => {
+                    failures += 1;
+                    std::thread::sleep(self.retry.delay(failures));
+                    resuming = true;
+                    continue;
+                }
+                Err(err) => return Err(request_error(endpoint.as_str(), err)),
+            };
+            if response.status() != 308 {
+                return Ok(Uploaded {
+                    location: response.header("Location").map(str::to_owned),
+                });
+            }
+            offset = match received_until(&response) {
+                Some(received) => received.min(total),
+                None if resuming => 0,
+                None => offset + len as u64,
+            };
+            resuming = false;
+            failures = 0;
+            if on_progress(UploadProgress { sent: offset, total }).is_break() {
+                return Err(UploadError::Cancelled);
+            }
+        }
+    }
+
+    fn upload_to_zenodo(
+        &self,
+        api: &Url,
+        zip_path: &Path,
+        on_progress: &mut dyn FnMut(UploadProgress) -> ControlFlow<()>,
+    ) -> Result<Uploaded, UploadError> {
+        let cancelled = Cell::new(false);
+        let depositions = api.join("deposit/depositions").map_err(|err| UploadError::BadResponse {
+            url: api.to_string(),
+            message: err.to_string(),
+        })?;
+        let response = self.retrying(depositions.as_str(), &cancelled, || {
+            self.agent
+                .post(depositions.as_str())
+                .set("Authorization", &self.authorization())
+                .set("Content-Type", "application/json")
+                .send_string("{}")
+                .map_err(Box::new)
+        })?;
+        let bad_response = |message: String| UploadError::BadResponse {
+            url: depositions.to_string(),
+            message,
+        };
+        let deposition: serde_json::Value =
+            serde_json::from_str(&response.into_string()?).map_err(|err| bad_response(err.to_string()))?;
+        let links = &deposition["links"];
+        let bucket = links["bucket"]
+            .as_str()
+            .ok_or_else(|| bad_response("the deposition has no bucket to upload into".to_owned()))?;
+
+        let file_name = zip_path.file_name().map(|name| name.to_string_lossy()).unwrap_or("model.zip".into());
+        let file_url = format!("{}/{file_name}", bucket.trim_end_matches('/'));
+        let total = std::fs::metadata(zip_path)?.len();
+        // the bucket takes the file in one go, so a retry sends all of it again
+        self.retrying(&file_url, &cancelled, || {
+            let reader = ProgressReader {
+                inner: File::open(zip_path).map_err(|err| Box::new(err.into()))?,
+                progress: UploadProgress { sent: 0, total },
+                on_progress: &mut *on_progress,
+                cancelled: &cancelled,
+            };
+            self.agent
+                .put(&file_url)
+                .set("Authorization", &self.authorization())
+                .set("Content-Type", "application/octet-stream")
+                .set("Content-Length", &total.to_string())
+                .send(reader)
+                .map_err(Box::new)
+        })?;
+        Ok(Uploaded {
+            location: links["html"].as_str().map(str::to_owned),
+        })
+    }
+}
+
+#[cfg(test)]
+mod fake_server {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    pub struct Request {
+        pub method: String,
+        pub path: String,
+        pub headers: Vec<(String, String)>,
+        pub body: Vec<u8>,
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Answers every request with `respond`, which returns the whole response head and body.
+    /// Returns the port it listens on and every request it got.
+    pub fn serve(mut respond: impl FnMut(&Request) -> String + Send + 'static) -> (u16, Arc<Mutex<Vec<Request>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(vec![]));
+        let received = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let mut parts = line.split_whitespace().map(str::to_owned);
+                let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                let mut headers = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    match line.trim_end().split_once(": ") {
+                        Some((key, value)) => headers.push((key.to_owned(), value.to_owned())),
+                        None => break,
+                    }
+                }
+                let mut request = Request { method, path, headers, body: vec![] };
+                let length = request.header("Content-Length").map_or(0, |length| length.parse().unwrap());
+                request.body = vec![0; length];
+                reader.read_exact(&mut request.body).unwrap();
+                let response = respond(&request);
+                // recorded before answering, so the client never gets ahead of the list
+                received.lock().unwrap().push(request);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (port, requests)
+    }
+
+    pub fn response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+}
+
+#[cfg(test)]
+fn test_zip(name: &str, contents: &[u8]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("bioimg_upload_test_{}_{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_resuming_chunked_upload() {
+    use fake_server::{response, serve};
+
+    // takes every chunk but the second one the first time it is sent
+    let mut received = vec![];
+    let mut dropped = false;
+    let (port, requests) = serve(move |request| {
+        let range = request.header("Content-Range").unwrap();
+        let (span, total) = range.strip_prefix("bytes ").unwrap().split_once('/').unwrap();
+        let total: usize = total.parse().unwrap();
+        if span != "*" {
+            if received.len() == 4 && !dropped {
+                dropped = true;
+                return response("503 Service Unavailable", "", "busy");
+            }
+            received.extend_from_slice(&request.body);
+        }
+        if received.len() == total {
+            return response("201 Created", "Location: /models/1\r\n", "");
+        }
+        response("308 Resume Incomplete", &format!("Range: bytes=0-{}\r\n", received.len() - 1), "")
+    });
+
+    let zip = test_zip("chunked.zip", b"0123456789");
+    let uploader = Uploader::new("secret").with_chunk_size(4).with_retry(RetryPolicy {
+        attempts: 3,
+        first_delay: Duration::from_millis(1),
+    });
+    let target = UploadTarget::BioimageIo {
+        endpoint: Url::parse(&format!("http://127.0.0.1:{port}/upload")).unwrap(),
+    };
+    let mut progress = vec![];
+    let uploaded = uploader
+        .upload(&target, &zip, &mut |p| {
+            progress.push(p.sent);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(uploaded.location.as_deref(), Some("/models/1"));
+    assert_eq!(progress, [4, 4, 8]);
+
+    let requests = requests.lock().unwrap();
+    let ranges: Vec<_> = requests.iter().map(|r| r.header("Content-Range").unwrap()).collect();
+    assert_eq!(ranges, ["bytes 0-3/10", "bytes 4-7/10", "bytes */10", "bytes 4-7/10", "bytes 8-9/10"]);
+    assert!(requests.iter().all(|r| r.method == "PUT" && r.header("Authorization") == Some("Bearer secret")));
+    drop(requests);
+
+    let cancelled = uploader.upload(&target, &zip, &mut |_| ControlFlow::Break(()));
+    assert!(matches!(cancelled, Err(UploadError::Cancelled)));
+    std::fs::remove_file(zip).unwrap();
+}
+
+#[test]
+fn test_zenodo_upload() {
+    use fake_server::{response, serve};
+
+    let mut port = None;
+    let (server_port, requests) = serve(move |request| match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/api/deposit/depositions") => {
+            let port = port.get_or_insert_with(|| request.header("Host").unwrap().to_owned());
+            let links = format!(r#"{{"links": {{"bucket": "http://{port}/files/abc", "html": "http://{port}/deposit/1"}}}}"#);
+            response("201 Created", "", &links)
+        }
+        ("PUT", path) if path.starts_with("/files/abc/") => response("201 Created", "", "{}"),
+        _ => response("404 Not Found", "", ""),
+    });
+
+    let zip = test_zip("zenodo.zip", b"zipped model");
+    let target = UploadTarget::Zenodo {
+        api: Url::parse(&format!("http://127.0.0.1:{server_port}/api/")).unwrap(),
+    };
+    let mut sent = 0;
+    let uploaded = Uploader::new("token")
+        .upload(&target, &zip, &mut |progress| {
+            sent = progress.sent;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    std::fs::remove_file(&zip).unwrap();
+    assert_eq!(uploaded.location, Some(format!("http://127.0.0.1:{server_port}/deposit/1")));
+    assert_eq!(sent, 12);
+    let requests = requests.lock().unwrap();
+    assert!(requests[1].path.ends_with("zenodo.zip"));
+    assert_eq!(requests[1].body, b"zipped model");
+    assert_eq!(requests[1].header("Authorization"), Some("Bearer token"));
+}
@@ -0,0 +1,365 @@
+//! Validating a model again whenever its description, or a file it refers to, changes on disk, for people who edit
+//! the yaml by hand. Changes are found by comparing modification times and sizes. On Linux, inotify says when to
+//! compare them; elsewhere, and in directories inotify can't watch, they are compared every so often.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::model_package::{resource_local_paths, validate_path, ModelPackage};
+use super::package_reader::{read_rdf_text, RDF_FILE_NAMES};
+use crate::rdf::{model::ModelRdf, resource_description::AnyResourceRdf, ValidationEntry, ValidationReport};
+
+/// How often [ValidationWatcher::run] looks for changes unless told otherwise, when it isn't notified of them
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long [ValidationWatcher::run] waits to be notified before it looks for changes anyway, for the filesystems
+/// that don't notify of every change, like network shares
+const NOTIFIED_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What a file looked like when it was last checked
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// `None` if there is no file at `path`
+fn stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+/// A new report, and how it differs from the one before
+#[derive(Debug, Clone)]
+pub struct WatchUpdate {
+    pub report: ValidationReport,
+    /// The entries the previous report didn't have, which are all of them in the first report
+    pub added: Vec<ValidationEntry>,
+    /// The entries of the previous report that are gone
+    pub resolved: Vec<ValidationEntry>,
+    /// The files whose changes led to this report, none for the first one
+    pub changed_files: Vec<PathBuf>,
+}
+
+/// Validates whatever [validate_path] can validate every time it changes. A description on its own is validated
+/// along with the files next to it that it refers to, as if it were in a package directory.
+pub struct ValidationWatcher {
+    path: PathBuf,
+    stamps: BTreeMap<PathBuf, Option<FileStamp>>,
+    report: Option<ValidationReport>,
+}
+
+impl ValidationWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            stamps: BTreeMap::new(),
+            report: None,
+        }
+    }
+
+    /// The description, or package, and the local files it refers to, as of the last [Self::poll]
+    pub fn watched_files(&self) -> impl Iterator<Item = &Path> {
+        self.stamps.keys().map(PathBuf::as_path)
+    }
+
+    fn files_to_watch(&self) -> Vec<PathBuf> {
+        if self.path.extension().is_some_and(|ext| ext == "zip") {
+            return vec![self.path.clone()];
+        }
+        let (mut files, dir) = match self.path.is_dir() {
+            true => (RDF_FILE_NAMES.iter().map(|name| self.path.join(name)).collect(), self.path.as_path()),
+            false => (vec![self.path.clone()], self.path.parent().unwrap_or(Path::new(""))),
+        };
+        // what the description refers to can change along with it, so this is looked up every time. Like when
+        // validating, descriptions without a type are taken to be models.
+        let parse = |raw: String| {
+            AnyResourceRdf::from_yaml(&raw)
+                .ok()
+                .or_else(|| Some(AnyResourceRdf::Model(Box::new(ModelRdf::from_yaml(&raw).ok()?))))
+        };
+        if let Some(mut rdf) = read_rdf_text(&self.path).ok().and_then(parse) {
+            files.extend(resource_local_paths(&mut rdf).into_iter().map(|path| dir.join(path)));
+        }
+        files
+    }
+
+    fn validate(&self) -> ValidationReport {
+        let is_description = matches!(self.path.extension().and_then(|ext| ext.to_str()), Some("yaml" | "yml"));
+        if is_description {
+            if let Ok(package) = ModelPackage::from_description_file(&self.path) {
+                return package.validate();
+            }
+        }
+        validate_path(&self.path)
+    }
+
+    /// The directories the watched files are in, which are watched rather than the files themselves, since editors
+    /// tend to replace a file when saving it
+    fn watched_dirs(&self) -> BTreeSet<PathBuf> {
+        let dir = |path: &Path| match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        self.stamps.keys().map(|path| dir(path)).collect()
+    }
+
+    /// Validates again if any of the watched files changed since the last call, and the first time it is called
+    pub fn poll(&mut self) -> Option<WatchUpdate> {
+        let changed_files: Vec<PathBuf> = self
+            .stamps
+            .iter()
+            .filter(|(path, last_seen)| stamp(path) != **last_seen)
+            .map(|(path, _)| path.clone())
+            .collect();
+        if self.report.is_some() && changed_files.is_empty() {
+            return None;
+        }
+        // stamped before validating, so that changes made meanwhile show up in the next poll
+        self.stamps = self.files_to_watch().into_iter().map(|path| (path.clone(), stamp(&path))).collect();
+        let report = self.validate();
+        let previous = self.report.replace(report.clone()).unwrap_or_default();
+        let added = report.entries.iter().filter(|entry| !previous.entries.contains(entry)).cloned().collect();
+        let resolved = previous.entries.iter().filter(|entry| !report.entries.contains(entry)).cloned().collect();
+        Some(WatchUpdate {
+            report,
+            added,
+            resolved,
+            changed_files,
+        })
+    }
+
+    /// Calls `on_update` with the first report, then with a new one after every change, until it breaks. Changes
+    /// are looked for every `interval` unless there is a notification for them.
+    pub fn run(&mut self, interval: Duration, mut on_update: impl FnMut(&WatchUpdate) -> ControlFlow<()>) {
+        let mut notifier = ChangeNotifier::new();
+        loop {
+            if let Some(update) = self.poll() {
+                if on_update(&update).is_break() {
+                    return;
+                }
+            }
+            notifier.wait(&self.watched_dirs(), interval);
+        }
+    }
+}
+
+/// Waits until a file in one of the watched directories might have changed
+struct ChangeNotifier {
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+    /// The inotify watches added so far
+    #[cfg(target_os = "linux")]
+    watches: BTreeSet<i32>,
+}
+
+impl ChangeNotifier {
+    fn new() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            inotify: inotify::Inotify::new(),
+            #[cfg(target_os = "linux")]
+            watches: BTreeSet::new(),
+        }
+    }
+
+    /// Waits for a notification of a change in `dirs`, or for `interval` if there can't be one for all of them. A
+    /// directory that wasn't watched before may have changed before it was, so it is waited on for `interval` too.
+    fn wait(&mut self, dirs: &BTreeSet<PathBuf>, interval: Duration) {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = &self.inotify {
+            // every directory is watched again each time, in case it was removed and made anew since
+            let mut all_watched = true;
+            for dir in dirs {
+                match inotify.watch(dir) {
+                    Some(watch) => all_watched &= !self.watches.insert(watch),
+                    None => all_watched = false,
+                }
+            }
+            return inotify.wait(if all_watched { NOTIFIED_POLL_INTERVAL } else { interval });
+        }
+        let _ = dirs;
+        std::thread::sleep(interval);
+    }
+}
+
+/// The few inotify calls needed to learn that something in a directory changed. Which files did is found by
+/// comparing their stamps, so the events themselves are never decoded. `test_watcher_woken_by_inotify` runs this
+/// through [ValidationWatcher::run].
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::CString;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// Files that were written, created, removed, or moved in or out of a directory
+    const EVENTS: u32 = libc::IN_CLOSE_WRITE
+        | libc::IN_MODIFY
+        | libc::IN_ATTRIB
+        | libc::IN_CREATE
+        | libc::IN_DELETE
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO;
+
+    pub struct Inotify {
+        fd: OwnedFd,
+    }
+
+    impl Inotify {
+        /// `None` if inotify can't be used, e.g. because there are too many instances already
+        pub fn new() -> Option<Self> {
+            // Safety: no pointers are passed in, and the descriptor that comes back is checked
+            let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return None;
+            }
+            // Safety: the descriptor was just opened, and nothing else owns it
+            Some(Self { fd: unsafe { OwnedFd::from_raw_fd(fd) } })
+        }
+
+        /// Watches `dir` for changes, unless it is watched already. Returns the watch, which stays the same for as long
+        /// as the directory does, or `None` if it can't be watched.
+        pub fn watch(&self, dir: &Path) -> Option<i32> {
+            let path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+            // Safety: `path` is a nul terminated string that outlives the call
+            let watch = unsafe { libc::inotify_add_watch(self.fd.as_raw_fd(), path.as_ptr(), EVENTS) };
+            (watch >= 0).then_some(watch)
+        }
+
+        /// Waits until there are changes, or `timeout` passes, and then forgets them: which files changed is found
+        /// by comparing them anyway
+        pub fn wait(&self, timeout: Duration) {
+            let mut pollfd = libc::pollfd {
+                fd: self.fd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+            // Safety: `pollfd` is a single valid pollfd that outlives the call
+            unsafe { libc::poll(&mut pollfd, 1, timeout) };
+            let mut events = [0u8; 4096];
+            // Safety: `events` can be written to along its whole length. The descriptor doesn't block, so this stops
+            // once there are no more events.
+            while unsafe { libc::read(self.fd.as_raw_fd(), events.as_mut_ptr().cast(), events.len()) } > 0 {}
+        }
+    }
+}
+
+#[test]
+fn test_validation_watcher() {
+    let dir = std::env::temp_dir().join(format!("bioimg_watch_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rdf_path = dir.join("rdf.yaml");
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/model_affable_shark.yaml");
+    let raw = std::fs::read_to_string(corpus).unwrap();
+    std::fs::write(&rdf_path, &raw).unwrap();
+
+    let mut watcher = ValidationWatcher::new(&rdf_path);
+    let first = watcher.poll().unwrap();
+    assert!(first.changed_files.is_empty());
+    assert_eq!(first.added, first.report.entries);
+    assert!(watcher.watched_files().any(|path| path == dir.join("README.md")));
+    assert!(watcher.poll().is_none());
+
+    std::fs::write(&rdf_path, raw.replace("name: NucleiSegmentationBoundaryModel", "name: ''")).unwrap();
+    let broken = watcher.poll().unwrap();
+    assert_eq!(broken.changed_files, vec![rdf_path.clone()]);
+    assert!(!broken.report.is_valid());
+    assert!(!broken.added.is_empty());
+
+    std::fs::write(&rdf_path, &raw).unwrap();
+    let fixed = watcher.poll().unwrap();
+    assert_eq!(fixed.resolved, broken.added);
+    assert_eq!(fixed.report, first.report);
+
+    std::fs::write(dir.join("README.md"), "# Nuclei").unwrap();
+    let documented = watcher.poll().unwrap();
+    assert_eq!(documented.changed_files, vec![dir.join("README.md")]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_change_notifier() {
+    use std::time::Instant;
+
+    let dir = std::env::temp_dir().join(format!("bioimg_notify_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut notifier = ChangeNotifier::new();
+    assert!(notifier.inotify.is_some());
+    // the directory is new, so the first wait doesn't wait for notifications
+    let start = Instant::now();
+    notifier.wait(&BTreeSet::from([dir.clone()]), Duration::ZERO);
+    assert!(start.elapsed() < NOTIFIED_POLL_INTERVAL);
+
+    let writer = {
+        let path = dir.join("rdf.yaml");
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            std::fs::write(path, "name: changed").unwrap();
+        })
+    };
+    let start = Instant::now();
+    notifier.wait(&BTreeSet::from([dir.clone()]), Duration::from_secs(60));
+    assert!(start.elapsed() < NOTIFIED_POLL_INTERVAL, "woken up by the change, not the timeout");
+    writer.join().unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_watching_other_resource_types() {
+    let dir = std::env::temp_dir().join(format!("bioimg_watch_dataset_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/dataset_nucmm.yaml");
+    std::fs::copy(corpus, dir.join("rdf.yaml")).unwrap();
+
+    let mut watcher = ValidationWatcher::new(dir.join("rdf.yaml"));
+    watcher.poll().unwrap();
+    assert!(watcher.watched_files().any(|path| path == dir.join("README.md")));
+    std::fs::write(dir.join("README.md"), "# NucMM").unwrap();
+    assert_eq!(watcher.poll().unwrap().changed_files, vec![dir.join("README.md")]);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_watcher_woken_by_inotify() {
+    use std::time::Instant;
+
+    let dir = std::env::temp_dir().join(format!("bioimg_watch_inotify_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rdf_path = dir.join("rdf.yaml");
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/valid/dataset_nucmm.yaml");
+    let raw = std::fs::read_to_string(corpus).unwrap();
+    std::fs::write(&rdf_path, &raw).unwrap();
+
+    let writer = {
+        let (rdf_path, raw) = (rdf_path.clone(), raw.replace("name: NucMM", "name: ''"));
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            std::fs::write(rdf_path, raw).unwrap();
+        })
+    };
+    let start = Instant::now();
+    let mut updates = vec![];
+    // far longer than the test may take, so that only a notification can get the change noticed in time
+    ValidationWatcher::new(&rdf_path).run(Duration::from_secs(3600), |update| {
+        updates.push(update.clone());
+        match updates.len() {
+            1 => ControlFlow::Continue(()),
+            _ => ControlFlow::Break(()),
+        }
+    });
+    assert!(start.elapsed() < NOTIFIED_POLL_INTERVAL);
+    assert_eq!(updates[1].changed_files, vec![rdf_path]);
+    assert!(!updates[1].report.is_valid());
+    writer.join().unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
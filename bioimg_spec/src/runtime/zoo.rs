@@ -0,0 +1,265 @@
+//! Browsing the resources published on bioimage.io: fetching the index of the collection,
+//! searching it and downloading the resources found for local editing.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::fetch::{FetchError, ResourceFetcher};
+use crate::rdf::Sha256;
+
+/// The index of every resource published on bioimage.io
+pub const BIOIMAGE_IO_COLLECTION_URL: &str = "https://uk1s3.embassy.ebi.ac.uk/public-datasets/bioimage.io/collection.json";
+
+/// How long the tags used across a collection are kept before they are downloaded again
+pub const TAG_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(thiserror::Error, Debug)]
+pub enum ZooError {
+    #[error("{0}")]
+    FetchError(#[from] FetchError),
+    #[error("Could not download {url}: {message}")]
+    HttpError { url: String, message: String },
+    #[error("{url} is not a collection index: {message}")]
+    BadIndex { url: String, message: String },
+}
+
+/// A published resource as listed in the index. Only the fields needed to find and download
+/// it are read; the whole description is at `rdf_source`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ZooEntry {
+    pub id: String,
+    #[serde(rename = "type", default)]
+    pub resource_type: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub rdf_source: Url,
+    /// The packaged resource, with its description and files
+    #[serde(default)]
+    pub download_url: Option<Url>,
+    #[serde(default)]
+    pub rdf_sha256: Option<Sha256>,
+}
+
+impl ZooEntry {
+    pub fn is_model(&self) -> bool {
+        self.resource_type == "model"
+    }
+}
+
+/// What to look for in the index. Empty fields match every entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZooQuery {
+    /// Found anywhere in the id, name or description, ignoring case
+    pub text: String,
+    /// Entries must have every one of these, ignoring case
+    pub tags: Vec<String>,
+    pub resource_type: Option<String>,
+}
+
+impl ZooQuery {
+    pub fn models() -> Self {
+        Self {
+            resource_type: Some("model".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    pub fn matches(&self, entry: &ZooEntry) -> bool {
+        let text = self.text.trim().to_lowercase();
+        let matches_text = text.is_empty()
+            || [&entry.id, &entry.name, &entry.description].iter().any(|field| field.to_lowercase().contains(&text));
+        let matches_tags = self
+            .tags
+            .iter()
+            .all(|wanted| entry.tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted.trim())));
+        let matches_type = self.resource_type.iter().all(|kind| entry.resource_type == *kind);
+        matches_text && matches_tags && matches_type
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ZooIndex {
+    #[serde(rename = "collection")]
+    pub entries: Vec<ZooEntry>,
+}
+
+impl ZooIndex {
+    pub fn from_json(raw: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(raw)
+    }
+
+    pub fn search<'a: 'q, 'q>(&'a self, query: &'q ZooQuery) -> impl Iterator<Item = &'a ZooEntry> + 'q {
+        self.entries.iter().filter(|entry| query.matches(entry))
+    }
+
+    /// Every tag in the index, sorted and without duplicates, to offer as filters
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self.entries.iter().flat_map(|entry| entry.tags.iter().map(String::as_str)).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    /// Every tag in the index with how often it is used, the most used first, since those are the ones
+    /// new resources should use too
+    pub fn tag_usage(&self) -> Vec<TagUsage> {
+        let mut usage: Vec<TagUsage> = vec![];
+        for tag in self.entries.iter().flat_map(|entry| entry.tags.iter().map(|tag| tag.trim())) {
+            match usage.iter_mut().find(|usage| usage.tag == tag) {
+                Some(usage) => usage.count += 1,
+                None if !tag.is_empty() => usage.push(TagUsage { tag: tag.to_owned(), count: 1 }),
+                None => (),
+            }
+        }
+        usage.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+        usage
+    }
+}
+
+/// A tag and how many resources of a collection have it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TagUsage {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Reads the index of a collection and downloads the resources in it. Nothing is ever uploaded.
+#[derive(Clone)]
+pub struct ZooClient {
+    index_url: Url,
+    fetcher: ResourceFetcher,
+    agent: ureq::Agent,
+}
+
+impl ZooClient {
+    /// A client for the collection at `index_url`, downloading resources with `fetcher`
+    pub fn new(index_url: Url, fetcher: ResourceFetcher) -> Self {
+        Self {
+            index_url,
+            fetcher,
+            agent: super::network::agent_builder().build(),
+        }
+    }
+
+    /// A client for bioimage.io, caching downloads in the cache directory of the user
+    pub fn bioimage_io() -> Result<Self, ZooError> {
+        let index_url = Url::parse(BIOIMAGE_IO_COLLECTION_URL).expect("the collection url is valid");
+        Ok(Self::new(index_url, ResourceFetcher::with_user_cache()?))
+    }
+
+    /// Downloads the index. Unlike resources, it changes over time, so it is never cached.
+    pub fn fetch_index(&self) -> Result<ZooIndex, ZooError> {
+        let url = self.index_url.to_string();
+        let raw = self
+            .agent
+            .get(&url)
+            .call()
+            .map_err(|err| ZooError::HttpError { url: url.clone(), message: err.to_string() })?
+            .into_string()
+            .map_err(|err| ZooError::HttpError { url: url.clone(), message: err.to_string() })?;
+        ZooIndex::from_json(&raw).map_err(|err| ZooError::BadIndex { url, message: err.to_string() })
+    }
+
+    /// The tags used across the collection, the most used first. They are kept in the cache for
+    /// [TAG_CACHE_MAX_AGE]; after that the index is downloaded again, and the old tags are used if that fails.
+    pub fn tag_usage(&self) -> Result<Vec<TagUsage>, ZooError> {
+        let path = self.fetcher.cache_path(&self.index_url, None).with_file_name("tags.json");
+        let cached = std::fs::read_to_string(&path).ok().and_then(|raw| serde_json::from_str::<Vec<TagUsage>>(&raw).ok());
+        let age = std::fs::metadata(&path).and_then(|meta| meta.modified()).ok().and_then(|time| time.elapsed().ok());
+        if let (Some(cached), true) = (&cached, age.is_some_and(|age| age < TAG_CACHE_MAX_AGE)) {
+            return Ok(cached.clone());
+        }
+        let usage = match (self.fetch_index(), cached) {
+            (Ok(index), _) => index.tag_usage(),
+            (Err(_), Some(cached)) => return Ok(cached),
+            (Err(err), None) => return Err(err),
+        };
+        // without a cache, the tags are just downloaded again next time
+        let _ = std::fs::create_dir_all(path.parent().expect("cache paths have a parent"))
+            .and_then(|_| std::fs::write(&path, serde_json::to_string(&usage).expect("tags serialize")));
+        Ok(usage)
+    }
+
+    /// Downloads `entry` and returns the local path to it: the package if the entry has one,
+    /// otherwise just its description
+    pub fn download(&self, entry: &ZooEntry) -> Result<PathBuf, ZooError> {
+        Ok(match &entry.download_url {
+            Some(url) => self.fetcher.fetch(url, None)?,
+            None => self.fetcher.fetch(&entry.rdf_source, entry.rdf_sha256.as_ref())?,
+        })
+    }
+}
+
+#[test]
+fn test_searching_zoo_index() {
+    let raw = r#"{
+        "collection": [
+            {
+                "id": "affable-shark",
+                "type": "model",
+                "name": "NucleiSegmentationBoundaryModel",
+                "description": "Nucleus segmentation for fluorescence microscopy",
+                "tags": ["fluorescence-light-microscopy", "nuclei", "unet"],
+                "rdf_source": "https://example.com/affable-shark/rdf.yaml",
+                "download_url": "https://example.com/affable-shark/package.zip",
+                "badges": []
+            },
+            {
+                "id": "ilastik",
+                "type": "application",
+                "name": "ilastik",
+                "rdf_source": "https://example.com/ilastik/rdf.yaml"
+            },
+            {
+                "id": "chatty-frog",
+                "type": "model",
+                "name": "StarDist H&E Nuclei Segmentation",
+                "tags": ["stardist", "Nuclei"],
+                "rdf_source": "https://example.com/chatty-frog/rdf.yaml"
+            }
+        ]
+    }"#;
+    let index = ZooIndex::from_json(raw).unwrap();
+    let ids = |query: &ZooQuery| index.search(query).map(|entry| entry.id.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(ids(&ZooQuery::default()), ["affable-shark", "ilastik", "chatty-frog"]);
+    assert_eq!(ids(&ZooQuery::models()), ["affable-shark", "chatty-frog"]);
+    let nuclei = ZooQuery {
+        tags: vec!["nuclei".to_owned()],
+        ..ZooQuery::models()
+    };
+    assert_eq!(ids(&nuclei), ["affable-shark", "chatty-frog"]);
+    let fluorescence = ZooQuery {
+        text: "FLUORESCENCE".to_owned(),
+        ..nuclei
+    };
+    assert_eq!(ids(&fluorescence), ["affable-shark"]);
+
+    assert!(index.entries[1].download_url.is_none());
+    assert!(index.entries[0].is_model());
+    assert_eq!(index.tags()[..2], ["Nuclei", "fluorescence-light-microscopy"]);
+    let usage = index.tag_usage();
+    assert_eq!(usage[0], TagUsage { tag: "Nuclei".to_owned(), count: 1 });
+    assert_eq!(usage.len(), 5);
+
+    // nothing listens there, so only the cache can answer
+    let cache_dir = std::env::temp_dir().join(format!("bioimg_zoo_tags_{}", std::process::id()));
+    let client = ZooClient::new(Url::parse("http://127.0.0.1:9/collection.json").unwrap(), ResourceFetcher::new(&cache_dir));
+    assert!(matches!(client.tag_usage(), Err(ZooError::HttpError { .. })));
+    let cache_path = client.fetcher.cache_path(&client.index_url, None).with_file_name("tags.json");
+    std::fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+    std::fs::write(&cache_path, serde_json::to_string(&usage).unwrap()).unwrap();
+    assert_eq!(client.tag_usage().unwrap(), usage);
+    // tags that are out of date are still better than none
+    let cached = std::fs::File::options().write(true).open(&cache_path).unwrap();
+    cached.set_modified(std::time::SystemTime::UNIX_EPOCH).unwrap();
+    assert_eq!(client.tag_usage().unwrap(), usage);
+    std::fs::remove_dir_all(cache_dir).unwrap();
+}
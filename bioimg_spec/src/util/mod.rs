@@ -1,3 +1,5 @@
+pub mod progress;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
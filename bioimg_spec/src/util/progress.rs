@@ -0,0 +1,196 @@
+//! Reporting how far long operations like hashing or packaging got, and stopping them when asked to
+
+use std::{
+    io::Read,
+    ops::ControlFlow,
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// How often the progress of work that runs on other threads is passed on
+const SHARED_PROGRESS_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How many of the bytes an operation has to get through it got through already
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub done: u64,
+    pub total: u64,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f32 {
+        match self.total {
+            0 => 1.0,
+            total => (self.done as f32 / total as f32).min(1.0),
+        }
+    }
+}
+
+/// Called every now and then by a long operation. Breaking stops the operation, which then fails
+/// with an error for which [is_cancellation] is true.
+pub type OnProgress<'a> = &'a mut dyn FnMut(Progress) -> ControlFlow<()>;
+
+/// For operations that nobody waits on
+pub fn ignore_progress(_: Progress) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
+
+const CANCELLED: &str = "cancelled";
+
+pub fn cancellation() -> std::io::Error {
+    std::io::Error::other(CANCELLED)
+}
+
+/// Whether `err` is how an operation stopped because it was told to
+pub fn is_cancellation(err: &std::io::Error) -> bool {
+    err.kind() == std::io::ErrorKind::Other && err.get_ref().is_some_and(|inner| inner.to_string() == CANCELLED)
+}
+
+/// Reports how much of `inner` has been read so far, counting from `progress`
+pub struct ProgressReader<'a, R> {
+    pub inner: R,
+    pub progress: Progress,
+    pub on_progress: OnProgress<'a>,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.progress.done += read as u64;
+        if (self.on_progress)(self.progress).is_break() {
+            return Err(cancellation());
+        }
+        Ok(read)
+    }
+}
+
+/// Progress of an operation whose parts run on several threads at once. The parts add to it as they go, and the
+/// thread that waits on them passes it on to an [OnProgress], which doesn't need to be [Send].
+pub struct SharedProgress {
+    done: AtomicU64,
+    total: u64,
+    cancelled: AtomicBool,
+}
+
+impl SharedProgress {
+    /// Runs `work` on another thread, passing on the progress it makes to `on_progress` until it is done.
+    /// Once `on_progress` breaks, [Self::add] fails for every part still running, and so does the whole of `work`
+    /// even if it got done anyway.
+    pub fn run<T: Send, E: From<std::io::Error> + Send>(
+        total: u64,
+        on_progress: OnProgress,
+        work: impl FnOnce(&SharedProgress) -> Result<T, E> + Send,
+    ) -> Result<T, E> {
+        let shared = SharedProgress {
+            done: AtomicU64::new(0),
+            total,
+            cancelled: AtomicBool::new(false),
+        };
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(|| work(&shared));
+            let mut reported = None;
+            loop {
+                let finished = worker.is_finished();
+                let progress = shared.get();
+                if reported != Some(progress) {
+                    reported = Some(progress);
+                    if on_progress(progress).is_break() {
+                        shared.cancelled.store(true, Ordering::Relaxed);
+                    }
+                }
+                if finished {
+                    break;
+                }
+                std::thread::sleep(SHARED_PROGRESS_INTERVAL);
+            }
+            let result = worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            match shared.cancelled.load(Ordering::Relaxed) {
+                true => Err(cancellation().into()),
+                false => result,
+            }
+        })
+    }
+
+    /// Counts `bytes` more as done, failing if the operation was cancelled
+    pub fn add(&self, bytes: u64) -> std::io::Result<()> {
+        self.done.fetch_add(bytes, Ordering::Relaxed);
+        match self.cancelled.load(Ordering::Relaxed) {
+            true => Err(cancellation()),
+            false => Ok(()),
+        }
+    }
+
+    pub fn get(&self) -> Progress {
+        Progress {
+            done: self.done.load(Ordering::Relaxed),
+            total: self.total,
+        }
+    }
+}
+
+/// Adds how much of `inner` has been read to a [SharedProgress]
+pub struct SharedProgressReader<'a, R> {
+    pub inner: R,
+    pub progress: &'a SharedProgress,
+}
+
+impl<R: Read> Read for SharedProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.progress.add(read as u64)?;
+        Ok(read)
+    }
+}
+
+/// Like [std::fs::read], reporting how much of the file was read
+pub fn read_file(path: &Path, on_progress: OnProgress) -> std::io::Result<Vec<u8>> {
+    let file = std::fs::File::open(path)?;
+    let total = file.metadata()?.len();
+    let mut bytes = Vec::with_capacity(total as usize);
+    let mut reader = ProgressReader {
+        inner: file,
+        progress: Progress { done: 0, total },
+        on_progress,
+    };
+    reader.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[test]
+fn test_progress_reader() {
+    let mut seen = vec![];
+    let mut reader = ProgressReader {
+        inner: &[0u8; 10][..],
+        progress: Progress { done: 0, total: 10 },
+        on_progress: &mut |progress| {
+            seen.push(progress.done);
+            match progress.done {
+                done if done <= 8 => ControlFlow::Continue(()),
+                _ => ControlFlow::Break(()),
+            }
+        },
+    };
+    let mut buf = [0u8; 4];
+    assert_eq!(reader.read(&mut buf).unwrap(), 4);
+    assert_eq!(reader.read(&mut buf).unwrap(), 4);
+    assert!(is_cancellation(&reader.read(&mut buf).unwrap_err()));
+    assert_eq!(seen, [4, 8, 10]);
+    assert!(!is_cancellation(&std::io::Error::other("something else")));
+
+    let mut last = None;
+    let mut on_progress = |progress| {
+        last = Some(progress);
+        ControlFlow::Continue(())
+    };
+    let sum = SharedProgress::run(10, &mut on_progress, |shared| {
+        std::thread::scope(|scope| {
+            scope.spawn(|| shared.add(4));
+            scope.spawn(|| shared.add(6));
+        });
+        Ok::<_, std::io::Error>(shared.get().done)
+    });
+    assert_eq!((sum.unwrap(), last), (10, Some(Progress { done: 10, total: 10 })));
+    let cancelled = SharedProgress::run(10, &mut |_| ControlFlow::Break(()), |shared| shared.add(10));
+    assert!(is_cancellation(&cancelled.unwrap_err()));
+}